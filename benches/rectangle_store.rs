@@ -126,6 +126,17 @@ fn bench_churn(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("remove_half_build", |b| {
+        b.iter(|| {
+            let mut pool = RectangleStore::new();
+            let ids: Vec<_> = (0..1024).map(|_| pool.add(&rect)).collect();
+            for &id in ids.iter().step_by(2) {
+                pool.remove(black_box(id));
+            }
+            black_box(pool.bytes());
+        });
+    });
+
     group.finish();
 }
 
@@ -0,0 +1,104 @@
+use std::{hint::black_box, time::Duration};
+
+use criterion::{
+    BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
+};
+use hui::{
+    RectangleStore, TextEditState,
+    testing::{AnimatedScene, long_text, nested_clips, rectangle_grid},
+};
+
+const VIEWPORT: [f32; 2] = [1920.0, 1080.0];
+
+fn bench_rectangle_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rectangle_grid");
+
+    for count in [1_000, 10_000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("build", count),
+            &count,
+            |b, &count| {
+                b.iter(|| {
+                    let scene = rectangle_grid(count, VIEWPORT);
+                    let mut store = RectangleStore::new();
+                    for rectangle in &scene.rectangles {
+                        let _ = store.add(rectangle);
+                    }
+                    black_box(store.bytes().len());
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_animated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("animated");
+    let count = 1_000;
+    group.throughput(Throughput::Elements(count as u64));
+
+    group.bench_function("step_and_write", |b| {
+        let mut scene = AnimatedScene::new(count, VIEWPORT);
+        let mut store = RectangleStore::new();
+        let ids: Vec<_> = scene
+            .scene()
+            .rectangles
+            .iter()
+            .map(|rectangle| store.add(rectangle))
+            .collect();
+
+        b.iter(|| {
+            scene.step(Duration::from_micros(16_667));
+            for (id, rectangle) in ids.iter().zip(&scene.scene().rectangles) {
+                if let Some(instance) = store.get_mut(*id) {
+                    instance.mvp = rectangle.mvp;
+                }
+            }
+            black_box(store.bytes().len());
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_nested_clips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_clips");
+
+    for depth in [16, 256] {
+        group.bench_with_input(
+            BenchmarkId::new("build", depth),
+            &depth,
+            |b, &depth| b.iter(|| black_box(nested_clips(depth, VIEWPORT))),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_long_text(c: &mut Criterion) {
+    let mut group = c.benchmark_group("long_text");
+    let text = long_text(20_000);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+
+    group.bench_function("edit_middle", |b| {
+        let mut state = TextEditState::new(text.clone());
+        b.iter(|| {
+            state.set_caret(text.len() / 2);
+            state.insert(black_box("x"));
+            state.delete(false);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_rectangle_grid,
+    bench_animated,
+    bench_nested_clips,
+    bench_long_text
+);
+criterion_main!(benches);
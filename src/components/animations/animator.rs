@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use bon::Builder;
+use glam::Mat4;
+use slotmap::{DefaultKey, SlotMap};
+
+use super::Easing;
+use crate::{
+    components::{
+        common::Bounds,
+        widgets::{
+            Block, BlockStyle, StylePatch, block_model,
+            block_states::Positioned, build_rectangle,
+        },
+    },
+    core::{RectangleId, Renderer},
+};
+
+pub type AnimationId = DefaultKey;
+
+// Where an animation takes a block; what isn't set stays as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct AnimationTarget {
+    position: Option<[f32; 2]>,
+    size:     Option<[f32; 2]>,
+    #[builder(default = StylePatch::builder().build())]
+    style:    StylePatch,
+}
+
+// Where a block was left by an animation that finished or was
+// cancelled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinishedAnimation {
+    pub id:     AnimationId,
+    pub bounds: Bounds,
+    pub style:  BlockStyle,
+}
+
+impl FinishedAnimation {
+    // Catches the block up with what the animation drew.
+    pub fn apply(
+        &self,
+        block: &mut Block<Positioned>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        block.update_size(self.bounds.size, view_projection, dpr, renderer);
+        block.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        block.update_style(self.style.clone(), renderer);
+    }
+}
+
+struct Animation {
+    rectangle_id: RectangleId,
+    rotation:     f32,
+    from:         (Bounds, BlockStyle),
+    to:           (Bounds, BlockStyle),
+    current:      (Bounds, BlockStyle),
+    elapsed:      Duration,
+    duration:     Duration,
+    easing:       Easing,
+}
+
+// Tweens the position, size and style of blocks, writing every tick's
+// values straight to their rectangles. The blocks themselves don't
+// change meanwhile, so leave them alone while they animate and apply
+// what `take_finished` returns to them afterwards.
+#[derive(Default)]
+pub struct Animator {
+    animations: SlotMap<AnimationId, Animation>,
+    finished:   Vec<FinishedAnimation>,
+}
+
+impl Animator {
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.animations.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.animations.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_animating(&self, id: AnimationId) -> bool {
+        self.animations.contains_key(id)
+    }
+
+    // Starts from where the block is, or from where its current animation
+    // got to, which the new one replaces.
+    pub fn animate(
+        &mut self,
+        block: &Block<Positioned>,
+        target: AnimationTarget,
+        duration: Duration,
+        easing: Easing,
+    ) -> AnimationId {
+        let rectangle_id = block.rectangle_id();
+        let running = self
+            .animations
+            .iter()
+            .find(|(_, animation)| animation.rectangle_id == rectangle_id)
+            .map(|(id, _)| id);
+        let from = match running.and_then(|id| self.animations.remove(id)) {
+            Some(animation) => animation.current,
+            None => (block.bounds().clone(), block.style().clone()),
+        };
+        let to = (
+            Bounds {
+                position:  target.position.unwrap_or(from.0.position),
+                size:      target.size.unwrap_or(from.0.size),
+                clip_rect: from.0.clip_rect,
+            },
+            from.1.merged(&target.style),
+        );
+        self.animations.insert(Animation {
+            rectangle_id,
+            rotation: block.rotation(),
+            current: from.clone(),
+            from,
+            to,
+            elapsed: Duration::ZERO,
+            duration,
+            easing,
+        })
+    }
+
+    // Stops the animation where it is. Returns whether it was running.
+    pub fn cancel(&mut self, id: AnimationId) -> bool {
+        let Some(animation) = self.animations.remove(id) else {
+            return false;
+        };
+        let (bounds, style) = animation.current;
+        self.finished.push(FinishedAnimation { id, bounds, style });
+        true
+    }
+
+    // Returns whether anything animated.
+    pub fn tick(
+        &mut self,
+        dt: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_animating = !self.animations.is_empty();
+        let mut done = Vec::new();
+        for (id, animation) in &mut self.animations {
+            animation.elapsed += dt;
+            let progress = match animation.duration.is_zero() {
+                true => 1.0,
+                false => {
+                    animation.elapsed.as_secs_f32()
+                        / animation.duration.as_secs_f32()
+                }
+            };
+            animation.current = animated_state(
+                &animation.from,
+                &animation.to,
+                animation.easing,
+                progress,
+            );
+            write_rectangle(animation, view_projection, dpr, renderer);
+            if progress >= 1.0 {
+                done.push(id);
+            }
+        }
+        for id in done {
+            let Some(animation) = self.animations.remove(id) else {
+                continue;
+            };
+            let (bounds, style) = animation.current;
+            if style.z_index() != animation.from.1.z_index() {
+                renderer.set_rectangle_z_index(
+                    animation.rectangle_id,
+                    style.z_index(),
+                );
+            }
+            self.finished.push(FinishedAnimation { id, bounds, style });
+        }
+        is_animating
+    }
+
+    #[must_use]
+    pub fn take_finished(&mut self) -> Vec<FinishedAnimation> {
+        std::mem::take(&mut self.finished)
+    }
+}
+
+// Keeps the rectangle's clip rect, which the renderer may have narrowed
+// by its clip stack.
+fn write_rectangle(
+    animation: &Animation,
+    view_projection: &Mat4,
+    dpr: f32,
+    renderer: &mut Renderer,
+) {
+    let (bounds, style) = &animation.current;
+    let mut rectangle = build_rectangle(view_projection, dpr, bounds, style);
+    let (model, _) = block_model(bounds, animation.rotation, dpr);
+    rectangle.mvp = (*view_projection * model).to_cols_array_2d();
+    if let Some(instance) = renderer.get_mut_rectangle(animation.rectangle_id)
+    {
+        rectangle.clip_rect = instance.clip_rect;
+        *instance = rectangle;
+    }
+}
+
+fn animated_state(
+    from: &(Bounds, BlockStyle),
+    to: &(Bounds, BlockStyle),
+    easing: Easing,
+    progress: f32,
+) -> (Bounds, BlockStyle) {
+    let t = easing.apply(progress);
+    let lerp = |from: [f32; 2], to: [f32; 2]| {
+        [0, 1].map(|index| from[index] + (to[index] - from[index]) * t)
+    };
+    let bounds = Bounds {
+        position:  lerp(from.0.position, to.0.position),
+        size:      lerp(from.0.size, to.0.size),
+        clip_rect: to.0.clip_rect,
+    };
+    (bounds, from.1.lerp(&to.1, t))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::animated_state;
+    use crate::components::{
+        animations::Easing, common::Bounds, widgets::BlockStyle,
+    };
+
+    #[rstest]
+    #[case(Easing::Linear, 0.5, [50.0, 10.0], 0.5)]
+    #[case(Easing::EaseInOut, 0.25, [6.25, 10.0], 0.0625)]
+    #[case(Easing::Linear, 1.0, [100.0, 10.0], 1.0)]
+    fn test_animated_state(
+        #[case] easing: Easing,
+        #[case] progress: f32,
+        #[case] position: [f32; 2],
+        #[case] alpha: f32,
+    ) {
+        let bounds = Bounds::without_clip_rect([0.0, 10.0], [20.0, 20.0]);
+        let from = (
+            bounds.clone(),
+            BlockStyle::builder()
+                .fill_color([1.0, 1.0, 1.0, 0.0])
+                .build(),
+        );
+        let to = (
+            Bounds { position: [100.0, 10.0], ..bounds },
+            BlockStyle::builder().build(),
+        );
+        let (bounds, style) = animated_state(&from, &to, easing, progress);
+        assert_eq!(bounds.position, position);
+        assert_eq!(bounds.size, [20.0, 20.0]);
+        assert_eq!(
+            style,
+            BlockStyle::builder()
+                .fill_color([1.0, 1.0, 1.0, alpha])
+                .build()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::f32::consts::TAU;
+
+// How an animation's progress maps to how far its values have moved,
+// both from 0 to 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Easing {
+    Linear,
+    #[default]
+    EaseInOut,
+    // The control points x1, y1, x2, y2 of a curve from (0, 0) to (1, 1),
+    // like CSS' cubic-bezier. The x coordinates are clamped into 0..=1.
+    CubicBezier([f32; 4]),
+    // Overshoots and settles by the end of the animation: `frequency`
+    // oscillations over its duration, damped by `damping_ratio`, with 1
+    // and above not overshooting at all.
+    Spring {
+        frequency:     f32,
+        damping_ratio: f32,
+    },
+}
+
+impl Easing {
+    #[must_use]
+    pub fn apply(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        if progress == 1.0 {
+            return 1.0;
+        }
+        match self {
+            Self::Linear => progress,
+            Self::EaseInOut => match progress < 0.5 {
+                true => 4.0 * progress.powi(3),
+                false => 1.0 - (2.0 - 2.0 * progress).powi(3) / 2.0,
+            },
+            Self::CubicBezier([x1, y1, x2, y2]) => {
+                let [x1, x2] = [x1, x2].map(|x| x.clamp(0.0, 1.0));
+                let t = solve_bezier(x1, x2, progress);
+                bezier(y1, y2, t)
+            }
+            Self::Spring { frequency, damping_ratio } => {
+                spring(frequency, damping_ratio, progress)
+            }
+        }
+    }
+}
+
+// One coordinate of a cubic bezier from 0 to 1 at `t`.
+#[inline(always)]
+fn bezier(p1: f32, p2: f32, t: f32) -> f32 {
+    let u = 1.0 - t;
+    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+// The `t` at which the curve's x is `x`. x only grows with t when the
+// control points are within 0..=1, so bisecting finds it.
+fn solve_bezier(x1: f32, x2: f32, x: f32) -> f32 {
+    let [mut low, mut high] = [0.0, 1.0];
+    for _ in 0..24 {
+        let t = (low + high) / 2.0;
+        match bezier(x1, x2, t) < x {
+            true => low = t,
+            false => high = t,
+        }
+    }
+    (low + high) / 2.0
+}
+
+// The step response of a damped spring.
+fn spring(frequency: f32, damping_ratio: f32, t: f32) -> f32 {
+    let omega = TAU * frequency.max(0.0);
+    let damping_ratio = damping_ratio.max(0.0);
+    if damping_ratio >= 1.0 {
+        return 1.0 - (-omega * t).exp() * (1.0 + omega * t);
+    }
+    let damped_omega = omega * (1.0 - damping_ratio * damping_ratio).sqrt();
+    let decay = (-damping_ratio * omega * t).exp();
+    1.0 - decay
+        * ((damped_omega * t).cos()
+            + damping_ratio * omega / damped_omega * (damped_omega * t).sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::Easing;
+
+    #[rstest]
+    #[case(Easing::Linear, 0.25, 0.25)]
+    #[case(Easing::EaseInOut, 0.25, 0.0625)]
+    #[case(Easing::EaseInOut, 0.5, 0.5)]
+    #[case(Easing::EaseInOut, 0.75, 0.9375)]
+    // The linear curve.
+    #[case(Easing::CubicBezier([0.0, 0.0, 1.0, 1.0]), 0.3, 0.3)]
+    #[case(Easing::CubicBezier([0.42, 0.0, 0.58, 1.0]), 0.5, 0.5)]
+    #[case(Easing::Linear, -1.0, 0.0)]
+    #[case(Easing::Spring { frequency: 2.0, damping_ratio: 0.3 }, 1.0, 1.0)]
+    fn test_apply(
+        #[case] easing: Easing,
+        #[case] progress: f32,
+        #[case] expected: f32,
+    ) {
+        assert!((easing.apply(progress) - expected).abs() < 1e-4);
+    }
+
+    #[rstest]
+    fn test_spring_overshoots() {
+        let bouncy = Easing::Spring { frequency: 2.0, damping_ratio: 0.3 };
+        let stiff = Easing::Spring { frequency: 2.0, damping_ratio: 1.0 };
+        let samples = |easing: Easing| {
+            (0..100).map(move |step| easing.apply(step as f32 / 100.0))
+        };
+        assert!(samples(bouncy).any(|value| value > 1.0));
+        assert!(samples(stiff).all(|value| value <= 1.0));
+        assert_eq!(bouncy.apply(0.0), 0.0);
+    }
+}
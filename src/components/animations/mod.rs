@@ -0,0 +1,7 @@
+mod animator;
+mod easing;
+
+pub use animator::{
+    AnimationId, AnimationTarget, Animator, FinishedAnimation,
+};
+pub use easing::Easing;
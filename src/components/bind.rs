@@ -0,0 +1,258 @@
+use std::{fmt::Display, str::FromStr};
+
+use glam::Mat4;
+
+use super::widgets::{Form, Validator};
+use crate::core::Renderer;
+
+type Getter<M> = dyn Fn(&M) -> String;
+// Returns false if the text doesn't parse into the field's type.
+type Setter<M> = dyn Fn(&mut M, &str) -> bool;
+
+struct BoundField<M> {
+    label:       String,
+    height:      f32,
+    validators:  Vec<Validator>,
+    get:         Box<Getter<M>>,
+    set:         Box<Setter<M>>,
+    form_field:  Option<usize>,
+    // What each side held at the last sync; a side that differs from it
+    // has changed since.
+    model_value: Option<String>,
+    form_value:  Option<String>,
+}
+
+// Maps the fields of a model struct to the fields of a `Form`, keeping
+// both in sync. Values go through `Display` and `FromStr`, so any field
+// that round-trips through text can be bound:
+//
+// Binding::new()
+//     .field("Volume", |s: &Settings| s.volume, |s, v| s.volume = v)
+//     .validate(Validator::range(0.0, 100.0, "Between 0 and 100"))
+//     .field("Name", |s| s.name.clone(), |s, v| s.name = v)
+pub struct Binding<M> {
+    fields: Vec<BoundField<M>>,
+}
+
+impl<M> Default for Binding<M> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<M> Binding<M> {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self { Self { fields: Vec::new() } }
+
+    #[must_use]
+    pub fn field<T>(
+        mut self,
+        label: impl Into<String>,
+        get: impl Fn(&M) -> T + 'static,
+        set: impl Fn(&mut M, T) + 'static,
+    ) -> Self
+    where
+        T: Display + FromStr + 'static,
+    {
+        let get = Box::new(move |model: &M| get(model).to_string());
+        let set = Box::new(move |model: &mut M, text: &str| {
+            text.trim()
+                .parse::<T>()
+                .map(|value| set(model, value))
+                .is_ok()
+        });
+        self.fields.push(BoundField {
+            label: label.into(),
+            height: 32.0,
+            validators: Vec::new(),
+            get,
+            set,
+            form_field: None,
+            model_value: None,
+            form_value: None,
+        });
+
+        self
+    }
+
+    // Adds a validator to the last bound field.
+    #[must_use]
+    pub fn validate(mut self, validator: Validator) -> Self {
+        if let Some(field) = self.fields.last_mut() {
+            field.validators.push(validator);
+        }
+        self
+    }
+
+    // Sets the height of the last bound field's input.
+    #[must_use]
+    pub fn height(mut self, height: f32) -> Self {
+        if let Some(field) = self.fields.last_mut() {
+            field.height = height;
+        }
+        self
+    }
+
+    // Labels of the bound fields in order, for the caller to draw next to
+    // `Form::field_bounds`.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| field.label.as_str())
+    }
+
+    // The form field a bound field was added as.
+    #[must_use]
+    pub fn form_field(&self, index: usize) -> Option<usize> {
+        self.fields.get(index)?.form_field
+    }
+
+    // Adds a form field for every bound field not added yet and fills them
+    // in from the model. Validators move into the form.
+    pub fn build_form(
+        &mut self,
+        model: &M,
+        form: &mut Form,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for field in &mut self.fields {
+            if field.form_field.is_none() {
+                let validators = std::mem::take(&mut field.validators);
+                field.form_field = Some(form.add_field(
+                    field.height,
+                    validators,
+                    view_projection,
+                    dpr,
+                    renderer,
+                ));
+            }
+        }
+        self.sync_from_model(model, form, dpr, renderer);
+    }
+
+    // Copies model values that changed since the last sync into the form.
+    // Returns the indices of the bound fields that changed.
+    pub fn sync_from_model(
+        &mut self,
+        model: &M,
+        form: &mut Form,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<usize> {
+        let changes = self.model_changes(model);
+        for (index, value) in &changes {
+            if let Some(form_field) = self.fields[*index].form_field {
+                form.set_value(form_field, value.clone(), dpr, renderer);
+            }
+        }
+        changes.into_iter().map(|(index, _)| index).collect()
+    }
+
+    // Copies form values that changed since the last sync into the model.
+    // Values that don't parse are left in the form for its validators to
+    // flag. Returns the indices of the bound fields that changed.
+    pub fn sync_to_model(&mut self, model: &mut M, form: &Form) -> Vec<usize> {
+        self.apply_to_model(model, |form_field| {
+            form.value(form_field).map(str::to_owned)
+        })
+    }
+
+    fn model_changes(&mut self, model: &M) -> Vec<(usize, String)> {
+        let mut changes = Vec::new();
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            let value = (field.get)(model);
+            if field.model_value.as_ref() != Some(&value) {
+                field.model_value = Some(value.clone());
+                field.form_value = Some(value.clone());
+                changes.push((index, value));
+            }
+        }
+        changes
+    }
+
+    fn apply_to_model(
+        &mut self,
+        model: &mut M,
+        form_value: impl Fn(usize) -> Option<String>,
+    ) -> Vec<usize> {
+        let mut changed = Vec::new();
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            let Some(value) = field.form_field.and_then(&form_value) else {
+                continue;
+            };
+            if field.form_value.as_ref() == Some(&value) {
+                continue;
+            }
+            if (field.set)(model, &value) {
+                field.model_value = Some((field.get)(model));
+                changed.push(index);
+            }
+            field.form_value = Some(value);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::Binding;
+
+    struct Settings {
+        volume: u32,
+        name:   String,
+    }
+
+    fn binding() -> Binding<Settings> {
+        let mut binding = Binding::new()
+            .field("Volume", |s: &Settings| s.volume, |s, v| s.volume = v)
+            .field("Name", |s: &Settings| s.name.clone(), |s, v| s.name = v);
+        for (index, field) in binding.fields.iter_mut().enumerate() {
+            field.form_field = Some(index);
+        }
+        binding
+    }
+
+    #[rstest]
+    fn test_binding_model_changes() {
+        let mut binding = binding();
+        let mut settings = Settings { volume: 50, name: "a".to_owned() };
+
+        assert_eq!(
+            binding.model_changes(&settings),
+            [(0, "50".to_owned()), (1, "a".to_owned())]
+        );
+        assert!(binding.model_changes(&settings).is_empty());
+
+        settings.volume = 60;
+        assert_eq!(binding.model_changes(&settings), [(0, "60".to_owned())]);
+    }
+
+    #[rstest]
+    #[case(&["70", "a"], &[0], 70)]
+    #[case(&["loud", "a"], &[], 50)]
+    #[case(&["50", "b"], &[1], 50)]
+    #[case(&["050", "a"], &[0], 50)]
+    fn test_binding_apply_to_model(
+        #[case] form_values: &[&str],
+        #[case] expected_changed: &[usize],
+        #[case] expected_volume: u32,
+    ) {
+        let mut binding = binding();
+        let mut settings = Settings { volume: 50, name: "a".to_owned() };
+        let _ = binding.model_changes(&settings);
+
+        let changed = binding.apply_to_model(&mut settings, |form_field| {
+            form_values.get(form_field).map(|value| value.to_string())
+        });
+        assert_eq!(changed, expected_changed);
+        assert_eq!(settings.volume, expected_volume);
+
+        // Nothing changed since, on either side.
+        let changed = binding.apply_to_model(&mut settings, |form_field| {
+            form_values.get(form_field).map(|value| value.to_string())
+        });
+        assert!(changed.is_empty());
+        assert!(binding.model_changes(&settings).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+// How urgently a screen reader should speak an announcement, as with the
+// politeness of an ARIA live region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Politeness {
+    // Waits until the user is idle, e.g. for loaded content or a toast.
+    Polite,
+    // Interrupts, e.g. for a validation error.
+    Assertive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    text:       String,
+    politeness: Politeness,
+}
+
+impl Announcement {
+    #[must_use]
+    #[inline(always)]
+    pub fn text(&self) -> &str { &self.text }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn politeness(&self) -> Politeness { self.politeness }
+}
+
+// The accessibility backend that speaks announcements, e.g. through the
+// platform's screen reader API.
+pub trait AnnouncementSink {
+    fn announce(&mut self, announcement: &Announcement);
+}
+
+// Queues what changed on screen without the focus moving to it, so that
+// screen reader users notice toasts, validation errors and content that
+// finished loading. Widgets announce as things happen; the app flushes
+// the queue to its backend once per frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Announcer {
+    pending: VecDeque<Announcement>,
+}
+
+impl Announcer {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self { Self { pending: VecDeque::new() } }
+
+    // An assertive announcement drops the polite ones still waiting, as
+    // they are likely stale by the time it has been spoken. Repeating a
+    // pending announcement does nothing.
+    pub fn announce(
+        &mut self,
+        text: impl Into<String>,
+        politeness: Politeness,
+    ) {
+        let announcement = Announcement { text: text.into(), politeness };
+        if announcement.text.is_empty() || self.pending.contains(&announcement)
+        {
+            return;
+        }
+        if politeness == Politeness::Assertive {
+            self.pending
+                .retain(|pending| pending.politeness == Politeness::Assertive);
+        }
+        self.pending.push_back(announcement);
+    }
+
+    #[inline(always)]
+    pub fn polite(&mut self, text: impl Into<String>) {
+        self.announce(text, Politeness::Polite);
+    }
+
+    #[inline(always)]
+    pub fn assertive(&mut self, text: impl Into<String>) {
+        self.announce(text, Politeness::Assertive);
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.pending.is_empty() }
+
+    #[inline(always)]
+    pub fn pending(&self) -> impl Iterator<Item = &Announcement> {
+        self.pending.iter()
+    }
+
+    // Hands the pending announcements to the backend, assertive ones
+    // first.
+    pub fn flush(&mut self, sink: &mut impl AnnouncementSink) {
+        let (assertive, polite): (Vec<_>, Vec<_>) =
+            self.pending.drain(..).partition(|announcement| {
+                announcement.politeness == Politeness::Assertive
+            });
+        for announcement in assertive.iter().chain(&polite) {
+            sink.announce(announcement);
+        }
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) { self.pending.clear(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Announcement, AnnouncementSink, Announcer};
+
+    #[derive(Default)]
+    struct Spoken(Vec<String>);
+
+    impl AnnouncementSink for Spoken {
+        fn announce(&mut self, announcement: &Announcement) {
+            self.0.push(announcement.text().to_owned());
+        }
+    }
+
+    #[rstest]
+    fn test_flush_order() {
+        let mut announcer = Announcer::new();
+        announcer.assertive("Name is required");
+        announcer.polite("Results loaded");
+        announcer.polite("Results loaded");
+        announcer.assertive("Connection lost");
+
+        let mut spoken = Spoken::default();
+        announcer.flush(&mut spoken);
+        announcer.flush(&mut spoken);
+
+        assert_eq!(spoken.0, ["Name is required", "Connection lost"]);
+        assert!(announcer.is_empty());
+    }
+
+    #[rstest]
+    fn test_polite_after_assertive_is_kept() {
+        let mut announcer = Announcer::new();
+        announcer.polite("Saved");
+        announcer.assertive("Disk full");
+        announcer.polite("3 new messages");
+        announcer.polite("");
+
+        let mut spoken = Spoken::default();
+        announcer.flush(&mut spoken);
+
+        assert_eq!(spoken.0, ["Disk full", "3 new messages"]);
+    }
+}
@@ -0,0 +1,54 @@
+use glam::Mat4;
+
+use super::build_model;
+use crate::core::ArcSegment;
+
+// A stroked arc around a center; angles are in radians, clockwise from
+// the positive x axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Arc {
+    pub center:      [f32; 2],
+    pub radius:      f32,
+    pub thickness:   f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+    pub round_caps:  bool,
+}
+
+impl Arc {
+    #[must_use]
+    pub fn build_segment(
+        &self,
+        color: [f32; 4],
+        clip_rect: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> ArcSegment {
+        // A pixel of slack keeps the antialiased edge inside the quad.
+        let extent = self.radius + self.thickness / 2.0 + 1.0;
+        let (model, half_size) = build_model(
+            [extent * 2.0; 2],
+            [self.center[0] - extent, self.center[1] - extent],
+            dpr,
+        );
+        let mvp = view_projection * model;
+
+        ArcSegment::builder()
+            .mvp(mvp.to_cols_array_2d())
+            .color(color)
+            .clip_rect(clip_rect.map(|value| value * dpr))
+            .geometry([
+                self.radius * dpr,
+                self.thickness * dpr,
+                self.start_angle,
+                self.sweep_angle.max(0.0),
+            ])
+            .size_and_caps([
+                half_size[0],
+                self.round_caps as u8 as f32,
+                0.0,
+                0.0,
+            ])
+            .build()
+    }
+}
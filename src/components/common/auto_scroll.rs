@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use bon::Builder;
+
+use super::{Bounds, intersect_rects};
+
+// Distances are in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct AutoScrollSettings {
+    // How far in from the edges scrolling starts.
+    #[builder(default = 40.0)]
+    edge_size: f32,
+    // Per second, reached at the edge and past it.
+    #[builder(default = 1200.0)]
+    max_speed: f32,
+    // Which of x and y scroll.
+    #[builder(default = [true, true])]
+    axes:      [bool; 2],
+}
+
+// Scrolls a region while a drag, of a selection, a list item or a text
+// cursor, holds the pointer near its edges, faster the closer it gets.
+// Only the visible part of the region counts, so a scroll view reaching
+// past the window scrolls at the window's edge, where the pointer can
+// still get to.
+pub struct AutoScroll {
+    region:   Bounds,
+    settings: AutoScrollSettings,
+    pointer:  Option<[f32; 2]>,
+}
+
+impl AutoScroll {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(region: Bounds, settings: AutoScrollSettings) -> Self {
+        Self { region, settings, pointer: None }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn region(&self) -> &Bounds { &self.region }
+
+    #[inline(always)]
+    pub fn set_region(&mut self, region: Bounds) { self.region = region; }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn settings(&self) -> &AutoScrollSettings { &self.settings }
+
+    #[inline(always)]
+    pub const fn set_settings(&mut self, settings: AutoScrollSettings) {
+        self.settings = settings;
+    }
+
+    // Call as the drag moves, and `end` when it's over.
+    #[inline(always)]
+    pub const fn drag(&mut self, pointer: [f32; 2]) {
+        self.pointer = Some(pointer);
+    }
+
+    #[inline(always)]
+    pub const fn end(&mut self) { self.pointer = None; }
+
+    // Scrolling speed per second; negative towards the start.
+    #[must_use]
+    pub fn velocity(&self) -> [f32; 2] {
+        let Some(pointer) = self.pointer else {
+            return [0.0; 2];
+        };
+        let [x, y] = self.region.position;
+        let [width, height] = self.region.size;
+        let visible =
+            intersect_rects([x, y, width, height], self.region.clip_rect);
+        edge_velocity(pointer, visible, &self.settings)
+    }
+
+    // Keeps the animation clock running while true.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_scrolling(&self) -> bool { self.velocity() != [0.0; 2] }
+
+    // How far to scroll the content by for the frame. Things under the
+    // pointer move as it does, so hit test the dragged position again
+    // afterwards.
+    #[must_use]
+    pub fn tick(&self, dt: Duration) -> [f32; 2] {
+        let seconds = dt.as_secs_f32();
+        self.velocity().map(|speed| speed * seconds)
+    }
+}
+
+fn edge_velocity(
+    pointer: [f32; 2],
+    rect: [f32; 4],
+    settings: &AutoScrollSettings,
+) -> [f32; 2] {
+    [0, 1].map(|axis| {
+        let start = rect[axis];
+        let size = rect[axis + 2];
+        if !settings.axes[axis] || size <= 0.0 {
+            return 0.0;
+        }
+        // Edges of small regions share the middle.
+        let edge_size = settings.edge_size.min(size / 2.0);
+        if edge_size <= 0.0 {
+            return 0.0;
+        }
+        let to_start = pointer[axis] - start;
+        let to_end = start + size - pointer[axis];
+        let proximity = |distance: f32| {
+            ((edge_size - distance) / edge_size).clamp(0.0, 1.0)
+        };
+
+        match to_start <= to_end {
+            true => -settings.max_speed * proximity(to_start),
+            false => settings.max_speed * proximity(to_end),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::{AutoScroll, AutoScrollSettings, edge_velocity};
+    use crate::components::common::Bounds;
+
+    const SETTINGS: AutoScrollSettings = AutoScrollSettings::builder()
+        .edge_size(40.0)
+        .max_speed(1000.0)
+        .build();
+
+    #[rstest]
+    #[case([200.0, 150.0], [0.0, 0.0])]
+    #[case([200.0, 20.0], [0.0, -500.0])]
+    #[case([390.0, 150.0], [750.0, 0.0])]
+    // past the edges
+    #[case([-30.0, 350.0], [-1000.0, 1000.0])]
+    fn test_edge_velocity(
+        #[case] pointer: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let rect = [0.0, 0.0, 400.0, 300.0];
+        assert_eq!(edge_velocity(pointer, rect, &SETTINGS), expected);
+    }
+
+    #[rstest]
+    fn test_edge_velocity_on_disabled_axis() {
+        let settings =
+            AutoScrollSettings::builder().axes([false, true]).build();
+        let rect = [0.0, 0.0, 400.0, 300.0];
+        assert_eq!(edge_velocity([0.0, 150.0], rect, &settings), [0.0, 0.0]);
+    }
+
+    #[rstest]
+    fn test_scrolls_at_the_visible_edge() {
+        // A scroll view reaching past the bottom of a 300 pixels high
+        // window.
+        let region = Bounds {
+            position:  [0.0, 100.0],
+            size:      [400.0, 500.0],
+            clip_rect: [0.0, 0.0, 400.0, 300.0],
+        };
+        let mut auto_scroll = AutoScroll::new(region, SETTINGS);
+        assert!(!auto_scroll.is_scrolling());
+
+        auto_scroll.drag([200.0, 290.0]);
+        assert_eq!(auto_scroll.velocity(), [0.0, 750.0]);
+        assert_eq!(auto_scroll.tick(Duration::from_millis(100)), [0.0, 75.0]);
+
+        auto_scroll.end();
+        assert_eq!(auto_scroll.tick(Duration::from_millis(100)), [0.0, 0.0]);
+    }
+}
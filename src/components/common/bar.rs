@@ -0,0 +1,56 @@
+use glam::Mat4;
+
+use super::build_rotated_model;
+use crate::core::Rectangle;
+
+// A rounded line segment, for glyphs such as chevrons and crosses that
+// are built from rotated rectangles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bar {
+    pub center:    [f32; 2],
+    pub length:    f32,
+    pub thickness: f32,
+    pub angle:     f32,
+}
+
+impl Bar {
+    #[must_use]
+    pub fn between(start: [f32; 2], end: [f32; 2], thickness: f32) -> Self {
+        let delta = [end[0] - start[0], end[1] - start[1]];
+        Self {
+            center: [(start[0] + end[0]) / 2.0, (start[1] + end[1]) / 2.0],
+            length: delta[0].hypot(delta[1]) + thickness,
+            thickness,
+            angle: delta[1].atan2(delta[0]),
+        }
+    }
+
+    #[must_use]
+    pub fn build_rectangle(
+        &self,
+        color: [f32; 4],
+        clip_rect: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let (model, half_size) = build_rotated_model(
+            [self.length, self.thickness],
+            self.center,
+            self.angle,
+            dpr,
+        );
+        let mvp = view_projection * model;
+
+        Rectangle::builder()
+            .mvp(mvp.to_cols_array_2d())
+            .fill_color(color)
+            .border_color([0.0; 4])
+            .corner_radii([half_size[1]; 4])
+            .shadow_color([0.0; 4])
+            .outline_color([0.0; 4])
+            .clip_rect(clip_rect.map(|value| value * dpr))
+            .rect_and_shadow([half_size[0], half_size[1], 0.0, 0.0])
+            .sizes([0.0; 4])
+            .build()
+    }
+}
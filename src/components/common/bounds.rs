@@ -2,6 +2,9 @@
 pub struct Bounds {
     pub position:  [f32; 2],
     pub size:      [f32; 2],
+    // x, y, width, height in logical pixels on screen, like the renderer's
+    // clip stack. It used to be relative to the rectangle's own top left
+    // corner; such clip rects have to be offset by `position` now.
     pub clip_rect: [f32; 4],
 }
 
@@ -15,3 +18,32 @@ impl Bounds {
         Bounds { position, size, clip_rect: [0.0, 0.0, f32::MAX, f32::MAX] }
     }
 }
+
+#[must_use]
+pub(crate) const fn intersect_rects(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let x = a[0].max(b[0]);
+    let y = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+
+    [x, y, (right - x).max(0.0), (bottom - y).max(0.0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::intersect_rects;
+
+    #[rstest]
+    #[case([0.0, 0.0, 100.0, 100.0], [50.0, 25.0, 100.0, 50.0], [50.0, 25.0, 50.0, 50.0])]
+    #[case([0.0, 0.0, 10.0, 10.0], [20.0, 20.0, 10.0, 10.0], [20.0, 20.0, 0.0, 0.0])]
+    #[case([0.0, 0.0, f32::MAX, f32::MAX], [5.0, 5.0, 10.0, 10.0], [5.0, 5.0, 10.0, 10.0])]
+    fn test_intersect_rects(
+        #[case] a: [f32; 4],
+        #[case] b: [f32; 4],
+        #[case] expected: [f32; 4],
+    ) {
+        assert_eq!(intersect_rects(a, b), expected);
+    }
+}
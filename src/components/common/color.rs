@@ -0,0 +1,50 @@
+// A color written as `0xrrggbbaa`, as in theme files, usable in consts
+// and statics:
+//
+// const ACCENT: [f32; 4] = rgba8(0x3584e4ff);
+#[must_use]
+pub const fn rgba8(rgba: u32) -> [f32; 4] {
+    let mut color = [0.0; 4];
+    let mut index = 0;
+    while index < 4 {
+        let shift = 24 - 8 * index as u32;
+        color[index] = ((rgba >> shift) & 0xff) as f32 / 255.0;
+        index += 1;
+    }
+    color
+}
+
+// The reverse of `rgba8`, rounding each channel to the nearest byte.
+#[must_use]
+pub const fn to_rgba8(color: [f32; 4]) -> u32 {
+    let mut rgba = 0;
+    let mut index = 0;
+    while index < 4 {
+        let channel = (color[index].clamp(0.0, 1.0) * 255.0 + 0.5) as u32;
+        rgba = (rgba << 8) | channel;
+        index += 1;
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{rgba8, to_rgba8};
+
+    #[rstest]
+    #[case(0x3584e4ff)]
+    #[case(0x00000000)]
+    #[case(0xffffff80)]
+    fn test_round_trip(#[case] rgba: u32) {
+        assert_eq!(to_rgba8(rgba8(rgba)), rgba);
+    }
+
+    #[rstest]
+    fn test_rgba8() {
+        const COLOR: [f32; 4] = rgba8(0xff000080);
+        assert_eq!(COLOR, [1.0, 0.0, 0.0, 128.0 / 255.0]);
+        assert_eq!(to_rgba8([2.0, -1.0, 0.499, 1.0]), 0xff007fff);
+    }
+}
@@ -0,0 +1,53 @@
+// What an app calls a widget across rebuilds: the tag it puts on it,
+// derived from the domain object behind it, so that a row rebuilt for
+// the same object has the same id.
+pub type WidgetId = u64;
+
+// Which widget has keyboard focus. Focus is kept by id, so it survives
+// the widget being destroyed and built again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FocusState {
+    focused: Option<WidgetId>,
+}
+
+impl FocusState {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self { Self { focused: None } }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn focused(&self) -> Option<WidgetId> { self.focused }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_focused(&self, id: WidgetId) -> bool { self.focused == Some(id) }
+
+    // Returns whether the focus moved.
+    pub fn focus(&mut self, id: WidgetId) -> bool {
+        self.focused.replace(id) != Some(id)
+    }
+
+    // Returns the widget that had focus.
+    #[inline(always)]
+    pub const fn blur(&mut self) -> Option<WidgetId> { self.focused.take() }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::FocusState;
+
+    #[rstest]
+    fn test_focus() {
+        let mut focus = FocusState::new();
+        assert!(focus.focus(1));
+        assert!(!focus.focus(1));
+        assert!(focus.is_focused(1));
+
+        assert!(focus.focus(2));
+        assert_eq!(focus.blur(), Some(2));
+        assert_eq!(focus.focused(), None);
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+// Browsers treat near-zero frame delays as 100ms, and so do we: a GIF with
+// zero delays would otherwise spin through frames as fast as it's ticked.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(10);
+const FALLBACK_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramePlayback {
+    delays:     Vec<Duration>,
+    frame:      usize,
+    elapsed:    Duration,
+    is_playing: bool,
+    is_looping: bool,
+}
+
+impl FramePlayback {
+    #[must_use]
+    pub fn new(delays: impl IntoIterator<Item = Duration>) -> Self {
+        let delays = delays
+            .into_iter()
+            .map(|delay| match delay <= MIN_FRAME_DELAY {
+                true => FALLBACK_FRAME_DELAY,
+                false => delay,
+            })
+            .collect();
+        Self {
+            delays,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            is_playing: true,
+            is_looping: true,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn frame(&self) -> usize { self.frame }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn frame_count(&self) -> usize { self.delays.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_playing(&self) -> bool { self.is_playing }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_looping(&self) -> bool { self.is_looping }
+
+    #[inline(always)]
+    pub const fn play(&mut self) { self.is_playing = true; }
+
+    #[inline(always)]
+    pub const fn pause(&mut self) { self.is_playing = false; }
+
+    #[inline(always)]
+    pub const fn set_looping(&mut self, is_looping: bool) {
+        self.is_looping = is_looping;
+    }
+
+    #[inline(always)]
+    pub const fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = Duration::ZERO;
+        self.is_playing = true;
+    }
+
+    pub fn advance(&mut self, dt: Duration) -> bool {
+        if !self.is_playing || self.delays.len() < 2 {
+            return false;
+        }
+        let previous_frame = self.frame;
+        self.elapsed += dt;
+
+        if self.is_looping {
+            let total: Duration = self.delays.iter().sum();
+            if self.elapsed >= total {
+                let elapsed = self.elapsed.as_nanos() % total.as_nanos();
+                self.elapsed = Duration::from_nanos(elapsed as u64);
+                self.frame = 0;
+            }
+        }
+
+        while self.elapsed >= self.delays[self.frame] {
+            self.elapsed -= self.delays[self.frame];
+
+            if self.frame + 1 < self.delays.len() {
+                self.frame += 1;
+            } else if self.is_looping {
+                self.frame = 0;
+            } else {
+                self.elapsed = Duration::ZERO;
+                self.is_playing = false;
+                break;
+            }
+        }
+
+        self.frame != previous_frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::FramePlayback;
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    // Frames of 100ms, 200ms and 300ms: 600ms per loop.
+    #[rstest]
+    #[case(true, &[ms(50)], 0, true)]
+    #[case(true, &[ms(100)], 1, true)]
+    #[case(true, &[ms(100), ms(250)], 2, true)]
+    #[case(true, &[ms(650)], 0, true)]
+    #[case(true, &[ms(1300)], 1, true)]
+    #[case(false, &[ms(599)], 2, true)]
+    #[case(false, &[ms(600)], 2, false)]
+    #[case(false, &[ms(5000)], 2, false)]
+    fn test_frame_playback_advance(
+        #[case] is_looping: bool,
+        #[case] steps: &[Duration],
+        #[case] expected_frame: usize,
+        #[case] expected_is_playing: bool,
+    ) {
+        let mut playback = FramePlayback::new([ms(100), ms(200), ms(300)]);
+        playback.set_looping(is_looping);
+
+        for step in steps {
+            playback.advance(*step);
+        }
+        assert_eq!(playback.frame(), expected_frame);
+        assert_eq!(playback.is_playing(), expected_is_playing);
+    }
+
+    #[rstest]
+    fn test_frame_playback_zero_delays() {
+        let mut playback = FramePlayback::new([Duration::ZERO; 3]);
+
+        assert!(playback.advance(ms(100)));
+        assert_eq!(playback.frame(), 1);
+    }
+
+    #[rstest]
+    fn test_frame_playback_paused() {
+        let mut playback = FramePlayback::new([ms(100), ms(100)]);
+        playback.pause();
+
+        assert!(!playback.advance(ms(150)));
+        assert_eq!(playback.frame(), 0);
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use super::Bounds;
+
+// The alpha channel of an image, for hit testing by where it's opaque.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlphaMask {
+    size:  [u32; 2],
+    alpha: Vec<u8>,
+}
+
+impl AlphaMask {
+    // Rows of alpha values, top to bottom.
+    #[must_use]
+    pub fn new(size: [u32; 2], alpha: Vec<u8>) -> Self {
+        debug_assert_eq!(alpha.len(), size[0] as usize * size[1] as usize);
+        Self { size, alpha }
+    }
+
+    #[must_use]
+    pub fn from_rgba(size: [u32; 2], rgba: &[u8]) -> Self {
+        let alpha = rgba.chunks_exact(4).map(|pixel| pixel[3]).collect();
+        Self::new(size, alpha)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn size(&self) -> [u32; 2] { self.size }
+
+    // At a point in [0, 1] across the image; transparent outside of it.
+    #[must_use]
+    pub fn alpha_at(&self, uv: [f32; 2]) -> u8 {
+        if !(0.0..=1.0).contains(&uv[0]) || !(0.0..=1.0).contains(&uv[1]) {
+            return 0;
+        }
+        let [x, y] = [0, 1].map(|axis| {
+            let size = self.size[axis] as usize;
+            ((uv[axis] * size as f32) as usize).min(size.saturating_sub(1))
+        });
+        self.alpha
+            .get(y * self.size[0] as usize + x)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+// The area of a widget's bounds that takes the pointer, so that presses
+// on the transparent corners of a round button go to what's behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HitShape {
+    Rect,
+    // Radii in logical pixels, clockwise from the top left.
+    RoundedRect([f32; 4]),
+    // Inscribed in the bounds; a circle for square ones.
+    Ellipse,
+    // Points as fractions of the bounds' size from their top left
+    // corner, so the shape follows the bounds as they resize. Crossing
+    // edges are filled even-odd.
+    Polygon(Vec<[f32; 2]>),
+    // Stretched over the bounds; hits where alpha is at least `threshold`.
+    Alpha { mask: Arc<AlphaMask>, threshold: u8 },
+}
+
+impl HitShape {
+    #[must_use]
+    pub fn contains(&self, bounds: &Bounds, position: [f32; 2]) -> bool {
+        let [x, y] = bounds.position;
+        let [width, height] = bounds.size;
+        let local = [position[0] - x, position[1] - y];
+        let is_in_rect = local[0] >= 0.0
+            && local[0] <= width
+            && local[1] >= 0.0
+            && local[1] <= height;
+        if !is_in_rect {
+            return false;
+        }
+
+        match self {
+            Self::Rect => true,
+            Self::RoundedRect(radii) => {
+                is_in_rounded_rect(local, [width, height], *radii)
+            }
+            Self::Ellipse => {
+                let [half_width, half_height] = [width / 2.0, height / 2.0];
+                if half_width <= 0.0 || half_height <= 0.0 {
+                    return false;
+                }
+                let dx = (local[0] - half_width) / half_width;
+                let dy = (local[1] - half_height) / half_height;
+                dx * dx + dy * dy <= 1.0
+            }
+            Self::Polygon(points) => {
+                if width <= 0.0 || height <= 0.0 {
+                    return false;
+                }
+                is_in_polygon([local[0] / width, local[1] / height], points)
+            }
+            Self::Alpha { mask, threshold } => {
+                if width <= 0.0 || height <= 0.0 {
+                    return false;
+                }
+                let uv = [local[0] / width, local[1] / height];
+                mask.alpha_at(uv) >= *threshold
+            }
+        }
+    }
+}
+
+fn is_in_rounded_rect(
+    local: [f32; 2],
+    size: [f32; 2],
+    radii: [f32; 4],
+) -> bool {
+    let max_radius = size[0].min(size[1]) / 2.0;
+    let [width, height] = size;
+    let is_right = local[0] > width / 2.0;
+    let is_bottom = local[1] > height / 2.0;
+    let corner = match (is_right, is_bottom) {
+        (false, false) => 0,
+        (true, false) => 1,
+        (true, true) => 2,
+        (false, true) => 3,
+    };
+    let radius = radii[corner].clamp(0.0, max_radius);
+
+    // Distance into the corner's square, measured from its circle's
+    // center.
+    let dx = match is_right {
+        true => local[0] - (width - radius),
+        false => radius - local[0],
+    };
+    let dy = match is_bottom {
+        true => local[1] - (height - radius),
+        false => radius - local[1],
+    };
+    dx <= 0.0 || dy <= 0.0 || dx * dx + dy * dy <= radius * radius
+}
+
+fn is_in_polygon(point: [f32; 2], points: &[[f32; 2]]) -> bool {
+    let mut is_inside = false;
+    let mut previous = match points.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for &current in points {
+        let crosses = (current[1] > point[1]) != (previous[1] > point[1]);
+        if crosses {
+            let t = (point[1] - current[1]) / (previous[1] - current[1]);
+            let edge_x = current[0] + t * (previous[0] - current[0]);
+            if point[0] < edge_x {
+                is_inside = !is_inside;
+            }
+        }
+        previous = current;
+    }
+    is_inside
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use super::{AlphaMask, HitShape};
+    use crate::components::common::Bounds;
+
+    fn bounds() -> Bounds {
+        Bounds::without_clip_rect([10.0, 10.0], [100.0, 100.0])
+    }
+
+    #[rstest]
+    #[case(HitShape::Rect, [12.0, 12.0], true)]
+    #[case(HitShape::Rect, [5.0, 50.0], false)]
+    // the transparent corner of a round button
+    #[case(HitShape::Ellipse, [15.0, 15.0], false)]
+    #[case(HitShape::Ellipse, [60.0, 12.0], true)]
+    #[case(HitShape::RoundedRect([20.0, 0.0, 0.0, 0.0]), [12.0, 12.0], false)]
+    #[case(HitShape::RoundedRect([20.0, 0.0, 0.0, 0.0]), [108.0, 12.0], true)]
+    #[case(HitShape::RoundedRect([20.0, 0.0, 0.0, 0.0]), [20.0, 20.0], true)]
+    // a triangle pointing up
+    #[case(
+        HitShape::Polygon(vec![[0.5, 0.0], [1.0, 1.0], [0.0, 1.0]]),
+        [60.0, 80.0],
+        true,
+    )]
+    #[case(
+        HitShape::Polygon(vec![[0.5, 0.0], [1.0, 1.0], [0.0, 1.0]]),
+        [15.0, 20.0],
+        false,
+    )]
+    fn test_hit_shape_contains(
+        #[case] shape: HitShape,
+        #[case] position: [f32; 2],
+        #[case] expected: bool,
+    ) {
+        assert_eq!(shape.contains(&bounds(), position), expected);
+    }
+
+    #[rstest]
+    #[case([20.0, 20.0], true)]
+    #[case([80.0, 30.0], true)]
+    #[case([20.0, 80.0], false)]
+    #[case([80.0, 80.0], false)]
+    fn test_alpha_hit_shape(
+        #[case] position: [f32; 2],
+        #[case] expected: bool,
+    ) {
+        // A 2 by 2 image whose top row is opaque enough.
+        let rgba =
+            [[0, 0, 0, 255], [0, 0, 0, 200], [0, 0, 0, 0], [0, 0, 0, 100]]
+                .concat();
+        let mask = AlphaMask::from_rgba([2, 2], &rgba);
+        let shape =
+            HitShape::Alpha { mask: Arc::new(mask), threshold: 128 };
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [100.0, 100.0]);
+
+        assert_eq!(shape.contains(&bounds, position), expected);
+    }
+}
@@ -0,0 +1,250 @@
+use std::time::{Duration, Instant};
+
+use bon::Builder;
+
+use crate::components::timing::{TimerId, TimerQueue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct HoverIntentSettings {
+    // How long the pointer has to rest on the target, e.g. before a
+    // tooltip shows or a submenu opens.
+    #[builder(default = Duration::from_millis(500))]
+    delay:       Duration,
+    // In logical pixels per second. Moving faster than this over the
+    // target starts the delay over, so passing through on the way to
+    // something else doesn't count.
+    #[builder(default = 300.0)]
+    max_speed:   f32,
+    // How long the intent outlives the pointer leaving, so that a submenu
+    // stays open while the pointer cuts across its parent's other items.
+    #[builder(default = Duration::ZERO)]
+    leave_delay: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoverIntentEvent {
+    Started,
+    Ended,
+}
+
+// Tells a hover the user means from the pointer merely passing over a
+// target. Waiting is done with the scheduler's timers: call `update` as
+// the pointer moves and `poll` after the timers expire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoverIntent {
+    settings:    HoverIntentSettings,
+    is_hovered:  bool,
+    is_intended: bool,
+    timer:       Option<TimerId>,
+    last_move:   Option<([f32; 2], Instant)>,
+}
+
+impl HoverIntent {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(settings: HoverIntentSettings) -> Self {
+        Self {
+            settings,
+            is_hovered: false,
+            is_intended: false,
+            timer: None,
+            last_move: None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn settings(&self) -> &HoverIntentSettings { &self.settings }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_hovered(&self) -> bool { self.is_hovered }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_intended(&self) -> bool { self.is_intended }
+
+    // `is_hovered` is whether the pointer is over the target now. Only
+    // leaving without a leave delay ends the intent right away.
+    pub fn update(
+        &mut self,
+        is_hovered: bool,
+        pointer: [f32; 2],
+        now: Instant,
+        timers: &mut TimerQueue,
+    ) -> Option<HoverIntentEvent> {
+        let is_fast =
+            self.last_move.is_some_and(|(last_pointer, last_time)| {
+                let dt =
+                    now.saturating_duration_since(last_time).as_secs_f32();
+                let distance = (pointer[0] - last_pointer[0])
+                    .hypot(pointer[1] - last_pointer[1]);
+                dt > 0.0 && distance / dt > self.settings.max_speed
+            });
+        let was_hovered = self.is_hovered;
+        self.is_hovered = is_hovered;
+        self.last_move = is_hovered.then_some((pointer, now));
+
+        match (was_hovered, is_hovered, self.is_intended) {
+            (false, true, false) => {
+                self.restart(self.settings.delay, now, timers);
+            }
+            (true, true, false) if is_fast => {
+                self.restart(self.settings.delay, now, timers);
+            }
+            (true, false, false) => self.cancel(timers),
+            // Back before the leave delay ran out.
+            (false, true, true) => self.cancel(timers),
+            (true, false, true) => {
+                if self.settings.leave_delay.is_zero() {
+                    self.is_intended = false;
+                    return Some(HoverIntentEvent::Ended);
+                }
+                self.restart(self.settings.leave_delay, now, timers);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // Call after `Scheduler::expire_timers` fired any.
+    pub fn poll(
+        &mut self,
+        timers: &mut TimerQueue,
+    ) -> Option<HoverIntentEvent> {
+        let timer = self.timer?;
+        if !timers.take_fired(timer) {
+            return None;
+        }
+        self.timer = None;
+        self.is_intended = !self.is_intended;
+        match self.is_intended {
+            true => Some(HoverIntentEvent::Started),
+            false => Some(HoverIntentEvent::Ended),
+        }
+    }
+
+    // Drops the intent without reporting it, e.g. when the target goes
+    // away or is pressed.
+    pub fn reset(&mut self, timers: &mut TimerQueue) {
+        self.cancel(timers);
+        self.is_hovered = false;
+        self.is_intended = false;
+        self.last_move = None;
+    }
+
+    fn restart(
+        &mut self,
+        delay: Duration,
+        now: Instant,
+        timers: &mut TimerQueue,
+    ) {
+        self.cancel(timers);
+        self.timer = Some(timers.start(now, delay));
+    }
+
+    fn cancel(&mut self, timers: &mut TimerQueue) {
+        if let Some(timer) = self.timer.take() {
+            timers.cancel(timer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use rstest::rstest;
+
+    use super::{HoverIntent, HoverIntentEvent, HoverIntentSettings};
+    use crate::components::timing::TimerQueue;
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    const SETTINGS: HoverIntentSettings = HoverIntentSettings::builder()
+        .delay(ms(500))
+        .max_speed(100.0)
+        .build();
+
+    fn expire(
+        intent: &mut HoverIntent,
+        timers: &mut TimerQueue,
+        now: Instant,
+    ) -> Option<HoverIntentEvent> {
+        timers.expire(now);
+        intent.poll(timers)
+    }
+
+    #[rstest]
+    fn test_sustained_hover() {
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let mut intent = HoverIntent::new(SETTINGS);
+
+        intent.update(true, [0.0, 0.0], start, &mut timers);
+        assert_eq!(expire(&mut intent, &mut timers, start + ms(400)), None);
+        // Slow moves don't start the delay over.
+        intent.update(true, [2.0, 0.0], start + ms(450), &mut timers);
+        assert_eq!(
+            expire(&mut intent, &mut timers, start + ms(500)),
+            Some(HoverIntentEvent::Started)
+        );
+
+        assert_eq!(
+            intent.update(false, [50.0, 0.0], start + ms(600), &mut timers),
+            Some(HoverIntentEvent::Ended)
+        );
+        assert!(timers.is_empty());
+    }
+
+    #[rstest]
+    fn test_passing_through_is_not_intent() {
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let mut intent = HoverIntent::new(SETTINGS);
+
+        intent.update(true, [0.0, 0.0], start, &mut timers);
+        intent.update(false, [40.0, 0.0], start + ms(100), &mut timers);
+        assert_eq!(expire(&mut intent, &mut timers, start + ms(1_000)), None);
+        assert!(!intent.is_intended());
+    }
+
+    #[rstest]
+    fn test_fast_moves_start_over() {
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let mut intent = HoverIntent::new(SETTINGS);
+
+        intent.update(true, [0.0, 0.0], start, &mut timers);
+        // 400 pixels per second
+        intent.update(true, [40.0, 0.0], start + ms(100), &mut timers);
+        assert_eq!(expire(&mut intent, &mut timers, start + ms(500)), None);
+        assert_eq!(
+            expire(&mut intent, &mut timers, start + ms(600)),
+            Some(HoverIntentEvent::Started)
+        );
+    }
+
+    #[rstest]
+    #[case(ms(100), true)]
+    #[case(ms(300), false)]
+    fn test_leave_delay(#[case] away: Duration, #[case] is_intended: bool) {
+        let settings = HoverIntentSettings::builder()
+            .delay(ms(0))
+            .leave_delay(ms(200))
+            .build();
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let mut intent = HoverIntent::new(settings);
+        intent.update(true, [0.0, 0.0], start, &mut timers);
+        expire(&mut intent, &mut timers, start);
+        assert!(intent.is_intended());
+
+        intent.update(false, [0.0, 0.0], start + ms(1_000), &mut timers);
+        expire(&mut intent, &mut timers, start + ms(1_000) + away);
+        intent.update(true, [0.0, 0.0], start + ms(1_000) + away, &mut timers);
+
+        assert_eq!(intent.is_intended(), is_intended);
+    }
+}
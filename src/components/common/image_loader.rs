@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, Sender},
+    },
+    time::Duration,
+};
+
+use rayon::ThreadPool;
+
+use crate::core::{Renderer, TextureId};
+
+pub type ImageDecoder = dyn Fn(&Path) -> Option<DecodedImage> + Send + Sync;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedFrame {
+    pub rgba:  Vec<u8>,
+    pub delay: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedImage {
+    pub size:   [u32; 2],
+    pub frames: Vec<DecodedFrame>,
+}
+
+impl DecodedImage {
+    #[must_use]
+    pub fn still(size: [u32; 2], rgba: Vec<u8>) -> Self {
+        let frame = DecodedFrame { rgba, delay: Duration::ZERO };
+        Self { size, frames: vec![frame] }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageFrame {
+    pub texture_id: TextureId,
+    pub delay:      Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageLoadState {
+    Loading,
+    Ready(TextureId),
+    Failed,
+}
+
+struct CachedImage {
+    state:           ImageLoadState,
+    frames:          Vec<ImageFrame>,
+    byte_size:       usize,
+    last_used_frame: u64,
+}
+
+pub struct ImageLoader {
+    thread_pool:  Arc<ThreadPool>,
+    decoder:      Arc<ImageDecoder>,
+    sender:       Sender<(PathBuf, Option<DecodedImage>)>,
+    receiver:     Receiver<(PathBuf, Option<DecodedImage>)>,
+    cache:        HashMap<PathBuf, CachedImage>,
+    budget_bytes: usize,
+    used_bytes:   usize,
+    frame:        u64,
+}
+
+impl ImageLoader {
+    #[must_use]
+    pub fn new(
+        thread_pool: Arc<ThreadPool>,
+        budget_bytes: usize,
+        decoder: Arc<ImageDecoder>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            thread_pool,
+            decoder,
+            sender,
+            receiver,
+            cache: HashMap::new(),
+            budget_bytes,
+            used_bytes: 0,
+            frame: 0,
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn with_image_decoder(
+        thread_pool: Arc<ThreadPool>,
+        budget_bytes: usize,
+    ) -> Self {
+        Self::new(thread_pool, budget_bytes, Arc::new(decode_file))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn used_bytes(&self) -> usize { self.used_bytes }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn budget_bytes(&self) -> usize { self.budget_bytes }
+
+    #[inline(always)]
+    pub const fn set_budget_bytes(&mut self, budget_bytes: usize) {
+        self.budget_bytes = budget_bytes;
+    }
+
+    #[must_use]
+    pub fn state(&self, path: &Path) -> Option<ImageLoadState> {
+        self.cache.get(path).map(|cached| cached.state)
+    }
+
+    #[must_use]
+    pub fn frames(&self, path: &Path) -> &[ImageFrame] {
+        self.cache.get(path).map_or(&[], |cached| &cached.frames)
+    }
+
+    pub fn load(&mut self, path: &Path) -> ImageLoadState {
+        if let Some(cached) = self.cache.get_mut(path) {
+            cached.last_used_frame = self.frame;
+            return cached.state;
+        }
+
+        let path = path.to_path_buf();
+        let decoder = self.decoder.clone();
+        let sender = self.sender.clone();
+        let task_path = path.clone();
+        self.thread_pool.spawn(move || {
+            let decoded = decoder(&task_path);
+            let _ = sender.send((task_path, decoded));
+        });
+
+        let cached = CachedImage {
+            state:           ImageLoadState::Loading,
+            frames:          Vec::new(),
+            byte_size:       0,
+            last_used_frame: self.frame,
+        };
+        self.cache.insert(path, cached);
+
+        ImageLoadState::Loading
+    }
+
+    pub fn poll(&mut self, renderer: &mut Renderer) -> bool {
+        let mut is_changed = false;
+
+        while let Ok((path, decoded)) = self.receiver.try_recv() {
+            let Some(cached) = self.cache.get_mut(&path) else { continue };
+
+            let decoded = decoded.filter(|image| !image.frames.is_empty());
+            cached.state = match decoded {
+                Some(image) => {
+                    for frame in &image.frames {
+                        let texture_id =
+                            renderer.add_texture(image.size, &frame.rgba);
+                        let frame =
+                            ImageFrame { texture_id, delay: frame.delay };
+                        cached.frames.push(frame);
+                        cached.byte_size += frame_byte_size(image.size);
+                    }
+                    self.used_bytes += cached.byte_size;
+                    ImageLoadState::Ready(cached.frames[0].texture_id)
+                }
+                None => ImageLoadState::Failed,
+            };
+            is_changed = true;
+        }
+        is_changed |= self.evict(renderer);
+        self.frame += 1;
+
+        is_changed
+    }
+
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        for (_, cached) in self.cache.drain() {
+            for frame in cached.frames {
+                renderer.remove_texture(frame.texture_id);
+            }
+        }
+        self.used_bytes = 0;
+    }
+
+    fn evict(&mut self, renderer: &mut Renderer) -> bool {
+        if self.used_bytes <= self.budget_bytes {
+            return false;
+        }
+
+        // Images requested during the current frame are on screen and are
+        // never evicted, even if that keeps the cache over budget.
+        let mut candidates: Vec<_> = self
+            .cache
+            .iter()
+            .filter(|(_, cached)| {
+                cached.last_used_frame < self.frame
+                    && matches!(cached.state, ImageLoadState::Ready(_))
+            })
+            .map(|(path, cached)| (cached.last_used_frame, path.clone()))
+            .collect();
+        candidates.sort_unstable();
+
+        let mut is_evicted = false;
+        for (_, path) in candidates {
+            if self.used_bytes <= self.budget_bytes {
+                break;
+            }
+            let Some(cached) = self.cache.remove(&path) else { continue };
+            for frame in cached.frames {
+                renderer.remove_texture(frame.texture_id);
+            }
+            self.used_bytes -= cached.byte_size;
+            is_evicted = true;
+        }
+
+        is_evicted
+    }
+}
+
+const fn frame_byte_size(size: [u32; 2]) -> usize {
+    size[0] as usize * size[1] as usize * 4
+}
+
+#[cfg(feature = "image")]
+fn decode_file(path: &Path) -> Option<DecodedImage> {
+    use image::{AnimationDecoder, ImageFormat, codecs::gif::GifDecoder};
+
+    let reader = image::ImageReader::open(path).ok()?.with_guessed_format();
+    let reader = reader.ok()?;
+
+    if reader.format() == Some(ImageFormat::Gif) {
+        let frames = GifDecoder::new(reader.into_inner())
+            .ok()?
+            .into_frames()
+            .collect_frames()
+            .ok()?;
+        let first_frame = frames.first()?.buffer();
+        let size = [first_frame.width(), first_frame.height()];
+
+        let frames = frames
+            .into_iter()
+            .map(|frame| DecodedFrame {
+                delay: frame.delay().into(),
+                rgba:  frame.into_buffer().into_raw(),
+            })
+            .collect();
+        return Some(DecodedImage { size, frames });
+    }
+
+    let image = reader.decode().ok()?.into_rgba8();
+    let size = [image.width(), image.height()];
+
+    Some(DecodedImage::still(size, image.into_raw()))
+}
@@ -0,0 +1,200 @@
+use super::InputState;
+
+// What a handler sits in; higher layers see events first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InputLayer {
+    Content,
+    // Tooltips, menus and other popups.
+    Overlay,
+    // Dialogs; everything registered before the topmost one is cut off
+    // from input while it's open. Popups opened from it afterwards sit
+    // above it whatever their layer.
+    Modal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputPhase {
+    Capture,
+    Bubble,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct InputHandler {
+    id:           String,
+    layer:        InputLayer,
+    is_capturing: bool,
+}
+
+// Decides who gets each input event and in what order, for when tooltips,
+// menus, shortcuts and widgets compete for the same clicks. Handlers that
+// capture get the event first, from the bottom layer up, e.g. shortcuts
+// or a drag that has to see every event. The rest get it from the top
+// layer down, the last registered first within a layer, as they are
+// drawn. Routing stops at the first handler that consumes the event.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRouter {
+    handlers: Vec<InputHandler>,
+}
+
+impl InputRouter {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self { Self { handlers: Vec::new() } }
+
+    // Registering an id again moves it to the top of its layer.
+    pub fn register(
+        &mut self,
+        id: &str,
+        layer: InputLayer,
+        is_capturing: bool,
+    ) {
+        self.unregister(id);
+        self.handlers.push(InputHandler {
+            id: id.to_owned(),
+            layer,
+            is_capturing,
+        });
+    }
+
+    pub fn unregister(&mut self, id: &str) {
+        self.handlers.retain(|handler| handler.id != id);
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) { self.handlers.clear(); }
+
+    #[must_use]
+    pub fn is_registered(&self, id: &str) -> bool {
+        self.handlers.iter().any(|handler| handler.id == id)
+    }
+
+    // Who the next event goes to, in order, unless one consumes it.
+    #[must_use]
+    pub fn route(&self) -> Vec<(&str, InputPhase)> {
+        let modal = self.top_modal();
+        let mut reachable: Vec<(usize, &InputHandler)> = self
+            .handlers
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| modal.is_none_or(|modal| *index >= modal))
+            .collect();
+        // Stable, so registration order holds within a layer.
+        reachable.sort_by_key(|(index, handler)| {
+            (Some(*index) != modal, handler.layer)
+        });
+
+        let capture = reachable
+            .iter()
+            .filter(|(_, handler)| handler.is_capturing)
+            .map(|(_, handler)| (handler.id.as_str(), InputPhase::Capture));
+        let bubble = reachable
+            .iter()
+            .rev()
+            .filter(|(_, handler)| !handler.is_capturing)
+            .map(|(_, handler)| (handler.id.as_str(), InputPhase::Bubble));
+        capture.chain(bubble).collect()
+    }
+
+    // Hands the event synced into `input_state` to each handler along the
+    // route until one consumes it. An open modal consumes whatever its
+    // handlers leave, so nothing beneath reacts to it.
+    pub fn dispatch(
+        &self,
+        input_state: &mut InputState,
+        mut handle: impl FnMut(&str, InputPhase, &mut InputState),
+    ) {
+        for (id, phase) in self.route() {
+            if input_state.is_consumed() {
+                return;
+            }
+            handle(id, phase, input_state);
+        }
+        if let Some(modal) = self.top_modal() {
+            input_state.consume(&self.handlers[modal].id);
+        }
+    }
+
+    fn top_modal(&self) -> Option<usize> {
+        self.handlers
+            .iter()
+            .rposition(|handler| handler.layer == InputLayer::Modal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{InputLayer, InputPhase, InputRouter};
+    use crate::components::common::InputState;
+
+    fn router() -> InputRouter {
+        let mut router = InputRouter::new();
+        router.register("canvas", InputLayer::Content, false);
+        router.register("shortcuts", InputLayer::Content, true);
+        router.register("button", InputLayer::Content, false);
+        router.register("tooltip", InputLayer::Overlay, false);
+        router
+    }
+
+    #[rstest]
+    fn test_route_order() {
+        assert_eq!(
+            router().route(),
+            [
+                ("shortcuts", InputPhase::Capture),
+                ("tooltip", InputPhase::Bubble),
+                ("button", InputPhase::Bubble),
+                ("canvas", InputPhase::Bubble),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_modal_cuts_off_what_is_beneath() {
+        let mut router = router();
+        router.register("dialog", InputLayer::Modal, false);
+        router.register("dropdown", InputLayer::Overlay, false);
+        assert_eq!(
+            router.route(),
+            [
+                ("dropdown", InputPhase::Bubble),
+                ("dialog", InputPhase::Bubble)
+            ]
+        );
+
+        let mut input_state = InputState::default();
+        router.dispatch(&mut input_state, |_, _, _| {});
+        assert_eq!(input_state.consumed_by(), Some("dialog"));
+    }
+
+    #[rstest]
+    #[case("shortcuts", &["shortcuts"])]
+    #[case("button", &["shortcuts", "tooltip", "button"])]
+    #[case("nobody", &["shortcuts", "tooltip", "button", "canvas"])]
+    fn test_dispatch_stops_when_consumed(
+        #[case] consumer: &str,
+        #[case] expected: &[&str],
+    ) {
+        let mut input_state = InputState::default();
+        let mut reached = Vec::new();
+        router().dispatch(&mut input_state, |id, _, input_state| {
+            reached.push(id.to_owned());
+            if id == consumer {
+                input_state.consume(id);
+            }
+        });
+
+        assert_eq!(reached, expected);
+        let consumed_by = (consumer != "nobody").then_some(consumer);
+        assert_eq!(input_state.consumed_by(), consumed_by);
+    }
+
+    #[rstest]
+    fn test_first_consumer_keeps_the_event() {
+        let mut input_state = InputState::default();
+        assert!(input_state.consume("menu"));
+        assert!(!input_state.consume("button"));
+        assert_eq!(input_state.consumed_by(), Some("menu"));
+    }
+}
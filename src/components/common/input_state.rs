@@ -1,31 +1,260 @@
+use std::collections::HashSet;
+
 use getset::Getters;
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::{
+    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+use super::{Modifiers, ShortcutPlatform};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub enum MouseButtonState {
+    #[default]
     Up,
     Down,
 }
 
+// How far one line of a mouse wheel scrolls, in logical pixels.
+const LINE_HEIGHT: f32 = 40.0;
+
+// Positions and deltas are in logical pixels, like widget bounds.
 #[derive(Getters)]
 #[get = "pub"]
 pub struct InputState {
     mouse_position:     Option<[f32; 2]>,
     left_mouse_button:  MouseButtonState,
     right_mouse_button: MouseButtonState,
+    modifiers:          Modifiers,
+    // Who consumed the event synced last, if anyone did.
+    #[getset(skip)]
+    consumed_by:        Option<String>,
+    #[getset(skip)]
+    scale_factor:       f32,
+    // Of the event synced last, positive to scroll towards the start.
+    #[getset(skip)]
+    scroll_delta:       [f32; 2],
+    // Of the event synced last too: the key it pressed, repeats included,
+    // and the text it typed, without control chars.
+    #[getset(skip)]
+    pressed_key:        Option<KeyCode>,
+    #[getset(skip)]
+    typed_text:         String,
+    // Since `begin_frame`; scrolling in lines and pixels.
+    #[getset(skip)]
+    mouse_delta:        [f32; 2],
+    #[getset(skip)]
+    frame_scroll:       [f32; 2],
+    #[getset(skip)]
+    frame_scroll_lines: [f32; 2],
+    // Physical keys, so that a key is released as the one pressed whatever
+    // the layout or modifiers make of it.
+    #[getset(skip)]
+    keys_down:          HashSet<KeyCode>,
+    // Since `begin_frame`.
+    #[getset(skip)]
+    keys_pressed:       HashSet<KeyCode>,
+    #[getset(skip)]
+    keys_released:      HashSet<KeyCode>,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            mouse_position:     None,
+            left_mouse_button:  MouseButtonState::default(),
+            right_mouse_button: MouseButtonState::default(),
+            modifiers:          Modifiers::default(),
+            consumed_by:        None,
+            scale_factor:       1.0,
+            scroll_delta:       [0.0, 0.0],
+            pressed_key:        None,
+            typed_text:         String::new(),
+            mouse_delta:        [0.0, 0.0],
+            frame_scroll:       [0.0, 0.0],
+            frame_scroll_lines: [0.0, 0.0],
+            keys_down:          HashSet::new(),
+            keys_pressed:       HashSet::new(),
+            keys_released:      HashSet::new(),
+        }
+    }
 }
 
 impl InputState {
     pub fn sync(&mut self, event: &WindowEvent) {
+        self.consumed_by = None;
+        self.scroll_delta = [0.0, 0.0];
+        self.pressed_key = None;
+        self.typed_text.clear();
         match event {
             WindowEvent::MouseInput { state, button, .. } => {
                 self.on_mouse_input(state, button)
             }
+            WindowEvent::KeyboardInput { event, .. } => {
+                if let PhysicalKey::Code(code) = event.physical_key {
+                    self.on_key(code, event.state, event.repeat);
+                }
+                if let Some(text) = &event.text
+                    && event.state == ElementState::Pressed
+                {
+                    self.typed_text.extend(
+                        text.chars().filter(|char| !char.is_control()),
+                    );
+                }
+            }
+            // Releases made while unfocused never arrive.
+            WindowEvent::Focused(false) => self.release_keys(),
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state().into();
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = [
+                    position.x as f32 / self.scale_factor,
+                    position.y as f32 / self.scale_factor,
+                ];
+                // Entering the window isn't a move.
+                if let Some([x, y]) = self.mouse_position {
+                    self.mouse_delta[0] += position[0] - x;
+                    self.mouse_delta[1] += position[1] - y;
+                }
+                self.mouse_position = Some(position);
+            }
+            WindowEvent::CursorLeft { .. } => self.mouse_position = None,
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = *scale_factor as f32;
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.scroll_delta = scroll_delta(delta, self.scale_factor);
+                let [x, y] = self.scroll_delta;
+                let [lines_x, lines_y] =
+                    scroll_lines(delta, self.scale_factor);
+                self.frame_scroll[0] += x;
+                self.frame_scroll[1] += y;
+                self.frame_scroll_lines[0] += lines_x;
+                self.frame_scroll_lines[1] += lines_y;
+            }
             _ => {}
         }
     }
 }
 
+impl InputState {
+    // Rolls over what only lasts a frame; call it once per frame, before
+    // syncing the frame's events.
+    pub fn begin_frame(&mut self) {
+        self.mouse_delta = [0.0, 0.0];
+        self.frame_scroll = [0.0, 0.0];
+        self.frame_scroll_lines = [0.0, 0.0];
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+    }
+
+    // How far the mouse moved since `begin_frame`.
+    #[must_use]
+    #[inline(always)]
+    pub const fn mouse_delta(&self) -> [f32; 2] { self.mouse_delta }
+
+    // How far the wheel scrolled since `begin_frame`, in logical pixels
+    // and in lines, whichever the wheel reported.
+    #[must_use]
+    #[inline(always)]
+    pub const fn frame_scroll(&self) -> [f32; 2] { self.frame_scroll }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn frame_scroll_lines(&self) -> [f32; 2] {
+        self.frame_scroll_lines
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    // Whether `key` went down since `begin_frame`, leaving out repeats.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_key_just_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_key_just_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn pressed_key(&self) -> Option<KeyCode> { self.pressed_key }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn typed_text(&self) -> &str { &self.typed_text }
+
+    #[inline(always)]
+    pub fn keys_down(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys_down.iter().copied()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_shift_pressed(&self) -> bool { self.modifiers.is_shift() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_ctrl_pressed(&self) -> bool { self.modifiers.is_ctrl() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_alt_pressed(&self) -> bool { self.modifiers.is_alt() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_super_pressed(&self) -> bool { self.modifiers.is_super() }
+
+    // Cmd on macOS, Ctrl elsewhere.
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_primary_pressed(&self) -> bool {
+        self.modifiers.is_primary(ShortcutPlatform::current())
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scale_factor(&self) -> f32 { self.scale_factor }
+
+    // Only changes are synced, so the window's scale factor has to be set
+    // once it's created.
+    #[inline(always)]
+    pub const fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scroll_delta(&self) -> [f32; 2] { self.scroll_delta }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_consumed(&self) -> bool { self.consumed_by.is_some() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn consumed_by(&self) -> Option<&str> { self.consumed_by.as_deref() }
+
+    // Keeps the event from everyone after `id`. Returns false if someone
+    // consumed it already, in which case they keep it.
+    pub fn consume(&mut self, id: &str) -> bool {
+        if self.is_consumed() {
+            return false;
+        }
+        self.consumed_by = Some(id.to_owned());
+        true
+    }
+}
+
 impl InputState {
     fn on_mouse_input(&mut self, state: &ElementState, button: &MouseButton) {
         let button_state = match button {
@@ -38,4 +267,112 @@ impl InputState {
             ElementState::Released => MouseButtonState::Up,
         };
     }
+
+    fn on_key(&mut self, key: KeyCode, state: ElementState, is_repeat: bool) {
+        match state {
+            ElementState::Pressed => {
+                self.pressed_key = Some(key);
+                if self.keys_down.insert(key) && !is_repeat {
+                    self.keys_pressed.insert(key);
+                }
+            }
+            ElementState::Released => {
+                if self.keys_down.remove(&key) {
+                    self.keys_released.insert(key);
+                }
+            }
+        }
+    }
+
+    fn release_keys(&mut self) {
+        self.keys_released.extend(self.keys_down.drain());
+    }
+}
+
+fn scroll_delta(delta: &MouseScrollDelta, scale_factor: f32) -> [f32; 2] {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => {
+            [x * LINE_HEIGHT, y * LINE_HEIGHT]
+        }
+        MouseScrollDelta::PixelDelta(position) => [
+            position.x as f32 / scale_factor,
+            position.y as f32 / scale_factor,
+        ],
+    }
+}
+
+fn scroll_lines(delta: &MouseScrollDelta, scale_factor: f32) -> [f32; 2] {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => [*x, *y],
+        MouseScrollDelta::PixelDelta(_) => {
+            scroll_delta(delta, scale_factor).map(|value| value / LINE_HEIGHT)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winit::{
+        dpi::PhysicalPosition,
+        event::{ElementState, MouseScrollDelta},
+        keyboard::KeyCode,
+    };
+
+    use super::{InputState, scroll_delta, scroll_lines};
+
+    #[rstest]
+    #[case(MouseScrollDelta::LineDelta(0.0, -1.5), 1.0, [0.0, -60.0])]
+    #[case(MouseScrollDelta::LineDelta(1.0, 0.0), 2.0, [40.0, 0.0])]
+    #[case(
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(8.0, 30.0)),
+        2.0,
+        [4.0, 15.0]
+    )]
+    fn test_scroll_delta(
+        #[case] delta: MouseScrollDelta,
+        #[case] scale_factor: f32,
+        #[case] expected: [f32; 2],
+    ) {
+        assert_eq!(scroll_delta(&delta, scale_factor), expected);
+    }
+
+    #[rstest]
+    #[case(MouseScrollDelta::LineDelta(0.0, -1.5), 1.0, [0.0, -1.5])]
+    #[case(
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(160.0, 20.0)),
+        2.0,
+        [2.0, 0.25]
+    )]
+    fn test_scroll_lines(
+        #[case] delta: MouseScrollDelta,
+        #[case] scale_factor: f32,
+        #[case] expected: [f32; 2],
+    ) {
+        assert_eq!(scroll_lines(&delta, scale_factor), expected);
+    }
+
+    #[rstest]
+    fn test_keys() {
+        let mut input_state = InputState::default();
+        input_state.on_key(KeyCode::KeyA, ElementState::Pressed, false);
+        input_state.on_key(KeyCode::KeyB, ElementState::Pressed, false);
+        assert!(input_state.is_key_down(KeyCode::KeyA));
+        assert!(input_state.is_key_just_pressed(KeyCode::KeyA));
+
+        input_state.begin_frame();
+        input_state.on_key(KeyCode::KeyA, ElementState::Pressed, true);
+        assert_eq!(input_state.pressed_key(), Some(KeyCode::KeyA));
+        input_state.on_key(KeyCode::KeyB, ElementState::Released, false);
+        assert!(input_state.is_key_down(KeyCode::KeyA));
+        assert!(!input_state.is_key_just_pressed(KeyCode::KeyA));
+        assert!(!input_state.is_key_down(KeyCode::KeyB));
+        assert!(input_state.is_key_just_released(KeyCode::KeyB));
+
+        input_state.begin_frame();
+        input_state.release_keys();
+        assert_eq!(input_state.keys_down().count(), 0);
+        assert!(input_state.is_key_just_released(KeyCode::KeyA));
+        assert!(!input_state.is_key_just_released(KeyCode::KeyB));
+    }
 }
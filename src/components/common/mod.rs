@@ -1,5 +1,47 @@
+mod announcer;
+mod arc;
+mod auto_scroll;
+mod bar;
 mod bounds;
+mod color;
+mod focus;
+mod frame_playback;
+mod hit_shape;
+mod hover_intent;
+mod image_loader;
+mod input_router;
 mod input_state;
+mod model;
+mod paged_list;
+mod selection_model;
+mod shortcut;
+mod spatial_hash;
+mod spatial_index;
+mod tag_map;
+mod undo_stack;
 
+pub use announcer::{Announcement, AnnouncementSink, Announcer, Politeness};
+pub(crate) use arc::Arc;
+pub use auto_scroll::{AutoScroll, AutoScrollSettings};
+pub(crate) use bar::Bar;
 pub use bounds::Bounds;
+pub(crate) use bounds::intersect_rects;
+pub use color::{rgba8, to_rgba8};
+pub use focus::{FocusState, WidgetId};
+pub use frame_playback::FramePlayback;
+pub use hit_shape::{AlphaMask, HitShape};
+pub use hover_intent::{HoverIntent, HoverIntentEvent, HoverIntentSettings};
+pub use image_loader::{
+    DecodedFrame, DecodedImage, ImageDecoder, ImageFrame, ImageLoadState,
+    ImageLoader,
+};
+pub use input_router::{InputLayer, InputPhase, InputRouter};
 pub use input_state::{InputState, MouseButtonState};
+pub(crate) use model::{build_model, build_rotated_model};
+pub use paged_list::{PageSource, PagedList, PagedRow};
+pub use selection_model::{SelectionEvent, SelectionMode, SelectionModel};
+pub use shortcut::{Modifiers, Shortcut, ShortcutPlatform};
+pub use spatial_hash::SpatialHash;
+pub use spatial_index::{SceneFilter, SpatialIndex};
+pub use tag_map::TagMap;
+pub use undo_stack::{Change, Command, UndoStack};
@@ -0,0 +1,39 @@
+use glam::{Mat4, Quat, Vec3};
+
+pub(crate) fn build_model(
+    size: [f32; 2],
+    position: [f32; 2],
+    dpr: f32,
+) -> (Mat4, [f32; 2]) {
+    let size = [size[0] * dpr, size[1] * dpr];
+    let position = [position[0] * dpr, position[1] * dpr];
+
+    let half_size = [size[0] / 2.0, size[1] / 2.0];
+    let center =
+        Vec3::new(position[0] + half_size[0], position[1] + half_size[1], 0.0);
+    let scale = Vec3::new(half_size[0], half_size[1], 1.0);
+    let model =
+        Mat4::from_scale_rotation_translation(scale, Quat::IDENTITY, center);
+
+    (model, half_size)
+}
+
+// Like `build_model`, but the quad is placed by its center and rotated
+// around it, clockwise in screen space.
+pub(crate) fn build_rotated_model(
+    size: [f32; 2],
+    center: [f32; 2],
+    rotation: f32,
+    dpr: f32,
+) -> (Mat4, [f32; 2]) {
+    let half_size = [size[0] * dpr / 2.0, size[1] * dpr / 2.0];
+    let center = Vec3::new(center[0] * dpr, center[1] * dpr, 0.0);
+    let scale = Vec3::new(half_size[0], half_size[1], 1.0);
+    let model = Mat4::from_scale_rotation_translation(
+        scale,
+        Quat::from_rotation_z(rotation),
+        center,
+    );
+
+    (model, half_size)
+}
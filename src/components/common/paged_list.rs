@@ -0,0 +1,334 @@
+use std::{
+    collections::BTreeMap,
+    ops::Range,
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, Sender},
+    },
+};
+
+use rayon::ThreadPool;
+
+// Loads the items of one page, on a worker thread. A page shorter than
+// `page_size` is the last one.
+pub trait PageSource<T>: Send + Sync {
+    fn load_page(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<Vec<T>, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PagedRow<'a, T> {
+    Item(&'a T),
+    // A placeholder for an item of a page that is still loading.
+    Loading,
+    // A failed page collapses into a single row; `PagedList::retry` loads
+    // it again.
+    Failed { page: usize, message: &'a str },
+}
+
+#[derive(Debug)]
+enum PageState<T> {
+    Loading,
+    Ready(Vec<T>),
+    Failed(String),
+}
+
+type PageResult<T> = (u64, usize, Result<Vec<T>, String>);
+
+// The rows of a list or table whose items come from a `PageSource` a page
+// at a time. Pages are requested as the visible rows come near them, and
+// the pages farthest from view are dropped once more than `max_pages` are
+// loaded; they load again when scrolled back to.
+pub struct PagedList<T> {
+    source:            Arc<dyn PageSource<T>>,
+    thread_pool:       Arc<ThreadPool>,
+    sender:            Sender<PageResult<T>>,
+    receiver:          Receiver<PageResult<T>>,
+    page_size:         usize,
+    max_pages:         usize,
+    // How many rows before the end the next page is requested.
+    prefetch_distance: usize,
+    pages:             BTreeMap<usize, PageState<T>>,
+    // Pages known to exist: those requested so far, or all of them once
+    // the last one has loaded.
+    page_count:        usize,
+    last_page_len:     Option<usize>,
+    generation:        u64,
+}
+
+impl<T: Send + 'static> PagedList<T> {
+    #[must_use]
+    pub fn new(
+        source: Arc<dyn PageSource<T>>,
+        thread_pool: Arc<ThreadPool>,
+        page_size: usize,
+        max_pages: usize,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            source,
+            thread_pool,
+            sender,
+            receiver,
+            page_size: page_size.max(1),
+            max_pages: max_pages.max(1),
+            prefetch_distance: page_size / 2,
+            pages: BTreeMap::new(),
+            page_count: 0,
+            last_page_len: None,
+            generation: 0,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn page_size(&self) -> usize { self.page_size }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_complete(&self) -> bool { self.last_page_len.is_some() }
+
+    #[inline(always)]
+    pub const fn set_prefetch_distance(&mut self, prefetch_distance: usize) {
+        self.prefetch_distance = prefetch_distance;
+    }
+
+    #[must_use]
+    pub fn row_count(&self) -> usize {
+        (0..self.page_count).map(|page| self.page_rows(page)).sum()
+    }
+
+    #[must_use]
+    pub fn row(&self, index: usize) -> Option<PagedRow<'_, T>> {
+        let (page, offset) = self.locate(index)?;
+        Some(match self.pages.get(&page) {
+            Some(PageState::Ready(items)) => PagedRow::Item(&items[offset]),
+            Some(PageState::Failed(message)) => {
+                PagedRow::Failed { page, message }
+            }
+            Some(PageState::Loading) | None => PagedRow::Loading,
+        })
+    }
+
+    // Called every frame with the rows in view: loads the pages they fall
+    // in, the next page when they come near the end, and drops far-away
+    // pages.
+    pub fn set_visible_rows(&mut self, rows: Range<usize>) {
+        if self.page_count == 0 {
+            self.request(0);
+        }
+        let first = self.locate(rows.start).map_or(0, |(page, _)| page);
+        let last = rows
+            .end
+            .checked_sub(1)
+            .and_then(|index| self.locate(index))
+            .map_or(self.page_count.saturating_sub(1), |(page, _)| page);
+        for page in first..=last {
+            if !self.pages.contains_key(&page) {
+                self.request(page);
+            }
+        }
+
+        let is_near_end =
+            rows.end + self.prefetch_distance >= self.row_count();
+        if is_near_end && !self.is_complete() && self.is_page_loaded(last) {
+            self.request(self.page_count);
+        }
+        self.evict(first..last + 1);
+    }
+
+    // Loads a failed page again.
+    pub fn retry(&mut self, page: usize) {
+        if matches!(self.pages.get(&page), Some(PageState::Failed(_))) {
+            self.request(page);
+        }
+    }
+
+    // Forgets every page, e.g. after the underlying data changed. Pages
+    // still loading are dropped when they arrive.
+    pub fn reset(&mut self) {
+        self.generation += 1;
+        self.pages.clear();
+        self.page_count = 0;
+        self.last_page_len = None;
+    }
+
+    // Takes in pages that loaded since the last call. Returns whether the
+    // rows changed.
+    pub fn poll(&mut self) -> bool {
+        let mut is_changed = false;
+        while let Ok((generation, page, result)) = self.receiver.try_recv() {
+            if generation == self.generation {
+                self.receive(page, result);
+                is_changed = true;
+            }
+        }
+
+        is_changed
+    }
+
+    fn request(&mut self, page: usize) {
+        if self.last_page_len.is_some() && page >= self.page_count {
+            return;
+        }
+        self.pages.insert(page, PageState::Loading);
+        self.page_count = self.page_count.max(page + 1);
+
+        let generation = self.generation;
+        let page_size = self.page_size;
+        let source = self.source.clone();
+        let sender = self.sender.clone();
+        self.thread_pool.spawn(move || {
+            let result = source.load_page(page, page_size);
+            let _ = sender.send((generation, page, result));
+        });
+    }
+
+    fn receive(&mut self, page: usize, result: Result<Vec<T>, String>) {
+        let state = match result {
+            Ok(items) => {
+                if items.len() < self.page_size {
+                    self.last_page_len = Some(items.len());
+                    self.page_count = page + 1;
+                    self.pages.split_off(&self.page_count);
+                }
+                PageState::Ready(items)
+            }
+            Err(message) => PageState::Failed(message),
+        };
+        if page < self.page_count {
+            self.pages.insert(page, state);
+        }
+    }
+
+    fn is_page_loaded(&self, page: usize) -> bool {
+        matches!(self.pages.get(&page), Some(PageState::Ready(_)))
+    }
+
+    fn page_rows(&self, page: usize) -> usize {
+        match self.pages.get(&page) {
+            Some(PageState::Ready(items)) => items.len(),
+            Some(PageState::Failed(_)) => 1,
+            _ if page + 1 == self.page_count => {
+                self.last_page_len.unwrap_or(self.page_size)
+            }
+            _ => self.page_size,
+        }
+    }
+
+    fn locate(&self, mut index: usize) -> Option<(usize, usize)> {
+        for page in 0..self.page_count {
+            let rows = self.page_rows(page);
+            if index < rows {
+                return Some((page, index));
+            }
+            index -= rows;
+        }
+
+        None
+    }
+
+    fn evict(&mut self, visible_pages: Range<usize>) {
+        let loaded: Vec<usize> = self
+            .pages
+            .iter()
+            .filter(|(_, state)| matches!(state, PageState::Ready(_)))
+            .map(|(page, _)| *page)
+            .collect();
+        for page in pages_to_evict(&loaded, visible_pages, self.max_pages) {
+            self.pages.remove(&page);
+        }
+    }
+}
+
+// The loaded pages farthest from view, beyond the first `max_pages`.
+// Visible pages are never evicted.
+fn pages_to_evict(
+    loaded: &[usize],
+    visible_pages: Range<usize>,
+    max_pages: usize,
+) -> Vec<usize> {
+    let distance = |page: usize| {
+        page.saturating_sub(visible_pages.end.saturating_sub(1))
+            .max(visible_pages.start.saturating_sub(page))
+    };
+    let mut loaded = loaded.to_vec();
+    loaded.sort_by_key(|page| distance(*page));
+
+    loaded
+        .into_iter()
+        .skip(max_pages)
+        .filter(|page| !visible_pages.contains(page))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+    use rstest::rstest;
+
+    use super::{PageSource, PagedList, PagedRow, pages_to_evict};
+
+    struct Numbers;
+
+    impl PageSource<usize> for Numbers {
+        fn load_page(
+            &self,
+            page: usize,
+            page_size: usize,
+        ) -> Result<Vec<usize>, String> {
+            Ok((page * page_size..(page + 1) * page_size).collect())
+        }
+    }
+
+    fn paged_list() -> PagedList<usize> {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build();
+        PagedList::new(Arc::new(Numbers), Arc::new(thread_pool.unwrap()), 4, 3)
+    }
+
+    #[rstest]
+    #[case(&[0, 1, 2, 3, 4], 0..1, 3, &[3, 4])]
+    #[case(&[0, 1, 2, 3, 4], 2..3, 3, &[0, 4])]
+    #[case(&[0, 1, 2, 3, 4], 1..4, 1, &[0, 4])]
+    #[case(&[0, 1], 0..1, 3, &[])]
+    fn test_pages_to_evict(
+        #[case] loaded: &[usize],
+        #[case] visible_pages: std::ops::Range<usize>,
+        #[case] max_pages: usize,
+        #[case] expected: &[usize],
+    ) {
+        assert_eq!(pages_to_evict(loaded, visible_pages, max_pages), expected);
+    }
+
+    #[rstest]
+    fn test_paged_list_rows() {
+        let mut list = paged_list();
+        list.set_visible_rows(0..3);
+        assert_eq!(list.row_count(), 4);
+        assert_eq!(list.row(0), Some(PagedRow::Loading));
+
+        list.receive(0, Ok(vec![0, 1, 2, 3]));
+        list.set_visible_rows(0..3);
+        assert_eq!(list.row_count(), 8);
+        assert_eq!(list.row(2), Some(PagedRow::Item(&2)));
+
+        list.receive(1, Err("offline".to_owned()));
+        assert_eq!(list.row_count(), 5);
+        assert_eq!(
+            list.row(4),
+            Some(PagedRow::Failed { page: 1, message: "offline" })
+        );
+
+        list.retry(1);
+        list.receive(1, Ok(vec![4, 5]));
+        assert!(list.is_complete());
+        assert_eq!(list.row_count(), 6);
+        assert_eq!(list.row(5), Some(PagedRow::Item(&5)));
+        assert_eq!(list.row(6), None);
+    }
+}
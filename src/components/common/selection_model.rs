@@ -0,0 +1,282 @@
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMode {
+    Single,
+    Multiple,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionEvent {
+    Selected(usize),
+    Deselected(usize),
+    // The item with keyboard focus, which keyboard moves start from.
+    CurrentChanged(Option<usize>),
+}
+
+// Which items of a list, table or tree are selected, by index. Clicks
+// replace the selection, toggle an item (ctrl) or select the range from
+// the anchor (shift); keyboard moves do the same relative to the current
+// item. Every change is reported per item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionModel {
+    mode:     SelectionMode,
+    len:      usize,
+    selected: BTreeSet<usize>,
+    // Where shift ranges start: the last item clicked without shift.
+    anchor:   Option<usize>,
+    current:  Option<usize>,
+    events:   Vec<SelectionEvent>,
+}
+
+impl SelectionModel {
+    #[must_use]
+    pub const fn new(mode: SelectionMode, len: usize) -> Self {
+        Self {
+            mode,
+            len,
+            selected: BTreeSet::new(),
+            anchor: None,
+            current: None,
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn mode(&self) -> SelectionMode { self.mode }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize { self.len }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn anchor(&self) -> Option<usize> { self.anchor }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn current(&self) -> Option<usize> { self.current }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    // Selected indices, ascending.
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn selected_count(&self) -> usize { self.selected.len() }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<SelectionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // For when items are added or removed at the end; selected items past
+    // the new end are deselected.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        let selected = self.selected.range(..len).copied().collect();
+        self.apply(selected);
+        self.anchor = self.anchor.filter(|anchor| *anchor < len);
+        let current = self.current.filter(|current| *current < len);
+        self.set_current(current);
+    }
+
+    pub fn set_mode(&mut self, mode: SelectionMode) {
+        self.mode = mode;
+        if mode == SelectionMode::Single && self.selected.len() > 1 {
+            let selected = self
+                .current
+                .filter(|current| self.selected.contains(current));
+            self.apply(selected.into_iter().collect());
+        }
+    }
+
+    // `is_toggling` is ctrl (cmd on macOS), `is_extending` is shift. Both
+    // together add the range to the selection instead of replacing it.
+    pub fn click(
+        &mut self,
+        index: usize,
+        is_toggling: bool,
+        is_extending: bool,
+    ) {
+        if index >= self.len {
+            return;
+        }
+        let is_multiple = self.mode == SelectionMode::Multiple;
+        let selected = match (is_toggling, is_extending && is_multiple) {
+            (false, false) => BTreeSet::from([index]),
+            (true, false) => {
+                let mut selected = match is_multiple {
+                    true => self.selected.clone(),
+                    false => BTreeSet::new(),
+                };
+                match self.selected.contains(&index) {
+                    true => selected.remove(&index),
+                    false => selected.insert(index),
+                };
+                selected
+            }
+            (is_adding, true) => {
+                let range = self.range_from_anchor(index);
+                match is_adding {
+                    true => self.selected.union(&range).copied().collect(),
+                    false => range,
+                }
+            }
+        };
+        if !(is_extending && is_multiple) {
+            self.anchor = Some(index);
+        }
+        self.apply(selected);
+        self.set_current(Some(index));
+    }
+
+    // Moves the current item by `step`, clamped to the list, selecting it
+    // alone or, when extending, the range from the anchor to it.
+    pub fn move_current(&mut self, step: isize, is_extending: bool) {
+        if self.len == 0 {
+            return;
+        }
+        let index = match self.current {
+            Some(current) => {
+                current.saturating_add_signed(step).min(self.len - 1)
+            }
+            None if step < 0 => self.len - 1,
+            None => 0,
+        };
+        self.move_to(index, is_extending);
+    }
+
+    // Home/End and page moves.
+    pub fn move_to(&mut self, index: usize, is_extending: bool) {
+        self.click(index.min(self.len.saturating_sub(1)), false, is_extending);
+    }
+
+    pub fn select_all(&mut self) {
+        if self.mode == SelectionMode::Multiple {
+            self.apply((0..self.len).collect());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.apply(BTreeSet::new());
+        self.anchor = None;
+    }
+
+    fn range_from_anchor(&self, index: usize) -> BTreeSet<usize> {
+        let anchor = self.anchor.or(self.current).unwrap_or(index);
+        (anchor.min(index)..=anchor.max(index)).collect()
+    }
+
+    fn set_current(&mut self, current: Option<usize>) {
+        if current != self.current {
+            self.current = current;
+            self.events.push(SelectionEvent::CurrentChanged(current));
+        }
+    }
+
+    fn apply(&mut self, selected: BTreeSet<usize>) {
+        let deselected = self.selected.difference(&selected);
+        self.events
+            .extend(deselected.copied().map(SelectionEvent::Deselected));
+        let newly_selected = selected.difference(&self.selected);
+        self.events
+            .extend(newly_selected.copied().map(SelectionEvent::Selected));
+        self.selected = selected;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{SelectionEvent, SelectionMode, SelectionModel};
+
+    // (index, is_toggling, is_extending)
+    #[rstest]
+    #[case(SelectionMode::Multiple, &[(2, false, false)], &[2])]
+    #[case(SelectionMode::Multiple, &[(2, false, false), (5, false, false)], &[5])]
+    #[case(SelectionMode::Multiple, &[(2, false, false), (5, true, false)], &[2, 5])]
+    #[case(SelectionMode::Multiple, &[(2, false, false), (2, true, false)], &[])]
+    #[case(SelectionMode::Multiple, &[(2, false, false), (5, false, true)], &[2, 3, 4, 5])]
+    #[case(SelectionMode::Multiple, &[(5, false, false), (2, false, true)], &[2, 3, 4, 5])]
+    #[case(
+        SelectionMode::Multiple,
+        &[(2, false, false), (5, false, true), (3, false, true)],
+        &[2, 3]
+    )]
+    #[case(
+        SelectionMode::Multiple,
+        &[(0, false, false), (4, true, false), (6, true, true)],
+        &[0, 4, 5, 6]
+    )]
+    #[case(SelectionMode::Single, &[(2, false, false), (5, true, false)], &[5])]
+    #[case(SelectionMode::Single, &[(2, false, false), (5, false, true)], &[5])]
+    #[case(SelectionMode::Single, &[(2, false, false), (2, true, false)], &[])]
+    #[case(SelectionMode::Multiple, &[(9, false, false)], &[])]
+    fn test_selection_model_click(
+        #[case] mode: SelectionMode,
+        #[case] clicks: &[(usize, bool, bool)],
+        #[case] expected: &[usize],
+    ) {
+        let mut model = SelectionModel::new(mode, 8);
+        for &(index, is_toggling, is_extending) in clicks {
+            model.click(index, is_toggling, is_extending);
+        }
+        assert_eq!(model.selected().collect::<Vec<_>>(), expected);
+    }
+
+    #[rstest]
+    fn test_selection_model_keyboard_extension() {
+        let mut model = SelectionModel::new(SelectionMode::Multiple, 8);
+        model.move_current(1, false);
+        assert_eq!(model.current(), Some(0));
+
+        model.click(3, false, false);
+        model.move_current(2, true);
+        model.move_current(-4, true);
+        assert_eq!(model.selected().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(model.anchor(), Some(3));
+
+        model.move_current(10, false);
+        assert_eq!(model.selected().collect::<Vec<_>>(), [7]);
+        assert_eq!(model.anchor(), Some(7));
+    }
+
+    #[rstest]
+    fn test_selection_model_events() {
+        let mut model = SelectionModel::new(SelectionMode::Multiple, 8);
+        model.click(1, false, false);
+        model.click(3, false, true);
+        let _ = model.take_events();
+
+        model.click(2, false, false);
+        assert_eq!(
+            model.take_events(),
+            [
+                SelectionEvent::Deselected(1),
+                SelectionEvent::Deselected(3),
+                SelectionEvent::CurrentChanged(Some(2)),
+            ]
+        );
+
+        model.select_all();
+        model.set_len(2);
+        let events = model.take_events();
+        assert_eq!(events.len(), 7 + 6 + 1);
+        assert_eq!(events.last(), Some(&SelectionEvent::CurrentChanged(None)));
+        assert_eq!(model.selected().collect::<Vec<_>>(), [0, 1]);
+    }
+}
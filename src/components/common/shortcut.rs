@@ -0,0 +1,191 @@
+use bon::Builder;
+use getset::CopyGetters;
+use winit::keyboard::ModifiersState;
+
+// Which modifier keys are held down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Builder, CopyGetters)]
+#[builder(const)]
+#[get_copy = "pub"]
+pub struct Modifiers {
+    #[builder(default = false)]
+    is_shift: bool,
+    #[builder(default = false)]
+    is_ctrl:  bool,
+    #[builder(default = false)]
+    is_alt:   bool,
+    // Cmd on macOS, the Windows key elsewhere.
+    #[builder(default = false)]
+    is_super: bool,
+}
+
+impl From<ModifiersState> for Modifiers {
+    fn from(state: ModifiersState) -> Self {
+        Self {
+            is_shift: state.shift_key(),
+            is_ctrl:  state.control_key(),
+            is_alt:   state.alt_key(),
+            is_super: state.super_key(),
+        }
+    }
+}
+
+impl Modifiers {
+    // The one most shortcuts go through: Cmd on macOS, Ctrl elsewhere.
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_primary(&self, platform: ShortcutPlatform) -> bool {
+        match platform {
+            ShortcutPlatform::MacOs => self.is_super,
+            ShortcutPlatform::Windows | ShortcutPlatform::Linux => {
+                self.is_ctrl
+            }
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        !(self.is_shift || self.is_ctrl || self.is_alt || self.is_super)
+    }
+}
+
+// Decides the names and order of modifiers in shortcut hints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutPlatform {
+    MacOs,
+    Windows,
+    Linux,
+}
+
+impl ShortcutPlatform {
+    #[must_use]
+    pub const fn current() -> Self {
+        if cfg!(target_os = "macos") || cfg!(target_os = "ios") {
+            Self::MacOs
+        } else if cfg!(target_os = "windows") {
+            Self::Windows
+        } else {
+            Self::Linux
+        }
+    }
+}
+
+// A key combination as written in menus and tooltips. Shortcuts that
+// follow the platform, like save, use `is_primary` rather than naming Ctrl
+// or Cmd, so one definition reads "Cmd+S" on macOS and "Ctrl+S" elsewhere:
+//
+// Shortcut::builder().key("S").is_primary(true).build()
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+pub struct Shortcut {
+    #[builder(into)]
+    key:        String,
+    #[builder(default = false)]
+    is_primary: bool,
+    #[builder(default = false)]
+    is_shift:   bool,
+    #[builder(default = false)]
+    is_ctrl:    bool,
+    #[builder(default = false)]
+    is_alt:     bool,
+}
+
+impl Shortcut {
+    #[must_use]
+    #[inline(always)]
+    pub fn key(&self) -> &str { &self.key }
+
+    // The modifiers that have to be held on `platform`.
+    #[must_use]
+    pub const fn modifiers(&self, platform: ShortcutPlatform) -> Modifiers {
+        let is_mac = matches!(platform, ShortcutPlatform::MacOs);
+        Modifiers {
+            is_shift: self.is_shift,
+            is_ctrl:  self.is_ctrl || (self.is_primary && !is_mac),
+            is_alt:   self.is_alt,
+            is_super: self.is_primary && is_mac,
+        }
+    }
+
+    // Whether exactly these modifiers are held, ignoring the key.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_held(
+        &self,
+        modifiers: &Modifiers,
+        platform: ShortcutPlatform,
+    ) -> bool {
+        self.modifiers(platform) == *modifiers
+    }
+
+    #[must_use]
+    pub fn display(&self, platform: ShortcutPlatform) -> String {
+        let modifiers = self.modifiers(platform);
+        // In the order each platform's own menus list them.
+        let names = match platform {
+            ShortcutPlatform::MacOs => [
+                (modifiers.is_ctrl, "Ctrl"),
+                (modifiers.is_alt, "Opt"),
+                (modifiers.is_shift, "Shift"),
+                (modifiers.is_super, "Cmd"),
+            ],
+            ShortcutPlatform::Windows | ShortcutPlatform::Linux => [
+                (modifiers.is_ctrl, "Ctrl"),
+                (modifiers.is_alt, "Alt"),
+                (modifiers.is_shift, "Shift"),
+                (modifiers.is_super, "Super"),
+            ],
+        };
+        names
+            .into_iter()
+            .filter_map(|(is_held, name)| is_held.then_some(name))
+            .chain([self.key.as_str()])
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Modifiers, Shortcut, ShortcutPlatform};
+
+    #[rstest]
+    #[case(ShortcutPlatform::MacOs, "Cmd+S", "Opt+Shift+Cmd+Z")]
+    #[case(ShortcutPlatform::Windows, "Ctrl+S", "Ctrl+Alt+Shift+Z")]
+    #[case(ShortcutPlatform::Linux, "Ctrl+S", "Ctrl+Alt+Shift+Z")]
+    fn test_display(
+        #[case] platform: ShortcutPlatform,
+        #[case] save: &str,
+        #[case] redo: &str,
+    ) {
+        let shortcut = Shortcut::builder().key("S").is_primary(true).build();
+        assert_eq!(shortcut.display(platform), save);
+
+        let shortcut = Shortcut::builder()
+            .key("Z")
+            .is_primary(true)
+            .is_shift(true)
+            .is_alt(true)
+            .build();
+        assert_eq!(shortcut.display(platform), redo);
+    }
+
+    #[rstest]
+    #[case(ShortcutPlatform::MacOs, Modifiers::builder().is_super(true).build(), true)]
+    #[case(ShortcutPlatform::MacOs, Modifiers::builder().is_ctrl(true).build(), false)]
+    #[case(ShortcutPlatform::Linux, Modifiers::builder().is_ctrl(true).build(), true)]
+    #[case(
+        ShortcutPlatform::Linux,
+        Modifiers::builder().is_ctrl(true).is_shift(true).build(),
+        false,
+    )]
+    fn test_is_held(
+        #[case] platform: ShortcutPlatform,
+        #[case] modifiers: Modifiers,
+        #[case] expected: bool,
+    ) {
+        let shortcut = Shortcut::builder().key("S").is_primary(true).build();
+        assert_eq!(shortcut.is_held(&modifiers, platform), expected);
+    }
+}
@@ -0,0 +1,409 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+// Rects spanning more cells than this on either axis, e.g. backgrounds,
+// are kept aside and checked by every query instead.
+const MAX_CELL_SPAN: i32 = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+struct SpatialHashEntry {
+    rect:  [f32; 4],
+    // Draw order; later entries are on top.
+    order: u64,
+    // `None` for oversized rects.
+    cells: Option<[[i32; 2]; 2]>,
+}
+
+// A uniform grid over rects (x, y, width, height) so that hit testing
+// and culling look at the few rects near a point or a viewport instead
+// of all of them, e.g. with tens of thousands of rectangle instances.
+// Moving a rect only touches the cells it leaves and enters.
+//
+// Queries take a `refine` callback that gets each candidate with its
+// rect and returns the part of it that counts, or `None` to skip it,
+// e.g. the part inside a clip rect. The part must lie within the rect.
+#[derive(Debug, Clone)]
+pub struct SpatialHash<K> {
+    cell_size:  f32,
+    entries:    HashMap<K, SpatialHashEntry>,
+    cells:      HashMap<[i32; 2], Vec<K>>,
+    oversized:  HashSet<K>,
+    next_order: u64,
+}
+
+impl<K: Clone + Eq + Hash> SpatialHash<K> {
+    // `cell_size` in the rects' units; about the size of a typical rect
+    // works best.
+    #[must_use]
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size:  cell_size.max(1.0),
+            entries:    HashMap::new(),
+            cells:      HashMap::new(),
+            oversized:  HashSet::new(),
+            next_order: 0,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool { self.entries.contains_key(key) }
+
+    #[must_use]
+    pub fn rect(&self, key: &K) -> Option<[f32; 4]> {
+        self.entries.get(key).map(|entry| entry.rect)
+    }
+
+    // New keys go on top; moving one keeps its place.
+    pub fn insert(&mut self, key: K, rect: [f32; 4]) {
+        let order = match self.entries.get(&key) {
+            Some(entry) if entry.rect == rect => return,
+            Some(entry) => entry.order,
+            None => {
+                self.next_order += 1;
+                self.next_order
+            }
+        };
+        self.unlink(&key);
+        let cells = self.cell_range(rect);
+        self.link(&key, cells);
+        self.entries
+            .insert(key, SpatialHashEntry { rect, order, cells });
+    }
+
+    // Moves the key above all others, e.g. after its rectangle was
+    // re-added to the renderer to draw on top.
+    pub fn raise(&mut self, key: &K) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.next_order += 1;
+            entry.order = self.next_order;
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<[f32; 4]> {
+        self.unlink(key);
+        self.entries.remove(key).map(|entry| entry.rect)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cells.clear();
+        self.oversized.clear();
+    }
+
+    // The keys whose refined rect overlaps `rect`, bottom to top.
+    #[must_use]
+    pub fn in_rect(
+        &self,
+        rect: [f32; 4],
+        refine: impl Fn(&K, [f32; 4]) -> Option<[f32; 4]>,
+    ) -> Vec<&K> {
+        let mut found: Vec<(&K, u64)> = self
+            .candidates(rect)
+            .filter_map(|(key, entry)| {
+                let refined = refine(key, entry.rect)?;
+                is_overlapping(refined, rect).then_some((key, entry.order))
+            })
+            .collect();
+        found.sort_by_key(|(_, order)| *order);
+        found.into_iter().map(|(key, _)| key).collect()
+    }
+
+    // The topmost key whose refined rect contains `point`.
+    #[must_use]
+    pub fn at_point(
+        &self,
+        point: [f32; 2],
+        refine: impl Fn(&K, [f32; 4]) -> Option<[f32; 4]>,
+    ) -> Option<&K> {
+        self.candidates([point[0], point[1], 0.0, 0.0])
+            .filter(|(key, entry)| {
+                refine(key, entry.rect)
+                    .is_some_and(|rect| rect_distance(rect, point) == 0.0)
+            })
+            .max_by_key(|(_, entry)| entry.order)
+            .map(|(key, _)| key)
+    }
+
+    // The key whose refined rect is closest to `point`, measured to its
+    // nearest edge, so rects under the point are at 0. Ties go to the one
+    // on top.
+    #[must_use]
+    pub fn nearest<'a>(
+        &'a self,
+        point: [f32; 2],
+        refine: impl Fn(&K, [f32; 4]) -> Option<[f32; 4]>,
+    ) -> Option<&'a K> {
+        let mut best: Option<(f32, u64, &K)> = None;
+        let consider = |best: &mut Option<(f32, u64, &'a K)>, key: &'a K| {
+            let entry = &self.entries[key];
+            let Some(rect) = refine(key, entry.rect) else {
+                return;
+            };
+            let distance = rect_distance(rect, point);
+            let is_better = best.is_none_or(|(best_distance, order, _)| {
+                distance < best_distance
+                    || (distance == best_distance && entry.order > order)
+            });
+            if is_better {
+                *best = Some((distance, entry.order, key));
+            }
+        };
+
+        for key in &self.oversized {
+            consider(&mut best, key);
+        }
+
+        // Rings of cells around the point's, until no cell further out
+        // can be closer than the best found.
+        let Some([min, max]) = self.occupied_cells() else {
+            return best.map(|(_, _, key)| key);
+        };
+        let center = self.cell_of(point);
+        let max_ring = (0..2)
+            .map(|axis| {
+                (center[axis] - min[axis]).max(max[axis] - center[axis])
+            })
+            .max()
+            .unwrap_or(0)
+            .max(0);
+        let mut seen = HashSet::new();
+        for ring in 0..=max_ring {
+            for cell in ring_cells(center, ring) {
+                for key in self.cells.get(&cell).into_iter().flatten() {
+                    if seen.insert(key) {
+                        consider(&mut best, key);
+                    }
+                }
+            }
+            let reach = ring as f32 * self.cell_size;
+            if best.is_some_and(|(distance, ..)| distance <= reach) {
+                break;
+            }
+        }
+
+        best.map(|(_, _, key)| key)
+    }
+
+    fn candidates(
+        &self,
+        rect: [f32; 4],
+    ) -> impl Iterator<Item = (&K, &SpatialHashEntry)> {
+        let [min, max] = self.rect_cells(rect);
+        let mut keys: HashSet<&K> = self.oversized.iter().collect();
+        if (max[0] - min[0]) > MAX_CELL_SPAN
+            || (max[1] - min[1]) > MAX_CELL_SPAN
+        {
+            keys.extend(self.entries.keys());
+        } else {
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    keys.extend(self.cells.get(&[x, y]).into_iter().flatten());
+                }
+            }
+        }
+        keys.into_iter().map(|key| (key, &self.entries[key]))
+    }
+
+    fn cell_of(&self, point: [f32; 2]) -> [i32; 2] {
+        point.map(|value| (value / self.cell_size).floor() as i32)
+    }
+
+    fn rect_cells(&self, rect: [f32; 4]) -> [[i32; 2]; 2] {
+        [
+            self.cell_of([rect[0], rect[1]]),
+            self.cell_of([rect[0] + rect[2], rect[1] + rect[3]]),
+        ]
+    }
+
+    fn cell_range(&self, rect: [f32; 4]) -> Option<[[i32; 2]; 2]> {
+        let [min, max] = self.rect_cells(rect);
+        let is_oversized = (max[0] - min[0]) > MAX_CELL_SPAN
+            || (max[1] - min[1]) > MAX_CELL_SPAN;
+        (!is_oversized).then_some([min, max])
+    }
+
+    fn occupied_cells(&self) -> Option<[[i32; 2]; 2]> {
+        self.cells.keys().fold(None, |range, cell| {
+            let [min, max] = range.unwrap_or([*cell, *cell]);
+            Some([
+                [min[0].min(cell[0]), min[1].min(cell[1])],
+                [max[0].max(cell[0]), max[1].max(cell[1])],
+            ])
+        })
+    }
+
+    fn link(&mut self, key: &K, cells: Option<[[i32; 2]; 2]>) {
+        let Some([min, max]) = cells else {
+            self.oversized.insert(key.clone());
+            return;
+        };
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                self.cells.entry([x, y]).or_default().push(key.clone());
+            }
+        }
+    }
+
+    fn unlink(&mut self, key: &K) {
+        let Some(entry) = self.entries.get(key) else {
+            return;
+        };
+        let Some([min, max]) = entry.cells else {
+            self.oversized.remove(key);
+            return;
+        };
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                if let Some(keys) = self.cells.get_mut(&[x, y]) {
+                    keys.retain(|cell_key| cell_key != key);
+                    if keys.is_empty() {
+                        self.cells.remove(&[x, y]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Edges touching count as overlapping, so a point query finds the rects
+// it lies on the border of.
+fn is_overlapping(a: [f32; 4], b: [f32; 4]) -> bool {
+    a[0] <= b[0] + b[2]
+        && b[0] <= a[0] + a[2]
+        && a[1] <= b[1] + b[3]
+        && b[1] <= a[1] + a[3]
+}
+
+fn rect_distance(rect: [f32; 4], point: [f32; 2]) -> f32 {
+    let dx = (rect[0] - point[0])
+        .max(point[0] - (rect[0] + rect[2]))
+        .max(0.0);
+    let dy = (rect[1] - point[1])
+        .max(point[1] - (rect[1] + rect[3]))
+        .max(0.0);
+    dx.hypot(dy)
+}
+
+fn ring_cells(center: [i32; 2], ring: i32) -> Vec<[i32; 2]> {
+    if ring == 0 {
+        return vec![center];
+    }
+    let [x, y] = center;
+    let mut cells = Vec::with_capacity(8 * ring as usize);
+    for offset in -ring..=ring {
+        cells.push([x + offset, y - ring]);
+        cells.push([x + offset, y + ring]);
+    }
+    for offset in (1 - ring)..ring {
+        cells.push([x - ring, y + offset]);
+        cells.push([x + ring, y + offset]);
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{SpatialHash, is_overlapping, rect_distance};
+
+    // Deterministic rects scattered over a 2000 by 2000 area.
+    fn scattered(count: usize) -> Vec<[f32; 4]> {
+        let mut seed: u32 = 7;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+        (0..count)
+            .map(|_| {
+                [
+                    next() * 2000.0,
+                    next() * 2000.0,
+                    next() * 80.0,
+                    next() * 40.0,
+                ]
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_queries_match_a_linear_scan() {
+        let rects = scattered(2_000);
+        let mut hash = SpatialHash::new(64.0);
+        for (index, rect) in rects.iter().enumerate() {
+            hash.insert(index, *rect);
+        }
+        // Moving some keeps the grid in sync.
+        for index in (0..rects.len()).step_by(7) {
+            hash.insert(index, rects[rects.len() - 1 - index]);
+        }
+        let rect_of = |index: usize| match index % 7 {
+            0 => rects[rects.len() - 1 - index],
+            _ => rects[index],
+        };
+        let keep = |_: &usize, rect| Some(rect);
+
+        for point in [[10.0, 10.0], [1000.0, 1500.0], [1999.0, 3.0]] {
+            let expected = (0..rects.len())
+                .rev()
+                .find(|index| rect_distance(rect_of(*index), point) == 0.0);
+            assert_eq!(hash.at_point(point, keep).copied(), expected);
+
+            let nearest = *hash.nearest(point, keep).unwrap();
+            let closest = (0..rects.len())
+                .map(|index| rect_distance(rect_of(index), point))
+                .fold(f32::MAX, f32::min);
+            assert_eq!(rect_distance(rect_of(nearest), point), closest);
+        }
+
+        let viewport = [300.0, 300.0, 400.0, 250.0];
+        let visible: Vec<usize> =
+            hash.in_rect(viewport, keep).into_iter().copied().collect();
+        let mut expected: Vec<usize> = (0..rects.len())
+            .filter(|index| is_overlapping(rect_of(*index), viewport))
+            .collect();
+        // Moved keys keep their place in the draw order.
+        expected.sort();
+        assert_eq!(visible, expected);
+    }
+
+    #[rstest]
+    fn test_refine_and_raise() {
+        let mut hash = SpatialHash::new(50.0);
+        hash.insert("back", [0.0, 0.0, 100.0, 100.0]);
+        hash.insert("front", [0.0, 0.0, 100.0, 100.0]);
+
+        assert_eq!(
+            hash.at_point([10.0, 10.0], |_, rect| Some(rect)),
+            Some(&"front")
+        );
+        hash.raise(&"back");
+        assert_eq!(
+            hash.at_point([10.0, 10.0], |_, rect| Some(rect)),
+            Some(&"back")
+        );
+        assert_eq!(
+            hash.at_point([10.0, 10.0], |key, rect| (*key != "back")
+                .then_some(rect)),
+            Some(&"front")
+        );
+
+        hash.remove(&"front");
+        assert_eq!(
+            hash.in_rect([0.0, 0.0, 5.0, 5.0], |_, rect| Some(rect)),
+            [&"back"]
+        );
+        assert_eq!(hash.len(), 1);
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use bon::Builder;
+
+use super::{Bounds, SpatialHash, intersect_rects};
+
+// Which widgets a query considers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Builder)]
+#[builder(const)]
+pub struct SceneFilter {
+    #[builder(default = false)]
+    is_interactive_only: bool,
+    // Skips hidden widgets and only counts the part of the others inside
+    // their clip rect.
+    #[builder(default = false)]
+    is_visible_only:     bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SpatialEntry {
+    bounds:         Bounds,
+    is_interactive: bool,
+    is_visible:     bool,
+}
+
+impl SpatialEntry {
+    fn rect(&self, rect: [f32; 4], filter: &SceneFilter) -> Option<[f32; 4]> {
+        if (filter.is_interactive_only && !self.is_interactive)
+            || (filter.is_visible_only && !self.is_visible)
+        {
+            return None;
+        }
+        match filter.is_visible_only {
+            true => Some(intersect_rects(rect, self.bounds.clip_rect)),
+            false => Some(rect),
+        }
+    }
+}
+
+// Answers where widgets are without walking all of them, for hit testing,
+// rubber band selection and keyboard navigation by direction. Widgets
+// are kept by id in a `SpatialHash` over their bounds.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    entries: HashMap<String, SpatialEntry>,
+    hash:    SpatialHash<String>,
+}
+
+impl SpatialIndex {
+    // `cell_size` in logical pixels; about the size of a typical widget
+    // works best.
+    #[must_use]
+    pub fn new(cell_size: f32) -> Self {
+        Self { entries: HashMap::new(), hash: SpatialHash::new(cell_size) }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn contains(&self, id: &str) -> bool { self.entries.contains_key(id) }
+
+    #[must_use]
+    pub fn bounds(&self, id: &str) -> Option<&Bounds> {
+        self.entries.get(id).map(|entry| &entry.bounds)
+    }
+
+    // New widgets go on top; updating one keeps its place.
+    pub fn insert(
+        &mut self,
+        id: &str,
+        bounds: Bounds,
+        is_interactive: bool,
+        is_visible: bool,
+    ) {
+        let [x, y] = bounds.position;
+        let [width, height] = bounds.size;
+        self.hash.insert(id.to_owned(), [x, y, width, height]);
+        self.entries.insert(
+            id.to_owned(),
+            SpatialEntry { bounds, is_interactive, is_visible },
+        );
+    }
+
+    pub fn update_bounds(&mut self, id: &str, bounds: Bounds) {
+        let Some(entry) = self.entries.get(id) else {
+            return;
+        };
+        let (is_interactive, is_visible) =
+            (entry.is_interactive, entry.is_visible);
+        self.insert(id, bounds, is_interactive, is_visible);
+    }
+
+    pub fn set_visible(&mut self, id: &str, is_visible: bool) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.is_visible = is_visible;
+        }
+    }
+
+    pub fn set_interactive(&mut self, id: &str, is_interactive: bool) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.is_interactive = is_interactive;
+        }
+    }
+
+    // Moves the widget above all others, e.g. after it was re-added to
+    // the renderer to draw on top.
+    #[inline(always)]
+    pub fn raise(&mut self, id: &str) { self.hash.raise(&id.to_owned()); }
+
+    pub fn remove(&mut self, id: &str) {
+        self.hash.remove(&id.to_owned());
+        self.entries.remove(id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hash.clear();
+    }
+
+    // The widgets overlapping `rect` (x, y, width, height), bottom to top.
+    #[must_use]
+    pub fn widgets_in_rect(
+        &self,
+        rect: [f32; 4],
+        filter: &SceneFilter,
+    ) -> Vec<&str> {
+        self.hash
+            .in_rect(rect, |id, rect| self.entries[id].rect(rect, filter))
+            .into_iter()
+            .map(String::as_str)
+            .collect()
+    }
+
+    // The topmost widget at `point`.
+    #[must_use]
+    pub fn widget_at_point(
+        &self,
+        point: [f32; 2],
+        filter: &SceneFilter,
+    ) -> Option<&str> {
+        self.hash
+            .at_point(point, |id, rect| self.entries[id].rect(rect, filter))
+            .map(String::as_str)
+    }
+
+    // The widget closest to `point` that `predicate` accepts, measured to
+    // the nearest edge, so widgets under the point are at 0. Ties go to
+    // the one on top.
+    #[must_use]
+    pub fn nearest_widget(
+        &self,
+        point: [f32; 2],
+        filter: &SceneFilter,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Option<&str> {
+        self.hash
+            .nearest(point, |id, rect| {
+                self.entries[id]
+                    .rect(rect, filter)
+                    .filter(|_| predicate(id))
+            })
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{SceneFilter, SpatialIndex};
+    use crate::components::common::Bounds;
+
+    fn index() -> SpatialIndex {
+        let mut index = SpatialIndex::new(50.0);
+        let background =
+            Bounds::without_clip_rect([0.0, 0.0], [10_000.0, 10_000.0]);
+        index.insert("background", background, false, true);
+        let card = Bounds::without_clip_rect([100.0, 100.0], [200.0, 100.0]);
+        index.insert("card", card, false, true);
+        let button = Bounds {
+            position:  [120.0, 120.0],
+            size:      [60.0, 30.0],
+            // Half of it scrolled out of view.
+            clip_rect: [100.0, 135.0, 200.0, 65.0],
+        };
+        index.insert("button", button, true, true);
+        let far = Bounds::without_clip_rect([900.0, 900.0], [20.0, 20.0]);
+        index.insert("far", far, true, true);
+        index
+    }
+
+    #[rstest]
+    #[case([130.0, 140.0], SceneFilter::builder().build(), Some("button"))]
+    #[case([130.0, 125.0], SceneFilter::builder().build(), Some("button"))]
+    #[case(
+        [130.0, 125.0],
+        SceneFilter::builder().is_visible_only(true).build(),
+        Some("card"),
+    )]
+    #[case(
+        [250.0, 150.0],
+        SceneFilter::builder().is_interactive_only(true).build(),
+        None,
+    )]
+    #[case([5.0, 5.0], SceneFilter::builder().build(), Some("background"))]
+    fn test_widget_at_point(
+        #[case] point: [f32; 2],
+        #[case] filter: SceneFilter,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(index().widget_at_point(point, &filter), expected);
+    }
+
+    #[rstest]
+    fn test_widgets_in_rect() {
+        let mut index = index();
+        let filter = SceneFilter::default();
+        assert_eq!(
+            index.widgets_in_rect([150.0, 130.0, 10.0, 10.0], &filter),
+            ["background", "card", "button"]
+        );
+
+        // Moving keeps the draw order.
+        let moved = Bounds::without_clip_rect([850.0, 850.0], [10.0, 10.0]);
+        index.update_bounds("card", moved);
+        assert_eq!(
+            index.widgets_in_rect([800.0, 800.0, 200.0, 200.0], &filter),
+            ["background", "card", "far"]
+        );
+    }
+
+    #[rstest]
+    fn test_nearest_widget() {
+        let index = index();
+        let filter = SceneFilter::builder().is_interactive_only(true).build();
+
+        assert_eq!(
+            index.nearest_widget([700.0, 700.0], &filter, |_| true),
+            Some("far")
+        );
+        assert_eq!(
+            index.nearest_widget([700.0, 700.0], &filter, |id| id != "far"),
+            Some("button")
+        );
+        assert_eq!(index.nearest_widget([0.0, 0.0], &filter, |_| false), None);
+    }
+}
@@ -0,0 +1,81 @@
+use slotmap::{DefaultKey, Key, KeyData, SlotMap};
+
+// Domain objects behind the `u64` tags put on widgets, so that a hit test
+// or an event leads straight back to what was clicked:
+//
+// let tag = tags.insert(node_id);
+// block.set_tag(Some(tag));
+// ...
+// let node_id = block.tag_at(position).and_then(|tag| tags.get(tag));
+//
+// Tags are generational, so a stale one left on a widget after its
+// object was removed finds nothing instead of a newer object.
+#[derive(Debug, Clone, Default)]
+pub struct TagMap<T> {
+    values: SlotMap<DefaultKey, T>,
+}
+
+impl<T> TagMap<T> {
+    #[must_use]
+    #[inline(always)]
+    pub fn new() -> Self { Self { values: SlotMap::new() } }
+
+    #[must_use]
+    pub fn insert(&mut self, value: T) -> u64 {
+        self.values.insert(value).data().as_ffi()
+    }
+
+    #[must_use]
+    pub fn get(&self, tag: u64) -> Option<&T> { self.values.get(key(tag)) }
+
+    #[must_use]
+    pub fn get_mut(&mut self, tag: u64) -> Option<&mut T> {
+        self.values.get_mut(key(tag))
+    }
+
+    pub fn remove(&mut self, tag: u64) -> Option<T> {
+        self.values.remove(key(tag))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn contains(&self, tag: u64) -> bool {
+        self.values.contains_key(key(tag))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.values.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    #[inline(always)]
+    pub fn clear(&mut self) { self.values.clear(); }
+}
+
+#[inline(always)]
+fn key(tag: u64) -> DefaultKey { KeyData::from_ffi(tag).into() }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::TagMap;
+
+    #[rstest]
+    fn test_stale_tags_find_nothing() {
+        let mut tags = TagMap::new();
+        let first = tags.insert("first");
+        assert_eq!(tags.get(first), Some(&"first"));
+
+        assert_eq!(tags.remove(first), Some("first"));
+        let second = tags.insert("second");
+
+        assert_ne!(first, second);
+        assert_eq!(tags.get(first), None);
+        assert_eq!(tags.get(second), Some(&"second"));
+        assert_eq!(tags.get(u64::MAX), None);
+    }
+}
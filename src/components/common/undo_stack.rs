@@ -0,0 +1,395 @@
+use std::{any::Any, collections::VecDeque};
+
+// An edit of the application's state `T` that can be taken back. Edits
+// made by the user directly, such as dragging or typing, have already
+// happened by the time they are recorded; `execute` is then only called
+// to redo them.
+pub trait Command<T>: Any {
+    fn execute(&mut self, target: &mut T);
+
+    fn undo(&mut self, target: &mut T);
+
+    // Folds `next`, which happened right after this command, into it so
+    // that both are undone at once, e.g. the steps of a drag or the
+    // characters typed into a field. Returns whether it did.
+    fn merge(&mut self, next: &dyn Command<T>) -> bool {
+        let _ = next;
+        false
+    }
+}
+
+// Sets a value of the state through `apply`, from `before` to `after`.
+// Changes with the same key merge, so recording every step of a drag,
+// resize, reorder or text edit leaves one entry on the stack; use a key
+// per widget and kind of edit.
+pub struct Change<T, V> {
+    key:    u64,
+    before: V,
+    after:  V,
+    apply:  fn(&mut T, &V),
+}
+
+impl<T, V> Change<T, V> {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(
+        key: u64,
+        before: V,
+        after: V,
+        apply: fn(&mut T, &V),
+    ) -> Self {
+        Self { key, before, after, apply }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn key(&self) -> u64 { self.key }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn before(&self) -> &V { &self.before }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn after(&self) -> &V { &self.after }
+}
+
+impl<T: 'static, V: Clone + 'static> Command<T> for Change<T, V> {
+    fn execute(&mut self, target: &mut T) {
+        (self.apply)(target, &self.after);
+    }
+
+    fn undo(&mut self, target: &mut T) { (self.apply)(target, &self.before); }
+
+    fn merge(&mut self, next: &dyn Command<T>) -> bool {
+        let next = next as &dyn Any;
+        match next.downcast_ref::<Self>() {
+            Some(next) if next.key == self.key => {
+                self.after = next.after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Commands undone and redone together; a transaction or a single command.
+type Entry<T> = Vec<Box<dyn Command<T>>>;
+
+// App-wide undo and redo for edits made through the UI. Each entry of the
+// stack is a command, a run of merged commands or a transaction. New
+// commands drop whatever was undone before them.
+pub struct UndoStack<T> {
+    undo:        VecDeque<Entry<T>>,
+    redo:        Vec<Entry<T>>,
+    // The oldest entries are dropped past this many; zero keeps them all.
+    limit:       usize,
+    // What the open transactions recorded so far.
+    transaction: Option<Entry<T>>,
+    depth:       usize,
+    // Whether the next command may merge into the last entry.
+    is_sealed:   bool,
+}
+
+impl<T: 'static> UndoStack<T> {
+    #[must_use]
+    pub const fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            transaction: None,
+            depth: 0,
+            is_sealed: true,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn limit(&self) -> usize { self.limit }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn can_undo(&self) -> bool { !self.undo.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn can_redo(&self) -> bool { !self.redo.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn undo_len(&self) -> usize { self.undo.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn redo_len(&self) -> usize { self.redo.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_in_transaction(&self) -> bool { self.depth > 0 }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    pub fn execute(
+        &mut self,
+        mut command: Box<dyn Command<T>>,
+        target: &mut T,
+    ) {
+        command.execute(target);
+        self.record(command);
+    }
+
+    // For edits that have already been applied, e.g. by a widget as the
+    // user dragged it.
+    pub fn record(&mut self, command: Box<dyn Command<T>>) {
+        self.redo.clear();
+        if let Some(transaction) = &mut self.transaction {
+            push_merged(transaction, command);
+            return;
+        }
+
+        let is_merged = match self.undo.back_mut() {
+            Some(entry) if !self.is_sealed && entry.len() == 1 => {
+                entry[0].merge(command.as_ref())
+            }
+            _ => false,
+        };
+        if !is_merged {
+            self.undo.push_back(vec![command]);
+            self.trim();
+        }
+        self.is_sealed = false;
+    }
+
+    // Stops the next command from merging into the last one, e.g. when a
+    // drag ends or the caret moves.
+    #[inline(always)]
+    pub const fn seal(&mut self) { self.is_sealed = true; }
+
+    // Everything recorded until the matching `commit_transaction` is
+    // undone as one entry. Transactions nest into the outermost one.
+    pub fn begin_transaction(&mut self) {
+        if self.depth == 0 {
+            self.transaction = Some(Vec::new());
+        }
+        self.depth += 1;
+    }
+
+    pub fn commit_transaction(&mut self) {
+        if self.depth == 0 {
+            return;
+        }
+        self.depth -= 1;
+        if self.depth > 0 {
+            return;
+        }
+        if let Some(transaction) = self.transaction.take()
+            && !transaction.is_empty()
+        {
+            self.undo.push_back(transaction);
+            self.trim();
+        }
+        self.is_sealed = true;
+    }
+
+    // Undoes what the open transactions recorded and drops it.
+    pub fn abort_transaction(&mut self, target: &mut T) {
+        if let Some(transaction) = self.transaction.take() {
+            for mut command in transaction.into_iter().rev() {
+                command.undo(target);
+            }
+        }
+        self.depth = 0;
+        self.is_sealed = true;
+    }
+
+    // Open transactions are committed first. Returns whether there was
+    // anything to undo.
+    pub fn undo(&mut self, target: &mut T) -> bool {
+        self.commit_all();
+        let Some(mut entry) = self.undo.pop_back() else {
+            return false;
+        };
+        for command in entry.iter_mut().rev() {
+            command.undo(target);
+        }
+        self.redo.push(entry);
+        self.is_sealed = true;
+        true
+    }
+
+    pub fn redo(&mut self, target: &mut T) -> bool {
+        self.commit_all();
+        let Some(mut entry) = self.redo.pop() else {
+            return false;
+        };
+        for command in &mut entry {
+            command.execute(target);
+        }
+        self.undo.push_back(entry);
+        self.is_sealed = true;
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.transaction = None;
+        self.depth = 0;
+        self.is_sealed = true;
+    }
+
+    fn commit_all(&mut self) {
+        while self.depth > 0 {
+            self.commit_transaction();
+        }
+    }
+
+    fn trim(&mut self) {
+        if self.limit == 0 {
+            return;
+        }
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+fn push_merged<T: 'static>(
+    entry: &mut Entry<T>,
+    command: Box<dyn Command<T>>,
+) {
+    if let Some(last) = entry.last_mut()
+        && last.merge(command.as_ref())
+    {
+        return;
+    }
+    entry.push(command);
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Change, Command, UndoStack};
+
+    fn set_x(state: &mut [i32; 2], x: &i32) { state[0] = *x; }
+
+    fn set_y(state: &mut [i32; 2], y: &i32) { state[1] = *y; }
+
+    fn change_x(before: i32, after: i32) -> Box<dyn Command<[i32; 2]>> {
+        Box::new(Change::new(0, before, after, set_x))
+    }
+
+    fn change_y(before: i32, after: i32) -> Box<dyn Command<[i32; 2]>> {
+        Box::new(Change::new(1, before, after, set_y))
+    }
+
+    #[rstest]
+    fn test_undo_and_redo() {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(0);
+        stack.execute(change_x(0, 1), &mut state);
+        stack.seal();
+        stack.execute(change_y(0, 2), &mut state);
+        assert_eq!(state, [1, 2]);
+
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, [1, 0]);
+        assert!(stack.undo(&mut state));
+        assert_eq!(state, [0, 0]);
+        assert!(!stack.undo(&mut state));
+
+        assert!(stack.redo(&mut state));
+        assert_eq!(state, [1, 0]);
+
+        // A new edit drops what was undone.
+        stack.execute(change_y(0, 5), &mut state);
+        assert!(!stack.can_redo());
+        assert_eq!(stack.undo_len(), 2);
+    }
+
+    #[rstest]
+    #[case(false, 1)]
+    #[case(true, 2)]
+    fn test_changes_merge_until_sealed(
+        #[case] is_sealed: bool,
+        #[case] expected_len: usize,
+    ) {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(0);
+        // The steps of a drag, applied as it went.
+        for x in 1..=3 {
+            state[0] = x;
+            stack.record(change_x(x - 1, x));
+        }
+        if is_sealed {
+            stack.seal();
+        }
+        state[0] = 4;
+        stack.record(change_x(3, 4));
+        assert_eq!(stack.undo_len(), expected_len);
+
+        stack.undo(&mut state);
+        let expected = if is_sealed { 3 } else { 0 };
+        assert_eq!(state, [expected, 0]);
+    }
+
+    #[rstest]
+    fn test_changes_with_other_keys_stay_apart() {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(0);
+        stack.execute(change_x(0, 1), &mut state);
+        stack.execute(change_y(0, 1), &mut state);
+        assert_eq!(stack.undo_len(), 2);
+    }
+
+    #[rstest]
+    fn test_transactions_undo_as_one() {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(0);
+        stack.begin_transaction();
+        stack.execute(change_x(0, 1), &mut state);
+        stack.begin_transaction();
+        stack.execute(change_y(0, 2), &mut state);
+        stack.commit_transaction();
+        assert!(stack.is_in_transaction());
+        stack.commit_transaction();
+
+        assert_eq!(stack.undo_len(), 1);
+        stack.undo(&mut state);
+        assert_eq!(state, [0, 0]);
+        stack.redo(&mut state);
+        assert_eq!(state, [1, 2]);
+    }
+
+    #[rstest]
+    fn test_abort_transaction() {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(0);
+        stack.begin_transaction();
+        stack.execute(change_x(0, 1), &mut state);
+        stack.execute(change_y(0, 2), &mut state);
+        stack.abort_transaction(&mut state);
+
+        assert_eq!(state, [0, 0]);
+        assert!(!stack.can_undo());
+    }
+
+    #[rstest]
+    fn test_limit_drops_the_oldest() {
+        let mut state = [0, 0];
+        let mut stack = UndoStack::new(2);
+        for x in 1..=3 {
+            stack.execute(change_x(x - 1, x), &mut state);
+            stack.seal();
+        }
+        assert_eq!(stack.undo_len(), 2);
+
+        while stack.undo(&mut state) {}
+        assert_eq!(state, [1, 0]);
+    }
+}
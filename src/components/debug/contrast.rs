@@ -0,0 +1,248 @@
+use super::DumpNode;
+use crate::components::common::Bounds;
+
+// The WCAG AA minimums; large text is at least 24 logical pixels.
+const MIN_RATIO: f32 = 4.5;
+const MIN_LARGE_RATIO: f32 = 3.0;
+const LARGE_FONT_SIZE: f32 = 24.0;
+
+// Text whose color doesn't stand out enough from what's drawn behind it.
+// `path` is the ids from the root down to the text's node, joined by `/`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastViolation {
+    pub path:       String,
+    pub text_color: [f32; 4],
+    // What the text is drawn over, with the fills beneath it blended.
+    pub background: [f32; 4],
+    pub ratio:      f32,
+    pub min_ratio:  f32,
+    // The text color moved towards black or white just far enough to pass.
+    pub suggested:  [f32; 4],
+}
+
+// Checks every node with a `text_color` style against the `fill_color`s
+// drawn before it that cover the middle of its bounds, blended over
+// `background`, e.g. the window's clear color. Nodes without bounds take
+// their parent's. Runs on any dump, so tests can assert a screen has no
+// violations and the inspector can highlight them.
+#[must_use]
+pub fn check_contrast(
+    root: &DumpNode,
+    background: [f32; 4],
+) -> Vec<ContrastViolation> {
+    let mut fills = Vec::new();
+    let mut violations = Vec::new();
+    check_node(
+        root,
+        &root.id,
+        None,
+        background,
+        &mut fills,
+        &mut violations,
+    );
+    violations
+}
+
+// Per WCAG, from 1 for equal colors up to 21 for black on white. Alpha is
+// ignored; blend translucent colors first.
+#[must_use]
+pub fn contrast_ratio(first: [f32; 4], second: [f32; 4]) -> f32 {
+    let first = relative_luminance(first);
+    let second = relative_luminance(second);
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+#[must_use]
+pub fn relative_luminance(color: [f32; 4]) -> f32 {
+    let [red, green, blue] = [color[0], color[1], color[2]].map(|channel| {
+        let channel = channel.clamp(0.0, 1.0);
+        match channel <= 0.039_28 {
+            true => channel / 12.92,
+            false => ((channel + 0.055) / 1.055).powf(2.4),
+        }
+    });
+    0.2126 * red + 0.7152 * green + 0.0722 * blue
+}
+
+fn check_node(
+    node: &DumpNode,
+    path: &str,
+    parent_bounds: Option<&Bounds>,
+    background: [f32; 4],
+    fills: &mut Vec<(Bounds, [f32; 4])>,
+    violations: &mut Vec<ContrastViolation>,
+) {
+    let bounds = node.bounds.as_ref().or(parent_bounds);
+    let text_color = style_color(node, "text_color");
+    if let (Some(text_color), Some(bounds)) = (text_color, bounds) {
+        let center = [
+            bounds.position[0] + bounds.size[0] / 2.0,
+            bounds.position[1] + bounds.size[1] / 2.0,
+        ];
+        let behind = fills
+            .iter()
+            .filter(|(fill_bounds, _)| is_inside(fill_bounds, center))
+            .fold(background, |behind, (_, fill)| blend(*fill, behind));
+        let text_color = blend(text_color, behind);
+        let is_large = style_value(node, "font_size")
+            .and_then(|value| value.parse::<f32>().ok())
+            .is_some_and(|font_size| font_size >= LARGE_FONT_SIZE);
+        let min_ratio = match is_large {
+            true => MIN_LARGE_RATIO,
+            false => MIN_RATIO,
+        };
+        let ratio = contrast_ratio(text_color, behind);
+        if ratio < min_ratio {
+            violations.push(ContrastViolation {
+                path: path.to_owned(),
+                text_color,
+                background: behind,
+                ratio,
+                min_ratio,
+                suggested: suggest_color(text_color, behind, min_ratio),
+            });
+        }
+    }
+    if let (Some(fill), Some(node_bounds)) =
+        (style_color(node, "fill_color"), &node.bounds)
+    {
+        fills.push((node_bounds.clone(), fill));
+    }
+
+    for child in &node.children {
+        let child_path = format!("{path}/{}", child.id);
+        check_node(child, &child_path, bounds, background, fills, violations);
+    }
+}
+
+fn style_value<'a>(node: &'a DumpNode, name: &str) -> Option<&'a str> {
+    node.style
+        .iter()
+        .find(|(style_name, _)| *style_name == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Style values hold the debug text of a `[f32; 4]`.
+fn style_color(node: &DumpNode, name: &str) -> Option<[f32; 4]> {
+    let value = style_value(node, name)?;
+    let channels = value.strip_prefix('[')?.strip_suffix(']')?;
+    let mut color = [0.0; 4];
+    let mut channels = channels.split(',');
+    for channel in &mut color {
+        *channel = channels.next()?.trim().parse().ok()?;
+    }
+    channels.next().is_none().then_some(color)
+}
+
+fn is_inside(bounds: &Bounds, point: [f32; 2]) -> bool {
+    (0..2).all(|axis| {
+        point[axis] >= bounds.position[axis]
+            && point[axis] <= bounds.position[axis] + bounds.size[axis]
+    })
+}
+
+// `color` drawn over an opaque `behind`.
+fn blend(color: [f32; 4], behind: [f32; 4]) -> [f32; 4] {
+    let alpha = color[3].clamp(0.0, 1.0);
+    let mix = |axis: usize| color[axis] * alpha + behind[axis] * (1.0 - alpha);
+    [mix(0), mix(1), mix(2), 1.0]
+}
+
+// Moves the color towards whichever of black and white contrasts more
+// with the background, keeping as much of its hue as passing allows.
+fn suggest_color(
+    color: [f32; 4],
+    background: [f32; 4],
+    min_ratio: f32,
+) -> [f32; 4] {
+    let black = [0.0, 0.0, 0.0, 1.0];
+    let white = [1.0, 1.0, 1.0, 1.0];
+    let target = match contrast_ratio(black, background)
+        >= contrast_ratio(white, background)
+    {
+        true => black,
+        false => white,
+    };
+    let mix = |amount: f32| {
+        let channel =
+            |axis: usize| color[axis] + (target[axis] - color[axis]) * amount;
+        [channel(0), channel(1), channel(2), 1.0]
+    };
+
+    // Contrast only grows along the way, so the least amount that passes
+    // is found by bisection.
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..24 {
+        let middle = (low + high) / 2.0;
+        match contrast_ratio(mix(middle), background) >= min_ratio {
+            true => high = middle,
+            false => low = middle,
+        }
+    }
+    mix(high)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{check_contrast, contrast_ratio};
+    use crate::components::{common::Bounds, debug::DumpNode};
+
+    const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    #[rstest]
+    #[case(BLACK, WHITE, 21.0)]
+    #[case(WHITE, WHITE, 1.0)]
+    #[case([0.5, 0.5, 0.5, 1.0], WHITE, 3.98)]
+    fn test_contrast_ratio(
+        #[case] first: [f32; 4],
+        #[case] second: [f32; 4],
+        #[case] expected: f32,
+    ) {
+        assert!((contrast_ratio(first, second) - expected).abs() < 0.01);
+    }
+
+    fn bounds(x: f32, width: f32) -> Bounds {
+        Bounds::without_clip_rect([x, 0.0], [width, 20.0])
+    }
+
+    #[rstest]
+    fn test_check_contrast() {
+        let gray = [0.6, 0.6, 0.6, 1.0];
+        let root = DumpNode::new("root", "Panel")
+            .with_child(
+                DumpNode::new("button", "Block")
+                    .with_bounds(bounds(0.0, 100.0))
+                    .with_style("fill_color", [0.1, 0.1, 0.1, 1.0]),
+            )
+            .with_child(
+                DumpNode::new("label", "Text")
+                    .with_bounds(bounds(10.0, 80.0))
+                    .with_style("text_color", gray),
+            )
+            .with_child(
+                DumpNode::new("hint", "Text")
+                    .with_bounds(bounds(200.0, 80.0))
+                    .with_style("text_color", gray),
+            )
+            .with_child(
+                DumpNode::new("title", "Text")
+                    .with_bounds(bounds(200.0, 80.0))
+                    .with_style("text_color", [0.55, 0.55, 0.55, 1.0])
+                    .with_style("font_size", 32.0),
+            );
+
+        let violations = check_contrast(&root, WHITE);
+
+        // Over the dark button, the label is fine; the large title passes
+        // with less.
+        assert_eq!(violations.len(), 1);
+        let violation = &violations[0];
+        assert_eq!(violation.path, "root/hint");
+        assert_eq!(violation.background, WHITE);
+        assert!(violation.suggested[0] < gray[0]);
+        assert!(contrast_ratio(violation.suggested, WHITE) >= 4.5);
+    }
+}
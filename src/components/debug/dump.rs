@@ -0,0 +1,176 @@
+use std::fmt::Write;
+
+use crate::components::common::Bounds;
+
+// Widgets that can describe themselves for `WidgetDump`s. Containers
+// add their children's nodes to their own.
+pub trait Inspect {
+    fn inspect(&self, id: &str) -> DumpNode;
+}
+
+// One widget in a dump. Style values are kept as their debug text so
+// that any style can be listed and compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpNode {
+    pub id:       String,
+    pub kind:     &'static str,
+    pub bounds:   Option<Bounds>,
+    pub style:    Vec<(&'static str, String)>,
+    // Whether the widget has changes not yet written to the renderer.
+    pub is_dirty: bool,
+    pub children: Vec<DumpNode>,
+}
+
+impl DumpNode {
+    #[must_use]
+    pub fn new(id: impl Into<String>, kind: &'static str) -> Self {
+        Self {
+            id: id.into(),
+            kind,
+            bounds: None,
+            style: Vec::new(),
+            is_dirty: false,
+            children: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    #[must_use]
+    pub fn with_style(
+        mut self,
+        name: &'static str,
+        value: impl std::fmt::Debug,
+    ) -> Self {
+        self.style.push((name, format!("{value:?}")));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_dirty(mut self, is_dirty: bool) -> Self {
+        self.is_dirty = is_dirty;
+        self
+    }
+
+    #[must_use]
+    pub fn with_child(mut self, child: Self) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    // Serializes the node and its children as indented JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json, 0);
+        json
+    }
+
+    fn write_json(&self, json: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth + 1);
+        let _ = write!(
+            json,
+            "{{\n{indent}\"id\": {},\n{indent}\"kind\": {},\n",
+            quote(&self.id),
+            quote(self.kind),
+        );
+        if let Some(bounds) = &self.bounds {
+            let _ = writeln!(
+                json,
+                "{indent}\"bounds\": {{\"position\": {:?}, \"size\": {:?}, \"clip_rect\": {:?}}},",
+                bounds.position, bounds.size, bounds.clip_rect,
+            );
+        }
+        let style: Vec<String> = self
+            .style
+            .iter()
+            .map(|(name, value)| format!("{}: {}", quote(name), quote(value)))
+            .collect();
+        let _ = writeln!(json, "{indent}\"style\": {{{}}},", style.join(", "));
+        let _ = write!(
+            json,
+            "{indent}\"is_dirty\": {},\n{indent}\"children\": [",
+            self.is_dirty
+        );
+        for (index, child) in self.children.iter().enumerate() {
+            json.push_str(if index == 0 { "\n" } else { ",\n" });
+            json.push_str(&"  ".repeat(depth + 2));
+            child.write_json(json, depth + 2);
+        }
+        if !self.children.is_empty() {
+            json.push('\n');
+            json.push_str(&indent);
+        }
+        let _ = write!(json, "]\n{}}}", "  ".repeat(depth));
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for char in text.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            char if char.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", u32::from(char));
+            }
+            char => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{DumpNode, quote};
+    use crate::components::common::Bounds;
+
+    #[rstest]
+    #[case("a", r#""a""#)]
+    #[case("a\"b", r#""a\"b""#)]
+    #[case("b\\c", r#""b\\c""#)]
+    #[case("c\n", r#""c\n""#)]
+    #[case("\u{1}", r#""\u0001""#)]
+    fn test_quote(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(quote(text), expected);
+    }
+
+    #[rstest]
+    fn test_to_json() {
+        let node = DumpNode::new("root", "Panel").with_child(
+            DumpNode::new("button", "Block")
+                .with_bounds(Bounds {
+                    position:  [1.0, 2.0],
+                    size:      [3.0, 4.0],
+                    clip_rect: [0.0, 0.0, 10.0, 10.0],
+                })
+                .with_style("border_size", 1.0),
+        );
+        let expected = r#"{
+  "id": "root",
+  "kind": "Panel",
+  "style": {},
+  "is_dirty": false,
+  "children": [
+    {
+      "id": "button",
+      "kind": "Block",
+      "bounds": {"position": [1.0, 2.0], "size": [3.0, 4.0], "clip_rect": [0.0, 0.0, 10.0, 10.0]},
+      "style": {"border_size": "1.0"},
+      "is_dirty": false,
+      "children": []
+    }
+  ]
+}"#;
+        assert_eq!(node.to_json(), expected);
+    }
+}
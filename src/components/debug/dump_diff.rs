@@ -0,0 +1,212 @@
+use super::DumpNode;
+use crate::components::common::Bounds;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpChangeKind {
+    Added,
+    Removed,
+    Kind {
+        before: &'static str,
+        after:  &'static str,
+    },
+    Bounds {
+        before: Option<Bounds>,
+        after:  Option<Bounds>,
+    },
+    // `None` for a style value only one side has.
+    Style {
+        name:   &'static str,
+        before: Option<String>,
+        after:  Option<String>,
+    },
+    Dirty {
+        after: bool,
+    },
+}
+
+// `path` is the ids from the root down to the node, joined by `/`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpChange {
+    pub path: String,
+    pub kind: DumpChangeKind,
+}
+
+// The differences between two dumps, in tree order. Children are
+// matched by id, so reordering alone isn't a change.
+#[must_use]
+pub fn diff_dumps(before: &DumpNode, after: &DumpNode) -> Vec<DumpChange> {
+    let mut changes = Vec::new();
+    diff_nodes(before, after, &before.id, &mut changes);
+    changes
+}
+
+fn diff_nodes(
+    before: &DumpNode,
+    after: &DumpNode,
+    path: &str,
+    changes: &mut Vec<DumpChange>,
+) {
+    let mut push =
+        |kind| changes.push(DumpChange { path: path.to_owned(), kind });
+    if before.kind != after.kind {
+        push(DumpChangeKind::Kind { before: before.kind, after: after.kind });
+    }
+    if before.bounds != after.bounds {
+        push(DumpChangeKind::Bounds {
+            before: before.bounds.clone(),
+            after:  after.bounds.clone(),
+        });
+    }
+    for (name, before_value) in &before.style {
+        let after_value = style_value(after, name);
+        if after_value != Some(before_value) {
+            push(DumpChangeKind::Style {
+                name,
+                before: Some(before_value.clone()),
+                after: after_value.cloned(),
+            });
+        }
+    }
+    for (name, after_value) in &after.style {
+        if style_value(before, name).is_none() {
+            push(DumpChangeKind::Style {
+                name,
+                before: None,
+                after: Some(after_value.clone()),
+            });
+        }
+    }
+    if before.is_dirty != after.is_dirty {
+        push(DumpChangeKind::Dirty { after: after.is_dirty });
+    }
+
+    for child in &before.children {
+        let child_path = format!("{path}/{}", child.id);
+        match after.children.iter().find(|other| other.id == child.id) {
+            Some(other) => diff_nodes(child, other, &child_path, changes),
+            None => changes.push(DumpChange {
+                path: child_path,
+                kind: DumpChangeKind::Removed,
+            }),
+        }
+    }
+    for child in &after.children {
+        if !before.children.iter().any(|other| other.id == child.id) {
+            changes.push(DumpChange {
+                path: format!("{path}/{}", child.id),
+                kind: DumpChangeKind::Added,
+            });
+        }
+    }
+}
+
+fn style_value<'a>(node: &'a DumpNode, name: &str) -> Option<&'a String> {
+    node.style
+        .iter()
+        .find(|(style_name, _)| *style_name == name)
+        .map(|(_, value)| value)
+}
+
+// Takes a dump on the frames it's asked to and diffs it against the
+// previous one, so that dumping costs nothing on other frames.
+#[derive(Debug, Default)]
+pub struct DumpRecorder {
+    previous:     Option<DumpNode>,
+    is_requested: bool,
+}
+
+impl DumpRecorder {
+    #[must_use]
+    pub const fn new() -> Self { Self { previous: None, is_requested: false } }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn previous(&self) -> Option<&DumpNode> {
+        self.previous.as_ref()
+    }
+
+    pub const fn request(&mut self) { self.is_requested = true; }
+
+    // Dumps the tree if requested, returning the changes since the last
+    // dump; the first dump is compared against nothing and yields none.
+    pub fn record(
+        &mut self,
+        dump: impl FnOnce() -> DumpNode,
+    ) -> Option<Vec<DumpChange>> {
+        if !std::mem::take(&mut self.is_requested) {
+            return None;
+        }
+        let current = dump();
+        let changes = self
+            .previous
+            .as_ref()
+            .map_or_else(Vec::new, |previous| diff_dumps(previous, &current));
+        self.previous = Some(current);
+
+        Some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        DumpChange, DumpChangeKind, DumpNode, DumpRecorder, diff_dumps,
+    };
+    use crate::components::common::Bounds;
+
+    fn tree(x: f32, border_size: f32, extra: bool) -> DumpNode {
+        let mut root = DumpNode::new("root", "Panel").with_child(
+            DumpNode::new("button", "Block")
+                .with_bounds(Bounds::without_clip_rect([x, 0.0], [10.0, 10.0]))
+                .with_style("border_size", border_size),
+        );
+        if extra {
+            root = root.with_child(DumpNode::new("label", "Label"));
+        }
+        root
+    }
+
+    #[rstest]
+    fn test_diff_dumps() {
+        let changes =
+            diff_dumps(&tree(0.0, 1.0, true), &tree(1.0, 2.0, false));
+        let kinds: Vec<(&str, &DumpChangeKind)> = changes
+            .iter()
+            .map(|DumpChange { path, kind }| (path.as_str(), kind))
+            .collect();
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            ("root/button", DumpChangeKind::Bounds { .. })
+        ));
+        assert_eq!(
+            kinds[1],
+            (
+                "root/button",
+                &DumpChangeKind::Style {
+                    name:   "border_size",
+                    before: Some("1.0".to_owned()),
+                    after:  Some("2.0".to_owned()),
+                }
+            )
+        );
+        assert_eq!(kinds[2], ("root/label", &DumpChangeKind::Removed));
+    }
+
+    #[rstest]
+    fn test_recorder_only_dumps_on_request() {
+        let mut recorder = DumpRecorder::new();
+        assert_eq!(recorder.record(|| unreachable!()), None);
+
+        recorder.request();
+        assert_eq!(
+            recorder.record(|| tree(0.0, 1.0, false)),
+            Some(Vec::new())
+        );
+        recorder.request();
+        let changes = recorder.record(|| tree(0.0, 1.0, true)).unwrap();
+        assert_eq!(changes[0].kind, DumpChangeKind::Added);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{ffi::c_void, path::PathBuf, ptr};
+
+use renderdoc::{RenderDoc, V141};
+
+use crate::components::common::{Modifiers, Shortcut, ShortcutPlatform};
+
+// Takes RenderDoc captures of chosen frames, so that a bug report can
+// come with a capture of the frame that goes wrong. Wrap each frame, from
+// building the command encoder to presenting, in `begin_frame` and
+// `end_frame`, and ask for captures with `capture_frames` or a hotkey.
+//
+// Only works while the app runs under RenderDoc or has its library
+// loaded; `new` fails otherwise.
+pub struct GpuCapture {
+    renderdoc:      RenderDoc<V141>,
+    hotkey:         Option<Shortcut>,
+    pending_frames: u32,
+    is_capturing:   bool,
+}
+
+impl GpuCapture {
+    pub fn new() -> Result<Self, renderdoc::Error> {
+        Ok(Self {
+            renderdoc:      RenderDoc::new()?,
+            hotkey:         None,
+            pending_frames: 0,
+            is_capturing:   false,
+        })
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn hotkey(&self) -> Option<&Shortcut> { self.hotkey.as_ref() }
+
+    #[inline(always)]
+    pub fn set_hotkey(&mut self, hotkey: Option<Shortcut>) {
+        self.hotkey = hotkey;
+    }
+
+    // Where captures are written, as a path and file name prefix, e.g.
+    // `bug_reports/frame`.
+    #[inline(always)]
+    pub fn set_path_template(&mut self, path_template: impl Into<PathBuf>) {
+        self.renderdoc.set_capture_file_path_template(path_template);
+    }
+
+    // Captures the next `count` frames, each into its own file.
+    #[inline(always)]
+    pub fn capture_frames(&mut self, count: u32) {
+        self.pending_frames += count;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_capturing(&self) -> bool { self.is_capturing }
+
+    // Asks for a capture of the next frame when `key` and `modifiers`
+    // match the hotkey. Returns whether they did.
+    pub fn handle_key(&mut self, key: &str, modifiers: &Modifiers) -> bool {
+        let Some(hotkey) = &self.hotkey else {
+            return false;
+        };
+        let is_hotkey = hotkey.key().eq_ignore_ascii_case(key)
+            && hotkey.is_held(modifiers, ShortcutPlatform::current());
+        if is_hotkey {
+            self.capture_frames(1);
+        }
+        is_hotkey
+    }
+
+    pub fn begin_frame(&mut self) {
+        if self.pending_frames == 0 || self.is_capturing {
+            return;
+        }
+        // Null handles let RenderDoc pick the only device and window.
+        self.renderdoc
+            .start_frame_capture(ptr::null::<c_void>(), ptr::null());
+        self.is_capturing = true;
+    }
+
+    // Returns the file the frame was captured into, if it was.
+    pub fn end_frame(&mut self) -> Option<PathBuf> {
+        if !self.is_capturing {
+            return None;
+        }
+        self.renderdoc
+            .end_frame_capture(ptr::null::<c_void>(), ptr::null());
+        self.is_capturing = false;
+        self.pending_frames -= 1;
+
+        let count = self.renderdoc.get_num_captures();
+        let (path, _) = self.renderdoc.get_capture(count.checked_sub(1)?)?;
+        Some(path)
+    }
+
+    // Every capture written so far, oldest first.
+    #[must_use]
+    pub fn captures(&self) -> Vec<PathBuf> {
+        (0..self.renderdoc.get_num_captures())
+            .filter_map(|index| self.renderdoc.get_capture(index))
+            .map(|(path, _)| path)
+            .collect()
+    }
+}
@@ -0,0 +1,13 @@
+mod contrast;
+mod dump;
+mod dump_diff;
+#[cfg(feature = "renderdoc")]
+mod gpu_capture;
+
+pub use contrast::{
+    ContrastViolation, check_contrast, contrast_ratio, relative_luminance,
+};
+pub use dump::{DumpNode, Inspect};
+pub use dump_diff::{DumpChange, DumpChangeKind, DumpRecorder, diff_dumps};
+#[cfg(feature = "renderdoc")]
+pub use gpu_capture::GpuCapture;
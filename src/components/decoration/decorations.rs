@@ -0,0 +1,224 @@
+use std::time::Instant;
+
+use bon::Builder;
+use glam::Mat4;
+use winit::window::{CursorIcon, ResizeDirection, Window};
+
+use super::{DoubleClick, resize_direction_at};
+use crate::{
+    components::{
+        common::Bounds,
+        widgets::{
+            Block, BlockStyle, TitleBar, TitleBarAction, TitleBarStyle,
+            block_states::Positioned,
+        },
+    },
+    core::Renderer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecorationAction {
+    Resize(ResizeDirection),
+    TitleBar(TitleBarAction),
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct DecorationStyle {
+    #[builder(default = TitleBarStyle::builder().build())]
+    title_bar:           TitleBarStyle,
+    #[builder(default = 32.0)]
+    title_bar_height:    f32,
+    #[builder(default = [0.7, 0.7, 0.7, 1.0])]
+    border_color:        [f32; 4],
+    #[builder(default = 1.0)]
+    border_width:        f32,
+    // The grabbable resize area, which may be wider than the drawn border.
+    #[builder(default = 6.0)]
+    resize_border_width: f32,
+    #[builder(default = 16.0)]
+    resize_corner_size:  f32,
+}
+
+pub struct Decorations {
+    window_size:  [f32; 2],
+    style:        DecorationStyle,
+    is_maximized: bool,
+    title_bar:    TitleBar,
+    border:       Block<Positioned>,
+    double_click: DoubleClick,
+}
+
+impl Decorations {
+    #[must_use]
+    pub fn new(
+        window_size: [f32; 2],
+        style: DecorationStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let title_bar = TitleBar::new(
+            title_bar_bounds(window_size, &style),
+            style.title_bar.clone(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let border = Block::<Positioned>::new(
+            Bounds::without_clip_rect([0.0, 0.0], window_size),
+            border_style(&style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+
+        Self {
+            window_size,
+            style,
+            is_maximized: false,
+            title_bar,
+            border,
+            double_click: DoubleClick::default(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn title_bar(&self) -> &TitleBar { &self.title_bar }
+
+    // The area left for the application below the title bar and inside
+    // the border.
+    #[must_use]
+    pub fn content_bounds(&self) -> Bounds {
+        let border_width = self.style.border_width;
+        let [width, height] = self.window_size;
+        let top = border_width + self.style.title_bar_height;
+
+        Bounds::without_clip_rect(
+            [border_width, top],
+            [
+                (width - border_width * 2.0).max(0.0),
+                (height - top - border_width).max(0.0),
+            ],
+        )
+    }
+
+    // A maximized window can't be resized by its edges.
+    #[must_use]
+    pub fn resize_direction_at(
+        &self,
+        position: [f32; 2],
+    ) -> Option<ResizeDirection> {
+        if self.is_maximized {
+            return None;
+        }
+        resize_direction_at(
+            self.window_size,
+            self.style.resize_border_width,
+            self.style.resize_corner_size,
+            position,
+        )
+    }
+
+    #[must_use]
+    pub fn cursor_icon(&self, position: [f32; 2]) -> CursorIcon {
+        self.resize_direction_at(position)
+            .map_or(CursorIcon::Default, CursorIcon::from)
+    }
+
+    pub fn hover(
+        &mut self,
+        position: Option<[f32; 2]>,
+        window: &Window,
+        renderer: &mut Renderer,
+    ) {
+        let cursor_icon = position.map_or(CursorIcon::Default, |position| {
+            self.cursor_icon(position)
+        });
+        window.set_cursor(cursor_icon);
+
+        // Caption buttons don't light up under the resize border.
+        let position = position.filter(|_| cursor_icon == CursorIcon::Default);
+        self.title_bar.hover(position, renderer);
+    }
+
+    // Handles a left-button press: resizes from the edges, toggles
+    // maximize on a double click in the drag region, and otherwise
+    // defers to the title bar.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        now: Instant,
+        window: &Window,
+    ) -> Option<DecorationAction> {
+        self.is_maximized = window.is_maximized();
+
+        if let Some(direction) = self.resize_direction_at(position) {
+            self.double_click.reset();
+            let _ = window.drag_resize_window(direction);
+            return Some(DecorationAction::Resize(direction));
+        }
+
+        let action = self.title_bar.action_at(position);
+        let is_double_click = action == Some(TitleBarAction::Drag)
+            && self.double_click.register(position, now);
+        if is_double_click {
+            window.set_maximized(!self.is_maximized);
+            return Some(DecorationAction::TitleBar(
+                TitleBarAction::ToggleMaximize,
+            ));
+        }
+
+        self.title_bar
+            .press(position, window)
+            .map(DecorationAction::TitleBar)
+    }
+
+    pub fn resize(
+        &mut self,
+        window_size: [f32; 2],
+        is_maximized: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.window_size = window_size;
+        self.is_maximized = is_maximized;
+        self.title_bar.update_bounds(
+            title_bar_bounds(window_size, &self.style),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.border
+            .update_size(window_size, view_projection, dpr, renderer);
+        self.border
+            .update_style(border_style(&self.style, dpr), renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.title_bar.destroy(renderer);
+        self.border.destroy(renderer);
+    }
+}
+
+fn title_bar_bounds(window_size: [f32; 2], style: &DecorationStyle) -> Bounds {
+    let border_width = style.border_width;
+    Bounds::without_clip_rect(
+        [border_width, border_width],
+        [
+            (window_size[0] - border_width * 2.0).max(0.0),
+            style.title_bar_height,
+        ],
+    )
+}
+
+// A transparent block the size of the window, so only its border shows.
+fn border_style(style: &DecorationStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color([0.0; 4])
+        .border_color(style.border_color)
+        .border_size(style.border_width * dpr)
+        .build()
+}
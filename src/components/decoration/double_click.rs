@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoubleClick {
+    interval:     Duration,
+    max_distance: f32,
+    last_click:   Option<(Instant, [f32; 2])>,
+}
+
+impl Default for DoubleClick {
+    fn default() -> Self { Self::new(Duration::from_millis(500), 4.0) }
+}
+
+impl DoubleClick {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(interval: Duration, max_distance: f32) -> Self {
+        Self { interval, max_distance, last_click: None }
+    }
+
+    // Returns whether this click completes a double click. A completed
+    // double click is forgotten, so a third click starts over.
+    pub fn register(&mut self, position: [f32; 2], now: Instant) -> bool {
+        let is_double_click =
+            self.last_click.is_some_and(|(last_time, last_position)| {
+                let dx = position[0] - last_position[0];
+                let dy = position[1] - last_position[1];
+                now.duration_since(last_time) <= self.interval
+                    && dx.hypot(dy) <= self.max_distance
+            });
+        self.last_click = match is_double_click {
+            true => None,
+            false => Some((now, position)),
+        };
+
+        is_double_click
+    }
+
+    #[inline(always)]
+    pub const fn reset(&mut self) { self.last_click = None; }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use rstest::rstest;
+
+    use super::DoubleClick;
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    #[rstest]
+    #[case(ms(200), [1.0, 1.0], true)]
+    #[case(ms(600), [0.0, 0.0], false)]
+    #[case(ms(200), [10.0, 0.0], false)]
+    fn test_double_click(
+        #[case] delay: Duration,
+        #[case] second_position: [f32; 2],
+        #[case] expected: bool,
+    ) {
+        let mut double_click = DoubleClick::default();
+        let start = Instant::now();
+
+        assert!(!double_click.register([0.0, 0.0], start));
+        assert_eq!(
+            double_click.register(second_position, start + delay),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_double_click_third_click_starts_over() {
+        let mut double_click = DoubleClick::default();
+        let start = Instant::now();
+
+        double_click.register([0.0, 0.0], start);
+        double_click.register([0.0, 0.0], start + ms(100));
+
+        assert!(!double_click.register([0.0, 0.0], start + ms(200)));
+    }
+}
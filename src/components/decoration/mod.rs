@@ -0,0 +1,7 @@
+mod decorations;
+mod double_click;
+mod resize_region;
+
+pub use decorations::{DecorationAction, DecorationStyle, Decorations};
+pub use double_click::DoubleClick;
+pub use resize_region::resize_direction_at;
@@ -0,0 +1,84 @@
+use winit::window::ResizeDirection;
+
+// Maps a position in window coordinates to the edge or corner of the
+// window it would resize. Corners take precedence over edges and reach
+// `corner_size` along both edges, which makes them easier to grab.
+#[must_use]
+pub fn resize_direction_at(
+    window_size: [f32; 2],
+    border_width: f32,
+    corner_size: f32,
+    position: [f32; 2],
+) -> Option<ResizeDirection> {
+    let [width, height] = window_size;
+    let [x, y] = position;
+    if x < 0.0 || y < 0.0 || x > width || y > height {
+        return None;
+    }
+
+    let corner_size = corner_size.max(border_width);
+    let near_left = x < corner_size;
+    let near_right = x > width - corner_size;
+    let near_top = y < corner_size;
+    let near_bottom = y > height - corner_size;
+    let on_left = x < border_width;
+    let on_right = x > width - border_width;
+    let on_top = y < border_width;
+    let on_bottom = y > height - border_width;
+
+    let is_corner_hit =
+        |near_x: bool, near_y: bool, on_x: bool, on_y: bool| {
+            near_x && near_y && (on_x || on_y)
+        };
+    if is_corner_hit(near_left, near_top, on_left, on_top) {
+        return Some(ResizeDirection::NorthWest);
+    }
+    if is_corner_hit(near_right, near_top, on_right, on_top) {
+        return Some(ResizeDirection::NorthEast);
+    }
+    if is_corner_hit(near_left, near_bottom, on_left, on_bottom) {
+        return Some(ResizeDirection::SouthWest);
+    }
+    if is_corner_hit(near_right, near_bottom, on_right, on_bottom) {
+        return Some(ResizeDirection::SouthEast);
+    }
+
+    match (on_left, on_right, on_top, on_bottom) {
+        (true, ..) => Some(ResizeDirection::West),
+        (_, true, ..) => Some(ResizeDirection::East),
+        (.., true, _) => Some(ResizeDirection::North),
+        (.., true) => Some(ResizeDirection::South),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winit::window::ResizeDirection;
+
+    use super::resize_direction_at;
+
+    #[rstest]
+    #[case([2.0, 2.0], Some(ResizeDirection::NorthWest))]
+    #[case([10.0, 2.0], Some(ResizeDirection::NorthWest))]
+    #[case([2.0, 10.0], Some(ResizeDirection::NorthWest))]
+    #[case([398.0, 2.0], Some(ResizeDirection::NorthEast))]
+    #[case([2.0, 298.0], Some(ResizeDirection::SouthWest))]
+    #[case([390.0, 298.0], Some(ResizeDirection::SouthEast))]
+    #[case([200.0, 2.0], Some(ResizeDirection::North))]
+    #[case([200.0, 298.0], Some(ResizeDirection::South))]
+    #[case([2.0, 150.0], Some(ResizeDirection::West))]
+    #[case([398.0, 150.0], Some(ResizeDirection::East))]
+    #[case([200.0, 150.0], None)]
+    #[case([10.0, 10.0], None)]
+    #[case([-1.0, 150.0], None)]
+    fn test_resize_direction_at(
+        #[case] position: [f32; 2],
+        #[case] expected: Option<ResizeDirection>,
+    ) {
+        let direction =
+            resize_direction_at([400.0, 300.0], 4.0, 16.0, position);
+        assert_eq!(direction, expected);
+    }
+}
@@ -0,0 +1,104 @@
+// Maps graph coordinates to screen coordinates: the screen position of
+// the graph origin, and a zoom factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphCamera {
+    offset:   [f32; 2],
+    zoom:     f32,
+    min_zoom: f32,
+    max_zoom: f32,
+}
+
+impl Default for GraphCamera {
+    fn default() -> Self { Self::new([0.0, 0.0]) }
+}
+
+impl GraphCamera {
+    #[must_use]
+    pub const fn new(offset: [f32; 2]) -> Self {
+        Self { offset, zoom: 1.0, min_zoom: 0.1, max_zoom: 4.0 }
+    }
+
+    #[must_use]
+    pub const fn with_zoom_limits(
+        mut self,
+        min_zoom: f32,
+        max_zoom: f32,
+    ) -> Self {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom;
+        self.zoom = self.zoom.clamp(min_zoom, max_zoom);
+        self
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn offset(&self) -> [f32; 2] { self.offset }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn zoom(&self) -> f32 { self.zoom }
+
+    #[must_use]
+    pub const fn to_screen(&self, point: [f32; 2]) -> [f32; 2] {
+        [
+            self.offset[0] + point[0] * self.zoom,
+            self.offset[1] + point[1] * self.zoom,
+        ]
+    }
+
+    #[must_use]
+    pub const fn to_graph(&self, point: [f32; 2]) -> [f32; 2] {
+        [
+            (point[0] - self.offset[0]) / self.zoom,
+            (point[1] - self.offset[1]) / self.zoom,
+        ]
+    }
+
+    // x, y, width, height
+    #[must_use]
+    pub const fn rect_to_screen(&self, rect: [f32; 4]) -> [f32; 4] {
+        let [x, y] = self.to_screen([rect[0], rect[1]]);
+        [x, y, rect[2] * self.zoom, rect[3] * self.zoom]
+    }
+
+    pub const fn pan(&mut self, delta: [f32; 2]) {
+        self.offset[0] += delta[0];
+        self.offset[1] += delta[1];
+    }
+
+    // Zooms by `factor`, keeping the graph point under `anchor` in place.
+    pub fn zoom_at(&mut self, anchor: [f32; 2], factor: f32) {
+        let point = self.to_graph(anchor);
+        self.zoom = (self.zoom * factor).clamp(self.min_zoom, self.max_zoom);
+        self.offset = [
+            anchor[0] - point[0] * self.zoom,
+            anchor[1] - point[1] * self.zoom,
+        ];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::GraphCamera;
+
+    #[rstest]
+    #[case([0.0, 0.0], 2.0, [100.0, 50.0], [200.0, 100.0])]
+    #[case([40.0, 30.0], 2.0, [100.0, 50.0], [160.0, 70.0])]
+    // Clamped to the maximum zoom.
+    #[case([40.0, 30.0], 100.0, [100.0, 50.0], [280.0, 110.0])]
+    fn test_graph_camera_zoom_at(
+        #[case] anchor: [f32; 2],
+        #[case] factor: f32,
+        #[case] point: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let mut camera = GraphCamera::new([0.0, 0.0]);
+        camera.zoom_at(anchor, factor);
+
+        assert_eq!(camera.to_screen(point), expected);
+        // The graph point under the anchor stays there.
+        assert_eq!(camera.to_graph(anchor), anchor);
+    }
+}
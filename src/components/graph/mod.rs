@@ -0,0 +1,9 @@
+mod camera;
+mod node_graph;
+mod wire;
+
+pub use camera::GraphCamera;
+pub use node_graph::{
+    Connection, ConnectionId, GraphEvent, NodeGraph, NodeGraphStyle, NodeId,
+    PortKind, PortRef,
+};
@@ -0,0 +1,982 @@
+use std::collections::HashSet;
+
+use bon::Builder;
+use glam::Mat4;
+use slotmap::{DefaultKey, SlotMap};
+
+use super::{
+    GraphCamera,
+    wire::{distance_to_polyline, wire_points},
+};
+use crate::{
+    components::{
+        common::{Bar, Bounds, intersect_rects},
+        widgets::{Block, BlockStyle, block_states::Positioned},
+    },
+    core::{RectangleId, Renderer},
+};
+
+pub type NodeId = DefaultKey;
+pub type ConnectionId = DefaultKey;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortKind {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortRef {
+    pub node:  NodeId,
+    pub kind:  PortKind,
+    pub index: usize,
+}
+
+// Always from an output port to an input port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Connection {
+    pub from: PortRef,
+    pub to:   PortRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphEvent {
+    NodeAdded(NodeId),
+    NodeRemoved(NodeId),
+    // Sent once a drag ends.
+    NodeMoved(NodeId),
+    Connected(ConnectionId, Connection),
+    Disconnected(Connection),
+    SelectionChanged,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct NodeGraphStyle {
+    #[builder(default = [0.2, 0.2, 0.23, 1.0])]
+    node_color:           [f32; 4],
+    #[builder(default = [0.35, 0.35, 0.4, 1.0])]
+    node_border_color:    [f32; 4],
+    #[builder(default = [0.35, 0.6, 1.0, 1.0])]
+    selection_color:      [f32; 4],
+    #[builder(default = 1.0)]
+    node_border_size:     f32,
+    #[builder(default = 6.0)]
+    corner_radius:        f32,
+    #[builder(default = [0.8, 0.8, 0.85, 1.0])]
+    port_color:           [f32; 4],
+    #[builder(default = 5.0)]
+    port_radius:          f32,
+    #[builder(default = [0.7, 0.7, 0.75, 1.0])]
+    wire_color:           [f32; 4],
+    #[builder(default = 2.0)]
+    wire_thickness:       f32,
+    #[builder(default = 24)]
+    wire_segments:        usize,
+    #[builder(default = [0.35, 0.6, 1.0, 0.15])]
+    marquee_color:        [f32; 4],
+    #[builder(default = [0.35, 0.6, 1.0, 0.8])]
+    marquee_border_color: [f32; 4],
+    // How close to a port or wire, in screen pixels, a press hits it.
+    #[builder(default = 6.0)]
+    hit_tolerance:        f32,
+}
+
+struct Node {
+    // x, y, width, height in graph coordinates
+    rect:    [f32; 4],
+    inputs:  usize,
+    outputs: usize,
+    block:   Block<Positioned>,
+    ports:   Vec<Block<Positioned>>,
+}
+
+struct Wire {
+    connection: Connection,
+    bars:       Vec<RectangleId>,
+}
+
+enum Gesture {
+    MoveNodes { last: [f32; 2], is_moved: bool },
+    Connect { from: PortRef, preview: Vec<RectangleId> },
+    Marquee { start: [f32; 2], block: Block<Positioned> },
+}
+
+// An editor for graphs of nodes with input ports on the left and output
+// ports on the right, connected by wires. Nodes live in graph
+// coordinates; the camera pans and zooms them into `bounds`. Presses
+// drag nodes, draw wires from ports, pick wires back up from inputs, or
+// start a marquee selection on the empty canvas. The caller draws node
+// titles in `node_bounds`.
+pub struct NodeGraph {
+    bounds:              Bounds,
+    style:               NodeGraphStyle,
+    camera:              GraphCamera,
+    nodes:               SlotMap<NodeId, Node>,
+    // Nodes in drawing order; later ones are hit first.
+    order:               Vec<NodeId>,
+    wires:               SlotMap<ConnectionId, Wire>,
+    selected:            HashSet<NodeId>,
+    selected_connection: Option<ConnectionId>,
+    gesture:             Option<Gesture>,
+    events:              Vec<GraphEvent>,
+}
+
+impl NodeGraph {
+    #[must_use]
+    pub fn new(bounds: Bounds, style: NodeGraphStyle) -> Self {
+        let camera = GraphCamera::new(bounds.position);
+        Self {
+            bounds,
+            style,
+            camera,
+            nodes: SlotMap::new(),
+            order: Vec::new(),
+            wires: SlotMap::new(),
+            selected: HashSet::new(),
+            selected_connection: None,
+            gesture: None,
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn camera(&self) -> &GraphCamera { &self.camera }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.order.iter().copied()
+    }
+
+    pub fn connections(
+        &self,
+    ) -> impl Iterator<Item = (ConnectionId, Connection)> + '_ {
+        self.wires.iter().map(|(id, wire)| (id, wire.connection))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_selected(&self, node: NodeId) -> bool {
+        self.selected.contains(&node)
+    }
+
+    pub fn selected_nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.order
+            .iter()
+            .copied()
+            .filter(|node| self.selected.contains(node))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn selected_connection(&self) -> Option<ConnectionId> {
+        self.selected_connection
+    }
+
+    // In graph coordinates.
+    #[must_use]
+    pub fn node_rect(&self, node: NodeId) -> Option<[f32; 4]> {
+        self.nodes.get(node).map(|node| node.rect)
+    }
+
+    // On screen, for the caller to draw the node's title and contents.
+    #[must_use]
+    pub fn node_bounds(&self, node: NodeId) -> Option<Bounds> {
+        let rect = self.nodes.get(node)?.rect;
+        Some(self.screen_bounds(self.camera.rect_to_screen(rect)))
+    }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<GraphEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // `rect` is in graph coordinates.
+    pub fn add_node(
+        &mut self,
+        rect: [f32; 4],
+        inputs: usize,
+        outputs: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> NodeId {
+        let block = Block::<Positioned>::new(
+            self.screen_bounds(self.camera.rect_to_screen(rect)),
+            self.node_style(false, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut node =
+            Node { rect, inputs, outputs, block, ports: Vec::new() };
+        node.ports = self.build_ports(&node, view_projection, dpr, renderer);
+        let id = self.nodes.insert(node);
+        self.order.push(id);
+        self.events.push(GraphEvent::NodeAdded(id));
+
+        id
+    }
+
+    // Also removes the node's connections.
+    pub fn remove_node(&mut self, node: NodeId, renderer: &mut Renderer) {
+        let Some(removed) = self.nodes.remove(node) else { return };
+        removed.block.destroy(renderer);
+        for port in &removed.ports {
+            port.destroy(renderer);
+        }
+        self.order.retain(|id| *id != node);
+        if self.selected.remove(&node) {
+            self.events.push(GraphEvent::SelectionChanged);
+        }
+
+        let attached: Vec<ConnectionId> = self
+            .wires
+            .iter()
+            .filter(|(_, wire)| {
+                wire.connection.from.node == node
+                    || wire.connection.to.node == node
+            })
+            .map(|(id, _)| id)
+            .collect();
+        for id in attached {
+            self.disconnect(id, renderer);
+        }
+        self.events.push(GraphEvent::NodeRemoved(node));
+    }
+
+    // Connects an output to an input, replacing what the input was
+    // connected to. Returns `None` for ports that don't exist or would
+    // connect a node to itself.
+    pub fn connect(
+        &mut self,
+        from: PortRef,
+        to: PortRef,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<ConnectionId> {
+        let is_valid = from.kind == PortKind::Output
+            && to.kind == PortKind::Input
+            && from.node != to.node
+            && self.port_position(from).is_some()
+            && self.port_position(to).is_some();
+        if !is_valid {
+            return None;
+        }
+        if let Some(existing) = self.connection_to(to) {
+            self.disconnect(existing, renderer);
+        }
+
+        let connection = Connection { from, to };
+        let bars =
+            self.build_wire(&connection, view_projection, dpr, renderer);
+        let id = self.wires.insert(Wire { connection, bars });
+        self.events.push(GraphEvent::Connected(id, connection));
+
+        Some(id)
+    }
+
+    pub fn disconnect(&mut self, id: ConnectionId, renderer: &mut Renderer) {
+        let Some(wire) = self.wires.remove(id) else { return };
+        for rectangle_id in wire.bars {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        if self.selected_connection == Some(id) {
+            self.selected_connection = None;
+            self.events.push(GraphEvent::SelectionChanged);
+        }
+        self.events.push(GraphEvent::Disconnected(wire.connection));
+    }
+
+    // Removes the selected nodes and the selected connection, as on
+    // Delete.
+    pub fn remove_selected(&mut self, renderer: &mut Renderer) {
+        if let Some(id) = self.selected_connection {
+            self.disconnect(id, renderer);
+        }
+        let selected: Vec<NodeId> = self.selected_nodes().collect();
+        for node in selected {
+            self.remove_node(node, renderer);
+        }
+    }
+
+    #[must_use]
+    pub fn node_at(&self, position: [f32; 2]) -> Option<NodeId> {
+        let point = self.camera.to_graph(position);
+        self.order
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| contains_point(self.nodes[*id].rect, point))
+    }
+
+    #[must_use]
+    pub fn port_at(&self, position: [f32; 2]) -> Option<PortRef> {
+        let point = self.camera.to_graph(position);
+        let radius = self.style.port_radius
+            + self.style.hit_tolerance / self.camera.zoom();
+        self.order.iter().rev().find_map(|id| {
+            let node = &self.nodes[*id];
+            let ports = (0..node.inputs)
+                .map(|index| (PortKind::Input, index))
+                .chain(
+                    (0..node.outputs).map(|index| (PortKind::Output, index)),
+                );
+            ports
+                .map(|(kind, index)| PortRef { node: *id, kind, index })
+                .find(|port| {
+                    self.port_position(*port).is_some_and(|position| {
+                        (position[0] - point[0]).hypot(position[1] - point[1])
+                            <= radius
+                    })
+                })
+        })
+    }
+
+    #[must_use]
+    pub fn connection_at(&self, position: [f32; 2]) -> Option<ConnectionId> {
+        let point = self.camera.to_graph(position);
+        let tolerance = self.style.wire_thickness / 2.0
+            + self.style.hit_tolerance / self.camera.zoom();
+        self.wires
+            .iter()
+            .map(|(id, wire)| {
+                let points = self.connection_points(&wire.connection);
+                (id, distance_to_polyline(&points, point))
+            })
+            .filter(|(_, distance)| *distance <= tolerance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    // Returns whether the press landed on the graph. `is_extending`
+    // (shift or ctrl) adds to the selection instead of replacing it.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        is_extending: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if !contains(&self.bounds, position) {
+            return false;
+        }
+        self.cancel_gesture(renderer);
+
+        if let Some(port) = self.port_at(position) {
+            let from = match port.kind {
+                PortKind::Output => Some(port),
+                // Dragging off a connected input picks its wire up.
+                PortKind::Input => self.connection_to(port).map(|id| {
+                    let from = self.wires[id].connection.from;
+                    self.disconnect(id, renderer);
+                    from
+                }),
+            };
+            if let Some(from) = from {
+                self.gesture =
+                    Some(Gesture::Connect { from, preview: Vec::new() });
+                return true;
+            }
+        }
+
+        if let Some(node) = self.node_at(position) {
+            if !self.selected.contains(&node) {
+                let mut selected = match is_extending {
+                    true => self.selected.clone(),
+                    false => HashSet::new(),
+                };
+                selected.insert(node);
+                self.set_selection(selected, None, dpr, renderer);
+            }
+            let last = self.camera.to_graph(position);
+            self.gesture = Some(Gesture::MoveNodes { last, is_moved: false });
+            return true;
+        }
+
+        if let Some(id) = self.connection_at(position) {
+            self.set_selection(HashSet::new(), Some(id), dpr, renderer);
+            return true;
+        }
+
+        if !is_extending {
+            self.set_selection(HashSet::new(), None, dpr, renderer);
+        }
+        let block = Block::<Positioned>::new(
+            self.screen_bounds([position[0], position[1], 0.0, 0.0]),
+            marquee_style(&self.style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.gesture = Some(Gesture::Marquee { start: position, block });
+
+        true
+    }
+
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let point = self.camera.to_graph(position);
+        match self.gesture.take() {
+            Some(Gesture::MoveNodes { last, .. }) => {
+                let delta = [point[0] - last[0], point[1] - last[1]];
+                let selected: Vec<NodeId> = self.selected_nodes().collect();
+                for node in &selected {
+                    if let Some(node) = self.nodes.get_mut(*node) {
+                        node.rect[0] += delta[0];
+                        node.rect[1] += delta[1];
+                    }
+                }
+                self.refresh_nodes(&selected, view_projection, dpr, renderer);
+                self.gesture = Some(Gesture::MoveNodes {
+                    last:     point,
+                    is_moved: true,
+                });
+            }
+            Some(Gesture::Connect { from, preview }) => {
+                for rectangle_id in preview {
+                    renderer.remove_rectangle(rectangle_id);
+                }
+                let preview = match self.port_position(from) {
+                    Some(start) => {
+                        let points = wire_points(
+                            start,
+                            point,
+                            self.style.wire_segments,
+                        );
+                        self.build_bars(
+                            &points,
+                            view_projection,
+                            dpr,
+                            renderer,
+                        )
+                    }
+                    None => Vec::new(),
+                };
+                self.gesture = Some(Gesture::Connect { from, preview });
+            }
+            Some(Gesture::Marquee { start, mut block }) => {
+                let bounds =
+                    self.screen_bounds(normalize_rect(start, position));
+                block.update_clip_rect(&bounds.clip_rect, renderer);
+                block.update_position(
+                    bounds.position,
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+                block.update_size(bounds.size, view_projection, dpr, renderer);
+                self.gesture = Some(Gesture::Marquee { start, block });
+            }
+            None => {}
+        }
+    }
+
+    pub fn release(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        match self.gesture.take() {
+            Some(Gesture::MoveNodes { is_moved: true, .. }) => {
+                let moved: Vec<NodeId> = self.selected_nodes().collect();
+                self.events
+                    .extend(moved.into_iter().map(GraphEvent::NodeMoved));
+            }
+            Some(Gesture::Connect { from, preview }) => {
+                for rectangle_id in preview {
+                    renderer.remove_rectangle(rectangle_id);
+                }
+                if let Some(to) = self.port_at(position) {
+                    self.connect(from, to, view_projection, dpr, renderer);
+                }
+            }
+            Some(Gesture::Marquee { start, block }) => {
+                block.destroy(renderer);
+                let [x, y, width, height] = normalize_rect(start, position);
+                let first = self.camera.to_graph([x, y]);
+                let last = self.camera.to_graph([x + width, y + height]);
+                let marquee = normalize_rect(first, last);
+                let mut selected = self.selected.clone();
+                selected.extend(
+                    self.order.iter().copied().filter(|id| {
+                        intersects(self.nodes[*id].rect, marquee)
+                    }),
+                );
+                self.set_selection(selected, None, dpr, renderer);
+            }
+            Some(Gesture::MoveNodes { .. }) | None => {}
+        }
+    }
+
+    pub fn pan(
+        &mut self,
+        delta: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.camera.pan(delta);
+        self.refresh_all(view_projection, dpr, renderer);
+    }
+
+    // Zooms by `factor` around `anchor`, e.g. the cursor on a wheel event.
+    pub fn zoom_at(
+        &mut self,
+        anchor: [f32; 2],
+        factor: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.camera.zoom_at(anchor, factor);
+        self.refresh_all(view_projection, dpr, renderer);
+    }
+
+    pub fn set_camera(
+        &mut self,
+        camera: GraphCamera,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.camera = camera;
+        self.refresh_all(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.refresh_all(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: NodeGraphStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.refresh_all(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for node in self.nodes.values() {
+            node.block.destroy(renderer);
+            for port in &node.ports {
+                port.destroy(renderer);
+            }
+        }
+        for wire in self.wires.values() {
+            for rectangle_id in &wire.bars {
+                renderer.remove_rectangle(*rectangle_id);
+            }
+        }
+        match &self.gesture {
+            Some(Gesture::Connect { preview, .. }) => {
+                for rectangle_id in preview {
+                    renderer.remove_rectangle(*rectangle_id);
+                }
+            }
+            Some(Gesture::Marquee { block, .. }) => block.destroy(renderer),
+            Some(Gesture::MoveNodes { .. }) | None => {}
+        }
+    }
+
+    fn cancel_gesture(&mut self, renderer: &mut Renderer) {
+        match self.gesture.take() {
+            Some(Gesture::Connect { preview, .. }) => {
+                for rectangle_id in preview {
+                    renderer.remove_rectangle(rectangle_id);
+                }
+            }
+            Some(Gesture::Marquee { block, .. }) => block.destroy(renderer),
+            Some(Gesture::MoveNodes { .. }) | None => {}
+        }
+    }
+
+    fn set_selection(
+        &mut self,
+        selected: HashSet<NodeId>,
+        selected_connection: Option<ConnectionId>,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if selected == self.selected
+            && selected_connection == self.selected_connection
+        {
+            return;
+        }
+        let changed: Vec<NodeId> = self
+            .selected
+            .symmetric_difference(&selected)
+            .copied()
+            .collect();
+        self.selected = selected;
+        for id in changed {
+            let style = self.node_style(self.selected.contains(&id), dpr);
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.block.update_style(style, renderer);
+            }
+        }
+
+        let previous_connection = self.selected_connection;
+        self.selected_connection = selected_connection;
+        for id in [previous_connection, selected_connection]
+            .into_iter()
+            .flatten()
+        {
+            let color = self.wire_color(id);
+            if let Some(wire) = self.wires.get(id) {
+                for rectangle_id in &wire.bars {
+                    if let Some(rectangle) =
+                        renderer.get_mut_rectangle(*rectangle_id)
+                    {
+                        rectangle.fill_color = color;
+                    }
+                }
+            }
+        }
+        self.events.push(GraphEvent::SelectionChanged);
+    }
+
+    fn connection_to(&self, port: PortRef) -> Option<ConnectionId> {
+        self.wires
+            .iter()
+            .find(|(_, wire)| wire.connection.to == port)
+            .map(|(id, _)| id)
+    }
+
+    // In graph coordinates.
+    fn port_position(&self, port: PortRef) -> Option<[f32; 2]> {
+        let node = self.nodes.get(port.node)?;
+        port_position(
+            node.rect,
+            node.inputs,
+            node.outputs,
+            port.kind,
+            port.index,
+        )
+    }
+
+    fn connection_points(&self, connection: &Connection) -> Vec<[f32; 2]> {
+        match (
+            self.port_position(connection.from),
+            self.port_position(connection.to),
+        ) {
+            (Some(start), Some(end)) => {
+                wire_points(start, end, self.style.wire_segments)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn screen_bounds(&self, rect: [f32; 4]) -> Bounds {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        Bounds {
+            position:  [rect[0], rect[1]],
+            size:      [rect[2], rect[3]],
+            clip_rect: intersect_rects(
+                self.bounds.clip_rect,
+                [x, y, width, height],
+            ),
+        }
+    }
+
+    fn node_style(&self, is_selected: bool, dpr: f32) -> BlockStyle {
+        let style = &self.style;
+        let zoom = self.camera.zoom();
+        let border_color = match is_selected {
+            true => style.selection_color,
+            false => style.node_border_color,
+        };
+        BlockStyle::builder()
+            .fill_color(style.node_color)
+            .border_color(border_color)
+            .border_size(style.node_border_size * dpr)
+            .corner_radii([style.corner_radius * zoom * dpr; 4])
+            .build()
+    }
+
+    fn wire_color(&self, id: ConnectionId) -> [f32; 4] {
+        match self.selected_connection == Some(id) {
+            true => self.style.selection_color,
+            false => self.style.wire_color,
+        }
+    }
+
+    fn build_ports(
+        &self,
+        node: &Node,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<Block<Positioned>> {
+        let radius = self.style.port_radius * self.camera.zoom();
+        let style = BlockStyle::builder()
+            .fill_color(self.style.port_color)
+            .corner_radii([radius * dpr; 4])
+            .build();
+        let ports = (0..node.inputs)
+            .map(|index| (PortKind::Input, index))
+            .chain((0..node.outputs).map(|index| (PortKind::Output, index)));
+
+        ports
+            .filter_map(|(kind, index)| {
+                port_position(
+                    node.rect,
+                    node.inputs,
+                    node.outputs,
+                    kind,
+                    index,
+                )
+            })
+            .map(|center| {
+                let [x, y] = self.camera.to_screen(center);
+                let rect =
+                    [x - radius, y - radius, radius * 2.0, radius * 2.0];
+                Block::<Positioned>::new(
+                    self.screen_bounds(rect),
+                    style.clone(),
+                    view_projection,
+                    dpr,
+                    renderer,
+                )
+            })
+            .collect()
+    }
+
+    fn build_wire(
+        &self,
+        connection: &Connection,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<RectangleId> {
+        let points = self.connection_points(connection);
+        self.build_bars(&points, view_projection, dpr, renderer)
+    }
+
+    // A polyline in graph coordinates as one bar per segment.
+    fn build_bars(
+        &self,
+        points: &[[f32; 2]],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<RectangleId> {
+        let thickness = self.style.wire_thickness * self.camera.zoom();
+        let clip_rect = self.screen_bounds([0.0; 4]).clip_rect;
+        points
+            .windows(2)
+            .map(|segment| {
+                let bar = Bar::between(
+                    self.camera.to_screen(segment[0]),
+                    self.camera.to_screen(segment[1]),
+                    thickness,
+                );
+                let rectangle = bar.build_rectangle(
+                    self.style.wire_color,
+                    clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                renderer.add_rectangle(&rectangle)
+            })
+            .collect()
+    }
+
+    // Rebuilds the blocks of the given nodes and the wires attached to
+    // them.
+    fn refresh_nodes(
+        &mut self,
+        ids: &[NodeId],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for id in ids {
+            let Some(node) = self.nodes.get(*id) else { continue };
+            let bounds =
+                self.screen_bounds(self.camera.rect_to_screen(node.rect));
+            let style = self.node_style(self.selected.contains(id), dpr);
+            let ports = self.build_ports(node, view_projection, dpr, renderer);
+
+            let node = &mut self.nodes[*id];
+            node.block.update_clip_rect(&bounds.clip_rect, renderer);
+            node.block.update_position(
+                bounds.position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            node.block.update_size(
+                bounds.size,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            node.block.update_style(style, renderer);
+            for port in std::mem::replace(&mut node.ports, ports) {
+                port.destroy(renderer);
+            }
+        }
+
+        let attached: Vec<ConnectionId> = self
+            .wires
+            .iter()
+            .filter(|(_, wire)| {
+                ids.contains(&wire.connection.from.node)
+                    || ids.contains(&wire.connection.to.node)
+            })
+            .map(|(id, _)| id)
+            .collect();
+        for id in attached {
+            let connection = self.wires[id].connection;
+            let bars =
+                self.build_wire(&connection, view_projection, dpr, renderer);
+            let color = self.wire_color(id);
+            for rectangle_id in &bars {
+                if let Some(rectangle) =
+                    renderer.get_mut_rectangle(*rectangle_id)
+                {
+                    rectangle.fill_color = color;
+                }
+            }
+            for rectangle_id in
+                std::mem::replace(&mut self.wires[id].bars, bars)
+            {
+                renderer.remove_rectangle(rectangle_id);
+            }
+        }
+    }
+
+    fn refresh_all(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let ids = self.order.clone();
+        self.refresh_nodes(&ids, view_projection, dpr, renderer);
+    }
+}
+
+fn marquee_style(style: &NodeGraphStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.marquee_color)
+        .border_color(style.marquee_border_color)
+        .border_size(dpr)
+        .build()
+}
+
+// Ports are spread evenly along the node's left (inputs) and right
+// (outputs) edges.
+fn port_position(
+    rect: [f32; 4],
+    inputs: usize,
+    outputs: usize,
+    kind: PortKind,
+    index: usize,
+) -> Option<[f32; 2]> {
+    let [x, y, width, height] = rect;
+    let (count, x) = match kind {
+        PortKind::Input => (inputs, x),
+        PortKind::Output => (outputs, x + width),
+    };
+    (index < count)
+        .then(|| [x, y + height * (index + 1) as f32 / (count + 1) as f32])
+}
+
+// The rect spanned by two corners, as x, y, width, height.
+fn normalize_rect(a: [f32; 2], b: [f32; 2]) -> [f32; 4] {
+    let x = a[0].min(b[0]);
+    let y = a[1].min(b[1]);
+    [x, y, (a[0] - b[0]).abs(), (a[1] - b[1]).abs()]
+}
+
+fn intersects(a: [f32; 4], b: [f32; 4]) -> bool {
+    a[0] <= b[0] + b[2]
+        && b[0] <= a[0] + a[2]
+        && a[1] <= b[1] + b[3]
+        && b[1] <= a[1] + a[3]
+}
+
+const fn contains_point(rect: [f32; 4], point: [f32; 2]) -> bool {
+    let [x, y, width, height] = rect;
+
+    point[0] >= x
+        && point[0] <= x + width
+        && point[1] >= y
+        && point[1] <= y + height
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    contains_point([x, y, width, height], position)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PortKind, intersects, normalize_rect, port_position};
+
+    #[rstest]
+    #[case(PortKind::Input, 0, Some([10.0, 40.0]))]
+    #[case(PortKind::Input, 1, Some([10.0, 60.0]))]
+    #[case(PortKind::Output, 0, Some([110.0, 50.0]))]
+    #[case(PortKind::Output, 1, None)]
+    fn test_port_position(
+        #[case] kind: PortKind,
+        #[case] index: usize,
+        #[case] expected: Option<[f32; 2]>,
+    ) {
+        let rect = [10.0, 20.0, 100.0, 60.0];
+        assert_eq!(port_position(rect, 2, 1, kind, index), expected);
+    }
+
+    #[rstest]
+    #[case([30.0, 40.0], [10.0, 50.0], [10.0, 40.0, 20.0, 10.0])]
+    #[case([10.0, 10.0], [20.0, 20.0], [10.0, 10.0, 10.0, 10.0])]
+    fn test_normalize_rect(
+        #[case] a: [f32; 2],
+        #[case] b: [f32; 2],
+        #[case] expected: [f32; 4],
+    ) {
+        assert_eq!(normalize_rect(a, b), expected);
+    }
+
+    #[rstest]
+    #[case([0.0, 0.0, 10.0, 10.0], [5.0, 5.0, 10.0, 10.0], true)]
+    #[case([0.0, 0.0, 10.0, 10.0], [11.0, 0.0, 10.0, 10.0], false)]
+    #[case([0.0, 0.0, 10.0, 10.0], [2.0, 2.0, 2.0, 2.0], true)]
+    fn test_intersects(
+        #[case] a: [f32; 4],
+        #[case] b: [f32; 4],
+        #[case] expected: bool,
+    ) {
+        assert_eq!(intersects(a, b), expected);
+    }
+}
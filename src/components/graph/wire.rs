@@ -0,0 +1,94 @@
+// How far the control points reach out of the ports, at least.
+const MIN_TANGENT: f32 = 40.0;
+
+// Points along a cubic bezier from an output port to an input port. The
+// curve leaves and enters the ports horizontally, as wires do in node
+// editors.
+#[must_use]
+pub(crate) fn wire_points(
+    start: [f32; 2],
+    end: [f32; 2],
+    segments: usize,
+) -> Vec<[f32; 2]> {
+    let tangent = ((end[0] - start[0]).abs() / 2.0).max(MIN_TANGENT);
+    let control_1 = [start[0] + tangent, start[1]];
+    let control_2 = [end[0] - tangent, end[1]];
+    let segments = segments.max(1);
+
+    (0..=segments)
+        .map(|step| {
+            let t = step as f32 / segments as f32;
+            let u = 1.0 - t;
+            let weights =
+                [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+            let points = [start, control_1, control_2, end];
+            std::array::from_fn(|axis| {
+                weights
+                    .iter()
+                    .zip(points)
+                    .map(|(weight, point)| weight * point[axis])
+                    .sum()
+            })
+        })
+        .collect()
+}
+
+#[must_use]
+pub(crate) fn distance_to_polyline(
+    points: &[[f32; 2]],
+    point: [f32; 2],
+) -> f32 {
+    points
+        .windows(2)
+        .map(|segment| distance_to_segment(segment[0], segment[1], point))
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn distance_to_segment(
+    start: [f32; 2],
+    end: [f32; 2],
+    point: [f32; 2],
+) -> f32 {
+    let delta = [end[0] - start[0], end[1] - start[1]];
+    let length_squared = delta[0] * delta[0] + delta[1] * delta[1];
+    let t = match length_squared > 0.0 {
+        true => {
+            let projection = (point[0] - start[0]) * delta[0]
+                + (point[1] - start[1]) * delta[1];
+            (projection / length_squared).clamp(0.0, 1.0)
+        }
+        false => 0.0,
+    };
+    let closest = [start[0] + delta[0] * t, start[1] + delta[1] * t];
+
+    (point[0] - closest[0]).hypot(point[1] - closest[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{distance_to_polyline, wire_points};
+
+    #[rstest]
+    fn test_wire_points() {
+        let points = wire_points([0.0, 0.0], [200.0, 100.0], 4);
+
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], [0.0, 0.0]);
+        assert_eq!(points[2], [100.0, 50.0]);
+        assert_eq!(points[4], [200.0, 100.0]);
+    }
+
+    #[rstest]
+    #[case([5.0, 3.0], 3.0)]
+    #[case([-4.0, 3.0], 5.0)]
+    #[case([12.0, 6.0], 2.0)]
+    fn test_distance_to_polyline(
+        #[case] point: [f32; 2],
+        #[case] expected: f32,
+    ) {
+        let points = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]];
+        assert_eq!(distance_to_polyline(&points, point), expected);
+    }
+}
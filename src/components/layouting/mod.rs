@@ -1,5 +1,16 @@
 mod desired_size;
+mod page_breaks;
+mod popup_placement;
+mod snapping;
+mod sticky_headers;
 mod vertical_layout;
 
 pub use desired_size::DesiredSize;
+pub use page_breaks::PageBreaks;
+pub use popup_placement::{
+    Placement, PlacementOptions, PopupAlignment, PopupArrow, PopupPositioner,
+    PopupSide, place_popup,
+};
+pub use snapping::{GuideAxis, SnapGuide, SnapManager, SnapSettings};
+pub use sticky_headers::{StickyHeaders, StickySection};
 pub use vertical_layout::{VerticalLayoutItem, fixed_vertical_layout};
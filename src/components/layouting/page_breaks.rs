@@ -0,0 +1,8 @@
+use crate::core::PageBreak;
+
+// Containers that know where their content shouldn't be split when it is
+// printed. Offsets are in physical pixels, like the exported scene, and
+// containers add their children's hints to their own.
+pub trait PageBreaks {
+    fn page_breaks(&self, dpr: f32) -> Vec<PageBreak>;
+}
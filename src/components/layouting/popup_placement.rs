@@ -0,0 +1,353 @@
+use bon::Builder;
+
+use crate::components::common::Bounds;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupSide {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl PopupSide {
+    #[must_use]
+    #[inline(always)]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Right => Self::Left,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+        }
+    }
+
+    // The axis the popup moves away from the anchor along.
+    #[inline(always)]
+    const fn main_axis(self) -> usize {
+        match self {
+            Self::Top | Self::Bottom => 1,
+            Self::Right | Self::Left => 0,
+        }
+    }
+}
+
+// How the popup lines up with the anchor across the side it is on: by
+// their left or top edges, centers, or right or bottom edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupAlignment {
+    Start,
+    Center,
+    End,
+}
+
+// Distances are in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct PlacementOptions {
+    #[builder(default = PopupSide::Bottom)]
+    side:          PopupSide,
+    #[builder(default = PopupAlignment::Start)]
+    alignment:     PopupAlignment,
+    // Between the anchor and the popup.
+    #[builder(default = 4.0)]
+    gap:           f32,
+    // Kept clear along the edges of the viewport.
+    #[builder(default = 8.0)]
+    padding:       f32,
+    // Moves the popup to the opposite side when it only fits there.
+    #[builder(default = true)]
+    flip:          bool,
+    // Slides the popup along the side it is on to keep it in the viewport.
+    #[builder(default = true)]
+    shift:         bool,
+    // Width of the arrow's base, and how close it may get to the popup's
+    // corners.
+    #[builder(default = 12.0)]
+    arrow_size:    f32,
+    #[builder(default = 8.0)]
+    arrow_padding: f32,
+}
+
+// Where the arrow pointing at the anchor goes: the middle of its base, on
+// the popup's edge that faces the anchor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopupArrow {
+    pub side:   PopupSide,
+    pub center: [f32; 2],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub bounds: Bounds,
+    // The side actually taken, which differs from the preferred one when
+    // the popup flipped.
+    pub side:   PopupSide,
+    pub arrow:  PopupArrow,
+}
+
+// Places a popup of the given size next to the anchor on the preferred
+// side, flipping and shifting it to keep it in the viewport. When it fits
+// on neither side it goes where there's more room.
+#[must_use]
+pub fn place_popup(
+    anchor: &Bounds,
+    popup_size: [f32; 2],
+    viewport: &Bounds,
+    options: &PlacementOptions,
+) -> Placement {
+    let side = choose_side(anchor, popup_size, viewport, options);
+    let main = side.main_axis();
+    let cross = 1 - main;
+
+    let mut position = [0.0; 2];
+    position[main] = match side {
+        PopupSide::Top | PopupSide::Left => {
+            anchor.position[main] - options.gap - popup_size[main]
+        }
+        PopupSide::Bottom | PopupSide::Right => {
+            anchor.position[main] + anchor.size[main] + options.gap
+        }
+    };
+    position[cross] = anchor.position[cross]
+        + match options.alignment {
+            PopupAlignment::Start => 0.0,
+            PopupAlignment::Center => {
+                (anchor.size[cross] - popup_size[cross]) / 2.0
+            }
+            PopupAlignment::End => anchor.size[cross] - popup_size[cross],
+        };
+    if options.shift {
+        let start = viewport.position[cross] + options.padding;
+        let end = viewport.position[cross] + viewport.size[cross]
+            - options.padding
+            - popup_size[cross];
+        // Popups wider than the viewport keep their start in view.
+        position[cross] = position[cross].min(end).max(start);
+    }
+
+    // The arrow points at the anchor's center, as far as the popup's
+    // corners allow.
+    let anchor_center = anchor.position[cross] + anchor.size[cross] / 2.0;
+    let inset = options.arrow_padding + options.arrow_size / 2.0;
+    let low = position[cross] + inset;
+    let high = position[cross] + popup_size[cross] - inset;
+    let mut center = [0.0; 2];
+    center[cross] = match low <= high {
+        true => anchor_center.clamp(low, high),
+        false => position[cross] + popup_size[cross] / 2.0,
+    };
+    center[main] = match side {
+        PopupSide::Top | PopupSide::Left => position[main] + popup_size[main],
+        PopupSide::Bottom | PopupSide::Right => position[main],
+    };
+
+    Placement {
+        bounds: Bounds::without_clip_rect(position, popup_size),
+        side,
+        arrow: PopupArrow { side: side.opposite(), center },
+    }
+}
+
+fn choose_side(
+    anchor: &Bounds,
+    popup_size: [f32; 2],
+    viewport: &Bounds,
+    options: &PlacementOptions,
+) -> PopupSide {
+    let preferred = options.side;
+    if !options.flip {
+        return preferred;
+    }
+    let needed = popup_size[preferred.main_axis()] + options.gap;
+    let space = |side| space_on(anchor, viewport, side) - options.padding;
+
+    let opposite = preferred.opposite();
+    let is_opposite_better = space(preferred) < needed
+        && (space(opposite) >= needed || space(opposite) > space(preferred));
+    match is_opposite_better {
+        true => opposite,
+        false => preferred,
+    }
+}
+
+// Room between the anchor and the viewport's edge on that side.
+fn space_on(anchor: &Bounds, viewport: &Bounds, side: PopupSide) -> f32 {
+    let axis = side.main_axis();
+    match side {
+        PopupSide::Top | PopupSide::Left => {
+            anchor.position[axis] - viewport.position[axis]
+        }
+        PopupSide::Bottom | PopupSide::Right => {
+            viewport.position[axis] + viewport.size[axis]
+                - anchor.position[axis]
+                - anchor.size[axis]
+        }
+    }
+}
+
+// Keeps a popup next to an anchor that moves, e.g. with scrolling, and
+// places it again whenever the anchor, the popup's size or the viewport
+// change.
+pub struct PopupPositioner {
+    options:    PlacementOptions,
+    anchor:     Bounds,
+    popup_size: [f32; 2],
+    viewport:   Bounds,
+    placement:  Placement,
+}
+
+impl PopupPositioner {
+    #[must_use]
+    pub fn new(
+        options: PlacementOptions,
+        anchor: Bounds,
+        popup_size: [f32; 2],
+        viewport: Bounds,
+    ) -> Self {
+        let placement = place_popup(&anchor, popup_size, &viewport, &options);
+        Self { options, anchor, popup_size, viewport, placement }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn options(&self) -> &PlacementOptions { &self.options }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn anchor(&self) -> &Bounds { &self.anchor }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn placement(&self) -> &Placement { &self.placement }
+
+    // These return whether the popup moved.
+    pub fn update_anchor(&mut self, anchor: Bounds) -> bool {
+        self.anchor = anchor;
+        self.replace()
+    }
+
+    pub fn update_popup_size(&mut self, popup_size: [f32; 2]) -> bool {
+        self.popup_size = popup_size;
+        self.replace()
+    }
+
+    pub fn update_viewport(&mut self, viewport: Bounds) -> bool {
+        self.viewport = viewport;
+        self.replace()
+    }
+
+    pub fn update_options(&mut self, options: PlacementOptions) -> bool {
+        self.options = options;
+        self.replace()
+    }
+
+    fn replace(&mut self) -> bool {
+        let placement = place_popup(
+            &self.anchor,
+            self.popup_size,
+            &self.viewport,
+            &self.options,
+        );
+        let is_moved = placement != self.placement;
+        self.placement = placement;
+        is_moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        PlacementOptions, PopupAlignment, PopupPositioner, PopupSide,
+        place_popup,
+    };
+    use crate::components::common::Bounds;
+
+    fn viewport() -> Bounds {
+        Bounds::without_clip_rect([0.0, 0.0], [400.0, 300.0])
+    }
+
+    #[rstest]
+    #[case(PopupSide::Bottom, [100.0, 100.0], PopupSide::Bottom, [100.0, 124.0])]
+    // no room below
+    #[case(PopupSide::Bottom, [100.0, 250.0], PopupSide::Top, [100.0, 166.0])]
+    #[case(PopupSide::Right, [100.0, 100.0], PopupSide::Right, [184.0, 100.0])]
+    // no room on the right
+    #[case(PopupSide::Right, [300.0, 100.0], PopupSide::Left, [216.0, 100.0])]
+    // no room above
+    #[case(PopupSide::Top, [100.0, 40.0], PopupSide::Bottom, [100.0, 64.0])]
+    fn test_flips_to_fit(
+        #[case] side: PopupSide,
+        #[case] anchor_position: [f32; 2],
+        #[case] expected_side: PopupSide,
+        #[case] expected_position: [f32; 2],
+    ) {
+        let anchor = Bounds::without_clip_rect(anchor_position, [80.0, 20.0]);
+        let options = PlacementOptions::builder().side(side).build();
+        let placement =
+            place_popup(&anchor, [80.0, 80.0], &viewport(), &options);
+
+        assert_eq!(placement.side, expected_side);
+        assert_eq!(placement.bounds.position, expected_position);
+    }
+
+    #[rstest]
+    #[case(PopupAlignment::Start, [100.0, 10.0], 100.0)]
+    #[case(PopupAlignment::Center, [100.0, 10.0], 80.0)]
+    #[case(PopupAlignment::End, [100.0, 10.0], 60.0)]
+    // shifted back into the viewport past its padding
+    #[case(PopupAlignment::Center, [350.0, 10.0], 272.0)]
+    #[case(PopupAlignment::End, [0.0, 10.0], 8.0)]
+    fn test_aligns_and_shifts(
+        #[case] alignment: PopupAlignment,
+        #[case] anchor_position: [f32; 2],
+        #[case] expected_x: f32,
+    ) {
+        let anchor = Bounds::without_clip_rect(anchor_position, [80.0, 20.0]);
+        let options = PlacementOptions::builder().alignment(alignment).build();
+        let placement =
+            place_popup(&anchor, [120.0, 50.0], &viewport(), &options);
+
+        assert_eq!(placement.bounds.position[0], expected_x);
+    }
+
+    #[rstest]
+    // under the anchor's center
+    #[case([100.0, 10.0], [140.0, 34.0])]
+    // kept off the popup's corner
+    #[case([360.0, 10.0], [386.0, 34.0])]
+    fn test_arrow_points_at_anchor(
+        #[case] anchor_position: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let anchor = Bounds::without_clip_rect(anchor_position, [80.0, 20.0]);
+        let options = PlacementOptions::builder()
+            .padding(0.0)
+            .arrow_size(12.0)
+            .arrow_padding(8.0)
+            .build();
+        let placement =
+            place_popup(&anchor, [120.0, 50.0], &viewport(), &options);
+
+        assert_eq!(placement.arrow.side, PopupSide::Top);
+        assert_eq!(placement.arrow.center, expected);
+    }
+
+    #[rstest]
+    fn test_positioner_follows_anchor() {
+        let anchor = Bounds::without_clip_rect([100.0, 100.0], [80.0, 20.0]);
+        let mut positioner = PopupPositioner::new(
+            PlacementOptions::builder().build(),
+            anchor.clone(),
+            [80.0, 80.0],
+            viewport(),
+        );
+        assert!(!positioner.update_anchor(anchor));
+
+        let scrolled = Bounds::without_clip_rect([100.0, 250.0], [80.0, 20.0]);
+        assert!(positioner.update_anchor(scrolled));
+        assert_eq!(positioner.placement().side, PopupSide::Top);
+    }
+}
@@ -0,0 +1,312 @@
+use bon::Builder;
+#[cfg(any(feature = "software", feature = "vello"))]
+use glam::Mat4;
+
+use crate::components::common::Bounds;
+#[cfg(any(feature = "software", feature = "vello"))]
+use crate::core::{PathCommand, PathId, Renderer, VectorPath};
+
+// Lines closer to each other than this are drawn as one guide.
+const ALIGNMENT_EPSILON: f32 = 0.01;
+
+// Distances are in logical pixels.
+#[derive(Debug, Clone, Builder)]
+#[builder(const)]
+pub struct SnapSettings {
+    // How close an edge has to get to a line before it snaps to it.
+    #[builder(default = 6.0)]
+    threshold:       f32,
+    #[builder(default = true)]
+    snap_to_edges:   bool,
+    // Centers snap to the centers and edges of siblings.
+    #[builder(default = true)]
+    snap_to_centers: bool,
+    // Spacing of the grid the top left corner snaps to; zero turns it
+    // off on that axis.
+    #[builder(default = [0.0, 0.0])]
+    grid_size:       [f32; 2],
+    #[cfg(any(feature = "software", feature = "vello"))]
+    #[builder(default = [0.93, 0.2, 0.55, 1.0])]
+    guide_color:     [f32; 4],
+    #[cfg(any(feature = "software", feature = "vello"))]
+    #[builder(default = 1.0)]
+    guide_width:     f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GuideAxis {
+    // A line across the x axis at `position`, from `start` to `end` in y.
+    Vertical,
+    Horizontal,
+}
+
+// A line the dragged widget is aligned to, spanning it and the siblings
+// on the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGuide {
+    pub axis:     GuideAxis,
+    pub position: f32,
+    pub start:    f32,
+    pub end:      f32,
+}
+
+// Snaps a dragged widget to the edges and centers of its siblings and to
+// a grid, and keeps the guides to show while it is aligned. Each axis
+// snaps on its own to the closest line within the threshold.
+pub struct SnapManager {
+    settings:    SnapSettings,
+    guides:      Vec<SnapGuide>,
+    #[cfg(any(feature = "software", feature = "vello"))]
+    guide_paths: Vec<PathId>,
+}
+
+impl SnapManager {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(settings: SnapSettings) -> Self {
+        Self {
+            settings,
+            guides: Vec::new(),
+            #[cfg(any(feature = "software", feature = "vello"))]
+            guide_paths: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn settings(&self) -> &SnapSettings { &self.settings }
+
+    #[inline(always)]
+    pub const fn set_settings(&mut self, settings: SnapSettings) {
+        self.settings = settings;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn guides(&self) -> &[SnapGuide] { &self.guides }
+
+    // Takes the dragged widget's bounds at the position the pointer puts
+    // it and returns the position to move it to instead.
+    pub fn snap(&mut self, bounds: &Bounds, siblings: &[Bounds]) -> [f32; 2] {
+        let position = [0, 1].map(|axis| {
+            bounds.position[axis] + self.snap_delta(bounds, siblings, axis)
+        });
+
+        let snapped = Bounds { position, ..bounds.clone() };
+        self.guides = [GuideAxis::Vertical, GuideAxis::Horizontal]
+            .into_iter()
+            .flat_map(|axis| self.aligned_guides(&snapped, siblings, axis))
+            .collect();
+        position
+    }
+
+    // Drops the guides once the drag is over; rendered guides go away
+    // with the next `render_guides`.
+    #[inline(always)]
+    pub fn end_drag(&mut self) { self.guides.clear(); }
+
+    fn snap_delta(
+        &self,
+        bounds: &Bounds,
+        siblings: &[Bounds],
+        axis: usize,
+    ) -> f32 {
+        let sources = self.lines(bounds, axis);
+        let mut best: Option<f32> = None;
+        let mut consider = |delta: f32| {
+            let is_closer = best.is_none_or(|best| delta.abs() < best.abs());
+            if delta.abs() <= self.settings.threshold && is_closer {
+                best = Some(delta);
+            }
+        };
+
+        for sibling in siblings {
+            for target in self.lines(sibling, axis) {
+                for source in &sources {
+                    consider(target - source);
+                }
+            }
+        }
+        let grid_size = self.settings.grid_size[axis];
+        if grid_size > 0.0 {
+            let start = bounds.position[axis];
+            consider((start / grid_size).round() * grid_size - start);
+        }
+        best.unwrap_or(0.0)
+    }
+
+    fn aligned_guides(
+        &self,
+        bounds: &Bounds,
+        siblings: &[Bounds],
+        axis: GuideAxis,
+    ) -> Vec<SnapGuide> {
+        let [along, across] = match axis {
+            GuideAxis::Vertical => [0, 1],
+            GuideAxis::Horizontal => [1, 0],
+        };
+        let sources = self.lines(bounds, along);
+
+        let mut guides: Vec<SnapGuide> = Vec::new();
+        for sibling in siblings {
+            for target in self.lines(sibling, along) {
+                if !sources
+                    .iter()
+                    .any(|source| (target - source).abs() < ALIGNMENT_EPSILON)
+                {
+                    continue;
+                }
+                let start =
+                    bounds.position[across].min(sibling.position[across]);
+                let end = (bounds.position[across] + bounds.size[across])
+                    .max(sibling.position[across] + sibling.size[across]);
+
+                // Siblings on the same line extend one guide.
+                match guides.iter_mut().find(|guide| {
+                    (guide.position - target).abs() < ALIGNMENT_EPSILON
+                }) {
+                    Some(guide) => {
+                        guide.start = guide.start.min(start);
+                        guide.end = guide.end.max(end);
+                    }
+                    None => guides.push(SnapGuide {
+                        axis,
+                        position: target,
+                        start,
+                        end,
+                    }),
+                }
+            }
+        }
+        guides
+    }
+
+    fn lines(&self, bounds: &Bounds, axis: usize) -> Vec<f32> {
+        let start = bounds.position[axis];
+        let size = bounds.size[axis];
+        let mut lines = Vec::with_capacity(3);
+        if self.settings.snap_to_edges {
+            lines.extend([start, start + size]);
+        }
+        if self.settings.snap_to_centers {
+            lines.push(start + size * 0.5);
+        }
+        lines
+    }
+}
+
+// Guides are drawn as paths, so they only show on the software and vello
+// backends.
+#[cfg(any(feature = "software", feature = "vello"))]
+impl SnapManager {
+    // Replaces the drawn guides with the current ones; call it after
+    // `snap` and `end_drag`. `clip_rect` is the editing area in logical
+    // pixels.
+    pub fn render_guides(
+        &mut self,
+        clip_rect: &[f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for path_id in self.guide_paths.drain(..) {
+            renderer.remove_path(path_id);
+        }
+        for guide in &self.guides {
+            let [from, to] = match guide.axis {
+                GuideAxis::Vertical => [
+                    [guide.position, guide.start],
+                    [guide.position, guide.end],
+                ],
+                GuideAxis::Horizontal => [
+                    [guide.start, guide.position],
+                    [guide.end, guide.position],
+                ],
+            };
+            let path = VectorPath::builder()
+                .mvp(view_projection.to_cols_array_2d())
+                .commands(vec![
+                    PathCommand::MoveTo(from.map(|value| value * dpr)),
+                    PathCommand::LineTo(to.map(|value| value * dpr)),
+                ])
+                .stroke_color(self.settings.guide_color)
+                .stroke_width(self.settings.guide_width * dpr)
+                .clip_rect(clip_rect.map(|value| value * dpr))
+                .build();
+            self.guide_paths.extend(renderer.add_path(&path));
+        }
+    }
+
+    pub fn destroy(&mut self, renderer: &mut Renderer) {
+        for path_id in self.guide_paths.drain(..) {
+            renderer.remove_path(path_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{GuideAxis, SnapGuide, SnapManager, SnapSettings};
+    use crate::components::common::Bounds;
+
+    fn siblings() -> Vec<Bounds> {
+        vec![
+            Bounds::without_clip_rect([100.0, 100.0], [50.0, 40.0]),
+            Bounds::without_clip_rect([100.0, 200.0], [80.0, 20.0]),
+        ]
+    }
+
+    #[rstest]
+    // the left edge onto the siblings' left edges
+    #[case([103.0, 300.0], [100.0, 300.0])]
+    // the right edge onto the first sibling's center
+    #[case([96.0, 300.0], [95.0, 300.0])]
+    // the bottom edge onto the first sibling's top edge
+    #[case([0.0, 85.0], [0.0, 80.0])]
+    // beyond the threshold
+    #[case([0.0, 300.0], [0.0, 300.0])]
+    fn test_snaps_to_siblings(
+        #[case] position: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let mut manager = SnapManager::new(SnapSettings::builder().build());
+        let bounds = Bounds::without_clip_rect(position, [30.0, 20.0]);
+        assert_eq!(manager.snap(&bounds, &siblings()), expected);
+    }
+
+    #[rstest]
+    #[case([13.0, 26.0], [12.0, 30.0])]
+    // siblings win when they are closer
+    #[case([102.0, 300.0], [100.0, 300.0])]
+    fn test_snaps_to_grid(
+        #[case] position: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let settings = SnapSettings::builder().grid_size([12.0, 15.0]).build();
+        let mut manager = SnapManager::new(settings);
+        let bounds = Bounds::without_clip_rect(position, [30.0, 20.0]);
+        assert_eq!(manager.snap(&bounds, &siblings()), expected);
+    }
+
+    #[rstest]
+    fn test_guides_span_aligned_siblings() {
+        let mut manager = SnapManager::new(SnapSettings::builder().build());
+        let bounds = Bounds::without_clip_rect([102.0, 300.0], [30.0, 20.0]);
+        manager.snap(&bounds, &siblings());
+
+        assert_eq!(
+            manager.guides(),
+            &[SnapGuide {
+                axis:     GuideAxis::Vertical,
+                position: 100.0,
+                start:    100.0,
+                end:      320.0,
+            }]
+        );
+
+        manager.end_drag();
+        assert!(manager.guides().is_empty());
+    }
+}
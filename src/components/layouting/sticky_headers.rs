@@ -0,0 +1,182 @@
+use glam::Mat4;
+
+use crate::{
+    components::{
+        common::{Bounds, intersect_rects},
+        widgets::{Block, block_states::Positioned},
+    },
+    core::Renderer,
+};
+
+// A section of scrolled content, in content coordinates: `top` and
+// `height` cover the header and the rows beneath it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StickySection {
+    pub top:           f32,
+    pub height:        f32,
+    pub header_height: f32,
+}
+
+// Keeps section headers pinned to the top of a scrolled viewport while
+// their section is in view. The next section's header pushes the pinned
+// one out as it arrives. Header blocks must be added after the rows so
+// they cover them.
+pub struct StickyHeaders {
+    sections:    Vec<StickySection>,
+    // Header tops in content coordinates as of the last update.
+    header_tops: Vec<f32>,
+}
+
+impl StickyHeaders {
+    #[must_use]
+    pub fn new(sections: Vec<StickySection>) -> Self {
+        let header_tops = sections.iter().map(|section| section.top).collect();
+        Self { sections, header_tops }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn sections(&self) -> &[StickySection] { &self.sections }
+
+    pub fn set_sections(&mut self, sections: Vec<StickySection>) {
+        self.header_tops =
+            sections.iter().map(|section| section.top).collect();
+        self.sections = sections;
+    }
+
+    // Whether the header is away from its natural place, e.g. to draw a
+    // shadow beneath it.
+    #[must_use]
+    pub fn is_pinned(&self, index: usize) -> bool {
+        let (Some(section), Some(top)) =
+            (self.sections.get(index), self.header_tops.get(index))
+        else {
+            return false;
+        };
+        *top > section.top
+    }
+
+    // Where the header is on screen, for the caller to draw its label.
+    #[must_use]
+    pub fn header_bounds(
+        &self,
+        index: usize,
+        viewport: &Bounds,
+        scroll_offset: f32,
+    ) -> Option<Bounds> {
+        let section = self.sections.get(index)?;
+        let top = *self.header_tops.get(index)?;
+        Some(header_bounds(section, top, viewport, scroll_offset))
+    }
+
+    // Moves the header blocks, one per section, for the new scroll
+    // offset. Only headers that moved are touched. Returns whether any
+    // did.
+    pub fn update(
+        &mut self,
+        headers: &mut [Block<Positioned>],
+        viewport: &Bounds,
+        scroll_offset: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let tops = sticky_header_tops(&self.sections, scroll_offset);
+        let mut is_moved = false;
+        for (index, (header, top)) in headers.iter_mut().zip(tops).enumerate()
+        {
+            let bounds = header_bounds(
+                &self.sections[index],
+                top,
+                viewport,
+                scroll_offset,
+            );
+            if header.position() != bounds.position {
+                header.update_position(
+                    bounds.position,
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+                is_moved = true;
+            }
+            if header.clip_rect() != bounds.clip_rect {
+                header.update_clip_rect(&bounds.clip_rect, renderer);
+            }
+            self.header_tops[index] = top;
+        }
+
+        is_moved
+    }
+}
+
+fn header_bounds(
+    section: &StickySection,
+    top: f32,
+    viewport: &Bounds,
+    scroll_offset: f32,
+) -> Bounds {
+    let [x, y] = viewport.position;
+    let [width, height] = viewport.size;
+    Bounds {
+        position:  [x, y + top - scroll_offset],
+        size:      [width, section.header_height],
+        clip_rect: intersect_rects(viewport.clip_rect, [x, y, width, height]),
+    }
+}
+
+// A header stays at its place until scrolled past, then sticks to the
+// top of the viewport until the end of its section reaches it.
+fn sticky_header_tops(
+    sections: &[StickySection],
+    scroll_offset: f32,
+) -> Vec<f32> {
+    sections
+        .iter()
+        .map(|section| {
+            let last_top =
+                section.top + section.height - section.header_height;
+            scroll_offset.min(last_top).max(section.top)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{StickySection, sticky_header_tops};
+
+    const SECTIONS: [StickySection; 3] = [
+        StickySection {
+            top:           0.0,
+            height:        100.0,
+            header_height: 20.0,
+        },
+        StickySection {
+            top:           100.0,
+            height:        50.0,
+            header_height: 20.0,
+        },
+        StickySection {
+            top:           150.0,
+            height:        200.0,
+            header_height: 20.0,
+        },
+    ];
+
+    #[rstest]
+    #[case(0.0, [0.0, 100.0, 150.0])]
+    #[case(50.0, [50.0, 100.0, 150.0])]
+    // The second header pushes the first one out.
+    #[case(90.0, [80.0, 100.0, 150.0])]
+    #[case(120.0, [80.0, 120.0, 150.0])]
+    #[case(140.0, [80.0, 130.0, 150.0])]
+    #[case(400.0, [80.0, 130.0, 330.0])]
+    fn test_sticky_header_tops(
+        #[case] scroll_offset: f32,
+        #[case] expected: [f32; 3],
+    ) {
+        assert_eq!(sticky_header_tops(&SECTIONS, scroll_offset), expected);
+    }
+}
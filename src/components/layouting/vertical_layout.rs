@@ -1,7 +1,7 @@
 use std::array;
 
 use super::DesiredSize;
-use crate::components::common::Bounds;
+use crate::{components::common::Bounds, instrument::span};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum VerticalLayoutItem {
@@ -24,6 +24,7 @@ pub fn fixed_vertical_layout<const N: usize>(
     size: [f32; 2],
     items: &[VerticalLayoutItem; N],
 ) -> [Option<Bounds>; N] {
+    span!("layout", items = N);
     let total_height = size[1];
     let mut used_height: f32 = 0.0;
     let mut resolved: [Option<f32>; N] = array::repeat(None);
@@ -107,17 +108,17 @@ fn expand_greedy<const N: usize>(
 
     let space_for_spacers = (remaining_height - widgets_total_height).max(0.0);
     for (i, item) in items.iter().enumerate() {
-        if let DesiredSize::Greedy { min_value, weight } = item.height() {
-            if let VerticalLayoutItem::Spacer(_) = item {
-                let height = if spacer_weight > 0 {
-                    min_value
-                        + space_for_spacers
-                            * (*weight as f32 / spacer_weight as f32)
-                } else {
-                    *min_value
-                };
-                resolved[i] = Some(height);
-            }
+        if let DesiredSize::Greedy { min_value, weight } = item.height()
+            && let VerticalLayoutItem::Spacer(_) = item
+        {
+            let height = if spacer_weight > 0 {
+                min_value
+                    + space_for_spacers
+                        * (*weight as f32 / spacer_weight as f32)
+            } else {
+                *min_value
+            };
+            resolved[i] = Some(height);
         }
     }
 }
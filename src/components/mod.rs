@@ -1,7 +1,94 @@
+mod animations;
+mod bind;
 mod common;
+mod debug;
+mod decoration;
+mod graph;
 mod layouting;
+mod scene;
+mod style;
+mod text;
+mod timing;
 mod widgets;
 
-pub use common::{Bounds, InputState, MouseButtonState};
-pub use layouting::{DesiredSize, VerticalLayoutItem, fixed_vertical_layout};
-pub use widgets::{Block, BlockStyle, block_states};
+pub use animations::{
+    AnimationId, AnimationTarget, Animator, Easing, FinishedAnimation,
+};
+pub use bind::Binding;
+pub use common::{
+    AlphaMask, Announcement, AnnouncementSink, Announcer, AutoScroll,
+    AutoScrollSettings, Bounds, Change, Command, DecodedFrame, DecodedImage,
+    FocusState, FramePlayback, HitShape, HoverIntent, HoverIntentEvent,
+    HoverIntentSettings, ImageDecoder, ImageFrame, ImageLoadState,
+    ImageLoader, InputLayer, InputPhase, InputRouter, InputState, Modifiers,
+    MouseButtonState, PageSource, PagedList, PagedRow, Politeness,
+    SceneFilter, SelectionEvent, SelectionMode, SelectionModel, Shortcut,
+    ShortcutPlatform, SpatialHash, SpatialIndex, TagMap, UndoStack, WidgetId,
+    rgba8, to_rgba8,
+};
+// For the stress scenes in `testing`.
+#[cfg(any(test, feature = "bench"))]
+pub(crate) use common::{build_model, intersect_rects};
+#[cfg(feature = "renderdoc")]
+pub use debug::GpuCapture;
+pub use debug::{
+    ContrastViolation, DumpChange, DumpChangeKind, DumpNode, DumpRecorder,
+    Inspect, check_contrast, contrast_ratio, diff_dumps, relative_luminance,
+};
+pub use decoration::{
+    DecorationAction, DecorationStyle, Decorations, DoubleClick,
+    resize_direction_at,
+};
+pub use graph::{
+    Connection, ConnectionId, GraphCamera, GraphEvent, NodeGraph,
+    NodeGraphStyle, NodeId, PortKind, PortRef,
+};
+pub use layouting::{
+    DesiredSize, GuideAxis, PageBreaks, Placement, PlacementOptions,
+    PopupAlignment, PopupArrow, PopupPositioner, PopupSide, SnapGuide,
+    SnapManager, SnapSettings, StickyHeaders, StickySection,
+    VerticalLayoutItem, fixed_vertical_layout, place_popup,
+};
+pub use scene::{Scene, SceneNodeId};
+#[cfg(feature = "hot-reload")]
+pub use style::ThemeWatcher;
+pub use style::{
+    BlockPreset, InheritedStyle, InteractionState, PseudoState, StateStyles,
+    StyleNodeId, StyleOverrides, StyleTree, Theme, ThemeBindings, ThemeError,
+    ThemeValue,
+};
+pub use text::{
+    AtlasGlyph, AtlasStats, FuzzyMatch, GlyphAtlas, ShapingService,
+    TextEditEvent, TextEditState, TextShaper, fuzzy_filter, fuzzy_match,
+};
+pub use timing::{
+    AnimationClock, PowerSettings, Scheduler, TimeSource, TimerId, TimerQueue,
+};
+#[cfg(any(test, feature = "bench"))]
+pub(crate) use widgets::build_rectangle;
+pub use widgets::{
+    AccessNode, AccessRole, Accordion, AccordionMode, Autocomplete,
+    AutocompleteStyle, Availability, Avatar, AvatarStyle, BadgeAnchor, Block,
+    BlockStyle, Button, ButtonState, ButtonStyle, CaptionButton,
+    CellAttributes, CellGlyphSource, ClipId, Collapsible, CollapsibleStyle,
+    ColorScale, CursorShape, CustomValidator, Form, FormStyle, Gantt,
+    GanttDependency, GanttEvent, GanttStyle, GanttTask, Gauge, GaugeBand,
+    GaugeKind, GaugeStyle, GizmoHandle, GizmoTarget, GlyphSource,
+    HasAvailability, Heatmap, HeatmapHover, HeatmapStyle, HighlightRow,
+    ImageStyle, ImageView, InteractionSnapshot, Knob, KnobDragMode, KnobEvent,
+    KnobStyle, LevelMeter, LevelMeterKind, LevelMeterStyle, MarchingAnts,
+    MarchingAntsStyle, Marquee, MarqueeStyle, MatchHighlightStyle,
+    MatchHighlights, Minimap, MinimapItem, MinimapStyle, PackedBlockStyle,
+    PageChange, Pagination, PaginationItem, PaginationStyle, PoolStats,
+    PopupPlacement, Recyclable, ScrollAnchor, ScrollTarget, ScrollView,
+    SearchField, SearchFieldStyle, Sparkline, SparklineKind, SparklineStyle,
+    Spectrogram, StatusBar, StatusBarStyle, StatusBarZone, StepState,
+    StepValidator, Stepper, StepperStyle, StylePatch, StyledBlock,
+    SuggestionProvider, TerminalCell, TerminalGrid, TerminalGridStyle,
+    TextGlyph, TextInput, TextInputStyle, Timeline, TimelineClip,
+    TimelineEvent, TimelineStyle, TitleBar, TitleBarAction, TitleBarStyle,
+    Toolbar, ToolbarItem, ToolbarItemKind, ToolbarStyle, TransformGizmo,
+    TransformGizmoStyle, ValidateOn, Validator, Waveform, WaveformEvent,
+    WaveformKind, WaveformStyle, Widget, WidgetContext, WidgetPool,
+    block_states, hit_test_tags,
+};
@@ -0,0 +1,301 @@
+use glam::Mat4;
+use slotmap::{DefaultKey, SlotMap};
+
+use super::widgets::{Block, block_states::Positioned};
+use crate::core::Renderer;
+
+pub type SceneNodeId = DefaultKey;
+
+struct SceneNode {
+    parent:         Option<SceneNodeId>,
+    children:       Vec<SceneNodeId>,
+    // Relative to the parent's top left.
+    position:       [f32; 2],
+    world_position: [f32; 2],
+    block:          Option<Block<Positioned>>,
+}
+
+// Blocks positioned relative to their parents, so that moving a
+// container moves everything in it. Nodes without a block only group
+// their children. Blocks are moved whenever a node or one of its
+// ancestors is, so move them through the scene rather than directly.
+pub struct Scene {
+    nodes: SlotMap<SceneNodeId, SceneNode>,
+    roots: Vec<SceneNodeId>,
+}
+
+impl Default for Scene {
+    fn default() -> Self { Self::new() }
+}
+
+impl Scene {
+    #[must_use]
+    pub fn new() -> Self { Self { nodes: SlotMap::new(), roots: Vec::new() } }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn roots(&self) -> &[SceneNodeId] { &self.roots }
+
+    #[must_use]
+    pub fn parent(&self, node: SceneNodeId) -> Option<SceneNodeId> {
+        self.nodes.get(node)?.parent
+    }
+
+    #[must_use]
+    pub fn children(&self, node: SceneNodeId) -> &[SceneNodeId] {
+        self.nodes.get(node).map_or(&[], |node| &node.children)
+    }
+
+    #[must_use]
+    pub fn position(&self, node: SceneNodeId) -> Option<[f32; 2]> {
+        self.nodes.get(node).map(|node| node.position)
+    }
+
+    // Where the node is in logical pixels.
+    #[must_use]
+    pub fn world_position(&self, node: SceneNodeId) -> Option<[f32; 2]> {
+        self.nodes.get(node).map(|node| node.world_position)
+    }
+
+    #[must_use]
+    pub fn block(&self, node: SceneNodeId) -> Option<&Block<Positioned>> {
+        self.nodes.get(node)?.block.as_ref()
+    }
+
+    // Restyling and resizing are fine; moving the block directly is undone
+    // by the next move of the node.
+    #[must_use]
+    pub fn block_mut(
+        &mut self,
+        node: SceneNodeId,
+    ) -> Option<&mut Block<Positioned>> {
+        self.nodes.get_mut(node)?.block.as_mut()
+    }
+
+    // Adds a node that only groups its children.
+    pub fn insert(
+        &mut self,
+        parent: Option<SceneNodeId>,
+        position: [f32; 2],
+    ) -> SceneNodeId {
+        let parent = parent.filter(|parent| self.nodes.contains_key(*parent));
+        let node = self.nodes.insert(SceneNode {
+            parent,
+            children: Vec::new(),
+            position,
+            world_position: self.child_position(parent, position),
+            block: None,
+        });
+        self.siblings_mut(parent).push(node);
+
+        node
+    }
+
+    // Adds a node for `block` and moves the block to it.
+    pub fn insert_block(
+        &mut self,
+        parent: Option<SceneNodeId>,
+        position: [f32; 2],
+        mut block: Block<Positioned>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> SceneNodeId {
+        let node = self.insert(parent, position);
+        let world_position = self.nodes[node].world_position;
+        block.update_position(world_position, view_projection, dpr, renderer);
+        self.nodes[node].block = Some(block);
+
+        node
+    }
+
+    // Moves the node relative to its parent, and its descendants with it.
+    pub fn set_position(
+        &mut self,
+        node: SceneNodeId,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(scene_node) = self.nodes.get_mut(node) else {
+            return;
+        };
+        if scene_node.position == position {
+            return;
+        }
+        scene_node.position = position;
+        self.move_subtree(node, view_projection, dpr, renderer);
+    }
+
+    // Moves the node under another parent, keeping its position relative
+    // to the parent. Moving a node under itself or one of its
+    // descendants is ignored.
+    pub fn reparent(
+        &mut self,
+        node: SceneNodeId,
+        parent: Option<SceneNodeId>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let parent = parent.filter(|parent| self.nodes.contains_key(*parent));
+        if !self.nodes.contains_key(node)
+            || parent.is_some_and(|parent| self.is_within(parent, node))
+        {
+            return;
+        }
+        let previous = self.nodes[node].parent;
+        self.siblings_mut(previous).retain(|child| *child != node);
+        self.siblings_mut(parent).push(node);
+        self.nodes[node].parent = parent;
+        self.move_subtree(node, view_projection, dpr, renderer);
+    }
+
+    // Removes the node and its descendants, destroying their blocks.
+    pub fn remove(&mut self, node: SceneNodeId, renderer: &mut Renderer) {
+        let Some(removed) = self.nodes.remove(node) else {
+            return;
+        };
+        self.siblings_mut(removed.parent)
+            .retain(|child| *child != node);
+
+        let mut removed = vec![removed];
+        while let Some(scene_node) = removed.pop() {
+            if let Some(block) = scene_node.block {
+                block.destroy(renderer);
+            }
+            removed.extend(
+                scene_node
+                    .children
+                    .into_iter()
+                    .filter_map(|child| self.nodes.remove(child)),
+            );
+        }
+    }
+
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        for (_, node) in self.nodes.drain() {
+            if let Some(block) = node.block {
+                block.destroy(renderer);
+            }
+        }
+        self.roots.clear();
+    }
+
+    fn move_subtree(
+        &mut self,
+        node: SceneNodeId,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for node in self.update_world_positions(node) {
+            let scene_node = &mut self.nodes[node];
+            if let Some(block) = &mut scene_node.block {
+                block.update_position(
+                    scene_node.world_position,
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+            }
+        }
+    }
+
+    // Recomputes the world positions of the node and its descendants,
+    // parents first, and returns the nodes that moved.
+    fn update_world_positions(
+        &mut self,
+        node: SceneNodeId,
+    ) -> Vec<SceneNodeId> {
+        let mut moved = Vec::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            let scene_node = &self.nodes[node];
+            let world_position =
+                self.child_position(scene_node.parent, scene_node.position);
+            let scene_node = &mut self.nodes[node];
+            // Descendants only move if this node did.
+            if scene_node.world_position == world_position {
+                continue;
+            }
+            scene_node.world_position = world_position;
+            moved.push(node);
+            stack.extend_from_slice(&scene_node.children);
+        }
+        moved
+    }
+
+    fn child_position(
+        &self,
+        parent: Option<SceneNodeId>,
+        position: [f32; 2],
+    ) -> [f32; 2] {
+        let origin = parent
+            .and_then(|parent| self.nodes.get(parent))
+            .map_or([0.0, 0.0], |parent| parent.world_position);
+        [origin[0] + position[0], origin[1] + position[1]]
+    }
+
+    fn siblings_mut(
+        &mut self,
+        parent: Option<SceneNodeId>,
+    ) -> &mut Vec<SceneNodeId> {
+        match parent.and_then(|parent| self.nodes.get_mut(parent)) {
+            Some(parent) => &mut parent.children,
+            None => &mut self.roots,
+        }
+    }
+
+    fn is_within(&self, node: SceneNodeId, ancestor: SceneNodeId) -> bool {
+        std::iter::successors(Some(node), |node| self.parent(*node))
+            .any(|node| node == ancestor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::Scene;
+
+    #[rstest]
+    fn test_world_positions() {
+        let mut scene = Scene::new();
+        let panel = scene.insert(None, [100.0, 50.0]);
+        let row = scene.insert(Some(panel), [10.0, 20.0]);
+        let cell = scene.insert(Some(row), [5.0, 0.0]);
+        let other = scene.insert(None, [0.0, 0.0]);
+        assert_eq!(scene.world_position(cell), Some([115.0, 70.0]));
+
+        scene.nodes[panel].position = [200.0, 50.0];
+        assert_eq!(scene.update_world_positions(panel), [panel, row, cell]);
+        assert_eq!(scene.world_position(cell), Some([215.0, 70.0]));
+        assert_eq!(scene.world_position(other), Some([0.0, 0.0]));
+        // Nothing moved.
+        assert!(scene.update_world_positions(panel).is_empty());
+    }
+
+    #[rstest]
+    fn test_is_within() {
+        let mut scene = Scene::new();
+        let panel = scene.insert(None, [0.0, 0.0]);
+        let row = scene.insert(Some(panel), [0.0, 0.0]);
+        let other = scene.insert(None, [0.0, 0.0]);
+        assert!(scene.is_within(row, panel));
+        assert!(scene.is_within(panel, panel));
+        assert!(!scene.is_within(panel, row));
+        assert!(!scene.is_within(row, other));
+        assert_eq!(scene.roots(), [panel, other]);
+        assert_eq!(scene.children(panel), [row]);
+    }
+}
@@ -0,0 +1,346 @@
+use std::sync::Arc;
+
+use slotmap::{DefaultKey, SlotMap};
+
+pub type StyleNodeId = DefaultKey;
+
+// The style properties children take from their parent unless they
+// override them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InheritedStyle {
+    pub text_color:  [f32; 4],
+    pub font_family: Arc<str>,
+    pub font_size:   f32,
+    pub opacity:     f32,
+    pub is_enabled:  bool,
+}
+
+impl Default for InheritedStyle {
+    fn default() -> Self {
+        Self {
+            text_color:  [0.1, 0.1, 0.1, 1.0],
+            font_family: Arc::from("sans-serif"),
+            font_size:   14.0,
+            opacity:     1.0,
+            is_enabled:  true,
+        }
+    }
+}
+
+// A node's own values. Opacity multiplies the parent's, and a node is
+// only enabled if its parent is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleOverrides {
+    pub text_color:  Option<[f32; 4]>,
+    pub font_family: Option<Arc<str>>,
+    pub font_size:   Option<f32>,
+    pub opacity:     Option<f32>,
+    pub is_enabled:  Option<bool>,
+}
+
+impl StyleOverrides {
+    #[must_use]
+    pub fn apply(&self, parent: &InheritedStyle) -> InheritedStyle {
+        InheritedStyle {
+            text_color:  self.text_color.unwrap_or(parent.text_color),
+            font_family: self
+                .font_family
+                .clone()
+                .unwrap_or_else(|| parent.font_family.clone()),
+            font_size:   self.font_size.unwrap_or(parent.font_size),
+            opacity:     parent.opacity * self.opacity.unwrap_or(1.0),
+            is_enabled:  parent.is_enabled && self.is_enabled.unwrap_or(true),
+        }
+    }
+}
+
+struct StyleNode {
+    parent:    Option<StyleNodeId>,
+    children:  Vec<StyleNodeId>,
+    overrides: StyleOverrides,
+    computed:  InheritedStyle,
+    is_dirty:  bool,
+}
+
+// Mirrors the widget tree to resolve inherited style. Changing a node's
+// overrides or moving it only marks it dirty; `update` then recomputes
+// the dirty nodes and walks down only as far as the computed style
+// actually changes, returning the nodes whose widgets need restyling.
+pub struct StyleTree {
+    root_style: InheritedStyle,
+    nodes:      SlotMap<StyleNodeId, StyleNode>,
+    roots:      Vec<StyleNodeId>,
+    dirty:      Vec<StyleNodeId>,
+}
+
+impl StyleTree {
+    #[must_use]
+    pub fn new(root_style: InheritedStyle) -> Self {
+        Self {
+            root_style,
+            nodes: SlotMap::new(),
+            roots: Vec::new(),
+            dirty: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn root_style(&self) -> &InheritedStyle { &self.root_style }
+
+    // The style as of the last `update`.
+    #[must_use]
+    pub fn computed(&self, node: StyleNodeId) -> Option<&InheritedStyle> {
+        self.nodes.get(node).map(|node| &node.computed)
+    }
+
+    #[must_use]
+    pub fn overrides(&self, node: StyleNodeId) -> Option<&StyleOverrides> {
+        self.nodes.get(node).map(|node| &node.overrides)
+    }
+
+    #[must_use]
+    pub fn parent(&self, node: StyleNodeId) -> Option<StyleNodeId> {
+        self.nodes.get(node)?.parent
+    }
+
+    #[must_use]
+    pub fn children(&self, node: StyleNodeId) -> &[StyleNodeId] {
+        self.nodes.get(node).map_or(&[], |node| &node.children)
+    }
+
+    // The node's style is computed right away, so it can be read before
+    // the next `update`.
+    pub fn insert(
+        &mut self,
+        parent: Option<StyleNodeId>,
+        overrides: StyleOverrides,
+    ) -> StyleNodeId {
+        let parent = parent.filter(|parent| self.nodes.contains_key(*parent));
+        let computed = overrides.apply(self.parent_style(parent));
+        let node = self.nodes.insert(StyleNode {
+            parent,
+            children: Vec::new(),
+            overrides,
+            computed,
+            is_dirty: false,
+        });
+        self.siblings_mut(parent).push(node);
+
+        node
+    }
+
+    // Removes the node and its descendants.
+    pub fn remove(&mut self, node: StyleNodeId) {
+        let Some(removed) = self.nodes.remove(node) else {
+            return;
+        };
+        self.siblings_mut(removed.parent)
+            .retain(|child| *child != node);
+
+        let mut stack = removed.children;
+        while let Some(child) = stack.pop() {
+            if let Some(removed) = self.nodes.remove(child) {
+                stack.extend(removed.children);
+            }
+        }
+    }
+
+    // Moves the node under another parent. Moving a node under itself or
+    // one of its descendants is ignored.
+    pub fn reparent(
+        &mut self,
+        node: StyleNodeId,
+        parent: Option<StyleNodeId>,
+    ) {
+        if !self.nodes.contains_key(node)
+            || parent.is_some_and(|parent| self.is_within(parent, node))
+        {
+            return;
+        }
+        let previous = self.nodes[node].parent;
+        self.siblings_mut(previous).retain(|child| *child != node);
+        self.siblings_mut(parent).push(node);
+        self.nodes[node].parent = parent;
+        self.mark_dirty(node);
+    }
+
+    pub fn set_overrides(
+        &mut self,
+        node: StyleNodeId,
+        overrides: StyleOverrides,
+    ) {
+        self.update_overrides(node, |current| *current = overrides);
+    }
+
+    pub fn update_overrides(
+        &mut self,
+        node: StyleNodeId,
+        update: impl FnOnce(&mut StyleOverrides),
+    ) {
+        let Some(style_node) = self.nodes.get_mut(node) else {
+            return;
+        };
+        update(&mut style_node.overrides);
+        self.mark_dirty(node);
+    }
+
+    pub fn set_root_style(&mut self, root_style: InheritedStyle) {
+        if self.root_style == root_style {
+            return;
+        }
+        self.root_style = root_style;
+        for root in self.roots.clone() {
+            self.mark_dirty(root);
+        }
+    }
+
+    // Recomputes the dirty nodes, parents first, and returns every node
+    // whose computed style changed.
+    pub fn update(&mut self) -> Vec<StyleNodeId> {
+        let mut dirty = std::mem::take(&mut self.dirty);
+        dirty.retain(|node| self.nodes.contains_key(*node));
+        dirty.sort_by_cached_key(|node| self.depth(*node));
+
+        let mut changed = Vec::new();
+        for node in dirty {
+            if !self.nodes[node].is_dirty {
+                continue;
+            }
+            let mut stack = vec![node];
+            while let Some(node) = stack.pop() {
+                let parent = self.nodes[node].parent;
+                let computed = self.nodes[node]
+                    .overrides
+                    .apply(self.parent_style(parent));
+                let style_node = &mut self.nodes[node];
+                style_node.is_dirty = false;
+                // Descendants only change if this node did.
+                if style_node.computed == computed {
+                    continue;
+                }
+                style_node.computed = computed;
+                changed.push(node);
+                stack.extend_from_slice(&style_node.children);
+            }
+        }
+        changed
+    }
+
+    fn mark_dirty(&mut self, node: StyleNodeId) {
+        let style_node = &mut self.nodes[node];
+        if !style_node.is_dirty {
+            style_node.is_dirty = true;
+            self.dirty.push(node);
+        }
+    }
+
+    fn parent_style(&self, parent: Option<StyleNodeId>) -> &InheritedStyle {
+        parent
+            .and_then(|parent| self.nodes.get(parent))
+            .map_or(&self.root_style, |parent| &parent.computed)
+    }
+
+    fn siblings_mut(
+        &mut self,
+        parent: Option<StyleNodeId>,
+    ) -> &mut Vec<StyleNodeId> {
+        match parent.and_then(|parent| self.nodes.get_mut(parent)) {
+            Some(parent) => &mut parent.children,
+            None => &mut self.roots,
+        }
+    }
+
+    fn depth(&self, node: StyleNodeId) -> usize {
+        std::iter::successors(Some(node), |node| self.parent(*node)).count()
+    }
+
+    fn is_within(&self, node: StyleNodeId, ancestor: StyleNodeId) -> bool {
+        std::iter::successors(Some(node), |node| self.parent(*node))
+            .any(|node| node == ancestor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{InheritedStyle, StyleOverrides, StyleTree};
+
+    // Overrides can't enable what a parent disabled.
+    #[rstest]
+    #[case(true, true)]
+    #[case(false, false)]
+    fn test_apply(#[case] is_parent_enabled: bool, #[case] is_enabled: bool) {
+        let parent = InheritedStyle {
+            opacity: 0.5,
+            is_enabled: is_parent_enabled,
+            ..Default::default()
+        };
+        let overrides = StyleOverrides {
+            font_size: Some(20.0),
+            opacity: Some(0.5),
+            is_enabled: Some(true),
+            ..Default::default()
+        };
+        let computed = overrides.apply(&parent);
+        assert_eq!(computed.font_size, 20.0);
+        assert_eq!(computed.opacity, 0.25);
+        assert_eq!(computed.is_enabled, is_enabled);
+    }
+
+    #[rstest]
+    fn test_update_stops_at_overrides() {
+        let mut tree = StyleTree::new(InheritedStyle::default());
+        let root = tree.insert(None, StyleOverrides::default());
+        let panel = tree.insert(Some(root), StyleOverrides::default());
+        let label = tree.insert(
+            Some(panel),
+            StyleOverrides {
+                text_color: Some([1.0, 0.0, 0.0, 1.0]),
+                ..Default::default()
+            },
+        );
+
+        tree.update_overrides(root, |overrides| {
+            overrides.text_color = Some([0.0, 0.0, 1.0, 1.0]);
+        });
+        assert_eq!(tree.update(), [root, panel]);
+        assert_eq!(
+            tree.computed(panel).unwrap().text_color,
+            [0.0, 0.0, 1.0, 1.0]
+        );
+        assert_eq!(
+            tree.computed(label).unwrap().text_color,
+            [1.0, 0.0, 0.0, 1.0]
+        );
+
+        tree.update_overrides(root, |overrides| {
+            overrides.is_enabled = Some(false);
+        });
+        assert_eq!(tree.update(), [root, panel, label]);
+        assert!(tree.update().is_empty());
+    }
+
+    #[rstest]
+    fn test_reparent_and_remove() {
+        let mut tree = StyleTree::new(InheritedStyle::default());
+        let faded = tree.insert(
+            None,
+            StyleOverrides { opacity: Some(0.5), ..Default::default() },
+        );
+        let child = tree.insert(None, StyleOverrides::default());
+        let grandchild = tree.insert(Some(child), StyleOverrides::default());
+
+        // A node can't move under its own descendant.
+        tree.reparent(child, Some(grandchild));
+        assert_eq!(tree.parent(child), None);
+
+        tree.reparent(child, Some(faded));
+        assert_eq!(tree.update(), [child, grandchild]);
+        assert_eq!(tree.computed(grandchild).unwrap().opacity, 0.5);
+
+        tree.remove(faded);
+        assert_eq!(tree.computed(grandchild), None);
+    }
+}
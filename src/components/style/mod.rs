@@ -0,0 +1,14 @@
+mod cascade;
+mod presets;
+mod state_styles;
+mod theme;
+mod theme_expression;
+#[cfg(feature = "hot-reload")]
+mod theme_watcher;
+
+pub use cascade::{InheritedStyle, StyleNodeId, StyleOverrides, StyleTree};
+pub use presets::BlockPreset;
+pub use state_styles::{InteractionState, PseudoState, StateStyles};
+pub use theme::{Theme, ThemeBindings, ThemeError, ThemeValue};
+#[cfg(feature = "hot-reload")]
+pub use theme_watcher::ThemeWatcher;
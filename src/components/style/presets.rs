@@ -0,0 +1,102 @@
+use super::Theme;
+use crate::components::{common::rgba8, widgets::BlockStyle};
+
+// Common block styles built from a theme's names, falling back to the
+// defaults below for names the theme doesn't have:
+//
+// surface     = #ffffff   card fill
+// accent      = #3584e4   pill fill, outline border
+// shadow      = #00000026 card shadow
+// radius      = 8
+// border_size = 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockPreset {
+    // A raised surface for grouping content.
+    Card,
+    // Fully rounded ends, for tags and chips.
+    Pill { height: f32 },
+    // A transparent fill inside an accent border.
+    Outline,
+    // Nothing but the shape, until a patch for a state fills it in.
+    Ghost,
+}
+
+const SURFACE: [f32; 4] = rgba8(0xffffffff);
+const ACCENT: [f32; 4] = rgba8(0x3584e4ff);
+const SHADOW: [f32; 4] = rgba8(0x00000026);
+const RADIUS: f32 = 8.0;
+const BORDER_SIZE: f32 = 1.0;
+
+impl BlockPreset {
+    #[must_use]
+    pub fn style(self, theme: &Theme) -> BlockStyle {
+        let radius = theme.number("radius").unwrap_or(RADIUS);
+        let accent = theme.color("accent").unwrap_or(ACCENT);
+        match self {
+            Self::Card => BlockStyle::builder()
+                .fill_color(theme.color("surface").unwrap_or(SURFACE))
+                .corner_radii([radius; 4])
+                .shadow_color(theme.color("shadow").unwrap_or(SHADOW))
+                .shadow_offset([0.0, 2.0])
+                .shadow_blur(8.0)
+                .build(),
+            Self::Pill { height } => BlockStyle::builder()
+                .fill_color(accent)
+                .corner_radii([height / 2.0; 4])
+                .build(),
+            Self::Outline => BlockStyle::builder()
+                .fill_color([0.0; 4])
+                .corner_radii([radius; 4])
+                .border_color(accent)
+                .border_size(
+                    theme.number("border_size").unwrap_or(BORDER_SIZE),
+                )
+                .build(),
+            Self::Ghost => BlockStyle::builder()
+                .fill_color([0.0; 4])
+                .corner_radii([radius; 4])
+                .build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ACCENT, BlockPreset, RADIUS};
+    use crate::components::{
+        style::Theme,
+        widgets::{BlockStyle, StylePatch},
+    };
+
+    #[rstest]
+    fn test_presets() {
+        let theme =
+            Theme::parse("accent = #ff0000\nradius = 4\nborder_size = 2")
+                .unwrap();
+        let outline = BlockPreset::Outline.style(&theme);
+        let expected = BlockStyle::builder()
+            .fill_color([0.0; 4])
+            .corner_radii([4.0; 4])
+            .border_color([1.0, 0.0, 0.0, 1.0])
+            .border_size(2.0)
+            .build();
+        assert_eq!(outline, expected);
+
+        let pill = BlockPreset::Pill { height: 24.0 }.style(&Theme::default());
+        let expected = BlockStyle::builder()
+            .fill_color(ACCENT)
+            .corner_radii([12.0; 4])
+            .build();
+        assert_eq!(pill, expected);
+
+        let hovered = StylePatch::builder().fill_color([0.0, 0.0, 0.0, 0.1]);
+        let ghost = BlockPreset::Ghost.style(&Theme::default());
+        let expected = BlockStyle::builder()
+            .fill_color([0.0, 0.0, 0.0, 0.1])
+            .corner_radii([RADIUS; 4])
+            .build();
+        assert_eq!(ghost.merged(&hovered.build()), expected);
+    }
+}
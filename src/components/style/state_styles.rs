@@ -0,0 +1,130 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PseudoState {
+    Hovered,
+    Pressed,
+    Focused,
+    Disabled,
+    Selected,
+}
+
+impl PseudoState {
+    // When several states apply, the first one with a variant wins.
+    pub const PRECEDENCE: [Self; 5] = [
+        Self::Disabled,
+        Self::Pressed,
+        Self::Hovered,
+        Self::Focused,
+        Self::Selected,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InteractionState {
+    pub is_hovered:  bool,
+    pub is_pressed:  bool,
+    pub is_focused:  bool,
+    pub is_disabled: bool,
+    pub is_selected: bool,
+}
+
+impl InteractionState {
+    #[must_use]
+    pub const fn has(&self, state: PseudoState) -> bool {
+        match state {
+            PseudoState::Hovered => self.is_hovered,
+            PseudoState::Pressed => self.is_pressed,
+            PseudoState::Focused => self.is_focused,
+            PseudoState::Disabled => self.is_disabled,
+            PseudoState::Selected => self.is_selected,
+        }
+    }
+}
+
+// A style with variants for interaction states, declared once, e.g.
+// `StateStyles::new(idle).with(PseudoState::Hovered, hovered)`.
+// Variants are whole styles rather than partial ones; block styles can
+// be given as a `StylePatch` of the base with `with_patch`.
+#[derive(Clone)]
+pub struct StateStyles<S> {
+    base:     S,
+    variants: Vec<(PseudoState, S)>,
+}
+
+impl<S> StateStyles<S> {
+    #[must_use]
+    pub const fn new(base: S) -> Self { Self { base, variants: Vec::new() } }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn base(&self) -> &S { &self.base }
+
+    // Replaces the state's variant if it already has one.
+    #[must_use]
+    pub fn with(mut self, state: PseudoState, style: S) -> Self {
+        self.set_variant(state, style);
+        self
+    }
+
+    pub fn set_variant(&mut self, state: PseudoState, style: S) {
+        match self.variants.iter_mut().find(|(key, _)| *key == state) {
+            Some((_, variant)) => *variant = style,
+            None => self.variants.push((state, style)),
+        }
+    }
+
+    #[must_use]
+    pub fn variant(&self, state: PseudoState) -> Option<&S> {
+        self.variants
+            .iter()
+            .find(|(key, _)| *key == state)
+            .map(|(_, style)| style)
+    }
+
+    // The state whose variant applies, or `None` for the base style.
+    #[must_use]
+    pub fn active_variant(
+        &self,
+        state: &InteractionState,
+    ) -> Option<PseudoState> {
+        PseudoState::PRECEDENCE.into_iter().find(|pseudo_state| {
+            state.has(*pseudo_state) && self.variant(*pseudo_state).is_some()
+        })
+    }
+
+    #[must_use]
+    pub fn resolve(&self, state: &InteractionState) -> &S {
+        self.active_variant(state)
+            .and_then(|pseudo_state| self.variant(pseudo_state))
+            .unwrap_or(&self.base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{InteractionState, PseudoState, StateStyles};
+
+    #[rstest]
+    #[case(InteractionState::default(), None)]
+    #[case(
+        InteractionState { is_hovered: true, is_pressed: true, ..Default::default() },
+        Some(PseudoState::Pressed)
+    )]
+    #[case(
+        InteractionState { is_hovered: true, is_disabled: true, ..Default::default() },
+        Some(PseudoState::Disabled)
+    )]
+    // Focus has no variant, so the base style is used.
+    #[case(InteractionState { is_focused: true, ..Default::default() }, None)]
+    fn test_active_variant(
+        #[case] state: InteractionState,
+        #[case] expected: Option<PseudoState>,
+    ) {
+        let styles = StateStyles::new("idle")
+            .with(PseudoState::Hovered, "hovered")
+            .with(PseudoState::Pressed, "pressed")
+            .with(PseudoState::Disabled, "disabled");
+        assert_eq!(styles.active_variant(&state), expected);
+    }
+}
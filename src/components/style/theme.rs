@@ -0,0 +1,307 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use super::theme_expression::evaluate;
+use crate::components::common::rgba8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThemeValue {
+    Color([f32; 4]),
+    Number(f32),
+}
+
+impl ThemeValue {
+    // `0xrrggbbaa`, for theme tokens defined as consts.
+    #[must_use]
+    #[inline(always)]
+    pub const fn hex(rgba: u32) -> Self { Self::Color(rgba8(rgba)) }
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(std::io::Error),
+    // `line` is one-based.
+    Parse {
+        line:    usize,
+        message: String,
+    },
+    #[cfg(feature = "hot-reload")]
+    Watch(notify::Error),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read theme: {error}"),
+            Self::Parse { line, message } => {
+                write!(f, "theme line {line}: {message}")
+            }
+            #[cfg(feature = "hot-reload")]
+            Self::Watch(error) => write!(f, "failed to watch theme: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+// Named colors and numbers that widget styles are built from. Theme
+// files hold one `name = value` per line. Values are `#rrggbb`,
+// `#rrggbbaa`, numbers and percentages, or expressions over other names
+// in any order, resolved when the theme is parsed. Lines starting with
+// `#` are comments:
+//
+// # Pixels
+// spacing = 8
+// padding = spacing * 2
+// accent = #3584e4
+// accent.hovered = accent.lighten(10%)
+// accent.disabled = accent.mix(#808080, 50%).alpha(60%)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    values: BTreeMap<String, ThemeValue>,
+}
+
+impl Theme {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let source = std::fs::read_to_string(path).map_err(ThemeError::Io)?;
+        Self::parse(&source)
+    }
+
+    pub fn parse(source: &str) -> Result<Self, ThemeError> {
+        let mut entries = BTreeMap::new();
+        for (index, line) in source.lines().enumerate() {
+            let parse_error = |message: String| ThemeError::Parse {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, expression)) = line.split_once('=') else {
+                return Err(parse_error(format!(
+                    "expected `name = value`, found `{line}`"
+                )));
+            };
+            let name = name.trim();
+            if !is_valid_name(name) {
+                return Err(parse_error(format!("invalid name `{name}`")));
+            }
+            entries.insert(name, (index + 1, expression.trim()));
+        }
+
+        let mut theme = Self::default();
+        let mut resolving = Vec::new();
+        for name in entries.keys() {
+            resolve(name, &entries, &mut theme.values, &mut resolving)?;
+        }
+        Ok(theme)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<ThemeValue> {
+        self.values.get(name).copied()
+    }
+
+    #[must_use]
+    pub fn color(&self, name: &str) -> Option<[f32; 4]> {
+        match self.get(name)? {
+            ThemeValue::Color(color) => Some(color),
+            ThemeValue::Number(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn number(&self, name: &str) -> Option<f32> {
+        match self.get(name)? {
+            ThemeValue::Number(number) => Some(number),
+            ThemeValue::Color(_) => None,
+        }
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: ThemeValue) {
+        self.values.insert(name.into(), value);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.values.keys().map(String::as_str)
+    }
+
+    // The names added, removed or changed in `other`, sorted.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .values
+            .iter()
+            .filter(|(name, value)| other.values.get(*name) != Some(value))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.extend(
+            other
+                .values
+                .keys()
+                .filter(|name| !self.values.contains_key(*name))
+                .cloned(),
+        );
+        names.sort();
+        names
+    }
+}
+
+// Which theme names each widget's style is built from, so that only
+// the widgets using changed names are restyled.
+pub struct ThemeBindings<W> {
+    bindings: Vec<(W, Vec<String>)>,
+}
+
+impl<W> Default for ThemeBindings<W> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<W> ThemeBindings<W> {
+    #[must_use]
+    pub const fn new() -> Self { Self { bindings: Vec::new() } }
+}
+
+impl<W: Clone + PartialEq> ThemeBindings<W> {
+    // Replaces the widget's names if it's already bound.
+    pub fn bind<I, S>(&mut self, widget: W, names: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names = names.into_iter().map(Into::into).collect();
+        match self.bindings.iter_mut().find(|(bound, _)| *bound == widget) {
+            Some((_, bound_names)) => *bound_names = names,
+            None => self.bindings.push((widget, names)),
+        }
+    }
+
+    pub fn unbind(&mut self, widget: &W) {
+        self.bindings.retain(|(bound, _)| bound != widget);
+    }
+
+    // The widgets using any of the changed names, in binding order.
+    #[must_use]
+    pub fn affected(&self, changed: &[String]) -> Vec<W> {
+        self.bindings
+            .iter()
+            .filter(|(_, names)| {
+                names.iter().any(|name| changed.contains(name))
+            })
+            .map(|(widget, _)| widget.clone())
+            .collect()
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut segments = name.split('.');
+    segments.all(|segment| {
+        segment.starts_with(|char: char| {
+            char.is_ascii_alphabetic() || char == '_'
+        }) && segment
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_')
+    })
+}
+
+// Resolves a name and, first, the names its expression refers to.
+fn resolve(
+    name: &str,
+    entries: &BTreeMap<&str, (usize, &str)>,
+    values: &mut BTreeMap<String, ThemeValue>,
+    resolving: &mut Vec<String>,
+) -> Result<ThemeValue, ThemeError> {
+    if let Some(value) = values.get(name) {
+        return Ok(*value);
+    }
+    let Some(&(line, expression)) = entries.get(name) else {
+        return Err(ThemeError::Parse {
+            line:    0,
+            message: format!("unknown name `{name}`"),
+        });
+    };
+    resolving.push(name.to_owned());
+
+    // Errors in the entries referred to are reported at their own line.
+    let mut inner_error = None;
+    let value = evaluate(expression, &mut |referred| {
+        if resolving.iter().any(|name| name == referred) {
+            return Err(format!("`{referred}` refers back to itself"));
+        }
+        if !entries.contains_key(referred) {
+            return Err(format!("unknown name `{referred}`"));
+        }
+        resolve(referred, entries, values, resolving).map_err(|error| {
+            inner_error = Some(error);
+            String::new()
+        })
+    });
+    resolving.pop();
+
+    let value = match (value, inner_error) {
+        (Ok(value), _) => value,
+        (Err(_), Some(error)) => return Err(error),
+        (Err(message), None) => {
+            return Err(ThemeError::Parse { line, message });
+        }
+    };
+    values.insert(name.to_owned(), value);
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Theme, ThemeBindings, ThemeError, ThemeValue};
+
+    #[rstest]
+    fn test_parse_and_diff() {
+        let theme =
+            Theme::parse("# Colors\naccent = #ff0000\n\n  spacing = 8\n")
+                .unwrap();
+        assert_eq!(theme.color("accent"), Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(theme.get("spacing"), Some(ThemeValue::Number(8.0)));
+
+        let edited =
+            Theme::parse("accent = #ff0000\nspacing = 4\nradius = 2").unwrap();
+        assert_eq!(theme.diff(&edited), ["radius", "spacing"]);
+    }
+
+    #[rstest]
+    fn test_variables() {
+        let theme = Theme::parse(
+            "padding = spacing * 2\nspacing = 4\nhovered = accent.alpha(50%)\n\
+             accent = #ff0000",
+        )
+        .unwrap();
+        assert_eq!(theme.number("padding"), Some(8.0));
+        assert_eq!(theme.color("hovered"), Some([1.0, 0.0, 0.0, 0.5]));
+    }
+
+    #[rstest]
+    #[case("accent = #ff0000\nspacing 8", 2)]
+    // A cycle is reported where it closes.
+    #[case("a = b + 1\nb = c\nc = a", 3)]
+    // Reported at the line of the broken entry, not the one using it.
+    #[case("a = b * 2\nb = #ff0000 + 1", 2)]
+    fn test_parse_error(#[case] source: &str, #[case] expected: usize) {
+        let error = Theme::parse(source).unwrap_err();
+        assert!(
+            matches!(error, ThemeError::Parse { line, .. } if line == expected)
+        );
+    }
+
+    #[rstest]
+    fn test_affected() {
+        let mut bindings = ThemeBindings::new();
+        bindings.bind("button", ["accent", "radius"]);
+        bindings.bind("panel", ["surface"]);
+        bindings.bind("label", ["accent"]);
+        bindings.unbind(&"label");
+
+        let changed = ["accent".to_owned(), "spacing".to_owned()];
+        assert_eq!(bindings.affected(&changed), ["button"]);
+    }
+}
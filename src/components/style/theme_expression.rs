@@ -0,0 +1,342 @@
+use super::ThemeValue;
+
+// Evaluates a theme value: literals, other names, arithmetic on numbers
+// and color methods, e.g. `accent.darken(10%)` or `spacing * 2`.
+// Names are looked up through `lookup`, which resolves them in turn.
+pub(super) fn evaluate(
+    expression: &str,
+    lookup: &mut dyn FnMut(&str) -> Result<ThemeValue, String>,
+) -> Result<ThemeValue, String> {
+    let mut parser =
+        Parser { chars: expression.chars().collect(), index: 0, lookup };
+    let value = parser.sum()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(value),
+        Some(char) => Err(format!("unexpected `{char}` in `{expression}`")),
+    }
+}
+
+struct Parser<'a> {
+    chars:  Vec<char>,
+    index:  usize,
+    lookup: &'a mut dyn FnMut(&str) -> Result<ThemeValue, String>,
+}
+
+impl Parser<'_> {
+    fn sum(&mut self) -> Result<ThemeValue, String> {
+        let mut value = self.product()?;
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some(operator @ ('+' | '-')) => operator,
+                _ => return Ok(value),
+            };
+            self.index += 1;
+            let right = self.product()?;
+            value = apply_operator(operator, value, right)?;
+        }
+    }
+
+    fn product(&mut self) -> Result<ThemeValue, String> {
+        let mut value = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some(operator @ ('*' | '/')) => operator,
+                _ => return Ok(value),
+            };
+            self.index += 1;
+            let right = self.unary()?;
+            value = apply_operator(operator, value, right)?;
+        }
+    }
+
+    fn unary(&mut self) -> Result<ThemeValue, String> {
+        self.skip_whitespace();
+        if self.peek() == Some('-') {
+            self.index += 1;
+            return match self.unary()? {
+                ThemeValue::Number(number) => Ok(ThemeValue::Number(-number)),
+                ThemeValue::Color(_) => Err("can't negate a color".to_owned()),
+            };
+        }
+        let mut value = self.primary()?;
+        while self.peek() == Some('.') {
+            self.index += 1;
+            let method = self.identifier();
+            value = self.call(&method, value)?;
+        }
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<ThemeValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.index += 1;
+                let value = self.sum()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some('#') => {
+                self.index += 1;
+                let hex = self.take_while(|char| char.is_ascii_hexdigit());
+                parse_hex_color(&hex)
+                    .map(ThemeValue::Color)
+                    .ok_or_else(|| format!("invalid color `#{hex}`"))
+            }
+            Some(char) if char.is_ascii_digit() || char == '.' => {
+                self.number().map(ThemeValue::Number)
+            }
+            Some(char) if char.is_ascii_alphabetic() || char == '_' => {
+                let name = self.name();
+                (self.lookup)(&name)
+            }
+            Some(char) => Err(format!("unexpected `{char}`")),
+            None => Err("expected a value".to_owned()),
+        }
+    }
+
+    // Percentages are fractions, so `10%` is 0.1.
+    fn number(&mut self) -> Result<f32, String> {
+        let digits =
+            self.take_while(|char| char.is_ascii_digit() || char == '.');
+        let number: f32 = digits
+            .parse()
+            .map_err(|_| format!("invalid number `{digits}`"))?;
+        if self.peek() == Some('%') {
+            self.index += 1;
+            return Ok(number / 100.0);
+        }
+        Ok(number)
+    }
+
+    // Dotted names stop before a segment that's followed by `(`, which
+    // is a method call on what came before.
+    fn name(&mut self) -> String {
+        let mut name = self.identifier();
+        while self.peek() == Some('.') {
+            let start = self.index;
+            self.index += 1;
+            let segment = self.identifier();
+            if segment.is_empty() || self.peek() == Some('(') {
+                self.index = start;
+                break;
+            }
+            name.push('.');
+            name.push_str(&segment);
+        }
+        name
+    }
+
+    fn identifier(&mut self) -> String {
+        self.take_while(|char| char.is_ascii_alphanumeric() || char == '_')
+    }
+
+    fn call(
+        &mut self,
+        method: &str,
+        value: ThemeValue,
+    ) -> Result<ThemeValue, String> {
+        self.expect('(')?;
+        let mut arguments = Vec::new();
+        self.skip_whitespace();
+        if self.peek() != Some(')') {
+            loop {
+                arguments.push(self.sum()?);
+                self.skip_whitespace();
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.index += 1;
+            }
+        }
+        self.expect(')')?;
+
+        let ThemeValue::Color(color) = value else {
+            return Err(format!("`{method}` needs a color"));
+        };
+        let color = match (method, arguments.as_slice()) {
+            ("darken", [ThemeValue::Number(amount)]) => {
+                adjust_lightness(color, -amount)
+            }
+            ("lighten", [ThemeValue::Number(amount)]) => {
+                adjust_lightness(color, *amount)
+            }
+            ("alpha", [ThemeValue::Number(alpha)]) => {
+                [color[0], color[1], color[2], alpha.clamp(0.0, 1.0)]
+            }
+            (
+                "mix",
+                [ThemeValue::Color(other), ThemeValue::Number(amount)],
+            ) => {
+                let amount = amount.clamp(0.0, 1.0);
+                std::array::from_fn(|index| {
+                    color[index] + (other[index] - color[index]) * amount
+                })
+            }
+            _ => return Err(format!("invalid call `{method}`")),
+        };
+        Ok(ThemeValue::Color(color))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(char) if char == expected => {
+                self.index += 1;
+                Ok(())
+            }
+            _ => Err(format!("expected `{expected}`")),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.index;
+        while self.peek().is_some_and(&predicate) {
+            self.index += 1;
+        }
+        self.chars[start..self.index].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> { self.chars.get(self.index).copied() }
+}
+
+fn apply_operator(
+    operator: char,
+    left: ThemeValue,
+    right: ThemeValue,
+) -> Result<ThemeValue, String> {
+    let (ThemeValue::Number(left), ThemeValue::Number(right)) = (left, right)
+    else {
+        return Err(format!("`{operator}` needs numbers"));
+    };
+    let value = match operator {
+        '+' => left + right,
+        '-' => left - right,
+        '*' => left * right,
+        _ if right == 0.0 => return Err("division by zero".to_owned()),
+        _ => left / right,
+    };
+    Ok(ThemeValue::Number(value))
+}
+
+pub(super) fn parse_hex_color(hex: &str) -> Option<[f32; 4]> {
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let mut color = [1.0; 4];
+    for (channel, index) in color.iter_mut().zip((0..hex.len()).step_by(2)) {
+        let byte = u8::from_str_radix(&hex[index..index + 2], 16).ok()?;
+        *channel = f32::from(byte) / 255.0;
+    }
+    Some(color)
+}
+
+// Shifts the HSL lightness, like CSS preprocessors do.
+fn adjust_lightness(color: [f32; 4], amount: f32) -> [f32; 4] {
+    let [hue, saturation, lightness] =
+        rgb_to_hsl([color[0], color[1], color[2]]);
+    let [red, green, blue] =
+        hsl_to_rgb([hue, saturation, (lightness + amount).clamp(0.0, 1.0)]);
+    [red, green, blue, color[3]]
+}
+
+fn rgb_to_hsl([red, green, blue]: [f32; 3]) -> [f32; 3] {
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return [0.0, 0.0, lightness];
+    }
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == red {
+        ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == green {
+        (blue - red) / delta + 2.0
+    } else {
+        (red - green) / delta + 4.0
+    };
+    [hue / 6.0, saturation, lightness]
+}
+
+fn hsl_to_rgb([hue, saturation, lightness]: [f32; 3]) -> [f32; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue * 6.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let [red, green, blue] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    let offset = lightness - chroma / 2.0;
+    [red + offset, green + offset, blue + offset]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ThemeValue, evaluate, parse_hex_color};
+
+    fn lookup(name: &str) -> Result<ThemeValue, String> {
+        match name {
+            "spacing" => Ok(ThemeValue::Number(8.0)),
+            "palette.accent" => Ok(ThemeValue::Color([0.2, 0.4, 0.8, 1.0])),
+            _ => Err(format!("unknown name `{name}`")),
+        }
+    }
+
+    #[rstest]
+    #[case("spacing * 2", ThemeValue::Number(16.0))]
+    #[case("-(spacing + 4) / 2", ThemeValue::Number(-6.0))]
+    #[case("50%", ThemeValue::Number(0.5))]
+    #[case("palette.accent.alpha(50%)", ThemeValue::Color([0.2, 0.4, 0.8, 0.5]))]
+    #[case("palette.accent.lighten(20%)", ThemeValue::Color([0.52, 0.64, 0.88, 1.0]))]
+    #[case("#000000ff.mix(#ffffff, 25%)", ThemeValue::Color([0.25, 0.25, 0.25, 1.0]))]
+    fn test_evaluate(#[case] expression: &str, #[case] expected: ThemeValue) {
+        let value = evaluate(expression, &mut lookup).unwrap();
+        let (ThemeValue::Color(value), ThemeValue::Color(expected)) =
+            (value, expected)
+        else {
+            assert_eq!(value, expected);
+            return;
+        };
+        for (channel, expected) in value.into_iter().zip(expected) {
+            assert!((channel - expected).abs() < 1e-5, "{value:?}");
+        }
+    }
+
+    #[rstest]
+    #[case("spacing.darken(10%)")]
+    #[case("palette.accent * 2")]
+    #[case("spacing / 0")]
+    #[case("spacing 2")]
+    #[case("missing")]
+    fn test_evaluate_errors(#[case] expression: &str) {
+        assert!(evaluate(expression, &mut lookup).is_err());
+    }
+
+    #[rstest]
+    #[case("ff0000", Some([1.0, 0.0, 0.0, 1.0]))]
+    #[case("00ff0080", Some([0.0, 1.0, 0.0, 128.0 / 255.0]))]
+    #[case("fff", None)]
+    #[case("gg0000", None)]
+    fn test_parse_hex_color(
+        #[case] hex: &str,
+        #[case] expected: Option<[f32; 4]>,
+    ) {
+        assert_eq!(parse_hex_color(hex), expected);
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{Theme, ThemeError};
+
+// Reloads a theme file when it changes on disk, for iterating on themes
+// without restarting. The directory is watched rather than the file, as
+// editors often save by replacing the file.
+pub struct ThemeWatcher {
+    path:     PathBuf,
+    active:   Theme,
+    receiver: Receiver<()>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ThemeWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, ThemeError> {
+        let path = path.into();
+        let active = Theme::load(&path)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let file_name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher = notify::recommended_watcher(
+            move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let is_theme = event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == file_name.as_deref());
+                if is_theme && !event.kind.is_access() {
+                    let _ = sender.send(());
+                }
+            },
+        )
+        .map_err(ThemeError::Watch)?;
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(ThemeError::Watch)?;
+
+        Ok(Self { path, active, receiver, _watcher: watcher })
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn active(&self) -> &Theme { &self.active }
+
+    // Reloads the theme if the file changed, returning the names whose
+    // values changed. A theme that fails to load is reported and the
+    // active one is kept.
+    pub fn poll(&mut self) -> Option<Result<Vec<String>, ThemeError>> {
+        let mut is_changed = false;
+        while self.receiver.try_recv().is_ok() {
+            is_changed = true;
+        }
+        if !is_changed {
+            return None;
+        }
+        let theme = match Theme::load(&self.path) {
+            Ok(theme) => theme,
+            Err(error) => return Some(Err(error)),
+        };
+        let changed = self.active.diff(&theme);
+        self.active = theme;
+
+        (!changed.is_empty()).then_some(Ok(changed))
+    }
+}
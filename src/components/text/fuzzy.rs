@@ -0,0 +1,173 @@
+use std::ops::Range;
+
+const MATCH_SCORE: i32 = 16;
+const BOUNDARY_BONUS: i32 = 10;
+const CONSECUTIVE_BONUS: i32 = 8;
+const MAX_LEADING_PENALTY: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FuzzyMatch {
+    pub score:     i32,
+    // Char indices of the matched chars in the candidate, ascending.
+    pub positions: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    // Byte ranges of the matched runs in `candidate`, for highlighting.
+    #[must_use]
+    pub fn ranges(&self, candidate: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut positions = self.positions.iter().peekable();
+        for (index, (offset, char)) in candidate.char_indices().enumerate() {
+            if positions.next_if_eq(&&index).is_none() {
+                continue;
+            }
+            let end = offset + char.len_utf8();
+            match ranges.last_mut() {
+                Some(range) if range.end == offset => range.end = end,
+                _ => ranges.push(offset..end),
+            }
+        }
+        ranges
+    }
+}
+
+// Matches `pattern` as a case-insensitive subsequence of `candidate`,
+// ignoring whitespace in the pattern. Among the possible matches the
+// shortest one is picked, scored higher for matches at word boundaries
+// and in runs, and lower for gaps and a late start.
+#[must_use]
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .map(fold_case)
+        .collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold_case).collect();
+
+    // The earliest end of a match, then the latest start before it.
+    let mut matched = 0;
+    let end = folded.iter().position(|char| {
+        matched += usize::from(*char == pattern[matched]);
+        matched == pattern.len()
+    })?;
+    let mut positions = vec![0; pattern.len()];
+    let mut remaining = pattern.len();
+    for index in (0..=end).rev() {
+        if folded[index] == pattern[remaining - 1] {
+            remaining -= 1;
+            positions[remaining] = index;
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+
+    let score = score(&chars, &positions);
+    Some(FuzzyMatch { score, positions })
+}
+
+// Matches every candidate, best first; equal scores keep their order.
+#[must_use]
+pub fn fuzzy_filter<I, S>(
+    pattern: &str,
+    candidates: I,
+) -> Vec<(usize, FuzzyMatch)>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut matches: Vec<(usize, FuzzyMatch)> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            fuzzy_match(pattern, candidate.as_ref())
+                .map(|found| (index, found))
+        })
+        .collect();
+    matches.sort_by_key(|(_, found)| std::cmp::Reverse(found.score));
+
+    matches
+}
+
+fn fold_case(char: char) -> char { char.to_lowercase().next().unwrap_or(char) }
+
+fn is_boundary(chars: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|index| chars[index]) else {
+        return true;
+    };
+    let current = chars[index];
+
+    !previous.is_alphanumeric() && current.is_alphanumeric()
+        || previous.is_lowercase() && current.is_uppercase()
+        || !previous.is_numeric() && current.is_numeric()
+}
+
+fn score(chars: &[char], positions: &[usize]) -> i32 {
+    let mut score = 0;
+    for (order, &index) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        if is_boundary(chars, index) {
+            score += BOUNDARY_BONUS;
+        }
+        if order > 0 && positions[order - 1] + 1 == index {
+            score += CONSECUTIVE_BONUS;
+        }
+    }
+    let (first, last) = (positions[0], positions[positions.len() - 1]);
+    let gaps = (last - first + 1 - positions.len()) as i32;
+    let leading = (first as i32).min(MAX_LEADING_PENALTY);
+
+    score - gaps - leading
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{fuzzy_filter, fuzzy_match};
+
+    #[rstest]
+    #[case("fb", "foo_bar", Some(vec![0, 4]))]
+    #[case("FB", "foo_bar", Some(vec![0, 4]))]
+    #[case("oba", "foo_bar", Some(vec![2, 4, 5]))]
+    #[case("ab", "a_ab", Some(vec![2, 3]))]
+    #[case("o b", "foo_bar", Some(vec![2, 4]))]
+    #[case("bf", "foo_bar", None)]
+    #[case("", "foo_bar", Some(vec![]))]
+    fn test_fuzzy_match_positions(
+        #[case] pattern: &str,
+        #[case] candidate: &str,
+        #[case] expected: Option<Vec<usize>>,
+    ) {
+        let found = fuzzy_match(pattern, candidate);
+        assert_eq!(found.map(|found| found.positions), expected);
+    }
+
+    #[rstest]
+    fn test_fuzzy_filter_ranks_boundaries_and_runs() {
+        let candidates = ["xopenfile", "open_file", "of", "OpenFile"];
+        let ranked: Vec<usize> = fuzzy_filter("of", candidates)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(ranked, [2, 3, 1, 0]);
+    }
+
+    #[rstest]
+    #[case("fb", "foo_bar", vec![0..1, 4..5])]
+    #[case("bar", "foo_bar", vec![4..7])]
+    #[case("éb", "é_b", vec![0..2, 3..4])]
+    fn test_fuzzy_match_ranges(
+        #[case] pattern: &str,
+        #[case] candidate: &str,
+        #[case] expected: Vec<std::ops::Range<usize>>,
+    ) {
+        let found = fuzzy_match(pattern, candidate).unwrap();
+        assert_eq!(found.ranges(candidate), expected);
+    }
+}
@@ -0,0 +1,554 @@
+use std::{collections::HashMap, hash::Hash};
+
+use glam::Mat4;
+
+use crate::{
+    components::common::build_model,
+    core::{Image, Renderer, TextRasterOptions, TextureId},
+};
+
+// Where a glyph lives: the page texture to bind when drawing it and its
+// rect there, in texels and in UVs (x, y, width, height).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    pub page:       usize,
+    pub texture_id: TextureId,
+    pub rect:       [u32; 4],
+    pub uv_rect:    [f32; 4],
+}
+
+impl AtlasGlyph {
+    // An image drawing the glyph's texels one to one at `position`, in
+    // `color`, with the options' coverage correction.
+    #[must_use]
+    pub fn build_image(
+        &self,
+        position: [f32; 2],
+        color: [f32; 4],
+        options: &TextRasterOptions,
+        clip_rect: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Image {
+        let size = [self.rect[2], self.rect[3]].map(|size| size as f32 / dpr);
+        let (model, half_size) = build_model(size, position, dpr);
+        let mvp = view_projection * model;
+        let [u, v, width, height] = self.uv_rect;
+        Image::builder()
+            .mvp(mvp.to_cols_array_2d())
+            .tint_color(color)
+            .corner_radii([0.0; 4])
+            .clip_rect(clip_rect.map(|value| value * dpr))
+            .uv_transform([width, height, u, v])
+            .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+            .cutout([0.0; 4])
+            .coverage(options.coverage())
+            .build()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtlasStats {
+    pub page_count:       usize,
+    pub glyph_count:      usize,
+    // Texels taken by glyphs, with their padding, and texels in pages.
+    pub used_area:        u64,
+    pub capacity_area:    u64,
+    pub hits:             u64,
+    pub misses:           u64,
+    pub evictions:        u64,
+    pub defragmentations: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shelf {
+    y:           u32,
+    height:      u32,
+    // Where the next glyph on the shelf goes.
+    x:           u32,
+    glyph_count: usize,
+}
+
+// Packs glyphs left to right on shelves stacked top to bottom. A shelf
+// only takes glyphs again once all of its glyphs are freed, which is
+// the waste `GlyphAtlas::defragment` reclaims.
+#[derive(Debug, Clone, Default)]
+struct ShelfPacker {
+    size:    u32,
+    shelves: Vec<Shelf>,
+    next_y:  u32,
+}
+
+impl ShelfPacker {
+    fn new(size: u32) -> Self { Self { size, ..Default::default() } }
+
+    // Returns the shelf and the position.
+    fn allocate(&mut self, size: [u32; 2]) -> Option<(usize, [u32; 2])> {
+        let [width, height] = size;
+        if width > self.size || height > self.size {
+            return None;
+        }
+        // The shortest shelf that fits wastes the least height.
+        let best = self
+            .shelves
+            .iter()
+            .enumerate()
+            .filter(|(_, shelf)| {
+                shelf.height >= height && shelf.x + width <= self.size
+            })
+            .min_by_key(|(_, shelf)| shelf.height)
+            .map(|(index, _)| index);
+        let index = match best {
+            Some(index) => index,
+            None => {
+                if self.next_y + height > self.size {
+                    return None;
+                }
+                self.shelves.push(Shelf {
+                    y: self.next_y,
+                    height,
+                    x: 0,
+                    glyph_count: 0,
+                });
+                self.next_y += height;
+                self.shelves.len() - 1
+            }
+        };
+        let shelf = &mut self.shelves[index];
+        let position = [shelf.x, shelf.y];
+        shelf.x += width;
+        shelf.glyph_count += 1;
+
+        Some((index, position))
+    }
+
+    fn free(&mut self, shelf: usize) {
+        let Some(shelf) = self.shelves.get_mut(shelf) else { return };
+        shelf.glyph_count = shelf.glyph_count.saturating_sub(1);
+        if shelf.glyph_count == 0 {
+            shelf.x = 0;
+        }
+    }
+}
+
+struct AtlasPage {
+    texture_id: TextureId,
+    packer:     ShelfPacker,
+}
+
+struct AtlasEntry {
+    glyph:     AtlasGlyph,
+    shelf:     usize,
+    // Kept to move the glyph when the pages are defragmented.
+    size:      [u32; 2],
+    rgba:      Vec<u8>,
+    last_used: u64,
+}
+
+// A glyph cache over square RGBA page textures. Glyphs not drawn in the
+// current frame are evicted least recently used first once every page
+// is full, and when the space lost to packing passes
+// `defragment_threshold` of the pages, the glyphs are repacked. That
+// moves them, so `generation` changes and callers look their glyphs up
+// again. Drawing binds each glyph's page texture, so text spanning pages
+// is split into one draw per page by the image renderer's batching.
+pub struct GlyphAtlas<K> {
+    page_size:            u32,
+    max_pages:            usize,
+    // Transparent texels around each glyph, against filtering bleed.
+    padding:              u32,
+    defragment_threshold: f32,
+    pages:                Vec<AtlasPage>,
+    entries:              HashMap<K, AtlasEntry>,
+    frame:                u64,
+    generation:           u64,
+    stats:                AtlasStats,
+}
+
+impl<K: Clone + Eq + Hash> GlyphAtlas<K> {
+    #[must_use]
+    pub fn new(page_size: u32, max_pages: usize) -> Self {
+        Self {
+            page_size:            page_size.max(1),
+            max_pages:            max_pages.max(1),
+            padding:              1,
+            defragment_threshold: 0.25,
+            pages:                Vec::new(),
+            entries:              HashMap::new(),
+            frame:                0,
+            generation:           0,
+            stats:                AtlasStats::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub const fn set_padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    #[inline(always)]
+    pub const fn set_defragment_threshold(&mut self, threshold: f32) {
+        self.defragment_threshold = threshold;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn generation(&self) -> u64 { self.generation }
+
+    #[must_use]
+    pub fn stats(&self) -> AtlasStats {
+        AtlasStats {
+            page_count: self.pages.len(),
+            glyph_count: self.entries.len(),
+            used_area: self.used_area(),
+            capacity_area: self.capacity_area(),
+            ..self.stats
+        }
+    }
+
+    // Glyphs looked up after this aren't evicted until the next frame.
+    pub fn begin_frame(&mut self) { self.frame += 1; }
+
+    pub fn get(&mut self, key: &K) -> Option<AtlasGlyph> {
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.frame;
+                self.stats.hits += 1;
+                Some(entry.glyph)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Adds a rasterized glyph, making room as needed. Returns `None` if
+    // it is larger than a page, or if every glyph is in use this frame.
+    pub fn insert(
+        &mut self,
+        key: K,
+        size: [u32; 2],
+        rgba: Vec<u8>,
+        renderer: &mut Renderer,
+    ) -> Option<AtlasGlyph> {
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.frame;
+            return Some(entry.glyph);
+        }
+        let padded = size.map(|size| size + self.padding * 2);
+        if padded[0] > self.page_size || padded[1] > self.page_size {
+            return None;
+        }
+
+        let (page, shelf, position) = loop {
+            if let Some(slot) = self.allocate(padded) {
+                break slot;
+            }
+            if self.pages.len() < self.max_pages {
+                self.add_page(renderer);
+                continue;
+            }
+            if !self.evict_least_recent() {
+                return None;
+            }
+            if self.waste() >= self.defragment_threshold {
+                self.defragment(renderer);
+            }
+        };
+
+        let entry = AtlasEntry {
+            glyph: self.glyph_at(page, position, size),
+            shelf,
+            size,
+            rgba,
+            last_used: self.frame,
+        };
+        self.upload(&entry, renderer);
+        let glyph = entry.glyph;
+        self.entries.insert(key, entry);
+
+        Some(glyph)
+    }
+
+    pub fn remove(&mut self, key: &K) -> bool {
+        let Some(entry) = self.entries.remove(key) else { return false };
+        self.pages[entry.glyph.page].packer.free(entry.shelf);
+        true
+    }
+
+    // Repacks every glyph from scratch, tallest first, and drops the
+    // pages left empty.
+    pub fn defragment(&mut self, renderer: &mut Renderer) {
+        let mut keys: Vec<K> = self.entries.keys().cloned().collect();
+        // Ties keep their current placement order, so repacking doesn't
+        // depend on the map's iteration order.
+        keys.sort_by_key(|key| {
+            let entry = &self.entries[key];
+            let [x, y, ..] = entry.glyph.rect;
+            (std::cmp::Reverse(entry.size[1]), entry.glyph.page, y, x)
+        });
+        let padding = self.padding;
+        let sizes: Vec<[u32; 2]> = keys
+            .iter()
+            .map(|key| self.entries[key].size.map(|size| size + padding * 2))
+            .collect();
+        let (packers, slots) =
+            repack(&sizes, self.page_size, self.pages.len());
+
+        for (key, slot) in keys.into_iter().zip(slots) {
+            match slot {
+                Some((page, shelf, position)) => {
+                    let size = self.entries[&key].size;
+                    let glyph = self.glyph_at(page, position, size);
+                    if let Some(entry) = self.entries.get_mut(&key) {
+                        entry.glyph = glyph;
+                        entry.shelf = shelf;
+                    }
+                }
+                None => {
+                    self.entries.remove(&key);
+                    self.stats.evictions += 1;
+                }
+            }
+        }
+        for (page, packer) in self.pages.iter_mut().zip(packers) {
+            page.packer = packer;
+        }
+        while let Some(page) =
+            self.pages.pop_if(|page| page.packer.shelves.is_empty())
+        {
+            renderer.remove_texture(page.texture_id);
+        }
+
+        let page_size = self.page_size;
+        let mut page_rgba: Vec<Vec<u8>> =
+            vec![
+                vec![0; (page_size * page_size * 4) as usize];
+                self.pages.len()
+            ];
+        for entry in self.entries.values() {
+            let [x, y, width, height] = entry.glyph.rect;
+            let rgba = &mut page_rgba[entry.glyph.page];
+            for row in 0..height {
+                let from = (row * width * 4) as usize;
+                let to = (((y + row) * page_size + x) * 4) as usize;
+                rgba[to..to + (width * 4) as usize].copy_from_slice(
+                    &entry.rgba[from..from + (width * 4) as usize],
+                );
+            }
+        }
+        for (page, rgba) in self.pages.iter().zip(page_rgba) {
+            renderer.write_texture(
+                page.texture_id,
+                [0, 0],
+                [page_size; 2],
+                &rgba,
+            );
+        }
+
+        self.generation += 1;
+        self.stats.defragmentations += 1;
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for page in &self.pages {
+            renderer.remove_texture(page.texture_id);
+        }
+    }
+
+    fn allocate(
+        &mut self,
+        size: [u32; 2],
+    ) -> Option<(usize, usize, [u32; 2])> {
+        self.pages.iter_mut().enumerate().find_map(|(index, page)| {
+            let (shelf, position) = page.packer.allocate(size)?;
+            Some((index, shelf, position))
+        })
+    }
+
+    fn add_page(&mut self, renderer: &mut Renderer) {
+        let size = [self.page_size; 2];
+        let rgba = vec![0; (self.page_size * self.page_size * 4) as usize];
+        let texture_id = renderer.add_texture(size, &rgba);
+        self.pages.push(AtlasPage {
+            texture_id,
+            packer: ShelfPacker::new(self.page_size),
+        });
+    }
+
+    // Evicts the least recently used glyph not used this frame.
+    fn evict_least_recent(&mut self) -> bool {
+        let victim = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.last_used < self.frame)
+            .min_by_key(|(_, entry)| {
+                let [x, y, ..] = entry.glyph.rect;
+                (entry.last_used, entry.glyph.page, y, x)
+            })
+            .map(|(key, _)| key.clone());
+        let Some(key) = victim else { return false };
+        self.remove(&key);
+        self.stats.evictions += 1;
+
+        true
+    }
+
+    fn glyph_at(
+        &self,
+        page: usize,
+        position: [u32; 2],
+        size: [u32; 2],
+    ) -> AtlasGlyph {
+        let rect = [
+            position[0] + self.padding,
+            position[1] + self.padding,
+            size[0],
+            size[1],
+        ];
+        AtlasGlyph {
+            page,
+            texture_id: self.pages[page].texture_id,
+            rect,
+            uv_rect: rect.map(|value| value as f32 / self.page_size as f32),
+        }
+    }
+
+    // Writes the glyph with its padding cleared, since the space may
+    // have held another glyph.
+    fn upload(&self, entry: &AtlasEntry, renderer: &mut Renderer) {
+        let [x, y, ..] = entry.glyph.rect;
+        let padded = pad_rgba(&entry.rgba, entry.size, self.padding);
+        renderer.write_texture(
+            self.pages[entry.glyph.page].texture_id,
+            [x - self.padding, y - self.padding],
+            entry.size.map(|size| size + self.padding * 2),
+            &padded,
+        );
+    }
+
+    fn used_area(&self) -> u64 {
+        self.entries
+            .values()
+            .map(|entry| {
+                let [width, height] =
+                    entry.size.map(|size| (size + self.padding * 2) as u64);
+                width * height
+            })
+            .sum()
+    }
+
+    fn capacity_area(&self) -> u64 {
+        self.pages.len() as u64 * (self.page_size as u64).pow(2)
+    }
+
+    // The share of the pages that is neither used nor free to allocate.
+    fn waste(&self) -> f32 {
+        let capacity = self.capacity_area();
+        if capacity == 0 {
+            return 0.0;
+        }
+        let free: u64 = self
+            .pages
+            .iter()
+            .map(|page| {
+                let packer = &page.packer;
+                let below = (packer.size - packer.next_y) as u64;
+                let beside: u64 = packer
+                    .shelves
+                    .iter()
+                    .map(|shelf| {
+                        ((packer.size - shelf.x) * shelf.height) as u64
+                    })
+                    .sum();
+                below * packer.size as u64 + beside
+            })
+            .sum();
+        let wasted = capacity.saturating_sub(free + self.used_area());
+        wasted as f32 / capacity as f32
+    }
+}
+
+type Slot = Option<(usize, usize, [u32; 2])>;
+
+// Packs the sizes, in order, into at most `page_count` fresh pages, and
+// returns the packers and each size's page, shelf and position.
+
+fn repack(
+    sizes: &[[u32; 2]],
+    page_size: u32,
+    page_count: usize,
+) -> (Vec<ShelfPacker>, Vec<Slot>) {
+    let mut packers = vec![ShelfPacker::new(page_size); page_count];
+    let slots = sizes
+        .iter()
+        .map(|size| {
+            packers.iter_mut().enumerate().find_map(|(page, packer)| {
+                let (shelf, position) = packer.allocate(*size)?;
+                Some((page, shelf, position))
+            })
+        })
+        .collect();
+    (packers, slots)
+}
+
+fn pad_rgba(rgba: &[u8], size: [u32; 2], padding: u32) -> Vec<u8> {
+    let [width, height] = size.map(|size| size as usize);
+    let padding = padding as usize;
+    let padded_width = width + padding * 2;
+    let mut padded = vec![0; padded_width * (height + padding * 2) * 4];
+    for row in 0..height {
+        let to = ((row + padding) * padded_width + padding) * 4;
+        padded[to..to + width * 4]
+            .copy_from_slice(&rgba[row * width * 4..(row + 1) * width * 4]);
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ShelfPacker, pad_rgba, repack};
+
+    #[rstest]
+    fn test_shelf_packer() {
+        let mut packer = ShelfPacker::new(16);
+        assert_eq!(packer.allocate([8, 8]), Some((0, [0, 0])));
+        assert_eq!(packer.allocate([8, 4]), Some((0, [8, 0])));
+        // The first shelf is full, so a new one starts below it.
+        assert_eq!(packer.allocate([16, 8]), Some((1, [0, 8])));
+        assert_eq!(packer.allocate([8, 8]), None);
+
+        // A shelf only takes glyphs again once it is empty.
+        packer.free(0);
+        assert_eq!(packer.allocate([8, 8]), None);
+        packer.free(0);
+        assert_eq!(packer.allocate([8, 8]), Some((0, [0, 0])));
+    }
+
+    #[rstest]
+    #[case(1, vec![Some((0, 0, [0, 0])), Some((0, 0, [8, 0])), None])]
+    #[case(
+        2,
+        vec![Some((0, 0, [0, 0])), Some((0, 0, [8, 0])), Some((1, 0, [0, 0]))]
+    )]
+    fn test_repack(
+        #[case] page_count: usize,
+        #[case] expected: Vec<Option<(usize, usize, [u32; 2])>>,
+    ) {
+        let sizes = [[8, 16], [8, 12], [4, 4]];
+        let (_, slots) = repack(&sizes, 16, page_count);
+        assert_eq!(slots, expected);
+    }
+
+    #[rstest]
+    #[case(0, vec![255])]
+    #[case(1, vec![0, 0, 0, 0, 255, 0, 0, 0, 0])]
+    fn test_pad_rgba(#[case] padding: u32, #[case] expected: Vec<u8>) {
+        let padded = pad_rgba(&[255; 4], [1, 1], padding);
+        let alphas: Vec<u8> = padded.chunks(4).map(|pixel| pixel[3]).collect();
+        assert_eq!(alphas, expected);
+    }
+}
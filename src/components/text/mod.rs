@@ -0,0 +1,9 @@
+mod fuzzy;
+mod glyph_atlas;
+mod shaping;
+mod text_edit;
+
+pub use fuzzy::{FuzzyMatch, fuzzy_filter, fuzzy_match};
+pub use glyph_atlas::{AtlasGlyph, AtlasStats, GlyphAtlas};
+pub use shaping::{ShapingService, TextShaper};
+pub use text_edit::{TextEditEvent, TextEditState};
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+};
+
+use rayon::ThreadPool;
+
+use crate::instrument::{counter, span};
+
+// Shapes and wraps text into a layout, on a worker thread. `max_width`
+// is `None` for text that doesn't wrap.
+pub trait TextShaper<L>: Send + Sync {
+    fn shape(&self, text: &str, max_width: Option<f32>) -> L;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LayoutRequest {
+    text:      String,
+    max_width: Option<f32>,
+}
+
+struct LayoutEntry<L> {
+    // The generation of the latest request, shared with the workers so
+    // that superseded requests are skipped before they are shaped.
+    generation: Arc<AtomicU64>,
+    request:    LayoutRequest,
+    layout:     Option<L>,
+    is_pending: bool,
+}
+
+type LayoutResult<K, L> = (K, u64, L);
+
+// Keeps shaping long text off the frame: each key's layout is requested
+// here and shaped on the thread pool, and `layout` keeps returning the
+// previous one (or `None`, for the caller's placeholder) until the
+// result arrives. A result only applies if no newer request for its key
+// was made since; older ones are dropped.
+pub struct ShapingService<K, L> {
+    shaper:      Arc<dyn TextShaper<L>>,
+    thread_pool: Arc<ThreadPool>,
+    sender:      Sender<LayoutResult<K, L>>,
+    receiver:    Receiver<LayoutResult<K, L>>,
+    entries:     HashMap<K, LayoutEntry<L>>,
+    // Counts requests across all keys, so that a key removed and
+    // requested again never reuses the generation of a result in flight.
+    generation:  u64,
+}
+
+impl<K, L> ShapingService<K, L>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+    L: Send + 'static,
+{
+    #[must_use]
+    pub fn new(
+        shaper: Arc<dyn TextShaper<L>>,
+        thread_pool: Arc<ThreadPool>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            shaper,
+            thread_pool,
+            sender,
+            receiver,
+            entries: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    // The latest layout that arrived for the key, which may be for an
+    // older request while a newer one is pending.
+    #[must_use]
+    pub fn layout(&self, key: &K) -> Option<&L> {
+        self.entries.get(key)?.layout.as_ref()
+    }
+
+    #[must_use]
+    pub fn is_pending(&self, key: &K) -> bool {
+        self.entries.get(key).is_some_and(|entry| entry.is_pending)
+    }
+
+    // Queues shaping the text for the key. Asking again for the text and
+    // width last asked for does nothing.
+    pub fn request(
+        &mut self,
+        key: K,
+        text: impl Into<String>,
+        max_width: Option<f32>,
+    ) {
+        let request = LayoutRequest { text: text.into(), max_width };
+        let entry =
+            self.entries
+                .entry(key.clone())
+                .or_insert_with(|| LayoutEntry {
+                    generation: Arc::new(AtomicU64::new(0)),
+                    request:    request.clone(),
+                    layout:     None,
+                    is_pending: false,
+                });
+        let is_shaped = entry.layout.is_some() || entry.is_pending;
+        if is_shaped && entry.request == request {
+            return;
+        }
+        self.generation += 1;
+        let generation = self.generation;
+        entry.generation.store(generation, Ordering::Release);
+        entry.request = request.clone();
+        entry.is_pending = true;
+
+        let latest = entry.generation.clone();
+        let shaper = self.shaper.clone();
+        let sender = self.sender.clone();
+        self.thread_pool.spawn(move || {
+            if latest.load(Ordering::Acquire) != generation {
+                return;
+            }
+            span!("shape", text_len = request.text.len());
+            let layout = shaper.shape(&request.text, request.max_width);
+            let _ = sender.send((key, generation, layout));
+        });
+    }
+
+    // Forgets the key; its pending results are dropped when they arrive.
+    pub fn remove(&mut self, key: &K) -> Option<L> {
+        let entry = self.entries.remove(key)?;
+        // No request has generation 0, so queued ones are skipped.
+        entry.generation.store(0, Ordering::Release);
+        entry.layout
+    }
+
+    // Applies the results that arrived, returning the keys whose layout
+    // changed.
+    pub fn poll(&mut self) -> Vec<K> {
+        let mut changed = Vec::new();
+        while let Ok((key, generation, layout)) = self.receiver.try_recv() {
+            if self.receive(&key, generation, layout) {
+                changed.push(key);
+            }
+        }
+        counter!(shaped_layouts, changed.len());
+        changed
+    }
+
+    fn receive(&mut self, key: &K, generation: u64, layout: L) -> bool {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return false;
+        };
+        if entry.generation.load(Ordering::Acquire) != generation {
+            return false;
+        }
+        entry.layout = Some(layout);
+        entry.is_pending = false;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use rayon::ThreadPoolBuilder;
+    use rstest::rstest;
+
+    use super::{ShapingService, TextShaper};
+
+    struct LineCounter;
+
+    impl TextShaper<usize> for LineCounter {
+        fn shape(&self, text: &str, _max_width: Option<f32>) -> usize {
+            text.lines().count()
+        }
+    }
+
+    fn service() -> ShapingService<u32, usize> {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build();
+        ShapingService::new(
+            Arc::new(LineCounter),
+            Arc::new(thread_pool.unwrap()),
+        )
+    }
+
+    #[rstest]
+    fn test_stale_results_are_dropped() {
+        let mut service = service();
+        service.request(0, "a\nb", None);
+        service.request(0, "a\nb\nc", None);
+        assert!(service.is_pending(&0));
+
+        // The first request's result comes in after the second was made.
+        assert!(!service.receive(&0, 1, 2));
+        assert_eq!(service.layout(&0), None);
+
+        assert!(service.receive(&0, 2, 3));
+        assert_eq!(service.layout(&0), Some(&3));
+        assert!(!service.is_pending(&0));
+    }
+
+    #[rstest]
+    fn test_previous_layout_is_kept_while_pending() {
+        let mut service = service();
+        service.request(0, "a", None);
+        assert!(service.receive(&0, 1, 1));
+
+        service.request(0, "a\nb", Some(100.0));
+        assert!(service.is_pending(&0));
+        assert_eq!(service.layout(&0), Some(&1));
+
+        // Asking for the same text again doesn't queue another request.
+        service.request(0, "a\nb", Some(100.0));
+        assert!(service.receive(&0, 2, 2));
+    }
+
+    #[rstest]
+    fn test_removed_keys_ignore_results() {
+        let mut service = service();
+        service.request(0, "a", None);
+        assert_eq!(service.remove(&0), None);
+        assert!(!service.receive(&0, 1, 1));
+    }
+
+    #[rstest]
+    fn test_requests_after_remove_ignore_earlier_results() {
+        let mut service = service();
+        service.request(0, "a", None);
+        service.remove(&0);
+        service.request(0, "a\nb\nc", None);
+
+        // The first request's result comes in after the second was made.
+        assert!(!service.receive(&0, 1, 1));
+        assert_eq!(service.layout(&0), None);
+
+        let started = Instant::now();
+        while service.layout(&0).is_none() {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            service.poll();
+            thread::yield_now();
+        }
+        assert_eq!(service.layout(&0), Some(&3));
+    }
+}
@@ -0,0 +1,302 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEditEvent {
+    TextChanged,
+    SelectionChanged,
+}
+
+// The editing state behind a text field: the text, a selection made of an
+// anchor and a caret (byte offsets on char boundaries), and the horizontal
+// scroll offset. Caret geometry is computed from per-char advances supplied
+// by whoever lays the text out.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextEditState {
+    text:          String,
+    anchor:        usize,
+    caret:         usize,
+    scroll_offset: f32,
+    events:        Vec<TextEditEvent>,
+}
+
+impl TextEditState {
+    #[must_use]
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let caret = text.len();
+        Self {
+            text,
+            anchor: caret,
+            caret,
+            scroll_offset: 0.0,
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn text(&self) -> &str { &self.text }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn caret(&self) -> usize { self.caret }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn anchor(&self) -> usize { self.anchor }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scroll_offset(&self) -> f32 { self.scroll_offset }
+
+    // The selected byte range, ordered regardless of the selection's
+    // direction.
+    #[must_use]
+    #[inline(always)]
+    pub fn selection(&self) -> Range<usize> {
+        self.anchor.min(self.caret)..self.anchor.max(self.caret)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn selected_text(&self) -> &str { &self.text[self.selection()] }
+
+    // Changes since the last call, for popups and validators that follow
+    // the editor.
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<TextEditEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text == self.text {
+            return;
+        }
+        self.text = text;
+        self.push_event(TextEditEvent::TextChanged);
+
+        let caret = self.text.len();
+        self.set_anchor_and_caret(caret, caret);
+    }
+
+    // Selects `range`, with the caret at its end. Offsets are clamped into
+    // the text and down to char boundaries.
+    pub fn set_selection(&mut self, range: Range<usize>) {
+        self.set_anchor_and_caret(range.start, range.end);
+    }
+
+    #[inline(always)]
+    pub fn set_caret(&mut self, caret: usize) {
+        self.set_anchor_and_caret(caret, caret);
+    }
+
+    #[inline(always)]
+    pub fn select_all(&mut self) {
+        self.set_anchor_and_caret(0, self.text.len());
+    }
+
+    // Moves the caret one char to the left or right, extending the
+    // selection or collapsing it the way text fields do.
+    pub fn move_caret(&mut self, is_forward: bool, is_extending: bool) {
+        let selection = self.selection();
+        let caret = match (is_extending, selection.is_empty(), is_forward) {
+            (false, false, false) => selection.start,
+            (false, false, true) => selection.end,
+            (_, _, false) => previous_char_boundary(&self.text, self.caret),
+            (_, _, true) => next_char_boundary(&self.text, self.caret),
+        };
+        let anchor = match is_extending {
+            true => self.anchor,
+            false => caret,
+        };
+        self.set_anchor_and_caret(anchor, caret);
+    }
+
+    // Replaces the selection with `text`, leaving the caret after it.
+    pub fn insert(&mut self, text: &str) {
+        let selection = self.selection();
+        if selection.is_empty() && text.is_empty() {
+            return;
+        }
+        self.text.replace_range(selection.clone(), text);
+        self.push_event(TextEditEvent::TextChanged);
+
+        let caret = selection.start + text.len();
+        self.set_anchor_and_caret(caret, caret);
+    }
+
+    // Deletes the selection, or the char before or after the caret.
+    pub fn delete(&mut self, is_forward: bool) {
+        if self.selection().is_empty() {
+            let caret = match is_forward {
+                true => next_char_boundary(&self.text, self.caret),
+                false => previous_char_boundary(&self.text, self.caret),
+            };
+            self.anchor = caret;
+        }
+        self.insert("");
+    }
+
+    // The caret's x offset from the left edge of the visible text.
+    // `advances` holds the advance of every char of the text.
+    #[must_use]
+    pub fn caret_position(&self, advances: &[f32]) -> f32 {
+        text_offset(&self.text, advances, self.caret) - self.scroll_offset
+    }
+
+    // Where the selection starts and ends, as `caret_position` does for
+    // the caret.
+    #[must_use]
+    pub fn selection_span(&self, advances: &[f32]) -> [f32; 2] {
+        let selection = self.selection();
+        [selection.start, selection.end].map(|index| {
+            text_offset(&self.text, advances, index) - self.scroll_offset
+        })
+    }
+
+    // Scrolls the least amount needed for the caret to be within
+    // `visible_width`. Returns whether the scroll offset changed.
+    pub fn scroll_to_caret(
+        &mut self,
+        advances: &[f32],
+        visible_width: f32,
+    ) -> bool {
+        let caret_offset = text_offset(&self.text, advances, self.caret);
+        let text_width = advances.iter().sum::<f32>();
+        let max_offset = (text_width - visible_width).max(0.0);
+
+        let scroll_offset = self
+            .scroll_offset
+            .max(caret_offset - visible_width)
+            .min(caret_offset)
+            .clamp(0.0, max_offset);
+        let is_changed = scroll_offset != self.scroll_offset;
+        self.scroll_offset = scroll_offset;
+
+        is_changed
+    }
+
+    fn set_anchor_and_caret(&mut self, anchor: usize, caret: usize) {
+        let anchor = self.text.floor_char_boundary(anchor);
+        let caret = self.text.floor_char_boundary(caret);
+        if (anchor, caret) == (self.anchor, self.caret) {
+            return;
+        }
+        self.anchor = anchor;
+        self.caret = caret;
+        self.push_event(TextEditEvent::SelectionChanged);
+    }
+
+    fn push_event(&mut self, event: TextEditEvent) {
+        if !self.events.contains(&event) {
+            self.events.push(event);
+        }
+    }
+}
+
+fn previous_char_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+fn next_char_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map_or(index, |char| index + char.len_utf8())
+}
+
+// The sum of the advances of the chars before the byte offset `index`.
+fn text_offset(text: &str, advances: &[f32], index: usize) -> f32 {
+    let char_count = text[..index].chars().count();
+    advances.iter().take(char_count).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use std::ops::Range;
+
+    use super::{TextEditEvent, TextEditState};
+
+    #[rstest]
+    #[case(0, 3, false, false, 0, 0)]
+    #[case(0, 3, true, false, 3, 3)]
+    #[case(2, 2, false, false, 1, 1)]
+    #[case(2, 2, true, true, 2, 3)]
+    #[case(3, 1, false, true, 3, 0)]
+    fn test_text_edit_move_caret(
+        #[case] anchor: usize,
+        #[case] caret: usize,
+        #[case] is_forward: bool,
+        #[case] is_extending: bool,
+        #[case] expected_anchor: usize,
+        #[case] expected_caret: usize,
+    ) {
+        let mut state = TextEditState::new("hello");
+        state.set_selection(Range { start: anchor, end: caret });
+        state.move_caret(is_forward, is_extending);
+
+        assert_eq!(state.anchor(), expected_anchor);
+        assert_eq!(state.caret(), expected_caret);
+    }
+
+    #[rstest]
+    fn test_text_edit_insert_and_delete() {
+        let mut state = TextEditState::new("héllo");
+        state.set_selection(1..3);
+        let _ = state.take_events();
+
+        state.insert("a");
+        assert_eq!(state.text(), "hallo");
+        assert_eq!(state.caret(), 2);
+        assert_eq!(
+            state.take_events(),
+            [TextEditEvent::TextChanged, TextEditEvent::SelectionChanged,]
+        );
+
+        state.delete(false);
+        state.delete(true);
+        assert_eq!(state.text(), "hlo");
+        assert_eq!(state.caret(), 1);
+    }
+
+    #[rstest]
+    fn test_text_edit_set_selection_clamps_to_char_boundaries() {
+        let mut state = TextEditState::new("héllo");
+        state.set_selection(2..100);
+
+        assert_eq!(state.selection(), 1..6);
+        assert_eq!(state.selected_text(), "éllo");
+    }
+
+    #[rstest]
+    #[case(5, 0.0, 20.0)]
+    #[case(10, 0.0, 70.0)]
+    #[case(0, 60.0, 0.0)]
+    #[case(4, 60.0, 40.0)]
+    fn test_text_edit_scroll_to_caret(
+        #[case] caret: usize,
+        #[case] scroll_offset: f32,
+        #[case] expected: f32,
+    ) {
+        let mut state = TextEditState::new("0123456789");
+        state.scroll_offset = scroll_offset;
+        state.set_caret(caret);
+
+        state.scroll_to_caret(&[10.0; 10], 30.0);
+        assert_eq!(state.scroll_offset(), expected);
+        assert!((0.0..=30.0).contains(&state.caret_position(&[10.0; 10])));
+    }
+
+    #[rstest]
+    fn test_text_edit_selection_span() {
+        let mut state = TextEditState::new("héllo");
+        state.set_selection(Range { start: 6, end: 1 });
+        assert_eq!(state.selection_span(&[10.0; 5]), [10.0, 50.0]);
+    }
+}
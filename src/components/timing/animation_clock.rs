@@ -0,0 +1,272 @@
+use std::time::{Duration, Instant};
+
+use winit::window::Window;
+
+// Long stalls (a blocked event loop, a debugger) advance animations by at
+// most this much, rather than skipping them straight to their end.
+const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(100);
+
+// Advances animations by presentation time rather than by whenever user
+// code happens to run, so they move at the same speed on 60, 120 and
+// 144 Hz monitors and catch up evenly when frames are dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClock {
+    frame_interval: Option<Duration>,
+    max_delta:      Duration,
+    last_present:   Option<Instant>,
+    elapsed:        Duration,
+    delta:          Duration,
+    frame:          u64,
+    is_paused:      bool,
+}
+
+impl Default for AnimationClock {
+    fn default() -> Self { Self::new() }
+}
+
+impl AnimationClock {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            frame_interval: None,
+            max_delta:      DEFAULT_MAX_DELTA,
+            last_present:   None,
+            elapsed:        Duration::ZERO,
+            delta:          Duration::ZERO,
+            frame:          0,
+            is_paused:      false,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn elapsed(&self) -> Duration { self.elapsed }
+
+    // The step of the last tick, to pass to `tick(dt, ..)` of widgets.
+    #[must_use]
+    #[inline(always)]
+    pub const fn delta(&self) -> Duration { self.delta }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn frame(&self) -> u64 { self.frame }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_paused(&self) -> bool { self.is_paused }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn frame_interval(&self) -> Option<Duration> {
+        self.frame_interval
+    }
+
+    #[inline(always)]
+    pub const fn set_max_delta(&mut self, max_delta: Duration) {
+        self.max_delta = max_delta;
+    }
+
+    pub fn set_refresh_rate_millihertz(&mut self, refresh_rate: Option<u32>) {
+        self.frame_interval = refresh_rate
+            .filter(|refresh_rate| *refresh_rate > 0)
+            .map(|refresh_rate| {
+                Duration::from_nanos(
+                    1_000_000_000_000 / u64::from(refresh_rate),
+                )
+            });
+    }
+
+    // Picks up the refresh rate of the monitor the window is on; call it
+    // again on `WindowEvent::Moved` and `ScaleFactorChanged`.
+    pub fn update_monitor(&mut self, window: &Window) {
+        let refresh_rate = window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz());
+        self.set_refresh_rate_millihertz(refresh_rate);
+    }
+
+    // The next vblank after `now`, extrapolated from the last presented
+    // frame. Without a known refresh rate this is just `now`.
+    #[must_use]
+    pub fn expected_present_time(&self, now: Instant) -> Instant {
+        let (Some(last_present), Some(frame_interval)) =
+            (self.last_present, self.frame_interval)
+        else {
+            return now;
+        };
+        let since_last = now.saturating_duration_since(last_present);
+        let frames = (since_last.as_secs_f64() / frame_interval.as_secs_f64())
+            .ceil()
+            .max(1.0);
+
+        last_present + frame_interval.mul_f64(frames)
+    }
+
+    // Advances the clock to the time the upcoming frame is presented and
+    // returns the step animations should take.
+    pub fn tick(&mut self, present_time: Instant) -> Duration {
+        if self.is_paused {
+            return Duration::ZERO;
+        }
+        self.delta = match self.last_present {
+            Some(last_present) => frame_delta(
+                present_time.saturating_duration_since(last_present),
+                self.frame_interval,
+                self.max_delta,
+            ),
+            None => Duration::ZERO,
+        };
+        self.last_present = Some(present_time);
+        self.elapsed += self.delta;
+        self.frame += 1;
+
+        self.delta
+    }
+
+    // Advances the clock by exactly `dt`, regardless of refresh rate and
+    // `max_delta`, for stepped time.
+    pub fn step(&mut self, dt: Duration) -> Duration {
+        if self.is_paused {
+            return Duration::ZERO;
+        }
+        self.delta = dt;
+        self.last_present =
+            self.last_present.map(|last_present| last_present + dt);
+        self.elapsed += dt;
+        self.frame += 1;
+
+        self.delta
+    }
+
+    // Forgets the last presented frame, so the next tick doesn't account
+    // for the time in between.
+    #[inline(always)]
+    pub const fn reset(&mut self) {
+        self.last_present = None;
+        self.delta = Duration::ZERO;
+    }
+
+    #[inline(always)]
+    pub const fn pause(&mut self) {
+        self.is_paused = true;
+        self.reset();
+    }
+
+    // The paused time isn't counted: animations pick up where they were.
+    #[inline(always)]
+    pub const fn resume(&mut self) {
+        self.is_paused = false;
+        self.reset();
+    }
+}
+
+// Rounds the time between presents to whole refresh intervals: scheduling
+// jitter is absorbed, while a dropped frame still advances by two.
+fn frame_delta(
+    since_last: Duration,
+    frame_interval: Option<Duration>,
+    max_delta: Duration,
+) -> Duration {
+    let delta = match frame_interval {
+        Some(frame_interval) if !since_last.is_zero() => {
+            let frames = (since_last.as_secs_f64()
+                / frame_interval.as_secs_f64())
+            .round()
+            .max(1.0);
+            frame_interval.mul_f64(frames)
+        }
+        _ => since_last,
+    };
+    delta.min(max_delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use rstest::rstest;
+
+    use super::{AnimationClock, frame_delta};
+
+    const fn us(value: u64) -> Duration { Duration::from_micros(value) }
+
+    const MAX_DELTA: Duration = Duration::from_millis(100);
+
+    #[rstest]
+    #[case(us(16_000), Some(us(16_667)), us(16_667))]
+    #[case(us(17_500), Some(us(16_667)), us(16_667))]
+    #[case(us(33_000), Some(us(16_667)), us(33_334))]
+    #[case(us(4_000), Some(us(8_333)), us(8_333))]
+    #[case(us(12_000), None, us(12_000))]
+    #[case(us(0), Some(us(16_667)), us(0))]
+    #[case(us(500_000), Some(us(16_667)), MAX_DELTA)]
+    fn test_frame_delta(
+        #[case] since_last: Duration,
+        #[case] frame_interval: Option<Duration>,
+        #[case] expected: Duration,
+    ) {
+        assert_eq!(
+            frame_delta(since_last, frame_interval, MAX_DELTA),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_animation_clock_tick() {
+        let mut clock = AnimationClock::new();
+        clock.set_refresh_rate_millihertz(Some(125_000));
+        let start = Instant::now();
+
+        assert_eq!(clock.tick(start), Duration::ZERO);
+        assert_eq!(clock.tick(start + us(7_000)), us(8_000));
+        assert_eq!(clock.tick(start + us(25_000)), us(16_000));
+        assert_eq!(clock.elapsed(), us(24_000));
+        assert_eq!(clock.frame(), 3);
+    }
+
+    #[rstest]
+    fn test_animation_clock_expected_present_time() {
+        let mut clock = AnimationClock::new();
+        clock.set_refresh_rate_millihertz(Some(100_000));
+        let start = Instant::now();
+        clock.tick(start);
+
+        assert_eq!(
+            clock.expected_present_time(start + us(3_000)),
+            start + us(10_000),
+        );
+        assert_eq!(
+            clock.expected_present_time(start + us(12_000)),
+            start + us(20_000),
+        );
+    }
+
+    #[rstest]
+    fn test_animation_clock_pause() {
+        let mut clock = AnimationClock::new();
+        clock.set_refresh_rate_millihertz(Some(100_000));
+        let start = Instant::now();
+        clock.tick(start);
+        clock.tick(start + us(10_000));
+
+        clock.pause();
+        assert_eq!(clock.tick(start + us(20_000)), Duration::ZERO);
+
+        clock.resume();
+        assert_eq!(clock.tick(start + us(5_000_000)), Duration::ZERO);
+        assert_eq!(clock.tick(start + us(5_010_000)), us(10_000));
+        assert_eq!(clock.elapsed(), us(20_000));
+    }
+
+    #[rstest]
+    fn test_animation_clock_step() {
+        let mut clock = AnimationClock::new();
+        clock.set_refresh_rate_millihertz(Some(60_000));
+
+        assert_eq!(clock.step(us(5_000)), us(5_000));
+        assert_eq!(clock.step(us(250_000)), us(250_000));
+        assert_eq!(clock.elapsed(), us(255_000));
+        assert_eq!(clock.frame(), 2);
+    }
+}
@@ -0,0 +1,11 @@
+mod animation_clock;
+mod power_settings;
+mod scheduler;
+mod time_source;
+mod timer_queue;
+
+pub use animation_clock::AnimationClock;
+pub use power_settings::PowerSettings;
+pub use scheduler::Scheduler;
+pub use time_source::TimeSource;
+pub use timer_queue::{TimerId, TimerQueue};
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use bon::Builder;
+use getset::CopyGetters;
+
+#[derive(Debug, Clone, Copy, PartialEq, Builder, CopyGetters)]
+#[builder(const)]
+#[get_copy = "pub"]
+pub struct PowerSettings {
+    // `None` leaves the frame rate to the present mode.
+    max_fps:                Option<u32>,
+    // The frame rate once there has been no input for `idle_timeout`.
+    idle_fps:               Option<u32>,
+    #[builder(default = Duration::from_secs(5))]
+    idle_timeout:           Duration,
+    #[builder(default = false)]
+    is_low_power:           bool,
+    #[builder(default = 30)]
+    low_power_fps:          u32,
+    // Fraction of the surface resolution the UI is rendered at in low
+    // power mode; see `Renderer::set_render_scale`.
+    #[builder(default = 0.5)]
+    low_power_render_scale: f32,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self { Self::builder().build() }
+}
+
+impl PowerSettings {
+    // The lowest of the frame rate caps that currently apply.
+    #[must_use]
+    pub fn target_fps(&self, is_idle: bool) -> Option<u32> {
+        [
+            self.max_fps,
+            self.idle_fps.filter(|_| is_idle),
+            self.is_low_power.then_some(self.low_power_fps),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|fps| *fps > 0)
+        .min()
+    }
+
+    #[must_use]
+    pub fn frame_interval(&self, is_idle: bool) -> Option<Duration> {
+        self.target_fps(is_idle)
+            .map(|fps| Duration::from_nanos(1_000_000_000 / u64::from(fps)))
+    }
+
+    #[must_use]
+    pub fn render_scale(&self) -> f32 {
+        match self.is_low_power {
+            true => self.low_power_render_scale.clamp(0.1, 1.0),
+            false => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::PowerSettings;
+
+    #[rstest]
+    #[case(None, None, false, false, None)]
+    #[case(Some(60), None, false, false, Some(60))]
+    #[case(Some(60), Some(10), false, false, Some(60))]
+    #[case(Some(60), Some(10), false, true, Some(10))]
+    #[case(None, None, true, false, Some(30))]
+    #[case(Some(144), Some(10), true, true, Some(10))]
+    #[case(Some(0), None, false, false, None)]
+    fn test_power_settings_target_fps(
+        #[case] max_fps: Option<u32>,
+        #[case] idle_fps: Option<u32>,
+        #[case] is_low_power: bool,
+        #[case] is_idle: bool,
+        #[case] expected: Option<u32>,
+    ) {
+        let settings = PowerSettings::builder()
+            .maybe_max_fps(max_fps)
+            .maybe_idle_fps(idle_fps)
+            .is_low_power(is_low_power)
+            .build();
+        assert_eq!(settings.target_fps(is_idle), expected);
+    }
+}
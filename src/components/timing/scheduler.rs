@@ -0,0 +1,334 @@
+use std::time::{Duration, Instant};
+
+use winit::{event::WindowEvent, window::Window};
+
+use super::{AnimationClock, PowerSettings, TimeSource, TimerQueue};
+
+// Decides when a window is worth redrawing. While the window is minimized
+// or fully occluded, redraw requests are held back and the animation clock
+// is paused; both resume once the window is visible again. Redraws are
+// also paced to the frame rate caps of the power settings. Under stepped
+// time, frames are driven by `step` instead of the event loop.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Scheduler {
+    clock:             AnimationClock,
+    power_settings:    PowerSettings,
+    is_occluded:       bool,
+    is_minimized:      bool,
+    is_redraw_pending: bool,
+    last_input:        Option<Instant>,
+    last_frame:        Option<Instant>,
+    time_source:       TimeSource,
+    timers:            TimerQueue,
+}
+
+impl Scheduler {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(
+        clock: AnimationClock,
+        power_settings: PowerSettings,
+    ) -> Self {
+        Self {
+            clock,
+            power_settings,
+            is_occluded: false,
+            is_minimized: false,
+            is_redraw_pending: false,
+            last_input: None,
+            last_frame: None,
+            time_source: TimeSource::System,
+            timers: TimerQueue::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn clock(&self) -> &AnimationClock { &self.clock }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn clock_mut(&mut self) -> &mut AnimationClock {
+        &mut self.clock
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn power_settings(&self) -> &PowerSettings {
+        &self.power_settings
+    }
+
+    #[inline(always)]
+    pub const fn set_power_settings(&mut self, power_settings: PowerSettings) {
+        self.power_settings = power_settings;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn time_source(&self) -> &TimeSource { &self.time_source }
+
+    pub const fn set_time_source(&mut self, time_source: TimeSource) {
+        self.time_source = time_source;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn timers(&self) -> &TimerQueue { &self.timers }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn timers_mut(&mut self) -> &mut TimerQueue { &mut self.timers }
+
+    // Call when woken up at the time `about_to_wait` returned. Returns
+    // whether any timer fired, in which case the widgets waiting on
+    // timers should be updated.
+    pub fn expire_timers(&mut self) -> bool {
+        let now = self.now();
+        self.timers.expire(now)
+    }
+
+    // The time to pass to widgets, e.g. for blinking and double clicks.
+    #[must_use]
+    #[inline(always)]
+    pub fn now(&self) -> Instant { self.time_source.now() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_visible(&self) -> bool {
+        !self.is_occluded && !self.is_minimized
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_redraw_pending(&self) -> bool { self.is_redraw_pending }
+
+    // Whether there has been no input for the idle timeout.
+    #[must_use]
+    pub fn is_idle(&self, now: Instant) -> bool {
+        let idle_timeout = self.power_settings.idle_timeout();
+        self.last_input.is_none_or(|last_input| {
+            now.saturating_duration_since(last_input) >= idle_timeout
+        })
+    }
+
+    #[inline(always)]
+    pub const fn register_input(&mut self, now: Instant) {
+        self.last_input = Some(now);
+    }
+
+    // The earliest time the next frame may start under the current frame
+    // rate cap.
+    #[must_use]
+    pub fn next_frame_time(&self, now: Instant) -> Instant {
+        let frame_interval =
+            self.power_settings.frame_interval(self.is_idle(now));
+        match (self.last_frame, frame_interval) {
+            (Some(last_frame), Some(frame_interval)) => {
+                (last_frame + frame_interval).max(now)
+            }
+            _ => now,
+        }
+    }
+
+    // Feed every window event through here; occlusion and minimizing
+    // (reported as a zero-sized resize) toggle the visibility, and user
+    // input resets the idle timeout.
+    pub fn handle_window_event(
+        &mut self,
+        event: &WindowEvent,
+        window: &Window,
+    ) {
+        let (is_occluded, is_minimized) = match event {
+            WindowEvent::KeyboardInput { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::Touch(_) => {
+                self.register_input(self.now());
+                return;
+            }
+            WindowEvent::Occluded(is_occluded) => {
+                (*is_occluded, self.is_minimized)
+            }
+            WindowEvent::Resized(size) => {
+                (self.is_occluded, size.width == 0 || size.height == 0)
+            }
+            _ => return,
+        };
+        if self.update_visibility(is_occluded, is_minimized) {
+            window.request_redraw();
+        }
+    }
+
+    // Marks the window for redrawing. The request reaches the window
+    // right away unless a frame rate cap delays it to `about_to_wait`.
+    pub fn request_redraw(&mut self, window: &Window) {
+        self.is_redraw_pending = true;
+        self.about_to_wait(self.now(), window);
+    }
+
+    // Call on `ApplicationHandler::about_to_wait`. Requests a pending
+    // redraw once it's due and returns when to wake up otherwise, to be
+    // passed to `ControlFlow::WaitUntil`; pending timers wake it up too.
+    pub fn about_to_wait(
+        &mut self,
+        now: Instant,
+        window: &Window,
+    ) -> Option<Instant> {
+        let next_timer = self.timers.next_deadline();
+        if !self.is_redraw_pending || !self.is_visible() {
+            return next_timer;
+        }
+        let next_frame_time = self.next_frame_time(now);
+        if next_frame_time > now {
+            return Some(next_timer.map_or(next_frame_time, |next_timer| {
+                next_timer.min(next_frame_time)
+            }));
+        }
+        window.request_redraw();
+
+        next_timer
+    }
+
+    // Call on `WindowEvent::RedrawRequested`. Returns the animation step
+    // for the frame, or `None` if the window shouldn't be drawn at all.
+    pub fn begin_frame(&mut self, now: Instant) -> Option<Duration> {
+        if !self.is_visible() {
+            return None;
+        }
+        self.is_redraw_pending = false;
+        self.last_frame = Some(now);
+        let present_time = self.clock.expected_present_time(now);
+
+        Some(self.clock.tick(present_time))
+    }
+
+    // Advances stepped time by `dt` and begins a frame that steps
+    // animations by exactly `dt`. Returns `None` under system time or
+    // while the window is hidden.
+    pub fn step(&mut self, dt: Duration) -> Option<Duration> {
+        if !self.time_source.is_stepped() {
+            return None;
+        }
+        self.time_source.advance(dt);
+        if !self.is_visible() {
+            return None;
+        }
+        self.is_redraw_pending = false;
+        self.last_frame = Some(self.now());
+
+        Some(self.clock.step(dt))
+    }
+
+    // Returns whether the window just became visible with a redraw that
+    // was held back while it was hidden.
+    fn update_visibility(
+        &mut self,
+        is_occluded: bool,
+        is_minimized: bool,
+    ) -> bool {
+        let was_visible = self.is_visible();
+        self.is_occluded = is_occluded;
+        self.is_minimized = is_minimized;
+
+        match (was_visible, self.is_visible()) {
+            (true, false) => {
+                self.clock.pause();
+                false
+            }
+            (false, true) => {
+                self.clock.resume();
+                self.is_redraw_pending
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use rstest::rstest;
+
+    use super::Scheduler;
+    use crate::{AnimationClock, PowerSettings, TimeSource};
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    #[rstest]
+    fn test_scheduler_hidden_window() {
+        let mut scheduler = Scheduler::default();
+
+        assert!(!scheduler.update_visibility(true, false));
+        assert!(scheduler.clock().is_paused());
+        assert_eq!(scheduler.begin_frame(Instant::now()), None);
+
+        assert!(!scheduler.update_visibility(true, true));
+        assert!(!scheduler.update_visibility(false, true));
+        assert!(scheduler.clock().is_paused());
+    }
+
+    #[rstest]
+    #[case(false, false)]
+    #[case(true, true)]
+    fn test_scheduler_resume(
+        #[case] is_redraw_pending: bool,
+        #[case] expected: bool,
+    ) {
+        let mut scheduler = Scheduler::default();
+        scheduler.update_visibility(false, true);
+        scheduler.is_redraw_pending = is_redraw_pending;
+
+        assert_eq!(scheduler.update_visibility(false, false), expected);
+        assert!(!scheduler.clock().is_paused());
+        assert!(scheduler.begin_frame(Instant::now()).is_some());
+        assert!(!scheduler.is_redraw_pending());
+    }
+
+    #[rstest]
+    #[case(ms(0), ms(50))]
+    #[case(ms(4_000), ms(50))]
+    #[case(ms(6_000), ms(100))]
+    fn test_scheduler_next_frame_time(
+        #[case] last_frame: Duration,
+        #[case] expected_interval: Duration,
+    ) {
+        // 20 fps while active, 10 fps after 5 seconds without input.
+        let power_settings = PowerSettings::builder()
+            .max_fps(20)
+            .idle_fps(10)
+            .idle_timeout(ms(5_000))
+            .build();
+        let mut scheduler =
+            Scheduler::new(AnimationClock::new(), power_settings);
+        let start = Instant::now();
+        scheduler.register_input(start);
+        scheduler.last_frame = Some(start + last_frame);
+
+        let now = start + last_frame + ms(20);
+        assert_eq!(
+            scheduler.next_frame_time(now),
+            start + last_frame + expected_interval,
+        );
+        assert_eq!(scheduler.next_frame_time(now + ms(200)), now + ms(200));
+    }
+
+    #[rstest]
+    fn test_scheduler_step() {
+        let mut scheduler = Scheduler::default();
+        assert_eq!(scheduler.step(ms(16)), None);
+
+        scheduler.set_time_source(TimeSource::stepped());
+        let start = scheduler.now();
+        assert_eq!(scheduler.step(ms(16)), Some(ms(16)));
+        assert_eq!(scheduler.step(ms(500)), Some(ms(500)));
+        assert_eq!(scheduler.now(), start + ms(516));
+        assert_eq!(scheduler.clock().elapsed(), ms(516));
+
+        // Time still moves while hidden, but animations don't.
+        scheduler.update_visibility(true, false);
+        assert_eq!(scheduler.step(ms(16)), None);
+        assert_eq!(scheduler.now(), start + ms(532));
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+// Where the scheduler reads the time from. Stepped time only moves when
+// it's stepped, so UI tests and golden-image captures see the same
+// frames on every run; its epoch is only an anchor for the instants
+// handed out, as nothing depends on their absolute value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeSource {
+    #[default]
+    System,
+    Stepped {
+        epoch:   Instant,
+        elapsed: Duration,
+    },
+}
+
+impl TimeSource {
+    #[must_use]
+    pub fn stepped() -> Self {
+        Self::Stepped { epoch: Instant::now(), elapsed: Duration::ZERO }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_stepped(&self) -> bool {
+        matches!(self, Self::Stepped { .. })
+    }
+
+    #[must_use]
+    pub fn now(&self) -> Instant {
+        match self {
+            Self::System => Instant::now(),
+            Self::Stepped { epoch, elapsed } => *epoch + *elapsed,
+        }
+    }
+
+    // Moves stepped time forward; system time can't be advanced.
+    pub fn advance(&mut self, dt: Duration) {
+        if let Self::Stepped { elapsed, .. } = self {
+            *elapsed += dt;
+        }
+    }
+}
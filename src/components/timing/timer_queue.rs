@@ -0,0 +1,128 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(u64);
+
+// One-shot timers shared by the widgets of a window, so that delays like
+// hover intent don't have each widget polling the time. The scheduler
+// wakes the event loop for the earliest one; after `expire`, widgets
+// check theirs with `take_fired`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TimerQueue {
+    next_id:   u64,
+    deadlines: BTreeMap<TimerId, Instant>,
+    // The same timers, soonest first.
+    queue:     BTreeSet<(Instant, TimerId)>,
+    fired:     BTreeSet<TimerId>,
+}
+
+impl TimerQueue {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            next_id:   0,
+            deadlines: BTreeMap::new(),
+            queue:     BTreeSet::new(),
+            fired:     BTreeSet::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn start(&mut self, now: Instant, delay: Duration) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        let deadline = now + delay;
+        self.deadlines.insert(id, deadline);
+        self.queue.insert((deadline, id));
+        id
+    }
+
+    // Also forgets a timer that fired but wasn't taken yet.
+    pub fn cancel(&mut self, id: TimerId) {
+        if let Some(deadline) = self.deadlines.remove(&id) {
+            self.queue.remove(&(deadline, id));
+        }
+        self.fired.remove(&id);
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_pending(&self, id: TimerId) -> bool {
+        self.deadlines.contains_key(&id)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.deadlines.is_empty() && self.fired.is_empty()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.queue.first().map(|(deadline, _)| *deadline)
+    }
+
+    // Fires the timers that are due; returns whether any did.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let mut is_any_fired = false;
+        while let Some(&(deadline, id)) = self.queue.first()
+            && deadline <= now
+        {
+            self.queue.pop_first();
+            self.deadlines.remove(&id);
+            self.fired.insert(id);
+            is_any_fired = true;
+        }
+        is_any_fired
+    }
+
+    // Returns whether the timer fired, at most once.
+    pub fn take_fired(&mut self, id: TimerId) -> bool {
+        self.fired.remove(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use rstest::rstest;
+
+    use super::TimerQueue;
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    #[rstest]
+    fn test_timers_fire_in_order() {
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let late = timers.start(start, ms(300));
+        let early = timers.start(start, ms(100));
+        assert_eq!(timers.next_deadline(), Some(start + ms(100)));
+
+        assert!(!timers.expire(start + ms(50)));
+        assert!(timers.expire(start + ms(100)));
+        assert!(timers.take_fired(early));
+        assert!(!timers.take_fired(early));
+        assert!(!timers.take_fired(late));
+        assert_eq!(timers.next_deadline(), Some(start + ms(300)));
+    }
+
+    #[rstest]
+    fn test_cancelled_timers_never_fire() {
+        let start = Instant::now();
+        let mut timers = TimerQueue::default();
+        let id = timers.start(start, ms(100));
+        timers.cancel(id);
+
+        assert!(!timers.is_pending(id));
+        assert!(!timers.expire(start + ms(200)));
+        assert!(!timers.take_fired(id));
+        assert!(timers.is_empty());
+    }
+}
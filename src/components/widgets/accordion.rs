@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use glam::Mat4;
+
+use super::{Collapsible, CollapsibleStyle};
+use crate::{
+    components::{PageBreaks, common::Bounds},
+    core::{PageBreak, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccordionMode {
+    // Expanding a section collapses the others.
+    SingleOpen,
+    MultiOpen,
+}
+
+pub struct Accordion {
+    // Position and width of the accordion, height of each header.
+    bounds:   Bounds,
+    mode:     AccordionMode,
+    style:    CollapsibleStyle,
+    sections: Vec<Collapsible>,
+}
+
+impl Accordion {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(
+        bounds: Bounds,
+        mode: AccordionMode,
+        style: CollapsibleStyle,
+    ) -> Self {
+        Self { bounds, mode, style, sections: Vec::new() }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn mode(&self) -> AccordionMode { self.mode }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn sections(&self) -> &[Collapsible] { &self.sections }
+
+    #[must_use]
+    pub fn height(&self) -> f32 {
+        self.sections.iter().map(Collapsible::height).sum()
+    }
+
+    #[must_use]
+    pub fn section_at(&self, position: [f32; 2]) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|section| section.header_contains(position))
+    }
+
+    pub fn add_section(
+        &mut self,
+        content_height: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> usize {
+        let section = Collapsible::new(
+            self.section_bounds(self.height()),
+            content_height,
+            false,
+            self.style.clone(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.sections.push(section);
+
+        self.sections.len() - 1
+    }
+
+    pub fn remove_section(
+        &mut self,
+        index: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if index < self.sections.len() {
+            self.sections.remove(index).destroy(renderer);
+            self.restack(view_projection, dpr, renderer);
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: AccordionMode) {
+        self.mode = mode;
+        if mode == AccordionMode::SingleOpen {
+            // Keep the first open section, if any.
+            let mut is_open_found = false;
+            for section in &mut self.sections {
+                if section.is_expanded() {
+                    section.set_expanded(!is_open_found);
+                    is_open_found = true;
+                }
+            }
+        }
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if index < self.sections.len() {
+            let expanded = expanded_states(self.mode, &self.expanded(), index);
+            self.apply(&expanded);
+        }
+    }
+
+    pub fn tick(
+        &mut self,
+        dt: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let mut is_changed = false;
+        for section in &mut self.sections {
+            is_changed |= section.tick(dt, view_projection, dpr, renderer);
+        }
+        if is_changed {
+            self.restack(view_projection, dpr, renderer);
+        }
+
+        is_changed
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.restack(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for section in &self.sections {
+            section.destroy(renderer);
+        }
+    }
+
+    fn expanded(&self) -> Vec<bool> {
+        self.sections.iter().map(Collapsible::is_expanded).collect()
+    }
+
+    fn apply(&mut self, expanded: &[bool]) {
+        for (section, is_expanded) in self.sections.iter_mut().zip(expanded) {
+            section.set_expanded(*is_expanded);
+        }
+    }
+
+    fn section_bounds(&self, offset: f32) -> Bounds {
+        let [x, y] = self.bounds.position;
+        Bounds {
+            position:  [x, y + offset],
+            size:      self.bounds.size,
+            clip_rect: self.bounds.clip_rect,
+        }
+    }
+
+    // Sections below an animating one move with it every tick.
+    fn restack(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let mut offset = 0.0;
+        for index in 0..self.sections.len() {
+            let bounds = self.section_bounds(offset);
+            let section = &mut self.sections[index];
+            if *section.bounds() != bounds {
+                section.update_bounds(bounds, view_projection, dpr, renderer);
+            }
+            offset += section.height();
+        }
+    }
+}
+
+impl PageBreaks for Accordion {
+    fn page_breaks(&self, dpr: f32) -> Vec<PageBreak> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.page_breaks(dpr))
+            .collect()
+    }
+}
+
+fn expanded_states(
+    mode: AccordionMode,
+    expanded: &[bool],
+    toggled: usize,
+) -> Vec<bool> {
+    expanded
+        .iter()
+        .enumerate()
+        .map(|(index, is_expanded)| match (index == toggled, mode) {
+            (true, _) => !is_expanded,
+            (false, AccordionMode::SingleOpen) => false,
+            (false, AccordionMode::MultiOpen) => *is_expanded,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{AccordionMode, expanded_states};
+
+    #[rstest]
+    #[case(AccordionMode::SingleOpen, &[false, true, false], 0, &[true, false, false])]
+    #[case(AccordionMode::SingleOpen, &[false, true, false], 1, &[false, false, false])]
+    #[case(AccordionMode::MultiOpen, &[false, true, false], 0, &[true, true, false])]
+    #[case(AccordionMode::MultiOpen, &[true, true, false], 1, &[true, false, false])]
+    fn test_expanded_states(
+        #[case] mode: AccordionMode,
+        #[case] expanded: &[bool],
+        #[case] toggled: usize,
+        #[case] expected: &[bool],
+    ) {
+        assert_eq!(expanded_states(mode, expanded, toggled), expected);
+    }
+}
@@ -0,0 +1,625 @@
+use std::sync::{
+    Arc,
+    mpsc::{self, Receiver, Sender},
+};
+
+use bon::Builder;
+use glam::Mat4;
+use rayon::ThreadPool;
+use winit::keyboard::{Key, NamedKey};
+
+use super::{
+    AccessNode, AccessRole, Availability, Block, BlockStyle,
+    block_states::Positioned,
+};
+use crate::{
+    components::{
+        common::Bounds,
+        layouting::{PlacementOptions, PopupSide, place_popup},
+        text::{TextEditState, fuzzy_filter},
+    },
+    core::Renderer,
+};
+
+// Returns the suggestions for a query. Runs on the thread pool, so it may
+// block on I/O.
+pub type SuggestionProvider = dyn Fn(&str) -> Vec<String> + Send + Sync;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupPlacement {
+    Below,
+    Above,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct AutocompleteStyle {
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    field_color:        [f32; 4],
+    #[builder(default = [0.75, 0.75, 0.75, 1.0])]
+    field_border_color: [f32; 4],
+    #[builder(default = 1.0)]
+    field_border_size:  f32,
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    popup_color:        [f32; 4],
+    #[builder(default = [0.0, 0.0, 0.0, 0.2])]
+    popup_shadow_color: [f32; 4],
+    #[builder(default = [0.85, 0.91, 1.0, 1.0])]
+    highlight_color:    [f32; 4],
+    #[builder(default = 4.0)]
+    corner_radius:      f32,
+    #[builder(default = 28.0)]
+    item_height:        f32,
+    #[builder(default = 8)]
+    max_visible_items:  usize,
+    #[builder(default = 2.0)]
+    popup_gap:          f32,
+    #[builder(default = [0.9, 0.9, 0.92, 1.0])]
+    skeleton_color:     [f32; 4],
+    // Placeholder rows the popup shows while loading.
+    #[builder(default = 3)]
+    skeleton_rows:      usize,
+}
+
+// A field suggesting completions for its text in a popup. Disabled and
+// read-only ones keep the popup closed, loading ones show placeholder
+// rows in it; none of them take edits, keys or clicks.
+pub struct Autocomplete {
+    bounds:       Bounds,
+    // The area the popup has to fit in, usually the window.
+    viewport:     Bounds,
+    style:        AutocompleteStyle,
+    state:        TextEditState,
+    availability: Availability,
+    provider:     Arc<SuggestionProvider>,
+    thread_pool:  Arc<ThreadPool>,
+    sender:       Sender<(u64, Vec<String>)>,
+    receiver:     Receiver<(u64, Vec<String>)>,
+    // Bumped on every query, so results of stale queries are dropped.
+    generation:   u64,
+    suggestions:  Vec<String>,
+    highlighted:  Option<usize>,
+    is_open:      bool,
+    placement:    PopupPlacement,
+    items:        Vec<Bounds>,
+    field:        Block<Positioned>,
+    popup:        Vec<Block<Positioned>>,
+}
+
+impl Autocomplete {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bounds: Bounds,
+        viewport: Bounds,
+        style: AutocompleteStyle,
+        provider: Arc<SuggestionProvider>,
+        thread_pool: Arc<ThreadPool>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let field = Block::<Positioned>::new(
+            bounds.clone(),
+            field_style(&style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            bounds,
+            viewport,
+            style,
+            state: TextEditState::default(),
+            availability: Availability::Enabled,
+            provider,
+            thread_pool,
+            sender,
+            receiver,
+            generation: 0,
+            suggestions: Vec::new(),
+            highlighted: None,
+            is_open: false,
+            placement: PopupPlacement::Below,
+            items: Vec::new(),
+            field,
+            popup: Vec::new(),
+        }
+    }
+
+    // A provider over a fixed list, keeping the items that fuzzy match the
+    // query, best first.
+    #[must_use]
+    pub fn filter_items(items: Vec<String>) -> Arc<SuggestionProvider> {
+        Arc::new(move |query: &str| {
+            fuzzy_filter(query, &items)
+                .into_iter()
+                .map(|(index, _)| items[index].clone())
+                .collect()
+        })
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn state(&self) -> &TextEditState { &self.state }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn availability(&self) -> Availability { self.availability }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn suggestions(&self) -> &[String] { &self.suggestions }
+
+    // Bounds of the visible suggestions, for drawing their labels; empty
+    // while the popup is closed.
+    #[must_use]
+    #[inline(always)]
+    pub fn items(&self) -> &[Bounds] { &self.items }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn highlighted(&self) -> Option<usize> { self.highlighted }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_open(&self) -> bool { self.is_open }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn placement(&self) -> PopupPlacement { self.placement }
+
+    #[must_use]
+    pub fn accessibility(&self) -> AccessNode {
+        AccessNode {
+            role:         AccessRole::TextInput,
+            label:        self.state.text().to_owned(),
+            bounds:       self.bounds.clone(),
+            is_focusable: matches!(
+                self.availability,
+                Availability::Enabled | Availability::ReadOnly
+            ),
+            is_disabled:  self.availability == Availability::Disabled,
+            is_read_only: self.availability == Availability::ReadOnly,
+            is_busy:      self.availability == Availability::Loading,
+        }
+    }
+
+    // Edits the text; a changed text queries the provider again.
+    pub fn edit(&mut self, edit: impl FnOnce(&mut TextEditState)) {
+        if !self.availability.is_enabled() {
+            return;
+        }
+        let previous_text = self.state.text().to_owned();
+        edit(&mut self.state);
+        if self.state.text() != previous_text {
+            self.query();
+        }
+    }
+
+    // Takes in suggestions that arrived since the last call. Returns
+    // whether the popup changed.
+    pub fn poll(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let mut latest = None;
+        while let Ok((generation, suggestions)) = self.receiver.try_recv() {
+            if generation == self.generation {
+                latest = Some(suggestions);
+            }
+        }
+        let Some(mut suggestions) = latest else {
+            return false;
+        };
+        suggestions.truncate(self.style.max_visible_items);
+
+        self.suggestions = suggestions;
+        self.highlighted = None;
+        self.is_open =
+            self.availability.is_enabled() && !self.suggestions.is_empty();
+        self.rebuild(view_projection, dpr, renderer);
+
+        true
+    }
+
+    // Handles popup navigation keys and returns the accepted suggestion,
+    // which also replaces the text. Other keys are left to the caller.
+    pub fn handle_key(
+        &mut self,
+        key: &Key,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<String> {
+        let Key::Named(key) = key else {
+            return None;
+        };
+        if !self.availability.is_enabled() {
+            return None;
+        }
+        match key {
+            NamedKey::ArrowDown | NamedKey::ArrowUp => {
+                let is_forward = *key == NamedKey::ArrowDown;
+                self.is_open = !self.suggestions.is_empty();
+                self.highlighted = step_highlight(
+                    self.highlighted,
+                    self.suggestions.len(),
+                    is_forward,
+                );
+                self.rebuild(view_projection, dpr, renderer);
+                None
+            }
+            NamedKey::Enter | NamedKey::Tab if self.is_open => {
+                let index = self.highlighted?;
+                self.accept(index, view_projection, dpr, renderer)
+            }
+            NamedKey::Escape => {
+                self.close(view_projection, dpr, renderer);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    // Returns the clicked suggestion. Clicking outside the field and the
+    // popup closes it.
+    pub fn click(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<String> {
+        if !self.availability.is_enabled() {
+            return None;
+        }
+        let index = self
+            .items
+            .iter()
+            .position(|bounds| contains(bounds, position));
+        if let Some(index) = index {
+            return self.accept(index, view_projection, dpr, renderer);
+        }
+        if !contains(&self.bounds, position) {
+            self.close(view_projection, dpr, renderer);
+        }
+
+        None
+    }
+
+    pub fn close(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if self.is_open {
+            self.is_open = false;
+            self.highlighted = None;
+            self.rebuild(view_projection, dpr, renderer);
+        }
+    }
+
+    // Anything but enabling closes the popup. Returns whether the
+    // availability changed.
+    pub fn set_availability(
+        &mut self,
+        availability: Availability,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if availability == self.availability {
+            return false;
+        }
+        self.availability = availability;
+        if !availability.is_enabled() {
+            self.is_open = false;
+            self.highlighted = None;
+        }
+        self.rebuild(view_projection, dpr, renderer);
+        true
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        viewport: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.viewport = viewport;
+        self.field
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+        self.field.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.field.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: AutocompleteStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.field
+            .update_style(field_style(&self.style, dpr), renderer);
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.field.destroy(renderer);
+        self.destroy_popup(renderer);
+    }
+
+    fn query(&mut self) {
+        self.generation += 1;
+        let generation = self.generation;
+        let query = self.state.text().to_owned();
+        let provider = self.provider.clone();
+        let sender = self.sender.clone();
+        self.thread_pool.spawn(move || {
+            let _ = sender.send((generation, provider(&query)));
+        });
+    }
+
+    fn accept(
+        &mut self,
+        index: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<String> {
+        let suggestion = self.suggestions.get(index)?.clone();
+        self.state.set_text(suggestion.clone());
+        // Suggestions still pending for the previous text are stale now.
+        self.generation += 1;
+        self.close(view_projection, dpr, renderer);
+
+        Some(suggestion)
+    }
+
+    fn destroy_popup(&self, renderer: &mut Renderer) {
+        for block in &self.popup {
+            block.destroy(renderer);
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy_popup(renderer);
+        self.popup.clear();
+        self.items.clear();
+        let is_loading = self.availability == Availability::Loading;
+        if !self.is_open && !is_loading {
+            return;
+        }
+
+        let style = &self.style;
+        let rows = match is_loading {
+            true => style.skeleton_rows,
+            false => self.suggestions.len(),
+        };
+        let popup_height = style.item_height * rows as f32;
+        let (placement, popup) =
+            place_dropdown(&self.bounds, &self.viewport, popup_height, style);
+        self.placement = placement;
+        let rows = (0..rows).map(|index| {
+            Bounds::without_clip_rect(
+                [
+                    popup.position[0],
+                    popup.position[1] + style.item_height * index as f32,
+                ],
+                [popup.size[0], style.item_height],
+            )
+        });
+        let skeletons = match is_loading {
+            true => rows.map(|row| skeleton_bar(&row)).collect(),
+            false => {
+                self.items = rows.collect();
+                Vec::new()
+            }
+        };
+
+        let popup_style = BlockStyle::builder()
+            .fill_color(style.popup_color)
+            .corner_radii([style.corner_radius * dpr; 4])
+            .shadow_color(style.popup_shadow_color)
+            .shadow_offset([0.0, 2.0 * dpr])
+            .shadow_blur(8.0 * dpr)
+            .build();
+        self.popup.push(Block::<Positioned>::new(
+            popup,
+            popup_style,
+            view_projection,
+            dpr,
+            renderer,
+        ));
+        if let Some(bounds) = self.highlighted.map(|index| &self.items[index])
+        {
+            let highlight_style = BlockStyle::builder()
+                .fill_color(style.highlight_color)
+                .build();
+            self.popup.push(Block::<Positioned>::new(
+                bounds.clone(),
+                highlight_style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        }
+        for bounds in skeletons {
+            let skeleton_style = BlockStyle::builder()
+                .fill_color(style.skeleton_color)
+                .corner_radii([bounds.size[1] / 2.0 * dpr; 4])
+                .build();
+            self.popup.push(Block::<Positioned>::new(
+                bounds,
+                skeleton_style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        }
+    }
+}
+
+// A placeholder bar in the middle of a popup row, inset from its edges.
+fn skeleton_bar(row: &Bounds) -> Bounds {
+    let [x, y] = row.position;
+    let [width, height] = row.size;
+    let inset = height * 0.3;
+    Bounds::without_clip_rect(
+        [x + inset, y + inset],
+        [(width - inset * 2.0).max(0.0), height - inset * 2.0],
+    )
+}
+
+fn field_style(style: &AutocompleteStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.field_color)
+        .border_color(style.field_border_color)
+        .border_size(style.field_border_size * dpr)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+// Moves the highlight one item down or up, wrapping around the ends.
+fn step_highlight(
+    highlighted: Option<usize>,
+    count: usize,
+    is_forward: bool,
+) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    Some(match (highlighted, is_forward) {
+        (None, true) => 0,
+        (None, false) => count - 1,
+        (Some(index), true) => (index + 1) % count,
+        (Some(index), false) => (index + count - 1) % count,
+    })
+}
+
+// Below the field unless the popup only fits above it, or there's more
+// room above when it fits neither.
+fn place_dropdown(
+    field: &Bounds,
+    viewport: &Bounds,
+    popup_height: f32,
+    style: &AutocompleteStyle,
+) -> (PopupPlacement, Bounds) {
+    let options = PlacementOptions::builder()
+        .side(PopupSide::Bottom)
+        .gap(style.popup_gap)
+        .padding(0.0)
+        .shift(false)
+        .build();
+    let placement =
+        place_popup(field, [field.size[0], popup_height], viewport, &options);
+    let popup_placement = match placement.side {
+        PopupSide::Top => PopupPlacement::Above,
+        _ => PopupPlacement::Below,
+    };
+    (popup_placement, placement.bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        AutocompleteStyle, PopupPlacement, place_dropdown, skeleton_bar,
+        step_highlight,
+    };
+    use crate::Bounds;
+
+    #[rstest]
+    #[case(None, 3, true, Some(0))]
+    #[case(None, 3, false, Some(2))]
+    #[case(Some(2), 3, true, Some(0))]
+    #[case(Some(0), 3, false, Some(2))]
+    #[case(Some(1), 3, true, Some(2))]
+    #[case(None, 0, true, None)]
+    fn test_step_highlight(
+        #[case] highlighted: Option<usize>,
+        #[case] count: usize,
+        #[case] is_forward: bool,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(step_highlight(highlighted, count, is_forward), expected);
+    }
+
+    #[rstest]
+    #[case(100.0, 150.0, PopupPlacement::Below)]
+    #[case(500.0, 150.0, PopupPlacement::Above)]
+    #[case(400.0, 500.0, PopupPlacement::Above)]
+    #[case(200.0, 500.0, PopupPlacement::Below)]
+    fn test_popup_placement(
+        #[case] field_y: f32,
+        #[case] popup_height: f32,
+        #[case] expected: PopupPlacement,
+    ) {
+        let viewport = Bounds::without_clip_rect([0.0, 0.0], [800.0, 600.0]);
+        let field = Bounds::without_clip_rect([20.0, field_y], [200.0, 30.0]);
+
+        let style = AutocompleteStyle::builder().build();
+        let (placement, popup) =
+            place_dropdown(&field, &viewport, popup_height, &style);
+
+        assert_eq!(placement, expected);
+        assert_eq!(popup.size, [200.0, popup_height]);
+    }
+
+    #[rstest]
+    #[case([10.0, 20.0], [200.0, 30.0], [19.0, 29.0], [182.0, 12.0])]
+    // Too narrow for the inset.
+    #[case([0.0, 0.0], [10.0, 30.0], [9.0, 9.0], [0.0, 12.0])]
+    fn test_skeleton_bar(
+        #[case] position: [f32; 2],
+        #[case] size: [f32; 2],
+        #[case] expected_position: [f32; 2],
+        #[case] expected_size: [f32; 2],
+    ) {
+        let bar = skeleton_bar(&Bounds::without_clip_rect(position, size));
+        assert_eq!(bar.position, expected_position);
+        assert_eq!(bar.size, expected_size);
+    }
+}
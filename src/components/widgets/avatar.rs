@@ -0,0 +1,382 @@
+use std::{f32::consts::FRAC_1_SQRT_2, path::PathBuf};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{
+    Block, BlockStyle, ImageStyle, ImageView, block_states::Positioned,
+};
+use crate::{
+    components::common::{Bounds, ImageLoader},
+    core::Renderer,
+};
+
+const FALLBACK_PALETTE: [[f32; 4]; 8] = [
+    [0.91, 0.30, 0.24, 1.0],
+    [0.90, 0.49, 0.13, 1.0],
+    [0.95, 0.77, 0.06, 1.0],
+    [0.18, 0.80, 0.44, 1.0],
+    [0.10, 0.74, 0.61, 1.0],
+    [0.20, 0.60, 0.86, 1.0],
+    [0.61, 0.35, 0.71, 1.0],
+    [0.91, 0.26, 0.58, 1.0],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BadgeAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct AvatarStyle {
+    // Derived from the name when unset.
+    fallback_color:     Option<[f32; 4]>,
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    tint_color:         [f32; 4],
+    #[builder(default = BadgeAnchor::BottomRight)]
+    badge_anchor:       BadgeAnchor,
+    // Badge diameter relative to the avatar diameter.
+    #[builder(default = 0.3)]
+    badge_scale:        f32,
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    badge_border_color: [f32; 4],
+    #[builder(default = 2.0)]
+    badge_border_size:  f32,
+}
+
+pub struct Avatar {
+    name:        String,
+    initials:    String,
+    bounds:      Bounds,
+    style:       AvatarStyle,
+    image_path:  Option<PathBuf>,
+    image:       Option<ImageView>,
+    fallback:    Option<Block<Positioned>>,
+    badge_color: Option<[f32; 4]>,
+    badge:       Option<Block<Positioned>>,
+}
+
+impl Avatar {
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        image_path: Option<PathBuf>,
+        bounds: Bounds,
+        style: AvatarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let name = name.into();
+        let mut avatar = Self {
+            initials: initials(&name),
+            name,
+            bounds,
+            style,
+            image_path,
+            image: None,
+            fallback: None,
+            badge_color: None,
+            badge: None,
+        };
+        avatar.build(view_projection, dpr, renderer);
+
+        avatar
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn name(&self) -> &str { &self.name }
+
+    // The text system doesn't exist yet, so initials are exposed for the
+    // caller to draw centered within `bounds`.
+    #[must_use]
+    #[inline(always)]
+    pub fn initials(&self) -> &str { &self.initials }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn badge_color(&self) -> Option<[f32; 4]> { self.badge_color }
+
+    #[must_use]
+    pub fn fallback_color(&self) -> [f32; 4] {
+        self.style
+            .fallback_color
+            .unwrap_or_else(|| fallback_color(&self.name))
+    }
+
+    #[must_use]
+    pub fn is_image_loaded(&self) -> bool {
+        self.image.as_ref().is_some_and(ImageView::is_loaded)
+    }
+
+    pub fn sync(
+        &mut self,
+        loader: &mut ImageLoader,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(image) = &mut self.image {
+            image.sync(loader, view_projection, dpr, renderer);
+        }
+    }
+
+    pub fn set_image_path(
+        &mut self,
+        image_path: Option<PathBuf>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.image_path = image_path;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn set_badge(
+        &mut self,
+        badge_color: Option<[f32; 4]>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        // The image cutout depends on whether a badge is shown.
+        self.badge_color = badge_color;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: AvatarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        if let Some(image) = &self.image {
+            image.destroy(renderer);
+        }
+        if let Some(fallback) = &self.fallback {
+            fallback.destroy(renderer);
+        }
+        if let Some(badge) = &self.badge {
+            badge.destroy(renderer);
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.build(view_projection, dpr, renderer);
+    }
+
+    fn build(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let radius = circle_radius(&self.bounds) * dpr;
+        let corner_radii = [radius; 4];
+        let fallback_color = self.fallback_color();
+
+        let badge_bounds = self.badge_color.map(|_| {
+            badge_bounds(
+                &self.bounds,
+                self.style.badge_anchor,
+                self.style.badge_scale,
+            )
+        });
+
+        self.image = self.image_path.as_ref().map(|image_path| {
+            // The image is drawn after rectangles, so it leaves a hole for
+            // the badge instead of covering it.
+            let cutout = match &badge_bounds {
+                Some(badge_bounds) => [
+                    badge_bounds.position[0] - self.bounds.position[0]
+                        + badge_bounds.size[0] / 2.0,
+                    badge_bounds.position[1] - self.bounds.position[1]
+                        + badge_bounds.size[1] / 2.0,
+                    badge_bounds.size[0] / 2.0 + self.style.badge_border_size,
+                ],
+                None => [0.0; 3],
+            };
+            let image_style = ImageStyle::builder()
+                .tint_color(self.style.tint_color)
+                .corner_radii(corner_radii)
+                .cutout(cutout)
+                .placeholder_color(fallback_color)
+                .build();
+            ImageView::new(
+                image_path.clone(),
+                self.bounds.clone(),
+                image_style,
+                view_projection,
+                dpr,
+                renderer,
+            )
+        });
+
+        self.fallback = self.image_path.is_none().then(|| {
+            let block_style = BlockStyle::builder()
+                .fill_color(fallback_color)
+                .corner_radii(corner_radii)
+                .build();
+            Block::<Positioned>::new(
+                self.bounds.clone(),
+                block_style,
+                view_projection,
+                dpr,
+                renderer,
+            )
+        });
+
+        self.badge = self.badge_color.zip(badge_bounds).map(
+            |(badge_color, badge_bounds)| {
+                let badge_radius = badge_bounds.size[0] / 2.0 * dpr;
+                let block_style = BlockStyle::builder()
+                    .fill_color(badge_color)
+                    .corner_radii([badge_radius; 4])
+                    .border_color(self.style.badge_border_color)
+                    .border_size(self.style.badge_border_size * dpr)
+                    .build();
+                Block::<Positioned>::new(
+                    badge_bounds,
+                    block_style,
+                    view_projection,
+                    dpr,
+                    renderer,
+                )
+            },
+        );
+    }
+}
+
+fn circle_radius(bounds: &Bounds) -> f32 {
+    bounds.size[0].min(bounds.size[1]) / 2.0
+}
+
+fn initials(name: &str) -> String {
+    let mut words = name.split_whitespace();
+    let first = words.next().and_then(|word| word.chars().next());
+    let last = words.next_back().and_then(|word| word.chars().next());
+
+    first
+        .into_iter()
+        .chain(last)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn fallback_color(name: &str) -> [f32; 4] {
+    // FNV-1a, so the same name gets the same color across runs.
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    });
+    FALLBACK_PALETTE[hash as usize % FALLBACK_PALETTE.len()]
+}
+
+// Places the badge center on the avatar's circle at 45 degrees, so it
+// straddles the edge regardless of the avatar size.
+fn badge_bounds(bounds: &Bounds, anchor: BadgeAnchor, scale: f32) -> Bounds {
+    let radius = circle_radius(bounds);
+    let diameter = radius * 2.0 * scale;
+    let [sign_x, sign_y] = match anchor {
+        BadgeAnchor::TopLeft => [-1.0, -1.0],
+        BadgeAnchor::TopRight => [1.0, -1.0],
+        BadgeAnchor::BottomLeft => [-1.0, 1.0],
+        BadgeAnchor::BottomRight => [1.0, 1.0],
+    };
+    let center = [
+        bounds.position[0]
+            + bounds.size[0] / 2.0
+            + sign_x * radius * FRAC_1_SQRT_2,
+        bounds.position[1]
+            + bounds.size[1] / 2.0
+            + sign_y * radius * FRAC_1_SQRT_2,
+    ];
+
+    Bounds {
+        position:  [center[0] - diameter / 2.0, center[1] - diameter / 2.0],
+        size:      [diameter, diameter],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        BadgeAnchor, FALLBACK_PALETTE, badge_bounds, fallback_color, initials,
+    };
+    use crate::Bounds;
+
+    #[rstest]
+    #[case("Ada Lovelace", "AL")]
+    #[case("grace brewster murray hopper", "GH")]
+    #[case("  Linus  ", "L")]
+    #[case("émile zola", "ÉZ")]
+    #[case("", "")]
+    fn test_initials(#[case] name: &str, #[case] expected: &str) {
+        assert_eq!(initials(name), expected);
+    }
+
+    #[rstest]
+    fn test_fallback_color_is_stable() {
+        let color = fallback_color("Ada Lovelace");
+
+        assert_eq!(color, fallback_color("Ada Lovelace"));
+        assert!(FALLBACK_PALETTE.contains(&color));
+    }
+
+    #[rstest]
+    #[case(BadgeAnchor::TopLeft, [-1.0, -1.0])]
+    #[case(BadgeAnchor::BottomRight, [1.0, 1.0])]
+    fn test_badge_bounds(
+        #[case] anchor: BadgeAnchor,
+        #[case] direction: [f32; 2],
+    ) {
+        let bounds = Bounds::without_clip_rect([10.0, 10.0], [40.0, 40.0]);
+        let badge = badge_bounds(&bounds, anchor, 0.25);
+
+        assert_eq!(badge.size, [10.0, 10.0]);
+        let center = [
+            badge.position[0] + badge.size[0] / 2.0,
+            badge.position[1] + badge.size[1] / 2.0,
+        ];
+        for axis in 0..2 {
+            let offset = center[axis] - 30.0;
+            assert!(
+                (offset - direction[axis] * 20.0 / 2f32.sqrt()).abs() < 1e-4
+            );
+        }
+    }
+}
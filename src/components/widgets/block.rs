@@ -1,24 +1,38 @@
 use bon::Builder;
-use glam::{Mat4, Quat, Vec3};
+use glam::Mat4;
 
 use crate::{
-    components::common::{Bounds, InputState, MouseButtonState},
-    core::{Rectangle, Renderer},
+    components::{
+        common::{
+            Bounds, HitShape, InputState, MouseButtonState, build_model,
+            build_rotated_model, rgba8, to_rgba8,
+        },
+        debug::{DumpNode, Inspect},
+        style::{PseudoState, StateStyles},
+    },
+    core::{Rectangle, RectangleId, Renderer},
 };
 use block_states::{Positioned, Unpositioned};
 
 pub mod block_states {
-    use crate::{Bounds, RectangleId};
+    use crate::{Bounds, HitShape, RectangleId};
 
     pub struct Unpositioned;
 
     pub struct Positioned {
         pub(super) rectangle_id: RectangleId,
         pub(super) bounds:       Bounds,
+        // radians, clockwise on screen around the center
+        pub(super) rotation:     f32,
+        pub(super) hit_shape:    HitShape,
+        pub(super) tag:          Option<u64>,
+        // What the block was last built with, which its clip rect is
+        // scaled by.
+        pub(super) dpr:          f32,
     }
 }
 
-#[derive(Clone, Builder)]
+#[derive(Debug, Clone, PartialEq, Builder)]
 #[builder(const)]
 pub struct BlockStyle {
     #[builder(default = [1.0, 1.0, 1.0, 1.0])]
@@ -41,6 +55,210 @@ pub struct BlockStyle {
     outline_color: [f32; 4],
     #[builder(default = 0.0)]
     outline_size:  f32,
+    // dash_length, gap_length of the border; solid unless both are
+    // positive
+    #[builder(default = [0.0, 0.0])]
+    dash_pattern:  [f32; 2],
+    #[builder(default = 0.0)]
+    dash_offset:   f32,
+    // Blocks with a higher one are drawn above, whatever the order they
+    // were added in.
+    #[builder(default = 0)]
+    z_index:       i32,
+}
+
+impl BlockStyle {
+    #[must_use]
+    pub const fn pack(&self) -> PackedBlockStyle {
+        let sizes = [
+            self.corner_radii[0],
+            self.corner_radii[1],
+            self.corner_radii[2],
+            self.corner_radii[3],
+            self.border_size,
+            self.shadow_offset[0],
+            self.shadow_offset[1],
+            self.shadow_blur,
+            self.shadow_spread,
+            self.outline_size,
+            self.dash_pattern[0],
+            self.dash_pattern[1],
+            self.dash_offset,
+        ];
+        let mut packed_sizes = [0; 13];
+        let mut index = 0;
+        while index < sizes.len() {
+            // Adding zero turns -0.0 into 0.0, so both pack the same.
+            packed_sizes[index] = (sizes[index] + 0.0).to_bits();
+            index += 1;
+        }
+        PackedBlockStyle {
+            colors:  [
+                to_rgba8(self.fill_color),
+                to_rgba8(self.border_color),
+                to_rgba8(self.shadow_color),
+                to_rgba8(self.outline_color),
+            ],
+            sizes:   packed_sizes,
+            z_index: self.z_index,
+        }
+    }
+}
+
+// The fields of a `BlockStyle` to change, e.g. what a hovered button
+// does differently from an idle one:
+//
+// const HOVERED: StylePatch =
+//     StylePatch::builder().fill_color(ACCENT_LIGHT).build();
+// let hovered = idle.merged(&HOVERED);
+#[derive(Debug, Clone, Copy, Default, PartialEq, Builder)]
+#[builder(const)]
+pub struct StylePatch {
+    fill_color:    Option<[f32; 4]>,
+    corner_radii:  Option<[f32; 4]>,
+    border_color:  Option<[f32; 4]>,
+    border_size:   Option<f32>,
+    shadow_color:  Option<[f32; 4]>,
+    shadow_offset: Option<[f32; 2]>,
+    shadow_blur:   Option<f32>,
+    shadow_spread: Option<f32>,
+    outline_color: Option<[f32; 4]>,
+    outline_size:  Option<f32>,
+    dash_pattern:  Option<[f32; 2]>,
+    dash_offset:   Option<f32>,
+    z_index:       Option<i32>,
+}
+
+impl BlockStyle {
+    #[must_use]
+    pub const fn merged(&self, patch: &StylePatch) -> Self {
+        Self {
+            fill_color:    or(patch.fill_color, self.fill_color),
+            corner_radii:  or(patch.corner_radii, self.corner_radii),
+            border_color:  or(patch.border_color, self.border_color),
+            border_size:   or(patch.border_size, self.border_size),
+            shadow_color:  or(patch.shadow_color, self.shadow_color),
+            shadow_offset: or(patch.shadow_offset, self.shadow_offset),
+            shadow_blur:   or(patch.shadow_blur, self.shadow_blur),
+            shadow_spread: or(patch.shadow_spread, self.shadow_spread),
+            outline_color: or(patch.outline_color, self.outline_color),
+            outline_size:  or(patch.outline_size, self.outline_size),
+            dash_pattern:  or(patch.dash_pattern, self.dash_pattern),
+            dash_offset:   or(patch.dash_offset, self.dash_offset),
+            z_index:       or(patch.z_index, self.z_index),
+        }
+    }
+}
+
+impl BlockStyle {
+    #[must_use]
+    #[inline(always)]
+    pub const fn z_index(&self) -> i32 { self.z_index }
+
+    // The style `t` of the way to `other`, e.g. while animating. The z
+    // index switches only at the end.
+    #[must_use]
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        let lerp_4 = |from: [f32; 4], to: [f32; 4]| {
+            [0, 1, 2, 3].map(|index| lerp(from[index], to[index]))
+        };
+        let lerp_2 = |from: [f32; 2], to: [f32; 2]| {
+            [0, 1].map(|index| lerp(from[index], to[index]))
+        };
+        Self {
+            fill_color:    lerp_4(self.fill_color, other.fill_color),
+            corner_radii:  lerp_4(self.corner_radii, other.corner_radii),
+            border_color:  lerp_4(self.border_color, other.border_color),
+            border_size:   lerp(self.border_size, other.border_size),
+            shadow_color:  lerp_4(self.shadow_color, other.shadow_color),
+            shadow_offset: lerp_2(self.shadow_offset, other.shadow_offset),
+            shadow_blur:   lerp(self.shadow_blur, other.shadow_blur),
+            shadow_spread: lerp(self.shadow_spread, other.shadow_spread),
+            outline_color: lerp_4(self.outline_color, other.outline_color),
+            outline_size:  lerp(self.outline_size, other.outline_size),
+            dash_pattern:  lerp_2(self.dash_pattern, other.dash_pattern),
+            dash_offset:   lerp(self.dash_offset, other.dash_offset),
+            z_index:       match t < 1.0 {
+                true => self.z_index,
+                false => other.z_index,
+            },
+        }
+    }
+}
+
+impl StateStyles<BlockStyle> {
+    // Adds the state's variant as the base style with `patch` applied.
+    #[must_use]
+    pub fn with_patch(self, state: PseudoState, patch: &StylePatch) -> Self {
+        let style = self.base().merged(patch);
+        self.with(state, style)
+    }
+}
+
+#[inline(always)]
+const fn or<T: Copy>(value: Option<T>, default: T) -> T {
+    match value {
+        Some(value) => value,
+        None => default,
+    }
+}
+
+// A `BlockStyle` in 72 bytes with colors rounded to 8 bits per channel,
+// for keeping many styles in statics or caches and telling cheaply
+// whether one changed. Two styles pack the same when they'd look the
+// same on an 8-bit surface:
+//
+// static BUTTON: PackedBlockStyle =
+//     BlockStyle::builder().fill_color(ACCENT).build().pack();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedBlockStyle {
+    // fill, border, shadow, outline
+    colors:  [u32; 4],
+    sizes:   [u32; 13],
+    z_index: i32,
+}
+
+impl PackedBlockStyle {
+    #[must_use]
+    pub const fn unpack(&self) -> BlockStyle {
+        let mut sizes = [0.0; 13];
+        let mut index = 0;
+        while index < sizes.len() {
+            sizes[index] = f32::from_bits(self.sizes[index]);
+            index += 1;
+        }
+        let [
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+            border_size,
+            shadow_x,
+            shadow_y,
+            shadow_blur,
+            shadow_spread,
+            outline_size,
+            dash_length,
+            gap_length,
+            dash_offset,
+        ] = sizes;
+        BlockStyle {
+            fill_color: rgba8(self.colors[0]),
+            corner_radii: [top_left, top_right, bottom_right, bottom_left],
+            border_color: rgba8(self.colors[1]),
+            border_size,
+            shadow_color: rgba8(self.colors[2]),
+            shadow_offset: [shadow_x, shadow_y],
+            shadow_blur,
+            shadow_spread,
+            outline_color: rgba8(self.colors[3]),
+            outline_size,
+            dash_pattern: [dash_length, gap_length],
+            dash_offset,
+            z_index: self.z_index,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -91,8 +309,18 @@ impl Block<Positioned> {
     ) -> Self {
         let rectangle = build_rectangle(view_projection, dpr, &bounds, &style);
         let rectangle_id = renderer.add_rectangle(&rectangle);
+        if style.z_index != 0 {
+            renderer.set_rectangle_z_index(rectangle_id, style.z_index);
+        }
 
-        let state = Positioned { rectangle_id, bounds: bounds };
+        let state = Positioned {
+            rectangle_id,
+            bounds,
+            rotation: 0.0,
+            hit_shape: HitShape::Rect,
+            tag: None,
+            dpr,
+        };
         Self { state, style }
     }
 
@@ -108,6 +336,44 @@ impl Block<Positioned> {
     #[inline(always)]
     pub const fn clip_rect(&self) -> [f32; 4] { self.state.bounds.clip_rect }
 
+    #[must_use]
+    #[inline(always)]
+    pub const fn rotation(&self) -> f32 { self.state.rotation }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn style(&self) -> &BlockStyle { &self.style }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn rectangle_id(&self) -> RectangleId {
+        self.state.rectangle_id
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn bounds(&self) -> &Bounds { &self.state.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn hit_shape(&self) -> &HitShape { &self.state.hit_shape }
+
+    // Presses outside of the shape fall through to what's behind, e.g.
+    // around a round button. The whole bounds take them by default.
+    #[inline(always)]
+    pub fn set_hit_shape(&mut self, hit_shape: HitShape) {
+        self.state.hit_shape = hit_shape;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn tag(&self) -> Option<u64> { self.state.tag }
+
+    // Whatever the application wants to find the block by, e.g. a key
+    // into a `TagMap` of domain objects.
+    #[inline(always)]
+    pub const fn set_tag(&mut self, tag: Option<u64>) { self.state.tag = tag; }
+
     #[inline(always)]
     const fn set_position(&mut self, position: [f32; 2]) {
         self.state.bounds.position = position;
@@ -130,21 +396,9 @@ impl Block<Positioned> {
         dpr: f32,
         renderer: &mut Renderer,
     ) {
-        if let Some(rectangle) =
-            renderer.get_mut_rectangle(self.state.rectangle_id)
-        {
-            let (model, half_size) = build_model(size, self.position(), dpr);
-            let mvp = *view_projection * model;
-
-            rectangle.mvp = mvp.to_cols_array_2d();
-            rectangle.sizes = [
-                half_size[0],
-                half_size[1],
-                self.style.shadow_offset[0],
-                self.style.shadow_offset[1],
-            ];
-        }
         self.set_size(size);
+        self.update_dpr(dpr, renderer);
+        self.update_model(view_projection, dpr, renderer);
     }
 
     pub fn update_position(
@@ -154,21 +408,28 @@ impl Block<Positioned> {
         dpr: f32,
         renderer: &mut Renderer,
     ) {
-        if let Some(rectangle) =
-            renderer.get_mut_rectangle(self.state.rectangle_id)
-        {
-            let (model, half_size) = build_model(self.size(), position, dpr);
-            let mvp = *view_projection * model;
-
-            rectangle.mvp = mvp.to_cols_array_2d();
-            rectangle.sizes = [
-                half_size[0],
-                half_size[1],
-                self.style.shadow_offset[0],
-                self.style.shadow_offset[1],
-            ];
-        }
         self.set_position(position);
+        self.update_dpr(dpr, renderer);
+        // Moving keeps the size, so only the transform changes.
+        let (model, _) =
+            block_model(&self.state.bounds, self.state.rotation, dpr);
+        let mvp = *view_projection * model;
+        renderer.set_rectangle_transform(
+            self.state.rectangle_id,
+            mvp.to_cols_array_2d(),
+        );
+    }
+
+    pub fn update_rotation(
+        &mut self,
+        rotation: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.state.rotation = rotation;
+        self.update_dpr(dpr, renderer);
+        self.update_model(view_projection, dpr, renderer);
     }
 
     pub fn update_clip_rect(
@@ -179,16 +440,22 @@ impl Block<Positioned> {
         if let Some(rectangle) =
             renderer.get_mut_rectangle(self.state.rectangle_id)
         {
-            rectangle.clip_rect = *clip_rect;
+            rectangle.clip_rect =
+                clip_rect.map(|value| value * self.state.dpr);
         }
         self.set_clip_rect(*clip_rect);
     }
 
+    // Leaves the renderer untouched when nothing changed, so bindings can
+    // apply styles every frame without redraws.
     pub fn update_style(
         &mut self,
         style: BlockStyle,
         renderer: &mut Renderer,
     ) {
+        if style == self.style {
+            return;
+        }
         if let Some(rectangle) =
             renderer.get_mut_rectangle(self.state.rectangle_id)
         {
@@ -203,25 +470,163 @@ impl Block<Positioned> {
                 style.shadow_blur,
                 style.outline_size,
             ];
+            rectangle.dash = build_dash(&style);
+        }
+        if style.z_index != self.style.z_index {
+            renderer
+                .set_rectangle_z_index(self.state.rectangle_id, style.z_index);
         }
         self.style = style;
     }
 
+    pub fn update_fill_color(
+        &mut self,
+        fill_color: [f32; 4],
+        renderer: &mut Renderer,
+    ) {
+        if fill_color == self.style.fill_color {
+            return;
+        }
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            rectangle.fill_color = fill_color;
+        }
+        self.style.fill_color = fill_color;
+    }
+
+    pub fn update_border_color(
+        &mut self,
+        border_color: [f32; 4],
+        renderer: &mut Renderer,
+    ) {
+        if border_color == self.style.border_color {
+            return;
+        }
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            rectangle.border_color = border_color;
+        }
+        self.style.border_color = border_color;
+    }
+
+    pub fn update_outline_color(
+        &mut self,
+        outline_color: [f32; 4],
+        renderer: &mut Renderer,
+    ) {
+        if outline_color == self.style.outline_color {
+            return;
+        }
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            rectangle.outline_color = outline_color;
+        }
+        self.style.outline_color = outline_color;
+    }
+
+    pub fn update_corner_radii(
+        &mut self,
+        corner_radii: [f32; 4],
+        renderer: &mut Renderer,
+    ) {
+        if corner_radii == self.style.corner_radii {
+            return;
+        }
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            rectangle.corner_radii = corner_radii;
+        }
+        self.style.corner_radii = corner_radii;
+    }
+
+    // Moves the border's dashes along it without touching the rest of the
+    // style, e.g. every frame for marching ants.
+    pub fn update_dash_offset(
+        &mut self,
+        dash_offset: f32,
+        renderer: &mut Renderer,
+    ) {
+        if dash_offset == self.style.dash_offset {
+            return;
+        }
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            rectangle.dash[2] = dash_offset;
+        }
+        self.style.dash_offset = dash_offset;
+    }
+
     #[inline(always)]
     pub fn destroy(&self, renderer: &mut Renderer) {
         renderer.remove_rectangle(self.state.rectangle_id);
     }
 
+    // Rescales the clip rect when the block moves to a screen with
+    // another device pixel ratio.
+    fn update_dpr(&mut self, dpr: f32, renderer: &mut Renderer) {
+        if dpr == self.state.dpr {
+            return;
+        }
+        self.state.dpr = dpr;
+        let clip_rect = self.state.bounds.clip_rect;
+        self.update_clip_rect(&clip_rect, renderer);
+    }
+
+    fn update_model(
+        &self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(rectangle) =
+            renderer.get_mut_rectangle(self.state.rectangle_id)
+        {
+            let (model, half_size) =
+                block_model(&self.state.bounds, self.state.rotation, dpr);
+            let mvp = *view_projection * model;
+
+            rectangle.mvp = mvp.to_cols_array_2d();
+            rectangle.rect_and_shadow = [
+                half_size[0],
+                half_size[1],
+                self.style.shadow_offset[0],
+                self.style.shadow_offset[1],
+            ];
+        }
+    }
+
+    // Rotated blocks are hit tested in their own frame.
     #[must_use]
-    #[inline(always)]
-    pub const fn contains(&self, position: [f32; 2]) -> bool {
-        let [x, y] = self.position();
-        let [height, width] = self.size();
+    pub fn contains(&self, position: [f32; 2]) -> bool {
+        let bounds = &self.state.bounds;
+        let position = match self.state.rotation {
+            0.0 => position,
+            rotation => {
+                let [x, y] = bounds.position;
+                let [width, height] = bounds.size;
+                let center = [x + width / 2.0, y + height / 2.0];
+                let offset =
+                    [position[0] - center[0], position[1] - center[1]];
+                let (sin, cos) = (-rotation).sin_cos();
+                [
+                    center[0] + offset[0] * cos - offset[1] * sin,
+                    center[1] + offset[0] * sin + offset[1] * cos,
+                ]
+            }
+        };
+        self.state.hit_shape.contains(bounds, position)
+    }
 
-        position[0] >= x
-            && position[0] <= x + width
-            && position[1] >= y
-            && position[1] <= y + height
+    // The block's tag, if it's hit at `position`.
+    #[must_use]
+    #[inline(always)]
+    pub fn tag_at(&self, position: [f32; 2]) -> Option<u64> {
+        self.state.tag.filter(|_| self.contains(position))
     }
 
     #[must_use]
@@ -234,7 +639,61 @@ impl Block<Positioned> {
     }
 }
 
-fn build_rectangle(
+impl Inspect for Block<Positioned> {
+    fn inspect(&self, id: &str) -> DumpNode {
+        let style = &self.style;
+        DumpNode::new(id, "Block")
+            .with_bounds(self.state.bounds.clone())
+            .with_style("fill_color", style.fill_color)
+            .with_style("corner_radii", style.corner_radii)
+            .with_style("border_color", style.border_color)
+            .with_style("border_size", style.border_size)
+            .with_style("shadow_color", style.shadow_color)
+            .with_style("shadow_offset", style.shadow_offset)
+            .with_style("shadow_blur", style.shadow_blur)
+            .with_style("shadow_spread", style.shadow_spread)
+            .with_style("outline_color", style.outline_color)
+            .with_style("outline_size", style.outline_size)
+            .with_style("dash_pattern", style.dash_pattern)
+            .with_style("dash_offset", style.dash_offset)
+            .with_style("z_index", style.z_index)
+            .with_style("tag", self.state.tag)
+    }
+}
+
+// The tag of the topmost tagged block hit at `position`, taking `blocks`
+// in the order they were added; like drawing, a higher z-index wins over
+// that order.
+#[must_use]
+pub fn hit_test_tags<'a>(
+    blocks: impl Iterator<Item = &'a Block<Positioned>>,
+    position: [f32; 2],
+) -> Option<u64> {
+    blocks
+        .filter_map(|block| {
+            let tag = block.tag_at(position)?;
+            Some((block.style.z_index, tag))
+        })
+        .max_by_key(|(z_index, _)| *z_index)
+        .map(|(_, tag)| tag)
+}
+
+// Unrotated blocks keep the model `build_rectangle` gives them.
+pub(crate) fn block_model(
+    bounds: &Bounds,
+    rotation: f32,
+    dpr: f32,
+) -> (Mat4, [f32; 2]) {
+    if rotation == 0.0 {
+        return build_model(bounds.size, bounds.position, dpr);
+    }
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let center = [x + width / 2.0, y + height / 2.0];
+    build_rotated_model(bounds.size, center, rotation, dpr)
+}
+
+pub(crate) fn build_rectangle(
     view_projection: &Mat4,
     dpr: f32,
     bounds: &Bounds,
@@ -262,26 +721,93 @@ fn build_rectangle(
         .corner_radii(block_style.corner_radii)
         .shadow_color(block_style.shadow_color)
         .outline_color(block_style.outline_color)
-        .clip_rect(bounds.clip_rect)
+        .clip_rect(bounds.clip_rect.map(|value| value * dpr))
         .rect_and_shadow(rect_and_shadow)
         .sizes(sizes)
+        .dash(build_dash(block_style))
         .build()
 }
 
-fn build_model(
-    size: [f32; 2],
-    position: [f32; 2],
-    dpr: f32,
-) -> (Mat4, [f32; 2]) {
-    let size = [size[0] * dpr, size[1] * dpr];
-    let position = [position[0] * dpr, position[1] * dpr];
+#[inline(always)]
+const fn build_dash(block_style: &BlockStyle) -> [f32; 4] {
+    let [dash_length, gap_length] = block_style.dash_pattern;
+    [dash_length, gap_length, block_style.dash_offset, 0.0]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
 
-    let half_size = [size[0] / 2.0, size[1] / 2.0];
-    let center =
-        Vec3::new(position[0] + half_size[0], position[1] + half_size[1], 0.0);
-    let scale = Vec3::new(half_size[0], half_size[1], 1.0);
-    let model =
-        Mat4::from_scale_rotation_translation(scale, Quat::IDENTITY, center);
+    use super::{BlockStyle, PackedBlockStyle, StylePatch};
+    use crate::components::{
+        common::rgba8,
+        style::{InteractionState, PseudoState, StateStyles},
+    };
 
-    (model, half_size)
+    static BUTTON: PackedBlockStyle = BlockStyle::builder()
+        .fill_color(rgba8(0x3584e4ff))
+        .corner_radii([4.0; 4])
+        .dash_pattern([2.0, -0.0])
+        .build()
+        .pack();
+
+    #[rstest]
+    fn test_pack() {
+        let style = BUTTON.unpack();
+        assert_eq!(style.fill_color, rgba8(0x3584e4ff));
+        assert_eq!(style.corner_radii, [4.0; 4]);
+        assert_eq!(style.pack(), BUTTON);
+
+        // Below what an 8-bit surface shows.
+        let mut nudged = style.clone();
+        nudged.fill_color[0] += 0.001;
+        nudged.dash_pattern[1] = 0.0;
+        assert_eq!(nudged.pack(), BUTTON);
+
+        nudged.border_size = 1.0;
+        assert_ne!(nudged.pack(), BUTTON);
+
+        nudged.border_size = 0.0;
+        nudged.z_index = 1;
+        assert_ne!(nudged.pack(), BUTTON);
+        assert_eq!(nudged.pack().unpack().z_index, 1);
+    }
+
+    #[rstest]
+    fn test_merged() {
+        const HOVERED: StylePatch = StylePatch::builder()
+            .fill_color([0.0, 0.0, 1.0, 1.0])
+            .border_size(2.0)
+            .build();
+        let idle = BUTTON.unpack();
+        let hovered = idle.merged(&HOVERED);
+        assert_eq!(hovered.fill_color, [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(hovered.border_size, 2.0);
+        assert_eq!(hovered.corner_radii, idle.corner_radii);
+        assert_eq!(idle.merged(&StylePatch::default()), idle);
+
+        let styles = StateStyles::new(idle.clone())
+            .with_patch(PseudoState::Hovered, &HOVERED);
+        let state =
+            InteractionState { is_hovered: true, ..Default::default() };
+        assert_eq!(*styles.resolve(&state), hovered);
+    }
+
+    #[rstest]
+    fn test_lerp() {
+        let from = BlockStyle::builder()
+            .fill_color([0.0, 0.0, 0.0, 1.0])
+            .border_size(2.0)
+            .build();
+        let to = BlockStyle::builder()
+            .fill_color([1.0, 0.5, 0.0, 1.0])
+            .border_size(4.0)
+            .z_index(1)
+            .build();
+        let halfway = from.lerp(&to, 0.5);
+        assert_eq!(halfway.fill_color, [0.5, 0.25, 0.0, 1.0]);
+        assert_eq!(halfway.border_size, 3.0);
+        assert_eq!(halfway.z_index, 0);
+        assert_eq!(from.lerp(&to, 1.0), to);
+    }
 }
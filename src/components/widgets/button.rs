@@ -0,0 +1,409 @@
+use std::{
+    f32::consts::{PI, TAU},
+    time::Duration,
+};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{
+    AccessNode, AccessRole, Availability, Block, BlockStyle,
+    block_states::Positioned,
+};
+use crate::{
+    components::common::{Arc, Bounds, InputState, MouseButtonState},
+    core::{ArcSegment, ArcSegmentId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ButtonState {
+    #[default]
+    Normal,
+    Hovered,
+    Pressed,
+    Disabled,
+    ReadOnly,
+    // Shows a spinner until the action it started is done.
+    Loading,
+}
+
+impl ButtonState {
+    #[must_use]
+    pub const fn availability(self) -> Availability {
+        match self {
+            Self::Normal | Self::Hovered | Self::Pressed => {
+                Availability::Enabled
+            }
+            Self::Disabled => Availability::Disabled,
+            Self::ReadOnly => Availability::ReadOnly,
+            Self::Loading => Availability::Loading,
+        }
+    }
+}
+
+#[derive(Clone, Builder)]
+pub struct ButtonStyle {
+    normal:        BlockStyle,
+    // The normal style unless given.
+    hovered:       Option<BlockStyle>,
+    pressed:       Option<BlockStyle>,
+    disabled:      Option<BlockStyle>,
+    read_only:     Option<BlockStyle>,
+    loading:       Option<BlockStyle>,
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    spinner_color: [f32; 4],
+    // Turns per second.
+    #[builder(default = 1.0)]
+    spinner_speed: f32,
+}
+
+impl ButtonStyle {
+    #[must_use]
+    pub fn get(&self, state: ButtonState) -> &BlockStyle {
+        let style = match state {
+            ButtonState::Normal => None,
+            ButtonState::Hovered => self.hovered.as_ref(),
+            ButtonState::Pressed => self.pressed.as_ref(),
+            ButtonState::Disabled => self.disabled.as_ref(),
+            ButtonState::ReadOnly => self.read_only.as_ref(),
+            ButtonState::Loading => self.loading.as_ref(),
+        };
+        style.unwrap_or(&self.normal)
+    }
+}
+
+// A block that follows the mouse through its hovered and pressed styles
+// and is clicked when pressed and released on it. Sync it with every
+// event and call `begin_frame` once per frame, before the events. Only
+// enabled buttons react to input; `tick` turns the spinner of a loading
+// one.
+pub struct Button {
+    style:            ButtonStyle,
+    state:            ButtonState,
+    was_down:         bool,
+    is_press_started: bool,
+    was_clicked:      bool,
+    block:            Block<Positioned>,
+    // The arc and its start angle while loading.
+    spinner:          Option<(ArcSegmentId, f32)>,
+}
+
+impl Button {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        style: ButtonStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let block = Block::<Positioned>::new(
+            bounds,
+            style.normal.clone(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        Self {
+            style,
+            state: ButtonState::Normal,
+            was_down: false,
+            is_press_started: false,
+            was_clicked: false,
+            block,
+            spinner: None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn block(&self) -> &Block<Positioned> { &self.block }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn state(&self) -> ButtonState { self.state }
+
+    // Whether the button was clicked since `begin_frame`.
+    #[must_use]
+    #[inline(always)]
+    pub const fn was_clicked(&self) -> bool { self.was_clicked }
+
+    #[inline(always)]
+    pub const fn begin_frame(&mut self) { self.was_clicked = false; }
+
+    #[must_use]
+    pub fn accessibility(&self) -> AccessNode {
+        let availability = self.state.availability();
+        AccessNode {
+            role:         AccessRole::Button,
+            label:        String::new(),
+            bounds:       Bounds {
+                position:  self.block.position(),
+                size:      self.block.size(),
+                clip_rect: self.block.clip_rect(),
+            },
+            is_focusable: true,
+            is_disabled:  availability == Availability::Disabled,
+            is_read_only: availability == Availability::ReadOnly,
+            is_busy:      availability == Availability::Loading,
+        }
+    }
+
+    // Events consumed by someone else only take the hover away. Returns
+    // whether the state changed.
+    pub fn sync_input(
+        &mut self,
+        input_state: &InputState,
+        renderer: &mut Renderer,
+    ) -> bool {
+        // Tracked while not enabled too, so a button enabled while the
+        // mouse is held doesn't take that for a new press.
+        let is_down =
+            matches!(input_state.left_mouse_button(), MouseButtonState::Down);
+        let is_pressed = is_down && !self.was_down;
+        self.was_down = is_down;
+
+        if !self.state.availability().is_enabled() {
+            return false;
+        }
+        let is_hovered = !input_state.is_consumed()
+            && input_state
+                .mouse_position()
+                .is_some_and(|position| self.block.contains(position));
+
+        let (state, is_clicked) = next_state(
+            is_hovered,
+            is_pressed,
+            is_down,
+            &mut self.is_press_started,
+        );
+        self.was_clicked |= is_clicked;
+        self.set_state(state, renderer)
+    }
+
+    // Enabling a button that is enabled already keeps its hover. Returns
+    // whether the state changed.
+    pub fn set_availability(
+        &mut self,
+        availability: Availability,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let state = match availability {
+            Availability::Enabled
+                if self.state.availability().is_enabled() =>
+            {
+                return false;
+            }
+            Availability::Enabled => ButtonState::Normal,
+            Availability::Disabled => ButtonState::Disabled,
+            Availability::ReadOnly => ButtonState::ReadOnly,
+            Availability::Loading => ButtonState::Loading,
+        };
+        self.is_press_started = false;
+        match state {
+            ButtonState::Loading if self.spinner.is_none() => {
+                let spinner = self.build_spinner(0.0, view_projection, dpr);
+                self.spinner = Some((renderer.add_arc(&spinner), 0.0));
+            }
+            ButtonState::Loading => {}
+            _ => self.remove_spinner(renderer),
+        }
+        self.set_state(state, renderer)
+    }
+
+    // Turns the spinner while loading. Returns whether it turned.
+    pub fn tick(
+        &mut self,
+        dt: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let Some((arc_id, angle)) = self.spinner else {
+            return false;
+        };
+        let angle = spinner_angle(angle, self.style.spinner_speed, dt);
+        self.spinner = Some((arc_id, angle));
+        let spinner = self.build_spinner(angle, view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_arc(arc_id) {
+            *instance = spinner;
+        }
+        true
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.block
+            .update_size(bounds.size, view_projection, dpr, renderer);
+        self.block.update_position(
+            bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.block.update_clip_rect(&bounds.clip_rect, renderer);
+        if let Some((arc_id, angle)) = self.spinner {
+            let spinner = self.build_spinner(angle, view_projection, dpr);
+            if let Some(instance) = renderer.get_mut_arc(arc_id) {
+                *instance = spinner;
+            }
+        }
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: ButtonStyle,
+        renderer: &mut Renderer,
+    ) {
+        self.block
+            .update_style(style.get(self.state).clone(), renderer);
+        self.style = style;
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.block.destroy(renderer);
+        if let Some((arc_id, _)) = self.spinner {
+            renderer.remove_arc(arc_id);
+        }
+    }
+
+    fn set_state(
+        &mut self,
+        state: ButtonState,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if state == self.state {
+            return false;
+        }
+        self.state = state;
+        self.block
+            .update_style(self.style.get(state).clone(), renderer);
+        true
+    }
+
+    fn remove_spinner(&mut self, renderer: &mut Renderer) {
+        if let Some((arc_id, _)) = self.spinner.take() {
+            renderer.remove_arc(arc_id);
+        }
+    }
+
+    // A three-quarter ring in the middle of the button.
+    fn build_spinner(
+        &self,
+        angle: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> ArcSegment {
+        let [x, y] = self.block.position();
+        let [width, height] = self.block.size();
+        let radius = width.min(height) * 0.25;
+        Arc {
+            center: [x + width / 2.0, y + height / 2.0],
+            radius,
+            thickness: (radius * 0.3).max(1.0),
+            start_angle: angle,
+            sweep_angle: PI * 1.5,
+            round_caps: true,
+        }
+        .build_segment(
+            self.style.spinner_color,
+            self.block.clip_rect(),
+            view_projection,
+            dpr,
+        )
+    }
+}
+
+#[inline(always)]
+fn spinner_angle(angle: f32, speed: f32, dt: Duration) -> f32 {
+    (angle + TAU * speed * dt.as_secs_f32()).rem_euclid(TAU)
+}
+
+// A press has to start on the button to click it; moving off while it's
+// held shows the button as normal, and releasing there cancels it.
+fn next_state(
+    is_hovered: bool,
+    is_pressed: bool,
+    is_down: bool,
+    is_press_started: &mut bool,
+) -> (ButtonState, bool) {
+    let mut is_clicked = false;
+    if is_pressed && is_hovered {
+        *is_press_started = true;
+    } else if !is_down && *is_press_started {
+        *is_press_started = false;
+        is_clicked = is_hovered;
+    }
+    let state = match (is_hovered, *is_press_started) {
+        (true, true) => ButtonState::Pressed,
+        (true, false) => ButtonState::Hovered,
+        (false, _) => ButtonState::Normal,
+    };
+    (state, is_clicked)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use std::{f32::consts::PI, time::Duration};
+
+    use super::{ButtonState, next_state, spinner_angle};
+    use crate::components::widgets::Availability;
+
+    #[rstest]
+    // hovered, pressed, down, press started, expected state, click
+    #[case(true, false, false, false, ButtonState::Hovered, false)]
+    #[case(true, true, true, false, ButtonState::Pressed, false)]
+    #[case(true, false, false, true, ButtonState::Hovered, true)]
+    // Moved off while held, then released there.
+    #[case(false, false, true, true, ButtonState::Normal, false)]
+    #[case(false, false, false, true, ButtonState::Normal, false)]
+    // Pressed elsewhere and moved onto it.
+    #[case(true, false, true, false, ButtonState::Hovered, false)]
+    fn test_next_state(
+        #[case] is_hovered: bool,
+        #[case] is_pressed: bool,
+        #[case] is_down: bool,
+        #[case] is_press_started: bool,
+        #[case] expected: ButtonState,
+        #[case] is_clicked: bool,
+    ) {
+        let mut is_press_started = is_press_started;
+        let actual =
+            next_state(is_hovered, is_pressed, is_down, &mut is_press_started);
+        assert_eq!(actual, (expected, is_clicked));
+    }
+
+    #[rstest]
+    #[case(ButtonState::Hovered, Availability::Enabled)]
+    #[case(ButtonState::Disabled, Availability::Disabled)]
+    #[case(ButtonState::ReadOnly, Availability::ReadOnly)]
+    #[case(ButtonState::Loading, Availability::Loading)]
+    fn test_availability(
+        #[case] state: ButtonState,
+        #[case] expected: Availability,
+    ) {
+        assert_eq!(state.availability(), expected);
+    }
+
+    #[rstest]
+    #[case(0.0, Duration::from_millis(250), PI / 2.0)]
+    // Wraps around.
+    #[case(PI * 1.5, Duration::from_millis(750), PI)]
+    fn test_spinner_angle(
+        #[case] angle: f32,
+        #[case] dt: Duration,
+        #[case] expected: f32,
+    ) {
+        let actual = spinner_angle(angle, 1.0, dt);
+        assert!((actual - expected).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,390 @@
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::{
+        PageBreaks,
+        common::{Bar, Bounds, intersect_rects},
+    },
+    core::{PageBreak, Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct CollapsibleStyle {
+    #[builder(default = [0.92, 0.92, 0.92, 1.0])]
+    header_color:      [f32; 4],
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    content_color:     [f32; 4],
+    #[builder(default = [0.3, 0.3, 0.3, 1.0])]
+    chevron_color:     [f32; 4],
+    #[builder(default = 10.0)]
+    chevron_size:      f32,
+    #[builder(default = 2.0)]
+    chevron_thickness: f32,
+    // Distance from the left edge of the header to the chevron.
+    #[builder(default = 12.0)]
+    chevron_padding:   f32,
+    #[builder(default = Duration::from_millis(200))]
+    duration:          Duration,
+}
+
+pub struct Collapsible {
+    // Bounds of the header; the content hangs below it.
+    bounds:         Bounds,
+    content_height: f32,
+    style:          CollapsibleStyle,
+    is_expanded:    bool,
+    progress:       f32,
+    header:         Block<Positioned>,
+    content:        Block<Positioned>,
+    chevron:        [RectangleId; 2],
+}
+
+impl Collapsible {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        content_height: f32,
+        is_expanded: bool,
+        style: CollapsibleStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let progress = if is_expanded { 1.0 } else { 0.0 };
+
+        let header_style =
+            BlockStyle::builder().fill_color(style.header_color).build();
+        let header = Block::<Positioned>::new(
+            bounds.clone(),
+            header_style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let content_style = BlockStyle::builder()
+            .fill_color(style.content_color)
+            .build();
+        let content = Block::<Positioned>::new(
+            content_bounds(&bounds, content_height, progress),
+            content_style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let chevron =
+            build_chevron(&bounds, &style, progress, view_projection, dpr)
+                .map(|bar| renderer.add_rectangle(&bar));
+
+        Self {
+            bounds,
+            content_height,
+            style,
+            is_expanded,
+            progress,
+            header,
+            content,
+            chevron,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn content_height(&self) -> f32 { self.content_height }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_expanded(&self) -> bool { self.is_expanded }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_animating(&self) -> bool {
+        self.progress != target_progress(self.is_expanded)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn height(&self) -> f32 {
+        self.bounds.size[1] + self.content_height * ease(self.progress)
+    }
+
+    // Children of the section are laid out in these bounds and clipped
+    // by their clip rect, which follows the expand animation.
+    #[must_use]
+    #[inline(always)]
+    pub fn content_bounds(&self) -> Bounds {
+        content_bounds(&self.bounds, self.content_height, self.progress)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn header_contains(&self, position: [f32; 2]) -> bool {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+
+        position[0] >= x
+            && position[0] <= x + width
+            && position[1] >= y
+            && position[1] <= y + height
+    }
+
+    #[inline(always)]
+    pub const fn set_expanded(&mut self, is_expanded: bool) {
+        self.is_expanded = is_expanded;
+    }
+
+    #[inline(always)]
+    pub const fn toggle(&mut self) { self.is_expanded = !self.is_expanded; }
+
+    pub fn tick(
+        &mut self,
+        dt: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let progress = advance_progress(
+            self.progress,
+            self.is_expanded,
+            dt,
+            self.style.duration,
+        );
+        if progress == self.progress {
+            return false;
+        }
+        self.progress = progress;
+        self.refresh(view_projection, dpr, renderer);
+
+        true
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.header.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.header.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.header
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn update_content_height(
+        &mut self,
+        content_height: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.content_height = content_height;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.header.destroy(renderer);
+        self.content.destroy(renderer);
+        for rectangle_id in self.chevron {
+            renderer.remove_rectangle(rectangle_id);
+        }
+    }
+
+    fn refresh(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let content_bounds = self.content_bounds();
+        self.content.update_position(
+            content_bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.content.update_size(
+            content_bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.content
+            .update_clip_rect(&content_bounds.clip_rect, renderer);
+
+        let chevron = build_chevron(
+            &self.bounds,
+            &self.style,
+            self.progress,
+            view_projection,
+            dpr,
+        );
+        for (rectangle_id, bar) in self.chevron.iter().zip(chevron) {
+            if let Some(rectangle) = renderer.get_mut_rectangle(*rectangle_id)
+            {
+                *rectangle = bar;
+            }
+        }
+    }
+}
+
+// Headers stay with their content, so a section isn't split unless it is
+// taller than a page.
+impl PageBreaks for Collapsible {
+    fn page_breaks(&self, dpr: f32) -> Vec<PageBreak> {
+        let top = self.bounds.position[1];
+        vec![PageBreak::AvoidInside {
+            top:    top * dpr,
+            bottom: (top + self.height()) * dpr,
+        }]
+    }
+}
+
+#[inline(always)]
+fn target_progress(is_expanded: bool) -> f32 {
+    if is_expanded { 1.0 } else { 0.0 }
+}
+
+fn advance_progress(
+    progress: f32,
+    is_expanded: bool,
+    dt: Duration,
+    duration: Duration,
+) -> f32 {
+    let step = match duration.is_zero() {
+        true => 1.0,
+        false => dt.as_secs_f32() / duration.as_secs_f32(),
+    };
+    match is_expanded {
+        true => (progress + step).min(1.0),
+        false => (progress - step).max(0.0),
+    }
+}
+
+// Smoothstep: starts and ends slowly, so the section doesn't snap open.
+#[inline(always)]
+fn ease(progress: f32) -> f32 { progress * progress * (3.0 - 2.0 * progress) }
+
+fn content_bounds(
+    header_bounds: &Bounds,
+    content_height: f32,
+    progress: f32,
+) -> Bounds {
+    let [x, y] = header_bounds.position;
+    let [width, header_height] = header_bounds.size;
+    let visible_height = content_height * ease(progress);
+
+    // The content keeps its full size and is revealed by the clip rect,
+    // so children don't get squashed while animating.
+    let visible_rect = [x, y + header_height, width, visible_height];
+    Bounds {
+        position:  [x, y + header_height],
+        size:      [width, content_height],
+        clip_rect: intersect_rects(header_bounds.clip_rect, visible_rect),
+    }
+}
+
+// Two bars forming a chevron that points right when collapsed and turns
+// clockwise to point down as the section expands.
+fn build_chevron(
+    header_bounds: &Bounds,
+    style: &CollapsibleStyle,
+    progress: f32,
+    view_projection: &Mat4,
+    dpr: f32,
+) -> [Rectangle; 2] {
+    let [x, y] = header_bounds.position;
+    let half = style.chevron_size / 2.0;
+    let center = [
+        x + style.chevron_padding + half,
+        y + header_bounds.size[1] / 2.0,
+    ];
+    let (sin, cos) = (ease(progress) * FRAC_PI_2).sin_cos();
+    let rotate = |point: [f32; 2]| {
+        [
+            center[0] + point[0] * cos - point[1] * sin,
+            center[1] + point[0] * sin + point[1] * cos,
+        ]
+    };
+
+    let tip = rotate([half / 2.0, 0.0]);
+    [-half, half].map(|end_y| {
+        let end = rotate([-half / 2.0, end_y]);
+        Bar::between(end, tip, style.chevron_thickness).build_rectangle(
+            style.chevron_color,
+            header_bounds.clip_rect,
+            view_projection,
+            dpr,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::{advance_progress, content_bounds, ease};
+    use crate::Bounds;
+
+    const fn ms(value: u64) -> Duration { Duration::from_millis(value) }
+
+    #[rstest]
+    #[case(0.0, true, ms(50), ms(200), 0.25)]
+    #[case(0.5, true, ms(500), ms(200), 1.0)]
+    #[case(1.0, false, ms(100), ms(200), 0.5)]
+    #[case(0.1, false, ms(100), ms(200), 0.0)]
+    #[case(0.0, true, ms(16), Duration::ZERO, 1.0)]
+    fn test_advance_progress(
+        #[case] progress: f32,
+        #[case] is_expanded: bool,
+        #[case] dt: Duration,
+        #[case] duration: Duration,
+        #[case] expected: f32,
+    ) {
+        let progress = advance_progress(progress, is_expanded, dt, duration);
+        assert!((progress - expected).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[case(0.0, 0.0)]
+    #[case(0.5, 0.5)]
+    #[case(1.0, 1.0)]
+    fn test_ease(#[case] progress: f32, #[case] expected: f32) {
+        assert_eq!(ease(progress), expected);
+    }
+
+    #[rstest]
+    #[case(0.0, [0.0, 40.0, 100.0, 0.0])]
+    #[case(0.5, [0.0, 40.0, 100.0, 50.0])]
+    #[case(1.0, [0.0, 40.0, 100.0, 100.0])]
+    fn test_content_bounds(#[case] progress: f32, #[case] expected: [f32; 4]) {
+        let header = Bounds::without_clip_rect([0.0, 0.0], [100.0, 40.0]);
+        let bounds = content_bounds(&header, 100.0, progress);
+
+        assert_eq!(bounds.position, [0.0, 40.0]);
+        assert_eq!(bounds.size, [100.0, 100.0]);
+        assert_eq!(bounds.clip_rect, expected);
+    }
+}
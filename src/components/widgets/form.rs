@@ -0,0 +1,468 @@
+use bon::Builder;
+use glam::Mat4;
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::{
+        PageBreaks,
+        common::{Announcer, Bounds},
+    },
+    core::{PageBreak, Renderer},
+};
+
+// Returns an error message for an invalid value.
+pub type CustomValidator = dyn Fn(&str) -> Option<String>;
+
+pub enum Validator {
+    Required {
+        message: String,
+    },
+    #[cfg(feature = "regex")]
+    Pattern {
+        regex:   Regex,
+        message: String,
+    },
+    // Inclusive bounds on the value parsed as a number.
+    Range {
+        min:     f64,
+        max:     f64,
+        message: String,
+    },
+    Custom(Box<CustomValidator>),
+}
+
+impl Validator {
+    #[must_use]
+    pub fn required(message: impl Into<String>) -> Self {
+        Self::Required { message: message.into() }
+    }
+
+    #[cfg(feature = "regex")]
+    #[must_use]
+    pub fn pattern(regex: Regex, message: impl Into<String>) -> Self {
+        Self::Pattern { regex, message: message.into() }
+    }
+
+    #[must_use]
+    pub fn range(min: f64, max: f64, message: impl Into<String>) -> Self {
+        Self::Range { min, max, message: message.into() }
+    }
+
+    #[must_use]
+    pub fn custom(
+        validator: impl Fn(&str) -> Option<String> + 'static,
+    ) -> Self {
+        Self::Custom(Box::new(validator))
+    }
+
+    // Only `Required` rejects an empty value; the others leave optional
+    // fields alone.
+    #[must_use]
+    pub fn validate(&self, value: &str) -> Option<String> {
+        let is_empty = value.trim().is_empty();
+        match self {
+            Self::Required { message } => is_empty.then(|| message.clone()),
+            _ if is_empty => None,
+            #[cfg(feature = "regex")]
+            Self::Pattern { regex, message } => {
+                (!regex.is_match(value)).then(|| message.clone())
+            }
+            Self::Range { min, max, message } => {
+                let is_in_range = value
+                    .trim()
+                    .parse::<f64>()
+                    .is_ok_and(|number| (*min..=*max).contains(&number));
+                (!is_in_range).then(|| message.clone())
+            }
+            Self::Custom(validator) => validator(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValidateOn {
+    // Fields are checked whenever their value is set.
+    Change,
+    Submit,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct FormStyle {
+    #[builder(default = [0.86, 0.15, 0.15, 1.0])]
+    error_color:       [f32; 4],
+    #[builder(default = 1.5)]
+    error_border_size: f32,
+    #[builder(default = 4.0)]
+    corner_radius:     f32,
+    // Space kept beneath every field for its error message, so the form
+    // doesn't jump when errors come and go.
+    #[builder(default = 18.0)]
+    message_height:    f32,
+    #[builder(default = 8.0)]
+    spacing:           f32,
+}
+
+struct FormField {
+    height:     f32,
+    validators: Vec<Validator>,
+    value:      String,
+    error:      Option<String>,
+    bounds:     Bounds,
+    outline:    Block<Positioned>,
+}
+
+pub struct Form {
+    // Position and width of the form.
+    bounds:      Bounds,
+    validate_on: ValidateOn,
+    style:       FormStyle,
+    fields:      Vec<FormField>,
+    // Fields whose error appeared since the last `announce_errors`.
+    unannounced: Vec<usize>,
+}
+
+impl Form {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(
+        bounds: Bounds,
+        validate_on: ValidateOn,
+        style: FormStyle,
+    ) -> Self {
+        Self {
+            bounds,
+            validate_on,
+            style,
+            fields: Vec::new(),
+            unannounced: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    pub fn height(&self) -> f32 {
+        let heights = self.fields.iter().map(|field| field.height);
+        field_offsets(heights, &self.style)
+            .last()
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    // Where the input widget of the field goes.
+    #[must_use]
+    pub fn field_bounds(&self, index: usize) -> Option<&Bounds> {
+        self.fields.get(index).map(|field| &field.bounds)
+    }
+
+    // Where the caller draws the field's error message, if any.
+    #[must_use]
+    pub fn message_bounds(&self, index: usize) -> Option<Bounds> {
+        let bounds = &self.fields.get(index)?.bounds;
+        Some(Bounds {
+            position:  [
+                bounds.position[0],
+                bounds.position[1] + bounds.size[1],
+            ],
+            size:      [bounds.size[0], self.style.message_height],
+            clip_rect: bounds.clip_rect,
+        })
+    }
+
+    #[must_use]
+    pub fn value(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).map(|field| field.value.as_str())
+    }
+
+    // The error shown for the field, which lags behind its value until it
+    // is validated.
+    #[must_use]
+    pub fn error(&self, index: usize) -> Option<&str> {
+        self.fields.get(index)?.error.as_deref()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.fields.iter().enumerate().filter_map(|(index, field)| {
+            field.error.as_deref().map(|error| (index, error))
+        })
+    }
+
+    // Whether every field passes its validators right now, regardless of
+    // the errors shown.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.fields.iter().all(|field| {
+            first_error(&field.validators, &field.value).is_none()
+        })
+    }
+
+    pub fn add_field(
+        &mut self,
+        height: f32,
+        validators: Vec<Validator>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> usize {
+        let heights = self.fields.iter().map(|field| field.height);
+        let offsets = field_offsets(heights.chain([height]), &self.style);
+        let bounds =
+            row_bounds(&self.bounds, offsets[self.fields.len()], height);
+        let outline = Block::<Positioned>::new(
+            bounds.clone(),
+            outline_style(&self.style, false, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.fields.push(FormField {
+            height,
+            validators,
+            value: String::new(),
+            error: None,
+            bounds,
+            outline,
+        });
+
+        self.fields.len() - 1
+    }
+
+    pub fn set_value(
+        &mut self,
+        index: usize,
+        value: impl Into<String>,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(field) = self.fields.get_mut(index) else {
+            return;
+        };
+        field.value = value.into();
+        if self.validate_on == ValidateOn::Change {
+            self.validate_field(index, dpr, renderer);
+        }
+    }
+
+    pub fn validate_field(
+        &mut self,
+        index: usize,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let Some(field) = self.fields.get_mut(index) else {
+            return false;
+        };
+        let error = first_error(&field.validators, &field.value);
+        let is_valid = error.is_none();
+        if error != field.error {
+            if error.is_some() && !self.unannounced.contains(&index) {
+                self.unannounced.push(index);
+            }
+            field.error = error;
+            let style = outline_style(&self.style, !is_valid, dpr);
+            field.outline.update_style(style, renderer);
+        }
+
+        is_valid
+    }
+
+    // Validates every field, showing all errors, and returns whether the
+    // form may be submitted.
+    pub fn submit(&mut self, dpr: f32, renderer: &mut Renderer) -> bool {
+        let mut is_valid = true;
+        for index in 0..self.fields.len() {
+            is_valid &= self.validate_field(index, dpr, renderer);
+        }
+
+        is_valid
+    }
+
+    // Tells screen reader users about the errors that appeared since the
+    // last call, as those are only shown next to their fields.
+    pub fn announce_errors(&mut self, announcer: &mut Announcer) {
+        for index in self.unannounced.drain(..) {
+            if let Some(error) = &self.fields[index].error {
+                announcer.assertive(error.clone());
+            }
+        }
+    }
+
+    // Clears values and errors, e.g. after a successful submit.
+    pub fn reset(&mut self, dpr: f32, renderer: &mut Renderer) {
+        self.unannounced.clear();
+        for field in &mut self.fields {
+            field.value.clear();
+            if field.error.take().is_some() {
+                let style = outline_style(&self.style, false, dpr);
+                field.outline.update_style(style, renderer);
+            }
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        let heights = self.fields.iter().map(|field| field.height);
+        let offsets = field_offsets(heights, &self.style);
+        for (field, offset) in self.fields.iter_mut().zip(offsets) {
+            let bounds = row_bounds(&self.bounds, offset, field.height);
+            field.outline.update_clip_rect(&bounds.clip_rect, renderer);
+            field.outline.update_position(
+                bounds.position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            field.outline.update_size(
+                bounds.size,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            field.bounds = bounds;
+        }
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for field in &self.fields {
+            field.outline.destroy(renderer);
+        }
+    }
+}
+
+// A field is kept on one page with the space for its error message.
+impl PageBreaks for Form {
+    fn page_breaks(&self, dpr: f32) -> Vec<PageBreak> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let top = field.bounds.position[1];
+                let bottom = top + field.bounds.size[1];
+                PageBreak::AvoidInside {
+                    top:    top * dpr,
+                    bottom: (bottom + self.style.message_height) * dpr,
+                }
+            })
+            .collect()
+    }
+}
+
+fn row_bounds(form_bounds: &Bounds, offset: f32, height: f32) -> Bounds {
+    Bounds {
+        position:  [form_bounds.position[0], form_bounds.position[1] + offset],
+        size:      [form_bounds.size[0], height],
+        clip_rect: form_bounds.clip_rect,
+    }
+}
+
+fn first_error(validators: &[Validator], value: &str) -> Option<String> {
+    validators
+        .iter()
+        .find_map(|validator| validator.validate(value))
+}
+
+// The offset of every field from the top of the form, followed by the
+// height of the whole form.
+fn field_offsets(
+    heights: impl Iterator<Item = f32>,
+    style: &FormStyle,
+) -> Vec<f32> {
+    let mut offsets = vec![0.0];
+    for height in heights {
+        let offset = offsets.last().copied().unwrap_or(0.0);
+        offsets.push(offset + height + style.message_height + style.spacing);
+    }
+    let count = offsets.len();
+    if count > 1 {
+        offsets[count - 1] -= style.spacing;
+    }
+
+    offsets
+}
+
+// The outline is always there, only its border shows in the error state.
+fn outline_style(style: &FormStyle, is_invalid: bool, dpr: f32) -> BlockStyle {
+    let border_color = match is_invalid {
+        true => style.error_color,
+        false => [0.0; 4],
+    };
+    BlockStyle::builder()
+        .fill_color([0.0; 4])
+        .border_color(border_color)
+        .border_size(style.error_border_size * dpr)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{FormStyle, Validator, field_offsets, first_error};
+
+    #[rstest]
+    #[case("", Some("required"))]
+    #[case("  ", Some("required"))]
+    #[case("abc", Some("number"))]
+    #[case("150", Some("number"))]
+    #[case("42", Some("even"))]
+    #[case("40", None)]
+    fn test_first_error(#[case] value: &str, #[case] expected: Option<&str>) {
+        let validators = [
+            Validator::required("required"),
+            Validator::range(0.0, 100.0, "number"),
+            Validator::custom(|value| {
+                (value != "40").then(|| "even".to_owned())
+            }),
+        ];
+        assert_eq!(first_error(&validators, value).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("7", None)]
+    #[case("-1", Some("range"))]
+    fn test_validator_range_skips_empty(
+        #[case] value: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let validator = Validator::range(0.0, 10.0, "range");
+        assert_eq!(validator.validate(value).as_deref(), expected);
+    }
+
+    #[cfg(feature = "regex")]
+    #[rstest]
+    #[case("user@example.com", None)]
+    #[case("user@", Some("email"))]
+    fn test_validator_pattern(
+        #[case] value: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let regex = regex::Regex::new(r"^[^@\s]+@[^@\s]+\.\w+$").unwrap();
+        let validator = Validator::pattern(regex, "email");
+        assert_eq!(validator.validate(value).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case(&[], &[0.0])]
+    #[case(&[30.0], &[0.0, 48.0])]
+    #[case(&[30.0, 40.0], &[0.0, 56.0, 114.0])]
+    fn test_field_offsets(#[case] heights: &[f32], #[case] expected: &[f32]) {
+        let style = FormStyle::builder()
+            .message_height(18.0)
+            .spacing(8.0)
+            .build();
+        let offsets = field_offsets(heights.iter().copied(), &style);
+        assert_eq!(offsets, expected);
+    }
+}
@@ -0,0 +1,686 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bar, Bounds, intersect_rects},
+    core::{RectangleId, Renderer},
+};
+
+// Days are counted from an epoch of the caller's choosing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GanttTask {
+    pub start:    f32,
+    pub duration: f32,
+}
+
+impl GanttTask {
+    #[must_use]
+    #[inline(always)]
+    pub const fn end(&self) -> f32 { self.start + self.duration }
+}
+
+// The second task starts once the first one ends; both are row indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GanttDependency {
+    pub from: usize,
+    pub to:   usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GanttEvent {
+    // Sent once a drag ends, with the task's new start.
+    Rescheduled { task: usize, start: f32 },
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct GanttStyle {
+    #[builder(default = [0.12, 0.12, 0.14, 1.0])]
+    background_color:    [f32; 4],
+    #[builder(default = 28.0)]
+    row_height:          f32,
+    #[builder(default = 18.0)]
+    bar_height:          f32,
+    #[builder(default = [0.3, 0.5, 0.75, 1.0])]
+    bar_color:           [f32; 4],
+    #[builder(default = [0.4, 0.62, 0.9, 1.0])]
+    dragged_bar_color:   [f32; 4],
+    #[builder(default = 4.0)]
+    corner_radius:       f32,
+    #[builder(default = [0.6, 0.6, 0.65, 1.0])]
+    connector_color:     [f32; 4],
+    #[builder(default = 1.5)]
+    connector_thickness: f32,
+    // How far connectors run out of and into the bars before turning.
+    #[builder(default = 8.0)]
+    connector_elbow:     f32,
+    #[builder(default = [1.0, 0.35, 0.3, 1.0])]
+    today_color:         [f32; 4],
+    #[builder(default = 2.0)]
+    today_width:         f32,
+    // Dragged bars snap to multiples of this many days.
+    #[builder(default = 1.0)]
+    snap_step:           f32,
+}
+
+struct Drag {
+    task:     usize,
+    // Days between the grab point and the task's start
+    offset:   f32,
+    original: f32,
+}
+
+// A schedule of tasks, one per row, as bars on a day scale with
+// dependency connectors between them and a line at today. Only the
+// rows and connectors in view have rectangles, so it scales to long
+// schedules. Bars are dragged to reschedule; the caller applies the
+// `Rescheduled` events and draws task names in `row_bounds`.
+pub struct Gantt {
+    bounds:       Bounds,
+    style:        GanttStyle,
+    tasks:        Vec<GanttTask>,
+    dependencies: Vec<GanttDependency>,
+    // Pixels per day
+    day_width:    f32,
+    // The day at the left edge, and pixels scrolled down.
+    scroll:       [f32; 2],
+    today:        Option<f32>,
+    background:   Block<Positioned>,
+    bars:         BTreeMap<usize, Block<Positioned>>,
+    connectors:   Vec<RectangleId>,
+    today_id:     Option<RectangleId>,
+    drag:         Option<Drag>,
+    events:       Vec<GanttEvent>,
+}
+
+impl Gantt {
+    const MAX_DAY_WIDTH: f32 = 1_000.0;
+    const MIN_DAY_WIDTH: f32 = 0.5;
+
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        day_width: f32,
+        style: GanttStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            BlockStyle::builder()
+                .fill_color(style.background_color)
+                .build(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        Self {
+            bounds,
+            style,
+            tasks: Vec::new(),
+            dependencies: Vec::new(),
+            day_width: day_width
+                .clamp(Self::MIN_DAY_WIDTH, Self::MAX_DAY_WIDTH),
+            scroll: [0.0, 0.0],
+            today: None,
+            background,
+            bars: BTreeMap::new(),
+            connectors: Vec::new(),
+            today_id: None,
+            drag: None,
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn tasks(&self) -> &[GanttTask] { &self.tasks }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn dependencies(&self) -> &[GanttDependency] { &self.dependencies }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn day_width(&self) -> f32 { self.day_width }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scroll(&self) -> [f32; 2] { self.scroll }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dragging(&self) -> bool { self.drag.is_some() }
+
+    #[must_use]
+    pub fn x_at(&self, day: f32) -> f32 {
+        self.bounds.position[0] + (day - self.scroll[0]) * self.day_width
+    }
+
+    #[must_use]
+    pub fn day_at(&self, x: f32) -> f32 {
+        self.scroll[0] + (x - self.bounds.position[0]) / self.day_width
+    }
+
+    #[must_use]
+    pub fn visible_rows(&self) -> Range<usize> {
+        visible_rows(
+            self.scroll[1],
+            self.bounds.size[1],
+            self.style.row_height,
+            self.tasks.len(),
+        )
+    }
+
+    // The full width of a row, for the caller's task names and stripes.
+    #[must_use]
+    pub fn row_bounds(&self, row: usize) -> Bounds {
+        Bounds {
+            position:  [self.bounds.position[0], self.row_top(row)],
+            size:      [self.bounds.size[0], self.style.row_height],
+            clip_rect: self.clip_rect(),
+        }
+    }
+
+    #[must_use]
+    pub fn bar_bounds(&self, row: usize) -> Option<Bounds> {
+        let task = self.tasks.get(row)?;
+        let style = &self.style;
+        let inset = ((style.row_height - style.bar_height) / 2.0).max(0.0);
+        Some(Bounds {
+            position:  [self.x_at(task.start), self.row_top(row) + inset],
+            size:      [
+                task.duration * self.day_width,
+                style.bar_height.min(style.row_height),
+            ],
+            clip_rect: self.clip_rect(),
+        })
+    }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<GanttEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Dependencies pointing at rows that don't exist are ignored.
+    pub fn set_tasks(
+        &mut self,
+        tasks: Vec<GanttTask>,
+        dependencies: Vec<GanttDependency>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.tasks = tasks;
+        self.dependencies = dependencies;
+        self.drag = None;
+        self.scroll[1] = self.scroll[1].min(self.max_scroll_y());
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn set_today(
+        &mut self,
+        today: Option<f32>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.today = today;
+        self.update_today(view_projection, dpr, renderer);
+    }
+
+    pub fn scroll_by(
+        &mut self,
+        delta: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.scroll = [
+            self.scroll[0] + delta[0] / self.day_width,
+            (self.scroll[1] + delta[1]).clamp(0.0, self.max_scroll_y()),
+        ];
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    // Zooms the day scale by `factor`, keeping the day under `anchor_x` in
+    // place.
+    pub fn zoom_at(
+        &mut self,
+        anchor_x: f32,
+        factor: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let day = self.day_at(anchor_x);
+        self.day_width = (self.day_width * factor)
+            .clamp(Self::MIN_DAY_WIDTH, Self::MAX_DAY_WIDTH);
+        self.scroll[0] =
+            day - (anchor_x - self.bounds.position[0]) / self.day_width;
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    // Grabs the bar under the position. Returns whether it hit one.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if !contains(&self.bounds, position) {
+            return false;
+        }
+        let Some(task) = self.visible_rows().find(|row| {
+            self.bar_bounds(*row)
+                .is_some_and(|bounds| contains(&bounds, position))
+        }) else {
+            return false;
+        };
+        let start = self.tasks[task].start;
+        self.drag = Some(Drag {
+            task,
+            offset: self.day_at(position[0]) - start,
+            original: start,
+        });
+        if let Some(bar) = self.bars.get_mut(&task) {
+            bar.update_style(bar_style(&self.style, true, dpr), renderer);
+        }
+
+        true
+    }
+
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(drag) = &self.drag else { return };
+        let start = snap_day(
+            self.day_at(position[0]) - drag.offset,
+            self.style.snap_step,
+        );
+        let task = drag.task;
+        if self.tasks[task].start == start {
+            return;
+        }
+        self.tasks[task].start = start;
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn release(&mut self, dpr: f32, renderer: &mut Renderer) {
+        let Some(drag) = self.drag.take() else { return };
+        if let Some(bar) = self.bars.get_mut(&drag.task) {
+            bar.update_style(bar_style(&self.style, false, dpr), renderer);
+        }
+        let start = self.tasks[drag.task].start;
+        if start != drag.original {
+            self.events
+                .push(GanttEvent::Rescheduled { task: drag.task, start });
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.scroll[1] = self.scroll[1].min(self.max_scroll_y());
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: GanttStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background.update_style(
+            BlockStyle::builder()
+                .fill_color(self.style.background_color)
+                .build(),
+            renderer,
+        );
+        let dragged = self.drag.as_ref().map(|drag| drag.task);
+        for (row, bar) in &mut self.bars {
+            let style = bar_style(&self.style, dragged == Some(*row), dpr);
+            bar.update_style(style, renderer);
+        }
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        for bar in self.bars.values() {
+            bar.destroy(renderer);
+        }
+        for rectangle_id in &self.connectors {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        if let Some(rectangle_id) = self.today_id {
+            renderer.remove_rectangle(rectangle_id);
+        }
+    }
+
+    fn row_top(&self, row: usize) -> f32 {
+        self.bounds.position[1] + row as f32 * self.style.row_height
+            - self.scroll[1]
+    }
+
+    fn max_scroll_y(&self) -> f32 {
+        (self.tasks.len() as f32 * self.style.row_height - self.bounds.size[1])
+            .max(0.0)
+    }
+
+    fn clip_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        intersect_rects(self.bounds.clip_rect, [x, y, width, height])
+    }
+
+    fn relayout(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.background
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+        self.background.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+
+        let rows = self.visible_rows();
+        let days = self.day_at(self.bounds.position[0])
+            ..self.day_at(self.bounds.position[0] + self.bounds.size[0]);
+        let visible: Vec<usize> = rows
+            .filter(|row| {
+                let task = &self.tasks[*row];
+                task.end() >= days.start && task.start <= days.end
+            })
+            .collect();
+
+        let hidden: Vec<usize> = self
+            .bars
+            .keys()
+            .copied()
+            .filter(|row| !visible.contains(row))
+            .collect();
+        for row in hidden {
+            if let Some(bar) = self.bars.remove(&row) {
+                bar.destroy(renderer);
+            }
+        }
+        let dragged = self.drag.as_ref().map(|drag| drag.task);
+        for row in visible {
+            let Some(bounds) = self.bar_bounds(row) else { continue };
+            match self.bars.get_mut(&row) {
+                Some(bar) => {
+                    bar.update_clip_rect(&bounds.clip_rect, renderer);
+                    bar.update_position(
+                        bounds.position,
+                        view_projection,
+                        dpr,
+                        renderer,
+                    );
+                    bar.update_size(
+                        bounds.size,
+                        view_projection,
+                        dpr,
+                        renderer,
+                    );
+                }
+                None => {
+                    let bar = Block::<Positioned>::new(
+                        bounds,
+                        bar_style(&self.style, dragged == Some(row), dpr),
+                        view_projection,
+                        dpr,
+                        renderer,
+                    );
+                    self.bars.insert(row, bar);
+                }
+            }
+        }
+
+        self.update_connectors(view_projection, dpr, renderer);
+        self.update_today(view_projection, dpr, renderer);
+    }
+
+    fn update_connectors(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for rectangle_id in self.connectors.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        let rows = self.visible_rows();
+        let clip_rect = self.clip_rect();
+        let style = &self.style;
+
+        let mut connectors = Vec::new();
+        for dependency in &self.dependencies {
+            let (Some(from), Some(to)) = (
+                self.bar_bounds(dependency.from),
+                self.bar_bounds(dependency.to),
+            ) else {
+                continue;
+            };
+            // Connectors crossing the view count too.
+            let first = dependency.from.min(dependency.to);
+            let last = dependency.from.max(dependency.to);
+            if last < rows.start || first >= rows.end {
+                continue;
+            }
+            let points = connector_points(
+                [
+                    from.position[0] + from.size[0],
+                    from.position[1] + from.size[1] / 2.0,
+                ],
+                [to.position[0], to.position[1] + to.size[1] / 2.0],
+                style.connector_elbow,
+            );
+            connectors.extend(points.windows(2).map(|segment| {
+                let bar = Bar::between(
+                    segment[0],
+                    segment[1],
+                    style.connector_thickness,
+                );
+                let rectangle = bar.build_rectangle(
+                    style.connector_color,
+                    clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                renderer.add_rectangle(&rectangle)
+            }));
+        }
+        self.connectors = connectors;
+    }
+
+    // Re-adds the line so it stays drawn above the bars.
+    fn update_today(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(rectangle_id) = self.today_id.take() {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        let Some(today) = self.today else { return };
+        let x = self.x_at(today);
+        let [_, y] = self.bounds.position;
+        let [_, height] = self.bounds.size;
+        let bar =
+            Bar::between([x, y], [x, y + height], self.style.today_width);
+        let rectangle = bar.build_rectangle(
+            self.style.today_color,
+            self.clip_rect(),
+            view_projection,
+            dpr,
+        );
+        self.today_id = Some(renderer.add_rectangle(&rectangle));
+    }
+}
+
+fn bar_style(style: &GanttStyle, is_dragged: bool, dpr: f32) -> BlockStyle {
+    let fill_color = match is_dragged {
+        true => style.dragged_bar_color,
+        false => style.bar_color,
+    };
+    BlockStyle::builder()
+        .fill_color(fill_color)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+fn visible_rows(
+    scroll_y: f32,
+    height: f32,
+    row_height: f32,
+    count: usize,
+) -> Range<usize> {
+    if row_height <= 0.0 {
+        return 0..0;
+    }
+    let first = (scroll_y / row_height).floor().max(0.0) as usize;
+    let last = ((scroll_y + height) / row_height).ceil().max(0.0) as usize;
+
+    first.min(count)..last.min(count)
+}
+
+fn snap_day(day: f32, step: f32) -> f32 {
+    match step > 0.0 {
+        true => (day / step).round() * step,
+        false => day,
+    }
+}
+
+// An elbowed path from the end of one bar to the start of another. When
+// the second bar starts too early to turn straight into it, the path
+// doubles back between the rows.
+fn connector_points(
+    from: [f32; 2],
+    to: [f32; 2],
+    elbow: f32,
+) -> Vec<[f32; 2]> {
+    let out_x = from[0] + elbow;
+    let in_x = to[0] - elbow;
+    match in_x >= out_x {
+        true => vec![from, [in_x, from[1]], [in_x, to[1]], to],
+        false => {
+            let middle_y = (from[1] + to[1]) / 2.0;
+            vec![
+                from,
+                [out_x, from[1]],
+                [out_x, middle_y],
+                [in_x, middle_y],
+                [in_x, to[1]],
+                to,
+            ]
+        }
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use rstest::rstest;
+
+    use super::{connector_points, snap_day, visible_rows};
+
+    #[rstest]
+    #[case(0.0, 100.0, 20.0, 100, 0..5)]
+    #[case(30.0, 100.0, 20.0, 100, 1..7)]
+    #[case(1_950.0, 100.0, 20.0, 100, 97..100)]
+    #[case(0.0, 100.0, 20.0, 3, 0..3)]
+    fn test_visible_rows(
+        #[case] scroll_y: f32,
+        #[case] height: f32,
+        #[case] row_height: f32,
+        #[case] count: usize,
+        #[case] expected: Range<usize>,
+    ) {
+        assert_eq!(
+            visible_rows(scroll_y, height, row_height, count),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(3.4, 1.0, 3.0)]
+    #[case(3.6, 1.0, 4.0)]
+    #[case(8.0, 7.0, 7.0)]
+    #[case(3.6, 0.0, 3.6)]
+    fn test_snap_day(
+        #[case] day: f32,
+        #[case] step: f32,
+        #[case] expected: f32,
+    ) {
+        assert_eq!(snap_day(day, step), expected);
+    }
+
+    #[rstest]
+    #[case(
+        [100.0, 10.0],
+        [150.0, 40.0],
+        vec![[100.0, 10.0], [142.0, 10.0], [142.0, 40.0], [150.0, 40.0]],
+    )]
+    #[case(
+        [100.0, 10.0],
+        [90.0, 40.0],
+        vec![
+            [100.0, 10.0],
+            [108.0, 10.0],
+            [108.0, 25.0],
+            [82.0, 25.0],
+            [82.0, 40.0],
+            [90.0, 40.0],
+        ],
+    )]
+    fn test_connector_points(
+        #[case] from: [f32; 2],
+        #[case] to: [f32; 2],
+        #[case] expected: Vec<[f32; 2]>,
+    ) {
+        assert_eq!(connector_points(from, to, 8.0), expected);
+    }
+}
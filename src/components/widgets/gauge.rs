@@ -0,0 +1,425 @@
+use std::f32::consts::PI;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Arc, Bar, Bounds},
+    core::{ArcSegment, ArcSegmentId, Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeKind {
+    // The value fills the track from its start.
+    Fill,
+    // A needle points at the value over a track colored by the bands.
+    Needle,
+}
+
+// Values from `from` up to `to` are shown in `color`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaugeBand {
+    pub from:  f32,
+    pub to:    f32,
+    pub color: [f32; 4],
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct GaugeStyle {
+    #[builder(default = GaugeKind::Fill)]
+    kind:         GaugeKind,
+    #[builder(default = [0.22, 0.22, 0.25, 1.0])]
+    track_color:  [f32; 4],
+    // For values outside every band.
+    #[builder(default = [0.35, 0.65, 1.0, 1.0])]
+    fill_color:   [f32; 4],
+    #[builder(default = 10.0)]
+    thickness:    f32,
+    // The default arc opens at the bottom and spans three quarters.
+    #[builder(default = PI * 0.75)]
+    start_angle:  f32,
+    #[builder(default = PI * 1.5)]
+    sweep_angle:  f32,
+    #[builder(default = true)]
+    is_round:     bool,
+    #[builder(default = [0.9, 0.9, 0.92, 1.0])]
+    needle_color: [f32; 4],
+    #[builder(default = 3.0)]
+    needle_width: f32,
+    #[builder(default = 6.0)]
+    hub_radius:   f32,
+}
+
+// A radial gauge: an arc track from the start to the end angle, filled
+// up to the value or read by a needle, in the color of the threshold
+// band the value falls in. The caller draws the value in
+// `label_bounds`.
+pub struct Gauge {
+    bounds: Bounds,
+    style:  GaugeStyle,
+    range:  [f32; 2],
+    value:  f32,
+    bands:  Vec<GaugeBand>,
+    track:  Vec<ArcSegmentId>,
+    fill:   Option<ArcSegmentId>,
+    needle: Option<(RectangleId, Block<Positioned>)>,
+}
+
+impl Gauge {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        range: [f32; 2],
+        style: GaugeStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let mut gauge = Self {
+            bounds,
+            style,
+            range,
+            value: range[0],
+            bands: Vec::new(),
+            track: Vec::new(),
+            fill: None,
+            needle: None,
+        };
+        gauge.rebuild(view_projection, dpr, renderer);
+
+        gauge
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn value(&self) -> f32 { self.value }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn bands(&self) -> &[GaugeBand] { &self.bands }
+
+    // The square inside the arc, for the caller's value label.
+    #[must_use]
+    pub fn label_bounds(&self) -> Bounds {
+        let (center, radius) = fit_circle(&self.bounds, self.style.thickness);
+        let half =
+            ((radius - self.style.thickness / 2.0) / 2.0_f32.sqrt()).max(0.0);
+        Bounds {
+            position:  [center[0] - half, center[1] - half],
+            size:      [half * 2.0; 2],
+            clip_rect: self.bounds.clip_rect,
+        }
+    }
+
+    pub fn set_value(
+        &mut self,
+        value: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        self.update_value(view_projection, dpr, renderer);
+    }
+
+    pub fn set_range(
+        &mut self,
+        range: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.range = range;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn set_bands(
+        &mut self,
+        bands: Vec<GaugeBand>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bands = bands;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: GaugeStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for arc_id in self.track.iter().chain(&self.fill) {
+            renderer.remove_arc(*arc_id);
+        }
+        if let Some((rectangle_id, hub)) = &self.needle {
+            renderer.remove_rectangle(*rectangle_id);
+            hub.destroy(renderer);
+        }
+    }
+
+    fn arc(&self, from: f32, to: f32) -> Arc {
+        let style = &self.style;
+        let (center, radius) = fit_circle(&self.bounds, style.thickness);
+        let start = value_angle(
+            from,
+            self.range,
+            style.start_angle,
+            style.sweep_angle,
+        );
+        let end =
+            value_angle(to, self.range, style.start_angle, style.sweep_angle);
+        Arc {
+            center,
+            radius,
+            thickness: style.thickness,
+            start_angle: start,
+            sweep_angle: end - start,
+            round_caps: style.is_round,
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for arc_id in self.track.drain(..).chain(self.fill.take()) {
+            renderer.remove_arc(arc_id);
+        }
+        if let Some((rectangle_id, hub)) = self.needle.take() {
+            renderer.remove_rectangle(rectangle_id);
+            hub.destroy(renderer);
+        }
+
+        let clip_rect = self.bounds.clip_rect;
+        let [min, max] = self.range;
+        let track = self.arc(min, max).build_segment(
+            self.style.track_color,
+            clip_rect,
+            view_projection,
+            dpr,
+        );
+        self.track.push(renderer.add_arc(&track));
+
+        match self.style.kind {
+            GaugeKind::Fill => {
+                let fill = self.build_fill(view_projection, dpr);
+                self.fill = Some(renderer.add_arc(&fill));
+            }
+            GaugeKind::Needle => {
+                // Bands meet edge to edge, so only the track ends are
+                // rounded.
+                let bands: Vec<_> = self
+                    .bands
+                    .iter()
+                    .map(|band| {
+                        let mut arc = self.arc(
+                            band.from.clamp(min, max),
+                            band.to.clamp(min, max),
+                        );
+                        arc.round_caps = false;
+                        arc.build_segment(
+                            band.color,
+                            clip_rect,
+                            view_projection,
+                            dpr,
+                        )
+                    })
+                    .collect();
+                self.track
+                    .extend(bands.iter().map(|band| renderer.add_arc(band)));
+
+                let (center, _) =
+                    fit_circle(&self.bounds, self.style.thickness);
+                let radius = self.style.hub_radius;
+                let hub = Block::<Positioned>::new(
+                    Bounds {
+                        position: [center[0] - radius, center[1] - radius],
+                        size: [radius * 2.0; 2],
+                        clip_rect,
+                    },
+                    BlockStyle::builder()
+                        .fill_color(self.style.needle_color)
+                        .corner_radii([radius * dpr; 4])
+                        .build(),
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+                let needle = self.build_needle(view_projection, dpr);
+                self.needle = Some((renderer.add_rectangle(&needle), hub));
+            }
+        }
+    }
+
+    fn update_value(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(arc_id) = self.fill {
+            let fill = self.build_fill(view_projection, dpr);
+            if let Some(instance) = renderer.get_mut_arc(arc_id) {
+                *instance = fill;
+            }
+        }
+        if let Some((rectangle_id, _)) = &self.needle {
+            let needle = self.build_needle(view_projection, dpr);
+            if let Some(instance) = renderer.get_mut_rectangle(*rectangle_id) {
+                *instance = needle;
+            }
+        }
+    }
+
+    fn build_fill(&self, view_projection: &Mat4, dpr: f32) -> ArcSegment {
+        let [min, max] = self.range;
+        let color = band_color(self.value, &self.bands, self.style.fill_color);
+        self.arc(min, self.value.clamp(min, max)).build_segment(
+            color,
+            self.bounds.clip_rect,
+            view_projection,
+            dpr,
+        )
+    }
+
+    fn build_needle(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let style = &self.style;
+        let (center, radius) = fit_circle(&self.bounds, style.thickness);
+        let angle = value_angle(
+            self.value,
+            self.range,
+            style.start_angle,
+            style.sweep_angle,
+        );
+        let length = (radius - style.thickness).max(0.0);
+        let tip = [
+            center[0] + angle.cos() * length,
+            center[1] + angle.sin() * length,
+        ];
+        Bar::between(center, tip, style.needle_width).build_rectangle(
+            style.needle_color,
+            self.bounds.clip_rect,
+            view_projection,
+            dpr,
+        )
+    }
+}
+
+// The center and stroke radius of the largest arc that fits the bounds.
+fn fit_circle(bounds: &Bounds, thickness: f32) -> ([f32; 2], f32) {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let radius = ((width.min(height) - thickness) / 2.0).max(0.0);
+
+    ([x + width / 2.0, y + height / 2.0], radius)
+}
+
+fn value_angle(
+    value: f32,
+    range: [f32; 2],
+    start_angle: f32,
+    sweep_angle: f32,
+) -> f32 {
+    let span = range[1] - range[0];
+    let t = match span > 0.0 {
+        true => ((value - range[0]) / span).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+
+    start_angle + sweep_angle * t
+}
+
+// The color of the last band containing the value.
+fn band_color(value: f32, bands: &[GaugeBand], default: [f32; 4]) -> [f32; 4] {
+    bands
+        .iter()
+        .rev()
+        .find(|band| value >= band.from && value <= band.to)
+        .map_or(default, |band| band.color)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use rstest::rstest;
+
+    use super::{GaugeBand, band_color, fit_circle, value_angle};
+    use crate::components::common::Bounds;
+
+    #[rstest]
+    #[case(0.0, PI * 0.75)]
+    #[case(50.0, PI * 1.5)]
+    #[case(100.0, PI * 2.25)]
+    #[case(150.0, PI * 2.25)]
+    fn test_value_angle(#[case] value: f32, #[case] expected: f32) {
+        let angle = value_angle(value, [0.0, 100.0], PI * 0.75, PI * 1.5);
+        assert!((angle - expected).abs() < 1e-5);
+    }
+
+    #[rstest]
+    #[case(10.0, [0.0, 1.0, 0.0, 1.0])]
+    #[case(75.0, [1.0, 1.0, 0.0, 1.0])]
+    #[case(95.0, [1.0, 0.0, 0.0, 1.0])]
+    #[case(-5.0, [0.5; 4])]
+    fn test_band_color(#[case] value: f32, #[case] expected: [f32; 4]) {
+        let bands = [
+            GaugeBand { from: 0.0, to: 70.0, color: [0.0, 1.0, 0.0, 1.0] },
+            GaugeBand {
+                from:  70.0,
+                to:    90.0,
+                color: [1.0, 1.0, 0.0, 1.0],
+            },
+            GaugeBand {
+                from:  90.0,
+                to:    100.0,
+                color: [1.0, 0.0, 0.0, 1.0],
+            },
+        ];
+        assert_eq!(band_color(value, &bands, [0.5; 4]), expected);
+    }
+
+    #[rstest]
+    fn test_fit_circle() {
+        let bounds = Bounds {
+            position:  [10.0, 20.0],
+            size:      [200.0, 100.0],
+            clip_rect: [0.0, 0.0, f32::MAX, f32::MAX],
+        };
+        assert_eq!(fit_circle(&bounds, 10.0), ([110.0, 70.0], 45.0));
+    }
+}
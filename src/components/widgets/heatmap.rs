@@ -0,0 +1,594 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{
+    Block, BlockStyle, block::build_rectangle, block_states::Positioned,
+};
+use crate::{
+    components::common::{Bounds, intersect_rects},
+    core::{RectangleId, Renderer},
+};
+
+// Colors evenly spaced over 0..=1, interpolated linearly in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScale {
+    stops: Vec<[f32; 4]>,
+}
+
+impl Default for ColorScale {
+    fn default() -> Self {
+        Self::new(vec![
+            [0.27, 0.0, 0.33, 1.0],
+            [0.23, 0.32, 0.55, 1.0],
+            [0.13, 0.57, 0.55, 1.0],
+            [0.37, 0.79, 0.38, 1.0],
+            [0.99, 0.91, 0.14, 1.0],
+        ])
+    }
+}
+
+impl ColorScale {
+    #[must_use]
+    pub fn new(stops: Vec<[f32; 4]>) -> Self { Self { stops } }
+
+    #[must_use]
+    pub fn color_at(&self, t: f32) -> [f32; 4] {
+        match self.stops.as_slice() {
+            [] => [0.0; 4],
+            [color] => *color,
+            stops => {
+                let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+                let index = (position.floor() as usize).min(stops.len() - 2);
+                let fraction = position - index as f32;
+                let [from, to] = [stops[index], stops[index + 1]];
+                std::array::from_fn(|channel| {
+                    from[channel] + (to[channel] - from[channel]) * fraction
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapHover {
+    pub column: usize,
+    pub row:    usize,
+    pub value:  f32,
+    // The cell, for the caller to anchor a tooltip to.
+    pub bounds: Bounds,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct HeatmapStyle {
+    #[builder(default = [0.12, 0.12, 0.14, 1.0])]
+    background_color: [f32; 4],
+    #[builder(default = 1.0)]
+    cell_gap:         f32,
+    // For cells without a value (NaN).
+    #[builder(default = [0.2, 0.2, 0.22, 1.0])]
+    missing_color:    [f32; 4],
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    hover_color:      [f32; 4],
+    #[builder(default = 2.0)]
+    hover_size:       f32,
+    // The legend is a strip on the right; zero hides it.
+    #[builder(default = 12.0)]
+    legend_width:     f32,
+    #[builder(default = 8.0)]
+    legend_gap:       f32,
+    #[builder(default = 32)]
+    legend_steps:     usize,
+}
+
+// A dense grid of cells colored by value. Cells are bare rectangle
+// instances rather than widgets, and new data of the same dimensions
+// only rewrites their colors. The legend runs from the lowest value at
+// the bottom to the highest at the top; the caller labels it with
+// `range` and draws tooltips from `hover`.
+pub struct Heatmap {
+    bounds:      Bounds,
+    style:       HeatmapStyle,
+    color_scale: ColorScale,
+    // columns, rows
+    dimensions:  [usize; 2],
+    // Row by row
+    values:      Vec<f32>,
+    range:       [f32; 2],
+    background:  Block<Positioned>,
+    cells:       Vec<RectangleId>,
+    legend:      Vec<RectangleId>,
+    hovered:     Option<([usize; 2], Block<Positioned>)>,
+}
+
+impl Heatmap {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        color_scale: ColorScale,
+        style: HeatmapStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            BlockStyle::builder()
+                .fill_color(style.background_color)
+                .build(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut heatmap = Self {
+            bounds,
+            style,
+            color_scale,
+            dimensions: [0, 0],
+            values: Vec::new(),
+            range: [0.0, 0.0],
+            background,
+            cells: Vec::new(),
+            legend: Vec::new(),
+            hovered: None,
+        };
+        heatmap.build_legend(view_projection, dpr, renderer);
+
+        heatmap
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn dimensions(&self) -> [usize; 2] { self.dimensions }
+
+    // The lowest and highest values, at the ends of the legend.
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    #[must_use]
+    pub fn value(&self, column: usize, row: usize) -> Option<f32> {
+        let [columns, rows] = self.dimensions;
+        (column < columns && row < rows)
+            .then(|| self.values[row * columns + column])
+    }
+
+    #[must_use]
+    pub fn grid_bounds(&self) -> Bounds {
+        let [legend, _] = self.legend_layout();
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        Bounds {
+            position:  [x, y],
+            size:      [(width - legend).max(0.0), height],
+            clip_rect: self.clip_rect(),
+        }
+    }
+
+    #[must_use]
+    pub fn legend_bounds(&self) -> Bounds {
+        let [legend, gap] = self.legend_layout();
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        Bounds {
+            position:  [x + width - legend + gap, y],
+            size:      [(legend - gap).max(0.0), height],
+            clip_rect: self.clip_rect(),
+        }
+    }
+
+    #[must_use]
+    pub fn cell_bounds(&self, column: usize, row: usize) -> Bounds {
+        let grid = self.grid_bounds();
+        let [x, y, width, height] = cell_rect(
+            &grid,
+            self.dimensions,
+            [column, row],
+            self.style.cell_gap,
+        );
+        Bounds {
+            position:  [x, y],
+            size:      [width, height],
+            clip_rect: grid.clip_rect,
+        }
+    }
+
+    // Calls `value` for every cell. Data of the same dimensions as before
+    // only recolors the existing cells.
+    pub fn set_data(
+        &mut self,
+        columns: usize,
+        rows: usize,
+        value: impl Fn(usize, usize) -> f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.values = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| value(column, row))
+            .collect();
+        self.range = value_range(&self.values);
+
+        match self.dimensions == [columns, rows] {
+            true => self.recolor(renderer),
+            false => {
+                self.dimensions = [columns, rows];
+                self.clear_hover(renderer);
+                self.rebuild_cells(view_projection, dpr, renderer);
+            }
+        }
+    }
+
+    pub fn set_color_scale(
+        &mut self,
+        color_scale: ColorScale,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.color_scale = color_scale;
+        self.recolor(renderer);
+        self.build_legend(view_projection, dpr, renderer);
+    }
+
+    // Outlines the cell under the position and returns it, or clears the
+    // outline when off the grid.
+    pub fn hover(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<HeatmapHover> {
+        let grid = self.grid_bounds();
+        let cell = cell_at(&grid, self.dimensions, position)
+            .filter(|_| contains(&self.bounds, position));
+        let Some([column, row]) = cell else {
+            self.clear_hover(renderer);
+            return None;
+        };
+        let bounds = self.cell_bounds(column, row);
+        match &mut self.hovered {
+            Some((hovered, _)) if *hovered == [column, row] => {}
+            Some((hovered, block)) => {
+                *hovered = [column, row];
+                block.update_position(
+                    bounds.position,
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+                block.update_size(bounds.size, view_projection, dpr, renderer);
+            }
+            None => {
+                let block = Block::<Positioned>::new(
+                    bounds.clone(),
+                    hover_style(&self.style, dpr),
+                    view_projection,
+                    dpr,
+                    renderer,
+                );
+                self.hovered = Some(([column, row], block));
+            }
+        }
+
+        Some(HeatmapHover {
+            column,
+            row,
+            value: self.values[row * self.dimensions[0] + column],
+            bounds,
+        })
+    }
+
+    pub fn clear_hover(&mut self, renderer: &mut Renderer) {
+        if let Some((_, block)) = self.hovered.take() {
+            block.destroy(renderer);
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.background
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+        self.background.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.clear_hover(renderer);
+        self.rebuild_cells(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: HeatmapStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background.update_style(
+            BlockStyle::builder()
+                .fill_color(self.style.background_color)
+                .build(),
+            renderer,
+        );
+        self.clear_hover(renderer);
+        self.rebuild_cells(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        for rectangle_id in self.cells.iter().chain(&self.legend) {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        if let Some((_, block)) = &self.hovered {
+            block.destroy(renderer);
+        }
+    }
+
+    // The legend's width including the gap before it, and the gap.
+    fn legend_layout(&self) -> [f32; 2] {
+        match self.style.legend_width > 0.0 {
+            true => [
+                self.style.legend_width + self.style.legend_gap,
+                self.style.legend_gap,
+            ],
+            false => [0.0, 0.0],
+        }
+    }
+
+    fn clip_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        intersect_rects(self.bounds.clip_rect, [x, y, width, height])
+    }
+
+    fn cell_color(&self, value: f32) -> [f32; 4] {
+        match value.is_nan() {
+            true => self.style.missing_color,
+            false => self.color_scale.color_at(normalize(value, self.range)),
+        }
+    }
+
+    fn recolor(&self, renderer: &mut Renderer) {
+        for (rectangle_id, value) in self.cells.iter().zip(&self.values) {
+            let color = self.cell_color(*value);
+            if let Some(rectangle) = renderer.get_mut_rectangle(*rectangle_id)
+            {
+                rectangle.fill_color = color;
+            }
+        }
+    }
+
+    fn rebuild_cells(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for rectangle_id in self.cells.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        let [columns, rows] = self.dimensions;
+        let cells: Vec<RectangleId> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let style = BlockStyle::builder()
+                    .fill_color(self.cell_color(
+                        self.value(column, row).unwrap_or(f32::NAN),
+                    ))
+                    .build();
+                let bounds = self.cell_bounds(column, row);
+                let rectangle =
+                    build_rectangle(view_projection, dpr, &bounds, &style);
+                renderer.add_rectangle(&rectangle)
+            })
+            .collect();
+        self.cells = cells;
+        self.build_legend(view_projection, dpr, renderer);
+    }
+
+    fn build_legend(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        for rectangle_id in self.legend.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        if self.style.legend_width <= 0.0 {
+            return;
+        }
+        let legend = self.legend_bounds();
+        let steps = self.style.legend_steps.max(1);
+        let step_height = legend.size[1] / steps as f32;
+        self.legend = (0..steps)
+            .map(|step| {
+                // The top step is the highest value.
+                let t = 1.0 - (step as f32 + 0.5) / steps as f32;
+                let bounds = Bounds {
+                    position:  [
+                        legend.position[0],
+                        legend.position[1] + step as f32 * step_height,
+                    ],
+                    size:      [legend.size[0], step_height],
+                    clip_rect: legend.clip_rect,
+                };
+                let style = BlockStyle::builder()
+                    .fill_color(self.color_scale.color_at(t))
+                    .build();
+                let rectangle =
+                    build_rectangle(view_projection, dpr, &bounds, &style);
+                renderer.add_rectangle(&rectangle)
+            })
+            .collect();
+    }
+}
+
+fn hover_style(style: &HeatmapStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color([0.0; 4])
+        .border_color(style.hover_color)
+        .border_size(style.hover_size * dpr)
+        .build()
+}
+
+// The lowest and highest of the values that aren't NaN.
+fn value_range(values: &[f32]) -> [f32; 2] {
+    let range = values
+        .iter()
+        .copied()
+        .filter(|value| !value.is_nan())
+        .fold([f32::INFINITY, f32::NEG_INFINITY], |[min, max], value| {
+            [min.min(value), max.max(value)]
+        });
+    match range[0] <= range[1] {
+        true => range,
+        false => [0.0, 0.0],
+    }
+}
+
+fn normalize(value: f32, range: [f32; 2]) -> f32 {
+    let span = range[1] - range[0];
+    match span > 0.0 {
+        true => ((value - range[0]) / span).clamp(0.0, 1.0),
+        false => 0.5,
+    }
+}
+
+// x, y, width, height of a cell, inset by half the gap on every side.
+fn cell_rect(
+    grid: &Bounds,
+    dimensions: [usize; 2],
+    cell: [usize; 2],
+    gap: f32,
+) -> [f32; 4] {
+    let [columns, rows] = dimensions.map(|count| count.max(1) as f32);
+    let width = grid.size[0] / columns;
+    let height = grid.size[1] / rows;
+    let inset = (gap / 2.0).min(width / 2.0).min(height / 2.0);
+    [
+        grid.position[0] + cell[0] as f32 * width + inset,
+        grid.position[1] + cell[1] as f32 * height + inset,
+        width - inset * 2.0,
+        height - inset * 2.0,
+    ]
+}
+
+fn cell_at(
+    grid: &Bounds,
+    dimensions: [usize; 2],
+    position: [f32; 2],
+) -> Option<[usize; 2]> {
+    let [columns, rows] = dimensions;
+    if columns == 0 || rows == 0 || !contains(grid, position) {
+        return None;
+    }
+    let column =
+        (position[0] - grid.position[0]) / grid.size[0] * columns as f32;
+    let row = (position[1] - grid.position[1]) / grid.size[1] * rows as f32;
+
+    Some([
+        (column as usize).min(columns - 1),
+        (row as usize).min(rows - 1),
+    ])
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ColorScale, cell_at, cell_rect, normalize, value_range};
+    use crate::components::common::Bounds;
+
+    fn grid() -> Bounds {
+        Bounds {
+            position:  [10.0, 20.0],
+            size:      [100.0, 50.0],
+            clip_rect: [0.0, 0.0, f32::MAX, f32::MAX],
+        }
+    }
+
+    #[rstest]
+    #[case(0.0, [0.0, 0.0, 0.0, 1.0])]
+    #[case(0.25, [0.5, 0.0, 0.0, 1.0])]
+    #[case(0.75, [1.0, 0.5, 0.0, 1.0])]
+    #[case(2.0, [1.0, 1.0, 0.0, 1.0])]
+    fn test_color_scale_color_at(#[case] t: f32, #[case] expected: [f32; 4]) {
+        let scale = ColorScale::new(vec![
+            [0.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+            [1.0, 1.0, 0.0, 1.0],
+        ]);
+        assert_eq!(scale.color_at(t), expected);
+    }
+
+    #[rstest]
+    #[case(&[3.0, -1.0, f32::NAN, 7.0], [-1.0, 7.0])]
+    #[case(&[f32::NAN], [0.0, 0.0])]
+    #[case(&[], [0.0, 0.0])]
+    fn test_value_range(#[case] values: &[f32], #[case] expected: [f32; 2]) {
+        assert_eq!(value_range(values), expected);
+    }
+
+    #[rstest]
+    #[case(5.0, [0.0, 10.0], 0.5)]
+    #[case(-5.0, [0.0, 10.0], 0.0)]
+    #[case(3.0, [3.0, 3.0], 0.5)]
+    fn test_normalize(
+        #[case] value: f32,
+        #[case] range: [f32; 2],
+        #[case] expected: f32,
+    ) {
+        assert_eq!(normalize(value, range), expected);
+    }
+
+    #[rstest]
+    #[case([0, 0], [10.0, 20.0, 25.0, 10.0])]
+    #[case([3, 4], [85.0, 60.0, 25.0, 10.0])]
+    fn test_cell_rect(#[case] cell: [usize; 2], #[case] expected: [f32; 4]) {
+        assert_eq!(cell_rect(&grid(), [4, 5], cell, 0.0), expected);
+    }
+
+    #[rstest]
+    #[case([10.0, 20.0], Some([0, 0]))]
+    #[case([60.0, 45.0], Some([2, 2]))]
+    #[case([110.0, 70.0], Some([3, 4]))]
+    #[case([5.0, 45.0], None)]
+    fn test_cell_at(
+        #[case] position: [f32; 2],
+        #[case] expected: Option<[usize; 2]>,
+    ) {
+        assert_eq!(cell_at(&grid(), [4, 5], position), expected);
+    }
+}
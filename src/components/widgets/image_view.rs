@@ -0,0 +1,343 @@
+use std::{path::PathBuf, time::Duration};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{
+        Bounds, FramePlayback, ImageFrame, ImageLoadState, ImageLoader,
+        build_model,
+    },
+    core::{Image, ImageId, ImageRepeat, Renderer, TextureId},
+};
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct ImageStyle {
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    tint_color:        [f32; 4],
+    #[builder(default = ImageRepeat::NoRepeat)]
+    repeat:            ImageRepeat,
+    #[builder(default = [1.0, 1.0])]
+    tile_scale:        [f32; 2],
+    #[builder(default = [0.0, 0.0])]
+    tile_offset:       [f32; 2],
+    #[builder(default = [0.0, 0.0, 0.0, 0.0])]
+    corner_radii:      [f32; 4],
+    // center.x, center.y, radius, relative to the top-left corner
+    #[builder(default = [0.0, 0.0, 0.0])]
+    cutout:            [f32; 3],
+    #[builder(default = [0.0, 0.0, 0.0, 0.0])]
+    placeholder_color: [f32; 4],
+}
+
+pub struct ImageView {
+    path:        PathBuf,
+    bounds:      Bounds,
+    style:       ImageStyle,
+    placeholder: Option<Block<Positioned>>,
+    image:       Option<(ImageId, TextureId)>,
+    frames:      Vec<ImageFrame>,
+    playback:    Option<FramePlayback>,
+}
+
+impl ImageView {
+    #[must_use]
+    pub fn new(
+        path: impl Into<PathBuf>,
+        bounds: Bounds,
+        style: ImageStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let placeholder =
+            build_placeholder(&bounds, &style, view_projection, dpr, renderer);
+        Self {
+            path: path.into(),
+            bounds,
+            style,
+            placeholder: Some(placeholder),
+            image: None,
+            frames: Vec::new(),
+            playback: None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_loaded(&self) -> bool { self.image.is_some() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn playback(&self) -> Option<&FramePlayback> {
+        self.playback.as_ref()
+    }
+
+    #[inline(always)]
+    pub fn play(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.play();
+        }
+    }
+
+    #[inline(always)]
+    pub fn pause(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.pause();
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_looping(&mut self, is_looping: bool) {
+        if let Some(playback) = &mut self.playback {
+            playback.set_looping(is_looping);
+        }
+    }
+
+    pub fn restart(&mut self, renderer: &mut Renderer) {
+        if let Some(playback) = &mut self.playback {
+            playback.restart();
+            self.show_current_frame(renderer);
+        }
+    }
+
+    pub fn advance(&mut self, dt: Duration, renderer: &mut Renderer) {
+        let Some(playback) = &mut self.playback else { return };
+        if playback.advance(dt) {
+            self.show_current_frame(renderer);
+        }
+    }
+
+    pub fn sync(
+        &mut self,
+        loader: &mut ImageLoader,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        match loader.load(&self.path) {
+            ImageLoadState::Ready(texture_id) => {
+                if self.frames.is_empty() {
+                    self.frames = loader.frames(&self.path).to_vec();
+                    self.playback = (self.frames.len() > 1).then(|| {
+                        FramePlayback::new(
+                            self.frames.iter().map(|frame| frame.delay),
+                        )
+                    });
+                }
+                let texture_id =
+                    self.current_frame_texture().unwrap_or(texture_id);
+                self.show_image(texture_id, view_projection, dpr, renderer)
+            }
+            ImageLoadState::Loading | ImageLoadState::Failed => {
+                self.show_placeholder(view_projection, dpr, renderer)
+            }
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: ImageStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        if let Some(placeholder) = &self.placeholder {
+            placeholder.destroy(renderer);
+        }
+        if let Some((image_id, _)) = self.image {
+            renderer.remove_image(image_id);
+        }
+    }
+
+    fn show_image(
+        &mut self,
+        texture_id: TextureId,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(placeholder) = self.placeholder.take() {
+            placeholder.destroy(renderer);
+        }
+        match self.image {
+            Some((_, current_texture_id))
+                if current_texture_id == texture_id => {}
+            Some((image_id, _)) => {
+                renderer.set_image_texture(image_id, texture_id);
+                self.image = Some((image_id, texture_id));
+                self.refresh(view_projection, dpr, renderer);
+            }
+            None => {
+                let image = build_image(
+                    view_projection,
+                    dpr,
+                    &self.bounds,
+                    &self.style,
+                    renderer.texture_size(texture_id),
+                );
+                self.image = renderer
+                    .add_image(texture_id, &image)
+                    .map(|image_id| (image_id, texture_id));
+            }
+        }
+    }
+
+    fn show_placeholder(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some((image_id, _)) = self.image.take() {
+            renderer.remove_image(image_id);
+        }
+        self.frames.clear();
+        self.playback = None;
+
+        if self.placeholder.is_none() {
+            self.placeholder = Some(build_placeholder(
+                &self.bounds,
+                &self.style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        }
+    }
+
+    fn current_frame_texture(&self) -> Option<TextureId> {
+        let frame = self.playback.as_ref().map_or(0, FramePlayback::frame);
+        self.frames.get(frame).map(|frame| frame.texture_id)
+    }
+
+    fn show_current_frame(&mut self, renderer: &mut Renderer) {
+        let Some(texture_id) = self.current_frame_texture() else { return };
+        if let Some((image_id, current_texture_id)) = &mut self.image
+            && *current_texture_id != texture_id
+        {
+            renderer.set_image_texture(*image_id, texture_id);
+            *current_texture_id = texture_id;
+        }
+    }
+
+    fn refresh(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if let Some(placeholder) = self.placeholder.take() {
+            placeholder.destroy(renderer);
+            self.placeholder = Some(build_placeholder(
+                &self.bounds,
+                &self.style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        }
+        if let Some((image_id, texture_id)) = self.image {
+            let image = build_image(
+                view_projection,
+                dpr,
+                &self.bounds,
+                &self.style,
+                renderer.texture_size(texture_id),
+            );
+            if let Some(instance) = renderer.get_mut_image(image_id) {
+                *instance = image;
+            }
+        }
+    }
+}
+
+fn build_placeholder(
+    bounds: &Bounds,
+    style: &ImageStyle,
+    view_projection: &Mat4,
+    dpr: f32,
+    renderer: &mut Renderer,
+) -> Block<Positioned> {
+    let block_style = BlockStyle::builder()
+        .fill_color(style.placeholder_color)
+        .corner_radii(style.corner_radii)
+        .build();
+    Block::<Positioned>::new(
+        bounds.clone(),
+        block_style,
+        view_projection,
+        dpr,
+        renderer,
+    )
+}
+
+fn build_image(
+    view_projection: &Mat4,
+    dpr: f32,
+    bounds: &Bounds,
+    style: &ImageStyle,
+    texture_size: Option<[u32; 2]>,
+) -> Image {
+    let (model, half_size) = build_model(bounds.size, bounds.position, dpr);
+    let mvp = view_projection * model;
+
+    let [texture_width, texture_height] = texture_size.unwrap_or([1, 1]);
+    let tile_size = [
+        texture_width as f32 * style.tile_scale[0],
+        texture_height as f32 * style.tile_scale[1],
+    ];
+    let repeats = style.repeat.repeats();
+    let mut uv_transform = [1.0, 1.0, 0.0, 0.0];
+    for axis in 0..2 {
+        if repeats[axis] {
+            uv_transform[axis] = bounds.size[axis] / tile_size[axis];
+            uv_transform[axis + 2] =
+                -style.tile_offset[axis] / tile_size[axis];
+        }
+    }
+
+    let [cutout_x, cutout_y, cutout_radius] = style.cutout.map(|v| v * dpr);
+    let cutout = [
+        cutout_x - half_size[0],
+        cutout_y - half_size[1],
+        cutout_radius,
+        0.0,
+    ];
+
+    let clip_rect = bounds.clip_rect.map(|value| value * dpr);
+    let mut image = Image::builder()
+        .mvp(mvp.to_cols_array_2d())
+        .tint_color(style.tint_color)
+        .corner_radii(style.corner_radii)
+        .clip_rect(clip_rect)
+        .uv_transform(uv_transform)
+        .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+        .cutout(cutout)
+        .build();
+    image.set_repeat(style.repeat);
+
+    image
+}
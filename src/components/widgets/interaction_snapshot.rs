@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use glam::Mat4;
+
+use super::{ScrollAnchor, ScrollView};
+use crate::{
+    components::common::{FocusState, WidgetId},
+    core::Renderer,
+};
+
+// Focus and scroll positions taken before a subtree is rebuilt, e.g. a
+// list refreshed from its model, and put back afterwards by widget id:
+//
+// let mut snapshot = InteractionSnapshot::new(&focus);
+// snapshot.capture_scroll(LIST_ID, &list);
+// list.clear(renderer);
+// ... add the new rows, tagged by their objects ...
+// snapshot.restore_scroll(LIST_ID, &mut list, ...);
+// snapshot.restore_focus(&mut focus, |id| list.child_with_tag(id).is_some());
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InteractionSnapshot {
+    focused:        Option<WidgetId>,
+    scroll_anchors: HashMap<WidgetId, ScrollAnchor>,
+}
+
+impl InteractionSnapshot {
+    #[must_use]
+    pub fn new(focus: &FocusState) -> Self {
+        Self {
+            focused:        focus.focused(),
+            scroll_anchors: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn focused(&self) -> Option<WidgetId> { self.focused }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn scroll_anchor(&self, id: WidgetId) -> Option<ScrollAnchor> {
+        self.scroll_anchors.get(&id).copied()
+    }
+
+    pub fn capture_scroll(&mut self, id: WidgetId, view: &ScrollView) {
+        self.scroll_anchors.insert(id, view.anchor());
+    }
+
+    // Focuses the widget that had focus if it was rebuilt, i.e. `exists`,
+    // and blurs otherwise. Returns whether the focus moved.
+    pub fn restore_focus(
+        &self,
+        focus: &mut FocusState,
+        exists: impl Fn(WidgetId) -> bool,
+    ) -> bool {
+        match self.focused {
+            Some(id) if exists(id) => focus.focus(id),
+            _ => focus.blur().is_some(),
+        }
+    }
+
+    // Returns whether the view scrolled; views that weren't captured
+    // don't.
+    pub fn restore_scroll(
+        &self,
+        id: WidgetId,
+        view: &mut ScrollView,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        self.scroll_anchor(id).is_some_and(|anchor| {
+            view.restore_anchor(anchor, view_projection, dpr, renderer)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::InteractionSnapshot;
+    use crate::components::common::FocusState;
+
+    #[rstest]
+    fn test_restore_focus() {
+        let mut focus = FocusState::new();
+        focus.focus(7);
+        let snapshot = InteractionSnapshot::new(&focus);
+
+        // Rebuilt widgets got new ids from nowhere else.
+        focus.focus(9);
+        assert!(snapshot.restore_focus(&mut focus, |id| id == 7));
+        assert_eq!(focus.focused(), Some(7));
+        assert!(!snapshot.restore_focus(&mut focus, |id| id == 7));
+
+        // The focused row is gone after the rebuild.
+        assert!(snapshot.restore_focus(&mut focus, |_| false));
+        assert_eq!(focus.focused(), None);
+    }
+}
@@ -0,0 +1,568 @@
+use std::{
+    f32::consts::{PI, TAU},
+    time::Instant,
+};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{
+    AccessNode, AccessRole, Availability, Block, BlockStyle,
+    block_states::Positioned,
+};
+use crate::{
+    components::{
+        common::{Arc, Bar, Bounds},
+        decoration::DoubleClick,
+    },
+    core::{ArcSegment, ArcSegmentId, Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnobDragMode {
+    // Dragging up turns the knob up.
+    Vertical,
+    // The knob follows the pointer around its center.
+    Circular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KnobEvent {
+    // Sent on every change while dragging.
+    Changed(f32),
+    // Sent once the drag ends, or on a reset.
+    Committed(f32),
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct KnobStyle {
+    #[builder(default = KnobDragMode::Vertical)]
+    drag_mode:            KnobDragMode,
+    #[builder(default = [0.2, 0.2, 0.23, 1.0])]
+    body_color:           [f32; 4],
+    #[builder(default = [0.3, 0.3, 0.34, 1.0])]
+    track_color:          [f32; 4],
+    #[builder(default = [0.35, 0.65, 1.0, 1.0])]
+    value_color:          [f32; 4],
+    // The value color of disabled and loading knobs.
+    #[builder(default = [0.45, 0.45, 0.5, 1.0])]
+    inactive_value_color: [f32; 4],
+    #[builder(default = 4.0)]
+    thickness:            f32,
+    // Gap between the value arc and the body.
+    #[builder(default = 3.0)]
+    gap:                  f32,
+    #[builder(default = PI * 0.75)]
+    start_angle:          f32,
+    #[builder(default = PI * 1.5)]
+    sweep_angle:          f32,
+    #[builder(default = [0.9, 0.9, 0.92, 1.0])]
+    indicator_color:      [f32; 4],
+    #[builder(default = 2.0)]
+    indicator_width:      f32,
+    // How far a vertical drag goes to cover the whole range.
+    #[builder(default = 200.0)]
+    drag_distance:        f32,
+    // Scales drags while the fine-adjust modifier is held.
+    #[builder(default = 0.1)]
+    fine_factor:          f32,
+    // How close to a detent, as a fraction of the range, snaps onto it.
+    #[builder(default = 0.02)]
+    detent_snap:          f32,
+}
+
+struct Drag {
+    last_position: [f32; 2],
+    start_value:   f32,
+    // The unsnapped value, so drags can leave a detent again.
+    raw_value:     f32,
+}
+
+// A rotary control for audio-style UIs, dragged vertically or around
+// its center, with an arc showing the value. Holding the fine-adjust
+// modifier slows the drag down, values snap onto detents, and a double
+// click resets to the default value. Only enabled knobs can be turned.
+pub struct Knob {
+    bounds:        Bounds,
+    style:         KnobStyle,
+    range:         [f32; 2],
+    value:         f32,
+    default_value: f32,
+    availability:  Availability,
+    detents:       Vec<f32>,
+    double_click:  DoubleClick,
+    drag:          Option<Drag>,
+    body:          Block<Positioned>,
+    track:         ArcSegmentId,
+    value_arc:     ArcSegmentId,
+    indicator:     RectangleId,
+    events:        Vec<KnobEvent>,
+}
+
+impl Knob {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        range: [f32; 2],
+        default_value: f32,
+        style: KnobStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let value = default_value.clamp(range[0], range[1]);
+        let (center, radius) = fit_knob(&bounds, &style);
+        let body = Block::<Positioned>::new(
+            body_bounds(&bounds, center, radius),
+            body_style(&style, radius, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut knob = Self {
+            bounds,
+            style,
+            range,
+            value,
+            default_value: value,
+            availability: Availability::Enabled,
+            detents: Vec::new(),
+            double_click: DoubleClick::default(),
+            drag: None,
+            body,
+            track: ArcSegmentId::default(),
+            value_arc: ArcSegmentId::default(),
+            indicator: RectangleId::default(),
+            events: Vec::new(),
+        };
+        let [track, value_arc] = knob.build_arcs(view_projection, dpr);
+        knob.track = renderer.add_arc(&track);
+        knob.value_arc = renderer.add_arc(&value_arc);
+        let indicator = knob.build_indicator(view_projection, dpr);
+        knob.indicator = renderer.add_rectangle(&indicator);
+
+        knob
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn value(&self) -> f32 { self.value }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn default_value(&self) -> f32 { self.default_value }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn availability(&self) -> Availability { self.availability }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dragging(&self) -> bool { self.drag.is_some() }
+
+    #[must_use]
+    pub fn accessibility(&self) -> AccessNode {
+        AccessNode {
+            role:         AccessRole::Slider,
+            label:        self.value.to_string(),
+            bounds:       self.bounds.clone(),
+            is_focusable: matches!(
+                self.availability,
+                Availability::Enabled | Availability::ReadOnly
+            ),
+            is_disabled:  self.availability == Availability::Disabled,
+            is_read_only: self.availability == Availability::ReadOnly,
+            is_busy:      self.availability == Availability::Loading,
+        }
+    }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<KnobEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Sets the value without sending events, e.g. from automation.
+    pub fn set_value(
+        &mut self,
+        value: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.value = value.clamp(self.range[0], self.range[1]);
+        self.update_value(view_projection, dpr, renderer);
+    }
+
+    #[inline(always)]
+    pub const fn set_default_value(&mut self, default_value: f32) {
+        self.default_value = default_value;
+    }
+
+    pub fn set_detents(&mut self, detents: Vec<f32>) {
+        self.detents = detents;
+    }
+
+    // Starts a drag, or resets to the default value on a double click.
+    // Returns whether the press landed on the knob, which it does on one
+    // that isn't enabled too, without doing anything.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        now: Instant,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let (center, radius) = fit_knob(&self.bounds, &self.style);
+        let outer = radius + self.style.gap + self.style.thickness;
+        let distance =
+            (position[0] - center[0]).hypot(position[1] - center[1]);
+        if distance > outer {
+            return false;
+        }
+        if !self.availability.is_enabled() {
+            return true;
+        }
+
+        if self.double_click.register(position, now) {
+            self.drag = None;
+            let value = self.default_value.clamp(self.range[0], self.range[1]);
+            if value != self.value {
+                self.set_value(value, view_projection, dpr, renderer);
+                self.events.push(KnobEvent::Changed(self.value));
+                self.events.push(KnobEvent::Committed(self.value));
+            }
+            return true;
+        }
+        self.drag = Some(Drag {
+            last_position: position,
+            start_value:   self.value,
+            raw_value:     self.value,
+        });
+
+        true
+    }
+
+    // `is_fine` is whether the fine-adjust modifier is held.
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        is_fine: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(drag) = &mut self.drag else { return };
+        let style = &self.style;
+        let span = self.range[1] - self.range[0];
+        let factor = match is_fine {
+            true => style.fine_factor,
+            false => 1.0,
+        };
+        let fraction = match style.drag_mode {
+            KnobDragMode::Vertical => {
+                (drag.last_position[1] - position[1])
+                    / style.drag_distance.max(1.0)
+            }
+            KnobDragMode::Circular => {
+                let (center, _) = fit_knob(&self.bounds, style);
+                let turn = angle_between(center, drag.last_position, position);
+                turn / style.sweep_angle.max(f32::EPSILON)
+            }
+        };
+        drag.last_position = position;
+        drag.raw_value = (drag.raw_value + fraction * factor * span)
+            .clamp(self.range[0], self.range[1]);
+
+        let value = snap_to_detent(
+            drag.raw_value,
+            &self.detents,
+            style.detent_snap * span.abs(),
+        );
+        if value != self.value {
+            self.value = value;
+            self.update_value(view_projection, dpr, renderer);
+            self.events.push(KnobEvent::Changed(value));
+        }
+    }
+
+    pub fn release(&mut self) {
+        let Some(drag) = self.drag.take() else { return };
+        if self.value != drag.start_value {
+            self.events.push(KnobEvent::Committed(self.value));
+        }
+    }
+
+    // Ends a drag in progress, committing what it changed so far.
+    // Returns whether the availability changed.
+    pub fn set_availability(
+        &mut self,
+        availability: Availability,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if availability == self.availability {
+            return false;
+        }
+        self.availability = availability;
+        if !availability.is_enabled() {
+            self.release();
+        }
+        self.update_value(view_projection, dpr, renderer);
+        true
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: KnobStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.body.destroy(renderer);
+        renderer.remove_arc(self.track);
+        renderer.remove_arc(self.value_arc);
+        renderer.remove_rectangle(self.indicator);
+    }
+
+    fn refresh(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let (center, radius) = fit_knob(&self.bounds, &self.style);
+        let bounds = body_bounds(&self.bounds, center, radius);
+        self.body.update_clip_rect(&bounds.clip_rect, renderer);
+        self.body.update_position(
+            bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.body
+            .update_size(bounds.size, view_projection, dpr, renderer);
+        self.body
+            .update_style(body_style(&self.style, radius, dpr), renderer);
+
+        let [track, _] = self.build_arcs(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_arc(self.track) {
+            *instance = track;
+        }
+        self.update_value(view_projection, dpr, renderer);
+    }
+
+    fn update_value(
+        &self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let [_, value_arc] = self.build_arcs(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_arc(self.value_arc) {
+            *instance = value_arc;
+        }
+        let indicator = self.build_indicator(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_rectangle(self.indicator) {
+            *instance = indicator;
+        }
+    }
+
+    fn value_angle(&self) -> f32 {
+        let span = self.range[1] - self.range[0];
+        let t = match span != 0.0 {
+            true => ((self.value - self.range[0]) / span).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        self.style.start_angle + self.style.sweep_angle * t
+    }
+
+    fn build_arcs(&self, view_projection: &Mat4, dpr: f32) -> [ArcSegment; 2] {
+        let style = &self.style;
+        let (center, radius) = fit_knob(&self.bounds, style);
+        let arc = Arc {
+            center,
+            radius: radius + style.gap + style.thickness / 2.0,
+            thickness: style.thickness,
+            start_angle: style.start_angle,
+            sweep_angle: style.sweep_angle,
+            round_caps: true,
+        };
+        let value_arc =
+            Arc { sweep_angle: self.value_angle() - style.start_angle, ..arc };
+        let clip_rect = self.bounds.clip_rect;
+
+        [
+            arc.build_segment(
+                style.track_color,
+                clip_rect,
+                view_projection,
+                dpr,
+            ),
+            value_arc.build_segment(
+                value_color(style, self.availability),
+                clip_rect,
+                view_projection,
+                dpr,
+            ),
+        ]
+    }
+
+    fn build_indicator(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let style = &self.style;
+        let (center, radius) = fit_knob(&self.bounds, style);
+        let angle = self.value_angle();
+        let direction = [angle.cos(), angle.sin()];
+        let inner = radius * 0.4;
+        let outer = (radius - style.indicator_width * 1.5).max(inner);
+        Bar::between(
+            [
+                center[0] + direction[0] * inner,
+                center[1] + direction[1] * inner,
+            ],
+            [
+                center[0] + direction[0] * outer,
+                center[1] + direction[1] * outer,
+            ],
+            style.indicator_width,
+        )
+        .build_rectangle(
+            style.indicator_color,
+            self.bounds.clip_rect,
+            view_projection,
+            dpr,
+        )
+    }
+}
+
+const fn value_color(
+    style: &KnobStyle,
+    availability: Availability,
+) -> [f32; 4] {
+    match availability {
+        Availability::Enabled | Availability::ReadOnly => style.value_color,
+        Availability::Disabled | Availability::Loading => {
+            style.inactive_value_color
+        }
+    }
+}
+
+fn body_style(style: &KnobStyle, radius: f32, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.body_color)
+        .corner_radii([radius * dpr; 4])
+        .build()
+}
+
+fn body_bounds(bounds: &Bounds, center: [f32; 2], radius: f32) -> Bounds {
+    Bounds {
+        position:  [center[0] - radius, center[1] - radius],
+        size:      [radius * 2.0; 2],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+// The center and body radius, leaving room for the value arc around it.
+fn fit_knob(bounds: &Bounds, style: &KnobStyle) -> ([f32; 2], f32) {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let radius =
+        (width.min(height) / 2.0 - style.gap - style.thickness).max(0.0);
+
+    ([x + width / 2.0, y + height / 2.0], radius)
+}
+
+// The signed turn from one position to another around the center, in
+// (-PI, PI]; clockwise in screen space is positive.
+fn angle_between(center: [f32; 2], from: [f32; 2], to: [f32; 2]) -> f32 {
+    let from = (from[1] - center[1]).atan2(from[0] - center[0]);
+    let to = (to[1] - center[1]).atan2(to[0] - center[0]);
+    let turn = (to - from).rem_euclid(TAU);
+    match turn > PI {
+        true => turn - TAU,
+        false => turn,
+    }
+}
+
+fn snap_to_detent(value: f32, detents: &[f32], threshold: f32) -> f32 {
+    detents
+        .iter()
+        .copied()
+        .filter(|detent| (detent - value).abs() <= threshold)
+        .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use rstest::rstest;
+
+    use super::{KnobStyle, angle_between, snap_to_detent, value_color};
+    use crate::components::widgets::Availability;
+
+    #[rstest]
+    #[case([10.0, 0.0], [0.0, 10.0], FRAC_PI_2)]
+    #[case([0.0, 10.0], [10.0, 0.0], -FRAC_PI_2)]
+    // Across the seam at PI.
+    #[case([-10.0, -1.0], [-10.0, 1.0], -0.199_337)]
+    fn test_angle_between(
+        #[case] from: [f32; 2],
+        #[case] to: [f32; 2],
+        #[case] expected: f32,
+    ) {
+        let turn = angle_between([0.0, 0.0], from, to);
+        assert!((turn - expected).abs() < 1e-5, "{turn}");
+    }
+
+    #[rstest]
+    #[case(0.49, 0.5)]
+    #[case(0.45, 0.45)]
+    #[case(0.02, 0.0)]
+    fn test_snap_to_detent(#[case] value: f32, #[case] expected: f32) {
+        assert_eq!(snap_to_detent(value, &[0.0, 0.5, 1.0], 0.02), expected);
+    }
+
+    #[rstest]
+    #[case(Availability::Enabled, [0.0, 0.0, 1.0, 1.0])]
+    #[case(Availability::ReadOnly, [0.0, 0.0, 1.0, 1.0])]
+    #[case(Availability::Disabled, [0.5, 0.5, 0.5, 1.0])]
+    #[case(Availability::Loading, [0.5, 0.5, 0.5, 1.0])]
+    fn test_value_color(
+        #[case] availability: Availability,
+        #[case] expected: [f32; 4],
+    ) {
+        let style = KnobStyle::builder()
+            .value_color([0.0, 0.0, 1.0, 1.0])
+            .inactive_value_color([0.5, 0.5, 0.5, 1.0])
+            .build();
+        assert_eq!(value_color(&style, availability), expected);
+    }
+}
@@ -0,0 +1,550 @@
+use std::time::Duration;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{BlockStyle, block::build_rectangle};
+use crate::{
+    components::common::Bounds,
+    core::{Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelMeterKind {
+    Continuous,
+    // Lit segments like an LED ladder.
+    Segmented,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct LevelMeterStyle {
+    #[builder(default = LevelMeterKind::Continuous)]
+    kind:           LevelMeterKind,
+    // Vertical meters fill from the bottom, horizontal ones from the left.
+    #[builder(default = false)]
+    is_horizontal:  bool,
+    #[builder(default = [0.12, 0.12, 0.14, 1.0])]
+    track_color:    [f32; 4],
+    #[builder(default = [0.3, 0.8, 0.4, 1.0])]
+    color:          [f32; 4],
+    #[builder(default = [0.95, 0.8, 0.25, 1.0])]
+    warn_color:     [f32; 4],
+    #[builder(default = [0.95, 0.3, 0.25, 1.0])]
+    clip_color:     [f32; 4],
+    // Levels from here on are drawn in the warn and clip colors.
+    #[builder(default = -12.0)]
+    warn_level:     f32,
+    #[builder(default = -3.0)]
+    clip_level:     f32,
+    // Unlit segments are drawn dimmed by this factor.
+    #[builder(default = 0.2)]
+    unlit_alpha:    f32,
+    #[builder(default = 24)]
+    segment_count:  usize,
+    #[builder(default = 1.0)]
+    segment_gap:    f32,
+    // Rising levels settle within about this time; zero follows at once.
+    #[builder(default = Duration::ZERO)]
+    attack:         Duration,
+    // How fast the level falls, in range units per second.
+    #[builder(default = 24.0)]
+    fall_rate:      f32,
+    #[builder(default = Duration::from_millis(1500))]
+    peak_hold:      Duration,
+    #[builder(default = 12.0)]
+    peak_fall_rate: f32,
+    #[builder(default = 2.0)]
+    peak_thickness: f32,
+}
+
+// A level meter for audio levels, with attack and fall ballistics and a
+// peak marker that holds before falling. Data feeds call `set_level` as
+// often as they like and the meter moves on `tick`, once per frame,
+// which only rewrites its own instances: the fill, the segments whose
+// lit state changed and the peak marker.
+pub struct LevelMeter {
+    bounds:      Bounds,
+    style:       LevelMeterStyle,
+    // In the caller's units, e.g. [-60.0, 0.0] dBFS.
+    range:       [f32; 2],
+    target:      f32,
+    level:       f32,
+    peak:        f32,
+    peak_hold:   Duration,
+    track:       RectangleId,
+    // The fill when continuous, or every segment.
+    bars:        Vec<RectangleId>,
+    lit:         usize,
+    peak_marker: RectangleId,
+}
+
+impl LevelMeter {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        range: [f32; 2],
+        style: LevelMeterStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let mut meter = Self {
+            bounds,
+            style,
+            range,
+            target: range[0],
+            level: range[0],
+            peak: range[0],
+            peak_hold: Duration::ZERO,
+            track: RectangleId::default(),
+            bars: Vec::new(),
+            lit: 0,
+            peak_marker: RectangleId::default(),
+        };
+        meter.rebuild(view_projection, dpr, renderer);
+        meter
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    // The shown level, after ballistics.
+    #[must_use]
+    #[inline(always)]
+    pub const fn level(&self) -> f32 { self.level }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn peak(&self) -> f32 { self.peak }
+
+    // Only records the level; the meter catches up on the next `tick`.
+    #[inline(always)]
+    pub const fn set_level(&mut self, level: f32) { self.target = level; }
+
+    pub fn tick(
+        &mut self,
+        elapsed: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let target = self.target.clamp(self.range[0], self.range[1]);
+        let level = follow(
+            self.level,
+            target,
+            elapsed.as_secs_f32(),
+            style.attack.as_secs_f32(),
+            style.fall_rate,
+        );
+        let (peak, peak_hold) = hold_peak(
+            self.peak,
+            self.peak_hold,
+            level,
+            elapsed,
+            style.peak_hold,
+            style.peak_fall_rate,
+        );
+        self.peak_hold = peak_hold;
+
+        if level != self.level {
+            self.level = level;
+            self.update_level(view_projection, dpr, renderer);
+        }
+        if peak != self.peak {
+            self.peak = peak;
+            let marker = self.build_peak_marker(view_projection, dpr);
+            if let Some(instance) =
+                renderer.get_mut_rectangle(self.peak_marker)
+            {
+                *instance = marker;
+            }
+        }
+    }
+
+    // Drops the level and the peak to the bottom of the range at once.
+    pub fn reset(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.target = self.range[0];
+        self.level = self.range[0];
+        self.peak = self.range[0];
+        self.peak_hold = Duration::ZERO;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn set_range(
+        &mut self,
+        range: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.range = range;
+        self.level = self.level.clamp(range[0], range[1]);
+        self.peak = self.peak.clamp(range[0], range[1]);
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: LevelMeterStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        renderer.remove_rectangle(self.track);
+        for rectangle_id in &self.bars {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        renderer.remove_rectangle(self.peak_marker);
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.bars.clear();
+
+        let style = BlockStyle::builder()
+            .fill_color(self.style.track_color)
+            .build();
+        let track =
+            build_rectangle(view_projection, dpr, &self.bounds, &style);
+        self.track = renderer.add_rectangle(&track);
+
+        let t = normalize(self.level, self.range);
+        match self.style.kind {
+            LevelMeterKind::Continuous => {
+                let fill = self.build_fill(view_projection, dpr);
+                self.bars.push(renderer.add_rectangle(&fill));
+            }
+            LevelMeterKind::Segmented => {
+                let count = self.style.segment_count.max(1);
+                self.lit = lit_segments(t, count);
+                for index in 0..count {
+                    let segment =
+                        self.build_segment(index, view_projection, dpr);
+                    self.bars.push(renderer.add_rectangle(&segment));
+                }
+            }
+        }
+
+        let marker = self.build_peak_marker(view_projection, dpr);
+        self.peak_marker = renderer.add_rectangle(&marker);
+    }
+
+    fn update_level(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let t = normalize(self.level, self.range);
+        match self.style.kind {
+            LevelMeterKind::Continuous => {
+                let fill = self.build_fill(view_projection, dpr);
+                if let Some(instance) =
+                    renderer.get_mut_rectangle(self.bars[0])
+                {
+                    *instance = fill;
+                }
+            }
+            LevelMeterKind::Segmented => {
+                let lit = lit_segments(t, self.bars.len());
+                let changed = lit.min(self.lit)..lit.max(self.lit);
+                self.lit = lit;
+                for index in changed {
+                    let color = self.segment_color(index);
+                    if let Some(instance) =
+                        renderer.get_mut_rectangle(self.bars[index])
+                    {
+                        instance.fill_color = color;
+                    }
+                }
+            }
+        }
+    }
+
+    fn zone_color(&self, value: f32) -> [f32; 4] {
+        let style = &self.style;
+        match value {
+            value if value >= style.clip_level => style.clip_color,
+            value if value >= style.warn_level => style.warn_color,
+            _ => style.color,
+        }
+    }
+
+    fn segment_color(&self, index: usize) -> [f32; 4] {
+        // Segments take the zone color of their lower edge.
+        let [min, max] = self.range;
+        let t = index as f32 / self.style.segment_count.max(1) as f32;
+        let [r, g, b, a] = self.zone_color(min + (max - min) * t);
+        match index < self.lit {
+            true => [r, g, b, a],
+            false => [r, g, b, a * self.style.unlit_alpha],
+        }
+    }
+
+    fn build_fill(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let t = normalize(self.level, self.range);
+        let [x, y, width, height] =
+            span_rect(&self.bounds, 0.0, t, self.style.is_horizontal);
+        self.build_part(
+            [x, y, width, height],
+            self.zone_color(self.level),
+            view_projection,
+            dpr,
+        )
+    }
+
+    fn build_segment(
+        &self,
+        index: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let count = self.style.segment_count.max(1);
+        let rect = segment_rect(
+            &self.bounds,
+            index,
+            count,
+            self.style.segment_gap,
+            self.style.is_horizontal,
+        );
+        self.build_part(rect, self.segment_color(index), view_projection, dpr)
+    }
+
+    fn build_peak_marker(
+        &self,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let style = &self.style;
+        let is_horizontal = style.is_horizontal;
+        let length = match is_horizontal {
+            true => self.bounds.size[0],
+            false => self.bounds.size[1],
+        };
+        let t = normalize(self.peak, self.range);
+        let thickness = (style.peak_thickness / length.max(1.0)).min(1.0);
+        let from = (t - thickness).max(0.0);
+        let rect =
+            span_rect(&self.bounds, from, from + thickness, is_horizontal);
+        // Hidden while the peak rests at the bottom.
+        let color = match self.peak > self.range[0] {
+            true => self.zone_color(self.peak),
+            false => [0.0; 4],
+        };
+        self.build_part(rect, color, view_projection, dpr)
+    }
+
+    fn build_part(
+        &self,
+        [x, y, width, height]: [f32; 4],
+        color: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let bounds = Bounds {
+            position:  [x, y],
+            size:      [width, height],
+            clip_rect: self.bounds.clip_rect,
+        };
+        let style = BlockStyle::builder().fill_color(color).build();
+        build_rectangle(view_projection, dpr, &bounds, &style)
+    }
+}
+
+fn normalize(value: f32, range: [f32; 2]) -> f32 {
+    let span = range[1] - range[0];
+    match span != 0.0 {
+        true => ((value - range[0]) / span).clamp(0.0, 1.0),
+        false => 0.0,
+    }
+}
+
+// Rises toward the target exponentially over the attack time and falls
+// linearly at the fall rate, never passing the target.
+fn follow(
+    level: f32,
+    target: f32,
+    elapsed: f32,
+    attack: f32,
+    fall_rate: f32,
+) -> f32 {
+    match target >= level {
+        true if attack <= 0.0 => target,
+        true => level + (target - level) * (1.0 - (-elapsed / attack).exp()),
+        false => (level - fall_rate * elapsed).max(target),
+    }
+}
+
+// Returns the peak and the hold time left. A new peak restarts the hold;
+// once it runs out the peak falls toward the level.
+fn hold_peak(
+    peak: f32,
+    hold_left: Duration,
+    level: f32,
+    elapsed: Duration,
+    hold: Duration,
+    fall_rate: f32,
+) -> (f32, Duration) {
+    if level >= peak {
+        return (level, hold);
+    }
+    match hold_left.checked_sub(elapsed) {
+        Some(hold_left) if !hold_left.is_zero() => (peak, hold_left),
+        _ => {
+            let falling = elapsed.saturating_sub(hold_left).as_secs_f32();
+            ((peak - fall_rate * falling).max(level), Duration::ZERO)
+        }
+    }
+}
+
+fn lit_segments(t: f32, count: usize) -> usize {
+    ((t * count as f32).round() as usize).min(count)
+}
+
+// The part of the meter between `from` and `to`, as fractions along it.
+fn span_rect(
+    bounds: &Bounds,
+    from: f32,
+    to: f32,
+    is_horizontal: bool,
+) -> [f32; 4] {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    match is_horizontal {
+        true => [x + width * from, y, width * (to - from), height],
+        false => [x, y + height * (1.0 - to), width, height * (to - from)],
+    }
+}
+
+fn segment_rect(
+    bounds: &Bounds,
+    index: usize,
+    count: usize,
+    gap: f32,
+    is_horizontal: bool,
+) -> [f32; 4] {
+    let length = match is_horizontal {
+        true => bounds.size[0],
+        false => bounds.size[1],
+    };
+    let step = 1.0 / count as f32;
+    let inset = (gap / length.max(1.0) / 2.0).min(step / 2.0);
+    let from = index as f32 * step + inset;
+    let to = (index + 1) as f32 * step - inset;
+    span_rect(bounds, from, to, is_horizontal)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::{follow, hold_peak, lit_segments, segment_rect};
+    use crate::components::common::Bounds;
+
+    #[rstest]
+    #[case(-60.0, -6.0, 0.0, -6.0)]
+    // Half of the way after ln(2) attack times.
+    #[case(-20.0, 0.0, 0.1, -10.0)]
+    #[case(-6.0, -60.0, 0.0, -8.4)]
+    // Falling stops at the target.
+    #[case(-6.0, -7.0, 0.0, -7.0)]
+    fn test_follow(
+        #[case] level: f32,
+        #[case] target: f32,
+        #[case] attack: f32,
+        #[case] expected: f32,
+    ) {
+        let elapsed = match attack > 0.0 {
+            true => attack * std::f32::consts::LN_2,
+            false => 0.1,
+        };
+        let level = follow(level, target, elapsed, attack, 24.0);
+        assert!((level - expected).abs() < 1e-4, "{level}");
+    }
+
+    #[rstest]
+    #[case(-10.0, 1000, -3.0, (-3.0, 1500))]
+    #[case(-10.0, 1000, -20.0, (-10.0, 900))]
+    // The hold runs out halfway through the frame.
+    #[case(-10.0, 50, -20.0, (-10.5, 0))]
+    fn test_hold_peak(
+        #[case] peak: f32,
+        #[case] hold_left: u64,
+        #[case] level: f32,
+        #[case] expected: (f32, u64),
+    ) {
+        let (peak, hold_left) = hold_peak(
+            peak,
+            Duration::from_millis(hold_left),
+            level,
+            Duration::from_millis(100),
+            Duration::from_millis(1500),
+            10.0,
+        );
+        assert!((peak - expected.0).abs() < 1e-4, "{peak}");
+        assert_eq!(hold_left, Duration::from_millis(expected.1));
+    }
+
+    #[rstest]
+    #[case(0.0, 0)]
+    #[case(0.49, 5)]
+    #[case(1.0, 10)]
+    fn test_lit_segments(#[case] t: f32, #[case] expected: usize) {
+        assert_eq!(lit_segments(t, 10), expected);
+    }
+
+    #[rstest]
+    #[case(false, [0.0, 73.0, 10.0, 6.0])]
+    #[case(true, [1.0, 0.0, 6.0, 100.0])]
+    fn test_segment_rect(
+        #[case] is_horizontal: bool,
+        #[case] expected: [f32; 4],
+    ) {
+        let bounds = Bounds {
+            position:  [0.0, 0.0],
+            size:      match is_horizontal {
+                true => [80.0, 100.0],
+                false => [10.0, 80.0],
+            },
+            clip_rect: [0.0, 0.0, f32::MAX, f32::MAX],
+        };
+        let rect = segment_rect(&bounds, 0, 10, 2.0, is_horizontal);
+        let rect = rect.map(|value| (value * 1e3).round() / 1e3);
+        assert_eq!(rect, expected);
+    }
+}
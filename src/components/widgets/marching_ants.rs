@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{components::common::Bounds, core::Renderer};
+
+// Sizes and the speed are in logical pixels.
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct MarchingAntsStyle {
+    #[builder(default = [0.0, 0.0, 0.0, 1.0])]
+    color:        [f32; 4],
+    #[builder(default = 1.0)]
+    thickness:    f32,
+    #[builder(default = [0.0, 0.0, 0.0, 0.0])]
+    corner_radii: [f32; 4],
+    #[builder(default = 4.0)]
+    dash_length:  f32,
+    #[builder(default = 4.0)]
+    gap_length:   f32,
+    // How far the dashes travel along the outline per second.
+    #[builder(default = 16.0)]
+    speed:        f32,
+}
+
+// The animated dashed outline of a selection. Lasso selections drawn as
+// paths can follow the same dashes by setting their `dash_offset` to
+// `dash_offset() * dpr` with a matching pattern.
+pub struct MarchingAnts {
+    bounds:      Bounds,
+    style:       MarchingAntsStyle,
+    // in logical pixels, within one dash and gap
+    dash_offset: f32,
+    outline:     Block<Positioned>,
+}
+
+impl MarchingAnts {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        style: MarchingAntsStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let outline = Block::<Positioned>::new(
+            bounds.clone(),
+            build_block_style(&style, 0.0, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        Self { bounds, style, dash_offset: 0.0, outline }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn dash_offset(&self) -> f32 { self.dash_offset }
+
+    // Pass the animation clock's delta; the dashes keep marching while
+    // the outline exists.
+    pub fn tick(&mut self, dt: Duration, dpr: f32, renderer: &mut Renderer) {
+        self.dash_offset = advance_dash_offset(
+            self.dash_offset,
+            dt,
+            self.style.speed,
+            self.style.dash_length + self.style.gap_length,
+        );
+        self.outline
+            .update_dash_offset(self.dash_offset * dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.outline.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.outline.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.outline
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: MarchingAntsStyle,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.outline.update_style(
+            build_block_style(&self.style, self.dash_offset, dpr),
+            renderer,
+        );
+    }
+
+    #[inline(always)]
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.outline.destroy(renderer);
+    }
+}
+
+fn build_block_style(
+    style: &MarchingAntsStyle,
+    dash_offset: f32,
+    dpr: f32,
+) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color([0.0; 4])
+        .outline_color([0.0; 4])
+        .border_color(style.color)
+        .border_size(style.thickness * dpr)
+        .corner_radii(style.corner_radii.map(|radius| radius * dpr))
+        .dash_pattern([style.dash_length * dpr, style.gap_length * dpr])
+        .dash_offset(dash_offset * dpr)
+        .build()
+}
+
+// Wraps around the pattern's period, so that the offset stays small no
+// matter how long the selection lives.
+fn advance_dash_offset(
+    dash_offset: f32,
+    dt: Duration,
+    speed: f32,
+    period: f32,
+) -> f32 {
+    if period <= 0.0 {
+        return 0.0;
+    }
+    (dash_offset + speed * dt.as_secs_f32()).rem_euclid(period)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::advance_dash_offset;
+
+    #[rstest]
+    #[case(0.0, 250, 16.0, 8.0, 4.0)]
+    #[case(6.0, 250, 16.0, 8.0, 2.0)]
+    #[case(2.0, 250, -16.0, 8.0, 6.0)]
+    #[case(3.0, 250, 16.0, 0.0, 0.0)]
+    fn test_advance_dash_offset(
+        #[case] dash_offset: f32,
+        #[case] dt_millis: u64,
+        #[case] speed: f32,
+        #[case] period: f32,
+        #[case] expected: f32,
+    ) {
+        let dt = Duration::from_millis(dt_millis);
+        let actual = advance_dash_offset(dash_offset, dt, speed, period);
+        assert!((actual - expected).abs() < 1e-5);
+    }
+}
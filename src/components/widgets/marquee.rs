@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bounds, SelectionEvent, intersect_rects},
+    core::Renderer,
+};
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct MarqueeStyle {
+    #[builder(default = [0.26, 0.52, 0.96, 0.15])]
+    fill_color:   [f32; 4],
+    #[builder(default = [0.26, 0.52, 0.96, 0.8])]
+    border_color: [f32; 4],
+    // in logical pixels
+    #[builder(default = 1.0)]
+    border_size:  f32,
+}
+
+// Rubber-band selection over a canvas: dragging on its empty space draws
+// a translucent rectangle and selects the registered widgets it touches,
+// reporting every change as the drag proceeds. Widgets are selected by
+// the index `register` returned for them.
+pub struct Marquee {
+    // The canvas; presses outside of it are left to others.
+    bounds:   Bounds,
+    style:    MarqueeStyle,
+    targets:  Vec<Bounds>,
+    selected: BTreeSet<usize>,
+    // What was selected before an extending drag started; it stays
+    // selected whatever the rectangle touches.
+    kept:     BTreeSet<usize>,
+    drag:     Option<(MarqueeDrag, Block<Positioned>)>,
+    events:   Vec<SelectionEvent>,
+}
+
+struct MarqueeDrag {
+    start: [f32; 2],
+    end:   [f32; 2],
+}
+
+impl Marquee {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(bounds: Bounds, style: MarqueeStyle) -> Self {
+        Self {
+            bounds,
+            style,
+            targets: Vec::new(),
+            selected: BTreeSet::new(),
+            kept: BTreeSet::new(),
+            drag: None,
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[inline(always)]
+    pub fn set_bounds(&mut self, bounds: Bounds) { self.bounds = bounds; }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dragging(&self) -> bool { self.drag.is_some() }
+
+    // x, y, width, height of the rectangle being dragged.
+    #[must_use]
+    pub fn rect(&self) -> Option<[f32; 4]> {
+        self.drag
+            .as_ref()
+            .map(|(drag, _)| normalize_rect(drag.start, drag.end))
+    }
+
+    #[must_use]
+    pub fn register(&mut self, bounds: Bounds) -> usize {
+        self.targets.push(bounds);
+        self.targets.len() - 1
+    }
+
+    // Widgets that moved keep their index.
+    pub fn update_target(&mut self, index: usize, bounds: Bounds) {
+        if let Some(target) = self.targets.get_mut(index) {
+            *target = bounds;
+        }
+    }
+
+    // Forgets every widget along with the selection, without reporting
+    // it.
+    pub fn clear_targets(&mut self) {
+        self.targets.clear();
+        self.selected.clear();
+        self.kept.clear();
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    // Selected indices, ascending.
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    pub fn take_events(&mut self) -> Vec<SelectionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Returns whether a drag started, which it does on the canvas's empty
+    // space only. `is_extending` (shift or ctrl) adds to the selection
+    // instead of replacing it.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        is_extending: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_on_target =
+            self.targets.iter().any(|target| contains(target, position));
+        if !contains(&self.bounds, position) || is_on_target {
+            return false;
+        }
+        self.release(renderer);
+
+        self.kept = match is_extending {
+            true => self.selected.clone(),
+            false => BTreeSet::new(),
+        };
+        self.apply(self.kept.clone());
+
+        let drag = MarqueeDrag { start: position, end: position };
+        let block = Block::<Positioned>::new(
+            self.rect_bounds(&drag),
+            build_block_style(&self.style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.drag = Some((drag, block));
+        true
+    }
+
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some((mut drag, mut block)) = self.drag.take() else {
+            return;
+        };
+        drag.end = position;
+
+        let bounds = self.rect_bounds(&drag);
+        block.update_position(bounds.position, view_projection, dpr, renderer);
+        block.update_size(bounds.size, view_projection, dpr, renderer);
+
+        let mut selected = self.kept.clone();
+        selected.extend(self.touched(normalize_rect(drag.start, drag.end)));
+        self.apply(selected);
+        self.drag = Some((drag, block));
+    }
+
+    // Ends the drag; the selection stays.
+    pub fn release(&mut self, renderer: &mut Renderer) {
+        if let Some((_, block)) = self.drag.take() {
+            block.destroy(renderer);
+        }
+    }
+
+    #[inline(always)]
+    pub fn destroy(&mut self, renderer: &mut Renderer) {
+        self.release(renderer);
+    }
+
+    // The rectangle, clipped to the canvas.
+    fn rect_bounds(&self, drag: &MarqueeDrag) -> Bounds {
+        let [x, y, width, height] = normalize_rect(drag.start, drag.end);
+        let [canvas_x, canvas_y] = self.bounds.position;
+        let [canvas_width, canvas_height] = self.bounds.size;
+        let canvas = [canvas_x, canvas_y, canvas_width, canvas_height];
+        Bounds {
+            position:  [x, y],
+            size:      [width, height],
+            clip_rect: intersect_rects(canvas, self.bounds.clip_rect),
+        }
+    }
+
+    fn touched(&self, rect: [f32; 4]) -> impl Iterator<Item = usize> + '_ {
+        self.targets
+            .iter()
+            .enumerate()
+            .filter(move |(_, target)| intersects(target, rect))
+            .map(|(index, _)| index)
+    }
+
+    fn apply(&mut self, selected: BTreeSet<usize>) {
+        let deselected = self.selected.difference(&selected);
+        self.events
+            .extend(deselected.copied().map(SelectionEvent::Deselected));
+        let newly_selected = selected.difference(&self.selected);
+        self.events
+            .extend(newly_selected.copied().map(SelectionEvent::Selected));
+        self.selected = selected;
+    }
+}
+
+fn build_block_style(style: &MarqueeStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.fill_color)
+        .border_color(style.border_color)
+        .border_size(style.border_size * dpr)
+        .outline_color([0.0; 4])
+        .build()
+}
+
+fn normalize_rect(a: [f32; 2], b: [f32; 2]) -> [f32; 4] {
+    let x = a[0].min(b[0]);
+    let y = a[1].min(b[1]);
+    [x, y, (a[0] - b[0]).abs(), (a[1] - b[1]).abs()]
+}
+
+fn intersects(bounds: &Bounds, rect: [f32; 4]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    x <= rect[0] + rect[2]
+        && rect[0] <= x + width
+        && y <= rect[1] + rect[3]
+        && rect[1] <= y + height
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Marquee, MarqueeStyle, normalize_rect};
+    use crate::components::common::Bounds;
+
+    #[rstest]
+    #[case([10.0, 10.0], [40.0, 30.0], &[0])]
+    // dragged up and to the left
+    #[case([40.0, 30.0], [10.0, 10.0], &[0])]
+    #[case([10.0, 10.0], [75.0, 30.0], &[0, 1])]
+    // touching an edge is enough
+    #[case([60.0, 0.0], [70.0, 5.0], &[1])]
+    #[case([0.0, 40.0], [90.0, 50.0], &[])]
+    fn test_marquee_touches_targets(
+        #[case] start: [f32; 2],
+        #[case] end: [f32; 2],
+        #[case] expected: &[usize],
+    ) {
+        let canvas = Bounds::without_clip_rect([0.0, 0.0], [100.0, 100.0]);
+        let mut marquee =
+            Marquee::new(canvas, MarqueeStyle::builder().build());
+        let _ = marquee
+            .register(Bounds::without_clip_rect([20.0, 0.0], [20.0, 20.0]));
+        let _ = marquee
+            .register(Bounds::without_clip_rect([70.0, 5.0], [20.0, 20.0]));
+
+        let rect = normalize_rect(start, end);
+        assert_eq!(marquee.touched(rect).collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,143 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::{common::Bounds, text::FuzzyMatch},
+    core::Renderer,
+};
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct MatchHighlightStyle {
+    #[builder(default = [1.0, 0.85, 0.3, 0.6])]
+    color:         [f32; 4],
+    #[builder(default = 2.0)]
+    corner_radius: f32,
+}
+
+// A row of text to highlight matches in: the bounds of the text line and
+// the advance of every char, as laid out by the caller.
+pub struct HighlightRow<'a> {
+    pub text_bounds: Bounds,
+    pub advances:    &'a [f32],
+    pub found:       &'a FuzzyMatch,
+}
+
+// Background spans behind the matched chars of labels and list rows,
+// drawn beneath the text.
+pub struct MatchHighlights {
+    style: MatchHighlightStyle,
+    spans: Vec<Block<Positioned>>,
+}
+
+impl MatchHighlights {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(style: MatchHighlightStyle) -> Self {
+        Self { style, spans: Vec::new() }
+    }
+
+    pub fn update(
+        &mut self,
+        rows: &[HighlightRow],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.spans.clear();
+
+        let style = BlockStyle::builder()
+            .fill_color(self.style.color)
+            .corner_radii([self.style.corner_radius * dpr; 4])
+            .build();
+        for row in rows {
+            let spans = highlight_spans(
+                &row.text_bounds,
+                row.advances,
+                &row.found.positions,
+            );
+            for span in spans {
+                self.spans.push(Block::<Positioned>::new(
+                    span,
+                    style.clone(),
+                    view_projection,
+                    dpr,
+                    renderer,
+                ));
+            }
+        }
+    }
+
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        self.destroy(renderer);
+        self.spans.clear();
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for span in &self.spans {
+            span.destroy(renderer);
+        }
+    }
+}
+
+// One span per run of consecutive matched chars.
+fn highlight_spans(
+    text_bounds: &Bounds,
+    advances: &[f32],
+    positions: &[usize],
+) -> Vec<Bounds> {
+    let [x, y] = text_bounds.position;
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for &position in positions
+        .iter()
+        .filter(|position| **position < advances.len())
+    {
+        match spans.last_mut() {
+            Some((_, end)) if *end == position => *end += 1,
+            _ => spans.push((position, position + 1)),
+        }
+    }
+
+    spans
+        .into_iter()
+        .map(|(start, end)| {
+            let offset: f32 = advances[..start].iter().sum();
+            let width: f32 = advances[start..end].iter().sum();
+            Bounds {
+                position:  [x + offset, y],
+                size:      [width, text_bounds.size[1]],
+                clip_rect: text_bounds.clip_rect,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::highlight_spans;
+    use crate::Bounds;
+
+    #[rstest]
+    #[case(&[0, 4], &[(10.0, 8.0), (42.0, 8.0)])]
+    #[case(&[1, 2, 3], &[(18.0, 24.0)])]
+    #[case(&[6, 9], &[(58.0, 8.0)])]
+    #[case(&[], &[])]
+    fn test_highlight_spans(
+        #[case] positions: &[usize],
+        #[case] expected: &[(f32, f32)],
+    ) {
+        let text_bounds =
+            Bounds::without_clip_rect([10.0, 5.0], [200.0, 20.0]);
+        let spans = highlight_spans(&text_bounds, &[8.0; 7], positions);
+
+        let spans: Vec<(f32, f32)> = spans
+            .iter()
+            .map(|span| (span.position[0], span.size[0]))
+            .collect();
+        assert_eq!(spans, expected);
+    }
+}
@@ -0,0 +1,550 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bounds, build_model},
+    core::{Image, ImageId, Renderer, TextureId},
+};
+
+// Something on the canvas, in content coordinates: x, y, width, height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinimapItem {
+    pub rect:  [f32; 4],
+    pub color: [f32; 4],
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct MinimapStyle {
+    #[builder(default = [0.12, 0.12, 0.14, 0.9])]
+    background_color:      [f32; 4],
+    #[builder(default = [1.0, 1.0, 1.0, 0.12])]
+    viewport_color:        [f32; 4],
+    #[builder(default = [1.0, 1.0, 1.0, 0.5])]
+    viewport_border_color: [f32; 4],
+    #[builder(default = 1.0)]
+    viewport_border_size:  f32,
+    #[builder(default = 4.0)]
+    corner_radius:         f32,
+}
+
+// A scaled-down view of a large canvas with the visible part outlined.
+// The canvas is rasterized once into a texture by `set_content` and only
+// again when the caller says it changed; scrolling just moves the
+// viewport rectangle. Dragging the rectangle, or pressing elsewhere to
+// jump there, yields viewport origins for the caller's scroll view or
+// camera.
+pub struct Minimap {
+    bounds:        Bounds,
+    style:         MinimapStyle,
+    content_size:  [f32; 2],
+    // x, y, width, height in content coordinates
+    viewport:      [f32; 4],
+    background:    Block<Positioned>,
+    content:       Option<(ImageId, TextureId)>,
+    // Images are drawn above rectangles, so the viewport rectangle is made
+    // of tinted images of a white pixel too: the fill and four edges.
+    white_texture: Option<TextureId>,
+    outline:       Vec<ImageId>,
+    // Where the viewport was grabbed, relative to its origin.
+    grab_offset:   Option<[f32; 2]>,
+}
+
+impl Minimap {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        content_size: [f32; 2],
+        style: MinimapStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            background_style(&style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        Self {
+            bounds,
+            style,
+            content_size,
+            viewport: [0.0, 0.0, content_size[0], content_size[1]],
+            background,
+            content: None,
+            white_texture: None,
+            outline: Vec::new(),
+            grab_offset: None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn viewport(&self) -> [f32; 4] { self.viewport }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dragging(&self) -> bool { self.grab_offset.is_some() }
+
+    // Rasterizes the canvas into the cached texture, at the minimap's
+    // current resolution.
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_content(
+        &mut self,
+        items: &[MinimapItem],
+        content_size: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.content_size = content_size;
+        let content_bounds = content_bounds(&self.bounds, content_size);
+        let pixel_size = content_bounds
+            .size
+            .map(|size| ((size * dpr).round() as u32).max(1));
+        let scale = pixel_size[0] as f32 / content_size[0].max(1.0);
+        let rgba = rasterize(items, pixel_size, scale);
+
+        let texture_id = renderer.add_texture(pixel_size, &rgba);
+        let image =
+            build_image(&content_bounds, [1.0; 4], view_projection, dpr);
+        match self.content {
+            Some((image_id, previous_texture_id)) => {
+                renderer.set_image_texture(image_id, texture_id);
+                renderer.remove_texture(previous_texture_id);
+                if let Some(instance) = renderer.get_mut_image(image_id) {
+                    *instance = image;
+                }
+                self.content = Some((image_id, texture_id));
+            }
+            None => {
+                self.content = renderer
+                    .add_image(texture_id, &image)
+                    .map(|image_id| (image_id, texture_id));
+            }
+        }
+
+        let white_texture = *self
+            .white_texture
+            .get_or_insert_with(|| renderer.add_texture([1, 1], &[255; 4]));
+        if self.outline.is_empty() {
+            let parts = outline_parts(
+                &self.bounds,
+                self.content_size,
+                self.viewport,
+                &self.style,
+            );
+            self.outline = parts
+                .iter()
+                .filter_map(|(bounds, color)| {
+                    let image =
+                        build_image(bounds, *color, view_projection, dpr);
+                    renderer.add_image(white_texture, &image)
+                })
+                .collect();
+        } else {
+            self.update_outline(view_projection, dpr, renderer);
+        }
+    }
+
+    // Follows the scroll view or camera; `viewport` is in content
+    // coordinates.
+    pub fn set_viewport(
+        &mut self,
+        viewport: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if viewport == self.viewport {
+            return;
+        }
+        self.viewport = viewport;
+        self.update_outline(view_projection, dpr, renderer);
+    }
+
+    // Grabs the viewport rectangle, or centers it on the position when
+    // pressed outside of it. Returns the new viewport origin.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<[f32; 2]> {
+        if !contains(&self.bounds, position) {
+            return None;
+        }
+        let [x, y, width, height] = self.viewport;
+        let point = to_content(&self.bounds, self.content_size, position);
+        let grab_offset = match contains_point(self.viewport, point) {
+            true => [point[0] - x, point[1] - y],
+            false => [width / 2.0, height / 2.0],
+        };
+        self.grab_offset = Some(grab_offset);
+
+        self.drag(position, view_projection, dpr, renderer)
+    }
+
+    // Moves the grabbed viewport. Returns the new viewport origin.
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<[f32; 2]> {
+        let grab_offset = self.grab_offset?;
+        let point = to_content(&self.bounds, self.content_size, position);
+        let [_, _, width, height] = self.viewport;
+        let origin = viewport_origin(
+            point,
+            grab_offset,
+            [width, height],
+            self.content_size,
+        );
+        self.set_viewport(
+            [origin[0], origin[1], width, height],
+            view_projection,
+            dpr,
+            renderer,
+        );
+
+        Some(origin)
+    }
+
+    #[inline(always)]
+    pub const fn release(&mut self) { self.grab_offset = None; }
+
+    // The cached texture is stretched to the new bounds until the next
+    // `set_content`.
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.background
+            .update_clip_rect(&self.bounds.clip_rect, renderer);
+        self.background.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        if let Some((image_id, _)) = self.content {
+            let content_bounds =
+                content_bounds(&self.bounds, self.content_size);
+            let image =
+                build_image(&content_bounds, [1.0; 4], view_projection, dpr);
+            if let Some(instance) = renderer.get_mut_image(image_id) {
+                *instance = image;
+            }
+        }
+        self.update_outline(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: MinimapStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background
+            .update_style(background_style(&self.style, dpr), renderer);
+        self.update_outline(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        for image_id in &self.outline {
+            renderer.remove_image(*image_id);
+        }
+        if let Some((image_id, texture_id)) = self.content {
+            renderer.remove_image(image_id);
+            renderer.remove_texture(texture_id);
+        }
+        if let Some(texture_id) = self.white_texture {
+            renderer.remove_texture(texture_id);
+        }
+    }
+
+    fn update_outline(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let parts = outline_parts(
+            &self.bounds,
+            self.content_size,
+            self.viewport,
+            &self.style,
+        );
+        for (image_id, (bounds, color)) in self.outline.iter().zip(parts) {
+            let image = build_image(&bounds, color, view_projection, dpr);
+            if let Some(instance) = renderer.get_mut_image(*image_id) {
+                *instance = image;
+            }
+        }
+    }
+}
+
+fn background_style(style: &MinimapStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.background_color)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+fn build_image(
+    bounds: &Bounds,
+    tint_color: [f32; 4],
+    view_projection: &Mat4,
+    dpr: f32,
+) -> Image {
+    let (model, half_size) = build_model(bounds.size, bounds.position, dpr);
+    let mvp = view_projection * model;
+    Image::builder()
+        .mvp(mvp.to_cols_array_2d())
+        .tint_color(tint_color)
+        .corner_radii([0.0; 4])
+        .clip_rect(bounds.clip_rect.map(|value| value * dpr))
+        .uv_transform([1.0, 1.0, 0.0, 0.0])
+        .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+        .cutout([0.0; 4])
+        .build()
+}
+
+// The viewport rectangle's fill, then its top, bottom, left and right
+// edges.
+fn outline_parts(
+    bounds: &Bounds,
+    content_size: [f32; 2],
+    viewport: [f32; 4],
+    style: &MinimapStyle,
+) -> [(Bounds, [f32; 4]); 5] {
+    let outline = outline_bounds(bounds, content_size, viewport);
+    let [x, y] = outline.position;
+    let [width, height] = outline.size;
+    let border = style
+        .viewport_border_size
+        .min(width / 2.0)
+        .min(height / 2.0);
+    let part = |rect: [f32; 4], color| {
+        let bounds = Bounds {
+            position:  [rect[0], rect[1]],
+            size:      [rect[2], rect[3]],
+            clip_rect: outline.clip_rect,
+        };
+        (bounds, color)
+    };
+    let border_color = style.viewport_border_color;
+
+    [
+        part([x, y, width, height], style.viewport_color),
+        part([x, y, width, border], border_color),
+        part([x, y + height - border, width, border], border_color),
+        part([x, y, border, height], border_color),
+        part([x + width - border, y, border, height], border_color),
+    ]
+}
+
+// How much the content shrinks to fit the minimap, keeping its aspect.
+fn fit_scale(size: [f32; 2], content_size: [f32; 2]) -> f32 {
+    let scale_x = size[0] / content_size[0].max(1.0);
+    let scale_y = size[1] / content_size[1].max(1.0);
+    scale_x.min(scale_y)
+}
+
+// The part of the minimap the content takes up, from its top-left corner.
+fn content_bounds(bounds: &Bounds, content_size: [f32; 2]) -> Bounds {
+    let scale = fit_scale(bounds.size, content_size);
+    Bounds {
+        position:  bounds.position,
+        size:      content_size.map(|size| size * scale),
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn outline_bounds(
+    bounds: &Bounds,
+    content_size: [f32; 2],
+    viewport: [f32; 4],
+) -> Bounds {
+    let scale = fit_scale(bounds.size, content_size);
+    let [x, y, width, height] = viewport.map(|value| value * scale);
+    Bounds {
+        position:  [bounds.position[0] + x, bounds.position[1] + y],
+        size:      [width, height],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn to_content(
+    bounds: &Bounds,
+    content_size: [f32; 2],
+    position: [f32; 2],
+) -> [f32; 2] {
+    let scale = fit_scale(bounds.size, content_size);
+    [
+        (position[0] - bounds.position[0]) / scale,
+        (position[1] - bounds.position[1]) / scale,
+    ]
+}
+
+// Keeps the viewport inside the content where it fits.
+fn viewport_origin(
+    point: [f32; 2],
+    grab_offset: [f32; 2],
+    viewport_size: [f32; 2],
+    content_size: [f32; 2],
+) -> [f32; 2] {
+    std::array::from_fn(|axis| {
+        let max = (content_size[axis] - viewport_size[axis]).max(0.0);
+        (point[axis] - grab_offset[axis]).clamp(0.0, max)
+    })
+}
+
+// Fills the item rects, blended over one another, into a transparent
+// RGBA buffer; `scale` maps content coordinates to pixels.
+fn rasterize(
+    items: &[MinimapItem],
+    pixel_size: [u32; 2],
+    scale: f32,
+) -> Vec<u8> {
+    let [width, height] = pixel_size.map(|size| size as usize);
+    let mut pixels = vec![[0.0f32; 4]; width * height];
+    for item in items {
+        let [x, y, item_width, item_height] =
+            item.rect.map(|value| value * scale);
+        // Anything on the canvas stays visible, however small.
+        let x_end = (x + item_width.max(1.0)).round().clamp(0.0, width as f32);
+        let y_end =
+            (y + item_height.max(1.0)).round().clamp(0.0, height as f32);
+        let x_start = x.round().clamp(0.0, x_end);
+        let y_start = y.round().clamp(0.0, y_end);
+
+        let alpha = item.color[3];
+        for row in y_start as usize..y_end as usize {
+            for pixel in &mut pixels[row * width..(row + 1) * width]
+                [x_start as usize..x_end as usize]
+            {
+                let color = [
+                    item.color[0] * alpha,
+                    item.color[1] * alpha,
+                    item.color[2] * alpha,
+                    alpha,
+                ];
+                for (value, color) in pixel.iter_mut().zip(color) {
+                    *value = color + *value * (1.0 - alpha);
+                }
+            }
+        }
+    }
+
+    // Back to straight alpha.
+    pixels
+        .into_iter()
+        .flat_map(|[red, green, blue, alpha]| {
+            let unpremultiply = if alpha > 0.0 { 1.0 / alpha } else { 0.0 };
+            [
+                red * unpremultiply,
+                green * unpremultiply,
+                blue * unpremultiply,
+                alpha,
+            ]
+            .map(|value| (value * 255.0).round().clamp(0.0, 255.0) as u8)
+        })
+        .collect()
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    contains_point([x, y, width, height], position)
+}
+
+const fn contains_point(rect: [f32; 4], point: [f32; 2]) -> bool {
+    let [x, y, width, height] = rect;
+
+    point[0] >= x
+        && point[0] <= x + width
+        && point[1] >= y
+        && point[1] <= y + height
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{MinimapItem, fit_scale, rasterize, viewport_origin};
+
+    #[rstest]
+    #[case([100.0, 100.0], [1000.0, 500.0], 0.1)]
+    #[case([100.0, 100.0], [500.0, 1000.0], 0.1)]
+    #[case([200.0, 100.0], [1000.0, 1000.0], 0.1)]
+    fn test_fit_scale(
+        #[case] size: [f32; 2],
+        #[case] content_size: [f32; 2],
+        #[case] expected: f32,
+    ) {
+        assert_eq!(fit_scale(size, content_size), expected);
+    }
+
+    #[rstest]
+    #[case([500.0, 500.0], [50.0, 50.0], [450.0, 400.0])]
+    #[case([10.0, 10.0], [50.0, 50.0], [0.0, 0.0])]
+    #[case([990.0, 300.0], [50.0, 50.0], [800.0, 250.0])]
+    fn test_viewport_origin(
+        #[case] point: [f32; 2],
+        #[case] grab_offset: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let origin = viewport_origin(
+            point,
+            grab_offset,
+            [200.0, 100.0],
+            [1000.0, 500.0],
+        );
+        assert_eq!(origin, expected);
+    }
+
+    #[rstest]
+    fn test_rasterize() {
+        let items = [
+            MinimapItem {
+                rect:  [0.0, 0.0, 10.0, 10.0],
+                color: [1.0, 0.0, 0.0, 1.0],
+            },
+            MinimapItem {
+                rect:  [4.0, 0.0, 2.0, 2.0],
+                color: [0.0, 0.0, 1.0, 0.5],
+            },
+        ];
+        let rgba = rasterize(&items, [4, 2], 0.2);
+        let pixel = |x: usize, y: usize| &rgba[(y * 4 + x) * 4..][..4];
+
+        assert_eq!(pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(1, 0), [128, 0, 128, 255]);
+        assert_eq!(pixel(1, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(3, 1), [0, 0, 0, 0]);
+    }
+}
@@ -1,3 +1,91 @@
+mod accordion;
+mod autocomplete;
+mod avatar;
 mod block;
+mod button;
+mod collapsible;
+mod form;
+mod gantt;
+mod gauge;
+mod heatmap;
+mod image_view;
+mod interaction_snapshot;
+mod knob;
+mod level_meter;
+mod marching_ants;
+mod marquee;
+mod match_highlights;
+mod minimap;
+mod pagination;
+mod scroll_view;
+mod search_field;
+mod sparkline;
+mod spectrogram;
+mod status_bar;
+mod stepper;
+mod styled_block;
+mod terminal_grid;
+mod text_input;
+mod timeline;
+mod title_bar;
+mod toolbar;
+mod transform_gizmo;
+mod waveform;
+mod widget;
+mod widget_pool;
 
-pub use block::{Block, BlockStyle, block_states};
+pub use accordion::{Accordion, AccordionMode};
+pub use autocomplete::{
+    Autocomplete, AutocompleteStyle, PopupPlacement, SuggestionProvider,
+};
+pub use avatar::{Avatar, AvatarStyle, BadgeAnchor};
+pub use block::{
+    Block, BlockStyle, PackedBlockStyle, StylePatch, block_states,
+    hit_test_tags,
+};
+pub(crate) use block::{block_model, build_rectangle};
+pub use button::{Button, ButtonState, ButtonStyle};
+pub use collapsible::{Collapsible, CollapsibleStyle};
+pub use form::{CustomValidator, Form, FormStyle, ValidateOn, Validator};
+pub use gantt::{Gantt, GanttDependency, GanttEvent, GanttStyle, GanttTask};
+pub use gauge::{Gauge, GaugeBand, GaugeKind, GaugeStyle};
+pub use heatmap::{ColorScale, Heatmap, HeatmapHover, HeatmapStyle};
+pub use image_view::{ImageStyle, ImageView};
+pub use interaction_snapshot::InteractionSnapshot;
+pub use knob::{Knob, KnobDragMode, KnobEvent, KnobStyle};
+pub use level_meter::{LevelMeter, LevelMeterKind, LevelMeterStyle};
+pub use marching_ants::{MarchingAnts, MarchingAntsStyle};
+pub use marquee::{Marquee, MarqueeStyle};
+pub use match_highlights::{
+    HighlightRow, MatchHighlightStyle, MatchHighlights,
+};
+pub use minimap::{Minimap, MinimapItem, MinimapStyle};
+pub use pagination::{
+    PageChange, Pagination, PaginationItem, PaginationStyle,
+};
+pub use scroll_view::{ScrollAnchor, ScrollTarget, ScrollView};
+pub use search_field::{SearchField, SearchFieldStyle};
+pub use sparkline::{Sparkline, SparklineKind, SparklineStyle};
+pub use spectrogram::Spectrogram;
+pub use status_bar::{StatusBar, StatusBarStyle, StatusBarZone};
+pub use stepper::{StepState, StepValidator, Stepper, StepperStyle};
+pub use styled_block::StyledBlock;
+pub use terminal_grid::{
+    CellAttributes, CellGlyphSource, CursorShape, TerminalCell, TerminalGrid,
+    TerminalGridStyle,
+};
+pub use text_input::{GlyphSource, TextGlyph, TextInput, TextInputStyle};
+pub use timeline::{
+    ClipId, Timeline, TimelineClip, TimelineEvent, TimelineStyle,
+};
+pub use title_bar::{CaptionButton, TitleBar, TitleBarAction, TitleBarStyle};
+pub use toolbar::{Toolbar, ToolbarItem, ToolbarItemKind, ToolbarStyle};
+pub use transform_gizmo::{
+    GizmoHandle, GizmoTarget, TransformGizmo, TransformGizmoStyle,
+};
+pub use waveform::{Waveform, WaveformEvent, WaveformKind, WaveformStyle};
+pub use widget::{
+    AccessNode, AccessRole, Availability, HasAvailability, Widget,
+    WidgetContext,
+};
+pub use widget_pool::{PoolStats, Recyclable, WidgetPool};
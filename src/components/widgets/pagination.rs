@@ -0,0 +1,466 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::Bounds,
+    core::Renderer,
+    format::{Formatter, localize, messages},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaginationItem {
+    Previous,
+    Page(usize),
+    Ellipsis,
+    Next,
+    // Space reserved for a text field the caller places over it.
+    JumpTo,
+}
+
+impl PaginationItem {
+    // The text for the item's tooltip or accessible name, in the global
+    // locale. Page numbers are one-based.
+    #[must_use]
+    pub fn label(&self) -> Option<String> {
+        match self {
+            Self::Previous => Some(localize(messages::PREVIOUS_PAGE, &[])),
+            Self::Next => Some(localize(messages::NEXT_PAGE, &[])),
+            Self::Page(page) => {
+                let page =
+                    Formatter::global().format_number(*page as f64 + 1.0, 0);
+                Some(localize(messages::PAGE, &[("page", &page)]))
+            }
+            Self::Ellipsis | Self::JumpTo => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageChange {
+    pub previous: usize,
+    pub current:  usize,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct PaginationStyle {
+    #[builder(default = [0.94, 0.94, 0.94, 1.0])]
+    item_color:       [f32; 4],
+    #[builder(default = [0.20, 0.45, 0.90, 1.0])]
+    current_color:    [f32; 4],
+    #[builder(default = [0.94, 0.94, 0.94, 0.4])]
+    disabled_color:   [f32; 4],
+    #[builder(default = [0.45, 0.45, 0.45, 1.0])]
+    ellipsis_color:   [f32; 4],
+    #[builder(default = 32.0)]
+    item_size:        f32,
+    #[builder(default = 4.0)]
+    spacing:          f32,
+    #[builder(default = 4.0)]
+    corner_radius:    f32,
+    #[builder(default = 64.0)]
+    jump_field_width: f32,
+    // Pages shown on each side of the current page.
+    #[builder(default = 1)]
+    sibling_count:    usize,
+    // Pages always shown at the start and the end.
+    #[builder(default = 1)]
+    boundary_count:   usize,
+    // Only previous, current and next are shown.
+    #[builder(default = false)]
+    is_compact:       bool,
+    #[builder(default = false)]
+    has_jump_field:   bool,
+}
+
+pub struct Pagination {
+    bounds:     Bounds,
+    style:      PaginationStyle,
+    page_count: usize,
+    current:    usize,
+    items:      Vec<(PaginationItem, Bounds)>,
+    blocks:     Vec<Block<Positioned>>,
+}
+
+impl Pagination {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        page_count: usize,
+        style: PaginationStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let mut pagination = Self {
+            bounds,
+            style,
+            page_count,
+            current: 0,
+            items: Vec::new(),
+            blocks: Vec::new(),
+        };
+        pagination.build(view_projection, dpr, renderer);
+
+        pagination
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn current(&self) -> usize { self.current }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn page_count(&self) -> usize { self.page_count }
+
+    // Items and their bounds, for drawing page numbers and arrows.
+    #[must_use]
+    #[inline(always)]
+    pub fn items(&self) -> &[(PaginationItem, Bounds)] { &self.items }
+
+    #[must_use]
+    pub fn item_at(&self, position: [f32; 2]) -> Option<PaginationItem> {
+        self.items
+            .iter()
+            .find(|(_, bounds)| contains(bounds, position))
+            .map(|(item, _)| *item)
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self, item: PaginationItem) -> bool {
+        match item {
+            PaginationItem::Previous => self.current > 0,
+            PaginationItem::Next => self.current + 1 < self.page_count,
+            PaginationItem::Page(_) | PaginationItem::JumpTo => true,
+            PaginationItem::Ellipsis => false,
+        }
+    }
+
+    pub fn click(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<PageChange> {
+        let page = match self.item_at(position)? {
+            PaginationItem::Previous => self.current.checked_sub(1)?,
+            PaginationItem::Next => self.current + 1,
+            PaginationItem::Page(page) => page,
+            PaginationItem::Ellipsis | PaginationItem::JumpTo => return None,
+        };
+        self.go_to(page, view_projection, dpr, renderer)
+    }
+
+    // Takes the text of the jump-to field, with pages numbered from one.
+    pub fn jump_to(
+        &mut self,
+        text: &str,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<PageChange> {
+        let page = parse_jump_target(text, self.page_count)?;
+        self.go_to(page, view_projection, dpr, renderer)
+    }
+
+    pub fn go_to(
+        &mut self,
+        page: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<PageChange> {
+        if page >= self.page_count || page == self.current {
+            return None;
+        }
+        let change = PageChange { previous: self.current, current: page };
+        self.current = page;
+        self.rebuild(view_projection, dpr, renderer);
+
+        Some(change)
+    }
+
+    pub fn set_page_count(
+        &mut self,
+        page_count: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.page_count = page_count;
+        self.current = self.current.min(page_count.saturating_sub(1));
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: PaginationStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for block in &self.blocks {
+            block.destroy(renderer);
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.build(view_projection, dpr, renderer);
+    }
+
+    fn build(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let mut items = Vec::new();
+        items.push(PaginationItem::Previous);
+        match style.is_compact {
+            true if self.page_count > 0 => {
+                items.push(PaginationItem::Page(self.current));
+            }
+            true => {}
+            false => items.extend(page_items(
+                self.current,
+                self.page_count,
+                style.sibling_count,
+                style.boundary_count,
+            )),
+        }
+        items.push(PaginationItem::Next);
+        if style.has_jump_field {
+            items.push(PaginationItem::JumpTo);
+        }
+
+        let [mut x, y] = self.bounds.position;
+        self.items = items
+            .into_iter()
+            .map(|item| {
+                let width = match item {
+                    PaginationItem::JumpTo => style.jump_field_width,
+                    _ => style.item_size,
+                };
+                let bounds = Bounds {
+                    position:  [x, y],
+                    size:      [width, style.item_size],
+                    clip_rect: self.bounds.clip_rect,
+                };
+                x += width + style.spacing;
+                (item, bounds)
+            })
+            .collect();
+
+        let mut blocks = Vec::new();
+        for (item, bounds) in &self.items {
+            if *item == PaginationItem::Ellipsis {
+                blocks.extend(build_ellipsis(
+                    bounds,
+                    style,
+                    view_projection,
+                    dpr,
+                    renderer,
+                ));
+                continue;
+            }
+            let fill_color = match item {
+                PaginationItem::Page(page) if *page == self.current => {
+                    style.current_color
+                }
+                item if !self.is_enabled(*item) => style.disabled_color,
+                _ => style.item_color,
+            };
+            let block_style = BlockStyle::builder()
+                .fill_color(fill_color)
+                .corner_radii([style.corner_radius * dpr; 4])
+                .build();
+            blocks.push(Block::<Positioned>::new(
+                bounds.clone(),
+                block_style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        }
+        self.blocks = blocks;
+    }
+}
+
+fn build_ellipsis(
+    bounds: &Bounds,
+    style: &PaginationStyle,
+    view_projection: &Mat4,
+    dpr: f32,
+    renderer: &mut Renderer,
+) -> Vec<Block<Positioned>> {
+    let dot_size = (style.item_size / 10.0).max(2.0);
+    let center = [
+        bounds.position[0] + bounds.size[0] / 2.0,
+        bounds.position[1] + bounds.size[1] / 2.0,
+    ];
+    let block_style = BlockStyle::builder()
+        .fill_color(style.ellipsis_color)
+        .corner_radii([dot_size / 2.0 * dpr; 4])
+        .build();
+
+    [-1.0, 0.0, 1.0]
+        .into_iter()
+        .map(|offset| {
+            let dot_bounds = Bounds {
+                position:  [
+                    center[0] + offset * dot_size * 2.0 - dot_size / 2.0,
+                    center[1] - dot_size / 2.0,
+                ],
+                size:      [dot_size, dot_size],
+                clip_rect: bounds.clip_rect,
+            };
+            Block::<Positioned>::new(
+                dot_bounds,
+                block_style.clone(),
+                view_projection,
+                dpr,
+                renderer,
+            )
+        })
+        .collect()
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+// Pages to show, with runs of hidden pages collapsed into an ellipsis. A
+// gap of a single page shows that page instead, since an ellipsis would
+// take the same space.
+fn page_items(
+    current: usize,
+    page_count: usize,
+    sibling_count: usize,
+    boundary_count: usize,
+) -> Vec<PaginationItem> {
+    // One-based and signed, so the bounds below can go out of range.
+    let page = current as isize + 1;
+    let count = page_count as isize;
+    let siblings = sibling_count as isize;
+    let boundary = boundary_count as isize;
+
+    let start_pages = 1..=boundary.min(count);
+    let end_pages = (count - boundary + 1).max(boundary + 1)..=count;
+
+    let siblings_start = (page - siblings)
+        .min(count - boundary - siblings * 2 - 1)
+        .max(boundary + 2);
+    let siblings_end = (page + siblings).max(boundary + siblings * 2 + 2).min(
+        match end_pages.is_empty() {
+            true => count - 1,
+            false => *end_pages.start() - 2,
+        },
+    );
+
+    let mut pages = Vec::new();
+    pages.extend(start_pages.map(Some));
+    if siblings_start > boundary + 2 {
+        pages.push(None);
+    } else if boundary + 1 < count - boundary {
+        pages.push(Some(boundary + 1));
+    }
+    pages.extend((siblings_start..=siblings_end).map(Some));
+    if siblings_end < count - boundary - 1 {
+        pages.push(None);
+    } else if count - boundary > boundary {
+        pages.push(Some(count - boundary));
+    }
+    pages.extend(end_pages.map(Some));
+
+    pages
+        .into_iter()
+        .map(|page| match page {
+            Some(page) => PaginationItem::Page(page as usize - 1),
+            None => PaginationItem::Ellipsis,
+        })
+        .collect()
+}
+
+fn parse_jump_target(text: &str, page_count: usize) -> Option<usize> {
+    let page: usize = text.trim().parse().ok()?;
+    (page >= 1 && page <= page_count).then(|| page - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PaginationItem, page_items, parse_jump_target};
+
+    // Pages are written one-based, with 0 standing for an ellipsis.
+    #[rstest]
+    #[case(0, 10, &[1, 2, 3, 4, 5, 0, 10])]
+    #[case(4, 10, &[1, 0, 4, 5, 6, 0, 10])]
+    #[case(9, 10, &[1, 0, 6, 7, 8, 9, 10])]
+    #[case(2, 5, &[1, 2, 3, 4, 5])]
+    #[case(0, 7, &[1, 2, 3, 4, 5, 6, 7])]
+    #[case(0, 1, &[1])]
+    #[case(0, 0, &[])]
+    fn test_page_items(
+        #[case] current: usize,
+        #[case] page_count: usize,
+        #[case] expected: &[usize],
+    ) {
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|page| match page {
+                0 => PaginationItem::Ellipsis,
+                page => PaginationItem::Page(page - 1),
+            })
+            .collect();
+        assert_eq!(page_items(current, page_count, 1, 1), expected);
+    }
+
+    #[rstest]
+    #[case("3", Some(2))]
+    #[case(" 10 ", Some(9))]
+    #[case("11", None)]
+    #[case("0", None)]
+    #[case("abc", None)]
+    fn test_parse_jump_target(
+        #[case] text: &str,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(parse_jump_target(text, 10), expected);
+    }
+}
@@ -0,0 +1,554 @@
+use std::time::Duration;
+
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bounds, InputState, WidgetId, intersect_rects},
+    core::Renderer,
+};
+
+struct ScrollChild {
+    block:    Block<Positioned>,
+    // Relative to the top left of the content.
+    position: [f32; 2],
+}
+
+// Where to scroll a view to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollTarget {
+    Offset([f32; 2]),
+    // The child's top left at the view's, as far as the content allows.
+    Child(usize),
+}
+
+// Where a view was scrolled to, relative to the first tagged child in
+// view if there was one, so that a rebuild that moves rows keeps showing
+// the same ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollAnchor {
+    pub offset: [f32; 2],
+    // The child's tag and the offset from its top left.
+    pub child:  Option<(WidgetId, [f32; 2])>,
+}
+
+#[derive(Clone, Copy)]
+struct ScrollAnimation {
+    from:     [f32; 2],
+    to:       [f32; 2],
+    progress: f32,
+}
+
+// Blocks in a viewport that the mouse wheel scrolls, clipped to it. The
+// content is as large as needed to hold every child.
+pub struct ScrollView {
+    bounds:       Bounds,
+    children:     Vec<ScrollChild>,
+    content_size: [f32; 2],
+    offset:       [f32; 2],
+    duration:     Duration,
+    animation:    Option<ScrollAnimation>,
+}
+
+impl ScrollView {
+    #[must_use]
+    pub const fn new(bounds: Bounds) -> Self {
+        Self {
+            bounds,
+            children: Vec::new(),
+            content_size: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            duration: Duration::from_millis(200),
+            animation: None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn content_size(&self) -> [f32; 2] { self.content_size }
+
+    // How far the content is scrolled, from zero to `max_offset`.
+    #[must_use]
+    #[inline(always)]
+    pub const fn offset(&self) -> [f32; 2] { self.offset }
+
+    // Where the view is scrolling to, or its offset if it isn't.
+    #[must_use]
+    #[inline(always)]
+    pub fn target_offset(&self) -> [f32; 2] {
+        self.animation.map_or(self.offset, |animation| animation.to)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_animating(&self) -> bool { self.animation.is_some() }
+
+    // How long animated scrolls take.
+    #[inline(always)]
+    pub const fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn max_offset(&self) -> [f32; 2] {
+        max_offset(self.content_size, self.bounds.size)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.children.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.children.is_empty() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn child(&self, index: usize) -> Option<&Block<Positioned>> {
+        self.children.get(index).map(|child| &child.block)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn child_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<&mut Block<Positioned>> {
+        self.children.get_mut(index).map(|child| &mut child.block)
+    }
+
+    #[must_use]
+    pub fn child_with_tag(&self, tag: WidgetId) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|child| child.block.tag() == Some(tag))
+    }
+
+    // Where the view is scrolling to, for `restore_anchor` after its
+    // children were rebuilt.
+    #[must_use]
+    pub fn anchor(&self) -> ScrollAnchor {
+        let offset = self.target_offset();
+        let children = self.children.iter().map(|child| {
+            (child.block.tag(), child.position, child.block.size())
+        });
+        ScrollAnchor {
+            offset,
+            child: anchor_child(offset, self.bounds.size, children),
+        }
+    }
+
+    // Scrolls to the anchor's child, wherever it is now, or to its offset
+    // without one. Returns whether it scrolled.
+    pub fn restore_anchor(
+        &mut self,
+        anchor: ScrollAnchor,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let child = anchor.child.and_then(|(tag, delta)| {
+            let index = self.child_with_tag(tag)?;
+            let position = self.children[index].position;
+            Some([position[0] + delta[0], position[1] + delta[1]])
+        });
+        let offset = child.unwrap_or(anchor.offset);
+        self.scroll_to_offset(offset, false, view_projection, dpr, renderer)
+    }
+
+    // Adds a block at `position` in the content and returns its index.
+    pub fn add_child(
+        &mut self,
+        position: [f32; 2],
+        size: [f32; 2],
+        style: BlockStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> usize {
+        let bounds = Bounds {
+            position: self.screen_position(position),
+            size,
+            clip_rect: self.viewport(),
+        };
+        let block = Block::<Positioned>::new(
+            bounds,
+            style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.children.push(ScrollChild { block, position });
+        self.content_size = [
+            self.content_size[0].max(position[0] + size[0]),
+            self.content_size[1].max(position[1] + size[1]),
+        ];
+        self.children.len() - 1
+    }
+
+    // Destroys every child and scrolls back to the start.
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        for child in self.children.drain(..) {
+            child.block.destroy(renderer);
+        }
+        self.content_size = [0.0, 0.0];
+        self.offset = [0.0, 0.0];
+        self.animation = None;
+    }
+
+    #[inline(always)]
+    pub fn destroy(mut self, renderer: &mut Renderer) { self.clear(renderer); }
+
+    // Scrolls by the mouse wheel while the mouse is over the view and the
+    // event wasn't consumed yet, from wherever an animated scroll got to.
+    // Returns whether it scrolled; consume the event then, so that views
+    // around this one don't scroll too.
+    pub fn sync_input(
+        &mut self,
+        input_state: &InputState,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let [delta_x, delta_y] = input_state.scroll_delta();
+        if (delta_x == 0.0 && delta_y == 0.0) || input_state.is_consumed() {
+            return false;
+        }
+        let is_hovered = input_state
+            .mouse_position()
+            .is_some_and(|position| self.contains(position));
+        if !is_hovered {
+            return false;
+        }
+        self.animation = None;
+        let offset = [self.offset[0] - delta_x, self.offset[1] - delta_y];
+        self.set_offset(offset, view_projection, dpr, renderer)
+    }
+
+    // Clamps the target's offset to the content and scrolls there, over
+    // the view's duration if `is_animated`, with `tick` moving it. Returns
+    // whether it scrolled or started to; an unknown child is ignored.
+    pub fn scroll_to(
+        &mut self,
+        target: ScrollTarget,
+        is_animated: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let offset = match target {
+            ScrollTarget::Offset(offset) => offset,
+            ScrollTarget::Child(index) => match self.children.get(index) {
+                Some(child) => child.position,
+                None => return false,
+            },
+        };
+        self.scroll_to_offset(
+            offset,
+            is_animated,
+            view_projection,
+            dpr,
+            renderer,
+        )
+    }
+
+    // Scrolls as little as needed to show the whole child, or its top
+    // left if it's larger than the view, e.g. for the focused widget.
+    pub fn ensure_visible(
+        &mut self,
+        index: usize,
+        is_animated: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let Some(child) = self.children.get(index) else {
+            return false;
+        };
+        let offset = visible_offset(
+            self.target_offset(),
+            self.bounds.size,
+            child.position,
+            child.block.size(),
+        );
+        self.scroll_to_offset(
+            offset,
+            is_animated,
+            view_projection,
+            dpr,
+            renderer,
+        )
+    }
+
+    // Advances an animated scroll. Returns whether it scrolled.
+    pub fn tick(
+        &mut self,
+        dt: Duration,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let Some(mut animation) = self.animation else {
+            return false;
+        };
+        animation.progress = match self.duration.is_zero() {
+            true => 1.0,
+            false => {
+                let step = dt.as_secs_f32() / self.duration.as_secs_f32();
+                (animation.progress + step).min(1.0)
+            }
+        };
+        self.animation = (animation.progress < 1.0).then_some(animation);
+        let offset =
+            animated_offset(animation.from, animation.to, animation.progress);
+        self.set_offset(offset, view_projection, dpr, renderer)
+    }
+
+    fn scroll_to_offset(
+        &mut self,
+        offset: [f32; 2],
+        is_animated: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let offset = clamp_offset(offset, self.max_offset());
+        if !is_animated || self.duration.is_zero() {
+            self.animation = None;
+            return self.set_offset(offset, view_projection, dpr, renderer);
+        }
+        if offset == self.target_offset() {
+            return false;
+        }
+        self.animation = (offset != self.offset).then_some(ScrollAnimation {
+            from:     self.offset,
+            to:       offset,
+            progress: 0.0,
+        });
+        true
+    }
+
+    fn set_offset(
+        &mut self,
+        offset: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let offset = clamp_offset(offset, self.max_offset());
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        for index in 0..self.children.len() {
+            let position = self.screen_position(self.children[index].position);
+            self.children[index].block.update_position(
+                position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+        }
+        true
+    }
+
+    // Moves and resizes the view, keeping the offset where it still fits.
+    pub fn set_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.offset = clamp_offset(self.offset, self.max_offset());
+        let max_offset = self.max_offset();
+        if let Some(animation) = &mut self.animation {
+            animation.to = clamp_offset(animation.to, max_offset);
+        }
+        let viewport = self.viewport();
+        for index in 0..self.children.len() {
+            let position = self.screen_position(self.children[index].position);
+            let block = &mut self.children[index].block;
+            block.update_position(position, view_projection, dpr, renderer);
+            block.update_clip_rect(&viewport, renderer);
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, position: [f32; 2]) -> bool {
+        let [x, y, width, height] = self.viewport();
+        (x..x + width).contains(&position[0])
+            && (y..y + height).contains(&position[1])
+    }
+
+    // The topmost child at `position`, leaving out what's scrolled out
+    // of view.
+    #[must_use]
+    pub fn child_at(&self, position: [f32; 2]) -> Option<usize> {
+        if !self.contains(position) {
+            return None;
+        }
+        self.children
+            .iter()
+            .rposition(|child| child.block.contains(position))
+    }
+
+    // The view's rect within its own clip rect.
+    fn viewport(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        intersect_rects([x, y, width, height], self.bounds.clip_rect)
+    }
+
+    fn screen_position(&self, position: [f32; 2]) -> [f32; 2] {
+        [
+            self.bounds.position[0] + position[0] - self.offset[0],
+            self.bounds.position[1] + position[1] - self.offset[1],
+        ]
+    }
+}
+
+#[inline(always)]
+fn max_offset(content_size: [f32; 2], size: [f32; 2]) -> [f32; 2] {
+    [
+        (content_size[0] - size[0]).max(0.0),
+        (content_size[1] - size[1]).max(0.0),
+    ]
+}
+
+#[inline(always)]
+fn clamp_offset(offset: [f32; 2], max_offset: [f32; 2]) -> [f32; 2] {
+    [
+        offset[0].clamp(0.0, max_offset[0]),
+        offset[1].clamp(0.0, max_offset[1]),
+    ]
+}
+
+// The offset closest to `offset` that shows the child whole, or its top
+// left if it's larger than the view.
+fn visible_offset(
+    offset: [f32; 2],
+    view_size: [f32; 2],
+    position: [f32; 2],
+    size: [f32; 2],
+) -> [f32; 2] {
+    let mut offset = offset;
+    for axis in 0..2 {
+        let end = position[axis] + size[axis];
+        if end > offset[axis] + view_size[axis] {
+            offset[axis] = end - view_size[axis];
+        }
+        offset[axis] = offset[axis].min(position[axis]);
+    }
+    offset
+}
+
+// The first tagged child that overlaps the view at `offset`, and the
+// offset from its top left.
+fn anchor_child(
+    offset: [f32; 2],
+    view_size: [f32; 2],
+    children: impl Iterator<Item = (Option<WidgetId>, [f32; 2], [f32; 2])>,
+) -> Option<(WidgetId, [f32; 2])> {
+    children
+        .filter(|(_, position, size)| {
+            (0..2).all(|axis| {
+                position[axis] + size[axis] > offset[axis]
+                    && position[axis] < offset[axis] + view_size[axis]
+            })
+        })
+        .find_map(|(tag, position, _)| {
+            let delta = [offset[0] - position[0], offset[1] - position[1]];
+            tag.map(|tag| (tag, delta))
+        })
+}
+
+// Eases out: starts fast, so the scroll answers right away, and settles.
+fn animated_offset(from: [f32; 2], to: [f32; 2], progress: f32) -> [f32; 2] {
+    let eased = 1.0 - (1.0 - progress).powi(3);
+    [
+        from[0] + (to[0] - from[0]) * eased,
+        from[1] + (to[1] - from[1]) * eased,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        anchor_child, animated_offset, clamp_offset, max_offset,
+        visible_offset,
+    };
+
+    #[rstest]
+    #[case([0.0, 50.0], [100.0, 400.0], [0.0, 50.0])]
+    #[case([-10.0, 500.0], [100.0, 400.0], [0.0, 300.0])]
+    // Content smaller than the view doesn't scroll.
+    #[case([0.0, 20.0], [80.0, 50.0], [0.0, 0.0])]
+    fn test_clamp_offset(
+        #[case] offset: [f32; 2],
+        #[case] content_size: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let max_offset = max_offset(content_size, [100.0, 100.0]);
+        assert_eq!(clamp_offset(offset, max_offset), expected);
+    }
+
+    #[rstest]
+    // Already visible.
+    #[case([0.0, 100.0], [0.0, 150.0], [50.0, 20.0], [0.0, 100.0])]
+    // Below the view, above it.
+    #[case([0.0, 0.0], [0.0, 250.0], [50.0, 20.0], [0.0, 170.0])]
+    #[case([0.0, 300.0], [0.0, 40.0], [50.0, 20.0], [0.0, 40.0])]
+    // Taller than the view.
+    #[case([0.0, 0.0], [0.0, 300.0], [50.0, 500.0], [0.0, 300.0])]
+    fn test_visible_offset(
+        #[case] offset: [f32; 2],
+        #[case] position: [f32; 2],
+        #[case] size: [f32; 2],
+        #[case] expected: [f32; 2],
+    ) {
+        let view_size = [100.0, 100.0];
+        assert_eq!(
+            visible_offset(offset, view_size, position, size),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0.0, [0.0, 100.0])]
+    #[case(0.5, [0.0, 187.5])]
+    #[case(1.0, [0.0, 200.0])]
+    fn test_animated_offset(
+        #[case] progress: f32,
+        #[case] expected: [f32; 2],
+    ) {
+        assert_eq!(
+            animated_offset([0.0, 100.0], [0.0, 200.0], progress),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_anchor_child() {
+        let children = [
+            (Some(1), [0.0, 0.0], [100.0, 50.0]),
+            (None, [0.0, 50.0], [100.0, 50.0]),
+            (Some(3), [0.0, 100.0], [100.0, 50.0]),
+        ];
+        let anchor = |offset| {
+            anchor_child(offset, [100.0, 100.0], children.into_iter())
+        };
+        assert_eq!(anchor([0.0, 20.0]), Some((1, [0.0, 20.0])));
+        // The untagged row in view is skipped.
+        assert_eq!(anchor([0.0, 60.0]), Some((3, [0.0, -40.0])));
+        assert_eq!(anchor([0.0, 150.0]), None);
+    }
+}
@@ -0,0 +1,355 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::{
+        common::{Bar, Bounds},
+        text::{FuzzyMatch, TextEditState, fuzzy_filter},
+    },
+    core::{RectangleId, Renderer},
+    format::{localize, messages},
+};
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct SearchFieldStyle {
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    background_color: [f32; 4],
+    #[builder(default = [0.75, 0.75, 0.75, 1.0])]
+    border_color:     [f32; 4],
+    #[builder(default = 1.0)]
+    border_size:      f32,
+    #[builder(default = [0.45, 0.45, 0.45, 1.0])]
+    glyph_color:      [f32; 4],
+    #[builder(default = 1.5)]
+    glyph_thickness:  f32,
+    #[builder(default = 12.0)]
+    glyph_size:       f32,
+    #[builder(default = 8.0)]
+    padding:          f32,
+    #[builder(default = 4.0)]
+    corner_radius:    f32,
+}
+
+pub struct SearchField {
+    bounds:     Bounds,
+    style:      SearchFieldStyle,
+    state:      TextEditState,
+    background: Block<Positioned>,
+    lens:       Block<Positioned>,
+    glyphs:     Vec<RectangleId>,
+}
+
+impl SearchField {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        style: SearchFieldStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            background_style(&style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let lens = Block::<Positioned>::new(
+            lens_bounds(&bounds, &style),
+            lens_style(&style, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut search_field = Self {
+            bounds,
+            style,
+            state: TextEditState::default(),
+            background,
+            lens,
+            glyphs: Vec::new(),
+        };
+        search_field.build_glyphs(view_projection, dpr, renderer);
+
+        search_field
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn state(&self) -> &TextEditState { &self.state }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn query(&self) -> &str { self.state.text() }
+
+    // Between the search glyph and the clear button, where the caller
+    // draws the query.
+    #[must_use]
+    pub fn text_bounds(&self) -> Bounds {
+        let style = &self.style;
+        let inset = style.padding * 2.0 + style.glyph_size;
+        Bounds {
+            position:  [
+                self.bounds.position[0] + inset,
+                self.bounds.position[1],
+            ],
+            size:      [
+                (self.bounds.size[0] - inset * 2.0).max(0.0),
+                self.bounds.size[1],
+            ],
+            clip_rect: self.bounds.clip_rect,
+        }
+    }
+
+    // Only there while the query isn't empty.
+    #[must_use]
+    pub fn clear_button_bounds(&self) -> Option<Bounds> {
+        (!self.query().is_empty())
+            .then(|| clear_button_bounds(&self.bounds, &self.style))
+    }
+
+    #[must_use]
+    pub fn clear_button_tooltip(&self) -> String {
+        localize(messages::CLEAR, &[])
+    }
+
+    // The candidates matching the query, best first, with the match
+    // positions for `MatchHighlights`.
+    #[must_use]
+    pub fn filter<I, S>(&self, candidates: I) -> Vec<(usize, FuzzyMatch)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        fuzzy_filter(self.query(), candidates)
+    }
+
+    // Edits the query. Returns whether it changed.
+    pub fn edit(
+        &mut self,
+        edit: impl FnOnce(&mut TextEditState),
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let was_empty = self.query().is_empty();
+        let previous_query = self.query().to_owned();
+        edit(&mut self.state);
+        if self.query().is_empty() != was_empty {
+            self.rebuild_glyphs(view_projection, dpr, renderer);
+        }
+
+        self.query() != previous_query
+    }
+
+    // Clears the query when the clear button is clicked. Returns whether
+    // it was.
+    pub fn click(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_clear_clicked = self
+            .clear_button_bounds()
+            .is_some_and(|bounds| contains(&bounds, position));
+        if is_clear_clicked {
+            self.edit(
+                |state| state.set_text(""),
+                view_projection,
+                dpr,
+                renderer,
+            );
+        }
+
+        is_clear_clicked
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        let lens_bounds = lens_bounds(&self.bounds, &self.style);
+        for (block, bounds) in [
+            (&mut self.background, &self.bounds),
+            (&mut self.lens, &lens_bounds),
+        ] {
+            block.update_clip_rect(&bounds.clip_rect, renderer);
+            block.update_position(
+                bounds.position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            block.update_size(bounds.size, view_projection, dpr, renderer);
+        }
+        self.rebuild_glyphs(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: SearchFieldStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background
+            .update_style(background_style(&self.style, dpr), renderer);
+        self.lens
+            .update_style(lens_style(&self.style, dpr), renderer);
+        self.update_bounds(
+            self.bounds.clone(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        self.lens.destroy(renderer);
+        self.destroy_glyphs(renderer);
+    }
+
+    fn destroy_glyphs(&self, renderer: &mut Renderer) {
+        for rectangle_id in &self.glyphs {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+    }
+
+    fn rebuild_glyphs(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy_glyphs(renderer);
+        self.build_glyphs(view_projection, dpr, renderer);
+    }
+
+    // The magnifier's handle, and the cross of the clear button.
+    fn build_glyphs(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let thickness = style.glyph_thickness;
+        let lens = lens_bounds(&self.bounds, style);
+        let radius = lens.size[0] / 2.0;
+        let lens_center =
+            [lens.position[0] + radius, lens.position[1] + radius];
+        let handle_start = [
+            lens_center[0] + radius * FRAC_1_SQRT_2,
+            lens_center[1] + radius * FRAC_1_SQRT_2,
+        ];
+        let handle_length = style.glyph_size * 0.3;
+        let handle_end = [
+            handle_start[0] + handle_length,
+            handle_start[1] + handle_length,
+        ];
+        let mut bars = vec![Bar::between(handle_start, handle_end, thickness)];
+
+        if let Some(bounds) = self.clear_button_bounds() {
+            let half = style.glyph_size * 0.3;
+            let [x, y] = [
+                bounds.position[0] + bounds.size[0] / 2.0,
+                bounds.position[1] + bounds.size[1] / 2.0,
+            ];
+            bars.push(Bar::between(
+                [x - half, y - half],
+                [x + half, y + half],
+                thickness,
+            ));
+            bars.push(Bar::between(
+                [x - half, y + half],
+                [x + half, y - half],
+                thickness,
+            ));
+        }
+
+        self.glyphs = bars
+            .iter()
+            .map(|bar| {
+                let rectangle = bar.build_rectangle(
+                    style.glyph_color,
+                    self.bounds.clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                renderer.add_rectangle(&rectangle)
+            })
+            .collect();
+    }
+}
+
+fn background_style(style: &SearchFieldStyle, dpr: f32) -> BlockStyle {
+    BlockStyle::builder()
+        .fill_color(style.background_color)
+        .border_color(style.border_color)
+        .border_size(style.border_size * dpr)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+// The magnifier's lens is a ring; its handle leaves room to the bottom
+// right, so the ring takes up the top-left part of the glyph square.
+fn lens_style(style: &SearchFieldStyle, dpr: f32) -> BlockStyle {
+    let diameter = style.glyph_size * 0.7;
+    BlockStyle::builder()
+        .fill_color([0.0; 4])
+        .border_color(style.glyph_color)
+        .border_size(style.glyph_thickness * dpr)
+        .corner_radii([diameter / 2.0 * dpr; 4])
+        .build()
+}
+
+fn lens_bounds(bounds: &Bounds, style: &SearchFieldStyle) -> Bounds {
+    let diameter = style.glyph_size * 0.7;
+    Bounds {
+        position:  [
+            bounds.position[0] + style.padding,
+            bounds.position[1] + (bounds.size[1] - style.glyph_size) / 2.0,
+        ],
+        size:      [diameter, diameter],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn clear_button_bounds(bounds: &Bounds, style: &SearchFieldStyle) -> Bounds {
+    let size = style.glyph_size + style.padding;
+    Bounds {
+        position:  [
+            bounds.position[0] + bounds.size[0] - size - style.padding / 2.0,
+            bounds.position[1] + (bounds.size[1] - size) / 2.0,
+        ],
+        size:      [size, size],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{BlockStyle, block::build_rectangle};
+use crate::{
+    components::common::{Bar, Bounds},
+    core::{Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparklineKind {
+    Line,
+    Bars,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct SparklineStyle {
+    #[builder(default = SparklineKind::Line)]
+    kind:      SparklineKind,
+    #[builder(default = [0.35, 0.65, 1.0, 1.0])]
+    color:     [f32; 4],
+    #[builder(default = 1.5)]
+    thickness: f32,
+    #[builder(default = 1.0)]
+    bar_gap:   f32,
+}
+
+// A tiny chart without axes for table cells and labels. The last
+// `capacity` values are kept in a ring; pushing one only builds the new
+// tail segment while the value range holds, and once the ring is full
+// the older segments are shifted by rewriting their instances in place.
+pub struct Sparkline {
+    bounds:      Bounds,
+    style:       SparklineStyle,
+    capacity:    usize,
+    values:      VecDeque<f32>,
+    // Fixed by the caller, or else the range of the values.
+    fixed_range: Option<[f32; 2]>,
+    range:       [f32; 2],
+    segments:    Vec<RectangleId>,
+}
+
+impl Sparkline {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        capacity: usize,
+        style: SparklineStyle,
+    ) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            bounds,
+            style,
+            capacity,
+            values: VecDeque::with_capacity(capacity),
+            fixed_range: None,
+            range: [0.0, 0.0],
+            segments: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize { self.capacity }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    #[must_use]
+    pub fn last(&self) -> Option<f32> { self.values.back().copied() }
+
+    pub fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.values.iter().copied()
+    }
+
+    pub fn push(
+        &mut self,
+        value: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let is_full = self.values.len() == self.capacity;
+        if is_full {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+
+        let range =
+            self.fixed_range.unwrap_or_else(|| auto_range(&self.values));
+        let first_changed = match is_full || range != self.range {
+            true => 0,
+            false => self.segments.len(),
+        };
+        self.range = range;
+        self.sync(first_changed, view_projection, dpr, renderer);
+    }
+
+    pub fn extend(
+        &mut self,
+        values: impl IntoIterator<Item = f32>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.values.extend(values);
+        let excess = self.values.len().saturating_sub(self.capacity);
+        self.values.drain(..excess);
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        self.values.clear();
+        for rectangle_id in self.segments.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+    }
+
+    // `None` fits the range to the values.
+    pub fn set_range(
+        &mut self,
+        range: Option<[f32; 2]>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.fixed_range = range;
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.sync(0, view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: SparklineStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        // Lines and bars have different segment counts.
+        if style.kind != self.style.kind {
+            for rectangle_id in self.segments.drain(..) {
+                renderer.remove_rectangle(rectangle_id);
+            }
+        }
+        self.style = style;
+        self.sync(0, view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for rectangle_id in &self.segments {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+    }
+
+    fn refresh(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.range =
+            self.fixed_range.unwrap_or_else(|| auto_range(&self.values));
+        self.sync(0, view_projection, dpr, renderer);
+    }
+
+    // Rewrites the segments from `first` on, adding missing ones and
+    // removing extra ones.
+    fn sync(
+        &mut self,
+        first: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let count = match self.style.kind {
+            SparklineKind::Line => self.values.len().saturating_sub(1),
+            SparklineKind::Bars => self.values.len(),
+        };
+        for rectangle_id in
+            self.segments.drain(count.min(self.segments.len())..)
+        {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        for index in first..count {
+            let rectangle = self.build_segment(index, view_projection, dpr);
+            match self.segments.get(index) {
+                Some(rectangle_id) => {
+                    if let Some(instance) =
+                        renderer.get_mut_rectangle(*rectangle_id)
+                    {
+                        *instance = rectangle;
+                    }
+                }
+                None => self.segments.push(renderer.add_rectangle(&rectangle)),
+            }
+        }
+    }
+
+    fn build_segment(
+        &self,
+        index: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let point = |index: usize| {
+            point_position(
+                &self.bounds,
+                self.capacity,
+                index,
+                self.values[index],
+                self.range,
+            )
+        };
+        match self.style.kind {
+            SparklineKind::Line => Bar::between(
+                point(index),
+                point(index + 1),
+                self.style.thickness,
+            )
+            .build_rectangle(
+                self.style.color,
+                self.bounds.clip_rect,
+                view_projection,
+                dpr,
+            ),
+            SparklineKind::Bars => {
+                let [x, y, width, height] = bar_rect(
+                    &self.bounds,
+                    self.capacity,
+                    index,
+                    self.values[index],
+                    self.range,
+                    self.style.bar_gap,
+                );
+                let bounds = Bounds {
+                    position:  [x, y],
+                    size:      [width, height],
+                    clip_rect: self.bounds.clip_rect,
+                };
+                let style =
+                    BlockStyle::builder().fill_color(self.style.color).build();
+                build_rectangle(view_projection, dpr, &bounds, &style)
+            }
+        }
+    }
+}
+
+fn auto_range(values: &VecDeque<f32>) -> [f32; 2] {
+    let range = values
+        .iter()
+        .fold([f32::INFINITY, f32::NEG_INFINITY], |[min, max], value| {
+            [min.min(*value), max.max(*value)]
+        });
+    match range[0] <= range[1] {
+        true => range,
+        false => [0.0, 0.0],
+    }
+}
+
+// 0 at the bottom, 1 at the top; a flat range sits in the middle.
+fn normalize(value: f32, range: [f32; 2]) -> f32 {
+    let span = range[1] - range[0];
+    match span > 0.0 {
+        true => ((value - range[0]) / span).clamp(0.0, 1.0),
+        false => 0.5,
+    }
+}
+
+// Points are spread over the full width once the ring is full, so the
+// newest one is always at the right edge then.
+fn point_position(
+    bounds: &Bounds,
+    capacity: usize,
+    index: usize,
+    value: f32,
+    range: [f32; 2],
+) -> [f32; 2] {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let step = width / (capacity.max(2) - 1) as f32;
+    [
+        x + index as f32 * step,
+        y + height * (1.0 - normalize(value, range)),
+    ]
+}
+
+// Bars grow from zero when the range spans it, or else from the bottom.
+fn bar_rect(
+    bounds: &Bounds,
+    capacity: usize,
+    index: usize,
+    value: f32,
+    range: [f32; 2],
+    gap: f32,
+) -> [f32; 4] {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let slot = width / capacity.max(1) as f32;
+    let base = match range[0] <= 0.0 && range[1] > 0.0 {
+        true => normalize(0.0, range),
+        false => 0.0,
+    };
+    let top = normalize(value, range).max(base);
+    let bottom = normalize(value, range).min(base);
+    [
+        x + index as f32 * slot + gap.min(slot) / 2.0,
+        y + height * (1.0 - top),
+        (slot - gap).max(0.0),
+        height * (top - bottom),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rstest::rstest;
+
+    use super::{auto_range, bar_rect, point_position};
+    use crate::components::common::Bounds;
+
+    fn bounds() -> Bounds {
+        Bounds {
+            position:  [10.0, 0.0],
+            size:      [90.0, 20.0],
+            clip_rect: [0.0, 0.0, f32::MAX, f32::MAX],
+        }
+    }
+
+    #[rstest]
+    #[case(vec![3.0, -1.0, 5.0], [-1.0, 5.0])]
+    #[case(vec![], [0.0, 0.0])]
+    fn test_auto_range(#[case] values: Vec<f32>, #[case] expected: [f32; 2]) {
+        assert_eq!(auto_range(&VecDeque::from(values)), expected);
+    }
+
+    #[rstest]
+    #[case(0, 0.0, [10.0, 20.0])]
+    #[case(3, 10.0, [40.0, 0.0])]
+    #[case(9, 5.0, [100.0, 10.0])]
+    fn test_point_position(
+        #[case] index: usize,
+        #[case] value: f32,
+        #[case] expected: [f32; 2],
+    ) {
+        assert_eq!(
+            point_position(&bounds(), 10, index, value, [0.0, 10.0]),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0, 5.0, [0.0, 10.0], [11.0, 10.0, 7.0, 10.0])]
+    // Negative values hang from zero.
+    #[case(1, -5.0, [-10.0, 10.0], [20.0, 10.0, 7.0, 5.0])]
+    fn test_bar_rect(
+        #[case] index: usize,
+        #[case] value: f32,
+        #[case] range: [f32; 2],
+        #[case] expected: [f32; 4],
+    ) {
+        assert_eq!(
+            bar_rect(&bounds(), 10, index, value, range, 2.0),
+            expected
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use glam::Mat4;
+
+use super::ColorScale;
+use crate::{
+    components::common::{Bounds, build_model},
+    core::{Image, ImageId, Renderer, TextureId},
+};
+
+// A strip of columns scrolling in from the right, e.g. an audio
+// spectrogram or a rolling telemetry heatmap. The columns live in a
+// texture used as a ring: pushing one writes only that texel column, and
+// the image's horizontal UV offset rotates the ring so the oldest column
+// is at the left edge. The values are kept so that a new color scale or
+// range can recolor the history.
+pub struct Spectrogram {
+    bounds:      Bounds,
+    // Values map from range[0] at the bottom of the scale to range[1].
+    range:       [f32; 2],
+    color_scale: ColorScale,
+    // Columns in the ring, and values per column.
+    size:        [u32; 2],
+    values:      Vec<f32>,
+    // Where the next column goes.
+    head:        u32,
+    texture_id:  TextureId,
+    image_id:    Option<ImageId>,
+}
+
+impl Spectrogram {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bounds: Bounds,
+        columns: u32,
+        bins: u32,
+        range: [f32; 2],
+        color_scale: ColorScale,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let size = [columns.max(1), bins.max(1)];
+        let values = vec![range[0]; (size[0] * size[1]) as usize];
+        let rgba = rasterize(&values, size, range, &color_scale);
+        let texture_id = renderer.add_texture(size, &rgba);
+
+        let mut spectrogram = Self {
+            bounds,
+            range,
+            color_scale,
+            size,
+            values,
+            head: 0,
+            texture_id,
+            image_id: None,
+        };
+        let image = spectrogram.build_image(view_projection, dpr);
+        spectrogram.image_id = renderer.add_image(texture_id, &image);
+
+        spectrogram
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn range(&self) -> [f32; 2] { self.range }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn columns(&self) -> u32 { self.size[0] }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bins(&self) -> u32 { self.size[1] }
+
+    // Appends a column of values, the first bin at the bottom. Missing
+    // bins are treated as the bottom of the range, extra ones dropped.
+    pub fn push_column(&mut self, column: &[f32], renderer: &mut Renderer) {
+        let [columns, bins] = self.size;
+        let head = self.head as usize;
+        for row in 0..bins as usize {
+            self.values[row * columns as usize + head] =
+                column.get(row).copied().unwrap_or(self.range[0]);
+        }
+        let rgba = rasterize_column(
+            &self.values,
+            self.size,
+            head,
+            self.range,
+            &self.color_scale,
+        );
+        renderer.write_texture(
+            self.texture_id,
+            [self.head, 0],
+            [1, bins],
+            &rgba,
+        );
+
+        self.head = (self.head + 1) % columns;
+        if let Some(image) = self
+            .image_id
+            .and_then(|image_id| renderer.get_mut_image(image_id))
+        {
+            image.uv_transform[2] = ring_offset(self.head, columns);
+        }
+    }
+
+    pub fn set_color_scale(
+        &mut self,
+        color_scale: ColorScale,
+        renderer: &mut Renderer,
+    ) {
+        self.color_scale = color_scale;
+        self.recolor(renderer);
+    }
+
+    pub fn set_range(&mut self, range: [f32; 2], renderer: &mut Renderer) {
+        self.range = range;
+        self.recolor(renderer);
+    }
+
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        self.values.fill(self.range[0]);
+        self.head = 0;
+        self.recolor(renderer);
+        if let Some(image) = self
+            .image_id
+            .and_then(|image_id| renderer.get_mut_image(image_id))
+        {
+            image.uv_transform[2] = 0.0;
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        let image = self.build_image(view_projection, dpr);
+        if let Some(instance) = self
+            .image_id
+            .and_then(|image_id| renderer.get_mut_image(image_id))
+        {
+            *instance = image;
+        }
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        if let Some(image_id) = self.image_id {
+            renderer.remove_image(image_id);
+        }
+        renderer.remove_texture(self.texture_id);
+    }
+
+    fn recolor(&mut self, renderer: &mut Renderer) {
+        let rgba =
+            rasterize(&self.values, self.size, self.range, &self.color_scale);
+        renderer.write_texture(self.texture_id, [0, 0], self.size, &rgba);
+    }
+
+    fn build_image(&self, view_projection: &Mat4, dpr: f32) -> Image {
+        let (model, half_size) =
+            build_model(self.bounds.size, self.bounds.position, dpr);
+        let mvp = view_projection * model;
+        Image::builder()
+            .mvp(mvp.to_cols_array_2d())
+            .tint_color([1.0; 4])
+            .corner_radii([0.0; 4])
+            .clip_rect(self.bounds.clip_rect.map(|value| value * dpr))
+            .uv_transform([
+                1.0,
+                1.0,
+                ring_offset(self.head, self.size[0]),
+                0.0,
+            ])
+            // Repeating horizontally wraps the ring around.
+            .repeat_and_size([1.0, 0.0, half_size[0], half_size[1]])
+            .cutout([0.0; 4])
+            .build()
+    }
+}
+
+// Values are stored row by row with the first bin in row 0, while the
+// texture's first row is its top, so rows are flipped on the way out.
+fn rasterize(
+    values: &[f32],
+    size: [u32; 2],
+    range: [f32; 2],
+    color_scale: &ColorScale,
+) -> Vec<u8> {
+    let [columns, bins] = size.map(|size| size as usize);
+    (0..bins)
+        .rev()
+        .flat_map(|row| &values[row * columns..(row + 1) * columns])
+        .flat_map(|value| to_rgba(*value, range, color_scale))
+        .collect()
+}
+
+fn rasterize_column(
+    values: &[f32],
+    size: [u32; 2],
+    column: usize,
+    range: [f32; 2],
+    color_scale: &ColorScale,
+) -> Vec<u8> {
+    let [columns, bins] = size.map(|size| size as usize);
+    (0..bins)
+        .rev()
+        .flat_map(|row| {
+            to_rgba(values[row * columns + column], range, color_scale)
+        })
+        .collect()
+}
+
+fn to_rgba(value: f32, range: [f32; 2], color_scale: &ColorScale) -> [u8; 4] {
+    let span = range[1] - range[0];
+    let t = match span != 0.0 {
+        true => (value - range[0]) / span,
+        false => 0.0,
+    };
+    color_scale
+        .color_at(t)
+        .map(|value| (value * 255.0).round().clamp(0.0, 255.0) as u8)
+}
+
+// The oldest column is the one the next push overwrites.
+fn ring_offset(head: u32, columns: u32) -> f32 { head as f32 / columns as f32 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ColorScale, rasterize, rasterize_column, ring_offset};
+
+    fn scale() -> ColorScale {
+        ColorScale::new(vec![[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]])
+    }
+
+    #[rstest]
+    fn test_rasterize_flips_rows() {
+        // Two columns of two bins, the first bin in the first row.
+        let values = [0.0, 1.0, 0.5, 0.0];
+        let rgba = rasterize(&values, [2, 2], [0.0, 1.0], &scale());
+        let reds: Vec<u8> = rgba.chunks(4).map(|pixel| pixel[0]).collect();
+        assert_eq!(reds, vec![128, 0, 0, 255]);
+    }
+
+    // The columns of `test_rasterize_flips_rows`.
+    #[rstest]
+    #[case(0, [128, 128, 128, 255, 0, 0, 0, 255])]
+    #[case(1, [0, 0, 0, 255, 255, 255, 255, 255])]
+    fn test_rasterize_column(
+        #[case] column: usize,
+        #[case] expected: [u8; 8],
+    ) {
+        let values = [0.0, 1.0, 0.5, 0.0];
+        let rgba =
+            rasterize_column(&values, [2, 2], column, [0.0, 1.0], &scale());
+        assert_eq!(rgba, expected);
+    }
+
+    #[rstest]
+    #[case(0, 0.0)]
+    #[case(3, 0.75)]
+    fn test_ring_offset(#[case] head: u32, #[case] expected: f32) {
+        assert_eq!(ring_offset(head, 4), expected);
+    }
+}
@@ -0,0 +1,220 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{components::common::Bounds, core::Renderer};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusBarZone {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct StatusBarStyle {
+    #[builder(default = [0.95, 0.95, 0.95, 1.0])]
+    background_color: [f32; 4],
+    #[builder(default = [0.82, 0.82, 0.82, 1.0])]
+    border_color:     [f32; 4],
+    #[builder(default = 1.0)]
+    border_size:      f32,
+    #[builder(default = 8.0)]
+    padding:          f32,
+    #[builder(default = 12.0)]
+    spacing:          f32,
+}
+
+pub struct StatusBar {
+    bounds:     Bounds,
+    style:      StatusBarStyle,
+    // Item widths per zone; the caller draws the items themselves.
+    widths:     [Vec<f32>; 3],
+    items:      [Vec<Bounds>; 3],
+    background: Block<Positioned>,
+    border:     Block<Positioned>,
+}
+
+impl StatusBar {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        style: StatusBarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background_style = BlockStyle::builder()
+            .fill_color(style.background_color)
+            .build();
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            background_style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let border_style =
+            BlockStyle::builder().fill_color(style.border_color).build();
+        let border = Block::<Positioned>::new(
+            border_bounds(&bounds, style.border_size),
+            border_style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+
+        Self {
+            bounds,
+            style,
+            widths: Default::default(),
+            items: Default::default(),
+            background,
+            border,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn items(&self, zone: StatusBarZone) -> &[Bounds] {
+        &self.items[zone as usize]
+    }
+
+    pub fn set_items(&mut self, zone: StatusBarZone, widths: Vec<f32>) {
+        self.widths[zone as usize] = widths;
+        self.layout_zone(zone);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        for block in [&mut self.background, &mut self.border] {
+            block.update_clip_rect(&self.bounds.clip_rect, renderer);
+        }
+        self.background.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let border_bounds =
+            border_bounds(&self.bounds, self.style.border_size);
+        self.border.update_position(
+            border_bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.border.update_size(
+            border_bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        for zone in [
+            StatusBarZone::Left,
+            StatusBarZone::Center,
+            StatusBarZone::Right,
+        ] {
+            self.layout_zone(zone);
+        }
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        self.border.destroy(renderer);
+    }
+
+    fn layout_zone(&mut self, zone: StatusBarZone) {
+        self.items[zone as usize] = layout_zone(
+            &self.bounds,
+            &self.style,
+            zone,
+            &self.widths[zone as usize],
+        );
+    }
+}
+
+// A hairline along the top edge, separating the bar from the content.
+fn border_bounds(bounds: &Bounds, border_size: f32) -> Bounds {
+    Bounds {
+        position:  bounds.position,
+        size:      [bounds.size[0], border_size],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn layout_zone(
+    bounds: &Bounds,
+    style: &StatusBarStyle,
+    zone: StatusBarZone,
+    widths: &[f32],
+) -> Vec<Bounds> {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let total = widths.iter().sum::<f32>()
+        + style.spacing * widths.len().saturating_sub(1) as f32;
+
+    let mut item_x = match zone {
+        StatusBarZone::Left => x + style.padding,
+        StatusBarZone::Center => x + (width - total) / 2.0,
+        StatusBarZone::Right => x + width - style.padding - total,
+    };
+    widths
+        .iter()
+        .map(|item_width| {
+            let bounds = Bounds {
+                position:  [item_x, y + style.border_size],
+                size:      [*item_width, height - style.border_size],
+                clip_rect: bounds.clip_rect,
+            };
+            item_x += item_width + style.spacing;
+            bounds
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{StatusBarStyle, StatusBarZone, layout_zone};
+    use crate::Bounds;
+
+    #[rstest]
+    #[case(StatusBarZone::Left, &[10.0, 60.0])]
+    #[case(StatusBarZone::Center, &[140.0, 190.0])]
+    #[case(StatusBarZone::Right, &[270.0, 320.0])]
+    fn test_layout_zone(
+        #[case] zone: StatusBarZone,
+        #[case] expected: &[f32],
+    ) {
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [400.0, 24.0]);
+        let style = StatusBarStyle::builder()
+            .padding(10.0)
+            .spacing(10.0)
+            .border_size(0.0)
+            .build();
+
+        let items = layout_zone(&bounds, &style, zone, &[40.0, 70.0]);
+        let positions: Vec<f32> =
+            items.iter().map(|item| item.position[0]).collect();
+        assert_eq!(positions, expected);
+    }
+}
@@ -0,0 +1,374 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{components::common::Bounds, core::Renderer};
+
+// Returns whether the given step is complete and may be left forward.
+pub type StepValidator = dyn Fn(usize) -> bool;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepState {
+    Completed,
+    Current,
+    Upcoming,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct StepperStyle {
+    #[builder(default = [0.18, 0.65, 0.35, 1.0])]
+    completed_color:     [f32; 4],
+    #[builder(default = [0.20, 0.45, 0.90, 1.0])]
+    current_color:       [f32; 4],
+    #[builder(default = [0.75, 0.75, 0.75, 1.0])]
+    upcoming_color:      [f32; 4],
+    #[builder(default = 28.0)]
+    step_size:           f32,
+    #[builder(default = 2.0)]
+    upcoming_border:     f32,
+    #[builder(default = 2.0)]
+    connector_thickness: f32,
+    // Space between a connector and the steps it joins.
+    #[builder(default = 6.0)]
+    connector_gap:       f32,
+}
+
+pub struct Stepper {
+    bounds:     Bounds,
+    style:      StepperStyle,
+    current:    usize,
+    validator:  Option<Box<StepValidator>>,
+    steps:      Vec<Block<Positioned>>,
+    connectors: Vec<Block<Positioned>>,
+}
+
+impl Stepper {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        step_count: usize,
+        style: StepperStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let mut stepper = Self {
+            bounds,
+            style,
+            current: 0,
+            validator: None,
+            steps: Vec::new(),
+            connectors: Vec::new(),
+        };
+        stepper.build(step_count, view_projection, dpr, renderer);
+
+        stepper
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn current(&self) -> usize { self.current }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn step_count(&self) -> usize { self.steps.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn step_state(&self, index: usize) -> StepState {
+        step_state(self.current, index)
+    }
+
+    // Bounds of a step's circle, for drawing its number or icon.
+    #[must_use]
+    pub fn step_bounds(&self, index: usize) -> Option<Bounds> {
+        let center = *step_centers(
+            &self.bounds,
+            self.style.step_size,
+            self.step_count(),
+        )
+        .get(index)?;
+        let half = self.style.step_size / 2.0;
+
+        Some(Bounds {
+            position:  [center[0] - half, center[1] - half],
+            size:      [self.style.step_size, self.style.step_size],
+            clip_rect: self.bounds.clip_rect,
+        })
+    }
+
+    #[must_use]
+    pub fn step_at(&self, position: [f32; 2]) -> Option<usize> {
+        let half = self.style.step_size / 2.0;
+        step_centers(&self.bounds, self.style.step_size, self.step_count())
+            .iter()
+            .position(|center| {
+                let dx = position[0] - center[0];
+                let dy = position[1] - center[1];
+                dx * dx + dy * dy <= half * half
+            })
+    }
+
+    pub fn set_validator(
+        &mut self,
+        validator: impl Fn(usize) -> bool + 'static,
+    ) {
+        self.validator = Some(Box::new(validator));
+    }
+
+    #[inline(always)]
+    pub fn clear_validator(&mut self) { self.validator = None; }
+
+    pub fn advance(&mut self, dpr: f32, renderer: &mut Renderer) -> bool {
+        self.go_to(self.current + 1, dpr, renderer)
+    }
+
+    pub fn retreat(&mut self, dpr: f32, renderer: &mut Renderer) -> bool {
+        match self.current.checked_sub(1) {
+            Some(index) => self.go_to(index, dpr, renderer),
+            None => false,
+        }
+    }
+
+    // Moving forward stops at the first step that fails validation, so
+    // the user lands on the step that still needs attention. Moving back
+    // is always allowed.
+    pub fn go_to(
+        &mut self,
+        index: usize,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if index >= self.step_count() {
+            return false;
+        }
+        let target = match &self.validator {
+            Some(validator) => reachable_step(self.current, index, validator),
+            None => index,
+        };
+        if target == self.current {
+            return false;
+        }
+        self.current = target;
+        self.refresh(dpr, renderer);
+
+        true
+    }
+
+    pub fn set_step_count(
+        &mut self,
+        step_count: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.current = self.current.min(step_count.saturating_sub(1));
+        self.build(step_count, view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.set_step_count(self.step_count(), view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: StepperStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.set_step_count(self.step_count(), view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for block in self.steps.iter().chain(&self.connectors) {
+            block.destroy(renderer);
+        }
+    }
+
+    fn build(
+        &mut self,
+        step_count: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let centers = step_centers(&self.bounds, style.step_size, step_count);
+        let half = style.step_size / 2.0;
+
+        self.steps = centers
+            .iter()
+            .enumerate()
+            .map(|(index, center)| {
+                let bounds = Bounds {
+                    position:  [center[0] - half, center[1] - half],
+                    size:      [style.step_size, style.step_size],
+                    clip_rect: self.bounds.clip_rect,
+                };
+                Block::<Positioned>::new(
+                    bounds,
+                    step_style(style, step_state(self.current, index), dpr),
+                    view_projection,
+                    dpr,
+                    renderer,
+                )
+            })
+            .collect();
+
+        self.connectors = centers
+            .windows(2)
+            .enumerate()
+            .map(|(index, pair)| {
+                let start = pair[0][0] + half + style.connector_gap;
+                let end = pair[1][0] - half - style.connector_gap;
+                let bounds = Bounds {
+                    position:  [
+                        start,
+                        pair[0][1] - style.connector_thickness / 2.0,
+                    ],
+                    size:      [
+                        (end - start).max(0.0),
+                        style.connector_thickness,
+                    ],
+                    clip_rect: self.bounds.clip_rect,
+                };
+                Block::<Positioned>::new(
+                    bounds,
+                    connector_style(style, index < self.current),
+                    view_projection,
+                    dpr,
+                    renderer,
+                )
+            })
+            .collect();
+    }
+
+    fn refresh(&mut self, dpr: f32, renderer: &mut Renderer) {
+        for (index, step) in self.steps.iter_mut().enumerate() {
+            let state = step_state(self.current, index);
+            step.update_style(step_style(&self.style, state, dpr), renderer);
+        }
+        for (index, connector) in self.connectors.iter_mut().enumerate() {
+            let style = connector_style(&self.style, index < self.current);
+            connector.update_style(style, renderer);
+        }
+    }
+}
+
+const fn step_state(current: usize, index: usize) -> StepState {
+    if index < current {
+        StepState::Completed
+    } else if index == current {
+        StepState::Current
+    } else {
+        StepState::Upcoming
+    }
+}
+
+fn step_style(style: &StepperStyle, state: StepState, dpr: f32) -> BlockStyle {
+    // Upcoming steps are drawn as rings.
+    let (fill_color, border_size) = match state {
+        StepState::Completed => (style.completed_color, 0.0),
+        StepState::Current => (style.current_color, 0.0),
+        StepState::Upcoming => ([0.0; 4], style.upcoming_border * dpr),
+    };
+    BlockStyle::builder()
+        .fill_color(fill_color)
+        .corner_radii([style.step_size / 2.0 * dpr; 4])
+        .border_color(style.upcoming_color)
+        .border_size(border_size)
+        .build()
+}
+
+fn connector_style(style: &StepperStyle, is_completed: bool) -> BlockStyle {
+    let color = match is_completed {
+        true => style.completed_color,
+        false => style.upcoming_color,
+    };
+    BlockStyle::builder().fill_color(color).build()
+}
+
+fn step_centers(
+    bounds: &Bounds,
+    step_size: f32,
+    step_count: usize,
+) -> Vec<[f32; 2]> {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    let center_y = y + height / 2.0;
+
+    if step_count == 1 {
+        return vec![[x + width / 2.0, center_y]];
+    }
+    let spacing = (width - step_size) / (step_count.max(2) - 1) as f32;
+    (0..step_count)
+        .map(|index| [x + step_size / 2.0 + index as f32 * spacing, center_y])
+        .collect()
+}
+
+fn reachable_step(
+    current: usize,
+    target: usize,
+    is_valid: impl Fn(usize) -> bool,
+) -> usize {
+    (current..target)
+        .find(|step| !is_valid(*step))
+        .unwrap_or(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{StepState, reachable_step, step_centers, step_state};
+    use crate::Bounds;
+
+    #[rstest]
+    #[case(0, 3, &[], 3)]
+    #[case(0, 3, &[1], 1)]
+    #[case(0, 3, &[0, 2], 0)]
+    #[case(3, 1, &[0, 1, 2], 1)]
+    fn test_reachable_step(
+        #[case] current: usize,
+        #[case] target: usize,
+        #[case] invalid_steps: &[usize],
+        #[case] expected: usize,
+    ) {
+        let is_valid = |step| !invalid_steps.contains(&step);
+        assert_eq!(reachable_step(current, target, is_valid), expected);
+    }
+
+    #[rstest]
+    #[case(1, &[[50.0, 10.0]])]
+    #[case(3, &[[10.0, 10.0], [50.0, 10.0], [90.0, 10.0]])]
+    fn test_step_centers(
+        #[case] step_count: usize,
+        #[case] expected: &[[f32; 2]],
+    ) {
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [100.0, 20.0]);
+        assert_eq!(step_centers(&bounds, 20.0, step_count), expected);
+    }
+
+    #[rstest]
+    #[case(0, StepState::Completed)]
+    #[case(1, StepState::Current)]
+    #[case(2, StepState::Upcoming)]
+    fn test_step_state(#[case] index: usize, #[case] expected: StepState) {
+        assert_eq!(step_state(1, index), expected);
+    }
+}
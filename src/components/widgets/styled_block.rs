@@ -0,0 +1,172 @@
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::{
+        common::{Bounds, HitShape, InputState, MouseButtonState},
+        debug::{DumpNode, Inspect},
+        style::{InteractionState, PseudoState, StateStyles},
+    },
+    core::Renderer,
+};
+
+// A block that restyles itself from its state variants as its
+// interaction state changes. Variants switch immediately; callers that
+// animate between them read `active_variant` before and after a change.
+pub struct StyledBlock {
+    styles: StateStyles<BlockStyle>,
+    state:  InteractionState,
+    block:  Block<Positioned>,
+}
+
+impl StyledBlock {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        styles: StateStyles<BlockStyle>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let state = InteractionState::default();
+        let block = Block::<Positioned>::new(
+            bounds,
+            styles.resolve(&state).clone(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        Self { styles, state, block }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn block(&self) -> &Block<Positioned> { &self.block }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn state(&self) -> &InteractionState { &self.state }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn active_variant(&self) -> Option<PseudoState> {
+        self.styles.active_variant(&self.state)
+    }
+
+    // Returns whether the applied variant changed.
+    pub fn set_state(
+        &mut self,
+        state: InteractionState,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let previous = self.active_variant();
+        self.state = state;
+        if self.active_variant() == previous {
+            return false;
+        }
+        self.block
+            .update_style(self.styles.resolve(&state).clone(), renderer);
+
+        true
+    }
+
+    pub fn update_state(
+        &mut self,
+        update: impl FnOnce(&mut InteractionState),
+        renderer: &mut Renderer,
+    ) -> bool {
+        let mut state = self.state;
+        update(&mut state);
+        self.set_state(state, renderer)
+    }
+
+    // Derives hover and press from the pointer. Disabled blocks are
+    // never hovered or pressed.
+    pub fn sync_pointer(
+        &mut self,
+        input_state: &InputState,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_hovered = !self.state.is_disabled
+            && input_state
+                .mouse_position()
+                .is_some_and(|position| self.block.contains(position));
+        let is_pressed = is_hovered
+            && matches!(
+                input_state.left_mouse_button(),
+                MouseButtonState::Down
+            );
+
+        self.update_state(
+            |state| {
+                state.is_hovered = is_hovered;
+                state.is_pressed = is_pressed;
+            },
+            renderer,
+        )
+    }
+
+    #[inline(always)]
+    pub fn set_hit_shape(&mut self, hit_shape: HitShape) {
+        self.block.set_hit_shape(hit_shape);
+    }
+
+    #[inline(always)]
+    pub const fn set_tag(&mut self, tag: Option<u64>) {
+        self.block.set_tag(tag);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.block
+            .update_size(bounds.size, view_projection, dpr, renderer);
+        self.block.update_position(
+            bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.block.update_clip_rect(&bounds.clip_rect, renderer);
+    }
+
+    #[inline(always)]
+    pub fn update_clip_rect(
+        &mut self,
+        clip_rect: &[f32; 4],
+        renderer: &mut Renderer,
+    ) {
+        self.block.update_clip_rect(clip_rect, renderer);
+    }
+
+    pub fn update_styles(
+        &mut self,
+        styles: StateStyles<BlockStyle>,
+        renderer: &mut Renderer,
+    ) {
+        self.block
+            .update_style(styles.resolve(&self.state).clone(), renderer);
+        self.styles = styles;
+    }
+
+    #[inline(always)]
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.block.destroy(renderer);
+    }
+}
+
+impl Inspect for StyledBlock {
+    fn inspect(&self, id: &str) -> DumpNode {
+        let mut node = self
+            .block
+            .inspect(id)
+            .with_style("state", self.state)
+            .with_style("active_variant", self.active_variant());
+        node.kind = "StyledBlock";
+        node
+    }
+}
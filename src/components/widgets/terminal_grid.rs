@@ -0,0 +1,825 @@
+use std::time::{Duration, Instant};
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{BlockStyle, GlyphSource, TextGlyph, block::build_rectangle};
+use crate::{
+    components::common::{Bounds, intersect_rects},
+    core::{ImageId, Rectangle, RectangleId, Renderer, TextRasterOptions},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellAttributes {
+    pub is_bold:       bool,
+    pub is_italic:     bool,
+    pub is_underlined: bool,
+    pub is_struck:     bool,
+    // Swaps the foreground and background colors.
+    pub is_inverse:    bool,
+}
+
+// Colors left as `None` use the grid's default colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerminalCell {
+    pub glyph:      char,
+    pub foreground: Option<[f32; 4]>,
+    pub background: Option<[f32; 4]>,
+    pub attributes: CellAttributes,
+}
+
+impl Default for TerminalCell {
+    fn default() -> Self {
+        Self {
+            glyph:      ' ',
+            foreground: None,
+            background: None,
+            attributes: CellAttributes::default(),
+        }
+    }
+}
+
+// Where a `TerminalGrid` gets its glyphs from: like `GlyphSource`, but
+// told the cell's attributes, so that bold and italic cells can come
+// from their own faces. Any `GlyphSource` draws them all in one face.
+pub trait CellGlyphSource {
+    fn cell_glyph(
+        &mut self,
+        glyph: char,
+        attributes: &CellAttributes,
+    ) -> TextGlyph;
+}
+
+impl<T: GlyphSource> CellGlyphSource for T {
+    #[inline(always)]
+    fn cell_glyph(
+        &mut self,
+        glyph: char,
+        _attributes: &CellAttributes,
+    ) -> TextGlyph {
+        self.glyph(glyph)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    Block,
+    Bar,
+    Underline,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct TerminalGridStyle {
+    #[builder(default = [8.0, 16.0])]
+    cell_size:        [f32; 2],
+    #[builder(default = [0.85, 0.85, 0.85, 1.0])]
+    foreground_color: [f32; 4],
+    #[builder(default = [0.08, 0.08, 0.1, 1.0])]
+    background_color: [f32; 4],
+    #[builder(default = CursorShape::Block)]
+    cursor_shape:     CursorShape,
+    #[builder(default = [0.85, 0.85, 0.85, 0.8])]
+    cursor_color:     [f32; 4],
+    // Zero keeps the cursor from blinking.
+    #[builder(default = Duration::from_millis(530))]
+    blink_interval:   Duration,
+    #[builder(default = [0.35, 0.65, 1.0, 0.35])]
+    selection_color:  [f32; 4],
+    // For underlines, strikes and bar or underline cursors.
+    #[builder(default = 1.0)]
+    line_thickness:   f32,
+    #[builder(default = TextRasterOptions::builder().build())]
+    raster_options:   TextRasterOptions,
+}
+
+// A fixed grid of cells for terminal emulators and roguelike tooling.
+// The grid draws cell backgrounds, underlines and strikes (merged into
+// runs of one color per row), the glyphs from the atlas, the blinking
+// cursor and the selection. Edits only damage their rows, and `flush`
+// rebuilds just those rows.
+pub struct TerminalGrid {
+    bounds:        Bounds,
+    style:         TerminalGridStyle,
+    size:          [usize; 2],
+    cells:         Vec<TerminalCell>,
+    damaged:       Vec<bool>,
+    background:    RectangleId,
+    row_parts:     Vec<Vec<RectangleId>>,
+    row_glyphs:    Vec<Vec<ImageId>>,
+    cursor:        [usize; 2],
+    is_cursor_on:  bool,
+    // Set on the first tick, so blinking follows the caller's clock.
+    blink_epoch:   Option<Instant>,
+    cursor_id:     RectangleId,
+    // The anchor and the head, in cells.
+    selection:     Option<([usize; 2], [usize; 2])>,
+    selection_ids: Vec<RectangleId>,
+    is_selecting:  bool,
+}
+
+impl TerminalGrid {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bounds: Bounds,
+        columns: usize,
+        rows: usize,
+        style: TerminalGridStyle,
+        glyphs: &mut impl CellGlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let size = [columns.max(1), rows.max(1)];
+        let mut grid = Self {
+            bounds,
+            style,
+            size,
+            cells: vec![TerminalCell::default(); size[0] * size[1]],
+            damaged: vec![true; size[1]],
+            background: RectangleId::default(),
+            row_parts: vec![Vec::new(); size[1]],
+            row_glyphs: vec![Vec::new(); size[1]],
+            cursor: [0, 0],
+            is_cursor_on: true,
+            blink_epoch: None,
+            cursor_id: RectangleId::default(),
+            selection: None,
+            selection_ids: Vec::new(),
+            is_selecting: false,
+        };
+        let background = grid.build_background(view_projection, dpr);
+        grid.background = renderer.add_rectangle(&background);
+        grid.flush(glyphs, view_projection, dpr, renderer);
+
+        grid
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn columns(&self) -> usize { self.size[0] }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn rows(&self) -> usize { self.size[1] }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn cursor(&self) -> [usize; 2] { self.cursor }
+
+    #[must_use]
+    pub fn cell(&self, column: usize, row: usize) -> Option<&TerminalCell> {
+        match column < self.size[0] {
+            true => self.cells.get(row * self.size[0] + column),
+            false => None,
+        }
+    }
+
+    #[must_use]
+    pub fn cell_bounds(&self, column: usize, row: usize) -> Bounds {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.style.cell_size;
+        Bounds {
+            position:  [x + column as f32 * width, y + row as f32 * height],
+            size:      [width, height],
+            clip_rect: self.clip_rect(),
+        }
+    }
+
+    #[must_use]
+    pub fn cell_at(&self, position: [f32; 2]) -> Option<[usize; 2]> {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.style.cell_size;
+        let column = ((position[0] - x) / width).floor();
+        let row = ((position[1] - y) / height).floor();
+        let is_inside = column >= 0.0
+            && row >= 0.0
+            && (column as usize) < self.size[0]
+            && (row as usize) < self.size[1];
+        is_inside.then_some([column as usize, row as usize])
+    }
+
+    pub fn set_cell(&mut self, column: usize, row: usize, cell: TerminalCell) {
+        if column >= self.size[0] || row >= self.size[1] {
+            return;
+        }
+        let index = row * self.size[0] + column;
+        if self.cells[index] != cell {
+            self.cells[index] = cell;
+            self.damaged[row] = true;
+        }
+    }
+
+    // Writes the text from a cell on, in the template's colors and
+    // attributes, stopping at the end of the row. Returns the column
+    // after the last char written.
+    pub fn write(
+        &mut self,
+        column: usize,
+        row: usize,
+        text: &str,
+        template: TerminalCell,
+    ) -> usize {
+        let mut column = column;
+        for glyph in text.chars() {
+            if column >= self.size[0] {
+                break;
+            }
+            self.set_cell(column, row, TerminalCell { glyph, ..template });
+            column += 1;
+        }
+        column
+    }
+
+    // Moves every row up, filling the bottom with blank cells.
+    pub fn scroll_up(&mut self, lines: usize) {
+        let lines = lines.min(self.size[1]);
+        if lines == 0 {
+            return;
+        }
+        self.cells.drain(..lines * self.size[0]);
+        self.cells
+            .resize(self.size[0] * self.size[1], TerminalCell::default());
+        self.selection = None;
+        self.damaged.fill(true);
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(TerminalCell::default());
+        self.selection = None;
+        self.damaged.fill(true);
+    }
+
+    // Keeps the cells that still fit; the rows cut off are removed right
+    // away, the rest is redrawn on the next `flush`.
+    pub fn resize(
+        &mut self,
+        columns: usize,
+        rows: usize,
+        renderer: &mut Renderer,
+    ) {
+        let size = [columns.max(1), rows.max(1)];
+        let mut cells = vec![TerminalCell::default(); size[0] * size[1]];
+        for row in 0..size[1].min(self.size[1]) {
+            let kept = size[0].min(self.size[0]);
+            cells[row * size[0]..row * size[0] + kept].copy_from_slice(
+                &self.cells[row * self.size[0]..row * self.size[0] + kept],
+            );
+        }
+        self.cells = cells;
+        self.size = size;
+        self.damaged = vec![true; size[1]];
+        let kept_rows = self.row_parts.len().min(size[1]);
+        for rectangle_id in self.row_parts.drain(kept_rows..).flatten() {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        for image_id in self.row_glyphs.drain(kept_rows..).flatten() {
+            renderer.remove_image(image_id);
+        }
+        self.row_parts.resize_with(size[1], Vec::new);
+        self.row_glyphs.resize_with(size[1], Vec::new);
+        self.cursor = [
+            self.cursor[0].min(size[0] - 1),
+            self.cursor[1].min(size[1] - 1),
+        ];
+        self.selection = None;
+    }
+
+    // Looks every glyph up again on the next `flush`, e.g. after the
+    // atlas moved them.
+    pub fn relayout(&mut self) { self.damaged.fill(true); }
+
+    // Moving the cursor shows it and restarts the blink.
+    pub fn set_cursor(&mut self, column: usize, row: usize, now: Instant) {
+        self.cursor =
+            [column.min(self.size[0] - 1), row.min(self.size[1] - 1)];
+        self.is_cursor_on = true;
+        self.blink_epoch = Some(now);
+    }
+
+    // Blinks the cursor; call once per frame.
+    pub fn tick(&mut self, now: Instant, renderer: &mut Renderer) {
+        let interval = self.style.blink_interval;
+        let blink_epoch = *self.blink_epoch.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(blink_epoch);
+        let is_cursor_on = interval.is_zero()
+            || (elapsed.as_nanos() / interval.as_nanos()).is_multiple_of(2);
+        if is_cursor_on != self.is_cursor_on {
+            self.is_cursor_on = is_cursor_on;
+            let color = self.cursor_color();
+            if let Some(rectangle) = renderer.get_mut_rectangle(self.cursor_id)
+            {
+                rectangle.fill_color = color;
+            }
+        }
+    }
+
+    // Starts a selection; returns whether the press landed on the grid.
+    pub fn press(&mut self, position: [f32; 2]) -> bool {
+        let Some(cell) = self.cell_at(position) else { return false };
+        self.selection = Some((cell, cell));
+        self.is_selecting = true;
+
+        true
+    }
+
+    pub fn drag(&mut self, position: [f32; 2]) {
+        let Some((anchor, _)) = self.selection else { return };
+        if !self.is_selecting {
+            return;
+        }
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.style.cell_size;
+        let head = [
+            ((position[0] - x) / width).clamp(0.0, self.size[0] as f32 - 1.0),
+            ((position[1] - y) / height).clamp(0.0, self.size[1] as f32 - 1.0),
+        ]
+        .map(|value| value as usize);
+        self.selection = Some((anchor, head));
+    }
+
+    pub fn release(&mut self) { self.is_selecting = false; }
+
+    pub fn clear_selection(&mut self) { self.selection = None; }
+
+    // The selected text for copying, one line per row with trailing
+    // blanks trimmed.
+    #[must_use]
+    pub fn selected_text(&self) -> Option<String> {
+        let (anchor, head) = self.selection?;
+        let (from, to) = ordered(anchor, head);
+        Some(extract_text(&self.cells, self.size[0], from, to))
+    }
+
+    // Rebuilds the damaged rows, the cursor and the selection, and
+    // returns the damaged rows.
+    pub fn flush(
+        &mut self,
+        glyphs: &mut impl CellGlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<usize> {
+        let rows: Vec<usize> =
+            (0..self.size[1]).filter(|row| self.damaged[*row]).collect();
+        for row in &rows {
+            for rectangle_id in self.row_parts[*row].drain(..) {
+                renderer.remove_rectangle(rectangle_id);
+            }
+            let parts = self.build_row(*row, view_projection, dpr);
+            self.row_parts[*row] = parts
+                .iter()
+                .map(|part| renderer.add_rectangle(part))
+                .collect();
+            for image_id in self.row_glyphs[*row].drain(..) {
+                renderer.remove_image(image_id);
+            }
+            self.row_glyphs[*row] = self.build_glyphs(
+                *row,
+                glyphs,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            self.damaged[*row] = false;
+        }
+
+        // Keeps the selection and the cursor above the rebuilt rows.
+        renderer.remove_rectangle(self.cursor_id);
+        for rectangle_id in self.selection_ids.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        if let Some((anchor, head)) = self.selection {
+            let (from, to) = ordered(anchor, head);
+            let style = BlockStyle::builder()
+                .fill_color(self.style.selection_color)
+                .build();
+            for rect in selection_rects(from, to, self.size[0]) {
+                let rectangle =
+                    self.build_cells(rect, &style, view_projection, dpr);
+                self.selection_ids.push(renderer.add_rectangle(&rectangle));
+            }
+        }
+        let cursor = self.build_cursor(view_projection, dpr);
+        self.cursor_id = renderer.add_rectangle(&cursor);
+
+        rows
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        glyphs: &mut impl CellGlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<usize> {
+        self.bounds = bounds;
+        self.damaged.fill(true);
+        let background = self.build_background(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_rectangle(self.background) {
+            *instance = background;
+        }
+        self.flush(glyphs, view_projection, dpr, renderer)
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: TerminalGridStyle,
+        glyphs: &mut impl CellGlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<usize> {
+        self.style = style;
+        let bounds = self.bounds.clone();
+        self.update_bounds(bounds, glyphs, view_projection, dpr, renderer)
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        renderer.remove_rectangle(self.background);
+        for rectangle_id in self.row_parts.iter().flatten() {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        for image_id in self.row_glyphs.iter().flatten() {
+            renderer.remove_image(*image_id);
+        }
+        for rectangle_id in &self.selection_ids {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        renderer.remove_rectangle(self.cursor_id);
+    }
+
+    fn clip_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        intersect_rects(self.bounds.clip_rect, [x, y, width, height])
+    }
+
+    fn cursor_color(&self) -> [f32; 4] {
+        match self.is_cursor_on {
+            true => self.style.cursor_color,
+            false => [0.0; 4],
+        }
+    }
+
+    fn build_background(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let style = BlockStyle::builder()
+            .fill_color(self.style.background_color)
+            .build();
+        build_rectangle(view_projection, dpr, &self.bounds, &style)
+    }
+
+    // Background runs that differ from the grid's background, then the
+    // underlines and strikes.
+    fn build_row(
+        &self,
+        row: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Vec<Rectangle> {
+        let style = &self.style;
+        let cells = &self.cells[row * self.size[0]..(row + 1) * self.size[0]];
+        let [_, cell_height] = style.cell_size;
+        let thickness = style.line_thickness;
+
+        let mut parts: Vec<Rectangle> = background_runs(
+            cells,
+            style.foreground_color,
+            style.background_color,
+        )
+        .into_iter()
+        .map(|(column, length, color)| {
+            let style = BlockStyle::builder().fill_color(color).build();
+            self.build_cells(
+                [column, row, length, 1],
+                &style,
+                view_projection,
+                dpr,
+            )
+        })
+        .collect();
+
+        let [foreground, background] =
+            [style.foreground_color, style.background_color];
+        let underlines =
+            line_runs(cells, foreground, background, |attributes| {
+                attributes.is_underlined
+            })
+            .into_iter()
+            .map(|run| (run, cell_height - thickness));
+        let strikes = line_runs(cells, foreground, background, |attributes| {
+            attributes.is_struck
+        })
+        .into_iter()
+        .map(|run| (run, (cell_height - thickness) / 2.0));
+        for ((column, length, color), offset) in underlines.chain(strikes) {
+            let mut bounds = self.cell_bounds(column, row);
+            bounds.position[1] += offset;
+            bounds.size = [bounds.size[0] * length as f32, thickness];
+            let style = BlockStyle::builder().fill_color(color).build();
+            parts.push(build_rectangle(view_projection, dpr, &bounds, &style));
+        }
+
+        parts
+    }
+
+    // Each cell's glyph from the atlas, in the color of its lines.
+    fn build_glyphs(
+        &self,
+        row: usize,
+        glyphs: &mut impl CellGlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Vec<ImageId> {
+        let style = &self.style;
+        let cells = &self.cells[row * self.size[0]..(row + 1) * self.size[0]];
+        let clip_rect = self.clip_rect();
+        let mut image_ids = Vec::new();
+        for (column, cell) in cells.iter().enumerate() {
+            let glyph = glyphs.cell_glyph(cell.glyph, &cell.attributes);
+            let Some((atlas_glyph, offset)) = glyph.image else {
+                continue;
+            };
+            let [x, y] = self.cell_bounds(column, row).position;
+            let image = atlas_glyph.build_image(
+                [x + offset[0], y + offset[1]],
+                glyph_color(
+                    cell,
+                    style.foreground_color,
+                    style.background_color,
+                ),
+                &style.raster_options,
+                clip_rect,
+                view_projection,
+                dpr,
+            );
+            image_ids
+                .extend(renderer.add_image(atlas_glyph.texture_id, &image));
+        }
+        image_ids
+    }
+
+    fn build_cursor(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let [column, row] = self.cursor;
+        let mut bounds = self.cell_bounds(column, row);
+        let thickness = self.style.line_thickness.max(1.0) * 2.0;
+        match self.style.cursor_shape {
+            CursorShape::Block => {}
+            CursorShape::Bar => bounds.size[0] = thickness,
+            CursorShape::Underline => {
+                bounds.position[1] += bounds.size[1] - thickness;
+                bounds.size[1] = thickness;
+            }
+        }
+        let style = BlockStyle::builder()
+            .fill_color(self.cursor_color())
+            .build();
+        build_rectangle(view_projection, dpr, &bounds, &style)
+    }
+
+    // A rect of cells, as column, row, columns and rows.
+    fn build_cells(
+        &self,
+        [column, row, columns, rows]: [usize; 4],
+        style: &BlockStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let mut bounds = self.cell_bounds(column, row);
+        bounds.size = [
+            bounds.size[0] * columns as f32,
+            bounds.size[1] * rows as f32,
+        ];
+        build_rectangle(view_projection, dpr, &bounds, style)
+    }
+}
+
+// Orders two cells in reading order.
+fn ordered(a: [usize; 2], b: [usize; 2]) -> ([usize; 2], [usize; 2]) {
+    match (a[1], a[0]) <= (b[1], b[0]) {
+        true => (a, b),
+        false => (b, a),
+    }
+}
+
+// Runs of cells sharing a background other than the default one, as the
+// first column, the length and the color.
+fn background_runs(
+    cells: &[TerminalCell],
+    foreground: [f32; 4],
+    background: [f32; 4],
+) -> Vec<(usize, usize, [f32; 4])> {
+    merged_runs(cells, |cell| {
+        let color = match cell.attributes.is_inverse {
+            true => cell.foreground.unwrap_or(foreground),
+            false => cell.background.unwrap_or(background),
+        };
+        (color != background).then_some(color)
+    })
+}
+
+// Runs of cells with a line `is_drawn` in the same color, like
+// `background_runs`.
+fn line_runs(
+    cells: &[TerminalCell],
+    foreground: [f32; 4],
+    background: [f32; 4],
+    is_drawn: fn(&CellAttributes) -> bool,
+) -> Vec<(usize, usize, [f32; 4])> {
+    merged_runs(cells, |cell| {
+        is_drawn(&cell.attributes)
+            .then(|| glyph_color(cell, foreground, background))
+    })
+}
+
+// The color of the cell's glyph and lines.
+fn glyph_color(
+    cell: &TerminalCell,
+    foreground: [f32; 4],
+    background: [f32; 4],
+) -> [f32; 4] {
+    match cell.attributes.is_inverse {
+        true => cell.background.unwrap_or(background),
+        false => cell.foreground.unwrap_or(foreground),
+    }
+}
+
+// Merges adjacent cells of the same color, skipping those without one.
+fn merged_runs(
+    cells: &[TerminalCell],
+    color: impl Fn(&TerminalCell) -> Option<[f32; 4]>,
+) -> Vec<(usize, usize, [f32; 4])> {
+    let mut runs: Vec<(usize, usize, [f32; 4])> = Vec::new();
+    for (column, cell) in cells.iter().enumerate() {
+        let Some(color) = color(cell) else {
+            continue;
+        };
+        match runs.last_mut() {
+            Some((start, length, run_color))
+                if *start + *length == column && *run_color == color =>
+            {
+                *length += 1;
+            }
+            _ => runs.push((column, 1, color)),
+        }
+    }
+    runs
+}
+
+// The selection from `from` to `to` (inclusive, in reading order) as up
+// to three rects of cells: the rest of the first row, the full rows in
+// between and the start of the last row.
+fn selection_rects(
+    from: [usize; 2],
+    to: [usize; 2],
+    columns: usize,
+) -> Vec<[usize; 4]> {
+    if from[1] == to[1] {
+        return vec![[from[0], from[1], to[0] + 1 - from[0], 1]];
+    }
+    let mut rects = vec![[from[0], from[1], columns - from[0], 1]];
+    if to[1] > from[1] + 1 {
+        rects.push([0, from[1] + 1, columns, to[1] - from[1] - 1]);
+    }
+    rects.push([0, to[1], to[0] + 1, 1]);
+    rects
+}
+
+fn extract_text(
+    cells: &[TerminalCell],
+    columns: usize,
+    from: [usize; 2],
+    to: [usize; 2],
+) -> String {
+    (from[1]..=to[1])
+        .map(|row| {
+            let first = match row == from[1] {
+                true => from[0],
+                false => 0,
+            };
+            let last = match row == to[1] {
+                true => to[0],
+                false => columns - 1,
+            };
+            let line: String = cells
+                [row * columns + first..=row * columns + last]
+                .iter()
+                .map(|cell| cell.glyph)
+                .collect();
+            line.trim_end().to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        CellAttributes, TerminalCell, background_runs, extract_text,
+        glyph_color, line_runs, selection_rects,
+    };
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+    const WHITE: [f32; 4] = [1.0; 4];
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    fn cells(text: &str) -> Vec<TerminalCell> {
+        text.chars()
+            .map(|glyph| TerminalCell { glyph, ..Default::default() })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_background_runs() {
+        let red = TerminalCell { background: Some(RED), ..Default::default() };
+        let inverse = TerminalCell {
+            attributes: CellAttributes {
+                is_inverse: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let row = [red, red, TerminalCell::default(), inverse, red];
+        assert_eq!(
+            background_runs(&row, WHITE, BLACK),
+            vec![(0, 2, RED), (3, 1, WHITE), (4, 1, RED)]
+        );
+    }
+
+    #[rstest]
+    #[case(
+        |attributes: &CellAttributes| attributes.is_underlined,
+        vec![(0, 2, WHITE), (2, 1, RED), (4, 1, RED)]
+    )]
+    #[case(|attributes: &CellAttributes| attributes.is_struck, vec![])]
+    fn test_line_runs(
+        #[case] is_drawn: fn(&CellAttributes) -> bool,
+        #[case] expected: Vec<(usize, usize, [f32; 4])>,
+    ) {
+        let underlined = TerminalCell {
+            attributes: CellAttributes {
+                is_underlined: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let red = TerminalCell { foreground: Some(RED), ..underlined };
+        let row = [underlined, underlined, red, TerminalCell::default(), red];
+        assert_eq!(line_runs(&row, WHITE, BLACK, is_drawn), expected);
+    }
+
+    #[rstest]
+    #[case(None, None, false, WHITE)]
+    #[case(Some(RED), None, false, RED)]
+    #[case(Some(RED), None, true, BLACK)]
+    #[case(None, Some(RED), true, RED)]
+    fn test_glyph_color(
+        #[case] foreground: Option<[f32; 4]>,
+        #[case] background: Option<[f32; 4]>,
+        #[case] is_inverse: bool,
+        #[case] expected: [f32; 4],
+    ) {
+        let cell = TerminalCell {
+            foreground,
+            background,
+            attributes: CellAttributes { is_inverse, ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(glyph_color(&cell, WHITE, BLACK), expected);
+    }
+
+    #[rstest]
+    #[case([2, 1], [5, 1], vec![[2, 1, 4, 1]])]
+    #[case([6, 0], [1, 1], vec![[6, 0, 4, 1], [0, 1, 2, 1]])]
+    #[case([6, 0], [1, 3], vec![[6, 0, 4, 1], [0, 1, 10, 2], [0, 3, 2, 1]])]
+    fn test_selection_rects(
+        #[case] from: [usize; 2],
+        #[case] to: [usize; 2],
+        #[case] expected: Vec<[usize; 4]>,
+    ) {
+        assert_eq!(selection_rects(from, to, 10), expected);
+    }
+
+    #[rstest]
+    #[case([0, 0], [1, 0], "ls")]
+    #[case([3, 0], [4, 1], "-la\ntotal")]
+    // Trailing blanks are trimmed.
+    #[case([0, 1], [8, 1], "total 8")]
+    fn test_extract_text(
+        #[case] from: [usize; 2],
+        #[case] to: [usize; 2],
+        #[case] expected: &str,
+    ) {
+        let cells = cells("ls -la   total 8  ");
+        assert_eq!(extract_text(&cells, 9, from, to), expected);
+    }
+}
@@ -0,0 +1,650 @@
+use std::ops::Range;
+
+use bon::Builder;
+use glam::Mat4;
+use winit::keyboard::KeyCode;
+
+use super::{
+    AccessNode, AccessRole, Availability, Block, BlockStyle,
+    block_states::Positioned,
+};
+use crate::{
+    components::{
+        common::{
+            Bounds, FocusState, InputState, MouseButtonState, WidgetId,
+            intersect_rects,
+        },
+        text::{AtlasGlyph, TextEditState},
+    },
+    core::{ImageId, Renderer, TextRasterOptions},
+};
+
+// A char of a `TextInput`, laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextGlyph {
+    pub advance: f32,
+    // None for chars that draw nothing, such as spaces. The offset is from
+    // the pen position at the top of the line.
+    pub image:   Option<(AtlasGlyph, [f32; 2])>,
+}
+
+// Where a `TextInput` gets its glyphs from: the app's rasterizer and
+// `GlyphAtlas`, at the input's font size. Once the atlas moves glyphs,
+// `TextInput::relayout` looks them up again.
+pub trait GlyphSource {
+    fn glyph(&mut self, char: char) -> TextGlyph;
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct TextInputStyle {
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    background_color:     [f32; 4],
+    #[builder(default = [0.75, 0.75, 0.75, 1.0])]
+    border_color:         [f32; 4],
+    #[builder(default = [0.35, 0.65, 1.0, 1.0])]
+    focused_border_color: [f32; 4],
+    #[builder(default = 1.0)]
+    border_size:          f32,
+    #[builder(default = 4.0)]
+    corner_radius:        f32,
+    #[builder(default = 8.0)]
+    padding:              f32,
+    // Centered vertically.
+    #[builder(default = 16.0)]
+    line_height:          f32,
+    #[builder(default = [0.1, 0.1, 0.1, 1.0])]
+    text_color:           [f32; 4],
+    // While disabled or loading.
+    #[builder(default = [0.55, 0.55, 0.55, 1.0])]
+    inactive_text_color:  [f32; 4],
+    #[builder(default = [0.35, 0.65, 1.0, 0.35])]
+    selection_color:      [f32; 4],
+    #[builder(default = [0.1, 0.1, 0.1, 1.0])]
+    caret_color:          [f32; 4],
+    #[builder(default = 1.5)]
+    caret_width:          f32,
+    #[builder(default = TextRasterOptions::builder().build())]
+    raster_options:       TextRasterOptions,
+}
+
+// A single-line text field. Clicking it focuses it by its id, and while
+// focused it takes typed text, arrows, Home, End, Backspace, Delete and
+// select all; text longer than the field scrolls to keep the caret in
+// view. Read-only inputs can still be focused and selected, disabled and
+// loading ones give up focus and ignore input.
+pub struct TextInput {
+    id:           WidgetId,
+    bounds:       Bounds,
+    style:        TextInputStyle,
+    state:        TextEditState,
+    availability: Availability,
+    is_focused:   bool,
+    is_selecting: bool,
+    was_down:     bool,
+    layout:       Vec<TextGlyph>,
+    background:   Block<Positioned>,
+    selection:    Block<Positioned>,
+    caret:        Block<Positioned>,
+    images:       Vec<ImageId>,
+}
+
+impl TextInput {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: WidgetId,
+        bounds: Bounds,
+        text: impl Into<String>,
+        style: TextInputStyle,
+        glyphs: &mut impl GlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            background_style(&style, false, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let [selection, caret] = [style.selection_color, style.caret_color]
+            .map(|color| {
+                Block::<Positioned>::new(
+                    Bounds { clip_rect: [0.0; 4], ..bounds.clone() },
+                    BlockStyle::builder().fill_color(color).build(),
+                    view_projection,
+                    dpr,
+                    renderer,
+                )
+            });
+        let mut text_input = Self {
+            id,
+            bounds,
+            style,
+            state: TextEditState::new(text),
+            availability: Availability::Enabled,
+            is_focused: false,
+            is_selecting: false,
+            was_down: false,
+            layout: Vec::new(),
+            background,
+            selection,
+            caret,
+            images: Vec::new(),
+        };
+        text_input.relayout(glyphs, view_projection, dpr, renderer);
+
+        text_input
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn id(&self) -> WidgetId { self.id }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn state(&self) -> &TextEditState { &self.state }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn text(&self) -> &str { self.state.text() }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn availability(&self) -> Availability { self.availability }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_focused(&self) -> bool { self.is_focused }
+
+    #[must_use]
+    pub fn accessibility(&self) -> AccessNode {
+        AccessNode {
+            role:         AccessRole::TextInput,
+            label:        self.text().to_owned(),
+            bounds:       self.bounds.clone(),
+            is_focusable: matches!(
+                self.availability,
+                Availability::Enabled | Availability::ReadOnly
+            ),
+            is_disabled:  self.availability == Availability::Disabled,
+            is_read_only: self.availability == Availability::ReadOnly,
+            is_busy:      self.availability == Availability::Loading,
+        }
+    }
+
+    // Edits the text or selection. Returns whether either changed.
+    pub fn edit(
+        &mut self,
+        edit: impl FnOnce(&mut TextEditState),
+        glyphs: &mut impl GlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let previous_text = self.text().to_owned();
+        let previous_selection = (self.state.anchor(), self.state.caret());
+        edit(&mut self.state);
+        if self.text() != previous_text {
+            self.relayout(glyphs, view_projection, dpr, renderer);
+            return true;
+        }
+        if (self.state.anchor(), self.state.caret()) != previous_selection {
+            self.update_text(view_projection, dpr, renderer);
+            return true;
+        }
+        false
+    }
+
+    // Picks up focus moved by someone else, e.g. restored after a
+    // rebuild. Returns whether it changed.
+    pub fn sync_focus(
+        &mut self,
+        focus: &FocusState,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_focused = focus.is_focused(self.id);
+        if is_focused == self.is_focused {
+            return false;
+        }
+        self.is_focused = is_focused;
+        self.is_selecting = false;
+        self.background.update_style(
+            background_style(&self.style, is_focused, dpr),
+            renderer,
+        );
+        self.update_text(view_projection, dpr, renderer);
+        true
+    }
+
+    // Events consumed by someone else don't focus the input or move its
+    // caret. Returns whether anything changed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sync_input(
+        &mut self,
+        input_state: &InputState,
+        focus: &mut FocusState,
+        glyphs: &mut impl GlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        let is_down =
+            matches!(input_state.left_mouse_button(), MouseButtonState::Down);
+        let is_pressed = is_down && !self.was_down;
+        self.was_down = is_down;
+
+        let is_selectable = matches!(
+            self.availability,
+            Availability::Enabled | Availability::ReadOnly
+        );
+        if !is_selectable {
+            if focus.is_focused(self.id) {
+                focus.blur();
+            }
+            return self.sync_focus(focus, view_projection, dpr, renderer);
+        }
+
+        let mouse_position = input_state
+            .mouse_position()
+            .filter(|_| !input_state.is_consumed());
+        let is_hovered = mouse_position
+            .is_some_and(|position| self.background.contains(position));
+        if is_pressed && is_hovered {
+            focus.focus(self.id);
+        } else if is_pressed && focus.is_focused(self.id) {
+            focus.blur();
+        }
+        let mut is_changed =
+            self.sync_focus(focus, view_projection, dpr, renderer);
+        if !self.is_focused {
+            return is_changed;
+        }
+
+        let is_editable = self.availability.is_enabled();
+        let advances = self.advances();
+        let text_x = self.text_rect()[0];
+        let is_shift_pressed = input_state.is_shift_pressed();
+        let is_primary_pressed = input_state.is_primary_pressed();
+        let typed_text = input_state.typed_text();
+        let pressed_key = input_state.pressed_key();
+        let is_selecting = self.is_selecting;
+        is_changed |= self.edit(
+            |state| {
+                if let Some([x, _]) = mouse_position {
+                    let caret = caret_at(
+                        state.text(),
+                        &advances,
+                        x - text_x + state.scroll_offset(),
+                    );
+                    if is_pressed && is_hovered {
+                        match is_shift_pressed {
+                            true => state.set_selection(Range {
+                                start: state.anchor(),
+                                end:   caret,
+                            }),
+                            false => state.set_caret(caret),
+                        }
+                    } else if is_down && is_selecting {
+                        state.set_selection(Range {
+                            start: state.anchor(),
+                            end:   caret,
+                        });
+                    }
+                }
+                let is_handled = pressed_key.is_some_and(|key| {
+                    apply_key(
+                        state,
+                        key,
+                        is_shift_pressed,
+                        is_primary_pressed,
+                        is_editable,
+                    )
+                });
+                if !is_handled
+                    && is_editable
+                    && !is_primary_pressed
+                    && !typed_text.is_empty()
+                {
+                    state.insert(typed_text);
+                }
+            },
+            glyphs,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.is_selecting =
+            is_down && (self.is_selecting || (is_pressed && is_hovered));
+        is_changed
+    }
+
+    // Disabling or starting to load drops the selection drag; the focus
+    // goes with the next `sync_input`. Returns whether it changed.
+    pub fn set_availability(
+        &mut self,
+        availability: Availability,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if availability == self.availability {
+            return false;
+        }
+        self.availability = availability;
+        self.is_selecting = false;
+        self.update_text(view_projection, dpr, renderer);
+        true
+    }
+
+    // Looks every char's glyph up again, e.g. after the atlas moved them.
+    pub fn relayout(
+        &mut self,
+        glyphs: &mut impl GlyphSource,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.layout =
+            self.text().chars().map(|char| glyphs.glyph(char)).collect();
+        self.update_text(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.background.update_size(
+            bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_position(
+            bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background
+            .update_clip_rect(&bounds.clip_rect, renderer);
+        self.bounds = bounds;
+        self.update_text(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: TextInputStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background.update_style(
+            background_style(&self.style, self.is_focused, dpr),
+            renderer,
+        );
+        for (block, color) in [
+            (&mut self.selection, self.style.selection_color),
+            (&mut self.caret, self.style.caret_color),
+        ] {
+            block.update_style(
+                BlockStyle::builder().fill_color(color).build(),
+                renderer,
+            );
+        }
+        self.update_text(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        self.selection.destroy(renderer);
+        self.caret.destroy(renderer);
+        self.destroy_images(renderer);
+    }
+
+    fn advances(&self) -> Vec<f32> {
+        self.layout.iter().map(|glyph| glyph.advance).collect()
+    }
+
+    // Inside the padding, with the line centered vertically.
+    fn text_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        let padding = self.style.padding;
+        let line_height = self.style.line_height;
+        [
+            x + padding,
+            y + (height - line_height) / 2.0,
+            (width - padding * 2.0).max(0.0),
+            line_height,
+        ]
+    }
+
+    // Scrolls to the caret and redraws the text, selection and caret.
+    fn update_text(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let advances = self.advances();
+        let text_rect = self.text_rect();
+        let [x, y, width, height] = text_rect;
+        self.state.scroll_to_caret(&advances, width);
+        let clip_rect = intersect_rects(text_rect, self.bounds.clip_rect);
+
+        let [start, end] = self.state.selection_span(&advances);
+        let is_selection_shown = self.is_focused && start != end;
+        let selection = Bounds {
+            position:  [x + start, y],
+            size:      [end - start, height],
+            clip_rect: match is_selection_shown {
+                true => clip_rect,
+                false => [0.0; 4],
+            },
+        };
+        let is_caret_shown = self.is_focused && self.availability.is_enabled();
+        let caret_x = x + self.state.caret_position(&advances);
+        let caret = Bounds {
+            position:  [caret_x - self.style.caret_width / 2.0, y],
+            size:      [self.style.caret_width, height],
+            clip_rect: match is_caret_shown {
+                true => clip_rect,
+                false => [0.0; 4],
+            },
+        };
+        for (block, bounds) in
+            [(&mut self.selection, selection), (&mut self.caret, caret)]
+        {
+            block.update_size(bounds.size, view_projection, dpr, renderer);
+            block.update_position(
+                bounds.position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            block.update_clip_rect(&bounds.clip_rect, renderer);
+        }
+
+        self.destroy_images(renderer);
+        self.build_images(clip_rect, view_projection, dpr, renderer);
+    }
+
+    fn destroy_images(&self, renderer: &mut Renderer) {
+        for image_id in &self.images {
+            renderer.remove_image(*image_id);
+        }
+    }
+
+    // Only the glyphs in view.
+    fn build_images(
+        &mut self,
+        clip_rect: [f32; 4],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let [x, y, width, _] = self.text_rect();
+        let color = match self.availability {
+            Availability::Enabled | Availability::ReadOnly => {
+                self.style.text_color
+            }
+            Availability::Disabled | Availability::Loading => {
+                self.style.inactive_text_color
+            }
+        };
+        let mut pen = x - self.state.scroll_offset();
+        self.images.clear();
+        for glyph in &self.layout {
+            let is_visible = pen + glyph.advance >= x && pen <= x + width;
+            if let Some((atlas_glyph, offset)) = glyph.image
+                && is_visible
+            {
+                let image = atlas_glyph.build_image(
+                    [pen + offset[0], y + offset[1]],
+                    color,
+                    &self.style.raster_options,
+                    clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                self.images.extend(
+                    renderer.add_image(atlas_glyph.texture_id, &image),
+                );
+            }
+            pen += glyph.advance;
+        }
+    }
+}
+
+fn background_style(
+    style: &TextInputStyle,
+    is_focused: bool,
+    dpr: f32,
+) -> BlockStyle {
+    let border_color = match is_focused {
+        true => style.focused_border_color,
+        false => style.border_color,
+    };
+    BlockStyle::builder()
+        .fill_color(style.background_color)
+        .border_color(border_color)
+        .border_size(style.border_size * dpr)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+// The byte offset of the char boundary closest to `x`, measured from the
+// start of the text.
+fn caret_at(text: &str, advances: &[f32], x: f32) -> usize {
+    let mut pen = 0.0;
+    for ((index, _), advance) in text.char_indices().zip(advances) {
+        if x < pen + advance / 2.0 {
+            return index;
+        }
+        pen += advance;
+    }
+    text.len()
+}
+
+// Applies an editing key. Returns whether it was one; edits are left out
+// unless `is_editable`, but still count.
+fn apply_key(
+    state: &mut TextEditState,
+    key: KeyCode,
+    is_shift_pressed: bool,
+    is_primary_pressed: bool,
+    is_editable: bool,
+) -> bool {
+    match key {
+        KeyCode::ArrowLeft | KeyCode::ArrowRight => {
+            state.move_caret(key == KeyCode::ArrowRight, is_shift_pressed);
+        }
+        KeyCode::Home | KeyCode::End => {
+            let caret = match key {
+                KeyCode::Home => 0,
+                _ => state.text().len(),
+            };
+            match is_shift_pressed {
+                true => state.set_selection(Range {
+                    start: state.anchor(),
+                    end:   caret,
+                }),
+                false => state.set_caret(caret),
+            }
+        }
+        KeyCode::KeyA if is_primary_pressed => state.select_all(),
+        KeyCode::Backspace | KeyCode::Delete => {
+            if is_editable {
+                state.delete(key == KeyCode::Delete);
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use rstest::rstest;
+    use winit::keyboard::KeyCode;
+
+    use super::{apply_key, caret_at};
+    use crate::components::text::TextEditState;
+
+    #[rstest]
+    #[case(0.0, 0)]
+    #[case(4.0, 0)]
+    #[case(6.0, 1)]
+    // Past the é, which takes two bytes.
+    #[case(16.0, 3)]
+    #[case(100.0, 6)]
+    fn test_caret_at(#[case] x: f32, #[case] expected: usize) {
+        assert_eq!(caret_at("héllo", &[10.0; 5], x), expected);
+    }
+
+    #[rstest]
+    // key, shift, primary, editable, handled, text, selection
+    #[case(KeyCode::Home, true, false, true, true, "hello", 0..3)]
+    #[case(KeyCode::End, false, false, true, true, "hello", 5..5)]
+    #[case(KeyCode::Backspace, false, false, true, true, "helo", 2..2)]
+    #[case(KeyCode::Backspace, false, false, false, true, "hello", 3..3)]
+    #[case(KeyCode::KeyA, false, true, true, true, "hello", 0..5)]
+    #[case(KeyCode::KeyA, false, false, true, false, "hello", 3..3)]
+    fn test_apply_key(
+        #[case] key: KeyCode,
+        #[case] is_shift_pressed: bool,
+        #[case] is_primary_pressed: bool,
+        #[case] is_editable: bool,
+        #[case] is_handled: bool,
+        #[case] text: &str,
+        #[case] selection: Range<usize>,
+    ) {
+        let mut state = TextEditState::new("hello");
+        state.set_caret(3);
+        let actual = apply_key(
+            &mut state,
+            key,
+            is_shift_pressed,
+            is_primary_pressed,
+            is_editable,
+        );
+        assert_eq!(actual, is_handled);
+        assert_eq!(state.text(), text);
+        assert_eq!(state.selection(), selection);
+    }
+}
@@ -0,0 +1,913 @@
+use std::collections::HashSet;
+
+use bon::Builder;
+use glam::Mat4;
+use slotmap::{DefaultKey, SlotMap};
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bar, Bounds, intersect_rects},
+    core::{Rectangle, RectangleId, Renderer},
+};
+
+pub type ClipId = DefaultKey;
+
+// Times are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineClip {
+    pub track:    usize,
+    pub start:    f32,
+    pub duration: f32,
+}
+
+impl TimelineClip {
+    #[must_use]
+    #[inline(always)]
+    pub const fn end(&self) -> f32 { self.start + self.duration }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineEvent {
+    // Sent once a drag ends, with the clip as it was dropped.
+    ClipMoved(ClipId, TimelineClip),
+    ClipResized(ClipId, TimelineClip),
+    SelectionChanged,
+    PlayheadMoved(f32),
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct TimelineStyle {
+    #[builder(default = [0.12, 0.12, 0.14, 1.0])]
+    background_color:  [f32; 4],
+    #[builder(default = [0.16, 0.16, 0.19, 1.0])]
+    ruler_color:       [f32; 4],
+    #[builder(default = 24.0)]
+    ruler_height:      f32,
+    #[builder(default = [0.5, 0.5, 0.55, 1.0])]
+    tick_color:        [f32; 4],
+    // The least distance between ticks; the step between them is picked
+    // from 1, 2 and 5 times a power of ten seconds.
+    #[builder(default = 80.0)]
+    tick_spacing:      f32,
+    #[builder(default = 40.0)]
+    track_height:      f32,
+    #[builder(default = 4.0)]
+    track_gap:         f32,
+    #[builder(default = [0.2, 0.2, 0.23, 1.0])]
+    separator_color:   [f32; 4],
+    #[builder(default = [0.3, 0.45, 0.7, 1.0])]
+    clip_color:        [f32; 4],
+    #[builder(default = [0.4, 0.55, 0.8, 1.0])]
+    clip_border_color: [f32; 4],
+    #[builder(default = [1.0, 0.8, 0.3, 1.0])]
+    selection_color:   [f32; 4],
+    #[builder(default = 4.0)]
+    corner_radius:     f32,
+    // How close to a clip's edge a press resizes instead of moving it.
+    #[builder(default = 6.0)]
+    handle_width:      f32,
+    // How close, in pixels, a dragged edge has to come to snap.
+    #[builder(default = 8.0)]
+    snap_distance:     f32,
+    #[builder(default = [1.0, 0.3, 0.3, 1.0])]
+    playhead_color:    [f32; 4],
+    #[builder(default = 2.0)]
+    playhead_width:    f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grab {
+    Body,
+    Start,
+    End,
+}
+
+enum Drag {
+    Playhead,
+    Clip {
+        id:       ClipId,
+        grab:     Grab,
+        offset:   f32,
+        original: TimelineClip,
+    },
+}
+
+struct Clip {
+    clip:  TimelineClip,
+    block: Block<Positioned>,
+}
+
+// A track editor: a time ruler over rows of clips, and a playhead.
+// Clips are moved across time and tracks and resized by their edges,
+// snapping to the edges of other clips and the playhead. Pressing the
+// ruler scrubs the playhead. The caller draws tick labels at `ticks`
+// and clip names in `clip_bounds`.
+pub struct Timeline {
+    bounds:      Bounds,
+    style:       TimelineStyle,
+    track_count: usize,
+    // Pixels per second
+    scale:       f32,
+    // The time at the left edge
+    scroll:      f32,
+    playhead:    f32,
+    clips:       SlotMap<ClipId, Clip>,
+    selected:    HashSet<ClipId>,
+    background:  Block<Positioned>,
+    ruler:       Block<Positioned>,
+    ticks:       Vec<(f32, RectangleId)>,
+    separators:  Vec<RectangleId>,
+    playhead_id: RectangleId,
+    drag:        Option<Drag>,
+    events:      Vec<TimelineEvent>,
+}
+
+impl Timeline {
+    const MAX_SCALE: f32 = 10_000.0;
+    const MIN_SCALE: f32 = 0.01;
+
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        track_count: usize,
+        scale: f32,
+        style: TimelineStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            BlockStyle::builder()
+                .fill_color(style.background_color)
+                .build(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let ruler = Block::<Positioned>::new(
+            ruler_bounds(&bounds, &style),
+            BlockStyle::builder().fill_color(style.ruler_color).build(),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut timeline = Self {
+            bounds,
+            style,
+            track_count,
+            scale: scale.clamp(Self::MIN_SCALE, Self::MAX_SCALE),
+            scroll: 0.0,
+            playhead: 0.0,
+            clips: SlotMap::new(),
+            selected: HashSet::new(),
+            background,
+            ruler,
+            ticks: Vec::new(),
+            separators: Vec::new(),
+            playhead_id: RectangleId::default(),
+            drag: None,
+            events: Vec::new(),
+        };
+        timeline.build_guides(view_projection, dpr, renderer);
+        timeline.playhead_id =
+            timeline.add_playhead(view_projection, dpr, renderer);
+
+        timeline
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn track_count(&self) -> usize { self.track_count }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scale(&self) -> f32 { self.scale }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scroll(&self) -> f32 { self.scroll }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn playhead(&self) -> f32 { self.playhead }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dragging(&self) -> bool { self.drag.is_some() }
+
+    #[must_use]
+    pub fn clip(&self, id: ClipId) -> Option<TimelineClip> {
+        self.clips.get(id).map(|clip| clip.clip)
+    }
+
+    pub fn clips(&self) -> impl Iterator<Item = (ClipId, TimelineClip)> + '_ {
+        self.clips.iter().map(|(id, clip)| (id, clip.clip))
+    }
+
+    // For the caller to draw the clip's name.
+    #[must_use]
+    pub fn clip_bounds(&self, id: ClipId) -> Option<Bounds> {
+        self.clips.get(id).map(|clip| self.layout_clip(&clip.clip))
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_selected(&self, id: ClipId) -> bool {
+        self.selected.contains(&id)
+    }
+
+    pub fn selected(&self) -> impl Iterator<Item = ClipId> + '_ {
+        self.selected.iter().copied()
+    }
+
+    // The time and x of every tick on the ruler, for the caller's labels.
+    pub fn ticks(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.ticks.iter().map(|(time, _)| (*time, self.x_at(*time)))
+    }
+
+    #[must_use]
+    pub fn x_at(&self, time: f32) -> f32 {
+        self.bounds.position[0] + (time - self.scroll) * self.scale
+    }
+
+    #[must_use]
+    pub fn time_at(&self, x: f32) -> f32 {
+        self.scroll + (x - self.bounds.position[0]) / self.scale
+    }
+
+    #[must_use]
+    pub fn track_at(&self, y: f32) -> Option<usize> {
+        let offset = y - self.bounds.position[1] - self.style.ruler_height;
+        let track = (offset / self.style.track_height).floor();
+        (track >= 0.0 && (track as usize) < self.track_count)
+            .then_some(track as usize)
+    }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<TimelineEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn add_clip(
+        &mut self,
+        clip: TimelineClip,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> ClipId {
+        let block = Block::<Positioned>::new(
+            self.layout_clip(&clip),
+            self.clip_style(false, dpr),
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let id = self.clips.insert(Clip { clip, block });
+        // Keeps the playhead drawn above the new clip.
+        renderer.remove_rectangle(self.playhead_id);
+        self.playhead_id = self.add_playhead(view_projection, dpr, renderer);
+
+        id
+    }
+
+    pub fn set_clip(
+        &mut self,
+        id: ClipId,
+        clip: TimelineClip,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let bounds = self.layout_clip(&clip);
+        let Some(entry) = self.clips.get_mut(id) else { return };
+        entry.clip = clip;
+        place_block(&mut entry.block, &bounds, view_projection, dpr, renderer);
+    }
+
+    pub fn remove_clip(&mut self, id: ClipId, renderer: &mut Renderer) {
+        let Some(clip) = self.clips.remove(id) else { return };
+        clip.block.destroy(renderer);
+        if self.selected.remove(&id) {
+            self.events.push(TimelineEvent::SelectionChanged);
+        }
+        if matches!(self.drag, Some(Drag::Clip { id: dragged, .. }) if dragged == id)
+        {
+            self.drag = None;
+        }
+    }
+
+    pub fn set_track_count(
+        &mut self,
+        track_count: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.track_count = track_count;
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn set_playhead(
+        &mut self,
+        time: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.playhead = time.max(0.0);
+        let playhead = self.build_playhead(view_projection, dpr);
+        if let Some(rectangle) = renderer.get_mut_rectangle(self.playhead_id) {
+            *rectangle = playhead;
+        }
+    }
+
+    // Scrolls by `delta` pixels; positive moves later in time.
+    pub fn scroll_by(
+        &mut self,
+        delta: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.scroll = (self.scroll + delta / self.scale).max(0.0);
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    // Zooms the time scale by `factor`, keeping the time under `anchor_x`
+    // in place.
+    pub fn zoom_at(
+        &mut self,
+        anchor_x: f32,
+        factor: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let time = self.time_at(anchor_x);
+        self.scale =
+            (self.scale * factor).clamp(Self::MIN_SCALE, Self::MAX_SCALE);
+        let offset = (anchor_x - self.bounds.position[0]) / self.scale;
+        self.scroll = (time - offset).max(0.0);
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    // Returns whether the press landed on the timeline. `is_extending`
+    // (shift or ctrl) toggles the clip in the selection instead of
+    // replacing it.
+    pub fn press(
+        &mut self,
+        position: [f32; 2],
+        is_extending: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> bool {
+        if !contains(&self.bounds, position) {
+            return false;
+        }
+        let ruler_bottom = self.bounds.position[1] + self.style.ruler_height;
+        if position[1] < ruler_bottom {
+            self.drag = Some(Drag::Playhead);
+            self.scrub(position[0], view_projection, dpr, renderer);
+            return true;
+        }
+
+        let Some((id, grab)) = self.clip_at(position) else {
+            if !is_extending {
+                self.set_selection(HashSet::new(), dpr, renderer);
+            }
+            return true;
+        };
+        let mut selected = match is_extending {
+            true => self.selected.clone(),
+            false => HashSet::new(),
+        };
+        match is_extending && self.selected.contains(&id) {
+            true => selected.remove(&id),
+            false => selected.insert(id),
+        };
+        self.set_selection(selected, dpr, renderer);
+
+        let original = self.clips[id].clip;
+        let time = self.time_at(position[0]);
+        let offset = match grab {
+            Grab::Body | Grab::Start => time - original.start,
+            Grab::End => time - original.end(),
+        };
+        self.drag = Some(Drag::Clip { id, grab, offset, original });
+
+        true
+    }
+
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        match self.drag {
+            Some(Drag::Playhead) => {
+                self.scrub(position[0], view_projection, dpr, renderer);
+            }
+            Some(Drag::Clip { id, grab, offset, original }) => {
+                let time = self.time_at(position[0]) - offset;
+                let targets = self.snap_targets(id);
+                let threshold = self.style.snap_distance / self.scale;
+                let min_duration = self.style.handle_width * 2.0 / self.scale;
+                let clip = match grab {
+                    Grab::Body => {
+                        let track = self
+                            .track_at(position[1])
+                            .unwrap_or(original.track);
+                        let start = snap_span(
+                            time.max(0.0),
+                            original.duration,
+                            &targets,
+                            threshold,
+                        );
+                        TimelineClip { track, start, ..original }
+                    }
+                    Grab::Start | Grab::End => {
+                        let edge =
+                            snap(time, &targets, threshold).unwrap_or(time);
+                        resize_clip(original, grab, edge, min_duration)
+                    }
+                };
+                self.set_clip(id, clip, view_projection, dpr, renderer);
+            }
+            None => {}
+        }
+    }
+
+    pub fn release(&mut self) {
+        let Some(Drag::Clip { id, grab, original, .. }) = self.drag.take()
+        else {
+            return;
+        };
+        let Some(clip) = self.clip(id) else { return };
+        if clip == original {
+            return;
+        }
+        self.events.push(match grab {
+            Grab::Body => TimelineEvent::ClipMoved(id, clip),
+            Grab::Start | Grab::End => TimelineEvent::ClipResized(id, clip),
+        });
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: TimelineStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.background.update_style(
+            BlockStyle::builder()
+                .fill_color(self.style.background_color)
+                .build(),
+            renderer,
+        );
+        self.ruler.update_style(
+            BlockStyle::builder()
+                .fill_color(self.style.ruler_color)
+                .build(),
+            renderer,
+        );
+        for (id, clip) in &mut self.clips {
+            let is_selected = self.selected.contains(&id);
+            clip.block.update_style(
+                clip_style(&self.style, is_selected, dpr),
+                renderer,
+            );
+        }
+        self.relayout(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        self.ruler.destroy(renderer);
+        for clip in self.clips.values() {
+            clip.block.destroy(renderer);
+        }
+        for (_, rectangle_id) in &self.ticks {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        for rectangle_id in &self.separators {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        renderer.remove_rectangle(self.playhead_id);
+    }
+
+    fn scrub(
+        &mut self,
+        x: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let time = self.time_at(x).max(0.0);
+        if time == self.playhead {
+            return;
+        }
+        self.set_playhead(time, view_projection, dpr, renderer);
+        self.events
+            .push(TimelineEvent::PlayheadMoved(self.playhead));
+    }
+
+    fn clip_at(&self, position: [f32; 2]) -> Option<(ClipId, Grab)> {
+        self.clips.iter().find_map(|(id, clip)| {
+            let bounds = self.layout_clip(&clip.clip);
+            if !contains(&bounds, position) {
+                return None;
+            }
+            // Narrow clips keep a grabbable middle.
+            let handle = self.style.handle_width.min(bounds.size[0] / 3.0);
+            let grab = if position[0] <= bounds.position[0] + handle {
+                Grab::Start
+            } else if position[0]
+                >= bounds.position[0] + bounds.size[0] - handle
+            {
+                Grab::End
+            } else {
+                Grab::Body
+            };
+            Some((id, grab))
+        })
+    }
+
+    // Edges of the other clips, the playhead and the start of time.
+    fn snap_targets(&self, except: ClipId) -> Vec<f32> {
+        self.clips
+            .iter()
+            .filter(|(id, _)| *id != except)
+            .flat_map(|(_, clip)| [clip.clip.start, clip.clip.end()])
+            .chain([self.playhead, 0.0])
+            .collect()
+    }
+
+    fn set_selection(
+        &mut self,
+        selected: HashSet<ClipId>,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        if selected == self.selected {
+            return;
+        }
+        let changed: Vec<ClipId> = self
+            .selected
+            .symmetric_difference(&selected)
+            .copied()
+            .collect();
+        self.selected = selected;
+        for id in changed {
+            let style = self.clip_style(self.selected.contains(&id), dpr);
+            if let Some(clip) = self.clips.get_mut(id) {
+                clip.block.update_style(style, renderer);
+            }
+        }
+        self.events.push(TimelineEvent::SelectionChanged);
+    }
+
+    fn clip_style(&self, is_selected: bool, dpr: f32) -> BlockStyle {
+        clip_style(&self.style, is_selected, dpr)
+    }
+
+    fn layout_clip(&self, clip: &TimelineClip) -> Bounds {
+        let style = &self.style;
+        let top = self.bounds.position[1]
+            + style.ruler_height
+            + clip.track as f32 * style.track_height;
+        Bounds {
+            position:  [self.x_at(clip.start), top + style.track_gap / 2.0],
+            size:      [
+                clip.duration * self.scale,
+                (style.track_height - style.track_gap).max(0.0),
+            ],
+            clip_rect: self.tracks_clip_rect(),
+        }
+    }
+
+    fn tracks_clip_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        let ruler_height = self.style.ruler_height.min(height);
+        intersect_rects(
+            self.bounds.clip_rect,
+            [x, y + ruler_height, width, height - ruler_height],
+        )
+    }
+
+    fn relayout(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        place_block(
+            &mut self.background,
+            &self.bounds,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let ruler = ruler_bounds(&self.bounds, &self.style);
+        place_block(&mut self.ruler, &ruler, view_projection, dpr, renderer);
+
+        let layouts: Vec<(ClipId, Bounds)> = self
+            .clips
+            .iter()
+            .map(|(id, clip)| (id, self.layout_clip(&clip.clip)))
+            .collect();
+        for (id, bounds) in layouts {
+            let block = &mut self.clips[id].block;
+            place_block(block, &bounds, view_projection, dpr, renderer);
+        }
+
+        for (_, rectangle_id) in self.ticks.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        for rectangle_id in self.separators.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        self.build_guides(view_projection, dpr, renderer);
+        renderer.remove_rectangle(self.playhead_id);
+        self.playhead_id = self.add_playhead(view_projection, dpr, renderer);
+    }
+
+    // Ticks on the ruler and lines between the tracks.
+    fn build_guides(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let [x, y] = self.bounds.position;
+        let [width, _] = self.bounds.size;
+        let ruler = ruler_bounds(&self.bounds, style);
+        let ruler_clip_rect = intersect_rects(
+            ruler.clip_rect,
+            [x, y, width, style.ruler_height],
+        );
+
+        let step = tick_step(self.scale, style.tick_spacing);
+        let end = self.time_at(x + width);
+        let times = tick_times(self.scroll, end, step);
+        self.ticks = times
+            .into_iter()
+            .map(|time| {
+                let tick_x = self.x_at(time);
+                let bar = Bar::between(
+                    [tick_x, y + style.ruler_height / 2.0],
+                    [tick_x, y + style.ruler_height - 1.0],
+                    1.0,
+                );
+                let rectangle = bar.build_rectangle(
+                    style.tick_color,
+                    ruler_clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                (time, renderer.add_rectangle(&rectangle))
+            })
+            .collect();
+
+        let clip_rect = self.tracks_clip_rect();
+        self.separators = (1..=self.track_count)
+            .map(|track| {
+                let line_y =
+                    y + style.ruler_height + track as f32 * style.track_height
+                        - 0.5;
+                let bar = Bar::between([x, line_y], [x + width, line_y], 1.0);
+                let rectangle = bar.build_rectangle(
+                    style.separator_color,
+                    clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                renderer.add_rectangle(&rectangle)
+            })
+            .collect();
+    }
+
+    fn add_playhead(
+        &self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> RectangleId {
+        renderer.add_rectangle(&self.build_playhead(view_projection, dpr))
+    }
+
+    fn build_playhead(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let x = self.x_at(self.playhead);
+        let [_, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        let clip_rect = intersect_rects(
+            self.bounds.clip_rect,
+            [self.bounds.position[0], y, width, height],
+        );
+        Bar::between([x, y], [x, y + height], self.style.playhead_width)
+            .build_rectangle(
+                self.style.playhead_color,
+                clip_rect,
+                view_projection,
+                dpr,
+            )
+    }
+}
+
+fn clip_style(
+    style: &TimelineStyle,
+    is_selected: bool,
+    dpr: f32,
+) -> BlockStyle {
+    let border_color = match is_selected {
+        true => style.selection_color,
+        false => style.clip_border_color,
+    };
+    BlockStyle::builder()
+        .fill_color(style.clip_color)
+        .border_color(border_color)
+        .border_size(dpr)
+        .corner_radii([style.corner_radius * dpr; 4])
+        .build()
+}
+
+fn ruler_bounds(bounds: &Bounds, style: &TimelineStyle) -> Bounds {
+    Bounds {
+        position:  bounds.position,
+        size:      [bounds.size[0], style.ruler_height.min(bounds.size[1])],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn place_block(
+    block: &mut Block<Positioned>,
+    bounds: &Bounds,
+    view_projection: &Mat4,
+    dpr: f32,
+    renderer: &mut Renderer,
+) {
+    block.update_clip_rect(&bounds.clip_rect, renderer);
+    block.update_position(bounds.position, view_projection, dpr, renderer);
+    block.update_size(bounds.size, view_projection, dpr, renderer);
+}
+
+// The smallest of 1, 2 and 5 times a power of ten seconds that puts ticks
+// at least `min_spacing` pixels apart.
+fn tick_step(scale: f32, min_spacing: f32) -> f32 {
+    let min_step = min_spacing / scale;
+    let magnitude = 10_f32.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= min_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn tick_times(start: f32, end: f32, step: f32) -> Vec<f32> {
+    let first = (start / step).ceil() as i64;
+    let last = (end / step).floor() as i64;
+    (first..=last).map(|index| index as f32 * step).collect()
+}
+
+// The target closest to `value`, if within `threshold`.
+fn snap(value: f32, targets: &[f32], threshold: f32) -> Option<f32> {
+    targets
+        .iter()
+        .copied()
+        .filter(|target| (target - value).abs() <= threshold)
+        .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+}
+
+// Snaps whichever edge of a moved span lands closer to a target; returns
+// the span's start.
+fn snap_span(
+    start: f32,
+    duration: f32,
+    targets: &[f32],
+    threshold: f32,
+) -> f32 {
+    let by_start =
+        snap(start, targets, threshold).map(|target| target - start);
+    let by_end = snap(start + duration, targets, threshold)
+        .map(|target| target - start - duration);
+    let delta = match (by_start, by_end) {
+        (Some(a), Some(b)) if b.abs() < a.abs() => b,
+        (Some(delta), _) | (None, Some(delta)) => delta,
+        (None, None) => 0.0,
+    };
+
+    (start + delta).max(0.0)
+}
+
+fn resize_clip(
+    clip: TimelineClip,
+    grab: Grab,
+    edge: f32,
+    min_duration: f32,
+) -> TimelineClip {
+    match grab {
+        Grab::Start => {
+            let start = edge.clamp(0.0, (clip.end() - min_duration).max(0.0));
+            TimelineClip { start, duration: clip.end() - start, ..clip }
+        }
+        Grab::End => {
+            let duration = (edge - clip.start).max(min_duration);
+            TimelineClip { duration, ..clip }
+        }
+        Grab::Body => clip,
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        Grab, TimelineClip, resize_clip, snap_span, tick_step, tick_times,
+    };
+
+    #[rstest]
+    #[case(100.0, 80.0, 1.0)]
+    #[case(10.0, 80.0, 10.0)]
+    #[case(30.0, 80.0, 5.0)]
+    #[case(500.0, 80.0, 0.2)]
+    fn test_tick_step(
+        #[case] scale: f32,
+        #[case] min_spacing: f32,
+        #[case] expected: f32,
+    ) {
+        assert!((tick_step(scale, min_spacing) - expected).abs() < 1e-5);
+    }
+
+    #[rstest]
+    #[case(0.0, 10.0, 5.0, vec![0.0, 5.0, 10.0])]
+    #[case(3.0, 12.0, 5.0, vec![5.0, 10.0])]
+    fn test_tick_times(
+        #[case] start: f32,
+        #[case] end: f32,
+        #[case] step: f32,
+        #[case] expected: Vec<f32>,
+    ) {
+        assert_eq!(tick_times(start, end, step), expected);
+    }
+
+    #[rstest]
+    // The start snaps to the 10.
+    #[case(9.7, 10.0)]
+    // The end snaps to the 10.
+    #[case(5.5, 6.0)]
+    // The end is closer to the 14.5 than the start to the 10.
+    #[case(10.8, 10.5)]
+    // Nothing within reach.
+    #[case(20.0, 20.0)]
+    fn test_snap_span(#[case] start: f32, #[case] expected: f32) {
+        assert_eq!(snap_span(start, 4.0, &[10.0, 14.5], 1.0), expected);
+    }
+
+    #[rstest]
+    #[case(Grab::Start, 4.0, TimelineClip { track: 0, start: 4.0, duration: 6.0 })]
+    #[case(Grab::Start, 9.5, TimelineClip { track: 0, start: 9.0, duration: 1.0 })]
+    #[case(Grab::End, 12.0, TimelineClip { track: 0, start: 2.0, duration: 10.0 })]
+    #[case(Grab::End, 1.0, TimelineClip { track: 0, start: 2.0, duration: 1.0 })]
+    fn test_resize_clip(
+        #[case] grab: Grab,
+        #[case] edge: f32,
+        #[case] expected: TimelineClip,
+    ) {
+        let clip = TimelineClip { track: 0, start: 2.0, duration: 8.0 };
+        assert_eq!(resize_clip(clip, grab, edge, 1.0), expected);
+    }
+}
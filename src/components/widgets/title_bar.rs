@@ -0,0 +1,363 @@
+use bon::Builder;
+use glam::Mat4;
+use winit::window::Window;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{
+    components::common::{Bar, Bounds},
+    core::{RectangleId, Renderer},
+    format::{localize, messages},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptionButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+impl CaptionButton {
+    // In the order they appear, from the right edge inwards.
+    pub const ALL: [Self; 3] = [Self::Close, Self::Maximize, Self::Minimize];
+
+    #[must_use]
+    pub fn tooltip(&self) -> String {
+        let id = match self {
+            Self::Minimize => messages::MINIMIZE,
+            Self::Maximize => messages::MAXIMIZE,
+            Self::Close => messages::CLOSE,
+        };
+        localize(id, &[])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TitleBarAction {
+    Drag,
+    Minimize,
+    ToggleMaximize,
+    // Closing is left to the application, which owns the event loop.
+    Close,
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct TitleBarStyle {
+    #[builder(default = [0.93, 0.93, 0.93, 1.0])]
+    background_color:  [f32; 4],
+    #[builder(default = [0.0, 0.0, 0.0, 0.08])]
+    hover_color:       [f32; 4],
+    #[builder(default = [0.91, 0.07, 0.14, 1.0])]
+    close_hover_color: [f32; 4],
+    #[builder(default = [0.1, 0.1, 0.1, 1.0])]
+    glyph_color:       [f32; 4],
+    #[builder(default = 46.0)]
+    button_width:      f32,
+    #[builder(default = 10.0)]
+    glyph_size:        f32,
+    #[builder(default = 1.0)]
+    glyph_thickness:   f32,
+}
+
+pub struct TitleBar {
+    bounds:     Bounds,
+    style:      TitleBarStyle,
+    hovered:    Option<CaptionButton>,
+    background: Block<Positioned>,
+    buttons:    Vec<(CaptionButton, Block<Positioned>)>,
+    glyphs:     Vec<RectangleId>,
+}
+
+impl TitleBar {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        style: TitleBarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let background_style = BlockStyle::builder()
+            .fill_color(style.background_color)
+            .build();
+        let background = Block::<Positioned>::new(
+            bounds.clone(),
+            background_style,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        let mut title_bar = Self {
+            bounds,
+            style,
+            hovered: None,
+            background,
+            buttons: Vec::new(),
+            glyphs: Vec::new(),
+        };
+        title_bar.build_buttons(view_projection, dpr, renderer);
+
+        title_bar
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn hovered(&self) -> Option<CaptionButton> { self.hovered }
+
+    #[must_use]
+    pub fn button_bounds(&self, button: CaptionButton) -> Bounds {
+        button_bounds(&self.bounds, self.style.button_width, button)
+    }
+
+    // The draggable part of the bar, where the caller draws the title.
+    #[must_use]
+    pub fn drag_region(&self) -> Bounds {
+        drag_region(&self.bounds, self.style.button_width)
+    }
+
+    #[must_use]
+    pub fn action_at(&self, position: [f32; 2]) -> Option<TitleBarAction> {
+        action_at(&self.bounds, self.style.button_width, position)
+    }
+
+    pub fn hover(
+        &mut self,
+        position: Option<[f32; 2]>,
+        renderer: &mut Renderer,
+    ) {
+        let hovered = position.and_then(|position| {
+            CaptionButton::ALL.into_iter().find(|button| {
+                contains(&self.button_bounds(*button), position)
+            })
+        });
+        if hovered == self.hovered {
+            return;
+        }
+        self.hovered = hovered;
+        for (button, block) in &mut self.buttons {
+            let style = button_style(&self.style, *button, hovered);
+            block.update_style(style, renderer);
+        }
+    }
+
+    // Performs the action under a left-button press on the window.
+    pub fn press(
+        &self,
+        position: [f32; 2],
+        window: &Window,
+    ) -> Option<TitleBarAction> {
+        let action = self.action_at(position)?;
+        match action {
+            TitleBarAction::Drag => {
+                let _ = window.drag_window();
+            }
+            TitleBarAction::Minimize => window.set_minimized(true),
+            TitleBarAction::ToggleMaximize => {
+                window.set_maximized(!window.is_maximized());
+            }
+            TitleBarAction::Close => {}
+        }
+
+        Some(action)
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.background.update_position(
+            self.bounds.position,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.background.update_size(
+            self.bounds.size,
+            view_projection,
+            dpr,
+            renderer,
+        );
+        self.destroy_buttons(renderer);
+        self.build_buttons(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        self.background.destroy(renderer);
+        self.destroy_buttons(renderer);
+    }
+
+    fn destroy_buttons(&self, renderer: &mut Renderer) {
+        for (_, block) in &self.buttons {
+            block.destroy(renderer);
+        }
+        for rectangle_id in &self.glyphs {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+    }
+
+    fn build_buttons(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        self.buttons.clear();
+        self.glyphs.clear();
+
+        for button in CaptionButton::ALL {
+            let bounds = self.button_bounds(button);
+            let block = Block::<Positioned>::new(
+                bounds.clone(),
+                button_style(style, button, self.hovered),
+                view_projection,
+                dpr,
+                renderer,
+            );
+            self.buttons.push((button, block));
+
+            let center = [
+                bounds.position[0] + bounds.size[0] / 2.0,
+                bounds.position[1] + bounds.size[1] / 2.0,
+            ];
+            for bar in glyph_bars(button, center, style) {
+                let rectangle = bar.build_rectangle(
+                    style.glyph_color,
+                    bounds.clip_rect,
+                    view_projection,
+                    dpr,
+                );
+                self.glyphs.push(renderer.add_rectangle(&rectangle));
+            }
+        }
+    }
+}
+
+fn button_style(
+    style: &TitleBarStyle,
+    button: CaptionButton,
+    hovered: Option<CaptionButton>,
+) -> BlockStyle {
+    let fill_color = match (hovered == Some(button), button) {
+        (true, CaptionButton::Close) => style.close_hover_color,
+        (true, _) => style.hover_color,
+        (false, _) => [0.0; 4],
+    };
+    BlockStyle::builder().fill_color(fill_color).build()
+}
+
+fn glyph_bars(
+    button: CaptionButton,
+    center: [f32; 2],
+    style: &TitleBarStyle,
+) -> Vec<Bar> {
+    let half = style.glyph_size / 2.0;
+    let thickness = style.glyph_thickness;
+    let [x, y] = center;
+
+    let line = |start: [f32; 2], end: [f32; 2]| {
+        // `Bar::between` extends past the ends for round caps; glyph lines
+        // are square, so only the segment itself is covered.
+        let mut bar = Bar::between(start, end, thickness);
+        bar.length -= thickness;
+        bar
+    };
+    match button {
+        CaptionButton::Minimize => vec![line([x - half, y], [x + half, y])],
+        CaptionButton::Maximize => vec![
+            line([x - half, y - half], [x + half, y - half]),
+            line([x - half, y + half], [x + half, y + half]),
+            line([x - half, y - half], [x - half, y + half]),
+            line([x + half, y - half], [x + half, y + half]),
+        ],
+        CaptionButton::Close => vec![
+            line([x - half, y - half], [x + half, y + half]),
+            line([x - half, y + half], [x + half, y - half]),
+        ],
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+fn button_bounds(
+    bounds: &Bounds,
+    button_width: f32,
+    button: CaptionButton,
+) -> Bounds {
+    let slot = CaptionButton::ALL
+        .iter()
+        .position(|candidate| *candidate == button)
+        .unwrap_or_default();
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    Bounds {
+        position:  [x + width - button_width * (slot + 1) as f32, y],
+        size:      [button_width, height],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn drag_region(bounds: &Bounds, button_width: f32) -> Bounds {
+    let buttons_width = button_width * CaptionButton::ALL.len() as f32;
+    Bounds {
+        position:  bounds.position,
+        size:      [(bounds.size[0] - buttons_width).max(0.0), bounds.size[1]],
+        clip_rect: bounds.clip_rect,
+    }
+}
+
+fn action_at(
+    bounds: &Bounds,
+    button_width: f32,
+    position: [f32; 2],
+) -> Option<TitleBarAction> {
+    let button = CaptionButton::ALL.into_iter().find(|button| {
+        contains(&button_bounds(bounds, button_width, *button), position)
+    });
+    match button {
+        Some(CaptionButton::Minimize) => Some(TitleBarAction::Minimize),
+        Some(CaptionButton::Maximize) => Some(TitleBarAction::ToggleMaximize),
+        Some(CaptionButton::Close) => Some(TitleBarAction::Close),
+        None => contains(&drag_region(bounds, button_width), position)
+            .then_some(TitleBarAction::Drag),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{TitleBarAction, action_at};
+    use crate::Bounds;
+
+    #[rstest]
+    #[case([10.0, 10.0], Some(TitleBarAction::Drag))]
+    #[case([300.0, 10.0], Some(TitleBarAction::Close))]
+    #[case([250.0, 10.0], Some(TitleBarAction::ToggleMaximize))]
+    #[case([210.0, 10.0], Some(TitleBarAction::Minimize))]
+    #[case([10.0, 40.0], None)]
+    fn test_action_at(
+        #[case] position: [f32; 2],
+        #[case] expected: Option<TitleBarAction>,
+    ) {
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [320.0, 32.0]);
+        assert_eq!(action_at(&bounds, 40.0, position), expected);
+    }
+}
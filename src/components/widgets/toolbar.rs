@@ -0,0 +1,475 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{Block, BlockStyle, block_states::Positioned};
+use crate::{components::common::Bounds, core::Renderer};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToolbarItemKind {
+    Button,
+    Separator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToolbarItem {
+    pub kind:     ToolbarItemKind,
+    pub width:    f32,
+    // Items with a lower priority move into the overflow menu first.
+    pub priority: u32,
+}
+
+impl ToolbarItem {
+    #[must_use]
+    #[inline(always)]
+    pub const fn button(width: f32) -> Self {
+        Self { kind: ToolbarItemKind::Button, width, priority: 0 }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn separator(width: f32) -> Self {
+        Self { kind: ToolbarItemKind::Separator, width, priority: 0 }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct ToolbarStyle {
+    #[builder(default = [0.96, 0.96, 0.96, 1.0])]
+    background_color:      [f32; 4],
+    #[builder(default = [0.90, 0.90, 0.90, 1.0])]
+    button_color:          [f32; 4],
+    #[builder(default = [0.75, 0.75, 0.75, 1.0])]
+    separator_color:       [f32; 4],
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    dropdown_color:        [f32; 4],
+    #[builder(default = [0.0, 0.0, 0.0, 0.2])]
+    dropdown_shadow_color: [f32; 4],
+    #[builder(default = 4.0)]
+    spacing:               f32,
+    #[builder(default = 4.0)]
+    padding:               f32,
+    #[builder(default = 1.0)]
+    separator_thickness:   f32,
+    #[builder(default = 4.0)]
+    corner_radius:         f32,
+    #[builder(default = 32.0)]
+    overflow_button_width: f32,
+    #[builder(default = 180.0)]
+    dropdown_width:        f32,
+    #[builder(default = 28.0)]
+    dropdown_item_height:  f32,
+}
+
+pub struct Toolbar {
+    bounds:           Bounds,
+    style:            ToolbarStyle,
+    items:            Vec<ToolbarItem>,
+    visible_items:    Vec<(usize, Bounds)>,
+    overflow_items:   Vec<(usize, Bounds)>,
+    overflow_button:  Option<Bounds>,
+    is_overflow_open: bool,
+    blocks:           Vec<Block<Positioned>>,
+}
+
+impl Toolbar {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        items: Vec<ToolbarItem>,
+        style: ToolbarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let mut toolbar = Self {
+            bounds,
+            style,
+            items,
+            visible_items: Vec::new(),
+            overflow_items: Vec::new(),
+            overflow_button: None,
+            is_overflow_open: false,
+            blocks: Vec::new(),
+        };
+        toolbar.build(view_projection, dpr, renderer);
+
+        toolbar
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn items(&self) -> &[ToolbarItem] { &self.items }
+
+    // Indices into `items` with their bounds, for drawing icons and labels.
+    #[must_use]
+    #[inline(always)]
+    pub fn visible_items(&self) -> &[(usize, Bounds)] { &self.visible_items }
+
+    // Bounds are those of the dropdown entries and only meaningful while
+    // the overflow menu is open.
+    #[must_use]
+    #[inline(always)]
+    pub fn overflow_items(&self) -> &[(usize, Bounds)] { &self.overflow_items }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn overflow_button(&self) -> Option<&Bounds> {
+        self.overflow_button.as_ref()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_overflow_open(&self) -> bool { self.is_overflow_open }
+
+    #[must_use]
+    pub fn item_at(&self, position: [f32; 2]) -> Option<usize> {
+        let overflow_items = match self.is_overflow_open {
+            true => self.overflow_items.as_slice(),
+            false => &[],
+        };
+        overflow_items
+            .iter()
+            .chain(&self.visible_items)
+            .find(|(index, bounds)| {
+                self.items[*index].kind == ToolbarItemKind::Button
+                    && contains(bounds, position)
+            })
+            .map(|(index, _)| *index)
+    }
+
+    // Returns the clicked button. Clicking the overflow button toggles the
+    // menu, and clicking anywhere else closes it.
+    pub fn click(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Option<usize> {
+        let is_overflow_button_clicked = self
+            .overflow_button
+            .as_ref()
+            .is_some_and(|bounds| contains(bounds, position));
+        if is_overflow_button_clicked {
+            let is_open = !self.is_overflow_open;
+            self.set_overflow_open(is_open, view_projection, dpr, renderer);
+            return None;
+        }
+
+        let item = self.item_at(position);
+        self.set_overflow_open(false, view_projection, dpr, renderer);
+
+        item
+    }
+
+    pub fn set_overflow_open(
+        &mut self,
+        is_open: bool,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let is_open = is_open && !self.overflow_items.is_empty();
+        if is_open != self.is_overflow_open {
+            self.is_overflow_open = is_open;
+            self.rebuild(view_projection, dpr, renderer);
+        }
+    }
+
+    pub fn set_items(
+        &mut self,
+        items: Vec<ToolbarItem>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.items = items;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: ToolbarStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.rebuild(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for block in &self.blocks {
+            block.destroy(renderer);
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        self.build(view_projection, dpr, renderer);
+    }
+
+    fn build(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let style = &self.style;
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        let item_height = height - style.padding * 2.0;
+        let clip_rect = self.bounds.clip_rect;
+
+        let (visible, overflow) = fit_items(
+            &self.items,
+            width - style.padding * 2.0,
+            style.spacing,
+            style.overflow_button_width,
+        );
+
+        let mut item_x = x + style.padding;
+        let item_y = y + style.padding;
+        self.visible_items = visible
+            .into_iter()
+            .map(|index| {
+                let item_width = self.items[index].width;
+                let bounds = Bounds {
+                    position: [item_x, item_y],
+                    size: [item_width, item_height],
+                    clip_rect,
+                };
+                item_x += item_width + style.spacing;
+                (index, bounds)
+            })
+            .collect();
+
+        // The overflow button sits at the far end, and the dropdown opens
+        // below it, aligned to its right edge.
+        self.overflow_button = (!overflow.is_empty()).then_some(Bounds {
+            position: [
+                x + width - style.padding - style.overflow_button_width,
+                item_y,
+            ],
+            size: [style.overflow_button_width, item_height],
+            clip_rect,
+        });
+        let dropdown_x = x + width - style.padding - style.dropdown_width;
+        let mut entry_y = y + height;
+        self.overflow_items = overflow
+            .into_iter()
+            .map(|index| {
+                let bounds = Bounds::without_clip_rect(
+                    [dropdown_x, entry_y],
+                    [style.dropdown_width, style.dropdown_item_height],
+                );
+                entry_y += style.dropdown_item_height;
+                (index, bounds)
+            })
+            .collect();
+        self.is_overflow_open &= !self.overflow_items.is_empty();
+
+        let mut blocks = Vec::new();
+        let mut push_block = |bounds: Bounds, block_style: BlockStyle| {
+            blocks.push(Block::<Positioned>::new(
+                bounds,
+                block_style,
+                view_projection,
+                dpr,
+                renderer,
+            ));
+        };
+
+        push_block(
+            self.bounds.clone(),
+            BlockStyle::builder()
+                .fill_color(style.background_color)
+                .build(),
+        );
+        let button_style = BlockStyle::builder()
+            .fill_color(style.button_color)
+            .corner_radii([style.corner_radius * dpr; 4])
+            .build();
+        for (index, bounds) in &self.visible_items {
+            match self.items[*index].kind {
+                ToolbarItemKind::Button => {
+                    push_block(bounds.clone(), button_style.clone());
+                }
+                ToolbarItemKind::Separator => {
+                    let line = Bounds {
+                        position: [
+                            bounds.position[0] + bounds.size[0] / 2.0
+                                - style.separator_thickness / 2.0,
+                            bounds.position[1] + bounds.size[1] * 0.2,
+                        ],
+                        size: [
+                            style.separator_thickness,
+                            bounds.size[1] * 0.6,
+                        ],
+                        clip_rect,
+                    };
+                    push_block(
+                        line,
+                        BlockStyle::builder()
+                            .fill_color(style.separator_color)
+                            .build(),
+                    );
+                }
+            }
+        }
+        if let Some(bounds) = &self.overflow_button {
+            push_block(bounds.clone(), button_style.clone());
+        }
+        if self.is_overflow_open {
+            let dropdown = Bounds::without_clip_rect(
+                [dropdown_x, y + height],
+                [style.dropdown_width, entry_y - y - height],
+            );
+            push_block(
+                dropdown,
+                BlockStyle::builder()
+                    .fill_color(style.dropdown_color)
+                    .corner_radii([style.corner_radius * dpr; 4])
+                    .shadow_color(style.dropdown_shadow_color)
+                    .shadow_offset([0.0, 2.0 * dpr])
+                    .shadow_blur(8.0 * dpr)
+                    .build(),
+            );
+        }
+        self.blocks = blocks;
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+
+    position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height
+}
+
+fn row_width(items: &[ToolbarItem], indices: &[usize], spacing: f32) -> f32 {
+    let widths: f32 = indices.iter().map(|index| items[*index].width).sum();
+    widths + spacing * indices.len().saturating_sub(1) as f32
+}
+
+// Separators only make sense between two buttons.
+fn drop_stray_separators(items: &[ToolbarItem], indices: &mut Vec<usize>) {
+    let is_separator =
+        |index: usize| items[index].kind == ToolbarItemKind::Separator;
+
+    let mut kept: Vec<usize> = Vec::with_capacity(indices.len());
+    for &index in indices.iter() {
+        let follows_separator =
+            kept.last().is_none_or(|last| is_separator(*last));
+        if is_separator(index) && follows_separator {
+            continue;
+        }
+        kept.push(index);
+    }
+    while kept.last().is_some_and(|last| is_separator(*last)) {
+        kept.pop();
+    }
+    *indices = kept;
+}
+
+// Splits items into those shown in the toolbar and buttons moved into the
+// overflow menu, both in their original order.
+fn fit_items(
+    items: &[ToolbarItem],
+    available_width: f32,
+    spacing: f32,
+    overflow_button_width: f32,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut visible: Vec<usize> = (0..items.len()).collect();
+    drop_stray_separators(items, &mut visible);
+    if row_width(items, &visible, spacing) <= available_width {
+        return (visible, Vec::new());
+    }
+
+    // Lowest priority first; among equals, the rightmost goes first.
+    let mut candidates: Vec<usize> = (0..items.len())
+        .filter(|index| items[*index].kind == ToolbarItemKind::Button)
+        .collect();
+    candidates.sort_by_key(|index| {
+        (items[*index].priority, std::cmp::Reverse(*index))
+    });
+
+    let mut overflow = Vec::new();
+    let available_width = available_width - overflow_button_width - spacing;
+    for candidate in candidates {
+        if row_width(items, &visible, spacing) <= available_width {
+            break;
+        }
+        visible.retain(|index| *index != candidate);
+        drop_stray_separators(items, &mut visible);
+        overflow.push(candidate);
+    }
+    overflow.sort_unstable();
+
+    (visible, overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ToolbarItem, fit_items};
+
+    fn items() -> Vec<ToolbarItem> {
+        vec![
+            ToolbarItem::button(30.0).with_priority(2),
+            ToolbarItem::button(30.0),
+            ToolbarItem::separator(10.0),
+            ToolbarItem::button(30.0).with_priority(1),
+            ToolbarItem::button(30.0),
+        ]
+    }
+
+    #[rstest]
+    #[case(130.0, &[0, 1, 2, 3, 4], &[])]
+    #[case(129.0, &[0, 2, 3], &[1, 4])]
+    #[case(69.0, &[0], &[1, 3, 4])]
+    #[case(10.0, &[], &[0, 1, 3, 4])]
+    fn test_fit_items(
+        #[case] available_width: f32,
+        #[case] expected_visible: &[usize],
+        #[case] expected_overflow: &[usize],
+    ) {
+        let (visible, overflow) =
+            fit_items(&items(), available_width, 0.0, 30.0);
+
+        assert_eq!(visible, expected_visible);
+        assert_eq!(overflow, expected_overflow);
+    }
+}
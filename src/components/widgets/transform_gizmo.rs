@@ -0,0 +1,583 @@
+use std::f32::consts::PI;
+
+use bon::Builder;
+use glam::Mat4;
+
+use super::{
+    Block, BlockStyle, block::build_rectangle, block_states::Positioned,
+};
+use crate::{
+    components::common::{Bar, Bounds, intersect_rects},
+    core::{RectangleId, Renderer},
+};
+
+// Scaling never shrinks the selection below this many logical pixels.
+const MIN_EXTENT: f32 = 1.0;
+
+// Sizes are in logical pixels.
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct TransformGizmoStyle {
+    #[builder(default = [0.26, 0.52, 0.96, 1.0])]
+    frame_color:            [f32; 4],
+    #[builder(default = 1.0)]
+    frame_size:             f32,
+    #[builder(default = [1.0, 1.0, 1.0, 1.0])]
+    handle_color:           [f32; 4],
+    #[builder(default = 8.0)]
+    handle_size:            f32,
+    // Distance from the top edge of the frame to the rotation handle.
+    #[builder(default = 24.0)]
+    rotation_handle_offset: f32,
+    // How far around a handle a press still grabs it.
+    #[builder(default = 4.0)]
+    hit_slop:               f32,
+    // Rotation snaps to multiples of this while uniform.
+    #[builder(default = PI / 12.0)]
+    rotation_step:          f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GizmoHandle {
+    Move,
+    // Corners clockwise from the top left, as with corner radii.
+    Scale(usize),
+    Rotate,
+}
+
+// Where a selected widget is, in logical pixels. The position and size
+// are those of the unrotated widget, which turns around its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoTarget {
+    pub position: [f32; 2],
+    pub size:     [f32; 2],
+    // radians, clockwise on screen
+    pub rotation: f32,
+}
+
+impl From<&Block<Positioned>> for GizmoTarget {
+    fn from(block: &Block<Positioned>) -> Self {
+        Self {
+            position: block.position(),
+            size:     block.size(),
+            rotation: block.rotation(),
+        }
+    }
+}
+
+// Handles around the selected widgets to move, scale and rotate them
+// together. The frame is the box around all of them; scaling and
+// rotating happen relative to it. Handles are drawn above everything
+// added before them and are re-added on every change to stay there.
+pub struct TransformGizmo {
+    // The canvas; handles are clipped to it.
+    bounds:     Bounds,
+    style:      TransformGizmoStyle,
+    targets:    Vec<GizmoTarget>,
+    gesture:    Option<Gesture>,
+    rectangles: Vec<RectangleId>,
+}
+
+struct Gesture {
+    handle:  GizmoHandle,
+    start:   [f32; 2],
+    frame:   [f32; 4],
+    targets: Vec<GizmoTarget>,
+}
+
+impl TransformGizmo {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(bounds: Bounds, style: TransformGizmoStyle) -> Self {
+        Self {
+            bounds,
+            style,
+            targets: Vec::new(),
+            gesture: None,
+            rectangles: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn targets(&self) -> &[GizmoTarget] { &self.targets }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn handle(&self) -> Option<GizmoHandle> {
+        self.gesture.as_ref().map(|gesture| gesture.handle)
+    }
+
+    // x, y, width, height of the box around the selected widgets.
+    #[must_use]
+    #[inline(always)]
+    pub fn frame(&self) -> Option<[f32; 4]> { frame(&self.targets) }
+
+    // Wraps the given widgets; `drag` transforms them in this order. An
+    // empty selection hides the gizmo.
+    pub fn select(
+        &mut self,
+        blocks: &[&Block<Positioned>],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.gesture = None;
+        self.targets = blocks.iter().map(|&block| block.into()).collect();
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    // Returns whether a handle was grabbed.
+    pub fn press(&mut self, position: [f32; 2]) -> bool {
+        let Some(frame) = self.frame() else {
+            return false;
+        };
+        let Some(handle) = hit_test(&self.style, frame, position) else {
+            return false;
+        };
+        self.gesture = Some(Gesture {
+            handle,
+            start: position,
+            frame,
+            targets: self.targets.clone(),
+        });
+        true
+    }
+
+    // `is_uniform` (shift) keeps the aspect ratio when scaling, snaps
+    // rotation to steps and moves along one axis only. `is_from_center`
+    // (alt) scales around the center rather than the opposite corner.
+    #[allow(clippy::too_many_arguments)]
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        is_uniform: bool,
+        is_from_center: bool,
+        blocks: &mut [&mut Block<Positioned>],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(gesture) = &self.gesture else {
+            return;
+        };
+        self.targets = transform(
+            gesture,
+            position,
+            is_uniform,
+            is_from_center,
+            self.style.rotation_step,
+        );
+
+        for (block, target) in blocks.iter_mut().zip(&self.targets) {
+            block.update_position(
+                target.position,
+                view_projection,
+                dpr,
+                renderer,
+            );
+            block.update_size(target.size, view_projection, dpr, renderer);
+            block.update_rotation(
+                target.rotation,
+                view_projection,
+                dpr,
+                renderer,
+            );
+        }
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    #[inline(always)]
+    pub fn release(&mut self) { self.gesture = None; }
+
+    // Puts the handles back on top after other widgets were added.
+    #[inline(always)]
+    pub fn raise(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.refresh(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&mut self, renderer: &mut Renderer) {
+        for rectangle_id in self.rectangles.drain(..) {
+            renderer.remove_rectangle(rectangle_id);
+        }
+    }
+
+    fn refresh(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.destroy(renderer);
+        let Some(frame) = self.frame() else {
+            return;
+        };
+
+        let [canvas_x, canvas_y] = self.bounds.position;
+        let [canvas_width, canvas_height] = self.bounds.size;
+        let clip_rect = intersect_rects(
+            [canvas_x, canvas_y, canvas_width, canvas_height],
+            self.bounds.clip_rect,
+        );
+        let style = &self.style;
+        let [x, y, width, _] = frame;
+
+        let top = [x + width / 2.0, y];
+        let rotation_handle = rotation_handle(style, frame);
+        let stem = Bar::between(top, rotation_handle, style.frame_size)
+            .build_rectangle(
+                style.frame_color,
+                clip_rect,
+                view_projection,
+                dpr,
+            );
+        self.rectangles.push(renderer.add_rectangle(&stem));
+
+        let frame_bounds = Bounds {
+            position: [frame[0], frame[1]],
+            size: [frame[2], frame[3]],
+            clip_rect,
+        };
+        let frame_style = BlockStyle::builder()
+            .fill_color([0.0; 4])
+            .outline_color([0.0; 4])
+            .border_color(style.frame_color)
+            .border_size(style.frame_size * dpr)
+            .build();
+        let rectangle =
+            build_rectangle(view_projection, dpr, &frame_bounds, &frame_style);
+        self.rectangles.push(renderer.add_rectangle(&rectangle));
+
+        let half = style.handle_size / 2.0;
+        let handles = corners(frame).into_iter().map(|corner| (corner, 0.0));
+        for (center, radius) in handles.chain([(rotation_handle, half)]) {
+            let handle_bounds = Bounds {
+                position: [center[0] - half, center[1] - half],
+                size: [style.handle_size; 2],
+                clip_rect,
+            };
+            let handle_style = BlockStyle::builder()
+                .fill_color(style.handle_color)
+                .outline_color([0.0; 4])
+                .border_color(style.frame_color)
+                .border_size(style.frame_size * dpr)
+                .corner_radii([radius * dpr; 4])
+                .build();
+            let rectangle = build_rectangle(
+                view_projection,
+                dpr,
+                &handle_bounds,
+                &handle_style,
+            );
+            self.rectangles.push(renderer.add_rectangle(&rectangle));
+        }
+    }
+}
+
+// The axis-aligned box around the targets, rotations included.
+fn frame(targets: &[GizmoTarget]) -> Option<[f32; 4]> {
+    let points = targets.iter().flat_map(|target| {
+        let [x, y] = target.position;
+        let [width, height] = target.size;
+        let center = [x + width / 2.0, y + height / 2.0];
+        let (sin, cos) = target.rotation.sin_cos();
+        corners([x, y, width, height]).map(|[corner_x, corner_y]| {
+            let offset = [corner_x - center[0], corner_y - center[1]];
+            [
+                center[0] + offset[0] * cos - offset[1] * sin,
+                center[1] + offset[0] * sin + offset[1] * cos,
+            ]
+        })
+    });
+
+    let mut bounds: Option<[f32; 4]> = None;
+    for [x, y] in points {
+        bounds = Some(match bounds {
+            None => [x, y, x, y],
+            Some([min_x, min_y, max_x, max_y]) => {
+                [min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)]
+            }
+        });
+    }
+    bounds.map(|[min_x, min_y, max_x, max_y]| {
+        [min_x, min_y, max_x - min_x, max_y - min_y]
+    })
+}
+
+const fn corners(rect: [f32; 4]) -> [[f32; 2]; 4] {
+    let [x, y, width, height] = rect;
+    [
+        [x, y],
+        [x + width, y],
+        [x + width, y + height],
+        [x, y + height],
+    ]
+}
+
+fn rotation_handle(style: &TransformGizmoStyle, frame: [f32; 4]) -> [f32; 2] {
+    let [x, y, width, _] = frame;
+    [x + width / 2.0, y - style.rotation_handle_offset]
+}
+
+// Handles win over the frame's inside, which moves the selection.
+fn hit_test(
+    style: &TransformGizmoStyle,
+    frame: [f32; 4],
+    position: [f32; 2],
+) -> Option<GizmoHandle> {
+    let reach = style.handle_size / 2.0 + style.hit_slop;
+    let is_near = |point: [f32; 2]| {
+        (position[0] - point[0]).abs() <= reach
+            && (position[1] - point[1]).abs() <= reach
+    };
+
+    if is_near(rotation_handle(style, frame)) {
+        return Some(GizmoHandle::Rotate);
+    }
+    if let Some(corner) = corners(frame).into_iter().position(is_near) {
+        return Some(GizmoHandle::Scale(corner));
+    }
+    let [x, y, width, height] = frame;
+    let is_inside = position[0] >= x
+        && position[0] <= x + width
+        && position[1] >= y
+        && position[1] <= y + height;
+    is_inside.then_some(GizmoHandle::Move)
+}
+
+// The targets as they were when the gesture started, transformed by
+// the pointer's travel since.
+fn transform(
+    gesture: &Gesture,
+    position: [f32; 2],
+    is_uniform: bool,
+    is_from_center: bool,
+    rotation_step: f32,
+) -> Vec<GizmoTarget> {
+    let delta = [0, 1].map(|axis| position[axis] - gesture.start[axis]);
+    let [x, y, width, height] = gesture.frame;
+    let center = [x + width / 2.0, y + height / 2.0];
+
+    match gesture.handle {
+        GizmoHandle::Move => {
+            let mut delta = delta;
+            if is_uniform {
+                let minor = usize::from(delta[0].abs() >= delta[1].abs());
+                delta[minor] = 0.0;
+            }
+            gesture
+                .targets
+                .iter()
+                .map(|target| GizmoTarget {
+                    position: [0, 1]
+                        .map(|axis| target.position[axis] + delta[axis]),
+                    ..*target
+                })
+                .collect()
+        }
+        GizmoHandle::Scale(corner) => {
+            let corners = corners(gesture.frame);
+            let grabbed = corners[corner];
+            let anchor = match is_from_center {
+                true => center,
+                false => corners[(corner + 2) % 4],
+            };
+            let mut scale = [0, 1].map(|axis| {
+                let extent = grabbed[axis] - anchor[axis];
+                if extent.abs() < f32::EPSILON {
+                    return 1.0;
+                }
+                let dragged = grabbed[axis] + delta[axis] - anchor[axis];
+                (dragged / extent).max(MIN_EXTENT / extent.abs())
+            });
+            if is_uniform {
+                scale = [scale[0].max(scale[1]); 2];
+            }
+
+            gesture
+                .targets
+                .iter()
+                .map(|target| {
+                    let size =
+                        [0, 1].map(|axis| target.size[axis] * scale[axis]);
+                    let position = [0, 1].map(|axis| {
+                        let center =
+                            target.position[axis] + target.size[axis] / 2.0;
+                        let center = anchor[axis]
+                            + (center - anchor[axis]) * scale[axis];
+                        center - size[axis] / 2.0
+                    });
+                    GizmoTarget { position, size, ..*target }
+                })
+                .collect()
+        }
+        GizmoHandle::Rotate => {
+            let angle_of = |point: [f32; 2]| {
+                (point[1] - center[1]).atan2(point[0] - center[0])
+            };
+            let mut angle = angle_of(position) - angle_of(gesture.start);
+            if is_uniform && rotation_step > 0.0 {
+                angle = (angle / rotation_step).round() * rotation_step;
+            }
+            let (sin, cos) = angle.sin_cos();
+
+            gesture
+                .targets
+                .iter()
+                .map(|target| {
+                    let offset = [0, 1].map(|axis| {
+                        target.position[axis] + target.size[axis] / 2.0
+                            - center[axis]
+                    });
+                    let target_center = [
+                        center[0] + offset[0] * cos - offset[1] * sin,
+                        center[1] + offset[0] * sin + offset[1] * cos,
+                    ];
+                    GizmoTarget {
+                        position: [0, 1].map(|axis| {
+                            target_center[axis] - target.size[axis] / 2.0
+                        }),
+                        rotation: target.rotation + angle,
+                        ..*target
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use rstest::rstest;
+
+    use super::{
+        Gesture, GizmoHandle, GizmoTarget, TransformGizmoStyle, frame,
+        hit_test, transform,
+    };
+
+    fn targets() -> Vec<GizmoTarget> {
+        vec![
+            GizmoTarget {
+                position: [0.0, 0.0],
+                size:     [20.0, 20.0],
+                rotation: 0.0,
+            },
+            GizmoTarget {
+                position: [60.0, 20.0],
+                size:     [40.0, 20.0],
+                rotation: 0.0,
+            },
+        ]
+    }
+
+    fn gesture(handle: GizmoHandle, start: [f32; 2]) -> Gesture {
+        let targets = targets();
+        let frame = frame(&targets).unwrap();
+        Gesture { handle, start, frame, targets }
+    }
+
+    fn assert_close(actual: [f32; 2], expected: [f32; 2]) {
+        assert!(
+            (actual[0] - expected[0]).abs() < 1e-4
+                && (actual[1] - expected[1]).abs() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[rstest]
+    #[case([50.0, -24.0], Some(GizmoHandle::Rotate))]
+    #[case([102.0, 38.0], Some(GizmoHandle::Scale(2)))]
+    #[case([-3.0, 41.0], Some(GizmoHandle::Scale(3)))]
+    #[case([50.0, 10.0], Some(GizmoHandle::Move))]
+    #[case([150.0, 10.0], None)]
+    fn test_hit_test(
+        #[case] position: [f32; 2],
+        #[case] expected: Option<GizmoHandle>,
+    ) {
+        let style = TransformGizmoStyle::builder().build();
+        let frame = frame(&targets()).unwrap();
+        assert_eq!(hit_test(&style, frame, position), expected);
+    }
+
+    #[rstest]
+    #[case(false, [[10.0, 4.0], [70.0, 24.0]])]
+    // along the dominant axis only
+    #[case(true, [[10.0, 0.0], [70.0, 20.0]])]
+    fn test_move(#[case] is_uniform: bool, #[case] expected: [[f32; 2]; 2]) {
+        let gesture = gesture(GizmoHandle::Move, [50.0, 10.0]);
+        let moved = transform(&gesture, [60.0, 14.0], is_uniform, false, 0.0);
+        for (target, position) in moved.iter().zip(expected) {
+            assert_close(target.position, position);
+        }
+    }
+
+    #[rstest]
+    // the top left corner stays
+    #[case(false, false, [[0.0, 0.0], [120.0, 30.0]], [[40.0, 30.0], [80.0, 30.0]])]
+    #[case(true, false, [[0.0, 0.0], [120.0, 40.0]], [[40.0, 40.0], [80.0, 40.0]])]
+    // around the center, so the travel counts from there
+    #[case(false, true, [[-100.0, -20.0], [80.0, 20.0]], [[60.0, 40.0], [120.0, 40.0]])]
+    fn test_scale(
+        #[case] is_uniform: bool,
+        #[case] is_from_center: bool,
+        #[case] positions: [[f32; 2]; 2],
+        #[case] sizes: [[f32; 2]; 2],
+    ) {
+        // The bottom right corner, dragged from 100, 40 to 200, 60.
+        let gesture = gesture(GizmoHandle::Scale(2), [100.0, 40.0]);
+        let scaled = transform(
+            &gesture,
+            [200.0, 60.0],
+            is_uniform,
+            is_from_center,
+            0.0,
+        );
+        for ((target, position), size) in
+            scaled.iter().zip(positions).zip(sizes)
+        {
+            assert_close(target.position, position);
+            assert_close(target.size, size);
+        }
+    }
+
+    #[rstest]
+    fn test_scale_keeps_a_minimal_extent() {
+        let gesture = gesture(GizmoHandle::Scale(2), [100.0, 40.0]);
+        let scaled = transform(&gesture, [-50.0, -50.0], false, false, 0.0);
+        let frame = frame(&scaled).unwrap();
+        assert_close([frame[2], frame[3]], [1.0, 1.0]);
+    }
+
+    #[rstest]
+    #[case(false, [100.0, 30.0])]
+    // snapped to a quarter turn
+    #[case(true, [98.0, 20.0])]
+    fn test_rotate(#[case] is_uniform: bool, #[case] pointer: [f32; 2]) {
+        // Around the frame's center at 50, 20, from straight above it.
+        let gesture = gesture(GizmoHandle::Rotate, [50.0, -30.0]);
+        let step = TransformGizmoStyle::builder().build().rotation_step;
+        let rotated = transform(&gesture, pointer, is_uniform, false, step);
+
+        let expected = match is_uniform {
+            true => FRAC_PI_2,
+            false => (10.0f32).atan2(50.0) + FRAC_PI_2,
+        };
+        assert!((rotated[0].rotation - expected).abs() < 1e-4);
+        if is_uniform {
+            // The first target's center goes from 10, 10 to 60, -20.
+            assert_close(rotated[0].position, [50.0, -30.0]);
+            assert_close(rotated[1].size, [40.0, 20.0]);
+        }
+    }
+}
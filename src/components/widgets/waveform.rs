@@ -0,0 +1,570 @@
+use bon::Builder;
+use glam::Mat4;
+
+use super::{BlockStyle, block::build_rectangle};
+use crate::{
+    components::common::{Bar, Bounds, intersect_rects},
+    core::{Rectangle, RectangleId, Renderer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveformKind {
+    // A bar per column with gaps in between.
+    Bars,
+    // Columns without gaps, which read as a filled outline.
+    Filled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaveformEvent {
+    // A click without a drag, at a position in buckets.
+    Seeked(f32),
+    // A drag selected this span of buckets.
+    Selected([f32; 2]),
+}
+
+#[derive(Clone, Builder)]
+#[builder(const)]
+pub struct WaveformStyle {
+    #[builder(default = WaveformKind::Filled)]
+    kind:            WaveformKind,
+    #[builder(default = [0.35, 0.65, 1.0, 1.0])]
+    color:           [f32; 4],
+    #[builder(default = 2.0)]
+    column_width:    f32,
+    #[builder(default = 1.0)]
+    bar_gap:         f32,
+    #[builder(default = [0.35, 0.65, 1.0, 0.25])]
+    selection_color: [f32; 4],
+    #[builder(default = [0.95, 0.95, 0.95, 1.0])]
+    playhead_color:  [f32; 4],
+    #[builder(default = 1.5)]
+    playhead_width:  f32,
+    // The narrowest view, in buckets, zooming in stops at.
+    #[builder(default = 8.0)]
+    min_span:        f32,
+}
+
+// An audio waveform over min/max sample buckets in -1..=1, drawn as one
+// rectangle per column, so the width over `column_width` has to stay
+// within the rectangle buffer. Zoomed out, each column merges the
+// buckets under it from a pyramid of halved resolutions, whose levels
+// are only built once a zoom first needs them. Positions, the view, the
+// selection and the playhead are all in buckets.
+pub struct Waveform {
+    bounds:       Bounds,
+    style:        WaveformStyle,
+    // The buckets, then each level merging pairs of the one before.
+    levels:       Vec<Vec<[f32; 2]>>,
+    view:         [f32; 2],
+    selection:    Option<[f32; 2]>,
+    playhead:     Option<f32>,
+    columns:      Vec<RectangleId>,
+    selection_id: RectangleId,
+    playhead_id:  RectangleId,
+    drag:         Option<Drag>,
+    events:       Vec<WaveformEvent>,
+}
+
+struct Drag {
+    anchor:     f32,
+    is_dragged: bool,
+}
+
+impl Waveform {
+    #[must_use]
+    pub fn new(
+        bounds: Bounds,
+        buckets: Vec<[f32; 2]>,
+        style: WaveformStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) -> Self {
+        let view = [0.0, buckets.len().max(1) as f32];
+        let mut waveform = Self {
+            bounds,
+            style,
+            levels: vec![buckets],
+            view,
+            selection: None,
+            playhead: None,
+            columns: Vec::new(),
+            selection_id: RectangleId::default(),
+            playhead_id: RectangleId::default(),
+            drag: None,
+            events: Vec::new(),
+        };
+        waveform.sync(view_projection, dpr, renderer);
+        waveform
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bounds(&self) -> &Bounds { &self.bounds }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn view(&self) -> [f32; 2] { self.view }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn selection(&self) -> Option<[f32; 2]> { self.selection }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn playhead(&self) -> Option<f32> { self.playhead }
+
+    #[must_use]
+    pub fn bucket_count(&self) -> usize { self.levels[0].len() }
+
+    #[must_use]
+    pub fn take_events(&mut self) -> Vec<WaveformEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Replaces the buckets and shows all of them.
+    pub fn set_buckets(
+        &mut self,
+        buckets: Vec<[f32; 2]>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.view = [0.0, buckets.len().max(1) as f32];
+        self.levels = vec![buckets];
+        self.selection = None;
+        self.sync(view_projection, dpr, renderer);
+    }
+
+    pub fn set_view(
+        &mut self,
+        view: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.view =
+            clamp_view(view, self.bucket_count() as f32, self.style.min_span);
+        self.sync(view_projection, dpr, renderer);
+    }
+
+    // Zooms by `factor` (above 1 zooms in), keeping the bucket under
+    // `anchor_x` in place.
+    pub fn zoom_at(
+        &mut self,
+        anchor_x: f32,
+        factor: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let anchor = self.bucket_at(anchor_x);
+        let [start, end] = self.view;
+        let factor = factor.max(f32::EPSILON);
+        let view = [
+            anchor - (anchor - start) / factor,
+            anchor + (end - anchor) / factor,
+        ];
+        self.set_view(view, view_projection, dpr, renderer);
+    }
+
+    pub fn scroll_by(
+        &mut self,
+        delta_x: f32,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let [start, end] = self.view;
+        let delta = delta_x / self.bounds.size[0].max(1.0) * (end - start);
+        self.set_view(
+            [start + delta, end + delta],
+            view_projection,
+            dpr,
+            renderer,
+        );
+    }
+
+    pub fn set_selection(
+        &mut self,
+        selection: Option<[f32; 2]>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.selection =
+            selection.map(|[from, to]| [from.min(to), from.max(to)]);
+        let rectangle = self.build_selection(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_rectangle(self.selection_id) {
+            *instance = rectangle;
+        }
+    }
+
+    pub fn set_playhead(
+        &mut self,
+        playhead: Option<f32>,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.playhead = playhead;
+        let rectangle = self.build_playhead(view_projection, dpr);
+        if let Some(instance) = renderer.get_mut_rectangle(self.playhead_id) {
+            *instance = rectangle;
+        }
+    }
+
+    // Returns whether the press landed on the waveform.
+    pub fn press(&mut self, position: [f32; 2]) -> bool {
+        if !contains(&self.bounds, position) {
+            return false;
+        }
+        self.drag = Some(Drag {
+            anchor:     self.bucket_at(position[0]),
+            is_dragged: false,
+        });
+
+        true
+    }
+
+    pub fn drag(
+        &mut self,
+        position: [f32; 2],
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(drag) = &mut self.drag else { return };
+        drag.is_dragged = true;
+        let anchor = drag.anchor;
+        let bucket = self
+            .bucket_at(position[0])
+            .clamp(0.0, self.bucket_count() as f32);
+        self.set_selection(
+            Some([anchor, bucket]),
+            view_projection,
+            dpr,
+            renderer,
+        );
+    }
+
+    pub fn release(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let Some(drag) = self.drag.take() else { return };
+        match (drag.is_dragged, self.selection) {
+            (true, Some(selection)) if selection[1] > selection[0] => {
+                self.events.push(WaveformEvent::Selected(selection));
+            }
+            _ => {
+                self.set_selection(None, view_projection, dpr, renderer);
+                self.events.push(WaveformEvent::Seeked(drag.anchor));
+            }
+        }
+    }
+
+    pub fn update_bounds(
+        &mut self,
+        bounds: Bounds,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.bounds = bounds;
+        self.sync(view_projection, dpr, renderer);
+    }
+
+    pub fn update_style(
+        &mut self,
+        style: WaveformStyle,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        self.style = style;
+        self.sync(view_projection, dpr, renderer);
+    }
+
+    pub fn destroy(&self, renderer: &mut Renderer) {
+        for rectangle_id in &self.columns {
+            renderer.remove_rectangle(*rectangle_id);
+        }
+        renderer.remove_rectangle(self.selection_id);
+        renderer.remove_rectangle(self.playhead_id);
+    }
+
+    #[must_use]
+    pub fn bucket_at(&self, x: f32) -> f32 {
+        let [start, end] = self.view;
+        let fraction =
+            (x - self.bounds.position[0]) / self.bounds.size[0].max(1.0);
+        start + fraction * (end - start)
+    }
+
+    fn x_at(&self, bucket: f32) -> f32 {
+        let [start, end] = self.view;
+        let fraction = (bucket - start) / (end - start).max(f32::EPSILON);
+        self.bounds.position[0] + fraction * self.bounds.size[0]
+    }
+
+    // Builds the pyramid levels up to `level`, or as far as it goes.
+    fn ensure_level(&mut self, level: usize) {
+        while self.levels.len() <= level {
+            let last = &self.levels[self.levels.len() - 1];
+            if last.len() <= 1 {
+                break;
+            }
+            let merged = merge_pairs(last);
+            self.levels.push(merged);
+        }
+    }
+
+    // Rewrites every column, adding or removing them to match the width.
+    fn sync(
+        &mut self,
+        view_projection: &Mat4,
+        dpr: f32,
+        renderer: &mut Renderer,
+    ) {
+        let count = column_count(self.bounds.size[0], self.style.column_width);
+        let [start, end] = self.view;
+        self.ensure_level(pick_level((end - start) / count.max(1) as f32));
+
+        for rectangle_id in self.columns.drain(count.min(self.columns.len())..)
+        {
+            renderer.remove_rectangle(rectangle_id);
+        }
+        let mut is_grown = false;
+        for column in 0..count {
+            let rectangle =
+                self.build_column(column, count, view_projection, dpr);
+            match self.columns.get(column) {
+                Some(rectangle_id) => {
+                    if let Some(instance) =
+                        renderer.get_mut_rectangle(*rectangle_id)
+                    {
+                        *instance = rectangle;
+                    }
+                }
+                None => {
+                    self.columns.push(renderer.add_rectangle(&rectangle));
+                    is_grown = true;
+                }
+            }
+        }
+
+        // Keeps the selection and the playhead drawn above new columns.
+        let selection = self.build_selection(view_projection, dpr);
+        let playhead = self.build_playhead(view_projection, dpr);
+        match is_grown || self.selection_id == RectangleId::default() {
+            true => {
+                renderer.remove_rectangle(self.selection_id);
+                renderer.remove_rectangle(self.playhead_id);
+                self.selection_id = renderer.add_rectangle(&selection);
+                self.playhead_id = renderer.add_rectangle(&playhead);
+            }
+            false => {
+                if let Some(instance) =
+                    renderer.get_mut_rectangle(self.selection_id)
+                {
+                    *instance = selection;
+                }
+                if let Some(instance) =
+                    renderer.get_mut_rectangle(self.playhead_id)
+                {
+                    *instance = playhead;
+                }
+            }
+        }
+    }
+
+    fn clip_rect(&self) -> [f32; 4] {
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        intersect_rects(self.bounds.clip_rect, [x, y, width, height])
+    }
+
+    fn build_column(
+        &self,
+        column: usize,
+        count: usize,
+        view_projection: &Mat4,
+        dpr: f32,
+    ) -> Rectangle {
+        let [from, to] = column_buckets(self.view, column, count);
+        let [x, y] = self.bounds.position;
+        let [width, height] = self.bounds.size;
+        let slot = width / count.max(1) as f32;
+        let gap = match self.style.kind {
+            WaveformKind::Bars => self.style.bar_gap.min(slot),
+            WaveformKind::Filled => 0.0,
+        };
+        let (top, size) = match bin(&self.levels, from, to) {
+            Some([min, max]) => {
+                let to_y = |value: f32| {
+                    y + height * (1.0 - value.clamp(-1.0, 1.0)) / 2.0
+                };
+                // Silence still shows as a line.
+                let size = (to_y(min) - to_y(max)).max(1.0);
+                (to_y(max).min(y + height - size), size)
+            }
+            None => (y, 0.0),
+        };
+        let bounds = Bounds {
+            position:  [x + column as f32 * slot + gap / 2.0, top],
+            size:      [slot - gap, size],
+            clip_rect: self.clip_rect(),
+        };
+        let style = BlockStyle::builder().fill_color(self.style.color).build();
+        build_rectangle(view_projection, dpr, &bounds, &style)
+    }
+
+    fn build_selection(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let [from, to] = self.selection.unwrap_or([0.0, 0.0]);
+        let [left, right] = [self.x_at(from), self.x_at(to)];
+        let bounds = Bounds {
+            position:  [left, self.bounds.position[1]],
+            size:      [right - left, self.bounds.size[1]],
+            clip_rect: self.clip_rect(),
+        };
+        let style = BlockStyle::builder()
+            .fill_color(self.style.selection_color)
+            .build();
+        build_rectangle(view_projection, dpr, &bounds, &style)
+    }
+
+    fn build_playhead(&self, view_projection: &Mat4, dpr: f32) -> Rectangle {
+        let [_, y] = self.bounds.position;
+        let height = self.bounds.size[1];
+        let (x, color) = match self.playhead {
+            Some(playhead) => (self.x_at(playhead), self.style.playhead_color),
+            None => (self.bounds.position[0], [0.0; 4]),
+        };
+        Bar::between([x, y], [x, y + height], self.style.playhead_width)
+            .build_rectangle(color, self.clip_rect(), view_projection, dpr)
+    }
+}
+
+const fn contains(bounds: &Bounds, position: [f32; 2]) -> bool {
+    let [x, y] = bounds.position;
+    let [width, height] = bounds.size;
+    position[0] >= x
+        && position[0] < x + width
+        && position[1] >= y
+        && position[1] < y + height
+}
+
+fn column_count(width: f32, column_width: f32) -> usize {
+    (width / column_width.max(1.0)).floor().max(0.0) as usize
+}
+
+fn clamp_view(view: [f32; 2], bucket_count: f32, min_span: f32) -> [f32; 2] {
+    let max_span = bucket_count.max(1.0);
+    let span = (view[1] - view[0]).clamp(min_span.min(max_span), max_span);
+    let start = view[0].clamp(0.0, max_span - span);
+    [start, start + span]
+}
+
+// The span of buckets under a column.
+fn column_buckets(view: [f32; 2], column: usize, count: usize) -> [f32; 2] {
+    let step = (view[1] - view[0]) / count.max(1) as f32;
+    let from = view[0] + step * column as f32;
+    [from, from + step]
+}
+
+// The coarsest level whose buckets still fit within a column.
+fn pick_level(buckets_per_column: f32) -> usize {
+    match buckets_per_column >= 2.0 {
+        true => buckets_per_column.log2().floor() as usize,
+        false => 0,
+    }
+}
+
+fn merge_pairs(buckets: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    buckets
+        .chunks(2)
+        .map(|pair| merge(pair.iter().copied()).unwrap_or([0.0, 0.0]))
+        .collect()
+}
+
+fn merge(buckets: impl Iterator<Item = [f32; 2]>) -> Option<[f32; 2]> {
+    buckets.reduce(|[min, max], [other_min, other_max]| {
+        [min.min(other_min), max.max(other_max)]
+    })
+}
+
+// The min and max over the buckets from `from` to `to`, read from the
+// coarsest built level that fits; `None` past the end.
+fn bin(levels: &[Vec<[f32; 2]>], from: f32, to: f32) -> Option<[f32; 2]> {
+    let level = pick_level(to - from).min(levels.len() - 1);
+    let buckets = &levels[level];
+    let scale = (1usize << level) as f32;
+    let first = (from / scale).floor().max(0.0) as usize;
+    let last = ((to / scale).ceil() as usize)
+        .max(first + 1)
+        .min(buckets.len());
+    buckets
+        .get(first..last)
+        .and_then(|buckets| merge(buckets.iter().copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{bin, clamp_view, merge_pairs, pick_level};
+
+    #[rstest]
+    #[case(0.5, 0)]
+    #[case(3.9, 1)]
+    #[case(4.0, 2)]
+    fn test_pick_level(
+        #[case] buckets_per_column: f32,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(pick_level(buckets_per_column), expected);
+    }
+
+    #[rstest]
+    #[case(&[[-0.5, 0.1], [-0.2, 0.8]], &[[-0.5, 0.8]])]
+    // An odd bucket out is kept as it is.
+    #[case(
+        &[[-0.5, 0.1], [-0.2, 0.8], [-0.9, 0.3]],
+        &[[-0.5, 0.8], [-0.9, 0.3]]
+    )]
+    #[case(&[], &[])]
+    fn test_merge_pairs(
+        #[case] buckets: &[[f32; 2]],
+        #[case] expected: &[[f32; 2]],
+    ) {
+        assert_eq!(merge_pairs(buckets), expected);
+    }
+
+    #[rstest]
+    #[case(0.0, 1.0, Some([-0.1, 0.1]))]
+    #[case(1.5, 1.7, Some([-0.2, 0.4]))]
+    #[case(0.0, 4.0, Some([-0.8, 0.4]))]
+    #[case(4.0, 5.0, None)]
+    fn test_bin(
+        #[case] from: f32,
+        #[case] to: f32,
+        #[case] expected: Option<[f32; 2]>,
+    ) {
+        let buckets = vec![[-0.1, 0.1], [-0.2, 0.4], [-0.8, 0.0], [0.0, 0.2]];
+        let levels = vec![buckets.clone(), merge_pairs(&buckets)];
+        assert_eq!(bin(&levels, from, to), expected);
+    }
+
+    #[rstest]
+    #[case([-10.0, 40.0], [0.0, 50.0])]
+    #[case([90.0, 120.0], [70.0, 100.0])]
+    // Zooming in stops at the narrowest span.
+    #[case([10.0, 11.0], [10.0, 18.0])]
+    fn test_clamp_view(#[case] view: [f32; 2], #[case] expected: [f32; 2]) {
+        assert_eq!(clamp_view(view, 100.0, 8.0), expected);
+    }
+}
@@ -0,0 +1,315 @@
+use glam::Mat4;
+
+use super::{
+    Autocomplete, Block, Button, Knob, ScrollView, StyledBlock, TextInput,
+    block_states::Positioned,
+};
+use crate::{
+    components::common::{Bounds, InputState},
+    core::Renderer,
+};
+
+// What a widget draws with. Rectangles, arcs, images and layers are
+// added to and changed on `renderer` directly, in physical pixels, i.e.
+// logical ones times `dpr`; a layer redraws whenever what's in it
+// changes.
+pub struct WidgetContext<'a> {
+    pub renderer:        &'a mut Renderer,
+    pub view_projection: &'a Mat4,
+    pub dpr:             f32,
+}
+
+impl<'a> WidgetContext<'a> {
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(
+        renderer: &'a mut Renderer,
+        view_projection: &'a Mat4,
+        dpr: f32,
+    ) -> Self {
+        Self { renderer, view_projection, dpr }
+    }
+
+    // Clips what's added until the matching `pop_clip`, e.g. children of
+    // a container. `clip_rect` is in logical pixels.
+    #[inline(always)]
+    pub fn push_clip(&mut self, clip_rect: [f32; 4]) {
+        self.renderer
+            .push_clip(clip_rect.map(|value| value * self.dpr));
+    }
+
+    #[inline(always)]
+    pub fn pop_clip(&mut self) { self.renderer.pop_clip(); }
+}
+
+// Whether an input widget takes input. Read-only widgets still show
+// their value as usual, and loading ones show that it's on its way;
+// neither reacts to input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Availability {
+    #[default]
+    Enabled,
+    Disabled,
+    ReadOnly,
+    Loading,
+}
+
+impl Availability {
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_enabled(self) -> bool { matches!(self, Self::Enabled) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessRole {
+    Generic,
+    Group,
+    Button,
+    Label,
+    Image,
+    TextInput,
+    Slider,
+    ScrollView,
+}
+
+// What a screen reader learns about a widget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    pub role:         AccessRole,
+    pub label:        String,
+    pub bounds:       Bounds,
+    pub is_focusable: bool,
+    pub is_disabled:  bool,
+    pub is_read_only: bool,
+    // Loading, so its value may still change on its own.
+    pub is_busy:      bool,
+}
+
+// The interface for widgets built outside of hui, so that containers and
+// apps can lay out, draw and route input to them like to their own.
+// Widgets write to the renderer as they change, as hui's do, and use
+// `paint` only for what they defer until the frame is drawn.
+pub trait Widget {
+    // The size the widget takes in logical pixels, given what's
+    // `available`; all of it unless the widget knows better.
+    fn desired_size(&self, available: [f32; 2]) -> [f32; 2] { available }
+
+    fn layout(&mut self, bounds: Bounds, context: &mut WidgetContext);
+
+    fn paint(&mut self, _context: &mut WidgetContext) {}
+
+    // Called with each event synced into `input_state`. Returns whether
+    // the widget changed; consume the event to keep it from the widgets
+    // behind.
+    fn event(
+        &mut self,
+        _input_state: &mut InputState,
+        _context: &mut WidgetContext,
+    ) -> bool {
+        false
+    }
+
+    // None for widgets that are only decoration.
+    fn accessibility(&self) -> Option<AccessNode> { None }
+
+    fn destroy(&mut self, renderer: &mut Renderer);
+}
+
+// Input widgets that can be disabled, made read-only or shown as loading,
+// so that a form sets the state of each the same way.
+pub trait HasAvailability {
+    fn availability(&self) -> Availability;
+
+    // Returns whether the availability changed.
+    fn set_availability(
+        &mut self,
+        availability: Availability,
+        context: &mut WidgetContext,
+    ) -> bool;
+
+    // The widget's node with its availability as disabled, read-only or
+    // busy, for widgets that aren't laid out as a `Widget`, too.
+    fn access_node(&self) -> AccessNode;
+}
+
+impl Widget for Block<Positioned> {
+    fn layout(&mut self, bounds: Bounds, context: &mut WidgetContext) {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        self.update_size(bounds.size, view_projection, *dpr, renderer);
+        self.update_position(bounds.position, view_projection, *dpr, renderer);
+        self.update_clip_rect(&bounds.clip_rect, renderer);
+    }
+
+    #[inline(always)]
+    fn destroy(&mut self, renderer: &mut Renderer) {
+        Block::destroy(self, renderer);
+    }
+}
+
+impl Widget for StyledBlock {
+    fn layout(&mut self, bounds: Bounds, context: &mut WidgetContext) {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        self.update_bounds(bounds, view_projection, *dpr, renderer);
+    }
+
+    fn event(
+        &mut self,
+        input_state: &mut InputState,
+        context: &mut WidgetContext,
+    ) -> bool {
+        self.sync_pointer(input_state, context.renderer)
+    }
+
+    #[inline(always)]
+    fn destroy(&mut self, renderer: &mut Renderer) {
+        StyledBlock::destroy(self, renderer);
+    }
+}
+
+impl Widget for Button {
+    fn layout(&mut self, bounds: Bounds, context: &mut WidgetContext) {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        self.update_bounds(bounds, view_projection, *dpr, renderer);
+    }
+
+    fn event(
+        &mut self,
+        input_state: &mut InputState,
+        context: &mut WidgetContext,
+    ) -> bool {
+        self.sync_input(input_state, context.renderer)
+    }
+
+    #[inline(always)]
+    fn accessibility(&self) -> Option<AccessNode> {
+        Some(Button::accessibility(self))
+    }
+
+    #[inline(always)]
+    fn destroy(&mut self, renderer: &mut Renderer) {
+        Button::destroy(self, renderer);
+    }
+}
+
+impl Widget for ScrollView {
+    fn layout(&mut self, bounds: Bounds, context: &mut WidgetContext) {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        self.set_bounds(bounds, view_projection, *dpr, renderer);
+    }
+
+    fn event(
+        &mut self,
+        input_state: &mut InputState,
+        context: &mut WidgetContext,
+    ) -> bool {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        self.sync_input(input_state, view_projection, *dpr, renderer)
+    }
+
+    fn accessibility(&self) -> Option<AccessNode> {
+        Some(AccessNode {
+            role:         AccessRole::ScrollView,
+            label:        String::new(),
+            bounds:       self.bounds().clone(),
+            is_focusable: false,
+            is_disabled:  false,
+            is_read_only: false,
+            is_busy:      false,
+        })
+    }
+
+    #[inline(always)]
+    fn destroy(&mut self, renderer: &mut Renderer) { self.clear(renderer); }
+}
+
+impl HasAvailability for Button {
+    #[inline(always)]
+    fn availability(&self) -> Availability { self.state().availability() }
+
+    fn set_availability(
+        &mut self,
+        availability: Availability,
+        context: &mut WidgetContext,
+    ) -> bool {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        Button::set_availability(
+            self,
+            availability,
+            view_projection,
+            *dpr,
+            renderer,
+        )
+    }
+
+    #[inline(always)]
+    fn access_node(&self) -> AccessNode { Button::accessibility(self) }
+}
+
+impl HasAvailability for TextInput {
+    #[inline(always)]
+    fn availability(&self) -> Availability { TextInput::availability(self) }
+
+    fn set_availability(
+        &mut self,
+        availability: Availability,
+        context: &mut WidgetContext,
+    ) -> bool {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        TextInput::set_availability(
+            self,
+            availability,
+            view_projection,
+            *dpr,
+            renderer,
+        )
+    }
+
+    #[inline(always)]
+    fn access_node(&self) -> AccessNode { TextInput::accessibility(self) }
+}
+
+impl HasAvailability for Autocomplete {
+    #[inline(always)]
+    fn availability(&self) -> Availability { Autocomplete::availability(self) }
+
+    fn set_availability(
+        &mut self,
+        availability: Availability,
+        context: &mut WidgetContext,
+    ) -> bool {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        Autocomplete::set_availability(
+            self,
+            availability,
+            view_projection,
+            *dpr,
+            renderer,
+        )
+    }
+
+    #[inline(always)]
+    fn access_node(&self) -> AccessNode { Autocomplete::accessibility(self) }
+}
+
+impl HasAvailability for Knob {
+    #[inline(always)]
+    fn availability(&self) -> Availability { Knob::availability(self) }
+
+    fn set_availability(
+        &mut self,
+        availability: Availability,
+        context: &mut WidgetContext,
+    ) -> bool {
+        let WidgetContext { renderer, view_projection, dpr } = context;
+        Knob::set_availability(
+            self,
+            availability,
+            view_projection,
+            *dpr,
+            renderer,
+        )
+    }
+
+    #[inline(always)]
+    fn access_node(&self) -> AccessNode { Knob::accessibility(self) }
+}
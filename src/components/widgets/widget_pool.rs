@@ -0,0 +1,121 @@
+use super::{Block, StyledBlock, block_states::Positioned};
+use crate::{components::style::InteractionState, core::Renderer};
+
+// A widget that can wait in a `WidgetPool` between uses. Parked widgets
+// keep their rectangles, so reusing one neither allocates nor moves
+// anything in the renderer's instance buffers.
+pub trait Recyclable {
+    // Hides the widget until it is reused.
+    fn park(&mut self, renderer: &mut Renderer);
+
+    fn destroy(&self, renderer: &mut Renderer);
+}
+
+impl Recyclable for Block<Positioned> {
+    fn park(&mut self, renderer: &mut Renderer) {
+        self.update_clip_rect(&[0.0; 4], renderer);
+        self.set_tag(None);
+    }
+
+    #[inline(always)]
+    fn destroy(&self, renderer: &mut Renderer) { self.destroy(renderer); }
+}
+
+impl Recyclable for StyledBlock {
+    fn park(&mut self, renderer: &mut Renderer) {
+        self.set_state(InteractionState::default(), renderer);
+        self.update_clip_rect(&[0.0; 4], renderer);
+        self.set_tag(None);
+    }
+
+    #[inline(always)]
+    fn destroy(&self, renderer: &mut Renderer) { self.destroy(renderer); }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub created:   u64,
+    pub reused:    u64,
+    pub released:  u64,
+    // Released while the pool was full, or dropped by `shrink`.
+    pub destroyed: u64,
+    pub idle:      usize,
+    pub peak_idle: usize,
+}
+
+// Keeps released widgets for reuse in places that create and drop many
+// of the same kind, such as the rows of a scrolling list or the nodes of
+// a graph. At most `max_idle` wait in the pool; the rest are destroyed.
+pub struct WidgetPool<W> {
+    idle:     Vec<W>,
+    max_idle: usize,
+    stats:    PoolStats,
+}
+
+impl<W: Recyclable> WidgetPool<W> {
+    #[must_use]
+    pub fn new(max_idle: usize) -> Self {
+        Self { idle: Vec::new(), max_idle, stats: PoolStats::default() }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn max_idle(&self) -> usize { self.max_idle }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn stats(&self) -> PoolStats {
+        PoolStats { idle: self.idle.len(), ..self.stats }
+    }
+
+    // Takes an idle widget and gives it to `reuse`, which has to restyle
+    // and reposition it, bounds and clip rect included. Without one idle,
+    // `create` builds a new one.
+    pub fn acquire(
+        &mut self,
+        renderer: &mut Renderer,
+        create: impl FnOnce(&mut Renderer) -> W,
+        reuse: impl FnOnce(&mut W, &mut Renderer),
+    ) -> W {
+        match self.idle.pop() {
+            Some(mut widget) => {
+                reuse(&mut widget, renderer);
+                self.stats.reused += 1;
+                widget
+            }
+            None => {
+                self.stats.created += 1;
+                create(renderer)
+            }
+        }
+    }
+
+    pub fn release(&mut self, mut widget: W, renderer: &mut Renderer) {
+        self.stats.released += 1;
+        if self.idle.len() >= self.max_idle {
+            widget.destroy(renderer);
+            self.stats.destroyed += 1;
+            return;
+        }
+        widget.park(renderer);
+        self.idle.push(widget);
+        self.stats.peak_idle = self.stats.peak_idle.max(self.idle.len());
+    }
+
+    // Destroys idle widgets beyond `max_idle`, e.g. once a long list was
+    // closed.
+    pub fn shrink(&mut self, max_idle: usize, renderer: &mut Renderer) {
+        self.max_idle = max_idle;
+        while self.idle.len() > max_idle {
+            if let Some(widget) = self.idle.pop() {
+                widget.destroy(renderer);
+                self.stats.destroyed += 1;
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self, renderer: &mut Renderer) {
+        self.shrink(0, renderer);
+    }
+}
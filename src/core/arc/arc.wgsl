@@ -0,0 +1,112 @@
+const TAU: f32 = 6.28318530718;
+
+struct VertexInput {
+    @location(0) position:      vec3<f32>,
+    @location(1) mvp_0:         vec4<f32>,
+    @location(2) mvp_1:         vec4<f32>,
+    @location(3) mvp_2:         vec4<f32>,
+    @location(4) mvp_3:         vec4<f32>,
+    @location(5) color:         vec4<f32>,
+    @location(6) clip_rect:     vec4<f32>,
+    @location(7) geometry:      vec4<f32>,
+    @location(8) size_and_caps: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position)              clip_position:  vec4<f32>,
+    @location(0)                    local_position: vec2<f32>,
+    @location(1) @interpolate(flat) color:          vec4<f32>,
+    @location(2) @interpolate(flat) clip_rect:      vec4<f32>,
+    @location(3) @interpolate(flat) geometry:       vec4<f32>,
+    @location(4) @interpolate(flat) round_caps:     f32,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    let mvp = mat4x4<f32>(
+        input.mvp_0,
+        input.mvp_1,
+        input.mvp_2,
+        input.mvp_3,
+    );
+    let half_size = input.size_and_caps.x;
+
+    var output: VertexOutput;
+    output.clip_position  = mvp * vec4<f32>(input.position, 1.0);
+    output.local_position = input.position.xy * half_size;
+    output.color          = input.color;
+    output.clip_rect      = input.clip_rect;
+    output.geometry       = input.geometry;
+    output.round_caps     = input.size_and_caps.y;
+
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let clip = input.clip_rect;
+    let pos  = input.clip_position.xy;
+
+    if pos.x < clip.x
+        || pos.x > clip.x + clip.z
+        || pos.y < clip.y
+        || pos.y > clip.y + clip.w
+    { discard; }
+
+    let distance = sd_arc(
+        input.local_position,
+        input.geometry.x,
+        input.geometry.y * 0.5,
+        input.geometry.z,
+        input.geometry.w,
+        input.round_caps > 0.5,
+    );
+    let aa_width = fwidth(distance) * 1.4;
+    let alpha    = 1.0 - smoothstep(0.0, aa_width, distance);
+
+    return vec4<f32>(input.color.rgb, input.color.a * alpha);
+}
+
+// Within the sweep the distance is to the ring; past it, to the nearest
+// end, which is a disc for round caps and a radial segment otherwise.
+fn sd_arc(
+    p: vec2<f32>,
+    radius: f32,
+    half_thickness: f32,
+    start: f32,
+    sweep: f32,
+    round_caps: bool,
+) -> f32 {
+    let ring = abs(length(p) - radius) - half_thickness;
+    if sweep >= TAU {
+        return ring;
+    }
+
+    var angle = atan2(p.y, p.x) - start;
+    angle = angle - floor(angle / TAU) * TAU;
+    if angle <= sweep {
+        return ring;
+    }
+
+    let start_direction = vec2<f32>(cos(start), sin(start));
+    let end_direction   = vec2<f32>(cos(start + sweep), sin(start + sweep));
+    if round_caps {
+        let to_start = length(p - start_direction * radius);
+        let to_end   = length(p - end_direction * radius);
+        return min(to_start, to_end) - half_thickness;
+    }
+
+    let inner = radius - half_thickness;
+    let outer = radius + half_thickness;
+    return min(
+        sd_segment(p, start_direction * inner, start_direction * outer),
+        sd_segment(p, end_direction * inner, end_direction * outer),
+    );
+}
+
+fn sd_segment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h  = clamp(dot(pa, ba) / dot(ba, ba), 0.0, 1.0);
+    return length(pa - ba * h);
+}
@@ -0,0 +1,45 @@
+use bon::Builder;
+use bytemuck::{Pod, Zeroable};
+use slotmap::DefaultKey;
+use wgpu::{VertexBufferLayout, VertexStepMode, vertex_attr_array};
+
+pub type ArcSegmentId = DefaultKey;
+
+// A stroked circular arc. Angles are in radians, clockwise in screen
+// space from the positive x axis.
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Zeroable, Pod, Builder)]
+#[builder(const)]
+pub struct ArcSegment {
+    pub mvp:           [[f32; 4]; 4],
+    pub color:         [f32; 4],
+    // x, y, width, height in physical pixels
+    pub clip_rect:     [f32; 4],
+    // radius, thickness, start_angle, sweep_angle; lengths in physical
+    // pixels
+    pub geometry:      [f32; 4],
+    // half_size, round_caps, unused, unused
+    pub size_and_caps: [f32; 4],
+}
+
+impl ArcSegment {
+    pub(crate) const LAYOUT: VertexBufferLayout<'static> = {
+        let instance_buffer_atributes = &vertex_attr_array![
+            1 => Float32x4, // mvp matrix, row 0
+            2 => Float32x4, // mvp matrix, row 1
+            3 => Float32x4, // mvp matrix, row 2
+            4 => Float32x4, // mvp matrix, row 3
+            5 => Float32x4, // color
+            6 => Float32x4, // clip rect
+            7 => Float32x4, // radius, thickness, start_angle, sweep_angle
+            8 => Float32x4, // half_size, round_caps
+        ];
+        VertexBufferLayout {
+            array_stride: Self::SIZE as u64,
+            step_mode:    VertexStepMode::Instance,
+            attributes:   instance_buffer_atributes,
+        }
+    };
+    pub const SIZE: usize = size_of::<Self>();
+}
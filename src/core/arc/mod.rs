@@ -0,0 +1,5 @@
+mod arc_segment;
+mod renderer;
+
+pub use arc_segment::{ArcSegment, ArcSegmentId};
+pub use renderer::ArcRenderer;
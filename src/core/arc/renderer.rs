@@ -0,0 +1,293 @@
+use slotmap::SlotMap;
+use wgpu::{
+    BlendComponent, BlendFactor, BlendOperation, BlendState, Buffer,
+    BufferUsages, ColorTargetState, ColorWrites, Device, FragmentState,
+    FrontFace, IndexFormat, MultisampleState, PipelineLayoutDescriptor,
+    PolygonMode, PrimitiveState, PrimitiveTopology, Queue, RenderPass,
+    RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderSource, TextureFormat, VertexBufferLayout, VertexState,
+    VertexStepMode,
+    util::{BufferInitDescriptor, DeviceExt},
+    vertex_attr_array,
+};
+
+use super::{ArcSegment, ArcSegmentId};
+use crate::{
+    core::{InstanceBuffer, PhaseOrder, RenderPhase, capture::FrameRecorder},
+    instrument::{counter, span},
+};
+
+#[rustfmt::skip]
+const VERTICES: &[[f32; 3]; 4] = &[
+    [-1.0,  1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [ 1.0,  1.0, 0.0],
+    [ 1.0, -1.0, 0.0],
+];
+#[rustfmt::skip]
+const INDICES: &[u16; 6] = &[
+    1, 0, 2,
+    1, 3, 2,
+];
+
+pub struct ArcRenderer {
+    render_pipeline: RenderPipeline,
+    vertex_buffer:   Buffer,
+    index_buffer:    Buffer,
+    instance_buffer: InstanceBuffer,
+    arcs:            SlotMap<ArcSegmentId, ArcSegment>,
+    arc_order:       PhaseOrder<ArcSegmentId>,
+    instance_bytes:  Vec<u8>,
+}
+
+impl ArcRenderer {
+    #[must_use]
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let render_pipeline = create_render_pipeline(device, surface_format);
+
+        let vertex_buffer_desc = BufferInitDescriptor {
+            label:    Some("hui::arc::vertex_buffer"),
+            contents: bytemuck::cast_slice(VERTICES),
+            usage:    BufferUsages::VERTEX,
+        };
+        let vertex_buffer = device.create_buffer_init(&vertex_buffer_desc);
+
+        let index_buffer_desc = BufferInitDescriptor {
+            label:    Some("hui::arc::index_buffer"),
+            contents: bytemuck::cast_slice(INDICES),
+            usage:    BufferUsages::INDEX,
+        };
+        let index_buffer = device.create_buffer_init(&index_buffer_desc);
+
+        let instance_buffer = InstanceBuffer::new(
+            device,
+            "hui::arc::instance_buffer",
+            ArcSegment::SIZE,
+        );
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            arcs: SlotMap::new(),
+            arc_order: PhaseOrder::new(),
+            instance_bytes: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut(&mut self, id: ArcSegmentId) -> Option<&mut ArcSegment> {
+        self.arcs.get_mut(id)
+    }
+
+    #[inline(always)]
+    pub fn add(
+        &mut self,
+        instance: &ArcSegment,
+        phase: RenderPhase,
+    ) -> ArcSegmentId {
+        let id = self.arcs.insert(*instance);
+        self.arc_order.push(phase, id);
+        id
+    }
+
+    #[inline(always)]
+    pub fn remove(&mut self, id: ArcSegmentId) -> Option<ArcSegment> {
+        self.arc_order.remove(id);
+        self.arcs.remove(id)
+    }
+
+    // In the order they are drawn in.
+    #[must_use]
+    pub fn instances(&self) -> Vec<ArcSegment> {
+        self.arc_order.iter().map(|id| self.arcs[id]).collect()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn buffer_bytes(&self) -> u64 { self.instance_buffer.size() }
+
+    // Uploads the arcs, once per frame before the phases are drawn.
+    pub(crate) fn prepare(
+        &mut self,
+        queue: &Queue,
+        recorder: &mut FrameRecorder,
+    ) {
+        if self.arcs.is_empty() {
+            recorder.skip("arc", "no instances");
+            return;
+        }
+        span!("build_instances", kind = "arc");
+        counter!(arcs, self.arc_order.len());
+        self.instance_bytes.clear();
+        for arc_id in self.arc_order.iter() {
+            let arc = &self.arcs[arc_id];
+            self.instance_bytes
+                .extend_from_slice(bytemuck::bytes_of(arc));
+        }
+
+        self.instance_buffer
+            .reserve(self.instance_bytes.len() as u64);
+        queue.write_buffer(
+            self.instance_buffer.buffer(),
+            0,
+            &self.instance_bytes,
+        );
+        recorder
+            .upload("hui::arc::instance_buffer", self.instance_bytes.len());
+    }
+
+    pub(crate) fn render(
+        &self,
+        phase: RenderPhase,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        let range = self.arc_order.range(phase);
+        if range.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.render_pipeline);
+
+        let vertex_buffer = self.vertex_buffer.slice(..);
+        render_pass.set_vertex_buffer(0, vertex_buffer);
+
+        let instance_buffer = self.instance_buffer.buffer().slice(..);
+        render_pass.set_vertex_buffer(1, instance_buffer);
+
+        let index_buffer = self.index_buffer.slice(..);
+        render_pass.set_index_buffer(index_buffer, IndexFormat::Uint16);
+
+        let instances = range.start as u32..range.end as u32;
+        render_pass.draw_indexed(
+            0..INDICES.len() as u32,
+            0,
+            instances.clone(),
+        );
+        recorder.draw("arc", 0..INDICES.len() as u32, instances);
+    }
+}
+
+fn create_render_pipeline(
+    device: &Device,
+    surface_format: TextureFormat,
+) -> RenderPipeline {
+    let shader_module_content =
+        ShaderSource::Wgsl(include_str!("arc.wgsl").into());
+    let shader_module_desc = ShaderModuleDescriptor {
+        label:  Some("hui::arc::shader_module"),
+        source: shader_module_content,
+    };
+    let shader_module = device.create_shader_module(shader_module_desc);
+
+    let vertex_buffer_attributes = vertex_attr_array![
+        0 => Float32x3,
+    ];
+    let vertex_buffer_layout = VertexBufferLayout {
+        array_stride: size_of::<[f32; 3]>() as u64,
+        step_mode:    VertexStepMode::Vertex,
+        attributes:   &vertex_buffer_attributes,
+    };
+
+    let vertex_state = VertexState {
+        module:              &shader_module,
+        entry_point:         Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers:             &[vertex_buffer_layout, ArcSegment::LAYOUT],
+    };
+
+    let blend_state = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+    };
+    let fragment_state_targets = [Some(ColorTargetState {
+        format:     surface_format,
+        blend:      Some(blend_state),
+        write_mask: ColorWrites::ALL,
+    })];
+    let fragment_state = FragmentState {
+        module:              &shader_module,
+        entry_point:         Some("fs_main"),
+        compilation_options: Default::default(),
+        targets:             &fragment_state_targets,
+    };
+
+    let primitive_state = PrimitiveState {
+        topology:           PrimitiveTopology::TriangleList,
+        strip_index_format: None,
+        front_face:         FrontFace::Ccw,
+        cull_mode:          None,
+        polygon_mode:       PolygonMode::Fill,
+        unclipped_depth:    false,
+        conservative:       false,
+    };
+    let multisample_state = MultisampleState {
+        count: 1,
+        mask: !0,
+        alpha_to_coverage_enabled: false,
+    };
+
+    let render_pipeline_layout_desc = PipelineLayoutDescriptor {
+        label:                Some("hui::arc::render_pipeline_layout"),
+        bind_group_layouts:   &[],
+        push_constant_ranges: &[],
+    };
+    let render_pipeline_layout =
+        device.create_pipeline_layout(&render_pipeline_layout_desc);
+
+    let render_pipeline_desc = RenderPipelineDescriptor {
+        label:         Some("hui::arc::render_pipeline"),
+        layout:        Some(&render_pipeline_layout),
+        vertex:        vertex_state,
+        fragment:      Some(fragment_state),
+        primitive:     primitive_state,
+        depth_stencil: None,
+        multisample:   multisample_state,
+        multiview:     None,
+        cache:         None,
+    };
+    device.create_render_pipeline(&render_pipeline_desc)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use wgpu::TextureFormat;
+
+    use super::ArcRenderer;
+    use crate::{
+        core::{ArcSegment, RenderPhase, capture::FrameRecorder},
+        testing::noop_device,
+    };
+
+    #[rstest]
+    fn test_prepare_grows_instance_buffer() {
+        let (device, queue) = noop_device();
+        let mut renderer =
+            ArcRenderer::new(&device, TextureFormat::Bgra8UnormSrgb);
+        let arc = ArcSegment::builder()
+            .mvp([[0.0; 4]; 4])
+            .color([1.0; 4])
+            .clip_rect([0.0; 4])
+            .geometry([0.0; 4])
+            .size_and_caps([0.0; 4])
+            .build();
+        for _ in 0..1500 {
+            renderer.add(&arc, RenderPhase::Content);
+        }
+
+        renderer.prepare(&queue, &mut FrameRecorder::default());
+
+        assert!(renderer.buffer_bytes() >= 1500 * ArcSegment::SIZE as u64);
+    }
+}
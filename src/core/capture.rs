@@ -0,0 +1,248 @@
+use std::{fmt::Write, ops::Range};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapturedCommand {
+    // `bytes` written into the buffer labelled `buffer`.
+    Upload {
+        buffer: &'static str,
+        bytes:  usize,
+    },
+    Draw {
+        pipeline:  &'static str,
+        vertices:  Range<u32>,
+        instances: Range<u32>,
+    },
+    // Whatever a registered `PrimitiveRenderer` drew.
+    Plugin {
+        renderer: &'static str,
+    },
+    // A draw that was left out, and why.
+    Skipped {
+        pipeline: &'static str,
+        reason:   &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedPass {
+    pub label:    &'static str,
+    // The target's label and how it starts, e.g. `Clear([0.0, 0.0, 0.0,
+    // 1.0])` or `Load`.
+    pub target:   &'static str,
+    pub load:     String,
+    pub commands: Vec<CapturedCommand>,
+}
+
+// What the renderer did for one frame, in order, to find out why
+// something didn't draw without a graphics debugger. Recorded by
+// `Renderer::capture_next_frame`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameCapture {
+    pub backend:            &'static str,
+    // Without a redraw only the composite pass runs.
+    pub is_redraw_required: bool,
+    pub passes:             Vec<CapturedPass>,
+}
+
+impl FrameCapture {
+    #[must_use]
+    pub fn draw_count(&self) -> usize {
+        self.passes
+            .iter()
+            .flat_map(|pass| &pass.commands)
+            .filter(|command| matches!(command, CapturedCommand::Draw { .. }))
+            .count()
+    }
+
+    // Serializes the capture as indented JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\n  \"backend\": \"{}\",\n  \"is_redraw_required\": {},\n  \"passes\": [",
+            self.backend, self.is_redraw_required,
+        );
+        for (index, pass) in self.passes.iter().enumerate() {
+            json.push_str(if index == 0 { "\n" } else { ",\n" });
+            let _ = write!(
+                json,
+                "    {{\n      \"label\": \"{}\",\n      \"target\": \"{}\",\n      \"load\": \"{}\",\n      \"commands\": [",
+                pass.label, pass.target, pass.load,
+            );
+            for (index, command) in pass.commands.iter().enumerate() {
+                json.push_str(if index == 0 { "\n" } else { ",\n" });
+                json.push_str("        ");
+                write_command(&mut json, command);
+            }
+            if !pass.commands.is_empty() {
+                json.push_str("\n      ");
+            }
+            json.push_str("]\n    }");
+        }
+        if !self.passes.is_empty() {
+            json.push_str("\n  ");
+        }
+        json.push_str("]\n}");
+        json
+    }
+}
+
+fn write_command(json: &mut String, command: &CapturedCommand) {
+    let _ = match command {
+        CapturedCommand::Upload { buffer, bytes } => {
+            write!(json, "{{\"upload\": \"{buffer}\", \"bytes\": {bytes}}}")
+        }
+        CapturedCommand::Draw { pipeline, vertices, instances } => write!(
+            json,
+            "{{\"draw\": \"{pipeline}\", \"vertices\": [{}, {}], \"instances\": [{}, {}]}}",
+            vertices.start, vertices.end, instances.start, instances.end,
+        ),
+        CapturedCommand::Plugin { renderer } => {
+            write!(json, "{{\"plugin\": \"{renderer}\"}}")
+        }
+        CapturedCommand::Skipped { pipeline, reason } => write!(
+            json,
+            "{{\"skipped\": \"{pipeline}\", \"reason\": \"{reason}\"}}"
+        ),
+    };
+}
+
+// Handed down to the renderers of each primitive; does nothing unless a
+// capture was asked for.
+#[derive(Debug, Default)]
+pub(crate) struct FrameRecorder {
+    capture: Option<FrameCapture>,
+}
+
+impl FrameRecorder {
+    #[inline(always)]
+    pub(crate) fn start(
+        &mut self,
+        backend: &'static str,
+        is_redraw_required: bool,
+    ) {
+        self.capture = Some(FrameCapture {
+            backend,
+            is_redraw_required,
+            passes: Vec::new(),
+        });
+    }
+
+    #[inline(always)]
+    pub(crate) fn finish(&mut self) -> Option<FrameCapture> {
+        self.capture.take()
+    }
+
+    #[inline(always)]
+    pub(crate) fn begin_pass(
+        &mut self,
+        label: &'static str,
+        target: &'static str,
+        load: impl FnOnce() -> String,
+    ) {
+        if let Some(capture) = &mut self.capture {
+            capture.passes.push(CapturedPass {
+                label,
+                target,
+                load: load(),
+                commands: Vec::new(),
+            });
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn upload(&mut self, buffer: &'static str, bytes: usize) {
+        self.push(CapturedCommand::Upload { buffer, bytes });
+    }
+
+    #[inline(always)]
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &'static str,
+        vertices: Range<u32>,
+        instances: Range<u32>,
+    ) {
+        self.push(CapturedCommand::Draw { pipeline, vertices, instances });
+    }
+
+    // For the CPU backends, which draw without vertices.
+    #[cfg(any(feature = "software", feature = "vello"))]
+    pub(crate) fn draw_instances(
+        &mut self,
+        pipeline: &'static str,
+        count: usize,
+    ) {
+        match count {
+            0 => self.skip(pipeline, "no instances"),
+            _ => self.draw(pipeline, 0..0, 0..count as u32),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn plugin(&mut self, renderer: &'static str) {
+        self.push(CapturedCommand::Plugin { renderer });
+    }
+
+    #[inline(always)]
+    pub(crate) fn skip(
+        &mut self,
+        pipeline: &'static str,
+        reason: &'static str,
+    ) {
+        self.push(CapturedCommand::Skipped { pipeline, reason });
+    }
+
+    fn push(&mut self, command: CapturedCommand) {
+        if let Some(pass) = self
+            .capture
+            .as_mut()
+            .and_then(|capture| capture.passes.last_mut())
+        {
+            pass.commands.push(command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::FrameRecorder;
+
+    #[rstest]
+    fn test_recorder() {
+        let mut recorder = FrameRecorder::default();
+        recorder.begin_pass("ignored", "target", || "Load".to_owned());
+        recorder.draw("ignored", 0..3, 0..1);
+        assert_eq!(recorder.finish(), None);
+
+        recorder.start("gpu", true);
+        recorder.begin_pass("main", "offscreen", || "Load".to_owned());
+        recorder.upload("rectangle", 256);
+        recorder.draw("rectangle", 0..6, 0..2);
+        recorder.skip("arc", "no instances");
+        recorder.plugin("grid");
+        let capture = recorder.finish().unwrap();
+
+        assert_eq!(capture.draw_count(), 1);
+        let expected = r#"{
+  "backend": "gpu",
+  "is_redraw_required": true,
+  "passes": [
+    {
+      "label": "main",
+      "target": "offscreen",
+      "load": "Load",
+      "commands": [
+        {"upload": "rectangle", "bytes": 256},
+        {"draw": "rectangle", "vertices": [0, 6], "instances": [0, 2]},
+        {"skipped": "arc", "reason": "no instances"},
+        {"plugin": "grid"}
+      ]
+    }
+  ]
+}"#;
+        assert_eq!(capture.to_json(), expected);
+    }
+}
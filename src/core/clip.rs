@@ -0,0 +1,67 @@
+// The clip rects pushed on the renderer, each intersected with the ones
+// below it, as `[x, y, width, height]` in physical pixels.
+#[derive(Debug, Default)]
+pub(crate) struct ClipStack {
+    rects: Vec<[f32; 4]>,
+}
+
+impl ClipStack {
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn current(&self) -> Option<[f32; 4]> {
+        self.rects.last().copied()
+    }
+
+    pub(crate) fn push(&mut self, clip_rect: [f32; 4]) {
+        let clip_rect = self.clip(clip_rect);
+        self.rects.push(clip_rect);
+    }
+
+    #[inline(always)]
+    pub(crate) fn pop(&mut self) -> Option<[f32; 4]> { self.rects.pop() }
+
+    // What's left of `clip_rect` inside the current clip rect.
+    #[must_use]
+    pub(crate) fn clip(&self, clip_rect: [f32; 4]) -> [f32; 4] {
+        let Some(current) = self.current() else {
+            return clip_rect;
+        };
+        let x = clip_rect[0].max(current[0]);
+        let y = clip_rect[1].max(current[1]);
+        let right = (clip_rect[0] + clip_rect[2]).min(current[0] + current[2]);
+        let bottom =
+            (clip_rect[1] + clip_rect[3]).min(current[1] + current[3]);
+
+        [x, y, (right - x).max(0.0), (bottom - y).max(0.0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::ClipStack;
+
+    #[rstest]
+    fn test_clip_stack() {
+        let mut clip_stack = ClipStack::default();
+        let everything = [0.0, 0.0, f32::MAX, f32::MAX];
+        assert_eq!(clip_stack.clip(everything), everything);
+
+        clip_stack.push([0.0, 0.0, 100.0, 100.0]);
+        clip_stack.push([50.0, 20.0, 100.0, 40.0]);
+        assert_eq!(clip_stack.current(), Some([50.0, 20.0, 50.0, 40.0]));
+        assert_eq!(
+            clip_stack.clip([80.0, 0.0, 10.0, 10.0]),
+            [80.0, 20.0, 10.0, 0.0]
+        );
+
+        assert_eq!(clip_stack.pop(), Some([50.0, 20.0, 50.0, 40.0]));
+        assert_eq!(
+            clip_stack.clip([80.0, 0.0, 10.0, 10.0]),
+            [80.0, 0.0, 10.0, 10.0]
+        );
+        let _ = clip_stack.pop();
+        assert_eq!(clip_stack.current(), None);
+    }
+}
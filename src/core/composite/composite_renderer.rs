@@ -10,6 +10,8 @@ use wgpu::{
     TextureViewDimension, VertexState,
 };
 
+use crate::core::capture::FrameRecorder;
+
 pub struct CompositeRenderer {
     render_pipeline:   RenderPipeline,
     bind_group_layout: BindGroupLayout,
@@ -29,8 +31,10 @@ impl CompositeRenderer {
             address_mode_u: AddressMode::ClampToEdge,
             address_mode_v: AddressMode::ClampToEdge,
             address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: FilterMode::Nearest,
-            min_filter: FilterMode::Nearest,
+            // Upscales the UI when it's rendered below surface resolution;
+            // at full resolution, samples land on texel centers anyway.
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
             ..Default::default()
         };
         let sampler = device.create_sampler(&sample_desc);
@@ -90,10 +94,15 @@ impl CompositeRenderer {
         );
     }
 
-    pub fn render(&self, render_pass: &mut RenderPass) {
+    pub(crate) fn render(
+        &self,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
         render_pass.draw(0..3, 0..1);
+        recorder.draw("composite", 0..3, 0..1);
     }
 }
 
@@ -110,7 +119,7 @@ fn create_bind_group(
         },
         BindGroupEntry {
             binding:  1,
-            resource: BindingResource::Sampler(&sampler),
+            resource: BindingResource::Sampler(sampler),
         },
     ];
     let bind_group_desc = BindGroupDescriptor {
@@ -1,3 +1,5 @@
 mod composite_renderer;
+mod surface_mask;
 
 pub use composite_renderer::CompositeRenderer;
+pub use surface_mask::{SurfaceMask, SurfaceMaskRenderer};
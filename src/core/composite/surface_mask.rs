@@ -0,0 +1,264 @@
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
+    BlendComponent, BlendFactor, BlendOperation, BlendState, Buffer,
+    BufferBindingType, BufferDescriptor, BufferUsages, ColorTargetState,
+    ColorWrites, Device, FragmentState, PipelineLayout,
+    PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, Queue,
+    RenderPass, RenderPipeline, RenderPipelineDescriptor, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, TextureFormat,
+    VertexState,
+};
+
+use crate::core::capture::FrameRecorder;
+
+// Rounds the corners of the whole surface output, for custom-shaped
+// windows composited by the OS with transparency. Values are in
+// physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceMask {
+    pub corner_radius: f32,
+    pub border_width:  f32,
+    pub border_color:  [f32; 4],
+}
+
+pub struct SurfaceMaskRenderer {
+    mask_pipeline:   RenderPipeline,
+    border_pipeline: RenderPipeline,
+    uniform_buffer:  Buffer,
+    bind_group:      BindGroup,
+    mask:            Option<SurfaceMask>,
+    size:            [f32; 2],
+    is_dirty:        bool,
+}
+
+impl SurfaceMaskRenderer {
+    #[must_use]
+    pub fn new(
+        device: &Device,
+        surface_format: TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let uniform_buffer_desc = BufferDescriptor {
+            label:              Some("hui::surface_mask::uniform_buffer"),
+            size:               size_of::<[[f32; 4]; 2]>() as u64,
+            usage:              BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        };
+        let uniform_buffer = device.create_buffer(&uniform_buffer_desc);
+
+        let bind_group_layout_entries = [BindGroupLayoutEntry {
+            binding:    0,
+            visibility: ShaderStages::FRAGMENT,
+            ty:         BindingType::Buffer {
+                ty:                 BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size:   None,
+            },
+            count:      None,
+        }];
+        let bind_group_layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::surface_mask::bind_group_layout"),
+            entries: &bind_group_layout_entries,
+        };
+        let bind_group_layout =
+            device.create_bind_group_layout(&bind_group_layout_desc);
+
+        let bind_group_entries = [BindGroupEntry {
+            binding:  0,
+            resource: uniform_buffer.as_entire_binding(),
+        }];
+        let bind_group_desc = BindGroupDescriptor {
+            label:   Some("hui::surface_mask::bind_group"),
+            layout:  &bind_group_layout,
+            entries: &bind_group_entries,
+        };
+        let bind_group = device.create_bind_group(&bind_group_desc);
+
+        let (mask_pipeline, border_pipeline) = create_render_pipelines(
+            device,
+            surface_format,
+            &bind_group_layout,
+        );
+
+        Self {
+            mask_pipeline,
+            border_pipeline,
+            uniform_buffer,
+            bind_group,
+            mask: None,
+            size: [width as f32, height as f32],
+            is_dirty: true,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn mask(&self) -> Option<&SurfaceMask> { self.mask.as_ref() }
+
+    #[inline(always)]
+    pub const fn set_mask(&mut self, mask: Option<SurfaceMask>) {
+        self.mask = mask;
+        self.is_dirty = true;
+    }
+
+    #[inline(always)]
+    pub const fn resize(&mut self, width: u32, height: u32) {
+        self.size = [width as f32, height as f32];
+        self.is_dirty = true;
+    }
+
+    // Must run last in the composite pass, so the mask covers the layers
+    // as well.
+    pub(crate) fn render(
+        &mut self,
+        queue: &Queue,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        let Some(mask) = &self.mask else {
+            recorder.skip("surface_mask", "no surface mask");
+            return;
+        };
+        if self.is_dirty {
+            let uniform = [
+                [
+                    self.size[0],
+                    self.size[1],
+                    mask.corner_radius,
+                    mask.border_width,
+                ],
+                mask.border_color,
+            ];
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&uniform),
+            );
+            recorder.upload(
+                "hui::surface_mask::uniform_buffer",
+                std::mem::size_of_val(&uniform),
+            );
+            self.is_dirty = false;
+        }
+
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_pipeline(&self.mask_pipeline);
+        render_pass.draw(0..3, 0..1);
+        recorder.draw("surface_mask", 0..3, 0..1);
+
+        if mask.border_width > 0.0 {
+            render_pass.set_pipeline(&self.border_pipeline);
+            render_pass.draw(0..3, 0..1);
+            recorder.draw("surface_mask_border", 0..3, 0..1);
+        }
+    }
+}
+
+fn create_render_pipelines(
+    device: &Device,
+    surface_format: TextureFormat,
+    bind_group_layout: &BindGroupLayout,
+) -> (RenderPipeline, RenderPipeline) {
+    let shader_module_content =
+        ShaderSource::Wgsl(include_str!("surface_mask.wgsl").into());
+    let shader_module_desc = ShaderModuleDescriptor {
+        label:  Some("hui::surface_mask::shader_module"),
+        source: shader_module_content,
+    };
+    let shader_module = device.create_shader_module(shader_module_desc);
+
+    let pipeline_layout_desc = PipelineLayoutDescriptor {
+        label:                Some("hui::surface_mask::pipeline_layout"),
+        bind_group_layouts:   &[bind_group_layout],
+        push_constant_ranges: &[],
+    };
+    let pipeline_layout = device.create_pipeline_layout(&pipeline_layout_desc);
+
+    // Scales everything already on the surface by the mask coverage.
+    let mask_component = BlendComponent {
+        src_factor: BlendFactor::Zero,
+        dst_factor: BlendFactor::SrcAlpha,
+        operation:  BlendOperation::Add,
+    };
+    let mask_blend_state =
+        BlendState { color: mask_component, alpha: mask_component };
+    let border_blend_state = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+    };
+
+    let mask_pipeline = create_render_pipeline(
+        device,
+        &shader_module,
+        &pipeline_layout,
+        surface_format,
+        "fs_mask",
+        mask_blend_state,
+    );
+    let border_pipeline = create_render_pipeline(
+        device,
+        &shader_module,
+        &pipeline_layout,
+        surface_format,
+        "fs_border",
+        border_blend_state,
+    );
+
+    (mask_pipeline, border_pipeline)
+}
+
+fn create_render_pipeline(
+    device: &Device,
+    shader_module: &ShaderModule,
+    pipeline_layout: &PipelineLayout,
+    surface_format: TextureFormat,
+    fragment_entry_point: &str,
+    blend_state: BlendState,
+) -> RenderPipeline {
+    let vertex_state = VertexState {
+        module:              shader_module,
+        entry_point:         Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers:             &[],
+    };
+
+    let fragment_state_targets = [Some(ColorTargetState {
+        format:     surface_format,
+        blend:      Some(blend_state),
+        write_mask: ColorWrites::ALL,
+    })];
+    let fragment_state = FragmentState {
+        module:              shader_module,
+        entry_point:         Some(fragment_entry_point),
+        compilation_options: Default::default(),
+        targets:             &fragment_state_targets,
+    };
+
+    let primitive_state = PrimitiveState {
+        topology: PrimitiveTopology::TriangleList,
+        ..Default::default()
+    };
+
+    let render_pipeline_desc = RenderPipelineDescriptor {
+        label:         Some("hui::surface_mask::render_pipeline"),
+        layout:        Some(pipeline_layout),
+        vertex:        vertex_state,
+        fragment:      Some(fragment_state),
+        primitive:     primitive_state,
+        depth_stencil: None,
+        multisample:   Default::default(),
+        multiview:     None,
+        cache:         None,
+    };
+    device.create_render_pipeline(&render_pipeline_desc)
+}
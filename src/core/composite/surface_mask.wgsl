@@ -0,0 +1,49 @@
+struct SurfaceMask {
+    // width, height, corner radius, border width
+    size_and_radii: vec4<f32>,
+    border_color:   vec4<f32>,
+}
+
+const positions = array<vec2<f32>, 3>(
+    vec2<f32>(-1.0, -1.0),
+    vec2<f32>( 3.0, -1.0),
+    vec2<f32>(-1.0,  3.0),
+);
+
+@group(0) @binding(0) var<uniform> mask: SurfaceMask;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(positions[vertex_index], 0.0, 1.0);
+}
+
+fn sd_rounded_rect(position: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+    let q = abs(position) - half_size + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+fn surface_distance(fragment_position: vec2<f32>) -> f32 {
+    let half_size = mask.size_and_radii.xy * 0.5;
+    let radius    = min(mask.size_and_radii.z, min(half_size.x, half_size.y));
+    return sd_rounded_rect(fragment_position - half_size, half_size, radius);
+}
+
+fn coverage(distance: f32) -> f32 {
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+// Blended as `dst * src.a`, so everything outside the rounded
+// rectangle becomes fully transparent.
+@fragment
+fn fs_mask(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 0.0, 0.0, coverage(surface_distance(position.xy)));
+}
+
+@fragment
+fn fs_border(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let distance     = surface_distance(position.xy);
+    let border_width = mask.size_and_radii.w;
+    let alpha        = coverage(distance) * (1.0 - coverage(distance + border_width));
+
+    return vec4<f32>(mask.border_color.rgb, mask.border_color.a * alpha);
+}
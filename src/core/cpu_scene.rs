@@ -0,0 +1,313 @@
+use slotmap::SlotMap;
+
+use crate::{
+    core::{
+        ArcSegment, ArcSegmentId, Image, ImageId, LayerId, PathId, PhaseOrder,
+        Rectangle, RectangleId, RenderPhase, SceneTexture, TextureId,
+        VectorPath, VectorScene, capture::FrameRecorder,
+        rectangle::RectangleStore,
+    },
+    instrument::counter,
+};
+
+// How a CPU backend keeps a texture, e.g. decoded for sampling.
+pub(crate) trait CpuTexture {
+    fn size(&self) -> [u32; 2];
+
+    // sRGB encoded with straight alpha, as the texture was added.
+    fn to_rgba(&self) -> Vec<u8>;
+}
+
+// One primitive to rasterize, in the order `CpuScene::draw` yields them.
+pub(crate) enum CpuPrimitive<'a, T> {
+    Rectangle(Rectangle),
+    Arc(&'a ArcSegment),
+    Path(&'a VectorPath),
+    Image(&'a T, &'a Image),
+}
+
+// The primitives of the software and vello backends, which only differ in
+// how they rasterize them.
+pub(crate) struct CpuScene<T> {
+    rectangles: RectangleStore,
+    arcs:       SlotMap<ArcSegmentId, ArcSegment>,
+    arc_order:  PhaseOrder<ArcSegmentId>,
+    paths:      SlotMap<PathId, VectorPath>,
+    path_order: PhaseOrder<PathId>,
+    textures:   SlotMap<TextureId, T>,
+    images:     SlotMap<ImageId, (RenderPhase, TextureId, Image)>,
+    // Layers are composited on the GPU only; their ids are handed out so
+    // that widgets work unchanged, but nothing is drawn into them.
+    layers:     SlotMap<LayerId, ()>,
+}
+
+impl<T: CpuTexture> CpuScene<T> {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self {
+            rectangles: RectangleStore::new(),
+            arcs:       SlotMap::new(),
+            arc_order:  PhaseOrder::new(),
+            paths:      SlotMap::new(),
+            path_order: PhaseOrder::new(),
+            textures:   SlotMap::new(),
+            images:     SlotMap::new(),
+            layers:     SlotMap::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn get_mut_rectangle(
+        &mut self,
+        id: RectangleId,
+    ) -> Option<&mut Rectangle> {
+        self.rectangles.get_mut(id)
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_rectangle_z_index(
+        &mut self,
+        id: RectangleId,
+        z_index: i32,
+    ) -> bool {
+        self.rectangles.set_z_index(id, z_index)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_rectangle(
+        &mut self,
+        instance: &Rectangle,
+        phase: RenderPhase,
+    ) -> RectangleId {
+        self.rectangles.add_in_phase(instance, phase, 0)
+    }
+
+    #[inline(always)]
+    pub(crate) fn remove_rectangle(
+        &mut self,
+        id: RectangleId,
+    ) -> Option<Rectangle> {
+        self.rectangles.remove(id)
+    }
+
+    #[inline(always)]
+    pub(crate) fn compact(&mut self) { self.rectangles.compact(); }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn get_mut_arc(
+        &mut self,
+        id: ArcSegmentId,
+    ) -> Option<&mut ArcSegment> {
+        self.arcs.get_mut(id)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_arc(
+        &mut self,
+        instance: &ArcSegment,
+        phase: RenderPhase,
+    ) -> ArcSegmentId {
+        let id = self.arcs.insert(*instance);
+        self.arc_order.push(phase, id);
+        id
+    }
+
+    #[inline(always)]
+    pub(crate) fn remove_arc(
+        &mut self,
+        id: ArcSegmentId,
+    ) -> Option<ArcSegment> {
+        self.arc_order.remove(id);
+        self.arcs.remove(id)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn get_mut_path(
+        &mut self,
+        id: PathId,
+    ) -> Option<&mut VectorPath> {
+        self.paths.get_mut(id)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_path(
+        &mut self,
+        path: &VectorPath,
+        phase: RenderPhase,
+    ) -> PathId {
+        let id = self.paths.insert(path.clone());
+        self.path_order.push(phase, id);
+        id
+    }
+
+    #[inline(always)]
+    pub(crate) fn remove_path(&mut self, id: PathId) -> Option<VectorPath> {
+        self.path_order.remove(id);
+        self.paths.remove(id)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_texture(&mut self, texture: T) -> TextureId {
+        self.textures.insert(texture)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn get_mut_texture(&mut self, id: TextureId) -> Option<&mut T> {
+        self.textures.get_mut(id)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn texture_size(&self, id: TextureId) -> Option<[u32; 2]> {
+        self.textures.get(id).map(T::size)
+    }
+
+    #[inline(always)]
+    pub(crate) fn remove_texture(&mut self, id: TextureId) -> bool {
+        self.textures.remove(id).is_some()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn get_mut_image(&mut self, id: ImageId) -> Option<&mut Image> {
+        self.images.get_mut(id).map(|(_, _, image)| image)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_image(
+        &mut self,
+        texture_id: TextureId,
+        instance: &Image,
+        phase: RenderPhase,
+    ) -> Option<ImageId> {
+        if !self.textures.contains_key(texture_id) {
+            return None;
+        }
+        Some(self.images.insert((phase, texture_id, *instance)))
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_image_texture(
+        &mut self,
+        id: ImageId,
+        texture_id: TextureId,
+    ) {
+        if let Some((_, image_texture_id, _)) = self.images.get_mut(id) {
+            *image_texture_id = texture_id;
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn remove_image(&mut self, id: ImageId) -> Option<Image> {
+        self.images.remove(id).map(|(_, _, image)| image)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_layer(&mut self) -> LayerId { self.layers.insert(()) }
+
+    #[inline(always)]
+    pub(crate) fn remove_layer(&mut self, id: LayerId) {
+        self.layers.remove(id);
+    }
+
+    #[must_use]
+    pub(crate) fn export(
+        &self,
+        size: [u32; 2],
+        clear_color: [f32; 4],
+    ) -> VectorScene {
+        let mut scene = VectorScene::new(size, clear_color);
+        scene.rectangles = self.rectangles.to_vec();
+        scene.arcs = self.arc_order.iter().map(|id| self.arcs[id]).collect();
+        scene.paths = self
+            .path_order
+            .iter()
+            .map(|id| self.paths[id].clone())
+            .collect();
+        let mut images: Vec<_> = self.images.values().copied().collect();
+        images.sort_by_key(|(phase, texture_id, image)| {
+            (*phase, *texture_id, image.repeat() as usize)
+        });
+        scene.images = images
+            .into_iter()
+            .map(|(_, texture_id, image)| (texture_id, image))
+            .collect();
+        scene.textures = self
+            .textures
+            .iter()
+            .map(|(id, texture)| {
+                let size = texture.size();
+                (id, SceneTexture { size, rgba: texture.to_rgba() })
+            })
+            .collect();
+        scene
+    }
+
+    // What `draw` is about to yield, for a frame capture.
+    pub(crate) fn record(
+        &self,
+        recorder: &mut FrameRecorder,
+        label: &'static str,
+        clear_color: [f32; 4],
+    ) {
+        recorder
+            .begin_pass(label, "pixmap", || format!("Clear({clear_color:?})"));
+        recorder.draw_instances("rectangle", self.rectangles.len());
+        recorder.draw_instances("arc", self.arc_order.len());
+        recorder.draw_instances("path", self.path_order.len());
+        recorder.draw_instances("image", self.images.len());
+        if !self.layers.is_empty() {
+            recorder.skip("layer", "layers are composited on the GPU only");
+        }
+    }
+
+    // Yields rectangles, arcs, paths and then images in each phase; the
+    // first three in the order the GPU renderers draw them in.
+    pub(crate) fn draw(&mut self, mut draw: impl FnMut(CpuPrimitive<'_, T>)) {
+        counter!(rectangles, self.rectangles.len());
+        counter!(arcs, self.arc_order.len());
+        counter!(paths, self.path_order.len());
+        let mut images: Vec<_> = self
+            .images
+            .values()
+            .filter_map(|(phase, texture_id, image)| {
+                let texture = self.textures.get(*texture_id)?;
+                let repeat = image.repeat() as usize;
+                Some((*phase, *texture_id, repeat, texture, image))
+            })
+            .collect();
+        images.sort_by_key(|(phase, texture_id, repeat, ..)| {
+            (*phase, *texture_id, *repeat)
+        });
+
+        for phase in RenderPhase::ALL {
+            let range = self.rectangles.phase_range(phase);
+            let bytes = &self.rectangles.bytes()
+                [range.start * Rectangle::SIZE..range.end * Rectangle::SIZE];
+            for rect in bytes.chunks_exact(Rectangle::SIZE) {
+                draw(CpuPrimitive::Rectangle(bytemuck::pod_read_unaligned(
+                    rect,
+                )));
+            }
+
+            for arc_id in self.arc_order.phase(phase) {
+                draw(CpuPrimitive::Arc(&self.arcs[arc_id]));
+            }
+
+            for path_id in self.path_order.phase(phase) {
+                draw(CpuPrimitive::Path(&self.paths[path_id]));
+            }
+
+            let images = images
+                .iter()
+                .filter(|(image_phase, ..)| *image_phase == phase);
+            for (.., texture, image) in images {
+                draw(CpuPrimitive::Image(texture, image));
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod pdf;
+mod print;
+mod scene;
+mod shapes;
+mod svg;
+
+pub use print::{PageBreak, PageSetup};
+pub use scene::{SceneTexture, VectorScene};
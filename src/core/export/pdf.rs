@@ -0,0 +1,351 @@
+use std::fmt::Write;
+
+use super::{
+    VectorScene,
+    shapes::{self, ImageShape, Paint, PaintedPath, Shape, ShapeKind, number},
+};
+use crate::core::PathCommand;
+
+// The objects every document has; each page and its contents, the
+// bitmaps and their alpha masks, then the graphics states for opacity
+// follow.
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+const FIRST_PAGE: usize = 3;
+
+// What a page shows: the scene's rows from `rows[0]` to `rows[1]`, scaled
+// by `scale` and placed at `origin` on a page of `size`, all in points.
+pub(super) struct PageView {
+    pub(super) size:   [f32; 2],
+    pub(super) origin: [f32; 2],
+    pub(super) scale:  f32,
+    pub(super) rows:   [f32; 2],
+}
+
+// The whole scene on a page of its own size.
+#[must_use]
+pub(super) fn write(scene: &VectorScene) -> Vec<u8> {
+    let [width, height] = scene.size.map(|size| size as f32);
+    write_pages(
+        scene,
+        &[PageView {
+            size:   [width, height],
+            origin: [0.0, 0.0],
+            scale:  1.0,
+            rows:   [0.0, height],
+        }],
+    )
+}
+
+// A PDF 1.4 document with uncompressed streams. Every page has all of
+// the shapes that may reach into its rows and clips them.
+#[must_use]
+pub(super) fn write_pages(scene: &VectorScene, pages: &[PageView]) -> Vec<u8> {
+    let shapes = shapes::build(scene);
+    let width = scene.size[0] as f32;
+
+    let mut opacities = Vec::new();
+    let contents: Vec<_> = pages
+        .iter()
+        .map(|page| {
+            let [page_width, page_height] = page.size.map(number);
+            let [x, y] = page.origin.map(number);
+            let [top, bottom] = page.rows;
+            let mut content = String::new();
+            // PDF's y axis points up; flipping it maps pixels to points.
+            let _ = writeln!(
+                content,
+                "1 0 0 -1 0 {page_height} cm\n{scale} 0 0 {scale} {x} {y} cm\n\
+                 0 0 {} {} re W n\n1 0 0 1 0 {} cm",
+                number(width),
+                number(bottom - top),
+                number(-top),
+                scale = number(page.scale),
+            );
+            if let Some(color) = shapes.background {
+                content.push_str("q\n");
+                set_paint(
+                    &mut content,
+                    &mut opacities,
+                    Paint::Fill { color, is_even_odd: false },
+                );
+                let _ = writeln!(
+                    content,
+                    "0 {} {} {} re f\nQ",
+                    number(top),
+                    number(width),
+                    number(bottom - top)
+                );
+            }
+            for shape in &shapes.shapes {
+                let is_visible = shape
+                    .clip_rect
+                    .is_none_or(|[_, y, _, h]| y < bottom && y + h > top);
+                if is_visible {
+                    write_shape(&mut content, &mut opacities, shape);
+                }
+            }
+            (page_width, page_height, content)
+        })
+        .collect();
+
+    let first_bitmap = FIRST_PAGE + pages.len() * 2;
+    let first_opacity = first_bitmap + shapes.bitmaps.len() * 2;
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    objects.push(format!("<< /Type /Catalog /Pages {PAGES} 0 R >>").into());
+    let kids: Vec<_> = (0..pages.len())
+        .map(|index| format!("{} 0 R", FIRST_PAGE + index * 2))
+        .collect();
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into(),
+    );
+
+    let mut resources = String::from("<< /XObject <<");
+    for index in 0..shapes.bitmaps.len() {
+        let _ =
+            write!(resources, " /Im{index} {} 0 R", first_bitmap + index * 2);
+    }
+    resources.push_str(" >> /ExtGState <<");
+    for index in 0..opacities.len() {
+        let _ = write!(resources, " /GS{index} {} 0 R", first_opacity + index);
+    }
+    resources.push_str(" >> >>");
+    for (index, (page_width, page_height, content)) in
+        contents.iter().enumerate()
+    {
+        let contents = FIRST_PAGE + index * 2 + 1;
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 \
+                 {page_width} {page_height}] /Resources {resources} \
+                 /Contents {contents} 0 R >>"
+            )
+            .into(),
+        );
+        objects.push(stream("", content.as_bytes()));
+    }
+
+    for (index, bitmap) in shapes.bitmaps.iter().enumerate() {
+        let [bitmap_width, bitmap_height] = bitmap.size;
+        let dictionary = |color_space: &str| {
+            format!(
+                "/Type /XObject /Subtype /Image /Width {bitmap_width} \
+                 /Height {bitmap_height} /ColorSpace {color_space} \
+                 /BitsPerComponent 8 /Interpolate true"
+            )
+        };
+        let (rgb, alpha): (Vec<_>, Vec<_>) = bitmap
+            .rgba
+            .chunks_exact(4)
+            .map(|texel| ([texel[0], texel[1], texel[2]], texel[3]))
+            .unzip();
+        let mask = first_bitmap + index * 2 + 1;
+        let image = format!("{} /SMask {mask} 0 R", dictionary("/DeviceRGB"));
+        objects.push(stream(&image, rgb.as_flattened()));
+        objects.push(stream(&dictionary("/DeviceGray"), &alpha));
+    }
+    for alpha in opacities {
+        let alpha = number(f32::from_bits(alpha));
+        objects.push(
+            format!("<< /Type /ExtGState /ca {alpha} /CA {alpha} >>").into(),
+        );
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref_offset = pdf.len();
+    let mut xref =
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(xref, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        xref,
+        "trailer\n<< /Size {} /Root {CATALOG} 0 R >>\nstartxref\n\
+         {xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.extend_from_slice(xref.as_bytes());
+    pdf
+}
+
+fn write_shape(content: &mut String, opacities: &mut Vec<u32>, shape: &Shape) {
+    content.push_str("q\n");
+    if let Some([x, y, width, height]) = shape.clip_rect {
+        let _ = writeln!(
+            content,
+            "{} {} {} {} re W n",
+            number(x),
+            number(y),
+            number(width),
+            number(height)
+        );
+    }
+    let _ = writeln!(content, "{} cm", shape.transform.map(number).join(" "));
+
+    match &shape.kind {
+        ShapeKind::Paths(paths) => {
+            for path in paths {
+                write_path(content, opacities, path);
+            }
+        }
+        ShapeKind::Image(image) => write_image(content, image),
+    }
+    content.push_str("Q\n");
+}
+
+fn write_path(
+    content: &mut String,
+    opacities: &mut Vec<u32>,
+    path: &PaintedPath,
+) {
+    content.push_str("q\n");
+    set_paint(content, opacities, path.paint);
+    path_operators(content, &path.commands);
+    let operator = match path.paint {
+        Paint::Fill { is_even_odd: true, .. } => "f*",
+        Paint::Fill { is_even_odd: false, .. } => "f",
+        Paint::Stroke { .. } => "S",
+    };
+    let _ = writeln!(content, "{operator}\nQ");
+}
+
+// Bitmaps are drawn into the unit square with their first row at the
+// top, so each tile flips it back.
+fn write_image(content: &mut String, image: &ImageShape) {
+    path_operators(content, &image.outline);
+    content.push_str("W n\n");
+    if let Some(cutout) = &image.cutout {
+        path_operators(content, cutout);
+        content.push_str("W* n\n");
+    }
+    for [x, y, width, height] in &image.tiles {
+        let _ = writeln!(
+            content,
+            "q {} 0 0 {} {} {} cm /Im{} Do Q",
+            number(*width),
+            number(-height),
+            number(*x),
+            number(y + height),
+            image.bitmap
+        );
+    }
+}
+
+fn set_paint(content: &mut String, opacities: &mut Vec<u32>, paint: Paint) {
+    let (color, operator) = match paint {
+        Paint::Fill { color, .. } => (color, "rg"),
+        Paint::Stroke { color, width, round_caps, dash } => {
+            let _ = writeln!(
+                content,
+                "{} w {} J",
+                number(width),
+                round_caps as u8
+            );
+            if let Some([dash_length, gap_length, dash_offset]) = dash {
+                let _ = writeln!(
+                    content,
+                    "[{} {}] {} d",
+                    number(dash_length),
+                    number(gap_length),
+                    number(dash_offset)
+                );
+            }
+            (color, "RG")
+        }
+    };
+    let [red, green, blue, alpha] = color;
+    let _ = writeln!(
+        content,
+        "{} {} {} {operator}",
+        number(red),
+        number(green),
+        number(blue)
+    );
+    if alpha < 1.0 {
+        let bits = alpha.to_bits();
+        let index = match opacities.iter().position(|value| *value == bits) {
+            Some(index) => index,
+            None => {
+                opacities.push(bits);
+                opacities.len() - 1
+            }
+        };
+        let _ = writeln!(content, "/GS{index} gs");
+    }
+}
+
+// PDF has no quadratic curves; they are raised to cubics.
+fn path_operators(content: &mut String, commands: &[PathCommand]) {
+    let [mut start, mut current] = [[0.0; 2]; 2];
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(to) => {
+                let _ =
+                    writeln!(content, "{} {} m", number(to[0]), number(to[1]));
+                [start, current] = [to, to];
+            }
+            PathCommand::LineTo(to) => {
+                let _ =
+                    writeln!(content, "{} {} l", number(to[0]), number(to[1]));
+                current = to;
+            }
+            PathCommand::QuadTo(control, to) => {
+                let raise = |from: [f32; 2]| {
+                    [
+                        from[0] + (control[0] - from[0]) * 2.0 / 3.0,
+                        from[1] + (control[1] - from[1]) * 2.0 / 3.0,
+                    ]
+                };
+                write_cubic(content, raise(current), raise(to), to);
+                current = to;
+            }
+            PathCommand::CubicTo(control_0, control_1, to) => {
+                write_cubic(content, control_0, control_1, to);
+                current = to;
+            }
+            PathCommand::Close => {
+                content.push_str("h\n");
+                current = start;
+            }
+        }
+    }
+}
+
+fn write_cubic(
+    content: &mut String,
+    control_0: [f32; 2],
+    control_1: [f32; 2],
+    to: [f32; 2],
+) {
+    let points: Vec<_> = [control_0, control_1, to]
+        .as_flattened()
+        .iter()
+        .map(|value| number(*value))
+        .collect();
+    let _ = writeln!(content, "{} c", points.join(" "));
+}
+
+// `entries` are the stream dictionary's entries besides its length.
+fn stream(entries: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = match entries.is_empty() {
+        true => format!("<< /Length {} >>", data.len()),
+        false => format!("<< {entries} /Length {} >>", data.len()),
+    }
+    .into_bytes();
+    object.extend_from_slice(b"\nstream\n");
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
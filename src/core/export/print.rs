@@ -0,0 +1,145 @@
+use bon::Builder;
+
+// Where a printed scene may be split, in physical pixels from its top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageBreak {
+    // Starts a new page here.
+    Before(f32),
+    // Moves the span to the next page rather than splitting it, unless it
+    // is taller than a page.
+    AvoidInside { top: f32, bottom: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct PageSetup {
+    // width, height in points, a 72nd of an inch; A4 by default
+    #[builder(default = [595.0, 842.0])]
+    pub page_size: [f32; 2],
+    // top, right, bottom, left in points
+    #[builder(default = [36.0; 4])]
+    pub margins:   [f32; 4],
+    // physical pixels of the scene per inch of paper
+    #[builder(default = 96.0)]
+    pub dpi:       f32,
+}
+
+impl PageSetup {
+    #[must_use]
+    #[inline(always)]
+    pub const fn points_per_pixel(&self) -> f32 { 72.0 / self.dpi }
+
+    // The printable area of a page in physical pixels; scenes meant for
+    // printing are laid out this wide.
+    #[must_use]
+    pub fn content_size(&self) -> [f32; 2] {
+        let [width, height] = self.page_size;
+        let [top, right, bottom, left] = self.margins;
+        [width - left - right, height - top - bottom]
+            .map(|size| size.max(0.0) / self.points_per_pixel())
+    }
+
+    // Splits a scene of the given height into the spans of it that go on
+    // each page, honoring the breaks where they fit.
+    #[must_use]
+    pub fn paginate(
+        &self,
+        height: f32,
+        breaks: &[PageBreak],
+    ) -> Vec<[f32; 2]> {
+        let [_, page_height] = self.content_size();
+        if page_height <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut pages = Vec::new();
+        let mut top = 0.0;
+        while top < height {
+            let mut bottom = (top + page_height).min(height);
+            for page_break in breaks {
+                if let PageBreak::Before(offset) = *page_break
+                    && offset > top
+                    && offset < bottom
+                {
+                    bottom = offset;
+                }
+            }
+            // Moving the bottom up can split a span that encloses the
+            // one that moved it.
+            let mut is_moved = true;
+            while is_moved {
+                is_moved = false;
+                for page_break in breaks {
+                    if let PageBreak::AvoidInside { top: start, bottom: end } =
+                        *page_break
+                        && start > top
+                        && start < bottom
+                        && end > bottom
+                        && end - start <= page_height
+                    {
+                        bottom = start;
+                        is_moved = true;
+                    }
+                }
+            }
+
+            pages.push([top, bottom]);
+            top = bottom;
+        }
+        pages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PageBreak, PageSetup};
+
+    // 100 pixels of content per page.
+    const SETUP: PageSetup = PageSetup::builder()
+        .page_size([100.0, 120.0])
+        .margins([10.0; 4])
+        .dpi(72.0)
+        .build();
+
+    #[rstest]
+    #[case(250.0, &[], &[[0.0, 100.0], [100.0, 200.0], [200.0, 250.0]])]
+    #[case(
+        150.0,
+        &[PageBreak::Before(30.0)],
+        &[[0.0, 30.0], [30.0, 130.0], [130.0, 150.0]],
+    )]
+    #[case(
+        150.0,
+        &[PageBreak::AvoidInside { top: 80.0, bottom: 120.0 }],
+        &[[0.0, 80.0], [80.0, 150.0]],
+    )]
+    #[case(
+        150.0,
+        &[
+            PageBreak::AvoidInside { top: 90.0, bottom: 110.0 },
+            PageBreak::AvoidInside { top: 60.0, bottom: 130.0 },
+        ],
+        &[[0.0, 60.0], [60.0, 150.0]],
+    )]
+    // Spans taller than a page are split anyway.
+    #[case(
+        150.0,
+        &[PageBreak::AvoidInside { top: 20.0, bottom: 140.0 }],
+        &[[0.0, 100.0], [100.0, 150.0]],
+    )]
+    fn test_paginate(
+        #[case] height: f32,
+        #[case] breaks: &[PageBreak],
+        #[case] expected: &[[f32; 2]],
+    ) {
+        assert_eq!(SETUP.paginate(height, breaks), expected);
+    }
+
+    #[rstest]
+    fn test_content_size_follows_dpi() {
+        let setup = PageSetup::builder().margins([0.0; 4]).dpi(144.0).build();
+        assert_eq!(setup.content_size(), [1190.0, 1684.0]);
+    }
+}
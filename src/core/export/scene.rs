@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use super::{PageBreak, PageSetup, pdf, pdf::PageView, svg};
+use crate::core::{ArcSegment, Image, Rectangle, TextureId, VectorPath};
+
+// Texture pixels as passed to `add_texture`: sRGB encoded with straight
+// alpha, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneTexture {
+    pub size: [u32; 2],
+    pub rgba: Vec<u8>,
+}
+
+// A copy of the primitives a renderer draws, in drawing order, that can
+// be written out as vector graphics. Text is drawn from glyph atlases,
+// so it ends up as embedded images; glyph outlines added as paths stay
+// vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorScene {
+    // in physical pixels, which become SVG user units and PDF points
+    pub size:        [u32; 2],
+    pub clear_color: [f32; 4],
+    pub rectangles:  Vec<Rectangle>,
+    pub arcs:        Vec<ArcSegment>,
+    pub paths:       Vec<VectorPath>,
+    pub images:      Vec<(TextureId, Image)>,
+    // Images whose texture is missing are left out. The GPU backend keeps
+    // no copy of its textures, so they have to be added here by hand.
+    pub textures:    HashMap<TextureId, SceneTexture>,
+}
+
+impl VectorScene {
+    #[must_use]
+    pub fn new(size: [u32; 2], clear_color: [f32; 4]) -> Self {
+        Self {
+            size,
+            clear_color,
+            rectangles: Vec::new(),
+            arcs: Vec::new(),
+            paths: Vec::new(),
+            images: Vec::new(),
+            textures: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn to_svg(&self) -> String { svg::write(self) }
+
+    #[must_use]
+    pub fn to_pdf(&self) -> Vec<u8> { pdf::write(self) }
+
+    // One page for each span `PageSetup::paginate` splits the scene into,
+    // drawn within the margins at the setup's dpi. Empty scenes print a
+    // blank page.
+    #[must_use]
+    pub fn to_print_pdf(
+        &self,
+        setup: &PageSetup,
+        breaks: &[PageBreak],
+    ) -> Vec<u8> {
+        let [top, .., left] = setup.margins;
+        let mut spans = setup.paginate(self.size[1] as f32, breaks);
+        if spans.is_empty() {
+            spans.push([0.0, 0.0]);
+        }
+        let pages: Vec<_> = spans
+            .into_iter()
+            .map(|rows| PageView {
+                size: setup.page_size,
+                origin: [left, top],
+                scale: setup.points_per_pixel(),
+                rows,
+            })
+            .collect();
+        pdf::write_pages(self, &pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+    use rstest::rstest;
+
+    use super::{SceneTexture, VectorScene};
+    use crate::{
+        components::{BlockStyle, Bounds, build_model, build_rectangle},
+        core::{
+            Image, PageBreak, PageSetup, PathCommand, TextureId, VectorPath,
+        },
+    };
+
+    fn scene() -> VectorScene {
+        let view_projection =
+            Mat4::orthographic_rh(0.0, 64.0, 32.0, 0.0, -1.0, 1.0);
+        let mut scene = VectorScene::new([64, 32], [1.0; 4]);
+
+        let bounds = Bounds {
+            position:  [8.0, 8.0],
+            size:      [32.0, 16.0],
+            clip_rect: [0.0, 0.0, 24.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([1.0, 0.0, 0.0, 1.0])
+            .border_color([0.0, 0.0, 0.0, 0.5])
+            .border_size(1.0)
+            .corner_radii([4.0; 4])
+            .build();
+        scene.rectangles.push(build_rectangle(
+            &view_projection,
+            1.0,
+            &bounds,
+            &style,
+        ));
+        scene.paths.push(
+            VectorPath::builder()
+                .mvp(view_projection.to_cols_array_2d())
+                .commands(vec![
+                    PathCommand::MoveTo([40.0, 8.0]),
+                    PathCommand::QuadTo([48.0, 0.0], [56.0, 8.0]),
+                    PathCommand::Close,
+                ])
+                .fill_color([0.0, 0.0, 1.0, 1.0])
+                .clip_rect([0.0, 0.0, 64.0, 32.0])
+                .build(),
+        );
+
+        let texture_id = TextureId::default();
+        let (model, half_size) = build_model([16.0, 16.0], [0.0, 16.0], 1.0);
+        scene.images.push((
+            texture_id,
+            Image::builder()
+                .mvp((view_projection * model).to_cols_array_2d())
+                .tint_color([1.0; 4])
+                .corner_radii([0.0; 4])
+                .clip_rect([0.0, 0.0, 64.0, 32.0])
+                .uv_transform([1.0, 1.0, 0.0, 0.0])
+                .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+                .cutout([0.0; 4])
+                .build(),
+        ));
+        scene.textures.insert(
+            texture_id,
+            SceneTexture { size: [1, 1], rgba: vec![0, 128, 255, 255] },
+        );
+        scene
+    }
+
+    #[rstest]
+    fn test_svg_has_every_primitive() {
+        let svg = scene().to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // the rectangle's fill and border, the path and the image outline
+        assert_eq!(svg.matches("<path d=").count(), 4);
+        assert!(svg.contains("fill=\"#ff0000\""));
+        assert!(svg.contains("fill-opacity=\"0.5\" fill-rule=\"evenodd\""));
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"0\" width=\"24\" height=\"32\"/>")
+        );
+        assert!(svg.contains("href=\"data:image/png;base64,iVBORw0KGgo"));
+        assert_eq!(svg.matches("<use href=\"#bitmap-0\"").count(), 1);
+    }
+
+    #[rstest]
+    fn test_images_without_texture_are_left_out() {
+        let mut scene = scene();
+        scene.textures.clear();
+        let svg = scene.to_svg();
+        assert!(!svg.contains("<image"));
+        assert!(!svg.contains("<use"));
+    }
+
+    #[rstest]
+    fn test_pdf_cross_references_its_objects() {
+        let pdf = scene().to_pdf();
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let text = String::from_utf8_lossy(&pdf);
+        let xref_offset: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .unwrap();
+        assert!(pdf[xref_offset..].starts_with(b"xref\n"));
+
+        // catalog, pages, page, contents, one bitmap with its mask and the
+        // border's opacity
+        let xref = std::str::from_utf8(&pdf[xref_offset..]).unwrap();
+        let offsets: Vec<usize> = xref
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), 7);
+        for (index, offset) in offsets.into_iter().enumerate() {
+            let object = format!("{} 0 obj\n", index + 1);
+            assert!(pdf[offset..].starts_with(object.as_bytes()));
+        }
+        assert!(text.contains("/Im0 Do"));
+        assert!(text.contains("/ca 0.5 /CA 0.5"));
+    }
+
+    #[rstest]
+    fn test_dashed_border_is_stroked() {
+        let mut scene = scene();
+        scene.rectangles[0].dash = [4.0, 2.0, 1.0, 0.0];
+        let svg = scene.to_svg();
+        assert!(svg.contains(
+            "stroke-width=\"1\" stroke-opacity=\"0.5\" \
+             stroke-dasharray=\"4 2\" stroke-dashoffset=\"1\""
+        ));
+        let pdf = scene.to_pdf();
+        assert!(String::from_utf8_lossy(&pdf).contains("[4 2] 1 d"));
+    }
+
+    // 20 pixels of content per page.
+    #[rstest]
+    #[case(&[], 2)]
+    #[case(&[PageBreak::Before(8.0)], 3)]
+    fn test_print_pdf_has_a_page_per_span(
+        #[case] breaks: &[PageBreak],
+        #[case] count: usize,
+    ) {
+        let setup = PageSetup::builder()
+            .page_size([84.0, 40.0])
+            .margins([10.0; 4])
+            .dpi(72.0)
+            .build();
+        let pdf = scene().to_print_pdf(&setup, breaks);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains(&format!("/Count {count} >>")));
+        assert_eq!(text.matches("/MediaBox [0 0 84 40]").count(), count);
+    }
+}
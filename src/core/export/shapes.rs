@@ -0,0 +1,601 @@
+use std::{collections::HashMap, f32::consts::TAU};
+
+use super::{SceneTexture, VectorScene};
+use crate::core::{
+    ArcSegment, Image, PathCommand, Rectangle, TextureId, VectorPath,
+    pixel_transform,
+};
+
+// How far the control points of a quarter circle's cubic lie from its
+// ends, relative to the radius.
+const KAPPA: f32 = 0.552_284_8;
+// Repeating images with tiny tiles would otherwise emit millions of them.
+const MAX_TILES: usize = 4096;
+
+// The scene flattened into what both SVG and PDF can express: paths
+// with a fill or a stroke and placed bitmaps. Colors are sRGB encoded
+// with straight alpha.
+pub(super) struct Shapes {
+    pub(super) background: Option<[f32; 4]>,
+    pub(super) shapes:     Vec<Shape>,
+    pub(super) bitmaps:    Vec<SceneTexture>,
+}
+
+pub(super) struct Shape {
+    // x, y, width, height in pixels; `None` for clip rects that cover
+    // the whole scene
+    pub(super) clip_rect: Option<[f32; 4]>,
+    // maps the shape's points to pixels, as `[a, b, c, d, e, f]`
+    pub(super) transform: [f32; 6],
+    pub(super) kind:      ShapeKind,
+}
+
+pub(super) enum ShapeKind {
+    Paths(Vec<PaintedPath>),
+    Image(ImageShape),
+}
+
+pub(super) struct PaintedPath {
+    pub(super) commands: Vec<PathCommand>,
+    pub(super) paint:    Paint,
+    // standard deviation of a gaussian blur; PDF has no blur and draws
+    // blurred paths sharp
+    pub(super) blur:     f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Paint {
+    Fill {
+        color:       [f32; 4],
+        is_even_odd: bool,
+    },
+    Stroke {
+        color:      [f32; 4],
+        width:      f32,
+        round_caps: bool,
+        // dash_length, gap_length, dash_offset
+        dash:       Option<[f32; 3]>,
+    },
+}
+
+pub(super) struct ImageShape {
+    pub(super) bitmap:  usize,
+    pub(super) outline: Vec<PathCommand>,
+    // the outline's bounds with the cutout circle, filled even-odd
+    pub(super) cutout:  Option<Vec<PathCommand>>,
+    // x, y, width, height of each placement of the bitmap; negative
+    // sizes mirror it
+    pub(super) tiles:   Vec<[f32; 4]>,
+}
+
+#[must_use]
+pub(super) fn build(scene: &VectorScene) -> Shapes {
+    let mut builder = ShapeBuilder {
+        scene,
+        shapes: Vec::new(),
+        bitmaps: Vec::new(),
+        bitmap_indices: HashMap::new(),
+    };
+    for rect in &scene.rectangles {
+        builder.add_rectangle(rect);
+    }
+    for arc in &scene.arcs {
+        builder.add_arc(arc);
+    }
+    for path in &scene.paths {
+        builder.add_path(path);
+    }
+    for (texture_id, image) in &scene.images {
+        builder.add_image(*texture_id, image);
+    }
+
+    let [.., clear_alpha] = scene.clear_color;
+    Shapes {
+        background: (clear_alpha > 0.0).then(|| srgb(scene.clear_color)),
+        shapes:     builder.shapes,
+        bitmaps:    builder.bitmaps,
+    }
+}
+
+struct ShapeBuilder<'a> {
+    scene:          &'a VectorScene,
+    shapes:         Vec<Shape>,
+    bitmaps:        Vec<SceneTexture>,
+    // Bitmaps are tinted once per texture and tint color.
+    bitmap_indices: HashMap<(TextureId, [u32; 4]), usize>,
+}
+
+impl ShapeBuilder<'_> {
+    fn add_rectangle(&mut self, rect: &Rectangle) {
+        let [half_x, half_y, offset_x, offset_y] = rect.rect_and_shadow;
+        let [border_size, shadow_spread, shadow_blur, outline_size] =
+            rect.sizes;
+        if half_x <= 0.0 || half_y <= 0.0 {
+            return;
+        }
+        let mut paths = Vec::new();
+
+        let [.., shadow_alpha] = rect.shadow_color;
+        if shadow_alpha > 0.0 {
+            let radius = rect.corner_radii.into_iter().fold(0.0, f32::max);
+            let shadow = rounded_rect(
+                [offset_x, offset_y],
+                [half_x + shadow_spread, half_y + shadow_spread],
+                [radius + shadow_spread; 4],
+            );
+            // The shaders fade the shadow out over twice its blur.
+            paths.push(PaintedPath {
+                commands: shadow,
+                paint:    fill(rect.shadow_color),
+                blur:     shadow_blur.max(0.0) * 0.5,
+            });
+        }
+
+        let [.., outline_alpha] = rect.outline_color;
+        if outline_size > 0.0 && outline_alpha > 0.0 {
+            let half_outline = outline_size * 0.5;
+            let outline = rounded_rect(
+                [0.0, 0.0],
+                [half_x + half_outline, half_y + half_outline],
+                rect.corner_radii.map(|radius| radius + half_outline),
+            );
+            paths.push(PaintedPath {
+                commands: outline,
+                paint:    Paint::Stroke {
+                    color:      srgb(rect.outline_color),
+                    width:      outline_size,
+                    round_caps: false,
+                    dash:       None,
+                },
+                blur:     0.0,
+            });
+        }
+
+        let outer =
+            rounded_rect([0.0, 0.0], [half_x, half_y], rect.corner_radii);
+        let [.., border_alpha] = rect.border_color;
+        if border_size > 0.0 && border_alpha > 0.0 {
+            let inner = rounded_rect(
+                [0.0, 0.0],
+                [half_x - border_size, half_y - border_size],
+                rect.corner_radii
+                    .map(|radius| (radius - border_size).max(0.0)),
+            );
+            paths.push(PaintedPath {
+                commands: inner.clone(),
+                paint:    fill(rect.fill_color),
+                blur:     0.0,
+            });
+            if rect.is_dashed() {
+                // Both start the dashes at the end of the top left corner.
+                let [dash_length, gap_length, dash_offset, _] = rect.dash;
+                let half_border = border_size * 0.5;
+                let center = rounded_rect(
+                    [0.0, 0.0],
+                    [half_x - half_border, half_y - half_border],
+                    rect.corner_radii
+                        .map(|radius| (radius - half_border).max(0.0)),
+                );
+                paths.push(PaintedPath {
+                    commands: center,
+                    paint:    Paint::Stroke {
+                        color:      srgb(rect.border_color),
+                        width:      border_size,
+                        round_caps: false,
+                        dash:       Some([
+                            dash_length,
+                            gap_length,
+                            dash_offset,
+                        ]),
+                    },
+                    blur:     0.0,
+                });
+            } else {
+                paths.push(PaintedPath {
+                    commands: [outer, inner].concat(),
+                    paint:    Paint::Fill {
+                        color:       srgb(rect.border_color),
+                        is_even_odd: true,
+                    },
+                    blur:     0.0,
+                });
+            }
+        } else {
+            paths.push(PaintedPath {
+                commands: outer,
+                paint:    fill(rect.fill_color),
+                blur:     0.0,
+            });
+        }
+
+        self.push(
+            rect.clip_rect,
+            quad_transform(&rect.mvp, self.scene.size, [half_x, half_y]),
+            ShapeKind::Paths(paths),
+        );
+    }
+
+    fn add_arc(&mut self, arc: &ArcSegment) {
+        let [radius, thickness, start, sweep] = arc.geometry;
+        let [half_size, round_caps, ..] = arc.size_and_caps;
+        if half_size <= 0.0 {
+            return;
+        }
+        let path = PaintedPath {
+            commands: arc_commands(radius, start, sweep),
+            paint:    Paint::Stroke {
+                color:      srgb(arc.color),
+                width:      thickness,
+                round_caps: round_caps > 0.5,
+                dash:       None,
+            },
+            blur:     0.0,
+        };
+        self.push(
+            arc.clip_rect,
+            quad_transform(&arc.mvp, self.scene.size, [half_size; 2]),
+            ShapeKind::Paths(vec![path]),
+        );
+    }
+
+    // Paths are transformed into pixels up front, so that the stroke
+    // width stays in pixels.
+    fn add_path(&mut self, path: &VectorPath) {
+        let transform = pixel_transform(&path.mvp, self.scene.size);
+        let apply = |[x, y]: [f32; 2]| {
+            let [a, b, c, d, e, f] = transform;
+            [a * x + c * y + e, b * x + d * y + f]
+        };
+        let commands: Vec<_> = path
+            .commands
+            .iter()
+            .map(|command| map_points(*command, apply))
+            .collect();
+
+        let mut paths = Vec::new();
+        let [.., fill_alpha] = path.fill_color;
+        if fill_alpha > 0.0 {
+            paths.push(PaintedPath {
+                commands: commands.clone(),
+                paint:    Paint::Fill {
+                    color:       srgb(path.fill_color),
+                    is_even_odd: path.is_even_odd,
+                },
+                blur:     0.0,
+            });
+        }
+        let [.., stroke_alpha] = path.stroke_color;
+        if path.stroke_width > 0.0 && stroke_alpha > 0.0 {
+            paths.push(PaintedPath {
+                commands,
+                paint: Paint::Stroke {
+                    color:      srgb(path.stroke_color),
+                    width:      path.stroke_width,
+                    round_caps: false,
+                    dash:       path.is_dashed().then(|| {
+                        let [dash_length, gap_length] = path.dash_pattern;
+                        [dash_length, gap_length, path.dash_offset]
+                    }),
+                },
+                blur: 0.0,
+            });
+        }
+        if !paths.is_empty() {
+            let identity = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+            self.push(path.clip_rect, identity, ShapeKind::Paths(paths));
+        }
+    }
+
+    // Glyph coverage correction is left out, like on the vello backend.
+    fn add_image(&mut self, texture_id: TextureId, image: &Image) {
+        let [scale_x, scale_y, offset_x, offset_y] = image.uv_transform;
+        let [repeat_x, repeat_y, half_x, half_y] = image.repeat_and_size;
+        if half_x <= 0.0 || half_y <= 0.0 || scale_x == 0.0 || scale_y == 0.0 {
+            return;
+        }
+        let Some(bitmap) = self.bitmap(texture_id, image.tint_color) else {
+            return;
+        };
+
+        let outline =
+            rounded_rect([0.0, 0.0], [half_x, half_y], image.corner_radii);
+        let [cutout_x, cutout_y, cutout_radius, _] = image.cutout;
+        let cutout = (cutout_radius > 0.0).then(|| {
+            let bounds = rounded_rect([0.0, 0.0], [half_x, half_y], [0.0; 4]);
+            let circle = arc_commands(cutout_radius, 0.0, TAU)
+                .into_iter()
+                .map(|command| offset(command, [cutout_x, cutout_y]));
+            bounds.into_iter().chain(circle).collect()
+        });
+
+        let columns = tile_spans(half_x, offset_x, scale_x, repeat_x > 0.5);
+        let rows = tile_spans(half_y, offset_y, scale_y, repeat_y > 0.5);
+        let tiles = rows
+            .iter()
+            .flat_map(|[y, height]| {
+                columns.iter().map(|[x, width]| [*x, *y, *width, *height])
+            })
+            .take(MAX_TILES)
+            .collect();
+
+        let image_shape = ImageShape { bitmap, outline, cutout, tiles };
+        self.push(
+            image.clip_rect,
+            quad_transform(&image.mvp, self.scene.size, [half_x, half_y]),
+            ShapeKind::Image(image_shape),
+        );
+    }
+
+    // The texture multiplied by the tint color in linear space, as the
+    // shader does.
+    fn bitmap(
+        &mut self,
+        texture_id: TextureId,
+        tint_color: [f32; 4],
+    ) -> Option<usize> {
+        let key = (texture_id, tint_color.map(f32::to_bits));
+        if let Some(index) = self.bitmap_indices.get(&key) {
+            return Some(*index);
+        }
+        let texture = self.scene.textures.get(&texture_id)?;
+        let [width, height] = texture.size.map(|value| value as usize);
+        if width == 0
+            || height == 0
+            || texture.rgba.len() != width * height * 4
+        {
+            return None;
+        }
+        let rgba = match tint_color == [1.0; 4] {
+            true => texture.rgba.clone(),
+            false => texture
+                .rgba
+                .chunks_exact(4)
+                .flat_map(|texel| {
+                    let [red, green, blue, alpha] = tint_color;
+                    [
+                        encode(decode(texel[0]) * red),
+                        encode(decode(texel[1]) * green),
+                        encode(decode(texel[2]) * blue),
+                        (texel[3] as f32 * alpha).round() as u8,
+                    ]
+                })
+                .collect(),
+        };
+
+        let index = self.bitmaps.len();
+        self.bitmaps.push(SceneTexture { size: texture.size, rgba });
+        self.bitmap_indices.insert(key, index);
+        Some(index)
+    }
+
+    fn push(
+        &mut self,
+        clip_rect: [f32; 4],
+        transform: [f32; 6],
+        kind: ShapeKind,
+    ) {
+        let [x, y, width, height] = clip_rect;
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        let [scene_width, scene_height] = self.scene.size.map(|v| v as f32);
+        let covers_scene = x <= 0.0
+            && y <= 0.0
+            && x + width >= scene_width
+            && y + height >= scene_height;
+        let clip_rect = (!covers_scene).then_some(clip_rect);
+        self.shapes.push(Shape { clip_rect, transform, kind });
+    }
+}
+
+// Maps a quad's local units, the unit quad scaled by its half size, to
+// pixels.
+fn quad_transform(
+    mvp: &[[f32; 4]; 4],
+    size: [u32; 2],
+    half_size: [f32; 2],
+) -> [f32; 6] {
+    let [a, b, c, d, e, f] = pixel_transform(mvp, size);
+    let [half_x, half_y] = half_size;
+    [a / half_x, b / half_x, c / half_y, d / half_y, e, f]
+}
+
+// Where each tile of an axis starts and how long it is, in local units;
+// the texture coordinates 0 and 1 map to the start and end of a tile.
+fn tile_spans(
+    half: f32,
+    offset: f32,
+    scale: f32,
+    repeats: bool,
+) -> Vec<[f32; 2]> {
+    let local = |uv: f32| 2.0 * half * ((uv - offset) / scale - 0.5);
+    let start = local(0.0);
+    let length = local(1.0) - start;
+    if !repeats {
+        return vec![[start, length]];
+    }
+    let period = length.abs();
+    let lower = start.min(start + length);
+    let first = ((-half - lower) / period).floor() as i64;
+    let last = ((half - lower) / period).ceil() as i64 - 1;
+    (first..=last)
+        .take(MAX_TILES)
+        .map(|index| [start + index as f32 * period, length])
+        .collect()
+}
+
+// The radii are ordered like in the shaders: top left, top right, bottom
+// right, bottom left.
+fn rounded_rect(
+    center: [f32; 2],
+    half_size: [f32; 2],
+    radii: [f32; 4],
+) -> Vec<PathCommand> {
+    let [half_x, half_y] = half_size.map(|value| value.max(0.0));
+    let [left, top, right, bottom] = [-half_x, -half_y, half_x, half_y];
+    let [top_left, top_right, bottom_right, bottom_left] =
+        radii.map(|radius| radius.clamp(0.0, half_x.min(half_y)));
+    // Square corners are left as the lines' meeting point.
+    let corner = |from: [f32; 2], corner: [f32; 2], to: [f32; 2]| {
+        let lerp = |a: [f32; 2]| {
+            [
+                a[0] + (corner[0] - a[0]) * KAPPA,
+                a[1] + (corner[1] - a[1]) * KAPPA,
+            ]
+        };
+        (from != to).then(|| PathCommand::CubicTo(lerp(from), lerp(to), to))
+    };
+
+    let commands = [
+        Some(PathCommand::MoveTo([left + top_left, top])),
+        Some(PathCommand::LineTo([right - top_right, top])),
+        corner(
+            [right - top_right, top],
+            [right, top],
+            [right, top + top_right],
+        ),
+        Some(PathCommand::LineTo([right, bottom - bottom_right])),
+        corner(
+            [right, bottom - bottom_right],
+            [right, bottom],
+            [right - bottom_right, bottom],
+        ),
+        Some(PathCommand::LineTo([left + bottom_left, bottom])),
+        corner(
+            [left + bottom_left, bottom],
+            [left, bottom],
+            [left, bottom - bottom_left],
+        ),
+        Some(PathCommand::LineTo([left, top + top_left])),
+        corner([left, top + top_left], [left, top], [left + top_left, top]),
+        Some(PathCommand::Close),
+    ];
+    commands
+        .into_iter()
+        .flatten()
+        .map(|command| offset(command, center))
+        .collect()
+}
+
+// A circular arc around the origin, clockwise on screen from `start`,
+// as cubics of at most a quarter turn each.
+fn arc_commands(radius: f32, start: f32, sweep: f32) -> Vec<PathCommand> {
+    let sweep = sweep.clamp(0.0, TAU);
+    let point = |angle: f32| [radius * angle.cos(), radius * angle.sin()];
+    let segment_count = (sweep / (TAU / 4.0)).ceil().max(1.0) as usize;
+    let step = sweep / segment_count as f32;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+
+    let mut commands = vec![PathCommand::MoveTo(point(start))];
+    for index in 0..segment_count {
+        let from = start + step * index as f32;
+        let to = from + step;
+        let [from_x, from_y] = point(from);
+        let [to_x, to_y] = point(to);
+        commands.push(PathCommand::CubicTo(
+            [from_x - handle * from.sin(), from_y + handle * from.cos()],
+            [to_x + handle * to.sin(), to_y - handle * to.cos()],
+            [to_x, to_y],
+        ));
+    }
+    if sweep >= TAU {
+        commands.push(PathCommand::Close);
+    }
+    commands
+}
+
+fn offset(command: PathCommand, by: [f32; 2]) -> PathCommand {
+    map_points(command, |[x, y]| [x + by[0], y + by[1]])
+}
+
+fn map_points(
+    command: PathCommand,
+    apply: impl Fn([f32; 2]) -> [f32; 2],
+) -> PathCommand {
+    match command {
+        PathCommand::MoveTo(to) => PathCommand::MoveTo(apply(to)),
+        PathCommand::LineTo(to) => PathCommand::LineTo(apply(to)),
+        PathCommand::QuadTo(control, to) => {
+            PathCommand::QuadTo(apply(control), apply(to))
+        }
+        PathCommand::CubicTo(control_0, control_1, to) => {
+            PathCommand::CubicTo(apply(control_0), apply(control_1), apply(to))
+        }
+        PathCommand::Close => PathCommand::Close,
+    }
+}
+
+// Coordinates rounded to a thousandth of a pixel, without trailing zeros.
+#[must_use]
+pub(super) fn number(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" | "" => "0".to_owned(),
+        _ => text.to_owned(),
+    }
+}
+
+fn fill(linear: [f32; 4]) -> Paint {
+    Paint::Fill { color: srgb(linear), is_even_odd: false }
+}
+
+// Instance colors are linear, like the values written to an sRGB target.
+fn srgb(linear: [f32; 4]) -> [f32; 4] {
+    let [red, green, blue, alpha] = linear;
+    let [red, green, blue] =
+        [red, green, blue].map(|value| encode(value) as f32 / 255.0);
+    [red, green, blue, alpha.clamp(0.0, 1.0)]
+}
+
+fn decode(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn encode(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PathCommand, arc_commands, tile_spans};
+
+    #[rstest]
+    #[case(false, 1)]
+    #[case(true, 5)]
+    fn test_tile_spans_cover_the_image(
+        #[case] repeats: bool,
+        #[case] count: usize,
+    ) {
+        // A quarter of the texture per half of the image, shifted by half
+        // a tile.
+        let spans = tile_spans(10.0, 0.5, 4.0, repeats);
+        assert_eq!(spans.len(), count);
+        assert_eq!(spans[0], [-12.5, 5.0]);
+        assert!(spans.iter().all(|[_, length]| *length == 5.0));
+    }
+
+    #[rstest]
+    fn test_full_circle_is_closed() {
+        let commands = arc_commands(10.0, 0.0, std::f32::consts::TAU);
+        assert_eq!(commands.len(), 6);
+        assert_eq!(commands.last(), Some(&PathCommand::Close));
+        let Some(PathCommand::CubicTo(_, _, [x, y])) = commands.get(4) else {
+            panic!("expected a cubic");
+        };
+        assert!((x - 10.0).abs() < 1e-3 && y.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Write;
+
+use super::{
+    SceneTexture, VectorScene,
+    shapes::{self, ImageShape, Paint, PaintedPath, Shape, ShapeKind, number},
+};
+use crate::core::PathCommand;
+
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[must_use]
+pub(super) fn write(scene: &VectorScene) -> String {
+    let shapes = shapes::build(scene);
+    let [width, height] = scene.size;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+         height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    );
+
+    // Each bitmap is embedded once and placed with `use` for every tile.
+    if !shapes.bitmaps.is_empty() {
+        svg.push_str("<defs>\n");
+        for (index, bitmap) in shapes.bitmaps.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<image id=\"bitmap-{index}\" width=\"1\" height=\"1\" \
+                 preserveAspectRatio=\"none\" \
+                 href=\"data:image/png;base64,{}\"/>",
+                base64(&encode_png(bitmap))
+            );
+        }
+        svg.push_str("</defs>\n");
+    }
+    if let Some(color) = shapes.background {
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{width}\" height=\"{height}\"{}/>",
+            paint_attributes(Paint::Fill { color, is_even_odd: false })
+        );
+    }
+    for (index, shape) in shapes.shapes.iter().enumerate() {
+        write_shape(&mut svg, index, shape);
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn write_shape(svg: &mut String, index: usize, shape: &Shape) {
+    if let Some([x, y, width, height]) = shape.clip_rect {
+        let _ = writeln!(
+            svg,
+            "<clipPath id=\"clip-{index}\"><rect x=\"{}\" y=\"{}\" \
+             width=\"{}\" height=\"{}\"/></clipPath>\n\
+             <g clip-path=\"url(#clip-{index})\">",
+            number(x),
+            number(y),
+            number(width),
+            number(height)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<g transform=\"matrix({})\">",
+        shape.transform.map(number).join(" ")
+    );
+
+    match &shape.kind {
+        ShapeKind::Paths(paths) => {
+            for (path_index, path) in paths.iter().enumerate() {
+                write_path(svg, &format!("{index}-{path_index}"), path);
+            }
+        }
+        ShapeKind::Image(image) => write_image(svg, index, image),
+    }
+
+    svg.push_str("</g>\n");
+    if shape.clip_rect.is_some() {
+        svg.push_str("</g>\n");
+    }
+}
+
+fn write_path(svg: &mut String, id: &str, path: &PaintedPath) {
+    let mut filter = String::new();
+    if path.blur > 0.0 {
+        let _ = writeln!(
+            svg,
+            "<filter id=\"blur-{id}\" x=\"-1\" y=\"-1\" width=\"3\" \
+             height=\"3\"><feGaussianBlur stdDeviation=\"{}\"/></filter>",
+            number(path.blur)
+        );
+        filter = format!(" filter=\"url(#blur-{id})\"");
+    }
+    let _ = writeln!(
+        svg,
+        "<path d=\"{}\"{}{filter}/>",
+        path_data(&path.commands),
+        paint_attributes(path.paint)
+    );
+}
+
+fn write_image(svg: &mut String, index: usize, image: &ImageShape) {
+    let _ = writeln!(
+        svg,
+        "<clipPath id=\"outline-{index}\"><path d=\"{}\"/></clipPath>\n\
+         <g clip-path=\"url(#outline-{index})\">",
+        path_data(&image.outline)
+    );
+    if let Some(cutout) = &image.cutout {
+        let _ = writeln!(
+            svg,
+            "<clipPath id=\"cutout-{index}\"><path d=\"{}\" \
+             clip-rule=\"evenodd\"/></clipPath>\n\
+             <g clip-path=\"url(#cutout-{index})\">",
+            path_data(cutout)
+        );
+    }
+    for [x, y, width, height] in &image.tiles {
+        let _ = writeln!(
+            svg,
+            "<use href=\"#bitmap-{}\" transform=\"matrix({} 0 0 {} {} {})\"/>",
+            image.bitmap,
+            number(*width),
+            number(*height),
+            number(*x),
+            number(*y)
+        );
+    }
+    if image.cutout.is_some() {
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</g>\n");
+}
+
+fn paint_attributes(paint: Paint) -> String {
+    match paint {
+        Paint::Fill { color, is_even_odd } => {
+            let mut attributes = format!(" fill=\"{}\"", hex(color));
+            if color[3] < 1.0 {
+                let _ = write!(
+                    attributes,
+                    " fill-opacity=\"{}\"",
+                    number(color[3])
+                );
+            }
+            if is_even_odd {
+                attributes.push_str(" fill-rule=\"evenodd\"");
+            }
+            attributes
+        }
+        Paint::Stroke { color, width, round_caps, dash } => {
+            let mut attributes = format!(
+                " fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"",
+                hex(color),
+                number(width)
+            );
+            if color[3] < 1.0 {
+                let _ = write!(
+                    attributes,
+                    " stroke-opacity=\"{}\"",
+                    number(color[3])
+                );
+            }
+            if round_caps {
+                attributes.push_str(" stroke-linecap=\"round\"");
+            }
+            if let Some([dash_length, gap_length, dash_offset]) = dash {
+                let _ = write!(
+                    attributes,
+                    " stroke-dasharray=\"{} {}\" stroke-dashoffset=\"{}\"",
+                    number(dash_length),
+                    number(gap_length),
+                    number(dash_offset)
+                );
+            }
+            attributes
+        }
+    }
+}
+
+fn path_data(commands: &[PathCommand]) -> String {
+    let point = |[x, y]: [f32; 2]| format!("{} {}", number(x), number(y));
+    commands
+        .iter()
+        .map(|command| match *command {
+            PathCommand::MoveTo(to) => format!("M{}", point(to)),
+            PathCommand::LineTo(to) => format!("L{}", point(to)),
+            PathCommand::QuadTo(control, to) => {
+                format!("Q{} {}", point(control), point(to))
+            }
+            PathCommand::CubicTo(control_0, control_1, to) => {
+                format!(
+                    "C{} {} {}",
+                    point(control_0),
+                    point(control_1),
+                    point(to)
+                )
+            }
+            PathCommand::Close => "Z".to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn hex(color: [f32; 4]) -> String {
+    let [red, green, blue, _] =
+        color.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{red:02x}{green:02x}{blue:02x}")
+}
+
+// An RGBA PNG with uncompressed deflate blocks, which every decoder
+// reads and which needs no compression library.
+fn encode_png(bitmap: &SceneTexture) -> Vec<u8> {
+    let [width, height] = bitmap.size;
+    let mut scanlines =
+        Vec::with_capacity(bitmap.rgba.len() + height as usize);
+    for row in bitmap.rgba.chunks_exact(width as usize * 4) {
+        // no filter
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(kind.iter().chain(data));
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let length = block.len() as u16;
+        zlib.push(is_last as u8);
+        zlib.extend_from_slice(&length.to_le_bytes());
+        zlib.extend_from_slice(&(!length).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let [mut a, mut b] = [1_u32, 0_u32];
+    for byte in data {
+        a = (a + *byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let value =
+            group.iter().enumerate().fold(0_u32, |value, (i, byte)| {
+                value | (*byte as u32) << (16 - 8 * i)
+            });
+        for i in 0..4 {
+            if i <= group.len() {
+                let index = (value >> (18 - 6 * i)) & 0x3F;
+                text.push(BASE64[index as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{adler32, base64, crc32};
+
+    #[rstest]
+    #[case(b"", "")]
+    #[case(b"f", "Zg==")]
+    #[case(b"fo", "Zm8=")]
+    #[case(b"foobar", "Zm9vYmFy")]
+    fn test_base64(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(base64(bytes), expected);
+    }
+
+    #[rstest]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND".iter()), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
@@ -0,0 +1,91 @@
+use bon::Builder;
+use bytemuck::{Pod, Zeroable};
+use slotmap::DefaultKey;
+use wgpu::{VertexBufferLayout, VertexStepMode, vertex_attr_array};
+
+pub type ImageId = DefaultKey;
+pub type TextureId = DefaultKey;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageRepeat {
+    NoRepeat,
+    RepeatX,
+    RepeatY,
+    Tile,
+}
+
+impl ImageRepeat {
+    #[must_use]
+    pub const fn repeats(&self) -> [bool; 2] {
+        match self {
+            Self::NoRepeat => [false, false],
+            Self::RepeatX => [true, false],
+            Self::RepeatY => [false, true],
+            Self::Tile => [true, true],
+        }
+    }
+}
+
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Zeroable, Pod, Builder)]
+#[builder(const)]
+pub struct Image {
+    pub mvp:             [[f32; 4]; 4],
+    pub tint_color:      [f32; 4],
+    pub corner_radii:    [f32; 4],
+    // x, y, width, height in physical pixels
+    pub clip_rect:       [f32; 4],
+    // tile_scale.x, tile_scale.y, tile_offset.x, tile_offset.y
+    pub uv_transform:    [f32; 4],
+    // repeat_x, repeat_y, half_size.x, half_size.y
+    pub repeat_and_size: [f32; 4],
+    // center.x, center.y, radius, unused, relative to the image center
+    pub cutout:          [f32; 4],
+    // gamma, contrast, unused, unused; a gamma above zero treats the
+    // texture's alpha as glyph coverage and corrects it
+    #[builder(default = [0.0; 4])]
+    pub coverage:        [f32; 4],
+}
+
+impl Image {
+    pub(crate) const LAYOUT: VertexBufferLayout<'static> = {
+        let instance_buffer_atributes = &vertex_attr_array![
+            1 => Float32x4, // mvp matrix, row 0
+            2 => Float32x4, // mvp matrix, row 1
+            3 => Float32x4, // mvp matrix, row 2
+            4 => Float32x4, // mvp matrix, row 3
+            5 => Float32x4, // tint color
+            6 => Float32x4, // corner radii
+            7 => Float32x4, // clip rect
+            8 => Float32x4, // tile_scale.xy, tile_offset.zw
+            9 => Float32x4, // repeat_x, repeat_y, half_size.zw
+            10 => Float32x4, // cutout center.xy, cutout radius
+            11 => Float32x4, // coverage gamma, contrast
+        ];
+        VertexBufferLayout {
+            array_stride: Self::SIZE as u64,
+            step_mode:    VertexStepMode::Instance,
+            attributes:   instance_buffer_atributes,
+        }
+    };
+    pub const SIZE: usize = size_of::<Self>();
+
+    #[must_use]
+    pub const fn repeat(&self) -> ImageRepeat {
+        let [repeat_x, repeat_y, ..] = self.repeat_and_size;
+        match (repeat_x > 0.5, repeat_y > 0.5) {
+            (false, false) => ImageRepeat::NoRepeat,
+            (true, false) => ImageRepeat::RepeatX,
+            (false, true) => ImageRepeat::RepeatY,
+            (true, true) => ImageRepeat::Tile,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn set_repeat(&mut self, repeat: ImageRepeat) {
+        let [repeat_x, repeat_y] = repeat.repeats();
+        self.repeat_and_size[0] = repeat_x as u8 as f32;
+        self.repeat_and_size[1] = repeat_y as u8 as f32;
+    }
+}
@@ -0,0 +1,118 @@
+struct VertexInput {
+    @location(0) position:        vec3<f32>,
+    @location(1) mvp_0:           vec4<f32>,
+    @location(2) mvp_1:           vec4<f32>,
+    @location(3) mvp_2:           vec4<f32>,
+    @location(4) mvp_3:           vec4<f32>,
+    @location(5) tint_color:      vec4<f32>,
+    @location(6) corner_radii:    vec4<f32>,
+    @location(7) clip_rect:       vec4<f32>,
+    @location(8) uv_transform:    vec4<f32>,
+    @location(9) repeat_and_size: vec4<f32>,
+    @location(10) cutout:         vec4<f32>,
+    @location(11) coverage:       vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position)              clip_position:  vec4<f32>,
+    @location(0)                    uv:             vec2<f32>,
+    @location(1)                    local_position: vec2<f32>,
+    @location(2) @interpolate(flat) tint_color:     vec4<f32>,
+    @location(3) @interpolate(flat) corner_radii:   vec4<f32>,
+    @location(4) @interpolate(flat) clip_rect:      vec4<f32>,
+    @location(5) @interpolate(flat) repeat:         vec2<f32>,
+    @location(6) @interpolate(flat) half_size:      vec2<f32>,
+    @location(7) @interpolate(flat) cutout:         vec3<f32>,
+    @location(8) @interpolate(flat) coverage:       vec2<f32>,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    let mvp = mat4x4<f32>(
+        input.mvp_0,
+        input.mvp_1,
+        input.mvp_2,
+        input.mvp_3,
+    );
+
+    let tile_scale  = input.uv_transform.xy;
+    let tile_offset = input.uv_transform.zw;
+    let half_size   = input.repeat_and_size.zw;
+    let base_uv     = vec2<f32>(
+        input.position.x * 0.5 + 0.5,
+        input.position.y * 0.5 + 0.5,
+    );
+
+    var output: VertexOutput;
+    output.clip_position  = mvp * vec4<f32>(input.position, 1.0);
+    output.uv             = base_uv * tile_scale + tile_offset;
+    output.local_position = input.position.xy * half_size;
+    output.tint_color     = input.tint_color;
+    output.corner_radii   = input.corner_radii;
+    output.clip_rect      = input.clip_rect;
+    output.repeat         = input.repeat_and_size.xy;
+    output.half_size      = half_size;
+    output.cutout         = input.cutout.xyz;
+    output.coverage       = input.coverage.xy;
+
+    return output;
+}
+
+@group(0) @binding(0) var image_texture: texture_2d<f32>;
+@group(1) @binding(0) var image_sampler: sampler;
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(image_texture, image_sampler, input.uv);
+    if input.coverage.x > 0.0 {
+        color.a = correct_coverage(color.a, input.coverage.x, input.coverage.y);
+    }
+
+    let clip = input.clip_rect;
+    let pos  = input.clip_position.xy;
+    if pos.x < clip.x
+        || pos.x > clip.x + clip.z
+        || pos.y < clip.y
+        || pos.y > clip.y + clip.w
+    { discard; }
+
+    // Axes that don't repeat draw the image once and leave the rest of the
+    // quad empty instead of stretching the edge texels.
+    let outside = (input.uv < vec2<f32>(0.0)) | (input.uv > vec2<f32>(1.0));
+    if (outside.x && input.repeat.x < 0.5) || (outside.y && input.repeat.y < 0.5)
+    { discard; }
+
+    var distance = sd_rounded_rect_4(
+        input.local_position,
+        input.half_size,
+        input.corner_radii,
+    );
+    // A circle punched out of the image, e.g. to make room for a badge
+    // that is drawn underneath it.
+    if input.cutout.z > 0.0 {
+        let cutout_distance =
+            input.cutout.z - length(input.local_position - input.cutout.xy);
+        distance = max(distance, cutout_distance);
+    }
+    let aa_width = fwidth(distance) * 1.4;
+    let alpha    = 1.0 - smoothstep(0.0, aa_width, distance);
+
+    let tinted = color * input.tint_color;
+    return vec4<f32>(tinted.rgb, tinted.a * alpha);
+}
+
+fn sd_rounded_rect_4(p: vec2<f32>, half_size: vec2<f32>, radii: vec4<f32>) -> f32 {
+    var r = select(radii.xw, radii.yz, p.x > 0.0);
+    r = vec2<f32>(select(r.x, r.y, p.y > 0.0));
+
+    let q = abs(p) - half_size + r;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - r.x;
+}
+
+// Glyph coverage is boosted in the middle by the contrast and then
+// gamma-corrected, so that thin stems don't wash out on light-on-dark
+// or low-DPI displays.
+fn correct_coverage(coverage: f32, gamma: f32, contrast: f32) -> f32 {
+    let boosted = clamp(coverage + contrast * coverage * (1.0 - coverage), 0.0, 1.0);
+    return pow(boosted, 1.0 / gamma);
+}
@@ -0,0 +1,212 @@
+use wgpu::{
+    AddressMode, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource,
+    BindingType, Color, ColorTargetState, ColorWrites, CommandEncoder, Device,
+    FilterMode, FragmentState, LoadOp, Operations, PipelineLayoutDescriptor,
+    PrimitiveState, PrimitiveTopology, RenderPassColorAttachment,
+    RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor, Sampler,
+    SamplerBindingType, SamplerDescriptor, ShaderModuleDescriptor,
+    ShaderSource, ShaderStages, StoreOp, Texture, TextureFormat,
+    TextureSampleType, TextureView, TextureViewDescriptor,
+    TextureViewDimension, VertexState,
+};
+
+#[must_use]
+pub const fn mip_level_count(size: [u32; 2]) -> u32 {
+    let max_side = if size[0] > size[1] { size[0] } else { size[1] };
+    u32::BITS - (max_side | 1).leading_zeros()
+}
+
+pub struct MipmapGenerator {
+    render_pipeline:   RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    sampler:           Sampler,
+}
+
+impl MipmapGenerator {
+    #[must_use]
+    pub fn new(device: &Device, format: TextureFormat) -> Self {
+        let sampler_desc = SamplerDescriptor {
+            label: Some("hui::mipmap::sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        };
+        let sampler = device.create_sampler(&sampler_desc);
+
+        let bind_group_layout_entries = [
+            BindGroupLayoutEntry {
+                binding:    0,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         BindingType::Texture {
+                    sample_type:    TextureSampleType::Float {
+                        filterable: true,
+                    },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled:   false,
+                },
+                count:      None,
+            },
+            BindGroupLayoutEntry {
+                binding:    1,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         BindingType::Sampler(
+                    SamplerBindingType::Filtering,
+                ),
+                count:      None,
+            },
+        ];
+        let bind_group_layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::mipmap::bind_group_layout"),
+            entries: &bind_group_layout_entries,
+        };
+        let bind_group_layout =
+            device.create_bind_group_layout(&bind_group_layout_desc);
+
+        let render_pipeline =
+            create_render_pipeline(device, format, &bind_group_layout);
+
+        Self { render_pipeline, bind_group_layout, sampler }
+    }
+
+    pub fn generate(
+        &self,
+        device: &Device,
+        command_encoder: &mut CommandEncoder,
+        texture: &Texture,
+    ) {
+        let mip_views: Vec<TextureView> = (0..texture.mip_level_count())
+            .map(|level| {
+                texture.create_view(&TextureViewDescriptor {
+                    label: Some("hui::mipmap::mip_view"),
+                    base_mip_level: level,
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        for levels in mip_views.windows(2) {
+            let [source_view, target_view] = levels else { continue };
+
+            let bind_group_entries = [
+                BindGroupEntry {
+                    binding:  0,
+                    resource: BindingResource::TextureView(source_view),
+                },
+                BindGroupEntry {
+                    binding:  1,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+            ];
+            let bind_group_desc = BindGroupDescriptor {
+                label:   Some("hui::mipmap::bind_group"),
+                layout:  &self.bind_group_layout,
+                entries: &bind_group_entries,
+            };
+            let bind_group = device.create_bind_group(&bind_group_desc);
+
+            let color_attachment = RenderPassColorAttachment {
+                view:           target_view,
+                depth_slice:    None,
+                resolve_target: None,
+                ops:            Operations {
+                    load:  LoadOp::Clear(Color::TRANSPARENT),
+                    store: StoreOp::Store,
+                },
+            };
+            let render_pass_desc = RenderPassDescriptor {
+                label:                    Some("hui::mipmap_render_pass"),
+                color_attachments:        &[Some(color_attachment)],
+                depth_stencil_attachment: None,
+                occlusion_query_set:      None,
+                timestamp_writes:         None,
+            };
+            let mut render_pass =
+                command_encoder.begin_render_pass(&render_pass_desc);
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+fn create_render_pipeline(
+    device: &Device,
+    format: TextureFormat,
+    bind_group_layout: &BindGroupLayout,
+) -> RenderPipeline {
+    let shader_module_content =
+        ShaderSource::Wgsl(include_str!("mipmap.wgsl").into());
+    let shader_module_desc = ShaderModuleDescriptor {
+        label:  Some("hui::mipmap::shader_module"),
+        source: shader_module_content,
+    };
+    let shader_module = device.create_shader_module(shader_module_desc);
+
+    let vertex_state = VertexState {
+        module:              &shader_module,
+        entry_point:         Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers:             &[],
+    };
+
+    let fragment_state_targets = [Some(ColorTargetState {
+        format,
+        blend: None,
+        write_mask: ColorWrites::ALL,
+    })];
+    let fragment_state = FragmentState {
+        module:              &shader_module,
+        entry_point:         Some("fs_main"),
+        compilation_options: Default::default(),
+        targets:             &fragment_state_targets,
+    };
+
+    let primitive_state = PrimitiveState {
+        topology: PrimitiveTopology::TriangleList,
+        ..Default::default()
+    };
+
+    let pipeline_layout_desc = PipelineLayoutDescriptor {
+        label:                Some("hui::mipmap::pipeline_layout"),
+        bind_group_layouts:   &[bind_group_layout],
+        push_constant_ranges: &[],
+    };
+    let pipeline_layout = device.create_pipeline_layout(&pipeline_layout_desc);
+
+    let render_pipeline_desc = RenderPipelineDescriptor {
+        label:         Some("hui::mipmap::render_pipeline"),
+        layout:        Some(&pipeline_layout),
+        vertex:        vertex_state,
+        fragment:      Some(fragment_state),
+        primitive:     primitive_state,
+        depth_stencil: None,
+        multisample:   Default::default(),
+        multiview:     None,
+        cache:         None,
+    };
+    device.create_render_pipeline(&render_pipeline_desc)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::mip_level_count;
+
+    #[rstest]
+    #[case([1, 1], 1)]
+    #[case([2, 1], 2)]
+    #[case([256, 256], 9)]
+    #[case([300, 17], 9)]
+    #[case([17, 1024], 11)]
+    #[case([0, 0], 1)]
+    fn test_mip_level_count(#[case] size: [u32; 2], #[case] expected: u32) {
+        assert_eq!(mip_level_count(size), expected);
+    }
+}
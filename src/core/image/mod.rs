@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+mod image;
+mod mipmap;
+mod renderer;
+
+pub use image::{Image, ImageId, ImageRepeat, TextureId};
+use mipmap::{MipmapGenerator, mip_level_count};
+pub use renderer::ImageRenderer;
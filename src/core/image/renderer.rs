@@ -0,0 +1,668 @@
+use std::ops::Range;
+
+use slotmap::SlotMap;
+use wgpu::{
+    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry,
+    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BlendComponent, BlendFactor, BlendOperation,
+    BlendState, Buffer, BufferUsages, ColorTargetState, ColorWrites,
+    CommandEncoderDescriptor, Device, Extent3d, FilterMode, FragmentState,
+    FrontFace, IndexFormat, MultisampleState, Origin3d,
+    PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology,
+    Queue, RenderPass, RenderPipeline, RenderPipelineDescriptor,
+    SamplerBindingType, SamplerDescriptor, ShaderModuleDescriptor,
+    ShaderSource, ShaderStages, TexelCopyBufferLayout, TexelCopyTextureInfo,
+    Texture, TextureAspect, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureSampleType, TextureUsages, TextureView,
+    TextureViewDimension, VertexBufferLayout, VertexState, VertexStepMode,
+    util::{BufferInitDescriptor, DeviceExt},
+    vertex_attr_array,
+};
+
+use super::{
+    Image, ImageId, ImageRepeat, MipmapGenerator, TextureId, mip_level_count,
+};
+use crate::{
+    core::{
+        InstanceBuffer, RenderPhase, capture::FrameRecorder,
+        memory::texture_bytes, phase_range,
+    },
+    instrument::{counter, span},
+};
+
+#[rustfmt::skip]
+const VERTICES: &[[f32; 3]; 4] = &[
+    [-1.0,  1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [ 1.0,  1.0, 0.0],
+    [ 1.0, -1.0, 0.0],
+];
+#[rustfmt::skip]
+const INDICES: &[u16; 6] = &[
+    1, 0, 2,
+    1, 3, 2,
+];
+
+const TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+const REPEATS: [ImageRepeat; 4] = [
+    ImageRepeat::NoRepeat,
+    ImageRepeat::RepeatX,
+    ImageRepeat::RepeatY,
+    ImageRepeat::Tile,
+];
+
+struct ImageTexture {
+    texture:    Texture,
+    view:       TextureView,
+    bind_group: BindGroup,
+}
+
+struct ImageBatch {
+    phase:         RenderPhase,
+    texture_id:    TextureId,
+    sampler_index: usize,
+    instances:     Range<u32>,
+}
+
+pub struct ImageRenderer {
+    render_pipeline:           RenderPipeline,
+    vertex_buffer:             Buffer,
+    index_buffer:              Buffer,
+    instance_buffer:           InstanceBuffer,
+    texture_bind_group_layout: BindGroupLayout,
+    sampler_bind_groups:       [BindGroup; 4],
+    mipmap_generator:          MipmapGenerator,
+    textures:                  SlotMap<TextureId, ImageTexture>,
+    images: SlotMap<ImageId, (RenderPhase, TextureId, Image)>,
+    instance_bytes:            Vec<u8>,
+    batches:                   Vec<ImageBatch>,
+}
+
+impl ImageRenderer {
+    #[must_use]
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let texture_bind_group_layout_entries = [BindGroupLayoutEntry {
+            binding:    0,
+            visibility: ShaderStages::FRAGMENT,
+            ty:         BindingType::Texture {
+                sample_type:    TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::D2,
+                multisampled:   false,
+            },
+            count:      None,
+        }];
+        let texture_bind_group_layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::image::texture_bind_group_layout"),
+            entries: &texture_bind_group_layout_entries,
+        };
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&texture_bind_group_layout_desc);
+
+        let sampler_bind_group_layout_entries = [BindGroupLayoutEntry {
+            binding:    0,
+            visibility: ShaderStages::FRAGMENT,
+            ty:         BindingType::Sampler(SamplerBindingType::Filtering),
+            count:      None,
+        }];
+        let sampler_bind_group_layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::image::sampler_bind_group_layout"),
+            entries: &sampler_bind_group_layout_entries,
+        };
+        let sampler_bind_group_layout =
+            device.create_bind_group_layout(&sampler_bind_group_layout_desc);
+
+        let sampler_bind_groups = REPEATS.map(|repeat| {
+            create_sampler_bind_group(
+                device,
+                &sampler_bind_group_layout,
+                repeat,
+            )
+        });
+
+        let render_pipeline = create_render_pipeline(
+            device,
+            surface_format,
+            &texture_bind_group_layout,
+            &sampler_bind_group_layout,
+        );
+
+        let vertex_buffer_desc = BufferInitDescriptor {
+            label:    Some("hui::image::vertex_buffer"),
+            contents: bytemuck::cast_slice(VERTICES),
+            usage:    BufferUsages::VERTEX,
+        };
+        let vertex_buffer = device.create_buffer_init(&vertex_buffer_desc);
+
+        let index_buffer_desc = BufferInitDescriptor {
+            label:    Some("hui::image::index_buffer"),
+            contents: bytemuck::cast_slice(INDICES),
+            usage:    BufferUsages::INDEX,
+        };
+        let index_buffer = device.create_buffer_init(&index_buffer_desc);
+
+        let instance_buffer = InstanceBuffer::new(
+            device,
+            "hui::image::instance_buffer",
+            Image::SIZE,
+        );
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            texture_bind_group_layout,
+            sampler_bind_groups,
+            mipmap_generator: MipmapGenerator::new(device, TEXTURE_FORMAT),
+            textures: SlotMap::new(),
+            images: SlotMap::new(),
+            instance_bytes: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn add_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        size: [u32; 2],
+        rgba: &[u8],
+    ) -> TextureId {
+        let extent = Extent3d {
+            width:                 size[0],
+            height:                size[1],
+            depth_or_array_layers: 1,
+        };
+        let texture_desc = TextureDescriptor {
+            label:           Some("hui::image::texture"),
+            size:            extent,
+            mip_level_count: mip_level_count(size),
+            sample_count:    1,
+            dimension:       TextureDimension::D2,
+            format:          TEXTURE_FORMAT,
+            usage:           TextureUsages::TEXTURE_BINDING
+                | TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_DST,
+            view_formats:    &[],
+        };
+        let texture = device.create_texture(&texture_desc);
+
+        let copy_texture_info = TexelCopyTextureInfo {
+            texture:   &texture,
+            mip_level: 0,
+            origin:    Default::default(),
+            aspect:    TextureAspect::All,
+        };
+        let copy_buffer_layout = TexelCopyBufferLayout {
+            offset:         0,
+            bytes_per_row:  Some(4 * size[0]),
+            rows_per_image: Some(size[1]),
+        };
+        queue.write_texture(
+            copy_texture_info,
+            rgba,
+            copy_buffer_layout,
+            extent,
+        );
+
+        if texture.mip_level_count() > 1 {
+            let command_encoder_desc = CommandEncoderDescriptor {
+                label: Some("hui::image::mipmap_command_encoder"),
+            };
+            let mut command_encoder =
+                device.create_command_encoder(&command_encoder_desc);
+            self.mipmap_generator.generate(
+                device,
+                &mut command_encoder,
+                &texture,
+            );
+            queue.submit([command_encoder.finish()]);
+        }
+
+        let view = texture.create_view(&Default::default());
+
+        let bind_group_entries = [BindGroupEntry {
+            binding:  0,
+            resource: BindingResource::TextureView(&view),
+        }];
+        let bind_group_desc = BindGroupDescriptor {
+            label:   Some("hui::image::texture_bind_group"),
+            layout:  &self.texture_bind_group_layout,
+            entries: &bind_group_entries,
+        };
+        let bind_group = device.create_bind_group(&bind_group_desc);
+
+        self.textures
+            .insert(ImageTexture { texture, view, bind_group })
+    }
+
+    // Overwrites a region of the texture's top level and regenerates its
+    // mipmaps. Returns false for an unknown texture.
+    pub fn write_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        id: TextureId,
+        origin: [u32; 2],
+        size: [u32; 2],
+        rgba: &[u8],
+    ) -> bool {
+        let Some(ImageTexture { texture, .. }) = self.textures.get(id) else {
+            return false;
+        };
+        let copy_texture_info = TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: Origin3d { x: origin[0], y: origin[1], z: 0 },
+            aspect: TextureAspect::All,
+        };
+        let copy_buffer_layout = TexelCopyBufferLayout {
+            offset:         0,
+            bytes_per_row:  Some(4 * size[0]),
+            rows_per_image: Some(size[1]),
+        };
+        let extent = Extent3d {
+            width:                 size[0],
+            height:                size[1],
+            depth_or_array_layers: 1,
+        };
+        queue.write_texture(
+            copy_texture_info,
+            rgba,
+            copy_buffer_layout,
+            extent,
+        );
+
+        if texture.mip_level_count() > 1 {
+            let command_encoder_desc = CommandEncoderDescriptor {
+                label: Some("hui::image::mipmap_command_encoder"),
+            };
+            let mut command_encoder =
+                device.create_command_encoder(&command_encoder_desc);
+            self.mipmap_generator.generate(
+                device,
+                &mut command_encoder,
+                texture,
+            );
+            queue.submit([command_encoder.finish()]);
+        }
+
+        true
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn texture_view(&self, id: TextureId) -> Option<&TextureView> {
+        self.textures.get(id).map(|texture| &texture.view)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn texture_size(&self, id: TextureId) -> Option<[u32; 2]> {
+        let texture = &self.textures.get(id)?.texture;
+        Some([texture.width(), texture.height()])
+    }
+
+    // Hands the texture back for the renderer to free later; images still
+    // using it are not drawn.
+    #[inline(always)]
+    pub(crate) fn remove_texture(&mut self, id: TextureId) -> Option<Texture> {
+        self.textures.remove(id).map(|texture| texture.texture)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut(&mut self, id: ImageId) -> Option<&mut Image> {
+        self.images.get_mut(id).map(|(_, _, image)| image)
+    }
+
+    #[inline(always)]
+    pub fn add(
+        &mut self,
+        texture_id: TextureId,
+        instance: &Image,
+        phase: RenderPhase,
+    ) -> Option<ImageId> {
+        if !self.textures.contains_key(texture_id) {
+            return None;
+        }
+        Some(self.images.insert((phase, texture_id, *instance)))
+    }
+
+    #[inline(always)]
+    pub fn set_texture(&mut self, id: ImageId, texture_id: TextureId) {
+        if let Some((_, image_texture_id, _)) = self.images.get_mut(id) {
+            *image_texture_id = texture_id;
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove(&mut self, id: ImageId) -> Option<Image> {
+        self.images.remove(id).map(|(_, _, image)| image)
+    }
+
+    // In the order they are drawn in.
+    #[must_use]
+    pub fn images(&self) -> Vec<(TextureId, Image)> {
+        let mut images: Vec<_> = self
+            .images
+            .values()
+            .filter(|(_, texture_id, _)| {
+                self.textures.contains_key(*texture_id)
+            })
+            .copied()
+            .collect();
+        images.sort_by_key(|(phase, texture_id, image)| {
+            (*phase, *texture_id, sampler_index(image.repeat()))
+        });
+        images
+            .into_iter()
+            .map(|(_, texture_id, image)| (texture_id, image))
+            .collect()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn buffer_bytes(&self) -> u64 { self.instance_buffer.size() }
+
+    #[must_use]
+    pub(crate) fn texture_bytes(&self) -> u64 {
+        self.textures
+            .values()
+            .map(|texture| texture_bytes(&texture.texture))
+            .sum()
+    }
+
+    // Batches and uploads the images, once per frame before the phases
+    // are drawn.
+    pub(crate) fn prepare(
+        &mut self,
+        queue: &Queue,
+        recorder: &mut FrameRecorder,
+    ) {
+        self.batches.clear();
+        if self.images.is_empty() {
+            recorder.skip("image", "no instances");
+            return;
+        }
+        self.build_batches();
+        if self.batches.is_empty() {
+            recorder.skip("image", "no image has its texture");
+            return;
+        }
+        counter!(image_batches, self.batches.len());
+
+        self.instance_buffer
+            .reserve(self.instance_bytes.len() as u64);
+        queue.write_buffer(
+            self.instance_buffer.buffer(),
+            0,
+            &self.instance_bytes,
+        );
+        recorder
+            .upload("hui::image::instance_buffer", self.instance_bytes.len());
+    }
+
+    pub(crate) fn render(
+        &self,
+        phase: RenderPhase,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        let range = phase_range(&self.batches, phase, |batch: &ImageBatch| {
+            batch.phase
+        });
+        if range.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.render_pipeline);
+
+        let vertex_buffer = self.vertex_buffer.slice(..);
+        render_pass.set_vertex_buffer(0, vertex_buffer);
+
+        let instance_buffer = self.instance_buffer.buffer().slice(..);
+        render_pass.set_vertex_buffer(1, instance_buffer);
+
+        let index_buffer = self.index_buffer.slice(..);
+        render_pass.set_index_buffer(index_buffer, IndexFormat::Uint16);
+
+        for batch in &self.batches[range] {
+            let texture = &self.textures[batch.texture_id];
+            let sampler_bind_group =
+                &self.sampler_bind_groups[batch.sampler_index];
+
+            render_pass.set_bind_group(0, &texture.bind_group, &[]);
+            render_pass.set_bind_group(1, sampler_bind_group, &[]);
+            render_pass.draw_indexed(
+                0..INDICES.len() as u32,
+                0,
+                batch.instances.clone(),
+            );
+            recorder.draw(
+                "image",
+                0..INDICES.len() as u32,
+                batch.instances.clone(),
+            );
+        }
+    }
+
+    fn build_batches(&mut self) {
+        span!(
+            "build_instances",
+            kind = "image",
+            images = self.images.len()
+        );
+        let mut images: Vec<_> = self
+            .images
+            .values()
+            .filter(|(_, texture_id, _)| {
+                self.textures.contains_key(*texture_id)
+            })
+            .map(|(phase, texture_id, image)| {
+                (*phase, *texture_id, sampler_index(image.repeat()), image)
+            })
+            .collect();
+        images.sort_by_key(|(phase, texture_id, sampler_index, _)| {
+            (*phase, *texture_id, *sampler_index)
+        });
+
+        self.instance_bytes.clear();
+        self.batches.clear();
+
+        for (i, (phase, texture_id, sampler_index, image)) in
+            images.into_iter().enumerate()
+        {
+            self.instance_bytes
+                .extend_from_slice(bytemuck::bytes_of(image));
+
+            let i = i as u32;
+            match self.batches.last_mut() {
+                Some(batch)
+                    if batch.phase == phase
+                        && batch.texture_id == texture_id
+                        && batch.sampler_index == sampler_index =>
+                {
+                    batch.instances.end = i + 1;
+                }
+                _ => self.batches.push(ImageBatch {
+                    phase,
+                    texture_id,
+                    sampler_index,
+                    instances: i..i + 1,
+                }),
+            }
+        }
+    }
+}
+
+const fn sampler_index(repeat: ImageRepeat) -> usize {
+    let [repeat_x, repeat_y] = repeat.repeats();
+    repeat_x as usize | (repeat_y as usize) << 1
+}
+
+fn create_sampler_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    repeat: ImageRepeat,
+) -> BindGroup {
+    let address_mode = |repeats| match repeats {
+        true => AddressMode::Repeat,
+        false => AddressMode::ClampToEdge,
+    };
+    let [repeat_x, repeat_y] = repeat.repeats();
+
+    let sampler_desc = SamplerDescriptor {
+        label: Some("hui::image::sampler"),
+        address_mode_u: address_mode(repeat_x),
+        address_mode_v: address_mode(repeat_y),
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Linear,
+        ..Default::default()
+    };
+    let sampler = device.create_sampler(&sampler_desc);
+
+    let bind_group_entries = [BindGroupEntry {
+        binding:  0,
+        resource: BindingResource::Sampler(&sampler),
+    }];
+    let bind_group_desc = BindGroupDescriptor {
+        label: Some("hui::image::sampler_bind_group"),
+        layout,
+        entries: &bind_group_entries,
+    };
+    device.create_bind_group(&bind_group_desc)
+}
+
+fn create_render_pipeline(
+    device: &Device,
+    surface_format: TextureFormat,
+    texture_bind_group_layout: &BindGroupLayout,
+    sampler_bind_group_layout: &BindGroupLayout,
+) -> RenderPipeline {
+    let shader_module_content =
+        ShaderSource::Wgsl(include_str!("image.wgsl").into());
+    let shader_module_desc = ShaderModuleDescriptor {
+        label:  Some("hui::image::shader_module"),
+        source: shader_module_content,
+    };
+    let shader_module = device.create_shader_module(shader_module_desc);
+
+    let vertex_buffer_attributes = vertex_attr_array![
+        0 => Float32x3,
+    ];
+    let vertex_buffer_layout = VertexBufferLayout {
+        array_stride: size_of::<[f32; 3]>() as u64,
+        step_mode:    VertexStepMode::Vertex,
+        attributes:   &vertex_buffer_attributes,
+    };
+
+    let vertex_state = VertexState {
+        module:              &shader_module,
+        entry_point:         Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers:             &[vertex_buffer_layout, Image::LAYOUT],
+    };
+
+    let blend_state = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+    };
+    let fragment_state_targets = [Some(ColorTargetState {
+        format:     surface_format,
+        blend:      Some(blend_state),
+        write_mask: ColorWrites::ALL,
+    })];
+    let fragment_state = FragmentState {
+        module:              &shader_module,
+        entry_point:         Some("fs_main"),
+        compilation_options: Default::default(),
+        targets:             &fragment_state_targets,
+    };
+
+    let primitive_state = PrimitiveState {
+        topology:           PrimitiveTopology::TriangleList,
+        strip_index_format: None,
+        front_face:         FrontFace::Ccw,
+        cull_mode:          None,
+        polygon_mode:       PolygonMode::Fill,
+        unclipped_depth:    false,
+        conservative:       false,
+    };
+    let multisample_state = MultisampleState {
+        count: 1,
+        mask: !0,
+        alpha_to_coverage_enabled: false,
+    };
+
+    let render_pipeline_layout_desc = PipelineLayoutDescriptor {
+        label:                Some("hui::image::render_pipeline_layout"),
+        bind_group_layouts:   &[
+            texture_bind_group_layout,
+            sampler_bind_group_layout,
+        ],
+        push_constant_ranges: &[],
+    };
+    let render_pipeline_layout =
+        device.create_pipeline_layout(&render_pipeline_layout_desc);
+
+    let render_pipeline_desc = RenderPipelineDescriptor {
+        label:         Some("hui::image::render_pipeline"),
+        layout:        Some(&render_pipeline_layout),
+        vertex:        vertex_state,
+        fragment:      Some(fragment_state),
+        primitive:     primitive_state,
+        depth_stencil: None,
+        multisample:   multisample_state,
+        multiview:     None,
+        cache:         None,
+    };
+    device.create_render_pipeline(&render_pipeline_desc)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use wgpu::TextureFormat;
+
+    use super::ImageRenderer;
+    use crate::{
+        core::{Image, RenderPhase, capture::FrameRecorder},
+        testing::noop_device,
+    };
+
+    #[rstest]
+    fn test_prepare_grows_instance_buffer() {
+        let (device, queue) = noop_device();
+        let mut renderer =
+            ImageRenderer::new(&device, TextureFormat::Bgra8UnormSrgb);
+        let texture_id =
+            renderer.add_texture(&device, &queue, [1, 1], &[255; 4]);
+        let image = Image::builder()
+            .mvp([[0.0; 4]; 4])
+            .tint_color([1.0; 4])
+            .corner_radii([0.0; 4])
+            .clip_rect([0.0; 4])
+            .uv_transform([1.0, 1.0, 0.0, 0.0])
+            .repeat_and_size([0.0; 4])
+            .cutout([0.0; 4])
+            .build();
+        for _ in 0..1500 {
+            renderer.add(texture_id, &image, RenderPhase::Content);
+        }
+
+        renderer.prepare(&queue, &mut FrameRecorder::default());
+
+        assert!(renderer.buffer_bytes() >= 1500 * Image::SIZE as u64);
+    }
+}
@@ -0,0 +1,84 @@
+use wgpu::{Buffer, BufferDescriptor, BufferUsages, Device};
+
+const INITIAL_INSTANCE_COUNT: u64 = 1024;
+
+// A vertex buffer of instances that doubles whenever they outgrow it.
+pub(crate) struct InstanceBuffer {
+    label:  &'static str,
+    buffer: Buffer,
+    device: Device,
+}
+
+impl InstanceBuffer {
+    #[must_use]
+    pub(crate) fn new(
+        device: &Device,
+        label: &'static str,
+        instance_size: usize,
+    ) -> Self {
+        let size = INITIAL_INSTANCE_COUNT * instance_size as u64;
+        Self {
+            label,
+            buffer: create_buffer(device, label, size),
+            device: device.clone(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn buffer(&self) -> &Buffer { &self.buffer }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn size(&self) -> u64 { self.buffer.size() }
+
+    // Makes room for `len` bytes. Returns whether the buffer was replaced,
+    // so that what was written to the old one has to be written again.
+    pub(crate) fn reserve(&mut self, len: u64) -> bool {
+        if len <= self.buffer.size() {
+            return false;
+        }
+        let size = grown_size(self.buffer.size(), len);
+        self.buffer = create_buffer(&self.device, self.label, size);
+        true
+    }
+}
+
+fn create_buffer(device: &Device, label: &str, size: u64) -> Buffer {
+    let buffer_desc = BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    };
+    device.create_buffer(&buffer_desc)
+}
+
+// Doubles `size` until `len` bytes fit.
+fn grown_size(size: u64, len: u64) -> u64 {
+    let mut size = size.max(1);
+    while size < len {
+        size *= 2;
+    }
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::grown_size;
+
+    #[rstest]
+    #[case(1024, 1024, 1024)]
+    #[case(1024, 1025, 2048)]
+    #[case(1024, 5000, 8192)]
+    #[case(0, 3, 4)]
+    fn test_grown_size(
+        #[case] size: u64,
+        #[case] len: u64,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(grown_size(size, len), expected);
+    }
+}
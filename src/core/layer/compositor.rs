@@ -0,0 +1,201 @@
+use wgpu::{
+    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry,
+    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BlendComponent, BlendFactor, BlendOperation,
+    BlendState, Buffer, BufferBindingType, ColorTargetState, ColorWrites,
+    Device, FilterMode, FragmentState, PipelineLayoutDescriptor,
+    PrimitiveState, PrimitiveTopology, RenderPass, RenderPipeline,
+    RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, TextureFormat,
+    TextureSampleType, TextureView, TextureViewDimension, VertexState,
+};
+
+pub struct LayerCompositor {
+    render_pipeline:   RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    sampler:           Sampler,
+}
+
+impl LayerCompositor {
+    #[must_use]
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let sampler_desc = SamplerDescriptor {
+            label: Some("hui::layer::sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        };
+        let sampler = device.create_sampler(&sampler_desc);
+
+        let texture_binding_type = BindingType::Texture {
+            sample_type:    TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled:   false,
+        };
+        let bind_group_layout_entries = [
+            BindGroupLayoutEntry {
+                binding:    0,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         texture_binding_type,
+                count:      None,
+            },
+            BindGroupLayoutEntry {
+                binding:    1,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         texture_binding_type,
+                count:      None,
+            },
+            BindGroupLayoutEntry {
+                binding:    2,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         BindingType::Sampler(
+                    SamplerBindingType::Filtering,
+                ),
+                count:      None,
+            },
+            BindGroupLayoutEntry {
+                binding:    3,
+                visibility: ShaderStages::FRAGMENT,
+                ty:         BindingType::Buffer {
+                    ty:                 BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size:   None,
+                },
+                count:      None,
+            },
+        ];
+        let bind_group_layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::layer::bind_group_layout"),
+            entries: &bind_group_layout_entries,
+        };
+        let bind_group_layout =
+            device.create_bind_group_layout(&bind_group_layout_desc);
+
+        let render_pipeline =
+            create_render_pipeline(device, surface_format, &bind_group_layout);
+
+        Self { render_pipeline, bind_group_layout, sampler }
+    }
+
+    #[must_use]
+    pub fn create_bind_group(
+        &self,
+        device: &Device,
+        layer_texture_view: &TextureView,
+        mask_texture_view: Option<&TextureView>,
+        uniform_buffer: &Buffer,
+    ) -> BindGroup {
+        // Without a mask the shader ignores binding 1, but it still has to
+        // point at a valid texture.
+        let mask_texture_view =
+            mask_texture_view.unwrap_or(layer_texture_view);
+
+        let bind_group_entries = [
+            BindGroupEntry {
+                binding:  0,
+                resource: BindingResource::TextureView(layer_texture_view),
+            },
+            BindGroupEntry {
+                binding:  1,
+                resource: BindingResource::TextureView(mask_texture_view),
+            },
+            BindGroupEntry {
+                binding:  2,
+                resource: BindingResource::Sampler(&self.sampler),
+            },
+            BindGroupEntry {
+                binding:  3,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ];
+        let bind_group_desc = BindGroupDescriptor {
+            label:   Some("hui::layer::bind_group"),
+            layout:  &self.bind_group_layout,
+            entries: &bind_group_entries,
+        };
+        device.create_bind_group(&bind_group_desc)
+    }
+
+    pub fn render(
+        &self,
+        bind_group: &BindGroup,
+        render_pass: &mut RenderPass,
+    ) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_render_pipeline(
+    device: &Device,
+    surface_format: TextureFormat,
+    bind_group_layout: &BindGroupLayout,
+) -> RenderPipeline {
+    let shader_module_content =
+        ShaderSource::Wgsl(include_str!("layer.wgsl").into());
+    let shader_module_desc = ShaderModuleDescriptor {
+        label:  Some("hui::layer::shader_module"),
+        source: shader_module_content,
+    };
+    let shader_module = device.create_shader_module(shader_module_desc);
+
+    let vertex_state = VertexState {
+        module:              &shader_module,
+        entry_point:         Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers:             &[],
+    };
+    let blend_state = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation:  BlendOperation::Add,
+        },
+    };
+
+    let fragment_state_targets = [Some(ColorTargetState {
+        format:     surface_format,
+        blend:      Some(blend_state),
+        write_mask: ColorWrites::ALL,
+    })];
+    let fragment_state = FragmentState {
+        module:              &shader_module,
+        entry_point:         Some("fs_main"),
+        compilation_options: Default::default(),
+        targets:             &fragment_state_targets,
+    };
+
+    let primitive_state = PrimitiveState {
+        topology: PrimitiveTopology::TriangleList,
+        ..Default::default()
+    };
+
+    let pipeline_layout_desc = PipelineLayoutDescriptor {
+        label:                Some("hui::layer::pipeline_layout"),
+        bind_group_layouts:   &[bind_group_layout],
+        push_constant_ranges: &[],
+    };
+    let pipeline_layout = device.create_pipeline_layout(&pipeline_layout_desc);
+
+    let render_pipeline_desc = RenderPipelineDescriptor {
+        label:         Some("hui::layer::render_pipeline"),
+        layout:        Some(&pipeline_layout),
+        vertex:        vertex_state,
+        fragment:      Some(fragment_state),
+        primitive:     primitive_state,
+        depth_stencil: None,
+        multisample:   Default::default(),
+        multiview:     None,
+        cache:         None,
+    };
+    device.create_render_pipeline(&render_pipeline_desc)
+}
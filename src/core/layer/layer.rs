@@ -0,0 +1,303 @@
+use slotmap::DefaultKey;
+use wgpu::{
+    BindGroup, Buffer, BufferDescriptor, BufferUsages, Device, Queue,
+    RenderPass, Texture, TextureFormat, TextureView,
+};
+
+use super::LayerCompositor;
+use crate::core::{
+    Rectangle, RectangleId, RectangleRenderer, RenderPhase,
+    capture::FrameRecorder, memory::texture_bytes,
+    renderer::create_offscreen_texture,
+};
+
+pub type LayerId = DefaultKey;
+
+#[derive(Debug, Clone)]
+pub struct LayerMask {
+    pub texture_view: TextureView,
+    // x, y, width, height in physical pixels
+    pub rect:         [f32; 4],
+}
+
+// Applied when the layer is composited, so that moving or fading a whole
+// layer, e.g. a sliding sidebar, doesn't touch its instances. The layer
+// is scaled about its top left corner and then offset, in the pixels its
+// instances are in. The mask stays where it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerTransform {
+    pub offset:  [f32; 2],
+    pub scale:   f32,
+    pub opacity: f32,
+}
+
+impl LayerTransform {
+    pub const IDENTITY: Self =
+        Self { offset: [0.0, 0.0], scale: 1.0, opacity: 1.0 };
+
+    // Where a point of the layer ends up.
+    #[must_use]
+    #[inline(always)]
+    pub const fn apply(&self, point: [f32; 2]) -> [f32; 2] {
+        [
+            point[0] * self.scale + self.offset[0],
+            point[1] * self.scale + self.offset[1],
+        ]
+    }
+}
+
+impl Default for LayerTransform {
+    fn default() -> Self { Self::IDENTITY }
+}
+
+// A masked rectangle layer: its rectangles are drawn into a target of
+// their own and composited over the content through the mask's alpha,
+// e.g. to cut them into blob shapes or brush strokes. Only rectangles can
+// be added; arcs, images and text, and so widgets, can't target a layer.
+pub struct Layer {
+    texture:            Texture,
+    texture_view:       TextureView,
+    rectangle_renderer: RectangleRenderer,
+    mask:               Option<LayerMask>,
+    transform:          LayerTransform,
+    uniform_buffer:     Buffer,
+    bind_group:         BindGroup,
+    is_redraw_required: bool,
+    is_uniform_dirty:   bool,
+}
+
+impl Layer {
+    #[must_use]
+    pub(crate) fn new(
+        device: &Device,
+        compositor: &LayerCompositor,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+        mask: Option<LayerMask>,
+    ) -> Self {
+        let (texture, texture_view) =
+            create_offscreen_texture(device, width, height, format);
+
+        let uniform_buffer_desc = BufferDescriptor {
+            label:              Some("hui::layer::uniform_buffer"),
+            size:               size_of::<[[f32; 4]; 3]>() as u64,
+            usage:              BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        };
+        let uniform_buffer = device.create_buffer(&uniform_buffer_desc);
+
+        let bind_group = compositor.create_bind_group(
+            device,
+            &texture_view,
+            mask.as_ref().map(|mask| &mask.texture_view),
+            &uniform_buffer,
+        );
+
+        Self {
+            texture,
+            texture_view,
+            rectangle_renderer: RectangleRenderer::new(device, format),
+            mask,
+            transform: LayerTransform::IDENTITY,
+            uniform_buffer,
+            bind_group,
+            is_redraw_required: true,
+            is_uniform_dirty: true,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn mask(&self) -> Option<&LayerMask> { self.mask.as_ref() }
+
+    #[inline(always)]
+    pub const fn set_mask_rect(&mut self, rect: [f32; 4]) {
+        if let Some(mask) = &mut self.mask {
+            mask.rect = rect;
+            self.is_uniform_dirty = true;
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn transform(&self) -> LayerTransform { self.transform }
+
+    // Only the uniform is written; nothing is redrawn into the layer.
+    #[inline(always)]
+    pub fn set_transform(&mut self, transform: LayerTransform) {
+        if transform != self.transform {
+            self.transform = transform;
+            self.is_uniform_dirty = true;
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_rectangle(
+        &mut self,
+        id: RectangleId,
+    ) -> Option<&mut Rectangle> {
+        self.is_redraw_required = true;
+        self.rectangle_renderer.get_mut(id)
+    }
+
+    #[inline(always)]
+    pub fn add_rectangle(&mut self, instance: &Rectangle) -> RectangleId {
+        self.is_redraw_required = true;
+        self.rectangle_renderer.add(instance)
+    }
+
+    #[inline(always)]
+    pub fn remove_rectangle(&mut self, id: RectangleId) -> Option<Rectangle> {
+        self.is_redraw_required = true;
+        self.rectangle_renderer.remove(id)
+    }
+
+    pub(crate) fn set_mask(
+        &mut self,
+        device: &Device,
+        compositor: &LayerCompositor,
+        mask: Option<LayerMask>,
+    ) {
+        self.mask = mask;
+        self.is_uniform_dirty = true;
+        self.update_bind_group(device, compositor);
+    }
+
+    #[must_use]
+    pub(crate) fn memory_bytes(&self) -> u64 {
+        texture_bytes(&self.texture) + self.rectangle_renderer.buffer_bytes()
+    }
+
+    // Frees the texture until `resize` recreates it.
+    #[inline(always)]
+    pub(crate) fn suspend(&mut self) { self.texture.destroy(); }
+
+    // Uploads the mask and redraws the layer on the next frame.
+    #[inline(always)]
+    pub(crate) const fn invalidate(&mut self) {
+        self.is_uniform_dirty = true;
+        self.is_redraw_required = true;
+    }
+
+    pub(crate) fn resize(
+        &mut self,
+        device: &Device,
+        compositor: &LayerCompositor,
+        width: u32,
+        height: u32,
+    ) {
+        (self.texture, self.texture_view) = create_offscreen_texture(
+            device,
+            width,
+            height,
+            self.texture.format(),
+        );
+        self.is_redraw_required = true;
+        self.update_bind_group(device, compositor);
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn texture_view(&self) -> &TextureView {
+        &self.texture_view
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn is_redraw_required(&self) -> bool {
+        self.is_redraw_required
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        queue: &Queue,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        // Layers only hold content.
+        self.rectangle_renderer.prepare(queue, recorder);
+        self.rectangle_renderer.render(
+            RenderPhase::Content,
+            render_pass,
+            recorder,
+        );
+        self.is_redraw_required = false;
+    }
+
+    pub(crate) fn composite(
+        &mut self,
+        queue: &Queue,
+        compositor: &LayerCompositor,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        if self.is_uniform_dirty {
+            let uniform = layer_uniform(self.mask.as_ref(), &self.transform);
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&uniform),
+            );
+            recorder.upload(
+                "hui::layer::uniform_buffer",
+                std::mem::size_of_val(&uniform),
+            );
+            self.is_uniform_dirty = false;
+        }
+        compositor.render(&self.bind_group, render_pass);
+        recorder.draw("layer_compositor", 0..3, 0..1);
+    }
+
+    fn update_bind_group(
+        &mut self,
+        device: &Device,
+        compositor: &LayerCompositor,
+    ) {
+        self.bind_group = compositor.create_bind_group(
+            device,
+            &self.texture_view,
+            self.mask.as_ref().map(|mask| &mask.texture_view),
+            &self.uniform_buffer,
+        );
+    }
+}
+
+fn layer_uniform(
+    mask: Option<&LayerMask>,
+    transform: &LayerTransform,
+) -> [[f32; 4]; 3] {
+    let (mask_rect, has_mask) = match mask {
+        Some(mask) => (mask.rect, 1.0),
+        None => ([0.0; 4], 0.0),
+    };
+    let [offset_x, offset_y] = transform.offset;
+    [
+        mask_rect,
+        [has_mask, transform.opacity.clamp(0.0, 1.0), 0.0, 0.0],
+        [offset_x, offset_y, transform.scale, 0.0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{LayerTransform, layer_uniform};
+
+    #[rstest]
+    fn test_layer_uniform() {
+        let transform = LayerTransform {
+            offset:  [-120.0, 8.0],
+            scale:   0.5,
+            opacity: 1.5,
+        };
+        assert_eq!(transform.apply([200.0, 10.0]), [-20.0, 13.0]);
+        assert_eq!(
+            layer_uniform(None, &transform),
+            [[0.0; 4], [0.0, 1.0, 0.0, 0.0], [-120.0, 8.0, 0.5, 0.0]]
+        );
+        assert_eq!(LayerTransform::default().apply([3.0, 4.0]), [3.0, 4.0]);
+    }
+}
@@ -0,0 +1,63 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0)       uv:            vec2<f32>,
+}
+
+struct LayerUniform {
+    // x, y, width, height in physical pixels
+    mask_rect: vec4<f32>,
+    // has_mask, opacity, unused, unused
+    params:    vec4<f32>,
+    // offset x, offset y in layer pixels, scale, unused
+    transform: vec4<f32>,
+}
+
+const positions = array<vec2<f32>, 3>(
+    vec2<f32>(-1.0, -1.0),
+    vec2<f32>( 3.0, -1.0),
+    vec2<f32>(-1.0,  3.0),
+);
+const uvs = array<vec2<f32>, 3>(
+    vec2<f32>(0.0, 1.0),
+    vec2<f32>(2.0, 1.0),
+    vec2<f32>(0.0, -1.0),
+);
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let pos = positions[vertex_index];
+    let uv  = uvs[vertex_index];
+
+    var output: VertexOutput;
+    output.clip_position = vec4<f32>(pos, 0.0, 1.0);
+    output.uv            = uv;
+
+    return output;
+}
+
+@group(0) @binding(0) var layer_texture: texture_2d<f32>;
+@group(0) @binding(1) var mask_texture:  texture_2d<f32>;
+@group(0) @binding(2) var layer_sampler: sampler;
+@group(0) @binding(3) var<uniform> layer: LayerUniform;
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // Back from where the transform put the layer to where it was drawn.
+    let size     = vec2<f32>(textureDimensions(layer_texture));
+    let layer_uv = (input.uv - layer.transform.xy / size) / layer.transform.z;
+    let color    = textureSample(layer_texture, layer_sampler, layer_uv);
+    let on_layer = all(layer_uv >= vec2<f32>(0.0))
+                && all(layer_uv <= vec2<f32>(1.0));
+
+    let rect    = layer.mask_rect;
+    let mask_uv = (input.clip_position.xy - rect.xy) / rect.zw;
+    let mask    = textureSample(mask_texture, layer_sampler, mask_uv).a;
+
+    let inside = all(mask_uv >= vec2<f32>(0.0))
+              && all(mask_uv <= vec2<f32>(1.0));
+    let alpha  = select(select(0.0, mask, inside), 1.0, layer.params.x == 0.0);
+
+    let opacity = select(0.0, layer.params.y, on_layer);
+
+    return vec4<f32>(color.rgb, color.a * alpha * opacity);
+}
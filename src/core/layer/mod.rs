@@ -0,0 +1,6 @@
+mod compositor;
+#[allow(clippy::module_inception)]
+mod layer;
+
+pub use compositor::LayerCompositor;
+pub use layer::{Layer, LayerId, LayerMask, LayerTransform};
@@ -0,0 +1,217 @@
+use bon::Builder;
+use getset::CopyGetters;
+use wgpu::Texture;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryCategory {
+    // Allocated when the renderer is created; the rectangle buffer grows
+    // with its instances.
+    InstanceBuffers,
+    // Image textures with their mipmaps, such as icons and glyph atlases.
+    Textures,
+    // Layer textures and their instance buffers.
+    Layers,
+    // The offscreen texture the UI is drawn into, sized to the surface.
+    Targets,
+    // Textures and layers removed but not freed yet, see `OrphanQueue`.
+    Orphans,
+}
+
+// GPU memory held by the renderer, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, CopyGetters)]
+#[get_copy = "pub"]
+pub struct MemoryUsage {
+    pub(crate) instance_buffers: u64,
+    pub(crate) textures:         u64,
+    pub(crate) layers:           u64,
+    pub(crate) targets:          u64,
+    pub(crate) orphans:          u64,
+}
+
+impl MemoryUsage {
+    #[must_use]
+    #[inline(always)]
+    pub const fn get(&self, category: MemoryCategory) -> u64 {
+        match category {
+            MemoryCategory::InstanceBuffers => self.instance_buffers,
+            MemoryCategory::Textures => self.textures,
+            MemoryCategory::Layers => self.layers,
+            MemoryCategory::Targets => self.targets,
+            MemoryCategory::Orphans => self.orphans,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn total(&self) -> u64 { self.live_total() + self.orphans }
+
+    // Without what is waiting to be freed.
+    #[must_use]
+    #[inline(always)]
+    pub const fn live_total(&self) -> u64 {
+        self.instance_buffers + self.textures + self.layers + self.targets
+    }
+}
+
+// Limits in bytes on what the renderer may hold, for apps that share the
+// GPU with heavier work such as a 3D scene. Only textures and layers can
+// be freed, so only they have limits of their own; the others count
+// towards the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Builder)]
+#[builder(const)]
+pub struct MemoryBudget {
+    total:    Option<u64>,
+    textures: Option<u64>,
+    layers:   Option<u64>,
+}
+
+impl MemoryBudget {
+    // The first limit `usage` goes over, checking the categories before
+    // the total.
+    #[must_use]
+    pub fn pressure(&self, usage: &MemoryUsage) -> Option<MemoryPressure> {
+        let limits = [
+            (
+                Some(MemoryCategory::Textures),
+                self.textures,
+                usage.textures,
+            ),
+            (Some(MemoryCategory::Layers), self.layers, usage.layers),
+            (None, self.total, usage.total()),
+        ];
+        limits.into_iter().find_map(|(category, limit, usage)| {
+            let limit = limit?;
+            (usage > limit).then_some(MemoryPressure {
+                category,
+                usage,
+                limit,
+            })
+        })
+    }
+}
+
+// Calls `evict` for as long as `usage` goes over the budget. Orphans
+// count until they are freed, and evicted things only become orphans,
+// so `flush` frees them whenever they are pending before anything else
+// is evicted. Stops once a call frees and evicts nothing.
+pub(crate) fn enforce_budget<T: ?Sized>(
+    budget: &MemoryBudget,
+    target: &mut T,
+    usage: impl Fn(&T) -> MemoryUsage,
+    mut flush: impl FnMut(&mut T),
+    mut evict: impl FnMut(&mut T, &MemoryPressure),
+) {
+    let mut current = usage(target);
+    while let Some(pressure) = budget.pressure(&current) {
+        match current.orphans > 0 {
+            true => flush(target),
+            false => evict(target, &pressure),
+        }
+        let next = usage(target);
+        if next.total() >= current.total()
+            && next.live_total() >= current.live_total()
+        {
+            return;
+        }
+        current = next;
+    }
+}
+
+// A limit of a `MemoryBudget` that was gone over. `category` is None when
+// it's the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryPressure {
+    pub category: Option<MemoryCategory>,
+    pub usage:    u64,
+    pub limit:    u64,
+}
+
+impl MemoryPressure {
+    // How many bytes have to be freed to get back within the limit.
+    #[must_use]
+    #[inline(always)]
+    pub const fn excess(&self) -> u64 { self.usage - self.limit }
+}
+
+// Including every mip level.
+pub(crate) fn texture_bytes(texture: &Texture) -> u64 {
+    let block_size = texture.format().block_copy_size(None).unwrap_or(4);
+    let size = texture.size();
+    (0..texture.mip_level_count())
+        .map(|level| {
+            let width = (size.width >> level).max(1) as u64;
+            let height = (size.height >> level).max(1) as u64;
+            width * height * size.depth_or_array_layers as u64
+        })
+        .sum::<u64>()
+        * block_size as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{
+        MemoryBudget, MemoryCategory, MemoryPressure, MemoryUsage,
+        enforce_budget,
+    };
+
+    const USAGE: MemoryUsage = MemoryUsage {
+        instance_buffers: 100,
+        textures:         400,
+        layers:           300,
+        targets:          200,
+        orphans:          0,
+    };
+
+    #[rstest]
+    #[case(MemoryBudget::builder().build(), None)]
+    #[case(MemoryBudget::builder().total(1000).textures(400).build(), None)]
+    #[case(
+        MemoryBudget::builder().total(500).layers(100).build(),
+        Some((Some(MemoryCategory::Layers), 300, 100)),
+    )]
+    #[case(
+        MemoryBudget::builder().total(900).textures(1000).build(),
+        Some((None, 1000, 900)),
+    )]
+    fn test_pressure(
+        #[case] budget: MemoryBudget,
+        #[case] expected: Option<(Option<MemoryCategory>, u64, u64)>,
+    ) {
+        let expected = expected.map(|(category, usage, limit)| {
+            MemoryPressure { category, usage, limit }
+        });
+        assert_eq!(budget.pressure(&USAGE), expected);
+    }
+
+    #[rstest]
+    fn test_enforce_budget_counts_orphans() {
+        // Textures of 100 bytes, and the bytes of the evicted ones until
+        // they are flushed.
+        let usage = |(textures, orphans): &(Vec<u64>, u64)| MemoryUsage {
+            textures: textures.iter().sum(),
+            orphans: *orphans,
+            ..MemoryUsage::default()
+        };
+        let flush = |(_, orphans): &mut (Vec<u64>, u64)| *orphans = 0;
+        let evict = |(textures, orphans): &mut (Vec<u64>, u64), _: &_| {
+            *orphans += textures.pop().unwrap_or(0);
+        };
+        let budget = MemoryBudget::builder().total(450).textures(350).build();
+
+        let mut state = (vec![100; 6], 0);
+        enforce_budget(&budget, &mut state, usage, flush, evict);
+        assert_eq!(state, (vec![100; 3], 100));
+
+        // Pending orphans go over the total; freeing them is enough.
+        let mut state = (vec![100; 3], 300);
+        enforce_budget(&budget, &mut state, usage, flush, evict);
+        assert_eq!(state, (vec![100; 3], 0));
+
+        // Nothing left to evict.
+        let mut state = (vec![100; 6], 0);
+        enforce_budget(&budget, &mut state, usage, flush, |_, _| {});
+        assert_eq!(state, (vec![100; 6], 0));
+    }
+}
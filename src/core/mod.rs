@@ -1,12 +1,75 @@
+mod arc;
+mod capture;
+mod clip;
 mod composite;
+#[cfg(any(feature = "software", feature = "vello"))]
+mod cpu_scene;
+mod export;
+mod image;
+mod instance_buffer;
+mod layer;
+mod memory;
+mod orphans;
+mod path;
+mod phase;
+mod plugin;
+mod present;
 mod rectangle;
 mod renderer;
+#[cfg(feature = "software")]
+mod software;
+mod text_raster;
+#[cfg(feature = "vello")]
+mod vello;
 
-use composite::CompositeRenderer;
+use arc::ArcRenderer;
+pub use arc::{ArcSegment, ArcSegmentId};
+
+pub use capture::{CapturedCommand, CapturedPass, FrameCapture};
+
+use clip::ClipStack;
+
+pub use composite::SurfaceMask;
+use composite::{CompositeRenderer, SurfaceMaskRenderer};
+
+pub use export::{PageBreak, PageSetup, SceneTexture, VectorScene};
+
+use image::ImageRenderer;
+pub use image::{Image, ImageId, ImageRepeat, TextureId};
+
+use instance_buffer::InstanceBuffer;
+
+use layer::LayerCompositor;
+pub use layer::{Layer, LayerId, LayerMask, LayerTransform};
+
+pub use memory::{MemoryBudget, MemoryCategory, MemoryPressure, MemoryUsage};
+
+pub use orphans::OrphanQueue;
+
+use path::pixel_transform;
+pub use path::{PathCommand, PathId, VectorPath};
+
+pub use phase::RenderPhase;
+use phase::{PhaseOrder, phase_range};
+
+use plugin::PrimitivePlugins;
+pub use plugin::{
+    PrimitiveRenderer, PrimitiveRendererId, PrimitiveSetup, PrimitiveStage,
+};
+
+pub use present::{PresentMode, PresentOptions, PresentSupport};
 
 use rectangle::RectangleRenderer;
 #[cfg(feature = "bench")]
 pub use rectangle::RectangleStore;
 pub use rectangle::{Rectangle, RectangleId};
 
-pub use renderer::Renderer;
+pub use renderer::{Backend, Renderer};
+
+#[cfg(feature = "software")]
+use software::SoftwareRenderer;
+
+#[cfg(feature = "vello")]
+use vello::VelloRenderer;
+
+pub use text_raster::{Hinting, TextRasterConfig, TextRasterOptions};
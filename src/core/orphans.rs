@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use wgpu::Texture;
+
+use super::{Layer, memory::texture_bytes};
+
+enum Orphan {
+    Texture(Texture),
+    Layer(Box<Layer>),
+}
+
+impl Orphan {
+    fn bytes(&self) -> u64 {
+        match self {
+            Self::Texture(texture) => texture_bytes(texture),
+            Self::Layer(layer) => layer.memory_bytes(),
+        }
+    }
+
+    fn free(self) {
+        match self {
+            Self::Texture(texture) => texture.destroy(),
+            Self::Layer(mut layer) => layer.suspend(),
+        }
+    }
+}
+
+// Textures and layers removed from the renderer, freed a few per frame
+// so that tearing down a large subtree doesn't stall a single frame.
+// Every frame frees at least one, and more while they fit into
+// `bytes_per_frame`.
+pub struct OrphanQueue {
+    orphans:         VecDeque<(Orphan, u64)>,
+    pending_bytes:   u64,
+    bytes_per_frame: u64,
+}
+
+impl Default for OrphanQueue {
+    fn default() -> Self {
+        Self {
+            orphans:         VecDeque::new(),
+            pending_bytes:   0,
+            bytes_per_frame: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl OrphanQueue {
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.orphans.len() }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.orphans.is_empty() }
+
+    // What is still waiting to be freed.
+    #[must_use]
+    #[inline(always)]
+    pub const fn pending_bytes(&self) -> u64 { self.pending_bytes }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn bytes_per_frame(&self) -> u64 { self.bytes_per_frame }
+
+    #[inline(always)]
+    pub const fn set_bytes_per_frame(&mut self, bytes_per_frame: u64) {
+        self.bytes_per_frame = bytes_per_frame;
+    }
+
+    // Frees everything at once, e.g. before the device goes away.
+    pub fn flush_now(&mut self) {
+        for (orphan, _) in self.orphans.drain(..) {
+            orphan.free();
+        }
+        self.pending_bytes = 0;
+    }
+
+    #[inline(always)]
+    pub(crate) fn push_texture(&mut self, texture: Texture) {
+        self.push(Orphan::Texture(texture));
+    }
+
+    #[inline(always)]
+    pub(crate) fn push_layer(&mut self, layer: Layer) {
+        self.push(Orphan::Layer(Box::new(layer)));
+    }
+
+    // Frees this frame's share.
+    pub(crate) fn collect(&mut self) {
+        let mut freed_bytes = 0;
+        while let Some((_, bytes)) = self.orphans.front() {
+            if freed_bytes > 0 && freed_bytes + bytes > self.bytes_per_frame {
+                break;
+            }
+            let Some((orphan, bytes)) = self.orphans.pop_front() else {
+                break;
+            };
+            orphan.free();
+            freed_bytes += bytes;
+            self.pending_bytes -= bytes;
+        }
+    }
+
+    fn push(&mut self, orphan: Orphan) {
+        let bytes = orphan.bytes();
+        self.pending_bytes += bytes;
+        self.orphans.push_back((orphan, bytes));
+    }
+}
@@ -0,0 +1,4 @@
+mod vector_path;
+
+pub(crate) use vector_path::pixel_transform;
+pub use vector_path::{PathCommand, PathId, VectorPath};
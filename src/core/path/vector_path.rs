@@ -0,0 +1,68 @@
+use bon::Builder;
+use slotmap::DefaultKey;
+
+pub type PathId = DefaultKey;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo([f32; 2]),
+    LineTo([f32; 2]),
+    QuadTo([f32; 2], [f32; 2]),
+    CubicTo([f32; 2], [f32; 2], [f32; 2]),
+    Close,
+}
+
+// A filled and stroked vector path. Only the software and vello backends
+// rasterize paths; the GPU backend has no tessellator for them.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct VectorPath {
+    // maps the commands' points to clip space, like the quads' mvp
+    pub mvp:          [[f32; 4]; 4],
+    pub commands:     Vec<PathCommand>,
+    #[builder(default = [0.0; 4])]
+    pub fill_color:   [f32; 4],
+    #[builder(default = [0.0; 4])]
+    pub stroke_color: [f32; 4],
+    // in physical pixels
+    #[builder(default = 0.0)]
+    pub stroke_width: f32,
+    #[builder(default = false)]
+    pub is_even_odd:  bool,
+    // dash_length, gap_length in physical pixels; the stroke is solid
+    // unless both are positive
+    #[builder(default = [0.0; 2])]
+    pub dash_pattern: [f32; 2],
+    // how far along the pattern the stroke starts, like SVG's
+    // stroke-dashoffset
+    #[builder(default = 0.0)]
+    pub dash_offset:  f32,
+    // x, y, width, height in physical pixels
+    pub clip_rect:    [f32; 4],
+}
+
+impl VectorPath {
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dashed(&self) -> bool {
+        self.dash_pattern[0] > 0.0 && self.dash_pattern[1] > 0.0
+    }
+}
+
+// The affine map from the points of an mvp to physical pixels of a
+// target, as `[a, b, c, d, e, f]` with x' = a x + c y + e and
+// y' = b x + d y + f.
+#[must_use]
+pub(crate) fn pixel_transform(
+    mvp: &[[f32; 4]; 4],
+    target_size: [u32; 2],
+) -> [f32; 6] {
+    let [width, height] = target_size.map(|value| value as f32 * 0.5);
+    [
+        mvp[0][0] * width,
+        -mvp[0][1] * height,
+        mvp[1][0] * width,
+        -mvp[1][1] * height,
+        (mvp[3][0] + 1.0) * width,
+        (1.0 - mvp[3][1]) * height,
+    ]
+}
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+// Parts of a frame drawn one after another, each above all of the ones
+// before it: z-indices only order rectangles within a phase. Each phase
+// clips with its own stack, so that e.g. a popup in the overlay isn't
+// clipped by the scroll view it was opened from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RenderPhase {
+    Background,
+    #[default]
+    Content,
+    // Popups, tooltips and drag previews.
+    Overlay,
+    // Inspector highlights and other debug visuals, above everything.
+    Debug,
+}
+
+impl RenderPhase {
+    pub const ALL: [Self; 4] =
+        [Self::Background, Self::Content, Self::Overlay, Self::Debug];
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn index(self) -> usize { self as usize }
+}
+
+// Ids in draw order: by phase, and in the order they were added within
+// one.
+#[derive(Debug, Default)]
+pub(crate) struct PhaseOrder<Id> {
+    entries: Vec<(RenderPhase, Id)>,
+}
+
+impl<Id: Copy + PartialEq> PhaseOrder<Id> {
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn new() -> Self { Self { entries: Vec::new() } }
+
+    #[cfg(any(
+        test,
+        feature = "software",
+        feature = "vello",
+        feature = "tracing"
+    ))]
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn len(&self) -> usize { self.entries.len() }
+
+    pub(crate) fn push(&mut self, phase: RenderPhase, id: Id) {
+        let index = self.entries.partition_point(|(other, _)| *other <= phase);
+        self.entries.insert(index, (phase, id));
+    }
+
+    pub(crate) fn remove(&mut self, id: Id) {
+        self.entries.retain(|(_, other)| *other != id);
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Id> + '_ {
+        self.entries.iter().map(|(_, id)| *id)
+    }
+
+    // Where the ids of `phase` are in `iter`.
+    #[must_use]
+    pub(crate) fn range(&self, phase: RenderPhase) -> Range<usize> {
+        phase_range(&self.entries, phase, |(other, _)| *other)
+    }
+
+    #[cfg(any(test, feature = "software", feature = "vello"))]
+    pub(crate) fn phase(
+        &self,
+        phase: RenderPhase,
+    ) -> impl Iterator<Item = Id> + '_ {
+        self.entries[self.range(phase)].iter().map(|(_, id)| *id)
+    }
+}
+
+// Where the items of `phase` are in `items`, sorted by phase.
+#[must_use]
+pub(crate) fn phase_range<T>(
+    items: &[T],
+    phase: RenderPhase,
+    phase_of: impl Fn(&T) -> RenderPhase,
+) -> Range<usize> {
+    let start = items.partition_point(|item| phase_of(item) < phase);
+    let end = items.partition_point(|item| phase_of(item) <= phase);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PhaseOrder, RenderPhase};
+
+    #[rstest]
+    fn test_phase_order() {
+        let mut order = PhaseOrder::new();
+        order.push(RenderPhase::Debug, 0);
+        order.push(RenderPhase::Content, 1);
+        order.push(RenderPhase::Background, 2);
+        order.push(RenderPhase::Content, 3);
+        assert_eq!(order.iter().collect::<Vec<_>>(), [2, 1, 3, 0]);
+        assert_eq!(order.range(RenderPhase::Content), 1..3);
+        assert_eq!(order.range(RenderPhase::Overlay), 3..3);
+
+        order.remove(1);
+        assert_eq!(order.phase(RenderPhase::Content).collect::<Vec<_>>(), [3]);
+        assert_eq!(order.len(), 3);
+    }
+}
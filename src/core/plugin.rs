@@ -0,0 +1,227 @@
+use std::any::Any;
+
+use slotmap::{DefaultKey, SlotMap};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer,
+    BufferBindingType, BufferDescriptor, BufferUsages, Device, Queue,
+    RenderPass, ShaderStages, TextureFormat,
+};
+
+use super::capture::FrameRecorder;
+
+pub type PrimitiveRendererId = DefaultKey;
+
+// Where in the content phase a primitive renderer draws, relative to
+// hui's own primitives. Renderers in the same stage draw in the order
+// they were registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PrimitiveStage {
+    // Below the rest of the content, e.g. a grid behind a canvas.
+    Background,
+    AfterRectangles,
+    AfterArcs,
+    // Above images.
+    Overlay,
+}
+
+// What a primitive renderer is created with. Pipelines have to target
+// `format`; the camera uniform, a column major view projection matrix
+// set with `Renderer::set_view_projection`, is bound with a layout of
+// `camera_layout`.
+pub struct PrimitiveSetup<'a> {
+    pub device:        &'a Device,
+    pub format:        TextureFormat,
+    pub camera_layout: &'a BindGroupLayout,
+}
+
+// A renderer for a kind of primitive hui doesn't draw itself, with its
+// own pipelines and instances, drawn in the main pass on the GPU
+// backend. Layers don't draw it.
+pub trait PrimitiveRenderer: Any {
+    // Names it in frame captures.
+    fn label(&self) -> &'static str;
+
+    // Called with the new size of the target it draws into, in physical
+    // pixels.
+    fn resize(&mut self, _device: &Device, _size: [u32; 2]) {}
+
+    // Uploads what changed and draws; the pass is left with this
+    // renderer's pipeline and bindings.
+    fn render(
+        &mut self,
+        queue: &Queue,
+        render_pass: &mut RenderPass,
+        camera: &BindGroup,
+    );
+}
+
+struct Camera {
+    buffer:     Buffer,
+    layout:     BindGroupLayout,
+    bind_group: BindGroup,
+}
+
+impl Camera {
+    fn new(device: &Device) -> Self {
+        let buffer_desc = BufferDescriptor {
+            label:              Some("hui::camera::uniform_buffer"),
+            size:               size_of::<[[f32; 4]; 4]>() as u64,
+            usage:              BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        };
+        let buffer = device.create_buffer(&buffer_desc);
+
+        let layout_entries = [BindGroupLayoutEntry {
+            binding:    0,
+            visibility: ShaderStages::VERTEX_FRAGMENT,
+            ty:         BindingType::Buffer {
+                ty:                 BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size:   None,
+            },
+            count:      None,
+        }];
+        let layout_desc = BindGroupLayoutDescriptor {
+            label:   Some("hui::camera::bind_group_layout"),
+            entries: &layout_entries,
+        };
+        let layout = device.create_bind_group_layout(&layout_desc);
+
+        let bind_group_entries = [BindGroupEntry {
+            binding:  0,
+            resource: buffer.as_entire_binding(),
+        }];
+        let bind_group_desc = BindGroupDescriptor {
+            label:   Some("hui::camera::bind_group"),
+            layout:  &layout,
+            entries: &bind_group_entries,
+        };
+        let bind_group = device.create_bind_group(&bind_group_desc);
+
+        Self { buffer, layout, bind_group }
+    }
+}
+
+// The registered primitive renderers, by stage.
+pub(crate) struct PrimitivePlugins {
+    renderers: SlotMap<PrimitiveRendererId, Box<dyn PrimitiveRenderer>>,
+    order:     Vec<(PrimitiveStage, PrimitiveRendererId)>,
+    camera:    Camera,
+}
+
+impl PrimitivePlugins {
+    pub(crate) fn new(device: &Device) -> Self {
+        Self {
+            renderers: SlotMap::new(),
+            order:     Vec::new(),
+            camera:    Camera::new(device),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) const fn camera_layout(&self) -> &BindGroupLayout {
+        &self.camera.layout
+    }
+
+    pub(crate) fn set_view_projection(
+        &self,
+        queue: &Queue,
+        view_projection: [[f32; 4]; 4],
+    ) {
+        let bytes = bytemuck::bytes_of(&view_projection);
+        queue.write_buffer(&self.camera.buffer, 0, bytes);
+    }
+
+    pub(crate) fn register(
+        &mut self,
+        stage: PrimitiveStage,
+        renderer: Box<dyn PrimitiveRenderer>,
+    ) -> PrimitiveRendererId {
+        let id = self.renderers.insert(renderer);
+        let index = insertion_index(&self.order, stage);
+        self.order.insert(index, (stage, id));
+        id
+    }
+
+    pub(crate) fn remove(
+        &mut self,
+        id: PrimitiveRendererId,
+    ) -> Option<Box<dyn PrimitiveRenderer>> {
+        self.order.retain(|(_, registered)| *registered != id);
+        self.renderers.remove(id)
+    }
+
+    pub(crate) fn get_mut<R: PrimitiveRenderer>(
+        &mut self,
+        id: PrimitiveRendererId,
+    ) -> Option<&mut R> {
+        let renderer: &mut dyn Any = self.renderers.get_mut(id)?.as_mut();
+        renderer.downcast_mut()
+    }
+
+    pub(crate) fn resize(&mut self, device: &Device, size: [u32; 2]) {
+        for renderer in self.renderers.values_mut() {
+            renderer.resize(device, size);
+        }
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        stage: PrimitiveStage,
+        queue: &Queue,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        let ids = self
+            .order
+            .iter()
+            .filter(|(registered, _)| *registered == stage)
+            .map(|(_, id)| *id);
+        for id in ids {
+            let renderer = &mut self.renderers[id];
+            recorder.plugin(renderer.label());
+            renderer.render(queue, render_pass, &self.camera.bind_group);
+        }
+    }
+}
+
+fn insertion_index(
+    order: &[(PrimitiveStage, PrimitiveRendererId)],
+    stage: PrimitiveStage,
+) -> usize {
+    order.partition_point(|(registered, _)| *registered <= stage)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use slotmap::SlotMap;
+
+    use super::{PrimitiveRendererId, PrimitiveStage, insertion_index};
+
+    #[rstest]
+    fn test_insertion_index() {
+        let mut ids = SlotMap::<PrimitiveRendererId, ()>::new();
+        let mut order = Vec::new();
+        for stage in [
+            PrimitiveStage::Overlay,
+            PrimitiveStage::Background,
+            PrimitiveStage::AfterArcs,
+            PrimitiveStage::Background,
+        ] {
+            let id = ids.insert(());
+            order.insert(insertion_index(&order, stage), (stage, id));
+        }
+        let ids: Vec<_> = ids.keys().collect();
+        assert_eq!(
+            order,
+            [
+                (PrimitiveStage::Background, ids[1]),
+                (PrimitiveStage::Background, ids[3]),
+                (PrimitiveStage::AfterArcs, ids[2]),
+                (PrimitiveStage::Overlay, ids[0]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use bon::Builder;
+use wgpu::{Adapter, Surface};
+
+// How finished frames reach the screen, trading lag for tearing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    // Waits for vblank: no tearing, up to a few frames of lag.
+    #[default]
+    Fifo,
+    // Replaces the queued frame with newer ones: no tearing, less lag,
+    // but frames are rendered that are never shown.
+    Mailbox,
+    // Shows frames right away, tearing mid-scanout.
+    Immediate,
+}
+
+impl PresentMode {
+    pub const ALL: [Self; 3] = [Self::Fifo, Self::Mailbox, Self::Immediate];
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            Self::Fifo => wgpu::PresentMode::Fifo,
+            Self::Mailbox => wgpu::PresentMode::Mailbox,
+            Self::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+
+    // Modes to fall back to, in order, when this one isn't supported:
+    // tearing is given up before lag is.
+    #[must_use]
+    const fn fallbacks(self) -> &'static [Self] {
+        match self {
+            Self::Fifo => &[],
+            Self::Mailbox => &[Self::Fifo],
+            Self::Immediate => &[Self::Mailbox, Self::Fifo],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Builder)]
+#[builder(const)]
+pub struct PresentOptions {
+    #[builder(default = PresentMode::Fifo)]
+    mode:              PresentMode,
+    // How many frames may be queued ahead of the one on screen. 1 lags
+    // least but leaves the GPU idle while the next frame is built.
+    #[builder(default = 2)]
+    max_frame_latency: u32,
+}
+
+impl Default for PresentOptions {
+    fn default() -> Self { Self::builder().build() }
+}
+
+impl PresentOptions {
+    #[must_use]
+    #[inline(always)]
+    pub const fn mode(&self) -> PresentMode { self.mode }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn max_frame_latency(&self) -> u32 { self.max_frame_latency }
+}
+
+// The present modes a surface can use with an adapter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresentSupport {
+    modes: Vec<PresentMode>,
+}
+
+impl PresentSupport {
+    #[must_use]
+    pub fn query(surface: &Surface, adapter: &Adapter) -> Self {
+        let capabilities = surface.get_capabilities(adapter);
+        Self::new(
+            PresentMode::ALL
+                .into_iter()
+                .filter(|mode| {
+                    capabilities.present_modes.contains(&mode.to_wgpu())
+                })
+                .collect(),
+        )
+    }
+
+    // Fifo is always supported.
+    #[must_use]
+    pub fn new(mut modes: Vec<PresentMode>) -> Self {
+        if !modes.contains(&PresentMode::Fifo) {
+            modes.insert(0, PresentMode::Fifo);
+        }
+        Self { modes }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn modes(&self) -> &[PresentMode] { &self.modes }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn supports(&self, mode: PresentMode) -> bool {
+        self.modes.contains(&mode)
+    }
+
+    // `mode`, or the closest supported one.
+    #[must_use]
+    pub fn resolve(&self, mode: PresentMode) -> PresentMode {
+        std::iter::once(mode)
+            .chain(mode.fallbacks().iter().copied())
+            .find(|mode| self.supports(*mode))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{PresentMode, PresentSupport};
+
+    #[rstest]
+    #[case(vec![], PresentMode::Immediate, PresentMode::Fifo)]
+    #[case(vec![PresentMode::Mailbox], PresentMode::Immediate, PresentMode::Mailbox)]
+    #[case(vec![PresentMode::Immediate], PresentMode::Mailbox, PresentMode::Fifo)]
+    #[case(vec![PresentMode::Immediate], PresentMode::Immediate, PresentMode::Immediate)]
+    #[case(vec![PresentMode::Mailbox], PresentMode::Fifo, PresentMode::Fifo)]
+    fn test_resolve(
+        #[case] modes: Vec<PresentMode>,
+        #[case] mode: PresentMode,
+        #[case] expected: PresentMode,
+    ) {
+        let support = PresentSupport::new(modes);
+        assert!(support.supports(PresentMode::Fifo));
+        assert_eq!(support.resolve(mode), expected);
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 mod rectangle;
 mod rectangle_store;
 mod renderer;
@@ -8,4 +9,4 @@ pub use renderer::RectangleRenderer;
 #[cfg(feature = "bench")]
 pub use rectangle_store::RectangleStore;
 #[cfg(not(feature = "bench"))]
-use rectangle_store::RectangleStore;
+pub(super) use rectangle_store::RectangleStore;
@@ -21,6 +21,10 @@ pub struct Rectangle {
     pub rect_and_shadow: [f32; 4],
     // border_size, shadow_spread, shadow_blur, outline_size
     pub sizes:           [f32; 4],
+    // dash_length, gap_length, dash_offset, unused; a zero dash length
+    // draws a solid border
+    #[builder(default = [0.0; 4])]
+    pub dash:            [f32; 4],
 }
 
 impl Rectangle {
@@ -38,6 +42,7 @@ impl Rectangle {
             10 => Float32x4, // clip rect
             11 => Float32x4, // half_size.xy, shadow_offset.zw
             12 => Float32x4, // border_size, shadow_spread, shadow_blur, outline_size
+            13 => Float32x4, // dash_length, gap_length, dash_offset
         ];
         VertexBufferLayout {
             array_stride: Self::SIZE as u64,
@@ -46,4 +51,10 @@ impl Rectangle {
         }
     };
     pub const SIZE: usize = size_of::<Self>();
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_dashed(&self) -> bool {
+        self.dash[0] > 0.0 && self.dash[1] > 0.0
+    }
 }
@@ -12,6 +12,7 @@ struct VertexInput {
     @location(10) clip_rect:       vec4<f32>,
     @location(11) rect_and_shadow: vec4<f32>,
     @location(12) sizes:           vec4<f32>,
+    @location(13) dash:            vec4<f32>,
 }
 
 struct VertexOutput {
@@ -29,6 +30,7 @@ struct VertexOutput {
     @location(10) @interpolate(flat) clip_rect:      vec4<f32>,
     @location(11) @interpolate(flat) outline_color:  vec4<f32>,
     @location(12) @interpolate(flat) outline_size:   f32,
+    @location(13) @interpolate(flat) dash:           vec4<f32>,
 }
 
 @vertex
@@ -68,19 +70,20 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     output.clip_rect      = input.clip_rect;
     output.outline_color  = input.outline_color;
     output.outline_size   = outline_size;
+    output.dash           = input.dash;
 
     return output;
 }
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    let clip    = input.clip_rect;
-    let abs_pos = input.local_position + input.half_size;
+    let clip = input.clip_rect;
+    let pos  = input.clip_position.xy;
 
-    if abs_pos.x < clip.x
-        || abs_pos.x > clip.x + clip.z
-        || abs_pos.y < clip.y
-        || abs_pos.y > clip.y + clip.w
+    if pos.x < clip.x
+        || pos.x > clip.x + clip.z
+        || pos.y < clip.y
+        || pos.y > clip.y + clip.w
     { discard; }
 
     let p  = input.local_position;
@@ -125,8 +128,12 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     let inner_radii    = max(r - vec4<f32>(b), vec4<f32>(0.0));
     let inner_distance = sd_rounded_rect_4(p, hs - vec2<f32>(b), inner_radii);
 
+    let center_radii  = max(r - vec4<f32>(b * 0.5), vec4<f32>(0.0));
+    let dash_position = border_position(p, hs - vec2<f32>(b * 0.5), center_radii);
+
     let border_alpha = (1.0 - smoothstep(0.0, aa_width, rect_distance))
-                     * smoothstep(-aa_width, 0.0, inner_distance);
+                     * smoothstep(-aa_width, 0.0, inner_distance)
+                     * dash_coverage(dash_position, input.dash);
     let fill_alpha   = 1.0 - smoothstep(0.0, aa_width, inner_distance);
 
     var rect_color = vec4<f32>(input.fill_color.rgb, input.fill_color.a * fill_alpha);
@@ -153,3 +160,70 @@ fn sd_rounded_rect_4(p: vec2<f32>, half_size: vec2<f32>, radii: vec4<f32>) -> f3
     let q = abs(p) - half_size + r;
     return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - r.x;
 }
+
+const HALF_PI: f32 = 1.5707963;
+
+// Distance along the border's center line, clockwise from the end of the
+// top left corner, where the vector backends start their dashes too.
+fn border_position(p: vec2<f32>, half_size: vec2<f32>, radii: vec4<f32>) -> f32 {
+    let hs   = half_size;
+    let r    = radii;
+    let arcs = r * HALF_PI;
+
+    let top    = 2.0 * hs.x - r.x - r.y;
+    let right  = 2.0 * hs.y - r.y - r.z;
+    let bottom = 2.0 * hs.x - r.z - r.w;
+    let left   = 2.0 * hs.y - r.w - r.x;
+
+    let right_start  = top + arcs.y;
+    let bottom_start = right_start + right + arcs.z;
+    let left_start   = bottom_start + bottom + arcs.w;
+
+    let top_left     = vec2<f32>(r.x - hs.x, r.x - hs.y);
+    let top_right    = vec2<f32>(hs.x - r.y, r.y - hs.y);
+    let bottom_right = vec2<f32>(hs.x - r.z, hs.y - r.z);
+    let bottom_left  = vec2<f32>(r.w - hs.x, hs.y - r.w);
+
+    if p.x > top_right.x && p.y < top_right.y {
+        let d = p - top_right;
+        return top + atan2(d.x, -d.y) * r.y;
+    }
+    if p.x > bottom_right.x && p.y > bottom_right.y {
+        let d = p - bottom_right;
+        return right_start + right + atan2(d.y, d.x) * r.z;
+    }
+    if p.x < bottom_left.x && p.y > bottom_left.y {
+        let d = p - bottom_left;
+        return bottom_start + bottom + atan2(-d.x, d.y) * r.w;
+    }
+    if p.x < top_left.x && p.y < top_left.y {
+        let d = p - top_left;
+        return left_start + left + atan2(-d.y, -d.x) * r.x;
+    }
+
+    // Between the corners the nearest side wins.
+    if hs.y - abs(p.y) < hs.x - abs(p.x) {
+        if p.y < 0.0 { return p.x - top_left.x; }
+        return bottom_start + bottom_right.x - p.x;
+    }
+    if p.x > 0.0 { return right_start + p.y - top_right.y; }
+    return left_start + bottom_left.y - p.y;
+}
+
+// Coverage of the dashes at a distance along the border, anti-aliased over
+// a pixel, as the quad's local units are physical pixels.
+fn dash_coverage(position: f32, dash: vec4<f32>) -> f32 {
+    if dash.x <= 0.0 || dash.y <= 0.0 { return 1.0; }
+
+    let period = dash.x + dash.y;
+    let phase  = position + dash.z;
+    let t      = phase - period * floor(phase / period);
+
+    // negative inside a dash
+    let distance = select(
+        min(t - dash.x, period - t),
+        max(-t, t - dash.x),
+        t < dash.x,
+    );
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
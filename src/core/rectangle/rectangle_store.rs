@@ -1,63 +1,303 @@
-use bytemuck::Zeroable;
+use std::ops::Range;
+
 use slotmap::SlotMap;
 
 use super::{Rectangle, RectangleId};
+use crate::core::{RenderPhase, phase_range};
 
+// Instances are packed in draw order: by phase, then by z-index, and in
+// the order they were added within one. Removing one leaves a hole
+// rather than moving the last instance into it, which would change
+// what's drawn on top; the holes are squeezed out before the bytes are
+// read, without changing any ids.
+//
+// The bytes changed since the last upload are tracked, so that moving a
+// few widgets only uploads what lies between them, and moving one only
+// its transform.
+//
+// When double buffered, the upload and the draw read a front copy that
+// `swap_buffers` refreshes at frame boundaries with what changed since,
+// so that the next frame can be edited while the last one is drawn.
 pub struct RectangleStore {
-    slots: SlotMap<RectangleId, usize>,
-    bytes: Vec<u8>,
+    // The index of each id's instance.
+    slots:  SlotMap<RectangleId, usize>,
+    // The id of each instance, `None` for holes.
+    owners: Vec<Option<RectangleId>>,
+    // The phase and z-index of each instance, holes keeping theirs, so
+    // that it stays sorted.
+    orders: Vec<(RenderPhase, i32)>,
+    bytes:  Vec<u8>,
+    holes:  usize,
+    dirty:  Option<Range<usize>>,
+    front:  Option<FrontBuffer>,
+}
+
+struct FrontBuffer {
+    bytes:        Vec<u8>,
+    phase_ranges: [Range<usize>; RenderPhase::ALL.len()],
+    // Changed by swaps since the last upload.
+    dirty:        Option<Range<usize>>,
+}
+
+impl FrontBuffer {
+    fn take_dirty_bytes(&mut self) -> Option<(usize, &[u8])> {
+        let dirty = self.dirty.take()?;
+        let dirty = dirty.start..dirty.end.min(self.bytes.len());
+        (!dirty.is_empty()).then(|| (dirty.start, &self.bytes[dirty]))
+    }
+}
+
+impl Default for RectangleStore {
+    fn default() -> Self { Self::new() }
 }
 
 impl RectangleStore {
     #[must_use]
     #[inline(always)]
-    pub fn new() -> Self { Self { slots: SlotMap::new(), bytes: Vec::new() } }
+    pub fn new() -> Self {
+        Self {
+            slots:  SlotMap::new(),
+            owners: Vec::new(),
+            orders: Vec::new(),
+            bytes:  Vec::new(),
+            holes:  0,
+            dirty:  None,
+            front:  None,
+        }
+    }
 
     #[must_use]
     #[inline(always)]
     pub fn is_empty(&self) -> bool { self.slots.is_empty() }
 
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_double_buffered(&self) -> bool { self.front.is_some() }
+
+    // Whether the front copy, or the instances without double buffering,
+    // has nothing to draw.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_front_empty(&self) -> bool {
+        match &self.front {
+            Some(front) => front.bytes.is_empty(),
+            None => self.is_empty(),
+        }
+    }
+
+    // Turning it on publishes the instances as they are; turning it off
+    // uploads them all again, as the buffer may hold an older frame.
+    pub fn set_double_buffered(&mut self, is_double_buffered: bool) {
+        if is_double_buffered == self.is_double_buffered() {
+            return;
+        }
+        match is_double_buffered {
+            true => {
+                self.front = Some(FrontBuffer {
+                    bytes:        Vec::new(),
+                    phase_ranges: Default::default(),
+                    dirty:        None,
+                });
+                self.mark_dirty(0, self.bytes.len());
+                self.swap_buffers();
+            }
+            false => {
+                self.front = None;
+                self.mark_dirty(0, self.bytes.len());
+            }
+        }
+    }
+
+    // Copies what changed since the last swap into the front copy, and
+    // returns whether anything did. Does nothing without double
+    // buffering.
+    pub fn swap_buffers(&mut self) -> bool {
+        if self.front.is_none() {
+            return false;
+        }
+        if self.holes > 0 {
+            self.repack();
+        }
+        let dirty = self.dirty.take();
+        let phase_ranges = RenderPhase::ALL.map(|phase| {
+            phase_range(&self.orders, phase, |(other, _)| *other)
+        });
+        let Some(front) = &mut self.front else {
+            return false;
+        };
+        // Removing the last instances only shortens the draw.
+        let is_resized = front.phase_ranges != phase_ranges;
+        front.phase_ranges = phase_ranges;
+        // Bytes past the end are only ever added dirty.
+        front.bytes.resize(self.bytes.len(), 0);
+        let dirty =
+            dirty.map(|dirty| dirty.start..dirty.end.min(self.bytes.len()));
+        let Some(dirty) = dirty.filter(|dirty| !dirty.is_empty()) else {
+            return is_resized;
+        };
+        front.bytes[dirty.clone()].copy_from_slice(&self.bytes[dirty.clone()]);
+        front.dirty = Some(merged(front.dirty.take(), dirty));
+        true
+    }
+
+    #[cfg(any(
+        test,
+        feature = "software",
+        feature = "vello",
+        feature = "tracing",
+        feature = "bench"
+    ))]
     #[must_use]
     #[inline(always)]
     pub fn len(&self) -> usize { self.slots.len() }
 
+    // Every instance, for the CPU backends.
+    #[cfg(any(
+        test,
+        feature = "software",
+        feature = "vello",
+        feature = "bench"
+    ))]
     #[must_use]
     #[inline(always)]
     pub fn bytes(&mut self) -> &[u8] {
-        &self.bytes[..self.slots.len() * Rectangle::SIZE]
+        if self.holes > 0 {
+            self.repack();
+        }
+        &self.bytes
     }
 
+    // What the next upload reads: the front copy when double buffered,
+    // every instance otherwise.
+    #[cfg(any(test, feature = "bench"))]
     #[must_use]
+    pub fn front_bytes(&mut self) -> &[u8] {
+        if self.front.is_none() {
+            return self.bytes();
+        }
+        self.front.as_ref().map_or(&[], |front| &front.bytes)
+    }
+
+    // The bytes changed since the last call and their offset, to write
+    // into the instance buffer.
+    pub fn take_dirty_bytes(&mut self) -> Option<(usize, &[u8])> {
+        if self.front.is_some() {
+            return self.front.as_mut()?.take_dirty_bytes();
+        }
+        if self.holes > 0 {
+            self.repack();
+        }
+        let dirty = self.dirty.take()?;
+        let dirty = dirty.start..dirty.end.min(self.bytes.len());
+        (!dirty.is_empty()).then(|| (dirty.start, &self.bytes[dirty]))
+    }
+
+    // How many bytes the next upload needs room for.
+    #[must_use]
+    pub fn upload_len(&mut self) -> usize {
+        if let Some(front) = &self.front {
+            return front.bytes.len();
+        }
+        if self.holes > 0 {
+            self.repack();
+        }
+        self.bytes.len()
+    }
+
+    // Makes the next upload write every instance, e.g. into a new buffer.
+    pub fn mark_all_dirty(&mut self) {
+        match &mut self.front {
+            Some(front) => front.dirty = Some(0..front.bytes.len()),
+            None => self.mark_dirty(0, self.bytes.len()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<Rectangle> {
+        self.bytes
+            .chunks_exact(Rectangle::SIZE)
+            .zip(&self.owners)
+            .filter(|(_, owner)| owner.is_some())
+            .map(|(bytes, _)| bytemuck::pod_read_unaligned(bytes))
+            .collect()
+    }
+
+    // The indices of the instances of `phase`.
+    #[must_use]
+    pub fn phase_range(&mut self, phase: RenderPhase) -> Range<usize> {
+        if let Some(front) = &self.front {
+            return front.phase_ranges[phase.index()].clone();
+        }
+        if self.holes > 0 {
+            self.repack();
+        }
+        phase_range(&self.orders, phase, |(other, _)| *other)
+    }
+
+    #[must_use]
+    #[inline(always)]
     pub fn add(&mut self, rect: &Rectangle) -> RectangleId {
-        let rect_offset = self.bytes.len();
-        let rect_bytes = bytemuck::bytes_of(rect);
-        self.bytes.extend_from_slice(rect_bytes);
-        self.slots.insert(rect_offset)
+        self.add_in_phase(rect, RenderPhase::Content, 0)
     }
 
+    // Draws `rect` above everything in earlier phases, everything in
+    // `phase` with a lower z-index and everything added before it with
+    // the same one.
     #[must_use]
-    pub fn remove(&mut self, id: RectangleId) -> Option<Rectangle> {
-        let mut removed_rect = Rectangle::zeroed();
+    pub fn add_in_phase(
+        &mut self,
+        rect: &Rectangle,
+        phase: RenderPhase,
+        z_index: i32,
+    ) -> RectangleId {
+        let order = (phase, z_index);
+        let index = self.insertion_index(order);
+        let id = self.slots.insert(index);
+        self.insert(index, bytemuck::bytes_of(rect), id, order);
+        id
+    }
 
-        let removed_rect_offset = self.slots.remove(id)?;
-        let last_rect_offset = self.bytes.len() - Rectangle::SIZE;
+    // Moves the instance above the others in its phase with its new
+    // z-index. Returns false if `id` was removed.
+    pub fn set_z_index(&mut self, id: RectangleId, z_index: i32) -> bool {
+        let Some(&index) = self.slots.get(id) else {
+            return false;
+        };
+        let (phase, old_z_index) = self.orders[index];
+        if old_z_index == z_index {
+            return true;
+        }
+        let offset = index * Rectangle::SIZE;
+        let rect_bytes: Vec<u8> =
+            self.bytes.drain(offset..offset + Rectangle::SIZE).collect();
+        self.owners.remove(index);
+        self.orders.remove(index);
 
-        let removed_rect_bytes =
-            &self.bytes[last_rect_offset..last_rect_offset + Rectangle::SIZE];
-        bytemuck::bytes_of_mut(&mut removed_rect)
-            .copy_from_slice(removed_rect_bytes);
+        let order = (phase, z_index);
+        let new_index = self.insertion_index(order);
+        self.insert(new_index, &rect_bytes, id, order);
+        // The instances between both indices moved by one as well.
+        self.reindex(index.min(new_index));
+        true
+    }
 
-        if removed_rect_offset != last_rect_offset {
-            self.bytes
-                .copy_within(last_rect_offset.., removed_rect_offset);
+    #[must_use]
+    pub fn remove(&mut self, id: RectangleId) -> Option<Rectangle> {
+        let index = self.slots.remove(id)?;
+        let offset = index * Rectangle::SIZE;
+        let removed_rect = bytemuck::pod_read_unaligned(
+            &self.bytes[offset..][..Rectangle::SIZE],
+        );
 
-            if let Some(moved_slot) =
-                self.slots.values_mut().find(|o| **o == last_rect_offset)
-            {
-                *moved_slot = removed_rect_offset;
-            }
+        self.owners[index] = None;
+        self.holes += 1;
+        // Holes at the end go right away.
+        while let Some(None) = self.owners.last() {
+            self.owners.pop();
+            self.orders.pop();
+            self.holes -= 1;
         }
-        self.bytes.truncate(self.bytes.len() - Rectangle::SIZE);
+        self.bytes.truncate(self.owners.len() * Rectangle::SIZE);
 
         Some(removed_rect)
     }
@@ -65,14 +305,113 @@ impl RectangleStore {
     #[must_use]
     #[inline(always)]
     pub fn get_mut(&mut self, id: RectangleId) -> Option<&mut Rectangle> {
-        let rect_offset = *self.slots.get(id)?;
+        let offset = *self.slots.get(id)? * Rectangle::SIZE;
+        self.mark_dirty(offset, Rectangle::SIZE);
 
-        let rect_bytes =
-            &mut self.bytes[rect_offset..rect_offset + Rectangle::SIZE];
+        let rect_bytes = &mut self.bytes[offset..offset + Rectangle::SIZE];
         let rect = bytemuck::from_bytes_mut(rect_bytes);
 
         Some(rect)
     }
+
+    // Like changing `mvp` through `get_mut`, leaving the rest of the
+    // instance out of the next upload.
+    pub fn set_transform(
+        &mut self,
+        id: RectangleId,
+        mvp: [[f32; 4]; 4],
+    ) -> bool {
+        let Some(index) = self.slots.get(id) else {
+            return false;
+        };
+        let offset = index * Rectangle::SIZE;
+        let mvp = bytemuck::bytes_of(&mvp);
+        self.bytes[offset..offset + mvp.len()].copy_from_slice(mvp);
+        self.mark_dirty(offset, mvp.len());
+        true
+    }
+
+    // Squeezes out the holes and gives back the memory left over from
+    // when there were more instances, e.g. after closing a large view.
+    pub fn compact(&mut self) {
+        self.repack();
+        self.owners.shrink_to_fit();
+        self.orders.shrink_to_fit();
+        self.bytes.shrink_to_fit();
+    }
+
+    fn repack(&mut self) {
+        let mut packed = 0;
+        let mut first_moved = None;
+        for index in 0..self.owners.len() {
+            let Some(id) = self.owners[index] else {
+                continue;
+            };
+            if index != packed {
+                let offset = index * Rectangle::SIZE;
+                self.bytes.copy_within(
+                    offset..offset + Rectangle::SIZE,
+                    packed * Rectangle::SIZE,
+                );
+                self.owners[packed] = Some(id);
+                self.orders[packed] = self.orders[index];
+                self.slots[id] = packed;
+                first_moved.get_or_insert(packed);
+            }
+            packed += 1;
+        }
+        self.owners.truncate(packed);
+        self.orders.truncate(packed);
+        self.bytes.truncate(packed * Rectangle::SIZE);
+        self.holes = 0;
+
+        if let Some(first_moved) = first_moved {
+            let offset = first_moved * Rectangle::SIZE;
+            self.mark_dirty(offset, self.bytes.len() - offset);
+        }
+    }
+
+    fn insertion_index(&self, order: (RenderPhase, i32)) -> usize {
+        self.orders.partition_point(|&other| other <= order)
+    }
+
+    fn insert(
+        &mut self,
+        index: usize,
+        rect_bytes: &[u8],
+        id: RectangleId,
+        order: (RenderPhase, i32),
+    ) {
+        let offset = index * Rectangle::SIZE;
+        self.bytes
+            .splice(offset..offset, rect_bytes.iter().copied());
+        self.owners.insert(index, Some(id));
+        self.orders.insert(index, order);
+        self.reindex(index);
+    }
+
+    // Points the slots of the instances from `index` on at where they
+    // are now, and marks them for upload.
+    fn reindex(&mut self, index: usize) {
+        for (index, owner) in self.owners.iter().enumerate().skip(index) {
+            if let Some(id) = owner {
+                self.slots[*id] = index;
+            }
+        }
+        let offset = index * Rectangle::SIZE;
+        self.mark_dirty(offset, self.bytes.len() - offset);
+    }
+
+    fn mark_dirty(&mut self, offset: usize, len: usize) {
+        self.dirty = Some(merged(self.dirty.take(), offset..offset + len));
+    }
+}
+
+fn merged(dirty: Option<Range<usize>>, range: Range<usize>) -> Range<usize> {
+    match dirty {
+        Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+        None => range,
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +419,7 @@ mod tests {
     use rstest::rstest;
 
     use super::{Rectangle, RectangleStore};
+    use crate::core::RenderPhase;
 
     #[rstest]
     fn test_rectangle_store() {
@@ -105,4 +445,235 @@ mod tests {
         let removed_rect = rectangle_store.remove(rect_id).unwrap();
         assert_eq!(removed_rect.fill_color, new_rect_fill_color);
     }
+
+    fn colored(red: f32) -> Rectangle {
+        Rectangle::builder()
+            .mvp([[1.0, 0.0, 0.0, 0.0]; 4])
+            .fill_color([red, 0.0, 0.0, 1.0])
+            .border_color([0.0; 4])
+            .corner_radii([0.0; 4])
+            .shadow_color([0.0; 4])
+            .outline_color([0.0; 4])
+            .clip_rect([0.0, 0.0, 1920.0, 1080.0])
+            .rect_and_shadow([50.0, 25.0, 0.0, 0.0])
+            .sizes([0.0; 4])
+            .build()
+    }
+
+    fn reds(rectangle_store: &mut RectangleStore) -> Vec<f32> {
+        rectangle_store
+            .bytes()
+            .chunks_exact(Rectangle::SIZE)
+            .map(|bytes| {
+                bytemuck::pod_read_unaligned::<Rectangle>(bytes).fill_color[0]
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_removal_keeps_draw_order_and_ids() {
+        let mut rectangle_store = RectangleStore::new();
+        let ids: Vec<_> = (0..5)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+
+        let _ = rectangle_store.remove(ids[1]);
+        let _ = rectangle_store.remove(ids[3]);
+        assert_eq!(rectangle_store.to_vec().len(), 3);
+        assert_eq!(reds(&mut rectangle_store), [0.0, 2.0, 4.0]);
+
+        let added = rectangle_store.add(&colored(5.0));
+        let _ = rectangle_store.remove(ids[0]);
+        rectangle_store.compact();
+        assert_eq!(reds(&mut rectangle_store), [2.0, 4.0, 5.0]);
+
+        rectangle_store.get_mut(ids[4]).unwrap().fill_color[0] = 40.0;
+        rectangle_store.get_mut(added).unwrap().fill_color[0] = 50.0;
+        assert_eq!(reds(&mut rectangle_store), [2.0, 40.0, 50.0]);
+        assert_eq!(rectangle_store.len(), 3);
+    }
+
+    #[rstest]
+    fn test_dirty_bytes() {
+        let mut rectangle_store = RectangleStore::new();
+        let ids: Vec<_> = (0..4)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (0, 4 * Rectangle::SIZE));
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+
+        let mvp = [[2.0, 0.0, 0.0, 0.0]; 4];
+        assert!(rectangle_store.set_transform(ids[2], mvp));
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (2 * Rectangle::SIZE, 64));
+        assert_eq!(rectangle_store.get_mut(ids[2]).unwrap().mvp, mvp);
+
+        // Everything after the first moved instance.
+        let _ = rectangle_store.take_dirty_bytes();
+        let _ = rectangle_store.remove(ids[1]);
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!(
+            (offset, bytes.len()),
+            (Rectangle::SIZE, 2 * Rectangle::SIZE)
+        );
+
+        // Removing the last one only shortens the draw.
+        let _ = rectangle_store.remove(ids[3]);
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+
+        // As into a new buffer.
+        rectangle_store.mark_all_dirty();
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (0, 2 * Rectangle::SIZE));
+        assert_eq!(rectangle_store.upload_len(), 2 * Rectangle::SIZE);
+    }
+
+    #[rstest]
+    fn test_z_index() {
+        let mut rectangle_store = RectangleStore::new();
+        let top = rectangle_store.add_in_phase(
+            &colored(0.0),
+            RenderPhase::Content,
+            10,
+        );
+        let _ = rectangle_store.add_in_phase(
+            &colored(1.0),
+            RenderPhase::Content,
+            -1,
+        );
+        let ids: Vec<_> = (2..5)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+        assert_eq!(reds(&mut rectangle_store), [1.0, 2.0, 3.0, 4.0, 0.0]);
+
+        let _ = rectangle_store.remove(ids[1]);
+        let _ = rectangle_store.take_dirty_bytes();
+        assert!(rectangle_store.set_z_index(ids[0], 5));
+        let (offset, _) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!(offset, Rectangle::SIZE);
+        assert_eq!(reds(&mut rectangle_store), [1.0, 4.0, 2.0, 0.0]);
+
+        assert!(rectangle_store.set_z_index(top, -5));
+        assert_eq!(reds(&mut rectangle_store), [0.0, 1.0, 4.0, 2.0]);
+
+        rectangle_store.get_mut(ids[0]).unwrap().fill_color[0] = 20.0;
+        rectangle_store.get_mut(top).unwrap().fill_color[0] = 10.0;
+        assert_eq!(reds(&mut rectangle_store), [10.0, 1.0, 4.0, 20.0]);
+    }
+
+    #[rstest]
+    fn test_phases() {
+        let mut rectangle_store = RectangleStore::new();
+        let overlay = rectangle_store.add_in_phase(
+            &colored(0.0),
+            RenderPhase::Overlay,
+            -10,
+        );
+        let _ = rectangle_store.add(&colored(1.0));
+        let _ = rectangle_store.add_in_phase(
+            &colored(2.0),
+            RenderPhase::Background,
+            10,
+        );
+        assert_eq!(reds(&mut rectangle_store), [2.0, 1.0, 0.0]);
+
+        // A z-index only moves it within its phase.
+        assert!(rectangle_store.set_z_index(overlay, -20));
+        assert_eq!(reds(&mut rectangle_store), [2.0, 1.0, 0.0]);
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Content), 1..2);
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Debug), 3..3);
+    }
+
+    #[rstest]
+    fn test_double_buffering() {
+        let mut rectangle_store = RectangleStore::new();
+        let ids: Vec<_> = (0..3)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+        rectangle_store.set_double_buffered(true);
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (0, 3 * Rectangle::SIZE));
+
+        // The next frame isn't drawn until it's swapped in.
+        rectangle_store.get_mut(ids[2]).unwrap().fill_color[0] = 20.0;
+        let _ = rectangle_store.add_in_phase(
+            &colored(3.0),
+            RenderPhase::Overlay,
+            0,
+        );
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Content), 0..3);
+
+        assert!(rectangle_store.swap_buffers());
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!(
+            (offset, bytes.len()),
+            (2 * Rectangle::SIZE, 2 * Rectangle::SIZE)
+        );
+        assert!(!rectangle_store.swap_buffers());
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Overlay), 3..4);
+
+        // Swapping twice before an upload uploads both frames' changes.
+        let _ = rectangle_store.remove(ids[1]);
+        rectangle_store.swap_buffers();
+        rectangle_store.get_mut(ids[0]).unwrap().fill_color[0] = 10.0;
+        rectangle_store.swap_buffers();
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (0, 3 * Rectangle::SIZE));
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Content), 0..2);
+
+        rectangle_store.set_double_buffered(false);
+        assert_eq!(reds(&mut rectangle_store), [10.0, 20.0, 3.0]);
+        let (offset, bytes) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!((offset, bytes.len()), (0, 3 * Rectangle::SIZE));
+    }
+
+    #[rstest]
+    fn test_swap_after_render() {
+        let mut rectangle_store = RectangleStore::new();
+        let ids: Vec<_> = (0..2)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+        rectangle_store.set_double_buffered(true);
+        let _ = rectangle_store.take_dirty_bytes();
+
+        // Edited, then a frame is drawn before the swap: the swap has to
+        // ask for another one.
+        rectangle_store.get_mut(ids[0]).unwrap().fill_color[0] = 10.0;
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+        assert!(rectangle_store.swap_buffers());
+        let (offset, _) = rectangle_store.take_dirty_bytes().unwrap();
+        assert_eq!(offset, 0);
+
+        let _ = rectangle_store.remove(ids[1]);
+        assert!(rectangle_store.swap_buffers());
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+        assert_eq!(rectangle_store.phase_range(RenderPhase::Content), 0..1);
+        assert!(!rectangle_store.swap_buffers());
+    }
+
+    #[rstest]
+    fn test_swap_without_changes_keeps_front() {
+        let mut rectangle_store = RectangleStore::new();
+        let ids: Vec<_> = (0..2)
+            .map(|index| rectangle_store.add(&colored(index as f32)))
+            .collect();
+        rectangle_store.set_double_buffered(true);
+        rectangle_store.get_mut(ids[1]).unwrap().fill_color[0] = 10.0;
+        assert!(rectangle_store.swap_buffers());
+        let _ = rectangle_store.take_dirty_bytes();
+        let front = rectangle_store.front_bytes().to_vec();
+
+        assert!(!rectangle_store.swap_buffers());
+        assert_eq!(rectangle_store.front_bytes(), front);
+        assert_eq!(rectangle_store.take_dirty_bytes(), None);
+        assert_eq!(
+            bytemuck::pod_read_unaligned::<Rectangle>(
+                &front[Rectangle::SIZE..]
+            )
+            .fill_color[0],
+            10.0
+        );
+    }
 }
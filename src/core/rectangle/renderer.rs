@@ -1,18 +1,20 @@
 use wgpu::{
     BlendComponent, BlendFactor, BlendOperation, BlendState, Buffer,
-    BufferDescriptor, BufferUsages, ColorTargetState, ColorWrites, Device,
-    FragmentState, FrontFace, IndexFormat, MultisampleState,
-    PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology,
-    Queue, RenderPass, RenderPipeline, RenderPipelineDescriptor,
-    ShaderModuleDescriptor, ShaderSource, TextureFormat, VertexBufferLayout,
-    VertexState, VertexStepMode,
+    BufferUsages, ColorTargetState, ColorWrites, Device, FragmentState,
+    FrontFace, IndexFormat, MultisampleState, PipelineLayoutDescriptor,
+    PolygonMode, PrimitiveState, PrimitiveTopology, Queue, RenderPass,
+    RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderSource, TextureFormat, VertexBufferLayout, VertexState,
+    VertexStepMode,
     util::{BufferInitDescriptor, DeviceExt},
     vertex_attr_array,
 };
 
 use super::{Rectangle, RectangleId, RectangleStore};
-
-const MAX_INSTANCE_COUNT: u64 = 1024;
+use crate::{
+    core::{InstanceBuffer, RenderPhase, capture::FrameRecorder},
+    instrument::{counter, span},
+};
 
 #[rustfmt::skip]
 const VERTICES: &[[f32; 3]; 4] = &[
@@ -31,7 +33,7 @@ pub struct RectangleRenderer {
     render_pipeline: RenderPipeline,
     vertex_buffer:   Buffer,
     index_buffer:    Buffer,
-    instance_buffer: Buffer,
+    instance_buffer: InstanceBuffer,
     instance_store:  RectangleStore,
 }
 
@@ -54,13 +56,11 @@ impl RectangleRenderer {
         };
         let index_buffer = device.create_buffer_init(&index_buffer_desc);
 
-        let instance_buffer_desc = BufferDescriptor {
-            label:              Some("hui::rectangle::instance_buffer"),
-            size:               MAX_INSTANCE_COUNT * Rectangle::SIZE as u64,
-            usage:              BufferUsages::VERTEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        };
-        let instance_buffer = device.create_buffer(&instance_buffer_desc);
+        let instance_buffer = InstanceBuffer::new(
+            device,
+            "hui::rectangle::instance_buffer",
+            Rectangle::SIZE,
+        );
 
         Self {
             render_pipeline,
@@ -77,39 +77,118 @@ impl RectangleRenderer {
         self.instance_store.get_mut(id)
     }
 
+    #[inline(always)]
+    pub fn set_transform(
+        &mut self,
+        id: RectangleId,
+        mvp: [[f32; 4]; 4],
+    ) -> bool {
+        self.instance_store.set_transform(id, mvp)
+    }
+
+    #[inline(always)]
+    pub fn set_z_index(&mut self, id: RectangleId, z_index: i32) -> bool {
+        self.instance_store.set_z_index(id, z_index)
+    }
+
     #[inline(always)]
     pub fn add(&mut self, instance: &Rectangle) -> RectangleId {
         self.instance_store.add(instance)
     }
 
+    #[inline(always)]
+    pub fn add_in_phase(
+        &mut self,
+        instance: &Rectangle,
+        phase: RenderPhase,
+    ) -> RectangleId {
+        self.instance_store.add_in_phase(instance, phase, 0)
+    }
+
     #[inline(always)]
     pub fn remove(&mut self, id: RectangleId) -> Option<Rectangle> {
         self.instance_store.remove(id)
     }
 
-    pub fn render(&mut self, queue: &Queue, render_pass: &mut RenderPass) {
-        if self.instance_store.is_empty() {
+    #[inline(always)]
+    pub fn compact(&mut self) { self.instance_store.compact(); }
+
+    #[inline(always)]
+    pub fn set_double_buffered(&mut self, is_double_buffered: bool) {
+        self.instance_store.set_double_buffered(is_double_buffered);
+    }
+
+    #[inline(always)]
+    pub fn swap_buffers(&mut self) -> bool {
+        self.instance_store.swap_buffers()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn instances(&self) -> Vec<Rectangle> { self.instance_store.to_vec() }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn buffer_bytes(&self) -> u64 { self.instance_buffer.size() }
+
+    // Uploads what changed, once per frame before the phases are drawn.
+    pub(crate) fn prepare(
+        &mut self,
+        queue: &Queue,
+        recorder: &mut FrameRecorder,
+    ) {
+        if self.instance_store.is_front_empty() {
+            recorder.skip("rectangle", "no instances");
             return;
         }
-        let instance_bytes = self.instance_store.bytes();
+        span!("build_instances", kind = "rectangle");
+        counter!(rectangles, self.instance_store.len());
+        let upload_len = self.instance_store.upload_len() as u64;
+        if self.instance_buffer.reserve(upload_len) {
+            self.instance_store.mark_all_dirty();
+        }
+        // The buffer keeps what was written in earlier frames.
+        if let Some((offset, dirty_bytes)) =
+            self.instance_store.take_dirty_bytes()
+        {
+            queue.write_buffer(
+                self.instance_buffer.buffer(),
+                offset as u64,
+                dirty_bytes,
+            );
+            recorder
+                .upload("hui::rectangle::instance_buffer", dirty_bytes.len());
+        }
+    }
 
+    pub(crate) fn render(
+        &mut self,
+        phase: RenderPhase,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        let range = self.instance_store.phase_range(phase);
+        if range.is_empty() {
+            return;
+        }
         render_pass.set_pipeline(&self.render_pipeline);
-        queue.write_buffer(&self.instance_buffer, 0, instance_bytes);
 
         let vertex_buffer = self.vertex_buffer.slice(..);
         render_pass.set_vertex_buffer(0, vertex_buffer);
 
-        let instance_buffer = self.instance_buffer.slice(..);
+        let instance_buffer = self.instance_buffer.buffer().slice(..);
         render_pass.set_vertex_buffer(1, instance_buffer);
 
         let index_buffer = self.index_buffer.slice(..);
         render_pass.set_index_buffer(index_buffer, IndexFormat::Uint16);
 
+        let instances = range.start as u32..range.end as u32;
         render_pass.draw_indexed(
             0..INDICES.len() as u32,
             0,
-            0..self.instance_store.len() as u32,
+            instances.clone(),
         );
+        recorder.draw("rectangle", 0..INDICES.len() as u32, instances);
     }
 }
 
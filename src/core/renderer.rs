@@ -1,26 +1,1187 @@
 use rayon::ThreadPool;
+use slotmap::SlotMap;
+#[cfg(feature = "software")]
+use tiny_skia::Pixmap;
+#[cfg(feature = "vello")]
+use vello_cpu::Pixmap as VelloPixmap;
 use wgpu::{
     Color, CommandEncoder, Device, Extent3d, LoadOp, Operations, Queue,
-    RenderPassColorAttachment, RenderPassDescriptor, StoreOp,
-    SurfaceConfiguration, Texture, TextureDescriptor, TextureDimension,
-    TextureFormat, TextureUsages, TextureView,
+    RenderPass, RenderPassColorAttachment, RenderPassDescriptor, StoreOp,
+    Surface, SurfaceConfiguration, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureView,
 };
 
-use super::{CompositeRenderer, Rectangle, RectangleId, RectangleRenderer};
+#[cfg(feature = "software")]
+use super::SoftwareRenderer;
+#[cfg(feature = "vello")]
+use super::VelloRenderer;
+use super::{
+    ArcRenderer, ArcSegment, ArcSegmentId, CompositeRenderer, Image, ImageId,
+    ImageRenderer, Layer, LayerCompositor, LayerId, LayerMask, LayerTransform,
+    Rectangle, RectangleId, RectangleRenderer, SurfaceMask,
+    SurfaceMaskRenderer, TextRasterConfig, TextureId, VectorScene,
+};
+use super::{
+    ClipStack, FrameCapture, MemoryBudget, MemoryPressure, MemoryUsage,
+    OrphanQueue, PresentMode, PresentOptions, PresentSupport,
+    PrimitivePlugins, PrimitiveRenderer, PrimitiveRendererId, PrimitiveSetup,
+    PrimitiveStage, RenderPhase,
+    capture::FrameRecorder,
+    memory::{enforce_budget, texture_bytes},
+};
+#[cfg(any(feature = "software", feature = "vello"))]
+use super::{PathId, VectorPath};
+use crate::instrument::span;
 
-pub struct Renderer {
+// Where the renderer draws to, chosen once when it is created.
+pub enum Backend<'a> {
+    // The device and queue are kept for uploads and for recreating the
+    // targets, so that no other call needs them.
+    Gpu {
+        device:           &'a Device,
+        queue:            &'a Queue,
+        surface_config:   &'a SurfaceConfiguration,
+        color_operations: Operations<Color>,
+        thread_pool:      &'a ThreadPool,
+    },
+    // Rasterizes the same primitives on the CPU into a pixmap of the given
+    // size, for environments without GPU access such as CI or servers
+    // rendering previews. Layers and surface masks are not drawn.
+    #[cfg(feature = "software")]
+    Software { size: [u32; 2], clear_color: [f32; 4] },
+    // Draws the primitives as vector paths with vello's sparse strip
+    // rasterizer, for scenes with many complex paths. Like the software
+    // backend it renders into a pixmap without layers or surface masks.
+    #[cfg(feature = "vello")]
+    Vello { size: [u32; 2], clear_color: [f32; 4] },
+}
+
+enum RendererBackend {
+    Gpu(Box<GpuRenderer>),
+    #[cfg(feature = "software")]
+    Software(Box<SoftwareRenderer>),
+    #[cfg(feature = "vello")]
+    Vello(Box<VelloRenderer>),
+}
+
+impl RendererBackend {
+    fn gpu_mut(&mut self) -> Option<&mut GpuRenderer> {
+        match self {
+            Self::Gpu(gpu) => Some(gpu),
+            #[cfg(feature = "software")]
+            Self::Software(_) => None,
+            #[cfg(feature = "vello")]
+            Self::Vello(_) => None,
+        }
+    }
+}
+
+struct GpuRenderer {
+    device:                 Device,
+    queue:                  Queue,
     offscreen_texture:      Texture,
     offscreen_texture_view: TextureView,
     color_operations:       Operations<Color>,
     rectangle_renderer:     RectangleRenderer,
+    arc_renderer:           ArcRenderer,
+    image_renderer:         ImageRenderer,
     composite_renderer:     CompositeRenderer,
-    is_redraw_required:     bool,
+    surface_mask_renderer:  SurfaceMaskRenderer,
+    layer_compositor:       LayerCompositor,
+    layers:                 SlotMap<LayerId, Layer>,
+    layer_order:            Vec<LayerId>,
+    plugins:                PrimitivePlugins,
+    surface_size:           [u32; 2],
+    render_scale:           f32,
+    is_suspended:           bool,
+}
+
+pub struct Renderer {
+    backend:            RendererBackend,
+    text_raster_config: TextRasterConfig,
+    is_redraw_required: bool,
+    recorder:           FrameRecorder,
+    is_capture_pending: bool,
+    frame_capture:      Option<FrameCapture>,
+    memory_budget:      MemoryBudget,
+    orphans:            OrphanQueue,
+    phase:              RenderPhase,
+    clip_stacks:        [ClipStack; RenderPhase::ALL.len()],
+    present_options:    PresentOptions,
 }
 
 impl Renderer {
     #[must_use]
     pub fn new(
         device: &Device,
+        queue: &Queue,
+        surface_config: &SurfaceConfiguration,
+        color_operations: Operations<Color>,
+        thread_pool: &ThreadPool,
+    ) -> Self {
+        Self::with_backend(Backend::Gpu {
+            device,
+            queue,
+            surface_config,
+            color_operations,
+            thread_pool,
+        })
+    }
+
+    #[must_use]
+    pub fn with_backend(backend: Backend) -> Self {
+        let backend = match backend {
+            Backend::Gpu {
+                device,
+                queue,
+                surface_config,
+                color_operations,
+                thread_pool,
+            } => RendererBackend::Gpu(Box::new(GpuRenderer::new(
+                device,
+                queue,
+                surface_config,
+                color_operations,
+                thread_pool,
+            ))),
+            #[cfg(feature = "software")]
+            Backend::Software { size, clear_color } => {
+                RendererBackend::Software(Box::new(SoftwareRenderer::new(
+                    size,
+                    clear_color,
+                )))
+            }
+            #[cfg(feature = "vello")]
+            Backend::Vello { size, clear_color } => RendererBackend::Vello(
+                Box::new(VelloRenderer::new(size, clear_color)),
+            ),
+        };
+
+        Self {
+            backend,
+            text_raster_config: TextRasterConfig::default(),
+            is_redraw_required: true,
+            recorder: FrameRecorder::default(),
+            is_capture_pending: false,
+            frame_capture: None,
+            memory_budget: MemoryBudget::default(),
+            orphans: OrphanQueue::default(),
+            phase: RenderPhase::default(),
+            clip_stacks: Default::default(),
+            present_options: PresentOptions::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn resize(&mut self, width: u32, height: u32) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.resize(width, height),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.resize(width, height);
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.resize(width, height);
+            }
+        }
+        self.is_redraw_required = true;
+    }
+
+    // Recovers from `SurfaceError::Lost` or `SurfaceError::Outdated`, e.g.
+    // after the monitor was unplugged: reconfigure the surface with the
+    // window's current size, then call this to recreate the targets and
+    // redraw everything on the next frame. Until a size without zeros
+    // comes in, as while minimized, `render` draws nothing.
+    pub fn recover_surface(&mut self, width: u32, height: u32) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.recover(width, height),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.resize(width, height);
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.resize(width, height);
+            }
+        }
+        self.is_redraw_required = true;
+    }
+
+    // Frees the textures sized to the surface when it goes away while the
+    // app keeps running, as on Android between `Suspended` and `Resumed`.
+    // Everything added to the renderer is kept; `render` draws nothing
+    // until `resume` recreates the textures for the new surface.
+    pub fn suspend(&mut self) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.suspend(),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => {}
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => {}
+        }
+    }
+
+    pub fn resume(&mut self, width: u32, height: u32) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.resume(width, height),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => {}
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => {}
+        }
+        self.is_redraw_required = true;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn is_suspended(&self) -> bool {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.is_suspended,
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => false,
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => false,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn render_scale(&self) -> f32 {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.render_scale,
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => 1.0,
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => 1.0,
+        }
+    }
+
+    // The size of the offscreen targets the UI is drawn into; view
+    // projections should cover this rather than the surface size.
+    #[must_use]
+    #[inline(always)]
+    pub fn render_size(&self) -> [u32; 2] {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => [
+                gpu.offscreen_texture.width(),
+                gpu.offscreen_texture.height(),
+            ],
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => software.size(),
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.size(),
+        }
+    }
+
+    // Renders the UI at a fraction of the surface resolution and upscales
+    // it when compositing, e.g. for `PowerSettings::render_scale`. Widgets
+    // then have to be built with the device pixel ratio multiplied by the
+    // same scale. The software backend always renders at full resolution.
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                let render_scale = render_scale.clamp(0.1, 1.0);
+                if render_scale == gpu.render_scale {
+                    return;
+                }
+                gpu.render_scale = render_scale;
+                gpu.resize_targets();
+                self.is_redraw_required = true;
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => {}
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => {}
+        }
+    }
+
+    // As last applied by `configure_present`, with the mode the surface
+    // ended up with.
+    #[must_use]
+    #[inline(always)]
+    pub const fn present_options(&self) -> PresentOptions {
+        self.present_options
+    }
+
+    // Reconfigures the surface to present with `options`, falling back to
+    // the closest mode in `support` when the requested one isn't, and
+    // returns the mode used. `surface_config` keeps the choice for later
+    // reconfigurations, e.g. on resize. Can be called at any time, e.g.
+    // to drop vsync while a tool drags something. The surface is left
+    // alone on the software and vello backends.
+    pub fn configure_present(
+        &mut self,
+        surface: &Surface,
+        surface_config: &mut SurfaceConfiguration,
+        support: &PresentSupport,
+        options: PresentOptions,
+    ) -> PresentMode {
+        let mode = support.resolve(options.mode());
+        let max_frame_latency = options.max_frame_latency().max(1);
+        surface_config.present_mode = mode.to_wgpu();
+        surface_config.desired_maximum_frame_latency = max_frame_latency;
+        if let Some(gpu) = self.backend.gpu_mut() {
+            surface.configure(&gpu.device, surface_config);
+        }
+
+        self.present_options = PresentOptions::builder()
+            .mode(mode)
+            .max_frame_latency(max_frame_latency)
+            .build();
+        self.is_redraw_required = true;
+        mode
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn text_raster_config(&self) -> &TextRasterConfig {
+        &self.text_raster_config
+    }
+
+    // Glyphs built afterwards use the new options; glyphs already in an
+    // atlas keep the hinting they were rasterized with.
+    pub fn set_text_raster_config(&mut self, config: TextRasterConfig) {
+        self.text_raster_config = config;
+        self.is_redraw_required = true;
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_rectangle(
+        &mut self,
+        id: RectangleId,
+    ) -> Option<&mut Rectangle> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.rectangle_renderer.get_mut(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().get_mut_rectangle(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().get_mut_rectangle(id)
+            }
+        }
+    }
+
+    // Moves a rectangle without changing the rest of it; only the
+    // transform is uploaded again. Returns whether it exists.
+    pub fn set_rectangle_transform(
+        &mut self,
+        id: RectangleId,
+        mvp: [[f32; 4]; 4],
+    ) -> bool {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.rectangle_renderer.set_transform(id, mvp)
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => software
+                .scene_mut()
+                .get_mut_rectangle(id)
+                .map(|rectangle| rectangle.mvp = mvp)
+                .is_some(),
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello
+                .scene_mut()
+                .get_mut_rectangle(id)
+                .map(|rectangle| rectangle.mvp = mvp)
+                .is_some(),
+        }
+    }
+
+    // Rectangles are drawn by z-index, and in the order they were added
+    // within one; this one goes above the others with `z_index`. Returns
+    // whether it exists.
+    pub fn set_rectangle_z_index(
+        &mut self,
+        id: RectangleId,
+        z_index: i32,
+    ) -> bool {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.rectangle_renderer.set_z_index(id, z_index)
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().set_rectangle_z_index(id, z_index)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().set_rectangle_z_index(id, z_index)
+            }
+        }
+    }
+
+    // The phase rectangles, arcs, paths and images are added to; the
+    // content phase unless set.
+    #[must_use]
+    #[inline(always)]
+    pub const fn render_phase(&self) -> RenderPhase { self.phase }
+
+    // Returns the phase set before, to go back to once the overlay or
+    // debug visuals are added.
+    #[inline(always)]
+    pub const fn set_render_phase(
+        &mut self,
+        phase: RenderPhase,
+    ) -> RenderPhase {
+        std::mem::replace(&mut self.phase, phase)
+    }
+
+    // Clips what's added from now on to `clip_rect` as well, in physical
+    // pixels, until it's popped, e.g. the children of a scroll view.
+    // Pushed clip rects intersect with the ones below them, in the same
+    // phase. Changing an instance's clip rect later replaces the pushed
+    // ones.
+    #[inline(always)]
+    pub fn push_clip(&mut self, clip_rect: [f32; 4]) {
+        self.clip_stacks[self.phase.index()].push(clip_rect);
+    }
+
+    #[inline(always)]
+    pub fn pop_clip(&mut self) -> Option<[f32; 4]> {
+        self.clip_stacks[self.phase.index()].pop()
+    }
+
+    // The intersection of the clip rects pushed in the current phase.
+    #[must_use]
+    #[inline(always)]
+    pub fn clip_rect(&self) -> Option<[f32; 4]> { self.clip_stack().current() }
+
+    #[inline(always)]
+    pub fn add_rectangle(&mut self, instance: &Rectangle) -> RectangleId {
+        self.is_redraw_required = true;
+        let instance = &Rectangle {
+            clip_rect: self.clip_stack().clip(instance.clip_rect),
+            ..*instance
+        };
+        let phase = self.phase;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.rectangle_renderer.add_in_phase(instance, phase)
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().add_rectangle(instance, phase)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().add_rectangle(instance, phase)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove_rectangle(&mut self, id: RectangleId) -> Option<Rectangle> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.rectangle_renderer.remove(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_rectangle(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().remove_rectangle(id)
+            }
+        }
+    }
+
+    // Re-packs the rectangle instances and frees the memory left over
+    // from removed ones. Ids stay valid. Holes are otherwise only
+    // squeezed out as frames are built, so this is for after removing
+    // many rectangles at once, e.g. when a large view closes.
+    pub fn compact(&mut self) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.rectangle_renderer.compact(),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().compact()
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene_mut().compact(),
+        }
+    }
+
+    // Makes frames draw the rectangles as of the last `swap_buffers`
+    // rather than as they are, so that the next frame can be built while
+    // the last one is uploaded and drawn:
+    //
+    // renderer.swap_buffers();
+    // renderer.render(...);
+    // ... update widgets for the next frame ...
+    //
+    // Only the rectangles of the GPU backend are double buffered; the
+    // CPU backends rasterize right away.
+    pub fn set_double_buffered(&mut self, is_double_buffered: bool) {
+        if let Some(gpu) = self.backend.gpu_mut() {
+            gpu.rectangle_renderer
+                .set_double_buffered(is_double_buffered);
+        }
+    }
+
+    // Publishes the rectangles as they are to the next frames. Call it at
+    // frame boundaries, once the frame is built.
+    pub fn swap_buffers(&mut self) {
+        if let Some(gpu) = self.backend.gpu_mut()
+            && gpu.rectangle_renderer.swap_buffers()
+        {
+            self.is_redraw_required = true;
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_arc(
+        &mut self,
+        id: ArcSegmentId,
+    ) -> Option<&mut ArcSegment> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.arc_renderer.get_mut(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().get_mut_arc(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene_mut().get_mut_arc(id),
+        }
+    }
+
+    #[inline(always)]
+    pub fn add_arc(&mut self, instance: &ArcSegment) -> ArcSegmentId {
+        self.is_redraw_required = true;
+        let instance = &ArcSegment {
+            clip_rect: self.clip_stack().clip(instance.clip_rect),
+            ..*instance
+        };
+        let phase = self.phase;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.arc_renderer.add(instance, phase),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().add_arc(instance, phase)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().add_arc(instance, phase)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove_arc(&mut self, id: ArcSegmentId) -> Option<ArcSegment> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.arc_renderer.remove(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_arc(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene_mut().remove_arc(id),
+        }
+    }
+
+    #[cfg(any(feature = "software", feature = "vello"))]
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_path(&mut self, id: PathId) -> Option<&mut VectorPath> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(_) => None,
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().get_mut_path(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().get_mut_path(id)
+            }
+        }
+    }
+
+    // Returns None on the GPU backend, which doesn't draw paths.
+    #[cfg(any(feature = "software", feature = "vello"))]
+    #[inline(always)]
+    pub fn add_path(&mut self, path: &VectorPath) -> Option<PathId> {
+        self.is_redraw_required = true;
+        let path = &VectorPath {
+            clip_rect: self.clip_stack().clip(path.clip_rect),
+            ..path.clone()
+        };
+        let phase = self.phase;
+        match &mut self.backend {
+            RendererBackend::Gpu(_) => None,
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                Some(software.scene_mut().add_path(path, phase))
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                Some(vello.scene_mut().add_path(path, phase))
+            }
+        }
+    }
+
+    #[cfg(any(feature = "software", feature = "vello"))]
+    #[inline(always)]
+    pub fn remove_path(&mut self, id: PathId) -> Option<VectorPath> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(_) => None,
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_path(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene_mut().remove_path(id),
+        }
+    }
+
+    #[inline(always)]
+    pub fn add_texture(&mut self, size: [u32; 2], rgba: &[u8]) -> TextureId {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.add_texture(
+                &gpu.device,
+                &gpu.queue,
+                size,
+                rgba,
+            ),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.add_texture(size, rgba)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.add_texture(size, rgba),
+        }
+    }
+
+    #[inline(always)]
+    pub fn write_texture(
+        &mut self,
+        id: TextureId,
+        origin: [u32; 2],
+        size: [u32; 2],
+        rgba: &[u8],
+    ) -> bool {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.write_texture(
+                &gpu.device,
+                &gpu.queue,
+                id,
+                origin,
+                size,
+                rgba,
+            ),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.write_texture(id, origin, size, rgba)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.write_texture(id, origin, size, rgba)
+            }
+        }
+    }
+
+    // Textures only have views on the GPU backend.
+    #[must_use]
+    #[inline(always)]
+    pub fn texture_view(&self, id: TextureId) -> Option<&TextureView> {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.texture_view(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => None,
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => None,
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn texture_size(&self, id: TextureId) -> Option<[u32; 2]> {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.texture_size(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene().texture_size(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene().texture_size(id),
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove_texture(&mut self, id: TextureId) -> bool {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu
+                .image_renderer
+                .remove_texture(id)
+                .map(|texture| self.orphans.push_texture(texture))
+                .is_some(),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_texture(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().remove_texture(id)
+            }
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_image(&mut self, id: ImageId) -> Option<&mut Image> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.get_mut(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().get_mut_image(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().get_mut_image(id)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn add_image(
+        &mut self,
+        texture_id: TextureId,
+        instance: &Image,
+    ) -> Option<ImageId> {
+        self.is_redraw_required = true;
+        let instance = &Image {
+            clip_rect: self.clip_stack().clip(instance.clip_rect),
+            ..*instance
+        };
+        let phase = self.phase;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.image_renderer.add(texture_id, instance, phase)
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().add_image(texture_id, instance, phase)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().add_image(texture_id, instance, phase)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_image_texture(&mut self, id: ImageId, texture_id: TextureId) {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.image_renderer.set_texture(id, texture_id);
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().set_image_texture(id, texture_id);
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().set_image_texture(id, texture_id);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove_image(&mut self, id: ImageId) -> Option<Image> {
+        self.is_redraw_required = true;
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.image_renderer.remove(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_image(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().remove_image(id)
+            }
+        }
+    }
+
+    // Adds a masked rectangle layer, see `Layer`.
+    pub fn add_layer(&mut self, mask: Option<LayerMask>) -> LayerId {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.add_layer(mask),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().add_layer()
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.scene_mut().add_layer(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_mut_layer(&mut self, id: LayerId) -> Option<&mut Layer> {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.layers.get_mut(id),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => None,
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => None,
+        }
+    }
+
+    pub fn set_layer_mask(&mut self, id: LayerId, mask: Option<LayerMask>) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                if let Some(layer) = gpu.layers.get_mut(id) {
+                    layer.set_mask(&gpu.device, &gpu.layer_compositor, mask);
+                }
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => {}
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => {}
+        }
+    }
+
+    // Moves, scales or fades the layer when it's composited, without
+    // redrawing it. Layers are only drawn on the GPU backend.
+    pub fn set_layer_transform(
+        &mut self,
+        id: LayerId,
+        transform: LayerTransform,
+    ) {
+        if let Some(layer) = self
+            .backend
+            .gpu_mut()
+            .and_then(|gpu| gpu.layers.get_mut(id))
+        {
+            layer.set_transform(transform);
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn surface_mask(&self) -> Option<&SurfaceMask> {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.surface_mask_renderer.mask(),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => None,
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_surface_mask(&mut self, mask: Option<SurfaceMask>) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.surface_mask_renderer.set_mask(mask);
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => {}
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => {}
+        }
+    }
+
+    // The layer's texture is freed over the next frames.
+    pub fn remove_layer(&mut self, id: LayerId) {
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => {
+                gpu.layer_order.retain(|layer_id| *layer_id != id);
+                if let Some(layer) = gpu.layers.remove(id) {
+                    self.orphans.push_layer(layer);
+                }
+            }
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                software.scene_mut().remove_layer(id)
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                vello.scene_mut().remove_layer(id)
+            }
+        }
+    }
+
+    // Textures and layers removed but not freed yet.
+    #[must_use]
+    #[inline(always)]
+    pub const fn orphans(&self) -> &OrphanQueue { &self.orphans }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn orphans_mut(&mut self) -> &mut OrphanQueue {
+        &mut self.orphans
+    }
+
+    // On the software and vello backends the arguments are unused and the
+    // frame is rasterized into the pixmap returned by `render_software` or
+    // `render_vello`.
+    pub fn render(
+        &mut self,
+        surface_texture_view: &TextureView,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        if let RendererBackend::Gpu(gpu) = &self.backend
+            && (gpu.is_suspended || gpu.is_surface_empty())
+        {
+            return;
+        }
+        span!("render");
+        self.orphans.collect();
+        self.start_capture();
+        match &mut self.backend {
+            RendererBackend::Gpu(gpu) => gpu.render(
+                surface_texture_view,
+                command_encoder,
+                self.is_redraw_required,
+                &mut self.recorder,
+            ),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => {
+                if self.is_redraw_required {
+                    software.record(&mut self.recorder);
+                    software.render();
+                }
+            }
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => {
+                if self.is_redraw_required {
+                    vello.record(&mut self.recorder);
+                    vello.render();
+                }
+            }
+        }
+        self.finish_capture();
+        self.is_redraw_required = false;
+    }
+
+    // Adds a renderer for primitives of a kind hui doesn't draw, created
+    // by `create`, to the main pass. Returns None on the software and
+    // vello backends, which have no pipelines.
+    pub fn register_primitive_renderer<R: PrimitiveRenderer>(
+        &mut self,
+        stage: PrimitiveStage,
+        create: impl FnOnce(&PrimitiveSetup) -> R,
+    ) -> Option<PrimitiveRendererId> {
+        let gpu = self.backend.gpu_mut()?;
+        let setup = PrimitiveSetup {
+            device:        &gpu.device,
+            format:        gpu.offscreen_texture.format(),
+            camera_layout: gpu.plugins.camera_layout(),
+        };
+        let mut renderer = create(&setup);
+        let [width, height] = scaled_size(gpu.surface_size, gpu.render_scale);
+        renderer.resize(&gpu.device, [width, height]);
+        self.is_redraw_required = true;
+        Some(gpu.plugins.register(stage, Box::new(renderer)))
+    }
+
+    // None if `R` isn't the type it was registered with.
+    #[must_use]
+    pub fn get_mut_primitive_renderer<R: PrimitiveRenderer>(
+        &mut self,
+        id: PrimitiveRendererId,
+    ) -> Option<&mut R> {
+        let gpu = self.backend.gpu_mut()?;
+        self.is_redraw_required = true;
+        gpu.plugins.get_mut(id)
+    }
+
+    pub fn remove_primitive_renderer(
+        &mut self,
+        id: PrimitiveRendererId,
+    ) -> Option<Box<dyn PrimitiveRenderer>> {
+        let gpu = self.backend.gpu_mut()?;
+        self.is_redraw_required = true;
+        gpu.plugins.remove(id)
+    }
+
+    // What primitive renderers find in their camera uniform.
+    pub fn set_view_projection(&mut self, view_projection: [[f32; 4]; 4]) {
+        if let Some(gpu) = self.backend.gpu_mut() {
+            gpu.plugins.set_view_projection(&gpu.queue, view_projection);
+            self.is_redraw_required = true;
+        }
+    }
+
+    // GPU memory held by the renderer; nothing on the software and vello
+    // backends.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => MemoryUsage {
+                orphans: self.orphans.pending_bytes(),
+                ..gpu.memory_usage()
+            },
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => MemoryUsage::default(),
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => MemoryUsage::default(),
+        }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn memory_budget(&self) -> MemoryBudget { self.memory_budget }
+
+    #[inline(always)]
+    pub const fn set_memory_budget(&mut self, budget: MemoryBudget) {
+        self.memory_budget = budget;
+    }
+
+    // Calls `evict` for as long as the budget is gone over, e.g. after
+    // loading textures, for it to remove textures or layers it can
+    // recreate later. Removed textures and layers count until they are
+    // freed, so while the budget is gone over the orphan queue is flushed
+    // right away rather than over the next frames. Stops once a call
+    // removes nothing.
+    pub fn enforce_memory_budget(
+        &mut self,
+        evict: impl FnMut(&mut Self, &MemoryPressure),
+    ) {
+        let budget = self.memory_budget;
+        enforce_budget(
+            &budget,
+            self,
+            Self::memory_usage,
+            |renderer| renderer.orphans.flush_now(),
+            evict,
+        );
+    }
+
+    // Records everything the next `render`, `render_software` or
+    // `render_vello` does, for `take_frame_capture` to return.
+    #[inline(always)]
+    pub fn capture_next_frame(&mut self) { self.is_capture_pending = true; }
+
+    // The last captured frame, once it has been rendered.
+    #[inline(always)]
+    pub fn take_frame_capture(&mut self) -> Option<FrameCapture> {
+        self.frame_capture.take()
+    }
+
+    #[inline(always)]
+    fn clip_stack(&self) -> &ClipStack {
+        &self.clip_stacks[self.phase.index()]
+    }
+
+    fn start_capture(&mut self) {
+        if !self.is_capture_pending {
+            return;
+        }
+        let backend = match &self.backend {
+            RendererBackend::Gpu(_) => "gpu",
+            #[cfg(feature = "software")]
+            RendererBackend::Software(_) => "software",
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(_) => "vello",
+        };
+        self.recorder.start(backend, self.is_redraw_required);
+    }
+
+    fn finish_capture(&mut self) {
+        if self.is_capture_pending {
+            self.is_capture_pending = false;
+            self.frame_capture = self.recorder.finish();
+        }
+    }
+
+    // Copies what the next frame draws, for writing it out as SVG or PDF.
+    // Layers and surface masks are left out, and so are the GPU backend's
+    // textures.
+    #[must_use]
+    pub fn export_scene(&self) -> VectorScene {
+        match &self.backend {
+            RendererBackend::Gpu(gpu) => gpu.export_scene(),
+            #[cfg(feature = "software")]
+            RendererBackend::Software(software) => software.export_scene(),
+            #[cfg(feature = "vello")]
+            RendererBackend::Vello(vello) => vello.export_scene(),
+        }
+    }
+
+    // Rasterizes the frame if anything changed since the last one and
+    // returns it. Returns None on the other backends.
+    #[cfg(feature = "software")]
+    pub fn render_software(&mut self) -> Option<&Pixmap> {
+        let RendererBackend::Software(software) = &mut self.backend else {
+            return None;
+        };
+        span!("render");
+        if self.is_capture_pending {
+            self.recorder.start("software", self.is_redraw_required);
+            if self.is_redraw_required {
+                software.record(&mut self.recorder);
+            }
+            self.is_capture_pending = false;
+            self.frame_capture = self.recorder.finish();
+        }
+        if self.is_redraw_required {
+            self.is_redraw_required = false;
+            return Some(software.render());
+        }
+        Some(software.pixmap())
+    }
+
+    // Like `render_software`, for the vello backend.
+    #[cfg(feature = "vello")]
+    pub fn render_vello(&mut self) -> Option<&VelloPixmap> {
+        let RendererBackend::Vello(vello) = &mut self.backend else {
+            return None;
+        };
+        span!("render");
+        if self.is_capture_pending {
+            self.recorder.start("vello", self.is_redraw_required);
+            if self.is_redraw_required {
+                vello.record(&mut self.recorder);
+            }
+            self.is_capture_pending = false;
+            self.frame_capture = self.recorder.finish();
+        }
+        if self.is_redraw_required {
+            self.is_redraw_required = false;
+            return Some(vello.render());
+        }
+        Some(vello.pixmap())
+    }
+}
+
+impl GpuRenderer {
+    fn export_scene(&self) -> VectorScene {
+        let size = [
+            self.offscreen_texture.width(),
+            self.offscreen_texture.height(),
+        ];
+        let clear_color = match self.color_operations.load {
+            LoadOp::Clear(color) => {
+                [color.r, color.g, color.b, color.a].map(|value| value as f32)
+            }
+            LoadOp::Load => [0.0; 4],
+        };
+        let mut scene = VectorScene::new(size, clear_color);
+        scene.rectangles = self.rectangle_renderer.instances();
+        scene.arcs = self.arc_renderer.instances();
+        scene.images = self.image_renderer.images();
+        scene
+    }
+
+    fn new(
+        device: &Device,
+        queue: &Queue,
         surface_config: &SurfaceConfiguration,
         color_operations: Operations<Color>,
         thread_pool: &ThreadPool,
@@ -42,63 +1203,120 @@ impl Renderer {
                 &offscreen_texture_view,
             )
         };
-        let (rectangle_renderer, composite_renderer) = thread_pool
-            .join(create_rectangle_renderer, create_composite_renderer);
+        let create_image_renderer =
+            || ImageRenderer::new(device, surface_config.format);
+        let create_primitive_renderers = || {
+            thread_pool.join(create_rectangle_renderer, create_image_renderer)
+        };
+        let ((rectangle_renderer, image_renderer), composite_renderer) =
+            thread_pool
+                .join(create_primitive_renderers, create_composite_renderer);
+        let arc_renderer = ArcRenderer::new(device, surface_config.format);
+        let layer_compositor =
+            LayerCompositor::new(device, surface_config.format);
+        let surface_mask_renderer = SurfaceMaskRenderer::new(
+            device,
+            surface_config.format,
+            surface_config.width,
+            surface_config.height,
+        );
 
         Self {
+            device: device.clone(),
+            queue: queue.clone(),
             offscreen_texture,
             offscreen_texture_view,
             color_operations,
             rectangle_renderer,
+            arc_renderer,
+            image_renderer,
             composite_renderer,
-            is_redraw_required: true,
+            surface_mask_renderer,
+            layer_compositor,
+            layers: SlotMap::new(),
+            layer_order: Vec::new(),
+            plugins: PrimitivePlugins::new(device),
+            surface_size: [surface_config.width, surface_config.height],
+            render_scale: 1.0,
+            is_suspended: false,
         }
     }
 
-    #[inline(always)]
-    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
-        (self.offscreen_texture, self.offscreen_texture_view) =
-            create_offscreen_texture(
-                device,
-                width,
-                height,
-                self.offscreen_texture.format(),
-            );
+    fn resize(&mut self, width: u32, height: u32) {
+        self.surface_size = [width, height];
+        // Minimized; the targets are kept until the window is restored.
+        if self.is_surface_empty() {
+            return;
+        }
+        self.surface_mask_renderer.resize(width, height);
+        self.resize_targets();
+    }
 
-        self.composite_renderer
-            .update_bind_group(device, &self.offscreen_texture_view);
-        self.is_redraw_required = true;
+    fn recover(&mut self, width: u32, height: u32) {
+        self.resize(width, height);
+        for layer in self.layers.values_mut() {
+            layer.invalidate();
+        }
     }
 
-    #[must_use]
-    #[inline(always)]
-    pub fn get_mut_rectangle(
-        &mut self,
-        id: RectangleId,
-    ) -> Option<&mut Rectangle> {
-        self.is_redraw_required = true;
-        self.rectangle_renderer.get_mut(id)
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            instance_buffers: self.rectangle_renderer.buffer_bytes()
+                + self.arc_renderer.buffer_bytes()
+                + self.image_renderer.buffer_bytes(),
+            textures:         self.image_renderer.texture_bytes(),
+            layers:           self
+                .layers
+                .values()
+                .map(Layer::memory_bytes)
+                .sum(),
+            targets:          texture_bytes(&self.offscreen_texture),
+            orphans:          0,
+        }
     }
 
     #[inline(always)]
-    pub fn add_rectangle(&mut self, instance: &Rectangle) -> RectangleId {
-        self.is_redraw_required = true;
-        self.rectangle_renderer.add(instance)
+    fn is_surface_empty(&self) -> bool { self.surface_size.contains(&0) }
+
+    fn suspend(&mut self) {
+        self.is_suspended = true;
+        self.offscreen_texture.destroy();
+        for layer in self.layers.values_mut() {
+            layer.suspend();
+        }
     }
 
-    #[inline(always)]
-    pub fn remove_rectangle(&mut self, id: RectangleId) -> Option<Rectangle> {
-        self.is_redraw_required = true;
-        self.rectangle_renderer.remove(id)
+    fn resume(&mut self, width: u32, height: u32) {
+        self.is_suspended = false;
+        // The targets have to come back even if the size didn't change.
+        self.surface_size = [0, 0];
+        self.recover(width, height);
     }
 
-    pub fn render(
+    fn add_layer(&mut self, mask: Option<LayerMask>) -> LayerId {
+        let layer = Layer::new(
+            &self.device,
+            &self.layer_compositor,
+            self.offscreen_texture.format(),
+            self.offscreen_texture.width(),
+            self.offscreen_texture.height(),
+            mask,
+        );
+        let layer_id = self.layers.insert(layer);
+        self.layer_order.push(layer_id);
+
+        layer_id
+    }
+
+    fn render(
         &mut self,
-        queue: &Queue,
         surface_texture_view: &TextureView,
         command_encoder: &mut CommandEncoder,
+        is_redraw_required: bool,
+        recorder: &mut FrameRecorder,
     ) {
-        if self.is_redraw_required {
+        if is_redraw_required {
+            span!("main_pass");
             let color_operations = Operations {
                 load:  self.color_operations.load,
                 store: StoreOp::Store,
@@ -118,11 +1336,27 @@ impl Renderer {
             };
             let mut render_pass =
                 command_encoder.begin_render_pass(&render_pass_desc);
+            recorder.begin_pass(
+                "hui::common_render_pass",
+                "hui::offscreen_texture",
+                || format!("{:?}", self.color_operations.load),
+            );
 
-            self.rectangle_renderer.render(queue, &mut render_pass);
-            self.is_redraw_required = false;
+            self.rectangle_renderer.prepare(&self.queue, recorder);
+            self.arc_renderer.prepare(&self.queue, recorder);
+            self.image_renderer.prepare(&self.queue, recorder);
+            for phase in RenderPhase::ALL {
+                self.render_phase(phase, &mut render_pass, recorder);
+            }
+        }
+
+        for layer in self.layers.values_mut() {
+            if layer.is_redraw_required() {
+                render_layer(layer, &self.queue, command_encoder, recorder);
+            }
         }
 
+        span!("composite_pass", layers = self.layer_order.len());
         let color_operations = Operations {
             load:  LoadOp::Load,
             store: self.color_operations.store,
@@ -142,12 +1376,132 @@ impl Renderer {
         };
         let mut composite_render_pass =
             command_encoder.begin_render_pass(&composite_render_pass_desc);
+        recorder.begin_pass("hui::composite_render_pass", "surface", || {
+            format!("{:?}", LoadOp::<Color>::Load)
+        });
 
-        self.composite_renderer.render(&mut composite_render_pass);
+        self.composite_renderer
+            .render(&mut composite_render_pass, recorder);
+
+        for layer_id in &self.layer_order {
+            let layer = &mut self.layers[*layer_id];
+            layer.composite(
+                &self.queue,
+                &self.layer_compositor,
+                &mut composite_render_pass,
+                recorder,
+            );
+        }
+        self.surface_mask_renderer.render(
+            &self.queue,
+            &mut composite_render_pass,
+            recorder,
+        );
     }
+
+    // Plugins draw in the content phase.
+    fn render_phase(
+        &mut self,
+        phase: RenderPhase,
+        render_pass: &mut RenderPass,
+        recorder: &mut FrameRecorder,
+    ) {
+        if phase != RenderPhase::Content {
+            self.rectangle_renderer.render(phase, render_pass, recorder);
+            self.arc_renderer.render(phase, render_pass, recorder);
+            self.image_renderer.render(phase, render_pass, recorder);
+            return;
+        }
+        let queue = &self.queue;
+        let plugins = &mut self.plugins;
+        plugins.render(
+            PrimitiveStage::Background,
+            queue,
+            render_pass,
+            recorder,
+        );
+        self.rectangle_renderer.render(phase, render_pass, recorder);
+        plugins.render(
+            PrimitiveStage::AfterRectangles,
+            queue,
+            render_pass,
+            recorder,
+        );
+        self.arc_renderer.render(phase, render_pass, recorder);
+        plugins.render(
+            PrimitiveStage::AfterArcs,
+            queue,
+            render_pass,
+            recorder,
+        );
+        self.image_renderer.render(phase, render_pass, recorder);
+        plugins.render(PrimitiveStage::Overlay, queue, render_pass, recorder);
+    }
+
+    fn resize_targets(&mut self) {
+        let device = &self.device;
+        // Recreated on resume.
+        if self.is_suspended {
+            return;
+        }
+        let [width, height] =
+            scaled_size(self.surface_size, self.render_scale);
+        (self.offscreen_texture, self.offscreen_texture_view) =
+            create_offscreen_texture(
+                device,
+                width,
+                height,
+                self.offscreen_texture.format(),
+            );
+
+        self.composite_renderer
+            .update_bind_group(device, &self.offscreen_texture_view);
+        for layer in self.layers.values_mut() {
+            layer.resize(device, &self.layer_compositor, width, height);
+        }
+        self.plugins.resize(device, [width, height]);
+    }
+}
+
+fn render_layer(
+    layer: &mut Layer,
+    queue: &Queue,
+    command_encoder: &mut CommandEncoder,
+    recorder: &mut FrameRecorder,
+) {
+    span!("layer_pass");
+    let color_operations = Operations {
+        load:  LoadOp::Clear(Color::TRANSPARENT),
+        store: StoreOp::Store,
+    };
+    let color_attachment = RenderPassColorAttachment {
+        view:           layer.texture_view(),
+        depth_slice:    None,
+        resolve_target: None,
+        ops:            color_operations,
+    };
+    let render_pass_desc = RenderPassDescriptor {
+        label:                    Some("hui::layer_render_pass"),
+        color_attachments:        &[Some(color_attachment)],
+        depth_stencil_attachment: None,
+        occlusion_query_set:      None,
+        timestamp_writes:         None,
+    };
+    let mut render_pass = command_encoder.begin_render_pass(&render_pass_desc);
+    recorder.begin_pass(
+        "hui::layer_render_pass",
+        "hui::layer_texture",
+        || format!("{:?}", color_operations.load),
+    );
+
+    layer.render(queue, &mut render_pass, recorder);
+}
+
+fn scaled_size(size: [u32; 2], render_scale: f32) -> [u32; 2] {
+    size.map(|value| ((value as f32 * render_scale).ceil() as u32).max(1))
 }
 
-fn create_offscreen_texture(
+pub(super) fn create_offscreen_texture(
     device: &Device,
     width: u32,
     height: u32,
@@ -0,0 +1,4 @@
+mod renderer;
+mod shading;
+
+pub use renderer::SoftwareRenderer;
@@ -0,0 +1,865 @@
+use glam::{Vec2, Vec4, Vec4Swizzles};
+use tiny_skia::{
+    ColorU8, FillRule, Mask, Path, PathBuilder, Pixmap, Stroke, StrokeDash,
+    Transform,
+};
+
+use super::shading::{
+    PixelQuad, blend, border_position, correct_coverage, dash_coverage,
+    linear_to_srgb, pixel_range, sd_arc, sd_rounded_rect_4, smoothstep,
+    srgb_to_linear,
+};
+use crate::{
+    core::{
+        ArcSegment, Image, PathCommand, Rectangle, TextureId, VectorPath,
+        VectorScene,
+        capture::FrameRecorder,
+        cpu_scene::{CpuPrimitive, CpuScene, CpuTexture},
+        pixel_transform,
+    },
+    instrument::span,
+};
+
+// Texels are decoded from sRGB once, so that sampling and blending happen
+// in linear space like on an sRGB render target.
+struct SoftwareTexture {
+    size:   [u32; 2],
+    texels: Vec<Vec4>,
+}
+
+impl CpuTexture for SoftwareTexture {
+    fn size(&self) -> [u32; 2] { self.size }
+
+    fn to_rgba(&self) -> Vec<u8> {
+        self.texels
+            .iter()
+            .flat_map(|texel| {
+                let [red, green, blue] =
+                    texel.xyz().to_array().map(linear_to_srgb);
+                [red, green, blue, (texel.w * 255.0).round() as u8]
+            })
+            .collect()
+    }
+}
+
+// Rasterizes the same instances as the GPU renderers on the CPU, one
+// pixel at a time, by evaluating the fragment shaders' distance
+// functions.
+pub struct SoftwareRenderer {
+    pixmap:      Pixmap,
+    // Linear, premultiplied colors the instances are blended into before
+    // they are encoded into the pixmap.
+    frame:       Vec<Vec4>,
+    clear_color: [f32; 4],
+    scene:       CpuScene<SoftwareTexture>,
+}
+
+impl SoftwareRenderer {
+    #[must_use]
+    pub fn new(size: [u32; 2], clear_color: [f32; 4]) -> Self {
+        let [width, height] = size.map(|value| value.max(1));
+        Self {
+            pixmap: create_pixmap(width, height),
+            frame: Vec::new(),
+            clear_color,
+            scene: CpuScene::new(),
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.pixmap = create_pixmap(width.max(1), height.max(1));
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn size(&self) -> [u32; 2] {
+        [self.pixmap.width(), self.pixmap.height()]
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn pixmap(&self) -> &Pixmap { &self.pixmap }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn scene(&self) -> &CpuScene<impl CpuTexture> {
+        &self.scene
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn scene_mut(
+        &mut self,
+    ) -> &mut CpuScene<impl CpuTexture> {
+        &mut self.scene
+    }
+
+    // `rgba` is sRGB encoded with straight alpha, as for the GPU textures.
+    pub fn add_texture(&mut self, size: [u32; 2], rgba: &[u8]) -> TextureId {
+        let texels = rgba.chunks_exact(4).map(decode_texel).collect();
+        self.scene.add_texture(SoftwareTexture { size, texels })
+    }
+
+    // Returns false for an unknown texture.
+    pub fn write_texture(
+        &mut self,
+        id: TextureId,
+        origin: [u32; 2],
+        size: [u32; 2],
+        rgba: &[u8],
+    ) -> bool {
+        let Some(texture) = self.scene.get_mut_texture(id) else {
+            return false;
+        };
+        let [width, height] = texture.size;
+        let rows = rgba.chunks_exact(4 * size[0] as usize);
+        for (y, row) in (origin[1]..height).zip(rows) {
+            let texels = row.chunks_exact(4).map(decode_texel);
+            for (x, texel) in (origin[0]..width).zip(texels) {
+                texture.texels[(y * width + x) as usize] = texel;
+            }
+        }
+        true
+    }
+
+    #[must_use]
+    pub fn export_scene(&self) -> VectorScene {
+        self.scene.export(self.size(), self.clear_color)
+    }
+
+    // What `render` is about to draw, for a frame capture.
+    pub(crate) fn record(&self, recorder: &mut FrameRecorder) {
+        self.scene
+            .record(recorder, "hui::software_pass", self.clear_color);
+    }
+
+    pub fn render(&mut self) -> &Pixmap {
+        span!("software_pass");
+        let size = self.size();
+        let clear_color = Vec4::from(self.clear_color);
+        let clear_color =
+            (clear_color.xyz() * clear_color.w).extend(clear_color.w);
+        self.frame.clear();
+        self.frame.resize((size[0] * size[1]) as usize, clear_color);
+
+        let frame = &mut self.frame;
+        self.scene.draw(|primitive| match primitive {
+            CpuPrimitive::Rectangle(rect) => {
+                draw_rectangle(frame, size, &rect)
+            }
+            CpuPrimitive::Arc(arc) => draw_arc(frame, size, arc),
+            CpuPrimitive::Path(path) => draw_path(frame, size, path),
+            CpuPrimitive::Image(texture, image) => {
+                draw_image(frame, size, texture, image);
+            }
+        });
+
+        let pixels = self.pixmap.pixels_mut();
+        for (pixel, color) in pixels.iter_mut().zip(&self.frame) {
+            *pixel = encode_pixel(*color);
+        }
+        &self.pixmap
+    }
+}
+
+fn draw_rectangle(frame: &mut [Vec4], size: [u32; 2], rect: &Rectangle) {
+    let [half_x, half_y, offset_x, offset_y] = rect.rect_and_shadow;
+    let [border_size, shadow_spread, shadow_blur, outline_size] = rect.sizes;
+    let half_size = Vec2::new(half_x, half_y);
+    let shadow_offset = Vec2::new(offset_x, offset_y);
+
+    let shadow_extent =
+        shadow_blur + shadow_spread + offset_x.abs().max(offset_y.abs());
+    let total_extent = shadow_extent.max(outline_size);
+    let Some(quad) = PixelQuad::new(
+        &rect.mvp,
+        half_size,
+        half_size + total_extent,
+        size,
+        rect.clip_rect,
+    ) else {
+        return;
+    };
+
+    let radii = Vec4::from(rect.corner_radii);
+    let fill_color = Vec4::from(rect.fill_color);
+    let border_color = Vec4::from(rect.border_color);
+    let shadow_color = Vec4::from(rect.shadow_color);
+    let outline_color = Vec4::from(rect.outline_color);
+    let (step_x, step_y) = quad.steps();
+
+    for y in quad.rows.clone() {
+        for x in quad.columns.clone() {
+            let p = quad.local_position(x, y);
+            if !quad.contains(p) {
+                continue;
+            }
+
+            let shadow_distance = sd_rounded_rect_4(
+                p - shadow_offset,
+                half_size + shadow_spread,
+                radii + shadow_spread,
+            );
+            let shadow_blur = shadow_blur.max(0.001);
+            let shadow_alpha = shadow_color.w
+                * (1.0
+                    - smoothstep(-shadow_blur, shadow_blur, shadow_distance));
+
+            let rect_distance = sd_rounded_rect_4(p, half_size, radii);
+            let inside_rect = (rect_distance <= 0.0) as u8 as f32;
+            let shadow_a = shadow_alpha * (1.0 - inside_rect);
+
+            let mut color = shadow_color.xyz().extend(shadow_a);
+
+            let outline_distance = sd_rounded_rect_4(
+                p,
+                half_size + outline_size,
+                radii + outline_size,
+            );
+            let aa_width = fwidth(
+                rect_distance,
+                |p| sd_rounded_rect_4(p, half_size, radii),
+                p,
+                step_x,
+                step_y,
+            ) * 1.4;
+
+            let outline_alpha = (1.0
+                - smoothstep(0.0, aa_width, outline_distance))
+                * smoothstep(-aa_width, 0.0, rect_distance);
+            let o_a = outline_color.w * outline_alpha;
+            color = color
+                .xyz()
+                .lerp(outline_color.xyz(), o_a)
+                .extend(color.w + o_a * (1.0 - color.w));
+
+            let alpha = 1.0 - smoothstep(0.0, aa_width, rect_distance);
+
+            let inner_radii = (radii - border_size).max(Vec4::ZERO);
+            let inner_distance =
+                sd_rounded_rect_4(p, half_size - border_size, inner_radii);
+
+            let dash_position = border_position(
+                p,
+                half_size - border_size * 0.5,
+                (radii - border_size * 0.5).max(Vec4::ZERO),
+            );
+            let border_alpha = (1.0
+                - smoothstep(0.0, aa_width, rect_distance))
+                * smoothstep(-aa_width, 0.0, inner_distance)
+                * dash_coverage(dash_position, rect.dash);
+            let fill_alpha = 1.0 - smoothstep(0.0, aa_width, inner_distance);
+
+            let rect_alpha = fill_color.w * fill_alpha;
+            let b_a = border_color.w * border_alpha;
+            let rect_color = fill_color.xyz().lerp(border_color.xyz(), b_a);
+            let rect_alpha = (rect_alpha + b_a * (1.0 - rect_alpha)) * alpha;
+
+            color = color
+                .xyz()
+                .lerp(rect_color, rect_alpha)
+                .extend(color.w + rect_alpha * (1.0 - color.w));
+
+            blend(&mut frame[(y * size[0] + x) as usize], color);
+        }
+    }
+}
+
+fn draw_arc(frame: &mut [Vec4], size: [u32; 2], arc: &ArcSegment) {
+    let [radius, thickness, start, sweep] = arc.geometry;
+    let [half_size, round_caps, ..] = arc.size_and_caps;
+    let half_size = Vec2::splat(half_size);
+    let Some(quad) =
+        PixelQuad::new(&arc.mvp, half_size, half_size, size, arc.clip_rect)
+    else {
+        return;
+    };
+
+    let color = Vec4::from(arc.color);
+    let distance =
+        |p| sd_arc(p, radius, thickness * 0.5, start, sweep, round_caps > 0.5);
+    let (step_x, step_y) = quad.steps();
+
+    for y in quad.rows.clone() {
+        for x in quad.columns.clone() {
+            let p = quad.local_position(x, y);
+            if !quad.contains(p) {
+                continue;
+            }
+            let d = distance(p);
+            let aa_width = fwidth(d, distance, p, step_x, step_y) * 1.4;
+            let alpha = 1.0 - smoothstep(0.0, aa_width, d);
+
+            let pixel = &mut frame[(y * size[0] + x) as usize];
+            blend(pixel, color.xyz().extend(color.w * alpha));
+        }
+    }
+}
+
+fn draw_image(
+    frame: &mut [Vec4],
+    size: [u32; 2],
+    texture: &SoftwareTexture,
+    image: &Image,
+) {
+    let [tile_scale_x, tile_scale_y, tile_offset_x, tile_offset_y] =
+        image.uv_transform;
+    let [repeat_x, repeat_y, half_x, half_y] = image.repeat_and_size;
+    let half_size = Vec2::new(half_x, half_y);
+    let Some(quad) = PixelQuad::new(
+        &image.mvp,
+        half_size,
+        half_size,
+        size,
+        image.clip_rect,
+    ) else {
+        return;
+    };
+
+    let tile_scale = Vec2::new(tile_scale_x, tile_scale_y);
+    let tile_offset = Vec2::new(tile_offset_x, tile_offset_y);
+    let repeat = [repeat_x > 0.5, repeat_y > 0.5];
+    let radii = Vec4::from(image.corner_radii);
+    let tint_color = Vec4::from(image.tint_color);
+    let [cutout_x, cutout_y, cutout_radius, _] = image.cutout;
+    let cutout_center = Vec2::new(cutout_x, cutout_y);
+    let [gamma, contrast, ..] = image.coverage;
+
+    let distance = |p: Vec2| {
+        let distance = sd_rounded_rect_4(p, half_size, radii);
+        // A circle punched out of the image, e.g. to make room for a
+        // badge that is drawn underneath it.
+        if cutout_radius > 0.0 {
+            return distance.max(cutout_radius - (p - cutout_center).length());
+        }
+        distance
+    };
+    let (step_x, step_y) = quad.steps();
+
+    for y in quad.rows.clone() {
+        for x in quad.columns.clone() {
+            let p = quad.local_position(x, y);
+            if !quad.contains(p) {
+                continue;
+            }
+            let uv = (p / half_size * 0.5 + 0.5) * tile_scale + tile_offset;
+            let is_outside = uv.cmplt(Vec2::ZERO) | uv.cmpgt(Vec2::ONE);
+            if (is_outside.test(0) && !repeat[0])
+                || (is_outside.test(1) && !repeat[1])
+            {
+                continue;
+            }
+
+            let mut color = sample(texture, uv, repeat);
+            if gamma > 0.0 {
+                color.w = correct_coverage(color.w, gamma, contrast);
+            }
+
+            let d = distance(p);
+            let aa_width = fwidth(d, distance, p, step_x, step_y) * 1.4;
+            let alpha = 1.0 - smoothstep(0.0, aa_width, d);
+
+            let tinted = color * tint_color;
+            let pixel = &mut frame[(y * size[0] + x) as usize];
+            blend(pixel, tinted.xyz().extend(tinted.w * alpha));
+        }
+    }
+}
+
+// Paths are transformed into pixels before they are stroked, so that the
+// stroke width stays in physical pixels.
+fn draw_path(frame: &mut [Vec4], size: [u32; 2], path: &VectorPath) {
+    let mut builder = PathBuilder::new();
+    for command in &path.commands {
+        match *command {
+            PathCommand::MoveTo([x, y]) => builder.move_to(x, y),
+            PathCommand::LineTo([x, y]) => builder.line_to(x, y),
+            PathCommand::QuadTo([x_1, y_1], [x, y]) => {
+                builder.quad_to(x_1, y_1, x, y);
+            }
+            PathCommand::CubicTo([x_1, y_1], [x_2, y_2], [x, y]) => {
+                builder.cubic_to(x_1, y_1, x_2, y_2, x, y);
+            }
+            PathCommand::Close => builder.close(),
+        }
+    }
+    let [a, b, c, d, e, f] = pixel_transform(&path.mvp, size);
+    let Some(pixels) = builder.finish().and_then(|shape| {
+        shape.transform(Transform::from_row(a, b, c, d, e, f))
+    }) else {
+        return;
+    };
+
+    let [.., fill_alpha] = path.fill_color;
+    if fill_alpha > 0.0 {
+        let fill_rule = match path.is_even_odd {
+            true => FillRule::EvenOdd,
+            false => FillRule::Winding,
+        };
+        let color = Vec4::from(path.fill_color);
+        fill_coverage(frame, size, &pixels, fill_rule, path.clip_rect, color);
+    }
+    let [.., stroke_alpha] = path.stroke_color;
+    if path.stroke_width > 0.0 && stroke_alpha > 0.0 {
+        let dash = path
+            .is_dashed()
+            .then(|| {
+                StrokeDash::new(path.dash_pattern.to_vec(), path.dash_offset)
+            })
+            .flatten();
+        let stroke =
+            Stroke { width: path.stroke_width, dash, ..Stroke::default() };
+        if let Some(outline) = pixels.stroke(&stroke, 1.0) {
+            let color = Vec4::from(path.stroke_color);
+            let clip_rect = path.clip_rect;
+            fill_coverage(
+                frame,
+                size,
+                &outline,
+                FillRule::Winding,
+                clip_rect,
+                color,
+            );
+        }
+    }
+}
+
+// Rasterizes the anti-aliased coverage of a path in pixels into a mask
+// over its bounds and blends the color through it.
+fn fill_coverage(
+    frame: &mut [Vec4],
+    size: [u32; 2],
+    path: &Path,
+    fill_rule: FillRule,
+    clip_rect: [f32; 4],
+    color: Vec4,
+) {
+    let bounds = path.bounds();
+    let [clip_x, clip_y, clip_width, clip_height] = clip_rect;
+    let clip_columns = pixel_range(clip_x, clip_x + clip_width, size[0]);
+    let clip_rows = pixel_range(clip_y, clip_y + clip_height, size[1]);
+    let left = (bounds.left().floor().max(0.0) as u32).max(clip_columns.start);
+    let top = (bounds.top().floor().max(0.0) as u32).max(clip_rows.start);
+    let right = (bounds.right().ceil().max(0.0) as u32).min(clip_columns.end);
+    let bottom = (bounds.bottom().ceil().max(0.0) as u32).min(clip_rows.end);
+    if right <= left || bottom <= top {
+        return;
+    }
+
+    let Some(mut mask) = Mask::new(right - left, bottom - top) else {
+        return;
+    };
+    let transform = Transform::from_translate(-(left as f32), -(top as f32));
+    mask.fill_path(path, fill_rule, true, transform);
+
+    let mask_width = right - left;
+    for (i, coverage) in mask.data().iter().enumerate() {
+        if *coverage == 0 {
+            continue;
+        }
+        let x = left + i as u32 % mask_width;
+        let y = top + i as u32 / mask_width;
+        let alpha = color.w * *coverage as f32 / 255.0;
+        blend(
+            &mut frame[(y * size[0] + x) as usize],
+            color.xyz().extend(alpha),
+        );
+    }
+}
+
+// Forward differences towards the next pixel in x and y, standing in for
+// the shaders' `fwidth`.
+fn fwidth(
+    value: f32,
+    distance: impl Fn(Vec2) -> f32,
+    p: Vec2,
+    step_x: Vec2,
+    step_y: Vec2,
+) -> f32 {
+    (distance(p + step_x) - value).abs() + (distance(p + step_y) - value).abs()
+}
+
+// Bilinear filtering of the top level, repeating or clamping to the edge
+// per axis like the GPU samplers.
+fn sample(texture: &SoftwareTexture, uv: Vec2, repeat: [bool; 2]) -> Vec4 {
+    let [width, height] = texture.size;
+    if width == 0 || height == 0 {
+        return Vec4::ZERO;
+    }
+    let texel_position =
+        uv * Vec2::new(width as f32, height as f32) - Vec2::splat(0.5);
+    let base = texel_position.floor();
+    let weight = texel_position - base;
+
+    let texel = |x: f32, y: f32| {
+        let x = wrap(x as i64, width, repeat[0]);
+        let y = wrap(y as i64, height, repeat[1]);
+        texture.texels[(y * width + x) as usize]
+    };
+    let top =
+        texel(base.x, base.y).lerp(texel(base.x + 1.0, base.y), weight.x);
+    let bottom = texel(base.x, base.y + 1.0)
+        .lerp(texel(base.x + 1.0, base.y + 1.0), weight.x);
+    top.lerp(bottom, weight.y)
+}
+
+const fn wrap(value: i64, size: u32, repeats: bool) -> u32 {
+    let size = size as i64;
+    if repeats {
+        value.rem_euclid(size) as u32
+    } else if value < 0 {
+        0
+    } else if value >= size {
+        (size - 1) as u32
+    } else {
+        value as u32
+    }
+}
+
+fn decode_texel(rgba: &[u8]) -> Vec4 {
+    Vec4::new(
+        srgb_to_linear(rgba[0]),
+        srgb_to_linear(rgba[1]),
+        srgb_to_linear(rgba[2]),
+        rgba[3] as f32 / 255.0,
+    )
+}
+
+fn encode_pixel(color: Vec4) -> tiny_skia::PremultipliedColorU8 {
+    let alpha = color.w.clamp(0.0, 1.0);
+    let straight = if alpha > 0.0 { color.xyz() / alpha } else { color.xyz() };
+    ColorU8::from_rgba(
+        linear_to_srgb(straight.x),
+        linear_to_srgb(straight.y),
+        linear_to_srgb(straight.z),
+        (alpha * 255.0).round() as u8,
+    )
+    .premultiply()
+}
+
+// Sizes too large to address fall back to a single pixel; zero sizes are
+// clamped away by the callers.
+fn create_pixmap(width: u32, height: u32) -> Pixmap {
+    Pixmap::new(width, height)
+        .unwrap_or_else(|| Pixmap::new(1, 1).expect("a 1x1 pixmap is valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+    use rstest::rstest;
+
+    use super::SoftwareRenderer;
+    use crate::{
+        components::{BlockStyle, Bounds, build_model, build_rectangle},
+        core::{
+            ArcSegment, Backend, CapturedCommand, Image, PathCommand,
+            RenderPhase, Renderer, VectorPath,
+        },
+    };
+
+    const VIEWPORT: [u32; 2] = [64, 32];
+
+    fn view_projection() -> Mat4 {
+        Mat4::orthographic_rh(
+            0.0,
+            VIEWPORT[0] as f32,
+            VIEWPORT[1] as f32,
+            0.0,
+            -1.0,
+            1.0,
+        )
+    }
+
+    fn pixel(renderer: &mut SoftwareRenderer, x: u32, y: u32) -> [u8; 4] {
+        let color = renderer.render().pixel(x, y).unwrap().demultiply();
+        [color.red(), color.green(), color.blue(), color.alpha()]
+    }
+
+    #[rstest]
+    fn test_rectangle_is_filled_and_clipped() {
+        let mut renderer = SoftwareRenderer::new(VIEWPORT, [0.0; 4]);
+        let bounds = Bounds {
+            position:  [8.0, 8.0],
+            size:      [32.0, 16.0],
+            clip_rect: [0.0, 0.0, 24.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([1.0, 0.0, 0.0, 1.0])
+            .build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer
+            .scene_mut()
+            .add_rectangle(&rect, RenderPhase::Content);
+
+        assert_eq!(pixel(&mut renderer, 16, 16), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut renderer, 30, 16), [0, 0, 0, 0]);
+        assert_eq!(pixel(&mut renderer, 4, 4), [0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    #[case(0.0, [255, 0, 0, 255], [0, 0, 0, 0])]
+    #[case(4.0, [0, 0, 0, 0], [255, 0, 0, 255])]
+    fn test_dashed_border_follows_offset(
+        #[case] dash_offset: f32,
+        #[case] first: [u8; 4],
+        #[case] second: [u8; 4],
+    ) {
+        let mut renderer = SoftwareRenderer::new(VIEWPORT, [0.0; 4]);
+        let bounds = Bounds {
+            position:  [8.0, 8.0],
+            size:      [32.0, 16.0],
+            clip_rect: [0.0, 0.0, 64.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([0.0; 4])
+            .border_color([1.0, 0.0, 0.0, 1.0])
+            .border_size(2.0)
+            .outline_color([0.0; 4])
+            .dash_pattern([4.0, 4.0])
+            .dash_offset(dash_offset)
+            .build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer
+            .scene_mut()
+            .add_rectangle(&rect, RenderPhase::Content);
+
+        // The dashes start at the top left corner of the border's center
+        // line, a pixel in from the rectangle's.
+        assert_eq!(pixel(&mut renderer, 9, 8), first);
+        assert_eq!(pixel(&mut renderer, 14, 8), second);
+    }
+
+    #[rstest]
+    fn test_removed_rectangle_is_not_drawn() {
+        let mut renderer =
+            SoftwareRenderer::new(VIEWPORT, [0.0, 0.0, 1.0, 1.0]);
+        let bounds = Bounds {
+            position:  [0.0, 0.0],
+            size:      [64.0, 32.0],
+            clip_rect: [0.0, 0.0, 64.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([1.0, 1.0, 1.0, 1.0])
+            .build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        let rect_id = renderer
+            .scene_mut()
+            .add_rectangle(&rect, RenderPhase::Content);
+        assert_eq!(pixel(&mut renderer, 32, 16), [255, 255, 255, 255]);
+
+        renderer.scene_mut().remove_rectangle(rect_id);
+        assert_eq!(pixel(&mut renderer, 32, 16), [0, 0, 255, 255]);
+    }
+
+    #[rstest]
+    fn test_arc_covers_its_sweep_only() {
+        let mut renderer = SoftwareRenderer::new(VIEWPORT, [0.0; 4]);
+        let model = Mat4::from_scale_rotation_translation(
+            [12.0, 12.0, 1.0].into(),
+            Default::default(),
+            [32.0, 16.0, 0.0].into(),
+        );
+        let arc = ArcSegment::builder()
+            .mvp((view_projection() * model).to_cols_array_2d())
+            .color([0.0, 1.0, 0.0, 1.0])
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .geometry([10.0, 4.0, 0.0, std::f32::consts::PI])
+            .size_and_caps([12.0, 0.0, 0.0, 0.0])
+            .build();
+        renderer.scene_mut().add_arc(&arc, RenderPhase::Content);
+
+        // Clockwise from the positive x axis in screen space, so the half
+        // below the center is drawn.
+        assert_eq!(pixel(&mut renderer, 32, 26), [0, 255, 0, 255]);
+        assert_eq!(pixel(&mut renderer, 32, 5), [0, 0, 0, 0]);
+        assert_eq!(pixel(&mut renderer, 32, 16), [0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    #[case(false, [0, 0, 255, 255])]
+    #[case(true, [0, 0, 0, 0])]
+    fn test_path_fill_rule(
+        #[case] is_even_odd: bool,
+        #[case] center: [u8; 4],
+    ) {
+        let mut renderer = SoftwareRenderer::new(VIEWPORT, [0.0; 4]);
+        // Two squares wound the same way, one inside the other.
+        let square = |left: f32, right: f32| {
+            [
+                PathCommand::MoveTo([left, left]),
+                PathCommand::LineTo([right, left]),
+                PathCommand::LineTo([right, right]),
+                PathCommand::LineTo([left, right]),
+                PathCommand::Close,
+            ]
+        };
+        let path = VectorPath::builder()
+            .mvp(view_projection().to_cols_array_2d())
+            .commands([square(4.0, 28.0), square(12.0, 20.0)].concat())
+            .fill_color([0.0, 0.0, 1.0, 1.0])
+            .is_even_odd(is_even_odd)
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .build();
+        let path_id =
+            renderer.scene_mut().add_path(&path, RenderPhase::Content);
+
+        assert_eq!(pixel(&mut renderer, 8, 8), [0, 0, 255, 255]);
+        assert_eq!(pixel(&mut renderer, 16, 16), center);
+        assert_eq!(pixel(&mut renderer, 40, 16), [0, 0, 0, 0]);
+
+        renderer.scene_mut().remove_path(path_id);
+        assert_eq!(pixel(&mut renderer, 8, 8), [0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    fn test_image_samples_texture() {
+        let mut renderer = SoftwareRenderer::new(VIEWPORT, [0.0; 4]);
+        let texture_id = renderer.add_texture([1, 1], &[0, 128, 255, 255]);
+        let (model, half_size) = build_model([16.0, 16.0], [0.0, 0.0], 1.0);
+        let image = Image::builder()
+            .mvp((view_projection() * model).to_cols_array_2d())
+            .tint_color([1.0; 4])
+            .corner_radii([0.0; 4])
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .uv_transform([1.0, 1.0, 0.0, 0.0])
+            .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+            .cutout([0.0; 4])
+            .build();
+        renderer
+            .scene_mut()
+            .add_image(texture_id, &image, RenderPhase::Content)
+            .unwrap();
+
+        assert_eq!(pixel(&mut renderer, 8, 8), [0, 128, 255, 255]);
+        assert_eq!(pixel(&mut renderer, 24, 8), [0, 0, 0, 0]);
+
+        assert!(renderer.write_texture(texture_id, [0, 0], [1, 1], &[255; 4]));
+        assert_eq!(pixel(&mut renderer, 8, 8), [255, 255, 255, 255]);
+    }
+
+    #[rstest]
+    fn test_renderer_with_software_backend() {
+        let mut renderer = Renderer::with_backend(Backend::Software {
+            size:        VIEWPORT,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+        });
+        assert_eq!(renderer.render_size(), VIEWPORT);
+
+        let bounds = Bounds {
+            position:  [0.0, 0.0],
+            size:      [32.0, 32.0],
+            clip_rect: [0.0, 0.0, 64.0, 32.0],
+        };
+        let style = BlockStyle::builder().fill_color([1.0; 4]).build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer.add_rectangle(&rect);
+
+        let pixmap = renderer.render_software().unwrap();
+        let inside = pixmap.pixel(16, 16).unwrap();
+        let outside = pixmap.pixel(48, 16).unwrap();
+        assert_eq!([inside.red(), inside.alpha()], [255, 255]);
+        assert_eq!([outside.red(), outside.alpha()], [0, 255]);
+    }
+
+    #[rstest]
+    fn test_push_clip() {
+        let mut renderer = Renderer::with_backend(Backend::Software {
+            size:        VIEWPORT,
+            clear_color: [0.0; 4],
+        });
+        let style = BlockStyle::builder().fill_color([1.0; 4]).build();
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [32.0, 32.0]);
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+
+        renderer.push_clip([0.0, 0.0, 16.0, 32.0]);
+        renderer.push_clip([8.0, 0.0, 64.0, 16.0]);
+        let clipped = renderer.add_rectangle(&rect);
+        assert_eq!(renderer.pop_clip(), Some([8.0, 0.0, 8.0, 16.0]));
+        let _ = renderer.pop_clip();
+        let unclipped = renderer.add_rectangle(&rect);
+
+        let clip_rect = |renderer: &mut Renderer, id| {
+            renderer.get_mut_rectangle(id).map(|rect| rect.clip_rect)
+        };
+        assert_eq!(
+            clip_rect(&mut renderer, clipped),
+            Some([8.0, 0.0, 8.0, 16.0])
+        );
+        assert_eq!(clip_rect(&mut renderer, unclipped), Some(rect.clip_rect));
+    }
+
+    #[rstest]
+    fn test_render_phase() {
+        let mut renderer = Renderer::with_backend(Backend::Software {
+            size:        VIEWPORT,
+            clear_color: [0.0; 4],
+        });
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [32.0, 32.0]);
+        let red = BlockStyle::builder()
+            .fill_color([1.0, 0.0, 0.0, 1.0])
+            .build();
+        let blue = BlockStyle::builder()
+            .fill_color([0.0, 0.0, 1.0, 1.0])
+            .build();
+        let red = build_rectangle(&view_projection(), 1.0, &bounds, &red);
+        let blue = build_rectangle(&view_projection(), 1.0, &bounds, &blue);
+
+        renderer.push_clip([0.0, 0.0, 8.0, 8.0]);
+        let previous = renderer.set_render_phase(RenderPhase::Overlay);
+        assert_eq!(previous, RenderPhase::Content);
+        assert_eq!(renderer.clip_rect(), None);
+        renderer.add_rectangle(&red);
+        renderer.set_render_phase(previous);
+        let blue = renderer.add_rectangle(&blue);
+        assert!(renderer.set_rectangle_z_index(blue, 100));
+
+        let pixmap = renderer.render_software().unwrap();
+        let pixel = pixmap.pixel(16, 16).unwrap();
+        assert_eq!([pixel.red(), pixel.blue()], [255, 0]);
+    }
+
+    #[rstest]
+    fn test_capture_next_frame() {
+        let mut renderer = Renderer::with_backend(Backend::Software {
+            size:        VIEWPORT,
+            clear_color: [0.0; 4],
+        });
+        let style = BlockStyle::builder().fill_color([1.0; 4]).build();
+        let bounds = Bounds::without_clip_rect([0.0, 0.0], [8.0, 8.0]);
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer.add_rectangle(&rect);
+        renderer.add_rectangle(&rect);
+
+        renderer.render_software();
+        assert_eq!(renderer.take_frame_capture(), None);
+
+        renderer.add_rectangle(&rect);
+        renderer.capture_next_frame();
+        renderer.render_software();
+        let capture = renderer.take_frame_capture().unwrap();
+        assert_eq!(capture.backend, "software");
+        assert_eq!(capture.passes.len(), 1);
+        assert_eq!(
+            capture.passes[0].commands[..2],
+            [
+                CapturedCommand::Draw {
+                    pipeline:  "rectangle",
+                    vertices:  0..0,
+                    instances: 0..3,
+                },
+                CapturedCommand::Skipped {
+                    pipeline: "arc",
+                    reason:   "no instances",
+                },
+            ]
+        );
+
+        // Nothing changed, so nothing is drawn.
+        renderer.capture_next_frame();
+        renderer.render_software();
+        let capture = renderer.take_frame_capture().unwrap();
+        assert!(!capture.is_redraw_required);
+        assert!(capture.passes.is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    f32::consts::{FRAC_PI_2, TAU},
+    ops::Range,
+};
+
+use glam::{Mat2, Vec2, Vec4, Vec4Swizzles};
+
+use crate::core::pixel_transform;
+
+// Maps the pixels covered by an instanced quad back to its local
+// position, in the same units the shaders use for `local_position`.
+pub(super) struct PixelQuad {
+    origin:             Vec2,
+    step_x:             Vec2,
+    step_y:             Vec2,
+    extent:             Vec2,
+    pub(super) columns: Range<u32>,
+    pub(super) rows:    Range<u32>,
+}
+
+impl PixelQuad {
+    // `half_size` scales the unit quad into local units and `extent` is
+    // the half size of the area that is actually drawn, e.g. including a
+    // rectangle's shadow. Quads whose projection is degenerate or that
+    // fall outside the clip rect cover no pixels.
+    #[must_use]
+    pub(super) fn new(
+        mvp: &[[f32; 4]; 4],
+        half_size: Vec2,
+        extent: Vec2,
+        target_size: [u32; 2],
+        clip_rect: [f32; 4],
+    ) -> Option<Self> {
+        if half_size.x <= 0.0 || half_size.y <= 0.0 {
+            return None;
+        }
+        let [a, b, c, d, e, f] = pixel_transform(mvp, target_size);
+        let to_pixel = Mat2::from_cols(Vec2::new(a, b), Vec2::new(c, d));
+        let translation = Vec2::new(e, f);
+        if to_pixel.determinant().abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let corners = [
+            Vec2::new(-extent.x, -extent.y),
+            Vec2::new(extent.x, -extent.y),
+            Vec2::new(-extent.x, extent.y),
+            Vec2::new(extent.x, extent.y),
+        ]
+        .map(|corner| to_pixel * (corner / half_size) + translation);
+        let min = corners.into_iter().reduce(Vec2::min)?;
+        let max = corners.into_iter().reduce(Vec2::max)?;
+
+        // Pixel centers have to lie within the clip rect, as in the
+        // fragment shaders.
+        let [clip_x, clip_y, clip_width, clip_height] = clip_rect;
+        let columns = pixel_range(
+            min.x.max(clip_x),
+            max.x.min(clip_x + clip_width),
+            target_size[0],
+        );
+        let rows = pixel_range(
+            min.y.max(clip_y),
+            max.y.min(clip_y + clip_height),
+            target_size[1],
+        );
+
+        let inverse = to_pixel.inverse();
+        let step_x = inverse.x_axis * half_size;
+        let step_y = inverse.y_axis * half_size;
+        let origin = (inverse * (Vec2::splat(0.5) - translation)) * half_size;
+
+        Some(Self { origin, step_x, step_y, extent, columns, rows })
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(super) fn local_position(&self, x: u32, y: u32) -> Vec2 {
+        self.origin + self.step_x * x as f32 + self.step_y * y as f32
+    }
+
+    // How far the local position moves between neighbouring pixels, for
+    // the `fwidth` based anti-aliasing.
+    #[must_use]
+    #[inline(always)]
+    pub(super) const fn steps(&self) -> (Vec2, Vec2) {
+        (self.step_x, self.step_y)
+    }
+
+    // Rotated quads don't fill their bounding box.
+    #[must_use]
+    #[inline(always)]
+    pub(super) fn contains(&self, local_position: Vec2) -> bool {
+        let tolerance = 1e-3;
+        local_position.x.abs() <= self.extent.x + tolerance
+            && local_position.y.abs() <= self.extent.y + tolerance
+    }
+}
+
+// The pixels whose centers lie between `start` and `end`.
+pub(super) fn pixel_range(start: f32, end: f32, limit: u32) -> Range<u32> {
+    let start = (start - 0.5).ceil().max(0.0);
+    let end = ((end - 0.5).floor() + 1.0).min(limit as f32);
+    if end <= start {
+        return 0..0;
+    }
+    start as u32..end as u32
+}
+
+// The distance functions and helpers below are ports of the shader
+// functions of the same names.
+
+#[must_use]
+pub(super) fn sd_rounded_rect_4(p: Vec2, half_size: Vec2, radii: Vec4) -> f32 {
+    let r = if p.x > 0.0 { radii.yz() } else { radii.xw() };
+    let r = if p.y > 0.0 { r.y } else { r.x };
+
+    let q = p.abs() - half_size + r;
+    q.x.max(q.y).min(0.0) + q.max(Vec2::ZERO).length() - r
+}
+
+#[must_use]
+pub(super) fn sd_arc(
+    p: Vec2,
+    radius: f32,
+    half_thickness: f32,
+    start: f32,
+    sweep: f32,
+    round_caps: bool,
+) -> f32 {
+    let ring = (p.length() - radius).abs() - half_thickness;
+    if sweep >= TAU {
+        return ring;
+    }
+
+    let angle = (p.y.atan2(p.x) - start).rem_euclid(TAU);
+    if angle <= sweep {
+        return ring;
+    }
+
+    let start_direction = Vec2::from_angle(start);
+    let end_direction = Vec2::from_angle(start + sweep);
+    if round_caps {
+        let to_start = (p - start_direction * radius).length();
+        let to_end = (p - end_direction * radius).length();
+        return to_start.min(to_end) - half_thickness;
+    }
+
+    let inner = radius - half_thickness;
+    let outer = radius + half_thickness;
+    sd_segment(p, start_direction * inner, start_direction * outer)
+        .min(sd_segment(p, end_direction * inner, end_direction * outer))
+}
+
+#[must_use]
+pub(super) fn border_position(p: Vec2, half_size: Vec2, radii: Vec4) -> f32 {
+    let (hs, r) = (half_size, radii);
+    let arcs = r * FRAC_PI_2;
+
+    let top = 2.0 * hs.x - r.x - r.y;
+    let right = 2.0 * hs.y - r.y - r.z;
+    let bottom = 2.0 * hs.x - r.z - r.w;
+    let left = 2.0 * hs.y - r.w - r.x;
+
+    let right_start = top + arcs.y;
+    let bottom_start = right_start + right + arcs.z;
+    let left_start = bottom_start + bottom + arcs.w;
+
+    let top_left = Vec2::new(r.x - hs.x, r.x - hs.y);
+    let top_right = Vec2::new(hs.x - r.y, r.y - hs.y);
+    let bottom_right = Vec2::new(hs.x - r.z, hs.y - r.z);
+    let bottom_left = Vec2::new(r.w - hs.x, hs.y - r.w);
+
+    if p.x > top_right.x && p.y < top_right.y {
+        let d = p - top_right;
+        return top + d.x.atan2(-d.y) * r.y;
+    }
+    if p.x > bottom_right.x && p.y > bottom_right.y {
+        let d = p - bottom_right;
+        return right_start + right + d.y.atan2(d.x) * r.z;
+    }
+    if p.x < bottom_left.x && p.y > bottom_left.y {
+        let d = p - bottom_left;
+        return bottom_start + bottom + (-d.x).atan2(d.y) * r.w;
+    }
+    if p.x < top_left.x && p.y < top_left.y {
+        let d = p - top_left;
+        return left_start + left + (-d.y).atan2(-d.x) * r.x;
+    }
+
+    if hs.y - p.y.abs() < hs.x - p.x.abs() {
+        if p.y < 0.0 {
+            return p.x - top_left.x;
+        }
+        return bottom_start + bottom_right.x - p.x;
+    }
+    if p.x > 0.0 {
+        return right_start + p.y - top_right.y;
+    }
+    left_start + bottom_left.y - p.y
+}
+
+#[must_use]
+pub(super) fn dash_coverage(position: f32, dash: [f32; 4]) -> f32 {
+    let [dash_length, gap_length, dash_offset, _] = dash;
+    if dash_length <= 0.0 || gap_length <= 0.0 {
+        return 1.0;
+    }
+
+    let period = dash_length + gap_length;
+    let t = (position + dash_offset).rem_euclid(period);
+    let distance = match t < dash_length {
+        true => (-t).max(t - dash_length),
+        false => (t - dash_length).min(period - t),
+    };
+    (0.5 - distance).clamp(0.0, 1.0)
+}
+
+fn sd_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = (pa.dot(ba) / ba.dot(ba)).clamp(0.0, 1.0);
+    (pa - ba * h).length()
+}
+
+#[must_use]
+pub(super) fn correct_coverage(
+    coverage: f32,
+    gamma: f32,
+    contrast: f32,
+) -> f32 {
+    let boosted =
+        (coverage + contrast * coverage * (1.0 - coverage)).clamp(0.0, 1.0);
+    boosted.powf(1.0 / gamma)
+}
+
+#[must_use]
+pub(super) fn smoothstep(edge_0: f32, edge_1: f32, x: f32) -> f32 {
+    if edge_1 <= edge_0 {
+        return if x < edge_0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge_0) / (edge_1 - edge_0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Source-over with the pipelines' blend state: color weighted by the
+// source alpha, alpha added on top of what is left.
+#[inline(always)]
+pub(super) fn blend(destination: &mut Vec4, source: Vec4) {
+    let alpha = source.w;
+    let color = source.xyz() * alpha + destination.xyz() * (1.0 - alpha);
+    *destination = color.extend(alpha + destination.w * (1.0 - alpha));
+}
+
+#[must_use]
+pub(super) fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[must_use]
+pub(super) fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Vec2, Vec4};
+    use rstest::rstest;
+
+    use super::{
+        PixelQuad, linear_to_srgb, sd_rounded_rect_4, srgb_to_linear,
+    };
+    use crate::components::build_model;
+
+    fn pixel_mvp(position: [f32; 2], size: [f32; 2]) -> [[f32; 4]; 4] {
+        let view_projection =
+            Mat4::orthographic_rh(0.0, 100.0, 50.0, 0.0, -1.0, 1.0);
+        let (model, _) = build_model(size, position, 1.0);
+        (view_projection * model).to_cols_array_2d()
+    }
+
+    #[rstest]
+    fn test_pixel_quad_covers_projected_rect() {
+        let half_size = Vec2::new(10.0, 5.0);
+        let quad = PixelQuad::new(
+            &pixel_mvp([20.0, 15.0], [20.0, 10.0]),
+            half_size,
+            half_size,
+            [100, 50],
+            [0.0, 0.0, 100.0, 50.0],
+        )
+        .unwrap();
+
+        assert_eq!(quad.columns, 20..40);
+        assert_eq!(quad.rows, 15..25);
+        let corner = quad.local_position(20, 15);
+        assert!((corner - Vec2::new(-9.5, -4.5)).length() < 1e-4);
+    }
+
+    #[rstest]
+    fn test_pixel_quad_is_clipped() {
+        let half_size = Vec2::new(10.0, 5.0);
+        let quad = PixelQuad::new(
+            &pixel_mvp([20.0, 15.0], [20.0, 10.0]),
+            half_size,
+            half_size,
+            [100, 50],
+            [25.0, 0.0, 100.0, 17.0],
+        )
+        .unwrap();
+
+        assert_eq!(quad.columns, 25..40);
+        assert_eq!(quad.rows, 15..17);
+    }
+
+    #[rstest]
+    #[case(Vec2::ZERO, -5.0)]
+    #[case(Vec2::new(12.0, 0.0), 2.0)]
+    #[case(Vec2::new(10.0, 5.0), 0.0)]
+    fn test_sd_rounded_rect(#[case] p: Vec2, #[case] expected: f32) {
+        let distance = sd_rounded_rect_4(p, Vec2::new(10.0, 5.0), Vec4::ZERO);
+        assert!((distance - expected).abs() < 1e-4);
+    }
+
+    #[rstest]
+    fn test_srgb_round_trip() {
+        for value in [0, 1, 64, 128, 200, 255] {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+    }
+}
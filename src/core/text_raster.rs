@@ -0,0 +1,102 @@
+use bon::Builder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hinting {
+    None,
+    // Vertical hinting only, which keeps glyph shapes.
+    Slight,
+    Full,
+}
+
+// How glyphs are rasterized and blended. Hinting and stem darkening are
+// read by whoever rasterizes the glyphs; gamma and contrast are applied
+// to glyph coverage in the image shader.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+#[builder(const)]
+pub struct TextRasterOptions {
+    #[builder(default = Hinting::Slight)]
+    pub hinting:        Hinting,
+    // Extra stem width, in pixels, for thin fonts at small sizes.
+    #[builder(default = 0.0)]
+    pub stem_darkening: f32,
+    #[builder(default = 1.0)]
+    pub gamma:          f32,
+    #[builder(default = 0.0)]
+    pub contrast:       f32,
+}
+
+impl Default for TextRasterOptions {
+    fn default() -> Self { Self::builder().build() }
+}
+
+impl TextRasterOptions {
+    // The image's coverage field for glyphs drawn with these options.
+    #[must_use]
+    pub const fn coverage(&self) -> [f32; 4] {
+        [self.gamma.max(f32::EPSILON), self.contrast, 0.0, 0.0]
+    }
+}
+
+// The options per font size: each band applies to sizes up to its
+// limit, and sizes past the last band use the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextRasterConfig {
+    defaults: TextRasterOptions,
+    // Sorted by their size limit.
+    bands:    Vec<(f32, TextRasterOptions)>,
+}
+
+impl TextRasterConfig {
+    #[must_use]
+    pub fn new(defaults: TextRasterOptions) -> Self {
+        Self { defaults, bands: Vec::new() }
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn defaults(&self) -> &TextRasterOptions { &self.defaults }
+
+    // Sets the options for sizes up to `max_size`, replacing the band
+    // with the same limit.
+    pub fn set_band(&mut self, max_size: f32, options: TextRasterOptions) {
+        match self
+            .bands
+            .binary_search_by(|(size, _)| size.total_cmp(&max_size))
+        {
+            Ok(index) => self.bands[index].1 = options,
+            Err(index) => self.bands.insert(index, (max_size, options)),
+        }
+    }
+
+    #[must_use]
+    pub fn options_for(&self, font_size: f32) -> &TextRasterOptions {
+        self.bands
+            .iter()
+            .find(|(max_size, _)| font_size <= *max_size)
+            .map_or(&self.defaults, |(_, options)| options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Hinting, TextRasterConfig, TextRasterOptions};
+
+    #[rstest]
+    #[case(9.0, Hinting::Full)]
+    #[case(12.0, Hinting::Full)]
+    #[case(14.0, Hinting::Slight)]
+    #[case(40.0, Hinting::None)]
+    fn test_options_for(#[case] font_size: f32, #[case] expected: Hinting) {
+        let mut config = TextRasterConfig::new(
+            TextRasterOptions::builder().hinting(Hinting::None).build(),
+        );
+        config.set_band(18.0, TextRasterOptions::default());
+        config.set_band(
+            12.0,
+            TextRasterOptions::builder().hinting(Hinting::Full).build(),
+        );
+        assert_eq!(config.options_for(font_size).hinting, expected);
+    }
+}
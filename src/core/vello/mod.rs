@@ -0,0 +1,3 @@
+mod renderer;
+
+pub use renderer::VelloRenderer;
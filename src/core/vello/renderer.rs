@@ -0,0 +1,742 @@
+use std::{f32::consts::FRAC_PI_2, f64::consts::TAU, sync::Arc};
+
+use vello_cpu::{
+    Image as VelloImage, ImageSource, PaintType, Pixmap, RenderContext,
+    color::{AlphaColor, LinearSrgb, PremulRgba8, Srgb},
+    kurbo::{
+        Affine, Arc as ArcShape, BezPath, Cap, Circle, Rect, RoundedRect,
+        RoundedRectRadii, Shape, Stroke,
+    },
+    peniko::{
+        BlendMode, Compose, Extend, Fill, ImageQuality, ImageSampler, Mix,
+    },
+};
+
+use crate::{
+    core::{
+        ArcSegment, Image, PathCommand, Rectangle, TextureId, VectorPath,
+        VectorScene,
+        capture::FrameRecorder,
+        cpu_scene::{CpuPrimitive, CpuScene, CpuTexture},
+        pixel_transform,
+    },
+    instrument::span,
+};
+
+// Flattening tolerance for curves, in pixels.
+const TOLERANCE: f64 = 0.1;
+
+// Shared with the paints of the frames that draw the texture.
+type VelloTexture = Arc<Pixmap>;
+
+impl CpuTexture for VelloTexture {
+    fn size(&self) -> [u32; 2] { [self.width() as u32, self.height() as u32] }
+
+    fn to_rgba(&self) -> Vec<u8> {
+        self.data()
+            .iter()
+            .flat_map(|pixel| {
+                let demultiply = |component: u8| match pixel.a {
+                    0 => 0,
+                    alpha => ((component as u16 * 255 + alpha as u16 / 2)
+                        / alpha as u16)
+                        .min(255) as u8,
+                };
+                [
+                    demultiply(pixel.r),
+                    demultiply(pixel.g),
+                    demultiply(pixel.b),
+                    pixel.a,
+                ]
+            })
+            .collect()
+    }
+}
+
+// Renders the primitives as vector paths with vello's sparse strip
+// rasterizer, which keeps the cost of complex paths proportional to the
+// area they cover instead of to their tessellation.
+pub struct VelloRenderer {
+    context:     RenderContext,
+    pixmap:      Pixmap,
+    clear_color: [f32; 4],
+    scene:       CpuScene<VelloTexture>,
+}
+
+impl VelloRenderer {
+    #[must_use]
+    pub fn new(size: [u32; 2], clear_color: [f32; 4]) -> Self {
+        let [width, height] = clamp_size(size);
+        Self {
+            context: RenderContext::new(width, height),
+            pixmap: Pixmap::new(width, height),
+            clear_color,
+            scene: CpuScene::new(),
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let [width, height] = clamp_size([width, height]);
+        self.context = RenderContext::new(width, height);
+        self.pixmap = Pixmap::new(width, height);
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn size(&self) -> [u32; 2] {
+        [self.pixmap.width() as u32, self.pixmap.height() as u32]
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn pixmap(&self) -> &Pixmap { &self.pixmap }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn scene(&self) -> &CpuScene<impl CpuTexture> {
+        &self.scene
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub(crate) const fn scene_mut(
+        &mut self,
+    ) -> &mut CpuScene<impl CpuTexture> {
+        &mut self.scene
+    }
+
+    // `rgba` is sRGB encoded with straight alpha, as for the GPU textures.
+    pub fn add_texture(&mut self, size: [u32; 2], rgba: &[u8]) -> TextureId {
+        let [width, height] = clamp_size(size);
+        let pixels = rgba.chunks_exact(4).map(premultiply).collect();
+        let pixmap = Pixmap::from_parts(pixels, width, height);
+        self.scene.add_texture(Arc::new(pixmap))
+    }
+
+    // Returns false for an unknown texture.
+    pub fn write_texture(
+        &mut self,
+        id: TextureId,
+        origin: [u32; 2],
+        size: [u32; 2],
+        rgba: &[u8],
+    ) -> bool {
+        let Some(texture) = self.scene.get_mut_texture(id) else {
+            return false;
+        };
+        let pixmap = Arc::make_mut(texture);
+        let [width, height] = [pixmap.width(), pixmap.height()];
+        let rows = rgba.chunks_exact(4 * size[0] as usize);
+        for (y, row) in (origin[1] as u16..height).zip(rows) {
+            let pixels = row.chunks_exact(4).map(premultiply);
+            for (x, pixel) in (origin[0] as u16..width).zip(pixels) {
+                pixmap.set_pixel(x, y, pixel);
+            }
+        }
+        pixmap.recompute_may_have_opacities();
+        true
+    }
+
+    #[must_use]
+    pub fn export_scene(&self) -> VectorScene {
+        self.scene.export(self.size(), self.clear_color)
+    }
+
+    // What `render` is about to draw, for a frame capture.
+    pub(crate) fn record(&self, recorder: &mut FrameRecorder) {
+        self.scene
+            .record(recorder, "hui::vello_pass", self.clear_color);
+    }
+
+    pub fn render(&mut self) -> &Pixmap {
+        span!("vello_pass");
+        let size = self.size();
+        let context = &mut self.context;
+        context.reset();
+
+        let [.., clear_alpha] = self.clear_color;
+        if clear_alpha > 0.0 {
+            context.set_paint(color(self.clear_color));
+            context.fill_rect(&Rect::new(
+                0.0,
+                0.0,
+                size[0] as f64,
+                size[1] as f64,
+            ));
+        }
+
+        self.scene.draw(|primitive| match primitive {
+            CpuPrimitive::Rectangle(rect) => {
+                draw_rectangle(context, size, &rect);
+            }
+            CpuPrimitive::Arc(arc) => draw_arc(context, size, arc),
+            CpuPrimitive::Path(path) => draw_path(context, size, path),
+            CpuPrimitive::Image(texture, image) => {
+                draw_image(context, size, texture, image);
+            }
+        });
+
+        context.flush();
+        context.render_to_pixmap(&mut self.pixmap);
+        &self.pixmap
+    }
+}
+
+fn draw_rectangle(
+    context: &mut RenderContext,
+    size: [u32; 2],
+    rect: &Rectangle,
+) {
+    let [half_x, half_y, offset_x, offset_y] = rect.rect_and_shadow;
+    let [border_size, shadow_spread, shadow_blur, outline_size] = rect.sizes;
+    if half_x <= 0.0 || half_y <= 0.0 {
+        return;
+    }
+    let is_clipped = push_clip_rect(context, size, rect.clip_rect);
+    context.set_transform(quad_transform(&rect.mvp, size, [half_x, half_y]));
+
+    let [.., shadow_alpha] = rect.shadow_color;
+    if shadow_alpha > 0.0 {
+        let spread = shadow_spread as f64;
+        let shadow_rect = Rect::new(
+            -half_x as f64 - spread + offset_x as f64,
+            -half_y as f64 - spread + offset_y as f64,
+            half_x as f64 + spread + offset_x as f64,
+            half_y as f64 + spread + offset_y as f64,
+        );
+        let radius = rect.corner_radii.into_iter().fold(0.0, f32::max);
+        context.set_paint(color(rect.shadow_color));
+        // The shaders fade the shadow out over twice its blur.
+        context.fill_blurred_rounded_rect(
+            &shadow_rect,
+            radius + shadow_spread,
+            shadow_blur.max(0.001) * 0.5,
+        );
+    }
+
+    let [.., outline_alpha] = rect.outline_color;
+    if outline_size > 0.0 && outline_alpha > 0.0 {
+        let half_outline = outline_size * 0.5;
+        let outline = rounded_rect(
+            [half_x + half_outline, half_y + half_outline],
+            rect.corner_radii.map(|radius| radius + half_outline),
+        );
+        context.set_paint(color(rect.outline_color));
+        context.set_stroke(Stroke::new(outline_size as f64));
+        context.stroke_path(&outline.to_path(TOLERANCE));
+    }
+
+    let outer = rounded_rect([half_x, half_y], rect.corner_radii);
+    let [.., border_alpha] = rect.border_color;
+    if border_size > 0.0 && border_alpha > 0.0 {
+        let inner = rounded_rect(
+            [half_x - border_size, half_y - border_size],
+            rect.corner_radii
+                .map(|radius| (radius - border_size).max(0.0)),
+        );
+        let inner = inner.to_path(TOLERANCE);
+        context.set_paint(color(rect.fill_color));
+        context.fill_path(&inner);
+
+        if rect.is_dashed() {
+            let [dash_length, gap_length, dash_offset, _] = rect.dash;
+            let half_border = border_size * 0.5;
+            let radii = rect
+                .corner_radii
+                .map(|radius| (radius - half_border).max(0.0));
+            let center = rounded_rect(
+                [half_x - half_border, half_y - half_border],
+                radii,
+            );
+            // kurbo starts the path at the beginning of the top left
+            // corner, the shaders start the dashes at its end.
+            let offset = dash_offset - radii[0] * FRAC_PI_2;
+            let stroke = Stroke::new(border_size as f64).with_dashes(
+                offset as f64,
+                [dash_length, gap_length].map(f64::from),
+            );
+            context.set_stroke(stroke);
+            context.set_paint(color(rect.border_color));
+            context.stroke_path(&center.to_path(TOLERANCE));
+        } else {
+            let mut border = outer.to_path(TOLERANCE);
+            border.extend(inner);
+            context.set_fill_rule(Fill::EvenOdd);
+            context.set_paint(color(rect.border_color));
+            context.fill_path(&border);
+            context.set_fill_rule(Fill::NonZero);
+        }
+    } else {
+        context.set_paint(color(rect.fill_color));
+        context.fill_path(&outer.to_path(TOLERANCE));
+    }
+
+    context.reset_transform();
+    if is_clipped {
+        context.pop_clip_path();
+    }
+}
+
+fn draw_arc(context: &mut RenderContext, size: [u32; 2], arc: &ArcSegment) {
+    let [radius, thickness, start, sweep] = arc.geometry.map(f64::from);
+    let [half_size, round_caps, ..] = arc.size_and_caps;
+    if half_size <= 0.0 {
+        return;
+    }
+    let is_clipped = push_clip_rect(context, size, arc.clip_rect);
+    context.set_transform(quad_transform(&arc.mvp, size, [half_size; 2]));
+
+    let path = if sweep >= TAU {
+        Circle::new((0.0, 0.0), radius).to_path(TOLERANCE)
+    } else {
+        let arc_shape =
+            ArcShape::new((0.0, 0.0), (radius, radius), start, sweep, 0.0);
+        arc_shape.to_path(TOLERANCE)
+    };
+    let cap = if round_caps > 0.5 { Cap::Round } else { Cap::Butt };
+    context.set_stroke(Stroke::new(thickness).with_caps(cap));
+    context.set_paint(color(arc.color));
+    context.stroke_path(&path);
+
+    context.reset_transform();
+    if is_clipped {
+        context.pop_clip_path();
+    }
+}
+
+// Paths are transformed into pixels before they are stroked, so that the
+// stroke width stays in physical pixels.
+fn draw_path(context: &mut RenderContext, size: [u32; 2], path: &VectorPath) {
+    let mut bez_path = BezPath::new();
+    for command in &path.commands {
+        let point = |[x, y]: [f32; 2]| (x as f64, y as f64);
+        match *command {
+            PathCommand::MoveTo(to) => bez_path.move_to(point(to)),
+            PathCommand::LineTo(to) => bez_path.line_to(point(to)),
+            PathCommand::QuadTo(control, to) => {
+                bez_path.quad_to(point(control), point(to));
+            }
+            PathCommand::CubicTo(control_0, control_1, to) => {
+                bez_path.curve_to(
+                    point(control_0),
+                    point(control_1),
+                    point(to),
+                );
+            }
+            PathCommand::Close => bez_path.close_path(),
+        }
+    }
+    let transform = pixel_transform(&path.mvp, size).map(f64::from);
+    bez_path.apply_affine(Affine::new(transform));
+
+    let is_clipped = push_clip_rect(context, size, path.clip_rect);
+    let [.., fill_alpha] = path.fill_color;
+    if fill_alpha > 0.0 {
+        if path.is_even_odd {
+            context.set_fill_rule(Fill::EvenOdd);
+        }
+        context.set_paint(color(path.fill_color));
+        context.fill_path(&bez_path);
+        context.set_fill_rule(Fill::NonZero);
+    }
+    let [.., stroke_alpha] = path.stroke_color;
+    if path.stroke_width > 0.0 && stroke_alpha > 0.0 {
+        let mut stroke = Stroke::new(path.stroke_width as f64);
+        if path.is_dashed() {
+            stroke = stroke.with_dashes(
+                path.dash_offset as f64,
+                path.dash_pattern.map(f64::from),
+            );
+        }
+        context.set_stroke(stroke);
+        context.set_paint(color(path.stroke_color));
+        context.stroke_path(&bez_path);
+    }
+    if is_clipped {
+        context.pop_clip_path();
+    }
+}
+
+// Glyph coverage correction is left out; text drawn through images looks
+// slightly lighter than on the GPU.
+fn draw_image(
+    context: &mut RenderContext,
+    size: [u32; 2],
+    texture: &VelloTexture,
+    image: &Image,
+) {
+    let [scale_x, scale_y, offset_x, offset_y] =
+        image.uv_transform.map(f64::from);
+    let [repeat_x, repeat_y, half_x, half_y] = image.repeat_and_size;
+    if half_x <= 0.0 || half_y <= 0.0 || scale_x == 0.0 || scale_y == 0.0 {
+        return;
+    }
+    let is_clipped = push_clip_rect(context, size, image.clip_rect);
+    context.set_transform(quad_transform(&image.mvp, size, [half_x, half_y]));
+
+    let shape = rounded_rect([half_x, half_y], image.corner_radii);
+    context.push_clip_path(&shape.to_path(TOLERANCE));
+    // A circle punched out of the image, e.g. to make room for a badge
+    // that is drawn underneath it.
+    let [cutout_x, cutout_y, cutout_radius, _] = image.cutout.map(f64::from);
+    if cutout_radius > 0.0 {
+        let mut cutout = shape.rect().to_path(TOLERANCE);
+        cutout.extend(
+            Circle::new((cutout_x, cutout_y), cutout_radius)
+                .to_path(TOLERANCE),
+        );
+        context.set_fill_rule(Fill::EvenOdd);
+        context.push_clip_path(&cutout);
+        context.set_fill_rule(Fill::NonZero);
+    }
+
+    // Where a texture coordinate of zero and one end up in local units;
+    // axes that don't repeat draw the image once within them.
+    let half = [half_x as f64, half_y as f64];
+    let local = |uv: f64, axis: usize, offset: f64, scale: f64| {
+        2.0 * half[axis] * ((uv - offset) / scale - 0.5)
+    };
+    let span = |axis: usize, offset: f64, scale: f64, repeats: bool| {
+        if repeats {
+            return [-half[axis], half[axis]];
+        }
+        let [start, end] = [
+            local(0.0, axis, offset, scale),
+            local(1.0, axis, offset, scale),
+        ];
+        [
+            start.min(end).max(-half[axis]),
+            start.max(end).min(half[axis]),
+        ]
+    };
+    let [x_0, x_1] = span(0, offset_x, scale_x, repeat_x > 0.5);
+    let [y_0, y_1] = span(1, offset_y, scale_y, repeat_y > 0.5);
+    let region = Rect::new(x_0, y_0, x_1, y_1);
+
+    let extend = |repeats: f32| {
+        if repeats > 0.5 { Extend::Repeat } else { Extend::Pad }
+    };
+    let sampler = ImageSampler {
+        x_extend: extend(repeat_x),
+        y_extend: extend(repeat_y),
+        quality:  ImageQuality::Medium,
+        alpha:    1.0,
+    };
+    let paint =
+        VelloImage { image: ImageSource::Pixmap(texture.clone()), sampler };
+    let texture_size = [texture.width() as f64, texture.height() as f64];
+    let paint_transform = Affine::new([
+        2.0 * half[0] / (texture_size[0] * scale_x),
+        0.0,
+        0.0,
+        2.0 * half[1] / (texture_size[1] * scale_y),
+        local(0.0, 0, offset_x, scale_x),
+        local(0.0, 1, offset_y, scale_y),
+    ]);
+
+    let [red, green, blue, alpha] = image.tint_color;
+    let is_tinted = [red, green, blue] != [1.0; 3];
+    if alpha < 1.0 || is_tinted {
+        context.push_opacity_layer(alpha);
+    }
+    context.set_paint(PaintType::Image(paint));
+    context.set_paint_transform(paint_transform);
+    context.fill_rect(&region);
+    context.reset_paint_transform();
+    // Multiplying within the image's alpha tints it like the shader does.
+    if is_tinted {
+        context
+            .push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcAtop));
+        context.set_paint(color([red, green, blue, 1.0]));
+        context.fill_rect(&region);
+        context.pop_layer();
+    }
+    if alpha < 1.0 || is_tinted {
+        context.pop_layer();
+    }
+
+    if cutout_radius > 0.0 {
+        context.pop_clip_path();
+    }
+    context.pop_clip_path();
+    context.reset_transform();
+    if is_clipped {
+        context.pop_clip_path();
+    }
+}
+
+// Maps a quad's local units, the unit quad scaled by its half size, to
+// pixels.
+fn quad_transform(
+    mvp: &[[f32; 4]; 4],
+    size: [u32; 2],
+    half_size: [f32; 2],
+) -> Affine {
+    let transform = pixel_transform(mvp, size).map(f64::from);
+    Affine::new(transform).pre_scale_non_uniform(
+        1.0 / half_size[0] as f64,
+        1.0 / half_size[1] as f64,
+    )
+}
+
+// Clip rects that cover the whole target are skipped. Returns whether a
+// clip path was pushed.
+fn push_clip_rect(
+    context: &mut RenderContext,
+    size: [u32; 2],
+    clip_rect: [f32; 4],
+) -> bool {
+    let [x, y, width, height] = clip_rect.map(f64::from);
+    let [target_width, target_height] = size.map(f64::from);
+    if x <= 0.0
+        && y <= 0.0
+        && x + width >= target_width
+        && y + height >= target_height
+    {
+        return false;
+    }
+    let clip = Rect::new(x, y, x + width, y + height);
+    context.push_clip_path(&clip.to_path(TOLERANCE));
+    true
+}
+
+// The radii are ordered like in the shaders: top left, top right, bottom
+// right, bottom left.
+fn rounded_rect(half_size: [f32; 2], radii: [f32; 4]) -> RoundedRect {
+    let [half_x, half_y] = half_size.map(|value| value.max(0.0) as f64);
+    let [top_left, top_right, bottom_right, bottom_left] =
+        radii.map(f64::from);
+    RoundedRect::from_rect(
+        Rect::new(-half_x, -half_y, half_x, half_y),
+        RoundedRectRadii::new(top_left, top_right, bottom_right, bottom_left),
+    )
+}
+
+// Instance colors are linear, like the values written to an sRGB target.
+fn color(linear: [f32; 4]) -> AlphaColor<Srgb> {
+    AlphaColor::<LinearSrgb>::new(linear).convert::<Srgb>()
+}
+
+fn premultiply(rgba: &[u8]) -> PremulRgba8 {
+    let alpha = rgba[3] as u16;
+    let multiply =
+        |component: u8| ((component as u16 * alpha + 127) / 255) as u8;
+    PremulRgba8 {
+        r: multiply(rgba[0]),
+        g: multiply(rgba[1]),
+        b: multiply(rgba[2]),
+        a: rgba[3],
+    }
+}
+
+fn clamp_size(size: [u32; 2]) -> [u16; 2] {
+    size.map(|value| value.clamp(1, u16::MAX as u32) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+    use rstest::rstest;
+
+    use super::VelloRenderer;
+    use crate::{
+        components::{BlockStyle, Bounds, build_model, build_rectangle},
+        core::{
+            ArcSegment, Backend, Image, PathCommand, RenderPhase, Renderer,
+            VectorPath,
+        },
+    };
+
+    const VIEWPORT: [u32; 2] = [64, 32];
+
+    fn view_projection() -> Mat4 {
+        Mat4::orthographic_rh(
+            0.0,
+            VIEWPORT[0] as f32,
+            VIEWPORT[1] as f32,
+            0.0,
+            -1.0,
+            1.0,
+        )
+    }
+
+    // Only used on opaque or cleared pixels, where premultiplied and
+    // straight alpha agree.
+    fn pixel(renderer: &mut VelloRenderer, x: u16, y: u16) -> [u8; 4] {
+        let color = renderer.render().sample(x, y);
+        [color.r, color.g, color.b, color.a]
+    }
+
+    #[rstest]
+    fn test_rectangle_is_filled_and_clipped() {
+        let mut renderer = VelloRenderer::new(VIEWPORT, [0.0; 4]);
+        let bounds = Bounds {
+            position:  [8.0, 8.0],
+            size:      [32.0, 16.0],
+            clip_rect: [0.0, 0.0, 24.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([1.0, 0.0, 0.0, 1.0])
+            .build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        let rect_id = renderer
+            .scene_mut()
+            .add_rectangle(&rect, RenderPhase::Content);
+
+        assert_eq!(pixel(&mut renderer, 16, 16), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut renderer, 30, 16), [0, 0, 0, 0]);
+        assert_eq!(pixel(&mut renderer, 4, 4), [0, 0, 0, 0]);
+
+        renderer.scene_mut().remove_rectangle(rect_id);
+        assert_eq!(pixel(&mut renderer, 16, 16), [0, 0, 0, 0]);
+    }
+
+    // kurbo starts rounded rectangles at a different point than the
+    // shaders, which the dash offset makes up for.
+    #[rstest]
+    #[case(0.0, [255, 0, 0, 255], [0, 0, 0, 0])]
+    #[case(4.0, [0, 0, 0, 0], [255, 0, 0, 255])]
+    fn test_dashed_border_follows_offset(
+        #[case] dash_offset: f32,
+        #[case] first: [u8; 4],
+        #[case] second: [u8; 4],
+    ) {
+        let mut renderer = VelloRenderer::new(VIEWPORT, [0.0; 4]);
+        let bounds = Bounds {
+            position:  [8.0, 8.0],
+            size:      [32.0, 16.0],
+            clip_rect: [0.0, 0.0, 64.0, 32.0],
+        };
+        let style = BlockStyle::builder()
+            .fill_color([0.0; 4])
+            .border_color([1.0, 0.0, 0.0, 1.0])
+            .border_size(2.0)
+            .corner_radii([4.0; 4])
+            .dash_pattern([4.0, 4.0])
+            .dash_offset(dash_offset)
+            .build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer
+            .scene_mut()
+            .add_rectangle(&rect, RenderPhase::Content);
+
+        // The ends of the dashes are anti-aliased over half a pixel more
+        // than on the other backends.
+        assert_eq!(pixel(&mut renderer, 13, 8), first);
+        assert_eq!(pixel(&mut renderer, 18, 8), second);
+    }
+
+    #[rstest]
+    fn test_arc_covers_its_sweep_only() {
+        let mut renderer = VelloRenderer::new(VIEWPORT, [0.0; 4]);
+        let model = Mat4::from_scale_rotation_translation(
+            [12.0, 12.0, 1.0].into(),
+            Default::default(),
+            [32.0, 16.0, 0.0].into(),
+        );
+        let arc = ArcSegment::builder()
+            .mvp((view_projection() * model).to_cols_array_2d())
+            .color([0.0, 1.0, 0.0, 1.0])
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .geometry([10.0, 4.0, 0.0, std::f32::consts::PI])
+            .size_and_caps([12.0, 0.0, 0.0, 0.0])
+            .build();
+        renderer.scene_mut().add_arc(&arc, RenderPhase::Content);
+
+        assert_eq!(pixel(&mut renderer, 32, 26), [0, 255, 0, 255]);
+        assert_eq!(pixel(&mut renderer, 32, 5), [0, 0, 0, 0]);
+        assert_eq!(pixel(&mut renderer, 32, 16), [0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    #[case(false, [0, 0, 255, 255])]
+    #[case(true, [0, 0, 0, 0])]
+    fn test_path_fill_rule(
+        #[case] is_even_odd: bool,
+        #[case] center: [u8; 4],
+    ) {
+        let mut renderer = VelloRenderer::new(VIEWPORT, [0.0; 4]);
+        let square = |left: f32, right: f32| {
+            [
+                PathCommand::MoveTo([left, left]),
+                PathCommand::LineTo([right, left]),
+                PathCommand::LineTo([right, right]),
+                PathCommand::LineTo([left, right]),
+                PathCommand::Close,
+            ]
+        };
+        let path = VectorPath::builder()
+            .mvp(view_projection().to_cols_array_2d())
+            .commands([square(4.0, 28.0), square(12.0, 20.0)].concat())
+            .fill_color([0.0, 0.0, 1.0, 1.0])
+            .is_even_odd(is_even_odd)
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .build();
+        renderer.scene_mut().add_path(&path, RenderPhase::Content);
+
+        assert_eq!(pixel(&mut renderer, 8, 8), [0, 0, 255, 255]);
+        assert_eq!(pixel(&mut renderer, 16, 16), center);
+        assert_eq!(pixel(&mut renderer, 40, 16), [0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    fn test_image_samples_texture() {
+        let mut renderer = VelloRenderer::new(VIEWPORT, [0.0; 4]);
+        let texture_id = renderer.add_texture([1, 1], &[0, 128, 255, 255]);
+        let (model, half_size) = build_model([16.0, 16.0], [0.0, 0.0], 1.0);
+        let image = Image::builder()
+            .mvp((view_projection() * model).to_cols_array_2d())
+            .tint_color([1.0; 4])
+            .corner_radii([0.0; 4])
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .uv_transform([1.0, 1.0, 0.0, 0.0])
+            .repeat_and_size([0.0, 0.0, half_size[0], half_size[1]])
+            .cutout([0.0; 4])
+            .build();
+        renderer
+            .scene_mut()
+            .add_image(texture_id, &image, RenderPhase::Content)
+            .unwrap();
+
+        assert_eq!(pixel(&mut renderer, 8, 8), [0, 128, 255, 255]);
+        assert_eq!(pixel(&mut renderer, 24, 8), [0, 0, 0, 0]);
+
+        assert!(renderer.write_texture(texture_id, [0, 0], [1, 1], &[255; 4]));
+        assert_eq!(pixel(&mut renderer, 8, 8), [255, 255, 255, 255]);
+    }
+
+    #[rstest]
+    fn test_renderer_with_vello_backend() {
+        let mut renderer = Renderer::with_backend(Backend::Vello {
+            size:        VIEWPORT,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+        });
+        assert_eq!(renderer.render_size(), VIEWPORT);
+
+        let bounds = Bounds {
+            position:  [0.0, 0.0],
+            size:      [32.0, 32.0],
+            clip_rect: [0.0, 0.0, 64.0, 32.0],
+        };
+        let style = BlockStyle::builder().fill_color([1.0; 4]).build();
+        let rect = build_rectangle(&view_projection(), 1.0, &bounds, &style);
+        renderer.add_rectangle(&rect);
+        let path = VectorPath::builder()
+            .mvp(view_projection().to_cols_array_2d())
+            .commands(vec![
+                PathCommand::MoveTo([40.0, 8.0]),
+                PathCommand::LineTo([56.0, 8.0]),
+            ])
+            .stroke_color([1.0; 4])
+            .stroke_width(4.0)
+            .clip_rect([0.0, 0.0, 64.0, 32.0])
+            .build();
+        assert!(renderer.add_path(&path).is_some());
+
+        let pixmap = renderer.render_vello().unwrap();
+        assert_eq!(pixmap.sample(16, 16).r, 255);
+        assert_eq!(pixmap.sample(48, 8).r, 255);
+        assert_eq!(pixmap.sample(48, 24).r, 0);
+        assert_eq!(pixmap.sample(48, 24).a, 255);
+    }
+}
@@ -0,0 +1,158 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilDate {
+    pub year:  i32,
+    pub month: u8,
+    pub day:   u8,
+}
+
+impl CivilDate {
+    #[must_use]
+    pub const fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        match month >= 1
+            && month <= 12
+            && day >= 1
+            && day <= days_in_month(year, month)
+        {
+            true => Some(Self { year, month, day }),
+            false => None,
+        }
+    }
+}
+
+#[must_use]
+pub const fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+const fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+// A numeric date format, e.g. 31.12.2024 or 12/31/2024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatePattern {
+    pub order:     DateOrder,
+    pub separator: char,
+    // Whether days and months are written with two digits.
+    pub is_padded: bool,
+}
+
+impl Default for DatePattern {
+    fn default() -> Self {
+        Self {
+            order:     DateOrder::YearMonthDay,
+            separator: '-',
+            is_padded: true,
+        }
+    }
+}
+
+impl DatePattern {
+    #[must_use]
+    pub fn for_locale(language: &str, region: Option<&str>) -> Self {
+        let pattern =
+            |order, separator| Self { order, separator, is_padded: true };
+        match (language, region) {
+            ("en", Some("US") | None) => Self {
+                is_padded: false,
+                ..pattern(DateOrder::MonthDayYear, '/')
+            },
+            ("en" | "fr" | "es" | "it" | "pt" | "el" | "id", _) => {
+                pattern(DateOrder::DayMonthYear, '/')
+            }
+            (
+                "de" | "ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "no"
+                | "da" | "tr",
+                _,
+            ) => pattern(DateOrder::DayMonthYear, '.'),
+            ("nl" | "hi", _) => pattern(DateOrder::DayMonthYear, '-'),
+            ("ja" | "zh", _) => pattern(DateOrder::YearMonthDay, '/'),
+            ("ko" | "hu", _) => pattern(DateOrder::YearMonthDay, '.'),
+            _ => Self::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn format(&self, date: &CivilDate) -> String {
+        let width = if self.is_padded { 2 } else { 1 };
+        let year = format!("{:04}", date.year);
+        let month = format!("{:0width$}", date.month);
+        let day = format!("{:0width$}", date.day);
+        let parts = match self.order {
+            DateOrder::DayMonthYear => [day, month, year],
+            DateOrder::MonthDayYear => [month, day, year],
+            DateOrder::YearMonthDay => [year, month, day],
+        };
+        parts.join(&self.separator.to_string())
+    }
+
+    // Reads a date in this order; padding is optional.
+    #[must_use]
+    pub fn parse(&self, text: &str) -> Option<CivilDate> {
+        let mut parts = text.trim().split(self.separator).map(str::trim);
+        let [first, second, third] =
+            [parts.next()?, parts.next()?, parts.next()?];
+        if parts.next().is_some() {
+            return None;
+        }
+        let (year, month, day) = match self.order {
+            DateOrder::DayMonthYear => (third, second, first),
+            DateOrder::MonthDayYear => (third, first, second),
+            DateOrder::YearMonthDay => (first, second, third),
+        };
+        CivilDate::new(
+            year.parse().ok()?,
+            month.parse().ok()?,
+            day.parse().ok()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{CivilDate, DatePattern};
+
+    #[rstest]
+    #[case("en", Some("US"), "1/5/2024")]
+    #[case("en", Some("GB"), "05/01/2024")]
+    #[case("de", None, "05.01.2024")]
+    #[case("ja", None, "2024/01/05")]
+    #[case("xx", None, "2024-01-05")]
+    fn test_format(
+        #[case] language: &str,
+        #[case] region: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let date = CivilDate::new(2024, 1, 5).unwrap();
+        let pattern = DatePattern::for_locale(language, region);
+        assert_eq!(pattern.format(&date), expected);
+    }
+
+    #[rstest]
+    #[case("de", "29.2.2024", CivilDate::new(2024, 2, 29))]
+    #[case("de", "29.02.2023", None)]
+    #[case("en", "12/31/1999", CivilDate::new(1999, 12, 31))]
+    #[case("en", "12/31", None)]
+    fn test_parse(
+        #[case] language: &str,
+        #[case] text: &str,
+        #[case] expected: Option<CivilDate>,
+    ) {
+        let pattern = DatePattern::for_locale(language, None);
+        assert_eq!(pattern.parse(text), expected);
+    }
+}
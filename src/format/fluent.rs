@@ -0,0 +1,91 @@
+use fluent_bundle::{
+    FluentArgs, FluentError, FluentResource, concurrent::FluentBundle,
+};
+use unic_langid::LanguageIdentifier;
+
+use super::Localizer;
+
+// A catalog of Fluent messages for one locale. Stock widgets look their
+// strings up by the ids in `messages`, so a catalog translating them
+// reads e.g. `hui-today = Heute`.
+pub struct FluentLocalizer {
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl FluentLocalizer {
+    #[must_use]
+    pub fn new(locale: &str) -> Option<Self> {
+        let locale: LanguageIdentifier = locale.parse().ok()?;
+        let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+        // Isolation marks show up as boxes in fonts without them.
+        bundle.set_use_isolating(false);
+
+        Some(Self { bundle })
+    }
+
+    // Adds the messages in an FTL source. Messages already in the catalog
+    // are kept, and reported as overriding errors.
+    pub fn add_source(
+        &mut self,
+        source: impl Into<String>,
+    ) -> Result<(), Vec<FluentError>> {
+        let resource = match FluentResource::try_new(source.into()) {
+            Ok(resource) => resource,
+            Err((_, errors)) => {
+                return Err(errors
+                    .into_iter()
+                    .map(FluentError::ParserError)
+                    .collect());
+            }
+        };
+        self.bundle.add_resource(resource)
+    }
+}
+
+impl Localizer for FluentLocalizer {
+    fn message(&self, id: &str, args: &[(&str, &str)]) -> Option<String> {
+        let pattern = self.bundle.get_message(id)?.value()?;
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, *value);
+        }
+        let mut errors = Vec::new();
+        let text = self.bundle.format_pattern(
+            pattern,
+            Some(&fluent_args),
+            &mut errors,
+        );
+
+        errors.is_empty().then(|| text.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::FluentLocalizer;
+    use crate::format::{Localizer, messages};
+
+    #[rstest]
+    #[case(messages::TODAY, &[], Some("Heute"))]
+    #[case(messages::PAGE, &[("page", "3")], Some("Seite 3"))]
+    #[case(messages::CLEAR, &[], None)]
+    fn test_message(
+        #[case] id: &str,
+        #[case] args: &[(&str, &str)],
+        #[case] expected: Option<&str>,
+    ) {
+        let mut localizer = FluentLocalizer::new("de-DE").unwrap();
+        localizer
+            .add_source("hui-today = Heute\nhui-page = Seite { $page }\n")
+            .unwrap();
+        assert_eq!(localizer.message(id, args).as_deref(), expected);
+    }
+
+    #[rstest]
+    fn test_invalid_source() {
+        let mut localizer = FluentLocalizer::new("de-DE").unwrap();
+        assert!(localizer.add_source("hui-clear = {").is_err());
+    }
+}
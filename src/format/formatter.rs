@@ -0,0 +1,255 @@
+#[cfg(feature = "icu")]
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use super::{CivilDate, DatePattern, NumberSymbols};
+
+static GLOBAL_FORMATTER: RwLock<Option<Formatter>> = RwLock::new(None);
+
+// A BCP 47 tag split into its language and region, e.g. "de-CH" or
+// "pt_BR". Scripts and extensions are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocaleTag {
+    pub language: String,
+    pub region:   Option<String>,
+}
+
+impl LocaleTag {
+    #[must_use]
+    pub fn parse(tag: &str) -> Self {
+        let mut subtags = tag.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let region = subtags
+            .find(|subtag| {
+                subtag.len() == 2
+                    && subtag.chars().all(|char| char.is_ascii_alphabetic())
+                    || subtag.len() == 3
+                        && subtag.chars().all(|char| char.is_ascii_digit())
+            })
+            .map(str::to_ascii_uppercase);
+        Self { language, region }
+    }
+}
+
+impl std::fmt::Display for LocaleTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{}-{region}", self.language),
+            None => f.write_str(&self.language),
+        }
+    }
+}
+
+// Formats and parses numbers and dates for a locale. Widgets that show
+// numbers or dates take an optional formatter of their own and fall back
+// to the global one. With the `icu` feature numbers are formatted with
+// CLDR data; otherwise, and for dates, a small built-in table is used.
+// Cloning is cheap.
+#[derive(Clone)]
+pub struct Formatter {
+    locale:            LocaleTag,
+    number_symbols:    NumberSymbols,
+    date_pattern:      DatePattern,
+    #[cfg(feature = "icu")]
+    decimal_formatter: Option<Arc<icu_decimal::DecimalFormatter>>,
+}
+
+impl Default for Formatter {
+    fn default() -> Self { Self::new("en-US") }
+}
+
+impl std::fmt::Debug for Formatter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Formatter")
+            .field("locale", &self.locale)
+            .field("number_symbols", &self.number_symbols)
+            .field("date_pattern", &self.date_pattern)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Formatter {
+    #[must_use]
+    pub fn new(locale: &str) -> Self {
+        let tag = LocaleTag::parse(locale);
+        let region = tag.region.as_deref();
+        let formatter = Self {
+            number_symbols: NumberSymbols::for_locale(&tag.language, region),
+            date_pattern: DatePattern::for_locale(&tag.language, region),
+            locale: tag,
+            #[cfg(feature = "icu")]
+            decimal_formatter: None,
+        };
+        #[cfg(feature = "icu")]
+        let formatter = formatter.with_decimal_formatter(locale);
+
+        formatter
+    }
+
+    // The formatter set with `set_global`, or the `en-US` one.
+    #[must_use]
+    pub fn global() -> Self {
+        let global = GLOBAL_FORMATTER
+            .read()
+            .unwrap_or_else(|error| error.into_inner());
+        global.clone().unwrap_or_default()
+    }
+
+    pub fn set_global(formatter: Self) {
+        let mut global = GLOBAL_FORMATTER
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        *global = Some(formatter);
+    }
+
+    // The widget's own formatter if it has one, or the global one.
+    #[must_use]
+    pub fn resolve(formatter: Option<&Self>) -> Self {
+        formatter.cloned().unwrap_or_else(Self::global)
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn locale(&self) -> &LocaleTag { &self.locale }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn number_symbols(&self) -> &NumberSymbols {
+        &self.number_symbols
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn date_pattern(&self) -> &DatePattern { &self.date_pattern }
+
+    // Overrides the locale's number symbols, e.g. for user preferences.
+    // Formatting then no longer goes through CLDR data.
+    #[must_use]
+    pub fn with_number_symbols(
+        mut self,
+        number_symbols: NumberSymbols,
+    ) -> Self {
+        self.number_symbols = number_symbols;
+        #[cfg(feature = "icu")]
+        {
+            self.decimal_formatter = None;
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_date_pattern(mut self, date_pattern: DatePattern) -> Self {
+        self.date_pattern = date_pattern;
+        self
+    }
+
+    #[must_use]
+    pub fn format_number(&self, value: f64, fraction_digits: usize) -> String {
+        #[cfg(feature = "icu")]
+        if let Some(formatted) = self.format_with_icu(value, fraction_digits) {
+            return formatted;
+        }
+        self.number_symbols.format(value, fraction_digits)
+    }
+
+    #[must_use]
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        self.number_symbols.parse(text)
+    }
+
+    #[must_use]
+    pub fn format_date(&self, date: &CivilDate) -> String {
+        self.date_pattern.format(date)
+    }
+
+    #[must_use]
+    pub fn parse_date(&self, text: &str) -> Option<CivilDate> {
+        self.date_pattern.parse(text)
+    }
+
+    #[cfg(feature = "icu")]
+    fn with_decimal_formatter(mut self, locale: &str) -> Self {
+        let Ok(locale) = icu_locale::Locale::try_from_str(locale) else {
+            return self;
+        };
+        let Ok(decimal_formatter) = icu_decimal::DecimalFormatter::try_new(
+            (&locale).into(),
+            Default::default(),
+        ) else {
+            return self;
+        };
+        self.decimal_formatter = Some(Arc::new(decimal_formatter));
+
+        // Parsing reads the symbols CLDR formats with.
+        if let Some(symbols) = self
+            .format_with_icu(1_234_567.5, 1)
+            .and_then(|sample| NumberSymbols::from_sample(&sample))
+        {
+            self.number_symbols = symbols;
+        }
+        self
+    }
+
+    #[cfg(feature = "icu")]
+    fn format_with_icu(
+        &self,
+        value: f64,
+        fraction_digits: usize,
+    ) -> Option<String> {
+        let decimal_formatter = self.decimal_formatter.as_ref()?;
+        let plain = format!("{value:.fraction_digits$}");
+        let decimal =
+            icu_decimal::input::Decimal::try_from_str(&plain).ok()?;
+        Some(decimal_formatter.format(&decimal).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Formatter, LocaleTag};
+    use crate::format::CivilDate;
+
+    #[rstest]
+    #[case("de-CH", "de", Some("CH"))]
+    #[case("pt_br", "pt", Some("BR"))]
+    #[case("zh-Hant-TW", "zh", Some("TW"))]
+    #[case("es-419", "es", Some("419"))]
+    #[case("fr", "fr", None)]
+    fn test_locale_tag(
+        #[case] tag: &str,
+        #[case] language: &str,
+        #[case] region: Option<&str>,
+    ) {
+        let tag = LocaleTag::parse(tag);
+        assert_eq!(tag.language, language);
+        assert_eq!(tag.region.as_deref(), region);
+    }
+
+    #[rstest]
+    #[case("de-DE", -1234.5, 2, "-1.234,50")]
+    #[case("en-US", 1234.5, 1, "1,234.5")]
+    fn test_number_round_trips(
+        #[case] locale: &str,
+        #[case] value: f64,
+        #[case] fraction_digits: usize,
+        #[case] expected: &str,
+    ) {
+        let formatter = Formatter::new(locale);
+        let formatted = formatter.format_number(value, fraction_digits);
+        assert_eq!(formatted, expected);
+        assert_eq!(formatter.parse_number(&formatted), Some(value));
+    }
+
+    #[rstest]
+    #[case("de-DE", "31.12.2024")]
+    #[case("en-US", "12/31/2024")]
+    fn test_date_round_trips(#[case] locale: &str, #[case] expected: &str) {
+        let formatter = Formatter::new(locale);
+        let date = CivilDate::new(2024, 12, 31).unwrap();
+        let formatted = formatter.format_date(&date);
+        assert_eq!(formatted, expected);
+        assert_eq!(formatter.parse_date(&formatted), Some(date));
+    }
+}
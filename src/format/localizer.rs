@@ -0,0 +1,109 @@
+use std::sync::{Arc, RwLock};
+
+static GLOBAL_LOCALIZER: RwLock<Option<Arc<dyn Localizer>>> =
+    RwLock::new(None);
+
+// The ids of the strings stock widgets show, with their English text.
+// `{ $name }` placeholders are filled from the message's arguments.
+pub mod messages {
+    pub const TODAY: &str = "hui-today";
+    pub const CLEAR: &str = "hui-clear";
+    pub const CLOSE: &str = "hui-close";
+    pub const MINIMIZE: &str = "hui-minimize";
+    pub const MAXIMIZE: &str = "hui-maximize";
+    pub const PREVIOUS_PAGE: &str = "hui-previous-page";
+    pub const NEXT_PAGE: &str = "hui-next-page";
+    pub const PAGE: &str = "hui-page";
+    pub const NO_RESULTS: &str = "hui-no-results";
+
+    pub(crate) const ENGLISH: [(&str, &str); 9] = [
+        (TODAY, "Today"),
+        (CLEAR, "Clear"),
+        (CLOSE, "Close"),
+        (MINIMIZE, "Minimize"),
+        (MAXIMIZE, "Maximize"),
+        (PREVIOUS_PAGE, "Previous page"),
+        (NEXT_PAGE, "Next page"),
+        (PAGE, "Page { $page }"),
+        (NO_RESULTS, "No results"),
+    ];
+}
+
+// Looks up the text of a message in the application's language. `None`
+// falls back to the English text, so a catalog only needs the messages
+// it translates.
+pub trait Localizer: Send + Sync {
+    fn message(&self, id: &str, args: &[(&str, &str)]) -> Option<String>;
+}
+
+// The built-in English strings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnglishLocalizer;
+
+impl Localizer for EnglishLocalizer {
+    fn message(&self, id: &str, args: &[(&str, &str)]) -> Option<String> {
+        let (_, text) =
+            messages::ENGLISH.iter().find(|(key, _)| *key == id)?;
+        Some(substitute(text, args))
+    }
+}
+
+pub fn set_localizer(localizer: Arc<dyn Localizer>) {
+    let mut global = GLOBAL_LOCALIZER
+        .write()
+        .unwrap_or_else(|error| error.into_inner());
+    *global = Some(localizer);
+}
+
+// The message's text from the localizer set with `set_localizer`, then
+// in English, then the id itself.
+#[must_use]
+pub fn localize(id: &str, args: &[(&str, &str)]) -> String {
+    let global = GLOBAL_LOCALIZER
+        .read()
+        .unwrap_or_else(|error| error.into_inner());
+    global
+        .as_ref()
+        .and_then(|localizer| localizer.message(id, args))
+        .or_else(|| EnglishLocalizer.message(id, args))
+        .unwrap_or_else(|| id.to_owned())
+}
+
+fn substitute(text: &str, args: &[(&str, &str)]) -> String {
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{ $") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = rest[start + 3..start + end].trim();
+        substituted.push_str(&rest[..start]);
+        match args.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => substituted.push_str(value),
+            None => substituted.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::substitute;
+
+    #[rstest]
+    #[case("Page { $page }", &[("page", "3")], "Page 3")]
+    #[case("{ $a } of { $b }", &[("b", "2"), ("a", "1")], "1 of 2")]
+    #[case("Page { $page }", &[], "Page { $page }")]
+    #[case("Unclosed { $page", &[("page", "3")], "Unclosed { $page")]
+    fn test_substitute(
+        #[case] text: &str,
+        #[case] args: &[(&str, &str)],
+        #[case] expected: &str,
+    ) {
+        assert_eq!(substitute(text, args), expected);
+    }
+}
@@ -0,0 +1,15 @@
+mod date;
+#[cfg(feature = "fluent")]
+mod fluent;
+mod formatter;
+mod localizer;
+mod number;
+
+pub use date::{CivilDate, DateOrder, DatePattern, days_in_month};
+#[cfg(feature = "fluent")]
+pub use fluent::FluentLocalizer;
+pub use formatter::{Formatter, LocaleTag};
+pub use localizer::{
+    EnglishLocalizer, Localizer, localize, messages, set_localizer,
+};
+pub use number::NumberSymbols;
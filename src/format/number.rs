@@ -0,0 +1,185 @@
+// The symbols numbers are written with. `grouping` is the size of the
+// group next to the decimal separator, then of the groups before it,
+// e.g. [3, 2] for 12,34,567.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberSymbols {
+    pub decimal_separator: char,
+    pub group_separator:   Option<char>,
+    pub grouping:          [usize; 2],
+}
+
+impl Default for NumberSymbols {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator:   Some(','),
+            grouping:          [3, 3],
+        }
+    }
+}
+
+impl NumberSymbols {
+    // A small built-in table for when CLDR data isn't compiled in.
+    #[must_use]
+    pub fn for_locale(language: &str, region: Option<&str>) -> Self {
+        let symbols = |decimal_separator, group_separator| Self {
+            decimal_separator,
+            group_separator: Some(group_separator),
+            grouping: [3, 3],
+        };
+        match (language, region) {
+            ("de" | "it", Some("CH")) => symbols('.', '\u{2019}'),
+            ("en" | "hi", Some("IN")) | ("hi", _) => {
+                Self { grouping: [3, 2], ..Self::default() }
+            }
+            (
+                "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el",
+                _,
+            ) => symbols(',', '.'),
+            ("fr", _) => symbols(',', '\u{202F}'),
+            (
+                "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no",
+                _,
+            ) => symbols(',', '\u{00A0}'),
+            _ => Self::default(),
+        }
+    }
+
+    // Reads the symbols from 1234567.5 as a formatter wrote it.
+    #[must_use]
+    pub fn from_sample(sample: &str) -> Option<Self> {
+        let decimal_separator = sample.chars().rev().nth(1)?;
+        let integer = &sample[..sample.rfind(decimal_separator)?];
+        let group_separator =
+            integer.chars().find(|char| !char.is_ascii_digit());
+        let groups: Vec<usize> = match group_separator {
+            Some(separator) => {
+                integer.split(separator).map(str::len).collect()
+            }
+            None => vec![integer.len()],
+        };
+        let grouping = match groups.as_slice() {
+            [.., secondary, primary] if groups.len() > 2 => {
+                [*primary, *secondary]
+            }
+            [_, primary] => [*primary, *primary],
+            _ => [3, 3],
+        };
+        Some(Self { decimal_separator, group_separator, grouping })
+    }
+
+    #[must_use]
+    pub fn format(&self, value: f64, fraction_digits: usize) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let plain = format!("{:.*}", fraction_digits, value.abs());
+        let (integer, fraction) = match plain.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (plain.as_str(), None),
+        };
+        let is_negative =
+            value < 0.0 && plain.chars().any(|char| char > '0' && char <= '9');
+
+        let mut formatted = String::new();
+        if is_negative {
+            formatted.push('-');
+        }
+        formatted.push_str(&self.group(integer));
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    // Accepts the decimal separator, group separators anywhere in the
+    // integer part and surrounding whitespace.
+    #[must_use]
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let normalized: String = text
+            .trim()
+            .chars()
+            .filter(|char| Some(*char) != self.group_separator)
+            .map(|char| match char {
+                char if char == self.decimal_separator => '.',
+                '\u{2212}' => '-',
+                char => char,
+            })
+            .collect();
+        let is_plain = normalized.chars().all(|char| {
+            char.is_ascii_digit() || matches!(char, '.' | '-' | '+')
+        });
+        match is_plain {
+            true => normalized.parse().ok(),
+            false => None,
+        }
+    }
+
+    fn group(&self, integer: &str) -> String {
+        let Some(separator) = self.group_separator else {
+            return integer.to_owned();
+        };
+        let [primary, secondary] = self.grouping.map(|size| size.max(1));
+        let digits: Vec<char> = integer.chars().collect();
+        let mut groups = Vec::new();
+        let mut end = digits.len();
+        let mut size = primary;
+        while end > size {
+            groups.push(digits[end - size..end].iter().collect::<String>());
+            end -= size;
+            size = secondary;
+        }
+        groups.push(digits[..end].iter().collect());
+        groups.reverse();
+        groups.join(&separator.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::NumberSymbols;
+
+    #[rstest]
+    #[case("en", None, 1234567.456, 2, "1,234,567.46")]
+    #[case("de", Some("DE"), -1234.5, 1, "-1.234,5")]
+    #[case("hi", None, 1234567.0, 0, "12,34,567")]
+    #[case("en", None, 999.0, 0, "999")]
+    // Rounding to zero drops the sign.
+    #[case("en", None, -0.001, 2, "0.00")]
+    fn test_format(
+        #[case] language: &str,
+        #[case] region: Option<&str>,
+        #[case] value: f64,
+        #[case] fraction_digits: usize,
+        #[case] expected: &str,
+    ) {
+        let symbols = NumberSymbols::for_locale(language, region);
+        assert_eq!(symbols.format(value, fraction_digits), expected);
+    }
+
+    #[rstest]
+    #[case("fr", " 1\u{202F}234,5 ", Some(1234.5))]
+    #[case("de", "1.234,5", Some(1234.5))]
+    #[case("en", "12a", None)]
+    fn test_parse(
+        #[case] language: &str,
+        #[case] text: &str,
+        #[case] expected: Option<f64>,
+    ) {
+        let symbols = NumberSymbols::for_locale(language, None);
+        assert_eq!(symbols.parse(text), expected);
+    }
+
+    #[rstest]
+    #[case("1.234.567,5", NumberSymbols::for_locale("de", None))]
+    #[case("12,34,567.5", NumberSymbols::for_locale("hi", None))]
+    fn test_from_sample(
+        #[case] sample: &str,
+        #[case] expected: NumberSymbols,
+    ) {
+        assert_eq!(NumberSymbols::from_sample(sample), Some(expected));
+    }
+}
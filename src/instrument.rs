@@ -0,0 +1,38 @@
+// Spans and counters for where frame time goes, under the `hui` target.
+// Without the `tracing` feature they compile to nothing.
+
+// Enters a span until the end of the enclosing block.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $($field:tt)+)?) => {
+        let _span =
+            tracing::debug_span!(target: "hui", $name $(, $($field)+)?)
+                .entered();
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal $(, $($field:tt)+)?) => {};
+}
+
+// Records a value per frame, e.g. instances written, as an event with
+// `counter` and `value` fields.
+#[cfg(feature = "tracing")]
+macro_rules! counter {
+    ($name:ident, $value:expr) => {
+        tracing::trace!(
+            target: "hui",
+            counter = stringify!($name),
+            value = ($value) as u64,
+        )
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! counter {
+    ($name:ident, $value:expr) => {};
+}
+
+pub(crate) use counter;
+pub(crate) use span;
@@ -1,10 +1,72 @@
 pub mod components;
 pub mod core;
+pub mod format;
+mod instrument;
+#[cfg(any(test, feature = "bench"))]
+pub mod testing;
 
+#[cfg(feature = "renderdoc")]
+pub use components::GpuCapture;
+#[cfg(feature = "hot-reload")]
+pub use components::ThemeWatcher;
 pub use components::{
-    Block, BlockStyle, Bounds, DesiredSize, InputState, MouseButtonState,
-    VerticalLayoutItem, block_states, fixed_vertical_layout,
+    AccessNode, AccessRole, Accordion, AccordionMode, AlphaMask,
+    AnimationClock, AnimationId, AnimationTarget, Animator, Announcement,
+    AnnouncementSink, Announcer, AtlasGlyph, AtlasStats, AutoScroll,
+    AutoScrollSettings, Autocomplete, AutocompleteStyle, Availability, Avatar,
+    AvatarStyle, BadgeAnchor, Binding, Block, BlockPreset, BlockStyle, Bounds,
+    Button, ButtonState, ButtonStyle, CaptionButton, CellAttributes,
+    CellGlyphSource, Change, ClipId, Collapsible, CollapsibleStyle,
+    ColorScale, Command, Connection, ConnectionId, ContrastViolation,
+    CursorShape, CustomValidator, DecodedFrame, DecodedImage,
+    DecorationAction, DecorationStyle, Decorations, DesiredSize, DoubleClick,
+    DumpChange, DumpChangeKind, DumpNode, DumpRecorder, Easing,
+    FinishedAnimation, FocusState, Form, FormStyle, FramePlayback, FuzzyMatch,
+    Gantt, GanttDependency, GanttEvent, GanttStyle, GanttTask, Gauge,
+    GaugeBand, GaugeKind, GaugeStyle, GizmoHandle, GizmoTarget, GlyphAtlas,
+    GlyphSource, GraphCamera, GraphEvent, GuideAxis, HasAvailability, Heatmap,
+    HeatmapHover, HeatmapStyle, HighlightRow, HitShape, HoverIntent,
+    HoverIntentEvent, HoverIntentSettings, ImageDecoder, ImageFrame,
+    ImageLoadState, ImageLoader, ImageStyle, ImageView, InheritedStyle,
+    InputLayer, InputPhase, InputRouter, InputState, Inspect,
+    InteractionSnapshot, InteractionState, Knob, KnobDragMode, KnobEvent,
+    KnobStyle, LevelMeter, LevelMeterKind, LevelMeterStyle, MarchingAnts,
+    MarchingAntsStyle, Marquee, MarqueeStyle, MatchHighlightStyle,
+    MatchHighlights, Minimap, MinimapItem, MinimapStyle, Modifiers,
+    MouseButtonState, NodeGraph, NodeGraphStyle, NodeId, PackedBlockStyle,
+    PageBreaks, PageChange, PageSource, PagedList, PagedRow, Pagination,
+    PaginationItem, PaginationStyle, Placement, PlacementOptions, Politeness,
+    PoolStats, PopupAlignment, PopupArrow, PopupPlacement, PopupPositioner,
+    PopupSide, PortKind, PortRef, PowerSettings, PseudoState, Recyclable,
+    Scene, SceneFilter, SceneNodeId, Scheduler, ScrollAnchor, ScrollTarget,
+    ScrollView, SearchField, SearchFieldStyle, SelectionEvent, SelectionMode,
+    SelectionModel, ShapingService, Shortcut, ShortcutPlatform, SnapGuide,
+    SnapManager, SnapSettings, Sparkline, SparklineKind, SparklineStyle,
+    SpatialHash, SpatialIndex, Spectrogram, StateStyles, StatusBar,
+    StatusBarStyle, StatusBarZone, StepState, StepValidator, Stepper,
+    StepperStyle, StickyHeaders, StickySection, StyleNodeId, StyleOverrides,
+    StylePatch, StyleTree, StyledBlock, SuggestionProvider, TagMap,
+    TerminalCell, TerminalGrid, TerminalGridStyle, TextEditEvent,
+    TextEditState, TextGlyph, TextInput, TextInputStyle, TextShaper, Theme,
+    ThemeBindings, ThemeError, ThemeValue, TimeSource, Timeline, TimelineClip,
+    TimelineEvent, TimelineStyle, TimerId, TimerQueue, TitleBar,
+    TitleBarAction, TitleBarStyle, Toolbar, ToolbarItem, ToolbarItemKind,
+    ToolbarStyle, TransformGizmo, TransformGizmoStyle, UndoStack, ValidateOn,
+    Validator, VerticalLayoutItem, Waveform, WaveformEvent, WaveformKind,
+    WaveformStyle, Widget, WidgetContext, WidgetId, WidgetPool, block_states,
+    check_contrast, contrast_ratio, diff_dumps, fixed_vertical_layout,
+    fuzzy_filter, fuzzy_match, hit_test_tags, place_popup, relative_luminance,
+    resize_direction_at, rgba8, to_rgba8,
 };
 #[cfg(feature = "bench")]
 pub use core::RectangleStore;
-pub use core::{Rectangle, RectangleId, Renderer};
+pub use core::{
+    ArcSegment, ArcSegmentId, Backend, CapturedCommand, CapturedPass,
+    FrameCapture, Hinting, Image, ImageId, ImageRepeat, Layer, LayerId,
+    LayerMask, LayerTransform, MemoryBudget, MemoryCategory, MemoryPressure,
+    MemoryUsage, OrphanQueue, PageBreak, PageSetup, PathCommand, PathId,
+    PresentMode, PresentOptions, PresentSupport, PrimitiveRenderer,
+    PrimitiveRendererId, PrimitiveSetup, PrimitiveStage, Rectangle,
+    RectangleId, RenderPhase, Renderer, SceneTexture, SurfaceMask,
+    TextRasterConfig, TextRasterOptions, TextureId, VectorPath, VectorScene,
+};
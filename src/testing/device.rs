@@ -0,0 +1,36 @@
+use std::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use wgpu::{
+    BackendOptions, Backends, Device, DeviceDescriptor, Instance,
+    InstanceDescriptor, NoopBackendOptions, Queue, RequestAdapterOptions,
+};
+
+// A device on wgpu's noop backend, which validates everything like a real
+// one but draws nothing, so tests don't need a GPU.
+pub(crate) fn noop_device() -> (Device, Queue) {
+    let instance = Instance::new(&InstanceDescriptor {
+        backends: Backends::NOOP,
+        backend_options: BackendOptions {
+            noop: NoopBackendOptions { enable: true },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let adapter =
+        block_on(instance.request_adapter(&RequestAdapterOptions::default()))
+            .expect("the noop backend always has an adapter");
+    block_on(adapter.request_device(&DeviceDescriptor::default()))
+        .expect("the noop adapter always gives a device")
+}
+
+// The noop backend never waits, so the first poll finishes.
+fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut context = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut context) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the noop backend never waits"),
+    }
+}
@@ -0,0 +1,10 @@
+#[cfg(test)]
+mod device;
+mod scenes;
+
+#[cfg(test)]
+pub(crate) use device::noop_device;
+
+pub use scenes::{
+    AnimatedScene, StressScene, long_text, nested_clips, rectangle_grid,
+};
@@ -0,0 +1,300 @@
+use std::time::Duration;
+
+use glam::Mat4;
+
+use crate::{
+    components::{
+        BlockStyle, Bounds, build_model, build_rectangle, intersect_rects,
+    },
+    core::{Rectangle, RectangleId, Renderer},
+};
+
+const WORDS: [&str; 16] = [
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "magna",
+];
+
+// Rectangles for a viewport, in drawing order, with the view projection
+// they were built for.
+#[derive(Debug, Clone)]
+pub struct StressScene {
+    pub viewport:        [f32; 2],
+    pub view_projection: Mat4,
+    pub rectangles:      Vec<Rectangle>,
+}
+
+impl StressScene {
+    #[must_use]
+    pub fn new(viewport: [f32; 2]) -> Self {
+        let view_projection = Mat4::orthographic_rh(
+            0.0,
+            viewport[0],
+            viewport[1],
+            0.0,
+            -1.0,
+            1.0,
+        );
+        Self { viewport, view_projection, rectangles: Vec::new() }
+    }
+
+    pub fn add_to(&self, renderer: &mut Renderer) -> Vec<RectangleId> {
+        self.rectangles
+            .iter()
+            .map(|rectangle| renderer.add_rectangle(rectangle))
+            .collect()
+    }
+}
+
+// `count` small rounded rectangles tiling the viewport, e.g. 10 000.
+#[must_use]
+pub fn rectangle_grid(count: usize, viewport: [f32; 2]) -> StressScene {
+    let mut scene = StressScene::new(viewport);
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns).max(1);
+    let cell = [viewport[0] / columns as f32, viewport[1] / rows as f32];
+    let mut random = Random::default();
+
+    scene.rectangles = (0..count)
+        .map(|index| {
+            let position = [
+                (index % columns) as f32 * cell[0],
+                (index / columns) as f32 * cell[1],
+            ];
+            let bounds = Bounds::without_clip_rect(
+                position,
+                cell.map(|size| size * 0.8),
+            );
+            let style = BlockStyle::builder()
+                .fill_color(random.color())
+                .corner_radii([2.0; 4])
+                .build();
+            build_rectangle(&scene.view_projection, 1.0, &bounds, &style)
+        })
+        .collect();
+    scene
+}
+
+// `depth` rectangles, each inset into and clipped by the one before.
+#[must_use]
+pub fn nested_clips(depth: usize, viewport: [f32; 2]) -> StressScene {
+    let mut scene = StressScene::new(viewport);
+    let mut random = Random::default();
+    let step = viewport.map(|size| size / (depth.max(1) * 2) as f32);
+    let mut clip_rect = [0.0, 0.0, viewport[0], viewport[1]];
+
+    for level in 0..depth {
+        let inset = step.map(|step| step * level as f32);
+        // Each level sticks out past its parent on the right, so that
+        // clipping actually cuts it.
+        let bounds = Bounds {
+            position: inset,
+            size: [viewport[0] - inset[0], viewport[1] - inset[1] * 2.0],
+            clip_rect,
+        };
+        let style = BlockStyle::builder()
+            .fill_color(random.color())
+            .border_color([0.0, 0.0, 0.0, 1.0])
+            .border_size(1.0)
+            .corner_radii([8.0; 4])
+            .build();
+        scene.rectangles.push(build_rectangle(
+            &scene.view_projection,
+            1.0,
+            &bounds,
+            &style,
+        ));
+        clip_rect = intersect_rects(
+            clip_rect,
+            [
+                bounds.position[0],
+                bounds.position[1],
+                bounds.size[0] - step[0],
+                bounds.size[1],
+            ],
+        );
+    }
+    scene
+}
+
+// Rectangles moving on circles, e.g. 1 000, advanced by `step`.
+#[derive(Debug, Clone)]
+pub struct AnimatedScene {
+    scene:   StressScene,
+    centers: Vec<[f32; 2]>,
+    phases:  Vec<f32>,
+    size:    f32,
+    elapsed: Duration,
+}
+
+impl AnimatedScene {
+    #[must_use]
+    pub fn new(count: usize, viewport: [f32; 2]) -> Self {
+        let scene = rectangle_grid(count, viewport);
+        let mut random = Random::default();
+        let centers = (0..count)
+            .map(|_| {
+                [random.next() * viewport[0], random.next() * viewport[1]]
+            })
+            .collect();
+        let phases = (0..count)
+            .map(|_| random.next() * std::f32::consts::TAU)
+            .collect();
+        let mut animated = Self {
+            scene,
+            centers,
+            phases,
+            size: 12.0,
+            elapsed: Duration::ZERO,
+        };
+        animated.step(Duration::ZERO);
+
+        animated
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn scene(&self) -> &StressScene { &self.scene }
+
+    #[must_use]
+    #[inline(always)]
+    pub const fn elapsed(&self) -> Duration { self.elapsed }
+
+    pub fn step(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        let time = self.elapsed.as_secs_f32();
+        let radius = self.size * 2.0;
+        for ((rectangle, center), phase) in self
+            .scene
+            .rectangles
+            .iter_mut()
+            .zip(&self.centers)
+            .zip(&self.phases)
+        {
+            let angle = time + phase;
+            let position = [
+                center[0] + angle.cos() * radius,
+                center[1] + angle.sin() * radius,
+            ];
+            let bounds = Bounds::without_clip_rect(position, [self.size; 2]);
+            let (model, _) = build_model(bounds.size, bounds.position, 1.0);
+            rectangle.mvp =
+                (self.scene.view_projection * model).to_cols_array_2d();
+        }
+    }
+
+    // Writes the rectangles added with `scene().add_to`.
+    pub fn write_to(&self, ids: &[RectangleId], renderer: &mut Renderer) {
+        for (id, rectangle) in ids.iter().zip(&self.scene.rectangles) {
+            if let Some(instance) = renderer.get_mut_rectangle(*id) {
+                instance.mvp = rectangle.mvp;
+            }
+        }
+    }
+}
+
+// `word_count` words of filler text, in paragraphs of about a hundred
+// words.
+#[must_use]
+pub fn long_text(word_count: usize) -> String {
+    let mut random = Random::default();
+    let mut text = String::with_capacity(word_count * 7);
+    for index in 0..word_count {
+        if index > 0 {
+            text.push_str(if index % 100 == 0 { "\n\n" } else { " " });
+        }
+        text.push_str(WORDS[(random.next_u32() as usize) % WORDS.len()]);
+    }
+    text
+}
+
+// Scenes are the same on every run, so their results can be compared.
+struct Random(u32);
+
+impl Default for Random {
+    fn default() -> Self { Self(0x9E37_79B9) }
+}
+
+impl Random {
+    // xorshift32
+    fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    fn next(&mut self) -> f32 { self.next_u32() as f32 / u32::MAX as f32 }
+
+    fn color(&mut self) -> [f32; 4] {
+        [self.next(), self.next(), self.next(), 1.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::{AnimatedScene, long_text, nested_clips, rectangle_grid};
+
+    #[rstest]
+    #[case(10_000)]
+    #[case(7)]
+    #[case(0)]
+    fn test_rectangle_grid(#[case] count: usize) {
+        let scene = rectangle_grid(count, [1920.0, 1080.0]);
+        assert_eq!(scene.rectangles.len(), count);
+        assert_eq!(
+            scene.rectangles,
+            rectangle_grid(count, [1920.0, 1080.0]).rectangles
+        );
+    }
+
+    #[rstest]
+    #[case(16)]
+    #[case(1)]
+    fn test_nested_clips_shrink(#[case] depth: usize) {
+        let scene = nested_clips(depth, [800.0, 600.0]);
+        assert_eq!(scene.rectangles.len(), depth);
+        for pair in scene.rectangles.windows(2) {
+            let [outer, inner] = [pair[0].clip_rect, pair[1].clip_rect];
+            assert!(inner[0] >= outer[0] && inner[1] >= outer[1]);
+            assert!(inner[2] < outer[2] && inner[3] <= outer[3]);
+        }
+    }
+
+    #[rstest]
+    fn test_animated_scene_steps() {
+        let mut scene = AnimatedScene::new(100, [800.0, 600.0]);
+        let before = scene.scene().rectangles.clone();
+        scene.step(Duration::from_millis(16));
+        assert_ne!(scene.scene().rectangles, before);
+        assert_eq!(scene.elapsed(), Duration::from_millis(16));
+    }
+
+    // A paragraph every hundred words.
+    #[rstest]
+    #[case(250, 2)]
+    #[case(100, 0)]
+    #[case(0, 0)]
+    fn test_long_text(#[case] word_count: usize, #[case] breaks: usize) {
+        let text = long_text(word_count);
+        assert_eq!(text.split_whitespace().count(), word_count);
+        assert_eq!(text.matches("\n\n").count(), breaks);
+    }
+}